@@ -0,0 +1,380 @@
+//! A prebuilt about panel showing the plugin's name, version, build information, and licenses.
+//!
+//! The [`about_info!()`][crate::about_info!()] macro captures the package information of the crate
+//! it's used in, so it should be invoked from the plugin itself. The git hash and build date are
+//! read from the `GIT_HASH` and `BUILD_DATE` environment variables at compile time. Cargo doesn't
+//! set these, so the plugin needs a build script to provide them. For instance:
+//!
+//! ```ignore
+//! // build.rs
+//! use std::process::Command;
+//!
+//! fn main() {
+//!     if let Ok(output) = Command::new("git").args(["rev-parse", "--short", "HEAD"]).output() {
+//!         let hash = String::from_utf8_lossy(&output.stdout);
+//!         println!("cargo:rustc-env=GIT_HASH={}", hash.trim());
+//!     }
+//!     println!("cargo:rerun-if-changed=.git/HEAD");
+//! }
+//! ```
+//!
+//! The panel is an ordinary view that emits [`AboutMessage`]s. It can be shown in place of or on
+//! top of the rest of the editor:
+//!
+//! ```ignore
+//! struct FooEditor {
+//!     about: AboutInfo,
+//!     show_about: bool,
+//!     // ...
+//! }
+//!
+//! // In `new()`
+//! let about = AboutInfo {
+//!     name: "Foo",
+//!     ..about_info!()
+//! }
+//! .with_license("Inter", include_str!("../assets/Inter-LICENSE.txt"));
+//!
+//! // In `update()`
+//! match message {
+//!     Message::About(AboutMessage::Close) => self.show_about = false,
+//!     Message::About(AboutMessage::OpenUrl(url)) => {
+//!         return about::open_url(url).map(Message::UrlOpened);
+//!     }
+//!     Message::UrlOpened(Err(err)) => nih_log!("{err}"),
+//!     // ...
+//! }
+//!
+//! // In `view()`
+//! if self.show_about {
+//!     return about::panel(&self.about).map(Message::About);
+//! }
+//! ```
+
+use std::process::Command;
+use std::sync::Arc;
+
+use crate::core::{Font, Length};
+use crate::runtime::task;
+use crate::widget::{button, column, container, scrollable, text, Column};
+use crate::{Element, Task};
+
+/// Information about a plugin shown in the about [`panel()`]. This is usually created with the
+/// [`about_info!()`][crate::about_info!()] macro, and any of the fields can be overridden afterwards.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AboutInfo {
+    /// The plugin's name. This defaults to the package name.
+    pub name: &'static str,
+    /// The plugin's version.
+    pub version: &'static str,
+    /// The plugin's authors, separated by colons like in `CARGO_PKG_AUTHORS`.
+    pub authors: Option<&'static str>,
+    /// A short description of the plugin.
+    pub description: Option<&'static str>,
+    /// The plugin's homepage. This is shown as a clickable link.
+    pub homepage: Option<&'static str>,
+    /// The plugin's source repository. This is shown as a clickable link.
+    pub repository: Option<&'static str>,
+    /// The plugin's license identifier, like `GPL-3.0-or-later`.
+    pub license: Option<&'static str>,
+    /// The git commit the plugin was built from, if the plugin's build script provides it.
+    pub git_hash: Option<&'static str>,
+    /// When the plugin was built, if the plugin's build script provides it.
+    pub build_date: Option<&'static str>,
+    /// The full license texts shown in the panel's scrollable license section, as `(name, text)`
+    /// pairs. This can include the licenses of fonts and libraries used by the plugin.
+    pub licenses: Vec<(&'static str, &'static str)>,
+}
+
+/// Create an [`AboutInfo`] for the crate this macro is used in. See the [module
+/// documentation][crate::about].
+#[macro_export]
+macro_rules! about_info {
+    () => {
+        $crate::about::AboutInfo {
+            name: env!("CARGO_PKG_NAME"),
+            version: env!("CARGO_PKG_VERSION"),
+            authors: $crate::about::non_empty(env!("CARGO_PKG_AUTHORS")),
+            description: $crate::about::non_empty(env!("CARGO_PKG_DESCRIPTION")),
+            homepage: $crate::about::non_empty(env!("CARGO_PKG_HOMEPAGE")),
+            repository: $crate::about::non_empty(env!("CARGO_PKG_REPOSITORY")),
+            license: $crate::about::non_empty(env!("CARGO_PKG_LICENSE")),
+            git_hash: option_env!("GIT_HASH"),
+            build_date: option_env!("BUILD_DATE"),
+            licenses: ::std::vec::Vec::new(),
+        }
+    };
+}
+
+/// Used by [`about_info!()`][crate::about_info!()] since Cargo sets unspecified package fields to
+/// empty strings.
+#[doc(hidden)]
+pub const fn non_empty(value: &'static str) -> Option<&'static str> {
+    if value.is_empty() {
+        None
+    } else {
+        Some(value)
+    }
+}
+
+impl AboutInfo {
+    /// Add a license text to the panel's license section.
+    pub fn with_license(mut self, name: &'static str, text: &'static str) -> Self {
+        self.licenses.push((name, text));
+        self
+    }
+
+    /// The version line shown in the panel, including the git hash and build date when they're
+    /// known.
+    pub fn version_string(&self) -> String {
+        let mut version = format!("Version {}", self.version);
+        if let Some(git_hash) = self.git_hash {
+            version.push_str(&format!(" ({git_hash})"));
+        }
+        if let Some(build_date) = self.build_date {
+            version.push_str(&format!(", built {build_date}"));
+        }
+
+        version
+    }
+}
+
+/// A message emitted by the about [`panel()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AboutMessage {
+    /// The panel's close button was pressed.
+    Close,
+    /// A link was clicked. This can be passed to [`open_url()`].
+    OpenUrl(String),
+}
+
+/// A prebuilt about panel for an [`AboutInfo`]. The panel fills the available space, and the
+/// license section scrolls if the license texts don't fit.
+pub fn panel(info: &AboutInfo) -> Element<'_, AboutMessage> {
+    let mut content = column![
+        text(info.name).size(24),
+        text(info.version_string()).size(13),
+    ]
+    .spacing(4);
+
+    if let Some(description) = info.description {
+        content = content.push(text(description));
+    }
+    if let Some(authors) = info.authors {
+        content = content.push(text(authors.split(':').collect::<Vec<_>>().join(", ")).size(13));
+    }
+    for url in [info.homepage, info.repository].into_iter().flatten() {
+        content = content.push(
+            button(text(url).size(13))
+                .padding(0)
+                .style(button::text)
+                .on_press(AboutMessage::OpenUrl(url.to_owned())),
+        );
+    }
+    if let Some(license) = info.license {
+        content = content.push(text(format!("License: {license}")).size(13));
+    }
+
+    if !info.licenses.is_empty() {
+        let licenses = Column::with_children(info.licenses.iter().map(|(name, license_text)| {
+            column![
+                text(*name).size(14),
+                text(*license_text).size(11).font(Font::MONOSPACE),
+            ]
+            .spacing(4)
+            .into()
+        }))
+        .spacing(16)
+        .padding([0, 12]);
+
+        content = content.push(scrollable(licenses).height(Length::Fill));
+    }
+
+    let close = button(text("Close")).on_press(AboutMessage::Close);
+
+    container(column![content.height(Length::Fill), close].spacing(12))
+        .padding(16)
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .into()
+}
+
+/// An error that occurred while opening a URL.
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum OpenUrlError {
+    /// Only `http://`, `https://`, and `mailto:` URLs can be opened. This prevents links from
+    /// opening local files or running programs.
+    #[error("'{0}' is not an http, https, or mailto URL")]
+    InvalidUrl(String),
+
+    /// There is no known way to open URLs on this platform.
+    #[error("opening URLs is not supported on this platform")]
+    Unsupported,
+
+    /// The program used to open the URL could not be started.
+    #[error("could not start '{program}' to open the URL")]
+    Launch {
+        program: &'static str,
+        #[source]
+        source: Arc<std::io::Error>,
+    },
+
+    /// The program used to open the URL reported an error.
+    #[error("'{program}' could not open the URL (exit code {code:?})")]
+    Failed {
+        program: &'static str,
+        code: Option<i32>,
+    },
+}
+
+/// Open a URL in the user's default browser or mail client. This runs on a separate thread, so the
+/// GUI doesn't block while the operating system launches the browser.
+pub fn open_url(url: impl Into<String>) -> Task<Result<(), OpenUrlError>> {
+    let url = url.into();
+
+    task::blocking(move |mut sender| {
+        let _ = sender.try_send(open_url_blocking(&url));
+    })
+}
+
+/// The blocking version of [`open_url()`]. This waits until the operating system's URL handler
+/// exits.
+pub fn open_url_blocking(url: &str) -> Result<(), OpenUrlError> {
+    validate_url(url)?;
+
+    let (program, mut command) = url_command(url).ok_or(OpenUrlError::Unsupported)?;
+    let status = command.status().map_err(|err| OpenUrlError::Launch {
+        program,
+        source: Arc::new(err),
+    })?;
+
+    if status.success() {
+        Ok(())
+    } else {
+        Err(OpenUrlError::Failed {
+            program,
+            code: status.code(),
+        })
+    }
+}
+
+/// Check that a URL can safely be passed to the platform's URL handler.
+fn validate_url(url: &str) -> Result<(), OpenUrlError> {
+    let lowercase = url.to_ascii_lowercase();
+    let has_valid_scheme = ["http://", "https://", "mailto:"]
+        .iter()
+        .any(|scheme| lowercase.starts_with(scheme) && lowercase.len() > scheme.len());
+    let has_invalid_chars = url
+        .chars()
+        .any(|c| c.is_control() || c.is_whitespace() || c == '"');
+
+    if has_valid_scheme && !has_invalid_chars {
+        Ok(())
+    } else {
+        Err(OpenUrlError::InvalidUrl(url.to_owned()))
+    }
+}
+
+/// The command used to open a URL on this platform, along with the program's name for error
+/// messages.
+#[allow(unreachable_code)]
+fn url_command(url: &str) -> Option<(&'static str, Command)> {
+    #[cfg(target_os = "windows")]
+    {
+        // `cmd /C start` would interpret characters like `&` in the URL, this doesn't
+        let mut command = Command::new("rundll32");
+        command.args(["url.dll,FileProtocolHandler", url]);
+        return Some(("rundll32", command));
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        let mut command = Command::new("open");
+        command.arg(url);
+        return Some(("open", command));
+    }
+
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "freebsd",
+        target_os = "dragonfly",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    ))]
+    {
+        let mut command = Command::new("xdg-open");
+        command.arg(url);
+        return Some(("xdg-open", command));
+    }
+
+    let _ = url;
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn about_info_macro() {
+        let info = crate::about_info!().with_license("ISC", "Permission to use...");
+        assert_eq!(info.name, "nih_plug_iced");
+        assert_eq!(info.version, env!("CARGO_PKG_VERSION"));
+        assert_eq!(info.license, Some("ISC"));
+        assert_eq!(info.homepage, None);
+        assert_eq!(info.licenses, [("ISC", "Permission to use...")]);
+
+        let info = AboutInfo {
+            git_hash: Some("1a2b3c4"),
+            build_date: Some("2024-01-01"),
+            ..info
+        };
+        assert_eq!(
+            info.version_string(),
+            format!("Version {} (1a2b3c4), built 2024-01-01", info.version)
+        );
+    }
+
+    #[test]
+    fn url_validation() {
+        for url in [
+            "https://example.com",
+            "http://example.com/path?a=1&b=2",
+            "HTTPS://EXAMPLE.COM",
+            "mailto:foo@example.com",
+        ] {
+            assert!(validate_url(url).is_ok(), "{url}");
+        }
+
+        for url in [
+            "",
+            "https://",
+            "file:///etc/passwd",
+            "example.com",
+            "-https://example.com",
+            "https://example.com\" && calc",
+            "https://example.com/\nfoo",
+            "javascript:alert(1)",
+        ] {
+            assert!(
+                matches!(validate_url(url), Err(OpenUrlError::InvalidUrl(_))),
+                "{url}"
+            );
+        }
+
+        // Invalid URLs are rejected before anything is launched
+        assert!(matches!(
+            open_url_blocking("file:///etc/passwd"),
+            Err(OpenUrlError::InvalidUrl(_))
+        ));
+    }
+
+    #[test]
+    fn url_command_passes_url_as_single_argument() {
+        let url = "https://example.com/?a=1&b=2";
+        if let Some((program, command)) = url_command(url) {
+            assert_eq!(command.get_program(), program);
+            assert_eq!(command.get_args().last(), Some(std::ffi::OsStr::new(url)));
+        }
+    }
+}
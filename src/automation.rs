@@ -0,0 +1,88 @@
+//! Showing when the host is writing automation.
+//!
+//! Some hosts indicate whether they're currently recording parameter changes as automation. Editors
+//! can use this to tint their parameter widgets, or to avoid sending lots of tiny parameter changes
+//! while a control is dragged. NIH-plug's [`GuiContext`][nih_plug::prelude::GuiContext] does not
+//! expose this state, so it is [`AutomationWrite::Unknown`] unless the plugin finds out about it
+//! some other way and reports it with
+//! [`IcedState::set_automation_write()`][crate::IcedState::set_automation_write()]. Editors should
+//! treat `Unknown` the same as [`AutomationWrite::Off`] and not show any indicator.
+//!
+//! Reporting a different state updates and redraws the editor, also while a parameter is being
+//! dragged, and calls [`IcedEditor::automation_write_changed()`][crate::IcedEditor::automation_write_changed()].
+//! The current state can be read at any time with
+//! [`IcedState::automation_write()`][crate::IcedState::automation_write()].
+//!
+//! ```ignore
+//! fn automation_write_changed(&mut self, state: AutomationWrite) {
+//!     self.writing_automation = state.is_writing();
+//! }
+//!
+//! fn view(&self) -> Element<'_, Self::Message> {
+//!     let writing_automation = self.writing_automation;
+//!
+//!     nih_widgets::ParamSlider::new(&self.params.gain)
+//!         .style(move |theme, status| {
+//!             let style = nih_widgets::param_slider::default(theme, status);
+//!             if writing_automation {
+//!                 nih_widgets::param_slider::Style {
+//!                     fill: theme.extended_palette().danger.base.color,
+//!                     ..style
+//!                 }
+//!             } else {
+//!                 style
+//!             }
+//!         })
+//!         .map(Message::ParamUpdate)
+//! }
+//! ```
+
+/// Whether the host is writing automation. See the [module documentation][self].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AutomationWrite {
+    /// The automation write state is not known. This is the case unless the plugin reports the
+    /// state itself.
+    #[default]
+    Unknown,
+    /// The host is not writing automation.
+    Off,
+    /// The host is recording parameter changes as automation.
+    Writing,
+}
+
+impl AutomationWrite {
+    /// Whether the host is known to be writing automation.
+    pub fn is_writing(self) -> bool {
+        self == AutomationWrite::Writing
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::IcedState;
+    use crossbeam::channel;
+
+    #[test]
+    fn changes_notify_the_editor() {
+        let (sender, receiver) = channel::bounded(1);
+        let state = IcedState::from_size(100, 100);
+        state.param_update_suppression.set_sender(sender);
+        assert_eq!(state.automation_write(), AutomationWrite::Unknown);
+
+        state.set_automation_write(AutomationWrite::Writing);
+        assert!(state.automation_write().is_writing());
+        assert!(receiver.try_recv().is_ok());
+
+        // Reporting the same state again doesn't cause another update
+        state.set_automation_write(AutomationWrite::Writing);
+        assert!(receiver.try_recv().is_err());
+
+        // Changes are not held back while a parameter is being dragged
+        state.begin_param_update_suppression();
+        state.set_automation_write(AutomationWrite::Off);
+        assert!(receiver.try_recv().is_ok());
+        state.end_param_update_suppression();
+        assert!(receiver.try_recv().is_err());
+    }
+}
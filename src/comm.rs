@@ -0,0 +1,191 @@
+//! Values the plugin's audio thread shares with the editor.
+//!
+//! The editor's widget state only lives while the window is open, so anything the audio thread
+//! reports while the window is closed is lost unless it's stored somewhere else. The types in this
+//! module are meant to be created by the plugin, stored in an `Arc` next to its parameters, and
+//! handed to the editor through its
+//! [`IcedEditor::InitializationFlags`][crate::IcedEditor::InitializationFlags]. The audio thread
+//! side of these types never blocks or allocates.
+//!
+//! [`ClipFlag`] latches when the plugin's output clips, so a
+//! [`PeakMeter`][crate::widgets::PeakMeter] can still show that it clipped after the editor has
+//! been closed and opened again:
+//!
+//! ```ignore
+//! // In the plugin's `process()` function
+//! self.output_clipped.check(peak_amplitude);
+//!
+//! // In the editor
+//! PeakMeter::new(self.peak_meter.load(Ordering::Relaxed))
+//!     .clip_flag(self.output_clipped.clone())
+//! ```
+//!
+//! [`ModulationRange`] holds the range of values a parameter currently has across the voices of a
+//! polyphonic synth, for instance with CLAP's polyphonic modulation. The
+//! [`ParamSlider`][crate::widgets::ParamSlider] and the [`ParamRing`][crate::widgets::ParamRing]
+//! draw it as a band behind their value:
+//!
+//! ```ignore
+//! // In the plugin, after the voices have been processed
+//! self.cutoff_voices
+//!     .set_from_voices(self.voices.iter().flatten().map(|voice| voice.cutoff_normalized));
+//!
+//! // In the editor
+//! ParamSlider::new(&self.params.cutoff).modulation_range(self.cutoff_voices.clone())
+//! ```
+
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+/// Set by the audio thread when the plugin's output clips, and cleared by the user. See the [module
+/// documentation][self].
+#[derive(Debug, Default)]
+pub struct ClipFlag {
+    clipped: AtomicBool,
+}
+
+impl ClipFlag {
+    /// Creates a new [`ClipFlag`] that's not set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the flag if `peak_amplitude` is above 0 dBFS. The amplitude is a linear gain value, so
+    /// this checks whether its absolute value exceeds 1.0. This can be called from the audio
+    /// thread.
+    pub fn check(&self, peak_amplitude: f32) {
+        if peak_amplitude.abs() > 1.0 {
+            self.set();
+        }
+    }
+
+    /// Set the flag. This can be called from the audio thread.
+    pub fn set(&self) {
+        self.clipped.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether the output clipped since the flag was last cleared.
+    pub fn is_set(&self) -> bool {
+        self.clipped.load(Ordering::Relaxed)
+    }
+
+    /// Clear the flag. The [`PeakMeter`][crate::widgets::PeakMeter] does this when its clip
+    /// indicator is clicked.
+    pub fn clear(&self) {
+        self.clipped.store(false, Ordering::Relaxed);
+    }
+}
+
+/// The bits of an empty [`ModulationRange`]. Both halves are `NaN`s.
+const EMPTY_RANGE: u64 = u64::MAX;
+
+/// The lowest and highest normalized value a polyphonically modulated parameter has across the
+/// active voices. The audio thread stores both values in a single atomic, so the editor never
+/// sees the minimum from one update with the maximum from another. See the [module
+/// documentation][self].
+#[derive(Debug)]
+pub struct ModulationRange {
+    /// The minimum's bits in the upper half and the maximum's bits in the lower half.
+    range: AtomicU64,
+}
+
+impl Default for ModulationRange {
+    fn default() -> Self {
+        Self {
+            range: AtomicU64::new(EMPTY_RANGE),
+        }
+    }
+}
+
+impl ModulationRange {
+    /// Creates a new, empty [`ModulationRange`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Store the range of normalized values the parameter has across the active voices. This is
+    /// wait-free, so it can be called from the audio thread.
+    pub fn set(&self, min: f32, max: f32) {
+        let range = ((min.to_bits() as u64) << 32) | max.to_bits() as u64;
+        self.range.store(range, Ordering::Relaxed);
+    }
+
+    /// Store the range spanned by `values`, the parameter's normalized value for every active
+    /// voice. `NaN` values are skipped, and the range is cleared if there are no other values. This
+    /// is wait-free, so it can be called from the audio thread.
+    pub fn set_from_voices(&self, values: impl IntoIterator<Item = f32>) {
+        let range = values.into_iter().filter(|value| !value.is_nan()).fold(
+            None,
+            |range: Option<(f32, f32)>, value| match range {
+                Some((min, max)) => Some((min.min(value), max.max(value))),
+                None => Some((value, value)),
+            },
+        );
+
+        match range {
+            Some((min, max)) => self.set(min, max),
+            None => self.clear(),
+        }
+    }
+
+    /// Clear the range, for instance when no voices are playing. Widgets don't draw empty ranges.
+    pub fn clear(&self) {
+        self.range.store(EMPTY_RANGE, Ordering::Relaxed);
+    }
+
+    /// The current range as a `(min, max)` pair, or `None` if it's empty. Both values are clamped
+    /// to `[0, 1]`, and the minimum is never above the maximum.
+    pub fn get(&self) -> Option<(f32, f32)> {
+        let range = self.range.load(Ordering::Relaxed);
+        let min = f32::from_bits((range >> 32) as u32);
+        let max = f32::from_bits(range as u32);
+        if min.is_nan() || max.is_nan() {
+            return None;
+        }
+
+        let (min, max) = (min.clamp(0.0, 1.0), max.clamp(0.0, 1.0));
+        Some((min.min(max), min.max(max)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_clipping_sets_the_flag() {
+        let flag = ClipFlag::new();
+        flag.check(1.0);
+        flag.check(-0.5);
+        flag.check(f32::NAN);
+        assert!(!flag.is_set());
+
+        flag.check(-1.01);
+        assert!(flag.is_set());
+        // Quieter blocks don't clear it
+        flag.check(0.1);
+        assert!(flag.is_set());
+
+        flag.clear();
+        assert!(!flag.is_set());
+    }
+
+    #[test]
+    fn modulation_ranges_are_sanitized_on_read() {
+        let range = ModulationRange::new();
+        assert_eq!(range.get(), None);
+
+        range.set(0.25, 0.75);
+        assert_eq!(range.get(), Some((0.25, 0.75)));
+        range.set(1.5, -0.5);
+        assert_eq!(range.get(), Some((0.0, 1.0)));
+        range.set(0.75, 0.25);
+        assert_eq!(range.get(), Some((0.25, 0.75)));
+        range.set(f32::NAN, 0.5);
+        assert_eq!(range.get(), None);
+
+        range.set_from_voices([0.5, f32::NAN, 0.125, 0.625]);
+        assert_eq!(range.get(), Some((0.125, 0.625)));
+        range.set_from_voices([]);
+        assert_eq!(range.get(), None);
+    }
+}
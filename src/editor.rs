@@ -4,11 +4,12 @@ use ::baseview::{WindowOpenOptions, WindowScalePolicy};
 use crossbeam::atomic::AtomicCell;
 use crossbeam::channel;
 use crate::iced_baseview::settings::IcedBaseviewSettings;
-use nih_plug::prelude::{Editor, GuiContext, ParentWindowHandle};
+use nih_plug::prelude::{Editor, GuiContext, ParamPtr, ParentWindowHandle, PluginApi, PluginState};
 use std::sync::Arc;
 use std::{borrow::Cow, sync::atomic::Ordering};
 
-use crate::{wrapper, IcedEditor, IcedState, ParameterUpdate};
+use crate::param_updates::{self, ParameterUpdate, RepeatedValueFilter};
+use crate::{wrapper, IcedEditor, IcedState};
 
 pub use crate::iced_baseview::*;
 
@@ -23,8 +24,65 @@ pub(crate) struct IcedEditorWrapper<E: IcedEditor> {
     pub(crate) scaling_factor: AtomicCell<Option<f32>>,
 
     /// A subscription for sending messages about parameter updates to the `IcedEditor`.
-    pub(crate) parameter_updates_sender: channel::Sender<ParameterUpdate>,
-    pub(crate) parameter_updates_receiver: Arc<channel::Receiver<ParameterUpdate>>,
+    pub(crate) parameter_updates_sender: channel::Sender<()>,
+    pub(crate) parameter_updates_receiver: Arc<channel::Receiver<()>>,
+
+    /// Keeps the compositor and renderer around for a moment after the editor closes, since some
+    /// hosts rapidly close and reopen the editor while loading projects or browsing presets.
+    pub(crate) graphics_cache: Arc<GraphicsCache>,
+
+    /// Opens the editor's window. This is only replaced in tests.
+    pub(crate) open_window: OpenWindow<E>,
+}
+
+/// The function [`IcedEditorWrapper`] opens its window with.
+pub(crate) type OpenWindow<E> = fn(
+    &ParentWindowHandle,
+    <wrapper::IcedEditorWrapperApplication<E> as Application>::Flags,
+    Settings,
+) -> std::result::Result<window::WindowHandle<wrapper::Message<E>>, Error>;
+
+impl<E: IcedEditor> IcedEditorWrapper<E> {
+    pub(crate) fn new(
+        iced_state: Arc<IcedState>,
+        initialization_flags: E::InitializationFlags,
+        fonts: Vec<Cow<'static, [u8]>>,
+    ) -> Self {
+        // We need some way to communicate parameter changes to the `IcedEditor` since parameter
+        // updates come from outside of the editor's reactive model. This channel only wakes up the
+        // editor, so it has room for a single notification and further changes are folded into
+        // it. Which parameters changed is stored in `IcedState`'s queue of up to
+        // `PARAMETER_UPDATE_QUEUE_CAPACITY` `ParameterUpdate`s, which the editor takes from when
+        // it wakes up.
+        let (parameter_updates_sender, parameter_updates_receiver) =
+            channel::bounded(param_updates::PARAMETER_UPDATES_CAPACITY);
+        iced_state
+            .param_update_suppression
+            .set_sender(parameter_updates_sender.clone());
+
+        Self {
+            iced_state,
+            initialization_flags,
+
+            // iced_baseview with iced 0.13 properly handles scale factors through
+            // WindowScalePolicy, but we still need to be careful about host-provided scaling vs
+            // system scaling. Some hosts (like Ableton Live) may provide their own scale factors
+            // that conflict with baseview's scaling. When a host provides a scale factor, we use it
+            // explicitly. Otherwise, we let iced_baseview use the system scale factor, which it now
+            // handles correctly on all platforms.
+            scaling_factor: AtomicCell::new(None),
+
+            parameter_updates_sender,
+            parameter_updates_receiver: Arc::new(parameter_updates_receiver),
+            fonts,
+            graphics_cache: GraphicsCache::new(GraphicsCache::DEFAULT_GRACE_PERIOD),
+
+            open_window: crate::iced_baseview::open_parented::<
+                wrapper::IcedEditorWrapperApplication<E>,
+                ParentWindowHandle,
+            >,
+        }
+    }
 }
 
 impl<E: IcedEditor> Editor for IcedEditorWrapper<E> {
@@ -33,16 +91,28 @@ impl<E: IcedEditor> Editor for IcedEditorWrapper<E> {
         parent: ParentWindowHandle,
         context: Arc<dyn GuiContext>,
     ) -> Box<dyn std::any::Any + Send> {
-        let (unscaled_width, unscaled_height) = self.iced_state.size();
+        let (unscaled_width, unscaled_height) = self.iced_state.clamped_size();
         let scaling_factor = self.scaling_factor.load();
 
-        // TODO: iced_baseview does not have gracefuly error handling for context creation failures.
-        //       This will panic if the context could not be created.
-        let window = crate::iced_baseview::open_parented::<wrapper::IcedEditorWrapperApplication<E>, _>(
+        // If the previous editor window is still shutting down on another thread, then initializing
+        // the new window's graphics at the same time can deadlock on some drivers
+        if !self
+            .graphics_cache
+            .wait_for_previous_instance(GraphicsCache::INSTANCE_SHUTDOWN_TIMEOUT)
+        {
+            nih_plug::nih_log!("The previous editor window did not shut down in time");
+        }
+
+        let window = (self.open_window)(
             &parent,
             (
-                context,
+                Arc::new(GestureTrackingContext {
+                    context,
+                    iced_state: self.iced_state.clone(),
+                    repeated_values: RepeatedValueFilter::default(),
+                }),
                 self.parameter_updates_receiver.clone(),
+                self.iced_state.clone(),
                 self.initialization_flags.clone(),
             ),
             Settings {
@@ -59,12 +129,30 @@ impl<E: IcedEditor> Editor for IcedEditorWrapper<E> {
                 iced_baseview: IcedBaseviewSettings {
                     ignore_non_modifier_keys: false,
                     always_redraw: true,
+                    display_size: self.iced_state.display_size().map(|(width, height)| {
+                        crate::core::Size::new(width as f32, height as f32)
+                    }),
+                    ..Default::default()
                 },
                 graphics_settings: GraphicsSettings::default(), // wgpu renderer by default
                 fonts: self.fonts.clone(),
+                graphics_cache: Some(self.graphics_cache.clone()),
             },
         );
 
+        let window = match window {
+            Ok(window) => window,
+            Err(error) => {
+                nih_plug::nih_error!("Could not open the editor window: {error:?}");
+                *self.iced_state.last_open_error.lock().unwrap() = Some(Arc::new(error));
+
+                // NIH-plug has no way to tell the host that the editor failed to open, so the host
+                // gets a handle without a window
+                return Box::new(());
+            }
+        };
+
+        *self.iced_state.last_open_error.lock().unwrap() = None;
         self.iced_state.open.store(true, Ordering::Release);
         Box::new(IcedEditorHandle {
             iced_state: self.iced_state.clone(),
@@ -73,7 +161,7 @@ impl<E: IcedEditor> Editor for IcedEditorWrapper<E> {
     }
 
     fn size(&self) -> (u32, u32) {
-        self.iced_state.size()
+        self.iced_state.clamped_size()
     }
 
     fn set_scale_factor(&self, factor: f32) -> bool {
@@ -87,20 +175,76 @@ impl<E: IcedEditor> Editor for IcedEditorWrapper<E> {
         true
     }
 
-    fn param_value_changed(&self, _id: &str, _normalized_value: f32) {
-        // If there's already a paramter change notification in the channel then we don't need
-        // to do anything else. This avoids queueing up redundant GUI redraws.
-        // NOTE: We could add an event containing the parameter's ID and the normalized value, but
-        //       these events aren't really necessary for Vizia.
-        let _ = self.parameter_updates_sender.try_send(ParameterUpdate);
+    fn param_value_changed(&self, id: &str, normalized_value: f32) {
+        // This is held back while a parameter is being dragged, see the `param_updates` module
+        self.iced_state.param_update_suppression.notify(
+            &self.parameter_updates_sender,
+            ParameterUpdate::Value {
+                id_hash: ParameterUpdate::hash_id(id),
+                normalized_value,
+            },
+        );
     }
 
-    fn param_modulation_changed(&self, _id: &str, _modulation_offset: f32) {
-        let _ = self.parameter_updates_sender.try_send(ParameterUpdate);
+    fn param_modulation_changed(&self, id: &str, modulation_offset: f32) {
+        self.iced_state.param_update_suppression.notify(
+            &self.parameter_updates_sender,
+            ParameterUpdate::Modulation {
+                id_hash: ParameterUpdate::hash_id(id),
+                modulation_offset,
+            },
+        );
     }
 
     fn param_values_changed(&self) {
-        let _ = self.parameter_updates_sender.try_send(ParameterUpdate);
+        self.iced_state
+            .param_update_suppression
+            .notify(&self.parameter_updates_sender, ParameterUpdate::All);
+    }
+}
+
+/// Wraps around the host's [`GuiContext`] to suppress parameter update redraws while a parameter
+/// gesture is active, and to skip setting parameters to the value they already have during a
+/// gesture. See the [`param_updates`][crate::param_updates] module.
+struct GestureTrackingContext {
+    context: Arc<dyn GuiContext>,
+    iced_state: Arc<IcedState>,
+    repeated_values: RepeatedValueFilter,
+}
+
+impl GuiContext for GestureTrackingContext {
+    fn plugin_api(&self) -> PluginApi {
+        self.context.plugin_api()
+    }
+
+    fn request_resize(&self) -> bool {
+        self.context.request_resize()
+    }
+
+    unsafe fn raw_begin_set_parameter(&self, param: ParamPtr) {
+        self.iced_state.param_update_suppression.begin();
+        self.repeated_values.begin(param);
+        self.context.raw_begin_set_parameter(param);
+    }
+
+    unsafe fn raw_set_parameter_normalized(&self, param: ParamPtr, normalized: f32) {
+        if self.repeated_values.should_set(param, normalized) {
+            self.context.raw_set_parameter_normalized(param, normalized);
+        }
+    }
+
+    unsafe fn raw_end_set_parameter(&self, param: ParamPtr) {
+        self.context.raw_end_set_parameter(param);
+        self.repeated_values.end(param);
+        self.iced_state.param_update_suppression.end();
+    }
+
+    fn get_state(&self) -> PluginState {
+        self.context.get_state()
+    }
+
+    fn set_state(&self, state: PluginState) {
+        self.context.set_state(state);
     }
 }
 
@@ -117,6 +261,418 @@ unsafe impl<Message: Send> Send for IcedEditorHandle<Message> {}
 impl<Message: Send> Drop for IcedEditorHandle<Message> {
     fn drop(&mut self) {
         self.iced_state.open.store(false, Ordering::Release);
+        self.iced_state.gui_channels.close();
+        self.iced_state.param_update_suppression.reset();
+        self.iced_state.resume_rendering();
         self.window.close_window();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::futures::futures::{self, stream, task, StreamExt};
+    use crate::futures::subscription::into_recipes;
+    use crate::widgets::ParamMessage;
+    use nih_plug::prelude::{FloatParam, FloatRange, Param};
+    use std::task::{Context, Poll};
+    use std::time::{Duration, Instant};
+
+    /// An editor that never gets to run, since its window never opens.
+    struct Unopened;
+
+    impl IcedEditor for Unopened {
+        type Executor = crate::executor::Default;
+        type Message = ();
+        type InitializationFlags = ();
+        type Theme = crate::Theme;
+
+        fn new(_flags: (), _context: Arc<dyn GuiContext>) -> (Self, Task<()>) {
+            unreachable!()
+        }
+
+        fn context(&self) -> &dyn GuiContext {
+            unreachable!()
+        }
+
+        fn update(&mut self, _message: ()) -> Task<()> {
+            unreachable!()
+        }
+
+        fn view(&self) -> Element<'_, (), crate::Theme, Renderer> {
+            unreachable!()
+        }
+
+        fn theme(&self) -> crate::Theme {
+            unreachable!()
+        }
+    }
+
+    /// An editor that records the sizes its window was resized to.
+    struct RecordsResizes(Arc<std::sync::Mutex<Vec<Size>>>);
+
+    impl IcedEditor for RecordsResizes {
+        type Executor = crate::executor::Default;
+        type Message = Size;
+        type InitializationFlags = Arc<std::sync::Mutex<Vec<Size>>>;
+        type Theme = crate::Theme;
+
+        fn new(
+            sizes: Self::InitializationFlags,
+            _context: Arc<dyn GuiContext>,
+        ) -> (Self, Task<Size>) {
+            (Self(sizes), Task::none())
+        }
+
+        fn context(&self) -> &dyn GuiContext {
+            unreachable!()
+        }
+
+        fn update(&mut self, size: Size) -> Task<Size> {
+            self.0.lock().unwrap().push(size);
+
+            Task::none()
+        }
+
+        fn subscription(
+            &self,
+            window_subs: &mut WindowSubs<Size>,
+        ) -> crate::futures::Subscription<Size> {
+            window_subs.on_resize_message(Some);
+
+            crate::futures::Subscription::none()
+        }
+
+        fn view(&self) -> Element<'_, Size, crate::Theme, Renderer> {
+            unreachable!()
+        }
+
+        fn theme(&self) -> crate::Theme {
+            unreachable!()
+        }
+    }
+
+    /// An editor that records the clipboard availability changes it was told about.
+    struct WatchesClipboard(Arc<std::sync::Mutex<Vec<bool>>>);
+
+    impl IcedEditor for WatchesClipboard {
+        type Executor = crate::executor::Default;
+        type Message = bool;
+        type InitializationFlags = Arc<std::sync::Mutex<Vec<bool>>>;
+        type Theme = crate::Theme;
+
+        fn new(
+            changes: Self::InitializationFlags,
+            _context: Arc<dyn GuiContext>,
+        ) -> (Self, Task<bool>) {
+            (Self(changes), Task::none())
+        }
+
+        fn context(&self) -> &dyn GuiContext {
+            unreachable!()
+        }
+
+        fn update(&mut self, available: bool) -> Task<bool> {
+            self.0.lock().unwrap().push(available);
+
+            Task::none()
+        }
+
+        fn subscription(
+            &self,
+            window_subs: &mut WindowSubs<bool>,
+        ) -> crate::futures::Subscription<bool> {
+            window_subs.on_clipboard_change = Some(crate::clipboard::ClipboardWatch::new(Some));
+
+            crate::futures::Subscription::none()
+        }
+
+        fn view(&self) -> Element<'_, bool, crate::Theme, Renderer> {
+            unreachable!()
+        }
+
+        fn theme(&self) -> crate::Theme {
+            unreachable!()
+        }
+    }
+
+    /// Everything that happened to an editor and its context, in order.
+    type Log = Arc<std::sync::Mutex<Vec<String>>>;
+
+    #[derive(Debug, Clone)]
+    enum RoutedMessage {
+        Param(ParamMessage),
+        Other,
+    }
+
+    /// An editor that lets the wrapper handle its parameter messages.
+    struct RoutesParams {
+        log: Log,
+        context: Arc<dyn GuiContext>,
+    }
+
+    impl IcedEditor for RoutesParams {
+        type Executor = crate::executor::Default;
+        type Message = RoutedMessage;
+        type InitializationFlags = Log;
+        type Theme = crate::Theme;
+
+        fn new(log: Log, context: Arc<dyn GuiContext>) -> (Self, Task<RoutedMessage>) {
+            (Self { log, context }, Task::none())
+        }
+
+        fn context(&self) -> &dyn GuiContext {
+            self.context.as_ref()
+        }
+
+        fn param_message(message: &RoutedMessage) -> Option<ParamMessage> {
+            match message {
+                RoutedMessage::Param(message) => Some(*message),
+                RoutedMessage::Other => None,
+            }
+        }
+
+        fn update(&mut self, message: RoutedMessage) -> Task<RoutedMessage> {
+            self.log.lock().unwrap().push(format!("update {message:?}"));
+
+            Task::none()
+        }
+
+        fn view(&self) -> Element<'_, RoutedMessage, crate::Theme, Renderer> {
+            unreachable!()
+        }
+
+        fn theme(&self) -> crate::Theme {
+            unreachable!()
+        }
+    }
+
+    /// A host's context that logs the parameter changes it receives.
+    struct LoggingContext(Log);
+
+    impl GuiContext for LoggingContext {
+        fn plugin_api(&self) -> PluginApi {
+            PluginApi::Clap
+        }
+
+        fn request_resize(&self) -> bool {
+            false
+        }
+
+        unsafe fn raw_begin_set_parameter(&self, _param: ParamPtr) {
+            self.0.lock().unwrap().push(String::from("begin"));
+        }
+
+        unsafe fn raw_set_parameter_normalized(&self, _param: ParamPtr, normalized: f32) {
+            self.0.lock().unwrap().push(format!("set {normalized}"));
+        }
+
+        unsafe fn raw_end_set_parameter(&self, _param: ParamPtr) {
+            self.0.lock().unwrap().push(String::from("end"));
+        }
+
+        fn get_state(&self) -> PluginState {
+            unimplemented!()
+        }
+
+        fn set_state(&self, _state: PluginState) {
+            unimplemented!()
+        }
+    }
+
+    /// The host's context, which the editor never uses since its window never opens.
+    struct NoContext;
+
+    impl GuiContext for NoContext {
+        fn plugin_api(&self) -> PluginApi {
+            PluginApi::Clap
+        }
+
+        fn request_resize(&self) -> bool {
+            false
+        }
+
+        unsafe fn raw_begin_set_parameter(&self, _param: ParamPtr) {}
+
+        unsafe fn raw_set_parameter_normalized(&self, _param: ParamPtr, _normalized: f32) {}
+
+        unsafe fn raw_end_set_parameter(&self, _param: ParamPtr) {}
+
+        fn get_state(&self) -> PluginState {
+            unimplemented!()
+        }
+
+        fn set_state(&self, _state: PluginState) {
+            unimplemented!()
+        }
+    }
+
+    #[test]
+    fn failing_to_open_is_reported() {
+        let iced_state = IcedState::from_size(200, 100);
+        let mut editor = IcedEditorWrapper::<Unopened>::new(iced_state.clone(), (), Vec::new());
+        let spawn = |editor: &IcedEditorWrapper<Unopened>| {
+            editor.spawn(ParentWindowHandle::X11Window(0), Arc::new(NoContext))
+        };
+
+        // Like baseview failing to create the window
+        editor.open_window = |_parent, _flags, _settings| Err(Error::WindowCreationFailed);
+        let handle = spawn(&editor);
+        assert!(!iced_state.is_open());
+        assert!(matches!(
+            iced_state.last_open_error().as_deref(),
+            Some(Error::WindowCreationFailed)
+        ));
+
+        // Dropping the handle when the host closes the editor leaves the state alone
+        drop(handle);
+        assert!(iced_state.last_open_error().is_some());
+
+        // Like the application failing to start in the window, which replaces the previous error
+        editor.open_window = |_parent, _flags, _settings| {
+            Err(Error::ExecutorCreationFailed(futures::io::Error::other(
+                "no threads left",
+            )))
+        };
+        let _handle = spawn(&editor);
+        assert!(!iced_state.is_open());
+        assert!(matches!(
+            iced_state.last_open_error().as_deref(),
+            Some(Error::ExecutorCreationFailed(_))
+        ));
+    }
+
+    #[test]
+    fn parameter_updates_reach_an_idle_gui() {
+        let iced_state = IcedState::from_size(200, 100);
+        let editor = IcedEditorWrapper::<Unopened>::new(iced_state, (), Vec::new());
+        let recipes = into_recipes(wrapper::parameter_updates::<Unopened>(
+            editor.parameter_updates_receiver.clone(),
+        ));
+        let [recipe]: [_; 1] = recipes.try_into().ok().unwrap();
+        let mut updates = recipe.stream(stream::empty().boxed());
+        let waker = task::noop_waker();
+        let mut context = Context::from_waker(&waker);
+
+        // The updates are forwarded by another thread, so they take a moment to arrive
+        let mut next_update = || {
+            let deadline = Instant::now() + Duration::from_secs(5);
+            loop {
+                match updates.poll_next_unpin(&mut context) {
+                    Poll::Ready(update) => return Some(update),
+                    Poll::Pending if Instant::now() >= deadline => return None,
+                    Poll::Pending => std::thread::sleep(Duration::from_millis(1)),
+                }
+            }
+        };
+
+        editor.param_value_changed("gain", 0.5);
+        assert!(matches!(
+            next_update(),
+            Some(Some(wrapper::Message::ParameterUpdate))
+        ));
+
+        // The host stops automating for a while, and the channel stays empty in the meantime
+        std::thread::sleep(Duration::from_millis(100));
+        editor.param_values_changed();
+        assert!(matches!(
+            next_update(),
+            Some(Some(wrapper::Message::ParameterUpdate))
+        ));
+
+        // The subscription only ends once the editor and its senders are gone
+        drop(editor);
+        assert!(matches!(next_update(), Some(None)));
+    }
+
+    #[test]
+    fn resizes_reach_the_editor() {
+        let (_sender, receiver) = channel::bounded(1);
+        let sizes = Arc::default();
+        let (mut application, _) = wrapper::IcedEditorWrapperApplication::<RecordsResizes>::new((
+            Arc::new(NoContext),
+            Arc::new(receiver),
+            IcedState::from_size(200, 100),
+            Arc::clone(&sizes),
+        ));
+
+        // Like the runtime does for every `Resized` event the widgets handled
+        let mut window_subs = WindowSubs::default();
+        let _ = application.subscription(&mut window_subs);
+        let on_resize = window_subs
+            .on_resize
+            .expect("The editor's callback should be forwarded");
+        let waker = task::noop_waker();
+        let mut context = Context::from_waker(&waker);
+        for size in [Size::new(300.0, 200.0), Size::new(400.0, 300.0)] {
+            // Tasks yield once before producing their output
+            let mut resized = crate::runtime::task::into_stream(on_resize(size).unwrap()).unwrap();
+            let message = (0..8)
+                .find_map(|_| match resized.poll_next_unpin(&mut context) {
+                    Poll::Ready(Some(crate::runtime::Action::Output(message))) => Some(message),
+                    _ => None,
+                })
+                .expect("The callback should produce a message");
+            let _ = application.update(message);
+        }
+
+        assert_eq!(
+            *sizes.lock().unwrap(),
+            [Size::new(300.0, 200.0), Size::new(400.0, 300.0)]
+        );
+    }
+
+    #[test]
+    fn clipboard_watches_reach_the_editor() {
+        let (_sender, receiver) = channel::bounded(1);
+        let changes = Arc::default();
+        let (mut application, _) =
+            wrapper::IcedEditorWrapperApplication::<WatchesClipboard>::new((
+                Arc::new(NoContext),
+                Arc::new(receiver),
+                IcedState::from_size(200, 100),
+                Arc::clone(&changes),
+            ));
+
+        let mut window_subs = WindowSubs::default();
+        let _ = application.subscription(&mut window_subs);
+        let watch = window_subs
+            .on_clipboard_change
+            .expect("The editor's clipboard watch should be forwarded");
+        let mut poller = crate::clipboard::ClipboardPoller::default();
+        let message = poller
+            .poll(Some(&watch), &crate::core::clipboard::Null, Instant::now())
+            .expect("The initial availability should be reported");
+        let _ = application.update(message);
+
+        assert_eq!(*changes.lock().unwrap(), [false]);
+    }
+
+    #[test]
+    fn param_messages_are_routed_to_the_context() {
+        let param = FloatParam::new("Gain", 0.5, FloatRange::Linear { min: 0.0, max: 1.0 });
+        let (_sender, receiver) = channel::bounded(1);
+        let log = Log::default();
+        let (mut application, _) = wrapper::IcedEditorWrapperApplication::<RoutesParams>::new((
+            Arc::new(LoggingContext(Arc::clone(&log))),
+            Arc::new(receiver),
+            IcedState::from_size(200, 100),
+            Arc::clone(&log),
+        ));
+
+        // Like a slider being dragged, with one of the editor's own messages in between
+        for message in [
+            RoutedMessage::Param(ParamMessage::BeginSetParameter(param.as_ptr())),
+            RoutedMessage::Param(ParamMessage::SetParameterNormalized(param.as_ptr(), 0.25)),
+            RoutedMessage::Other,
+            RoutedMessage::Param(ParamMessage::EndSetParameter(param.as_ptr())),
+        ] {
+            let _ = application.update(wrapper::Message::EditorMessage(message));
+        }
+
+        assert_eq!(
+            *log.lock().unwrap(),
+            ["begin", "set 0.25", "update Other", "end"]
+        );
+    }
+}
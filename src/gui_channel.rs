@@ -0,0 +1,201 @@
+//! Sending values from the plugin to the open editor.
+//!
+//! Parameter changes reach the editor on their own, but a plugin sometimes has other news for the
+//! GUI, for instance when a preset database scan that runs on one of the plugin's own threads
+//! finishes. [`IcedState::send_to_gui()`][crate::IcedState::send_to_gui()] sends such a value
+//! from any thread, and the editor receives it through the [`gui_channel()`] subscription for that
+//! value's type:
+//!
+//! ```ignore
+//! // In the plugin
+//! let _ = editor_state.send_to_gui(PresetScanFinished(presets));
+//!
+//! // In the editor
+//! fn subscription(
+//!     &self,
+//!     _window_subs: &mut WindowSubs<Self::Message>,
+//! ) -> Subscription<Self::Message> {
+//!     gui_channel::<PresetScanFinished>().map(Message::PresetsScanned)
+//! }
+//! ```
+//!
+//! Values are only delivered while the window is open and the editor is subscribed to their type.
+//! Sending returns [`GuiClosed`] otherwise instead of keeping the value around for a window that
+//! may never open, so the plugin can hold on to its latest state and hand it to the editor when
+//! it's created. Values sent while the window is open are queued until the editor handles them,
+//! even while rendering is paused. Sending briefly takes a lock and allocates, so it should not be
+//! done from the audio thread.
+
+use std::any::{Any, TypeId};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::marker::PhantomData;
+use std::sync::{Arc, Mutex};
+
+use crate::futures::futures::channel::mpsc;
+use crate::futures::subscription::{from_recipe, EventStream, Hasher, Recipe};
+use crate::futures::{BoxStream, Subscription};
+
+thread_local! {
+    /// The channels of the editor whose subscriptions are currently being collected.
+    static CHANNELS: RefCell<Option<Arc<GuiChannels>>> = const { RefCell::new(None) };
+}
+
+/// The error returned by [`IcedState::send_to_gui()`][crate::IcedState::send_to_gui()] when the
+/// editor's window is closed, or when the editor is not subscribed to [`gui_channel()`] for the
+/// value's type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[error("the editor is not open or does not receive values of this type")]
+pub struct GuiClosed;
+
+/// The values the editor receives through `gui_channel::<T>()`. The editor's `subscription()`
+/// should keep returning this for as long as it wants to receive them. This only works from within
+/// [`IcedEditor::subscription()`][crate::IcedEditor::subscription()], and it returns an empty
+/// subscription anywhere else. See the [module documentation][self].
+pub fn gui_channel<T: Send + 'static>() -> Subscription<T> {
+    match CHANNELS.with(|channels| channels.borrow().clone()) {
+        Some(channels) => from_recipe(GuiChannelRecipe::<T> {
+            channels,
+            _value: PhantomData,
+        }),
+        None => {
+            nih_plug::nih_debug_assert_failure!(
+                "gui_channel() was called outside of IcedEditor::subscription()"
+            );
+            Subscription::none()
+        }
+    }
+}
+
+/// Make `channels` available to [`gui_channel()`] while `f` collects the editor's subscriptions.
+pub(crate) fn with_channels<R>(channels: &Arc<GuiChannels>, f: impl FnOnce() -> R) -> R {
+    let previous = CHANNELS.with(|current| current.replace(Some(channels.clone())));
+    let result = f();
+    CHANNELS.with(|current| *current.borrow_mut() = previous);
+
+    result
+}
+
+/// The senders for the editor's active [`gui_channel()`] subscriptions, keyed by the value type.
+#[derive(Debug, Default)]
+pub(crate) struct GuiChannels {
+    /// Every value is an `mpsc::UnboundedSender<T>` for the type its key belongs to.
+    senders: Mutex<HashMap<TypeId, Box<dyn Any + Send>>>,
+}
+
+impl GuiChannels {
+    /// Send `value` to the editor's `gui_channel::<T>()` subscription, if it has one.
+    pub fn send<T: Send + 'static>(&self, value: T) -> Result<(), GuiClosed> {
+        let mut senders = self.senders.lock().unwrap();
+        let sender = senders
+            .get(&TypeId::of::<T>())
+            .and_then(|sender| sender.downcast_ref::<mpsc::UnboundedSender<T>>())
+            .ok_or(GuiClosed)?;
+
+        // The receiver is dropped when the editor unsubscribes or when the window closes
+        if sender.unbounded_send(value).is_err() {
+            senders.remove(&TypeId::of::<T>());
+            return Err(GuiClosed);
+        }
+
+        Ok(())
+    }
+
+    /// Start a new channel for `T`, replacing the previous one.
+    fn register<T: Send + 'static>(&self) -> mpsc::UnboundedReceiver<T> {
+        let (sender, receiver) = mpsc::unbounded::<T>();
+        self.senders
+            .lock()
+            .unwrap()
+            .insert(TypeId::of::<T>(), Box::new(sender));
+
+        receiver
+    }
+
+    /// Drop all channels. Called when the window closes, so values sent while the runtime is still
+    /// shutting down are refused as well.
+    pub fn close(&self) {
+        self.senders.lock().unwrap().clear();
+    }
+}
+
+/// The subscription behind [`gui_channel()`]. The stream awaits the channel, so the executor is
+/// woken up as soon as a value is sent and the message is picked up like any other subscription
+/// output.
+struct GuiChannelRecipe<T> {
+    channels: Arc<GuiChannels>,
+    _value: PhantomData<fn() -> T>,
+}
+
+impl<T: Send + 'static> Recipe for GuiChannelRecipe<T> {
+    type Output = T;
+
+    fn hash(&self, state: &mut Hasher) {
+        // There's one channel per value type
+        TypeId::of::<Self>().hash(state);
+    }
+
+    fn stream(self: Box<Self>, _input: EventStream) -> BoxStream<Self::Output> {
+        Box::pin(self.channels.register::<T>())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::futures::futures::task::{self, ArcWake, Context, Poll};
+    use crate::futures::futures::{stream, StreamExt};
+    use crate::futures::subscription::into_recipes;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    #[derive(Debug, PartialEq)]
+    struct ScanFinished(usize);
+
+    /// Stands in for the executor, which is woken up when the stream has something new.
+    #[derive(Default)]
+    struct Woken(AtomicBool);
+
+    impl ArcWake for Woken {
+        fn wake_by_ref(woken: &Arc<Self>) {
+            woken.0.store(true, Ordering::Release);
+        }
+    }
+
+    #[test]
+    fn values_reach_the_subscription() {
+        let channels = Arc::new(GuiChannels::default());
+        assert_eq!(channels.send(ScanFinished(0)), Err(GuiClosed));
+
+        let recipes = into_recipes(with_channels(&channels, gui_channel::<ScanFinished>));
+        let [recipe]: [_; 1] = recipes.try_into().ok().unwrap();
+        let mut values = recipe.stream(stream::empty().boxed());
+        let woken = Arc::new(Woken::default());
+        let waker = task::waker(woken.clone());
+        let mut context = Context::from_waker(&waker);
+        assert!(values.poll_next_unpin(&mut context).is_pending());
+
+        // Values sent from the plugin's own threads wake up the stream
+        let sender = channels.clone();
+        let scan = std::thread::spawn(move || sender.send(ScanFinished(42)));
+        assert_eq!(scan.join().unwrap(), Ok(()));
+        assert!(woken.0.load(Ordering::Acquire));
+        assert_eq!(
+            values.poll_next_unpin(&mut context),
+            Poll::Ready(Some(ScanFinished(42)))
+        );
+
+        // Only the subscribed type is accepted
+        assert_eq!(channels.send(1u32), Err(GuiClosed));
+
+        // Unsubscribing and closing the window both refuse further values
+        drop(values);
+        assert_eq!(channels.send(ScanFinished(1)), Err(GuiClosed));
+        assert!(channels.senders.lock().unwrap().is_empty());
+
+        let _values = channels.register::<ScanFinished>();
+        assert_eq!(channels.send(ScanFinished(2)), Ok(()));
+        channels.close();
+        assert_eq!(channels.send(ScanFinished(3)), Err(GuiClosed));
+    }
+}
@@ -1,10 +1,15 @@
 //! Create interactive, native cross-platform applications.
-#[cfg(feature = "trace")]
+mod boost;
+mod child;
+mod idle;
+mod momentum;
 mod profiler;
+mod queue;
+mod resize;
 mod state;
+mod tasks;
 
 use baseview::EventStatus;
-
 use iced_debug::Span;
 use iced_runtime::Action;
 use iced_runtime::Task;
@@ -12,14 +17,29 @@ use iced_widget::core::Color;
 use iced_widget::core::Element;
 use iced_widget::Theme;
 use raw_window_handle::HasRawDisplayHandle;
-pub use state::State;
 
+pub use queue::{queue_depth, EventQueueDepth};
+pub use state::{Changes, State};
+
+use boost::InteractionBoost;
+use child::ChildState;
+use idle::IdleTrimmer;
+use momentum::MomentumDamping;
+use profiler::Stage;
+use queue::EventBacklog;
+use resize::ResizeScaler;
+use tasks::TaskLimiter;
+
+use crate::child_window::{ChildWindowRequest, ChildWindowSettings, ChildWindowStatus};
+use crate::clipboard::ClipboardPoller;
 use crate::core::renderer;
 use crate::core::widget::operation;
-use crate::core::Size;
+use crate::core::{mouse, Point, Size};
 use crate::futures::futures;
 use crate::futures::{Executor, Runtime, Subscription};
 use crate::graphics::compositor::{self, Compositor};
+use crate::graphics::Viewport;
+use crate::graphics_cache::InstanceGuard;
 use crate::runtime::clipboard;
 use crate::runtime::user_interface::{self, UserInterface};
 use crate::window::{IcedWindow, RuntimeEvent, WindowQueue, WindowSubs};
@@ -27,14 +47,13 @@ use crate::{Clipboard, Error, Proxy, Renderer, Settings};
 
 use futures::channel::mpsc;
 
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::mem::ManuallyDrop;
+use std::path::PathBuf;
 use std::rc::Rc;
+use std::time::Instant;
 
-#[cfg(feature = "trace")]
 pub use profiler::Profiler;
-#[cfg(feature = "trace")]
-use tracing::{info_span, instrument::Instrument};
 
 /// An interactive, native cross-platform application.
 ///
@@ -97,11 +116,50 @@ where
     /// runtime.
     fn update(&mut self, message: Self::Message) -> Task<Self::Message>;
 
+    /// Called with every __message__ right before it is passed to
+    /// [`update`](#tymethod.update). Returning `false` drops the message, so
+    /// it never reaches `update`. This can be used for cross-cutting concerns
+    /// like logging or validation.
+    ///
+    /// Messages from widgets, tasks, and subscriptions all go through this
+    /// hook in the order they were produced, and a message is only passed to
+    /// `update` after the messages before it have been updated. The user
+    /// interface is still rebuilt and redrawn after a message is dropped, but
+    /// anything `update` would have done in response to it is skipped. For
+    /// instance, a dropped text input message leaves the text input's value
+    /// unchanged, and a dropped message's [`Task`] is never run.
+    ///
+    /// By default, it passes every message through.
+    fn pre_update(&mut self, _message: &Self::Message) -> bool {
+        true
+    }
+
     /// Returns the widgets to display in the [`Application`] for the main window.
     ///
     /// These widgets can produce __messages__ based on user interaction.
     fn view(&self) -> Element<'_, Self::Message, Self::Theme, Renderer>;
 
+    /// Returns the widgets to display in the optional child window. The child window is opened
+    /// when this returns `Some` and it's closed again when this returns `None`. Messages produced
+    /// by these widgets are handled by [`update`](#tymethod.update) like any other message.
+    ///
+    /// See the [`child_window`][crate::child_window] module for more information and the host
+    /// limitations. By default, it returns `None`.
+    fn child_view(&self) -> Option<Element<'_, Self::Message, Self::Theme, Renderer>> {
+        None
+    }
+
+    /// Returns the settings used to open the child window.
+    fn child_window_settings(&self) -> ChildWindowSettings {
+        ChildWindowSettings::default()
+    }
+
+    /// Called when the child window is opened, when it is closed by the user, or when it could not
+    /// be opened and its contents should be shown in the main window instead.
+    ///
+    /// By default, it does nothing.
+    fn child_window_status_changed(&mut self, _status: ChildWindowStatus) {}
+
     /// Returns the current `Theme` of the [`Application`].
     fn theme(&self) -> Self::Theme;
 
@@ -117,6 +175,9 @@ where
     /// [`update`](#tymethod.update).
     ///
     /// A `Subscription` will be kept alive as long as you keep returning it!
+    /// The same goes for the callbacks in `window_subs`: they start out empty
+    /// on every call, so a callback stops firing as soon as you stop setting
+    /// it.
     ///
     /// By default, it returns an empty subscription.
     fn subscription(
@@ -136,12 +197,96 @@ where
         baseview::WindowScalePolicy::SystemScaleFactor
     }
 
+    /// Called when the scale factor used to convert between logical and
+    /// physical coordinates changes, and once when the window is opened.
+    ///
+    /// By default, it does nothing.
+    fn scale_factor_changed(&mut self, _scale_factor: f32) {}
+
     /// Ignore non-modifier keyboard keys. Overrides the field in
     /// `IcedBaseviewSettings` if set
     fn ignore_non_modifier_keys(&self) -> Option<bool> {
         None
     }
 
+    /// The [`InteractionSettings`][crate::InteractionSettings] used by the widgets. Overrides the
+    /// field in `IcedBaseviewSettings` if set
+    fn interaction_settings(&self) -> Option<crate::InteractionSettings> {
+        None
+    }
+
+    /// The categories of baseview events forwarded to iced. Overrides the field in
+    /// `IcedBaseviewSettings` if set. This is checked for every event, so the filter can be
+    /// changed at runtime by changing the application's state.
+    fn event_filter(&self) -> Option<crate::settings::EventFilter> {
+        None
+    }
+
+    /// Whether the render loop is paused. This is checked before handling every event, so the
+    /// loop can be paused and resumed from anywhere by changing what this returns, for instance
+    /// through an `Arc<AtomicBool>` shared with the host integration.
+    ///
+    /// While paused, the window stays open but nothing is updated, drawn, or presented:
+    ///
+    /// - Mouse, keyboard, and child window input is dropped. The window's size, scale factor, and
+    ///   cursor position are still tracked so the first frame after resuming is laid out correctly.
+    /// - Messages, including the output of running tasks and subscriptions, are buffered and handled
+    ///   in order after resuming. Queued tasks are not started.
+    /// - Closing the window works as usual, including the
+    ///   [`on_window_will_close`][crate::window::WindowSubs::on_window_will_close] callback.
+    ///
+    /// Resuming updates the application and redraws the window on the next frame, even if nothing
+    /// changed in the meantime. Unlike a window that isn't redrawn because nothing changed, a
+    /// paused window doesn't respond to input at all. baseview doesn't report when a window is
+    /// hidden or occluded, so the runtime never pauses on its own.
+    ///
+    /// By default, the render loop is never paused.
+    fn is_rendering_paused(&self) -> bool {
+        false
+    }
+
+    /// Whether the parameter widgets should ignore input, for instance while a preset is locked or
+    /// while the host reports the project as read-only. This is checked before handling every
+    /// event, and it's made available to widgets through
+    /// [`interaction::is_input_locked()`][crate::interaction::is_input_locked()].
+    ///
+    /// The built-in parameter widgets keep showing live values while locked, drawn with their
+    /// disabled style. They don't start new drags, scrolls, or text entries, and a drag that's in
+    /// progress when the editor gets locked ends right away with a matching end of the automation
+    /// gesture. Other widgets like buttons are not affected. Custom widgets that edit parameters
+    /// should check the lock themselves.
+    ///
+    /// By default, input is never locked.
+    fn is_input_locked(&self) -> bool {
+        false
+    }
+
+    /// Whether the window should close. This is checked after every
+    /// [`update`](#tymethod.update), so the application can close its own window from there
+    /// without returning a task. Returning a task from [`iced_runtime::exit()`] or
+    /// [`window::close()`][crate::window::close()] closes the window as well.
+    ///
+    /// The window is closed the same way as when the host closes it, so the
+    /// [`on_window_will_close`][crate::window::WindowSubs::on_window_will_close] callback is still
+    /// called and the application is updated one last time with its messages.
+    ///
+    /// By default, the window is never closed.
+    fn should_exit(&self) -> bool {
+        false
+    }
+
+    /// Resolve a key press or release before the widgets see it. This is usually implemented by
+    /// forwarding to [`Keymap::resolve()`][crate::Keymap::resolve()]. See the
+    /// [`keymap`][crate::keymap] module for when this is called.
+    ///
+    /// By default, no keys are resolved.
+    fn resolve_key(
+        &self,
+        _event: &crate::keyboard::Event,
+    ) -> Option<crate::keymap::Resolution<Self::Message>> {
+        None
+    }
+
     //fn renderer_settings() -> crate::renderer::Settings;
 }
 
@@ -188,18 +333,13 @@ pub(crate) fn run<A, C>(
 ) -> Result<IcedWindow<A>, Error>
 where
     A: Application + 'static + Send,
-    C: Compositor<Renderer = Renderer> + 'static,
+    C: Compositor<Renderer = Renderer> + Send + 'static,
     A::Theme: DefaultStyle,
 {
     use futures::task;
 
-    #[cfg(feature = "trace")]
-    let _guard = Profiler::init();
-
     let boot_trace = iced_debug::boot();
-
-    #[cfg(feature = "trace")]
-    let _ = info_span!("Application", "RUN").entered();
+    let opened = Instant::now();
 
     let viewport = {
         // Assume scale for now until there is an event with a new one.
@@ -218,8 +358,9 @@ where
 
     let (runtime_tx, runtime_rx) = mpsc::unbounded::<Action<A::Message>>();
 
+    let proxy = Proxy::new(runtime_tx);
+    let wakeup = proxy.wakeup();
     let mut runtime = {
-        let proxy = Proxy::new(runtime_tx);
         let executor = A::Executor::new().map_err(Error::ExecutorCreationFailed)?;
 
         Runtime::new(executor, proxy)
@@ -227,8 +368,11 @@ where
 
     let (application, init_task) = runtime.enter(|| A::new(flags));
 
+    let mut tasks = TaskLimiter::new(settings.iced_baseview.task_limit);
     if let Some(stream) = crate::runtime::task::into_stream(init_task) {
-        runtime.run(stream);
+        if let Some(stream) = tasks.admit(stream) {
+            runtime.run(stream);
+        }
     }
 
     let mut window_subs = WindowSubs::default();
@@ -243,14 +387,37 @@ where
 
     let window06 = crate::conversion::convert_window(window);
 
-    let graphics_settings = settings.graphics_settings;
-    let mut compositor = runtime.block_on(C::new(graphics_settings, window06.clone()))?;
-    let surface = compositor.create_surface(
+    // With a graphics cache, the compositor and renderer of a window that was closed a moment ago
+    // are reused. See the `graphics_cache` module.
+    let graphics_started = Instant::now();
+    let instance_guard = settings
+        .graphics_cache
+        .as_ref()
+        .map(|cache| cache.instance_started());
+    let cached_graphics = instance_guard
+        .as_ref()
+        .and_then(|guard| guard.cache().take::<(C, Renderer)>());
+    let reused_graphics = cached_graphics.is_some();
+
+    let (mut compositor, mut renderer) = match cached_graphics {
+        Some(graphics) => graphics,
+        None => {
+            let graphics_settings = settings.graphics_settings;
+            let compositor = runtime.block_on(C::new(graphics_settings, window06.clone()))?;
+            let renderer = compositor.create_renderer();
+
+            (compositor, renderer)
+        }
+    };
+    let mut surface = compositor.create_surface(
         window06,
         viewport.physical_width(),
         viewport.physical_height(),
     );
-    let renderer = compositor.create_renderer();
+    log::debug!(
+        "Initialized graphics in {:?} (reused: {reused_graphics})",
+        graphics_started.elapsed()
+    );
 
     for font in settings.fonts {
         compositor.load_font(font);
@@ -258,34 +425,56 @@ where
 
     let (window_queue, window_queue_rx) = WindowQueue::new();
     let event_status = Rc::new(RefCell::new(baseview::EventStatus::Ignored));
+    let interaction_boost = Rc::new(Cell::new(false));
 
     let state = State::new(&application, viewport);
 
-    let display_handle = crate::conversion::convert_raw_display_handle(window.raw_display_handle());
-    let clipboard = Clipboard::new(display_handle);
-
-    let instance = Box::pin({
-        let run_instance = run_instance::<A, C>(
-            application,
-            compositor,
-            renderer,
-            runtime,
-            event_receiver,
-            clipboard,
-            window_subs,
-            settings.iced_baseview,
-            surface,
-            event_status.clone(),
-            state,
-            window_queue,
-            boot_trace,
+    // Until the event loop draws the first frame the window would show whatever happened to be in
+    // the surface's memory, so it's cleared to the background color as soon as it exists. A reused
+    // renderer may still hold the previous window's last frame, which is discarded first.
+    let physical_size = state.physical_size();
+    if physical_size.width > 0 && physical_size.height > 0 {
+        renderer::Renderer::reset(
+            &mut renderer,
+            crate::core::Rectangle::with_size(state.logical_size()),
         );
+        match compositor.present(
+            &mut renderer,
+            &mut surface,
+            state.viewport(),
+            state.background_color(),
+            || {},
+        ) {
+            Ok(()) => log::debug!("Cleared the window {:?} after opening it", opened.elapsed()),
+            Err(error) => {
+                log::debug!("Could not clear the window before the first frame: {error:?}")
+            }
+        }
+    }
 
-        #[cfg(feature = "trace")]
-        let run_instance = run_instance.instrument(info_span!("Application", "LOOP"));
+    let display_handle = crate::conversion::convert_raw_display_handle(window.raw_display_handle());
+    let clipboard = Clipboard::new(display_handle);
 
-        run_instance
-    });
+    let profiler = Profiler::start(&settings.iced_baseview);
+    let instance = Box::pin(run_instance::<A, C>(
+        application,
+        compositor,
+        renderer,
+        runtime,
+        tasks,
+        event_receiver,
+        clipboard,
+        window_subs,
+        settings.iced_baseview,
+        surface,
+        event_status.clone(),
+        interaction_boost.clone(),
+        state,
+        window_queue,
+        instance_guard,
+        boot_trace,
+        profiler,
+    ));
 
     let runtime_context = task::Context::from_waker(task::noop_waker_ref());
 
@@ -296,8 +485,12 @@ where
         runtime_rx,
         window_queue_rx,
         event_status,
+        interaction_boost,
+        child_window: None,
 
         processed_close_signal: false,
+        finished: false,
+        wakeup,
     })
 }
 
@@ -307,6 +500,7 @@ async fn run_instance<A, C>(
     mut compositor: C,
     mut renderer: Renderer,
     mut runtime: Runtime<A::Executor, Proxy<A::Message>, iced_runtime::Action<A::Message>>,
+    mut tasks: TaskLimiter<iced_runtime::Action<A::Message>>,
     mut event_receiver: mpsc::UnboundedReceiver<RuntimeEvent<A::Message>>,
     mut clipboard: Clipboard,
     mut window_subs: WindowSubs<<A as Application>::Message>,
@@ -314,18 +508,23 @@ async fn run_instance<A, C>(
     settings: crate::settings::IcedBaseviewSettings,
     mut surface: C::Surface,
     event_status: Rc<RefCell<baseview::EventStatus>>,
+    interaction_boost: Rc<Cell<bool>>,
     mut state: State<A>,
     mut window_queue: WindowQueue,
+    instance_guard: Option<InstanceGuard>,
     boot_trace: Span,
+    mut profiler: Profiler,
 ) where
     // What an absolute monstrosity of generics.
-    C: Compositor<Renderer = Renderer> + 'static,
+    C: Compositor<Renderer = Renderer> + Send + 'static,
     A: Application + 'static,
     A::Theme: DefaultStyle,
 {
     use futures::stream::StreamExt;
 
     let mut viewport_version = state.viewport_version();
+    let mut scale_factor = state.scale_factor();
+    application.scale_factor_changed(scale_factor);
 
     let cache = user_interface::Cache::default();
     let mut events = Vec::new();
@@ -339,7 +538,23 @@ async fn run_instance<A, C>(
         &mut renderer,
         state.logical_size(),
         window_id,
+        &profiler,
     ));
+    #[cfg(feature = "debug")]
+    let mut cache_stats = crate::ui_cache::CacheStats::default();
+    #[cfg(feature = "debug")]
+    cache_stats.record(true);
+
+    let mut child = ChildState::<C::Surface>::new();
+    let mut child_interface = build_child_interface(
+        &application,
+        &mut child,
+        user_interface::Cache::default(),
+        &mut renderer,
+        &mut window_queue,
+        &settings,
+        state.scale_factor(),
+    );
 
     // Triggered whenever a baseview event gets sent
     let mut redraw_requested = true;
@@ -352,24 +567,130 @@ async fn run_instance<A, C>(
 
     let mut render_span = None;
 
+    let mut boost = InteractionBoost::new(settings.boost_during_interaction, settings.boost_linger);
+    let mut clipboard_poller = ClipboardPoller::default();
+    let mut momentum = MomentumDamping::new(settings.momentum_scroll_damping);
+    let mut backlog = EventBacklog::new(settings.event_queue_limit);
+    let mut child_momentum = MomentumDamping::new(settings.momentum_scroll_damping);
+    let mut resize_scaler = ResizeScaler::new(settings.resize_resolution);
+    let mut idle = IdleTrimmer::new(settings.idle_trim, Instant::now());
+    // The physical size the surface was last configured with, which is smaller than the window
+    // while resizing at a reduced resolution
+    let mut surface_size = state.physical_size();
+    // See `Application::is_rendering_paused()`
+    let mut rendering_paused = false;
+    // See `Application::is_input_locked()`
+    let mut input_locked = false;
+    // See the `hover` module
+    let mut hovered = None;
+    // The cursor position as of the last frame, see `WindowSubs::on_cursor_moved`
+    let mut reported_cursor = None;
+    // Set when a font is loaded while the window is open, see `Action::LoadFont`
+    let mut fonts_changed = false;
+    // The modifiers as of the last time the main and child window's widgets handled input, see
+    // `interaction::modifiers()`
+    let mut modifiers = state.modifiers();
+    let mut child_modifiers = child.modifiers;
+
     loop {
         // Empty the queue if possible
         let event = if let Ok(event) = event_receiver.try_next() {
+            backlog.received(true);
             event
         } else {
+            backlog.received(false);
             event_receiver.next().await
         };
 
         let Some(event) = event else {
+            // Manually drop the user interface
+            let _ = ManuallyDrop::into_inner(user_interface);
             break;
         };
 
+        // Other editors may run on the same thread, so this needs to be set before the widgets
+        // handle any events or the view is rebuilt
+        crate::interaction::set_current(
+            application
+                .interaction_settings()
+                .unwrap_or(settings.interaction),
+        );
+        crate::scale::set_drawing(state.scale_factor(), settings.snap_text_to_pixel);
+
+        if application.is_input_locked() != input_locked {
+            input_locked = !input_locked;
+            crate::interaction::set_input_locked(input_locked);
+
+            // Widgets end their gestures when they see the next event after the editor got locked,
+            // so they get a redraw request right away. That's also what redraws them with their
+            // disabled style. This must not be an input event, since it's broadcast to the
+            // application's subscriptions as well.
+            events.push(iced_runtime::core::Event::Window(
+                crate::core::window::Event::RedrawRequested(Instant::now()),
+            ));
+            did_process_event = true;
+        }
+        crate::interaction::set_input_locked(input_locked);
+        crate::interaction::set_modifiers(modifiers);
+
+        if application.is_rendering_paused() != rendering_paused {
+            rendering_paused = !rendering_paused;
+            idle.set_paused(rendering_paused, Instant::now());
+
+            // The last presented frame may be long out of date, so resuming always updates and
+            // redraws the window
+            if !rendering_paused {
+                let _ = idle.wake(Instant::now());
+                needs_update = true;
+                did_process_event = true;
+                redraw_requested = true;
+            }
+        }
+
+        // The previous frame has been presented by the time the next one starts, so this is the
+        // only place where the renderer can be replaced without losing anything that was drawn
+        if matches!(event, RuntimeEvent::MainEventsCleared) && idle.should_trim(Instant::now()) {
+            log::debug!("The window seems to be hidden, releasing the renderer's caches");
+            renderer = compositor.create_renderer();
+        }
+
+        // Text that was shaped while a font was still missing was measured with a fallback font.
+        // The widgets measure their text again when they're laid out during the next update, and
+        // the renderer's text caches still hold the old measurements, so they're thrown away
+        // together with the renderer.
+        if matches!(event, RuntimeEvent::MainEventsCleared) && fonts_changed {
+            log::debug!("A font was loaded, laying out the window again");
+            fonts_changed = false;
+            renderer = compositor.create_renderer();
+            needs_update = true;
+            did_process_event = true;
+            redraw_requested = true;
+        }
+
+        let host_closing = matches!(event, RuntimeEvent::WillClose);
         match event {
-            RuntimeEvent::MainEventsCleared => {
-                if let Some(message) = &window_subs.on_frame {
-                    if let Some(message) = message() {
-                        messages.push(message);
-                    }
+            RuntimeEvent::MainEventsCleared
+            | RuntimeEvent::InteractionFrame
+            | RuntimeEvent::RedrawRequested
+                if rendering_paused || idle.is_trimmed() =>
+            {
+                continue;
+            }
+            event @ (RuntimeEvent::MainEventsCleared | RuntimeEvent::InteractionFrame) => {
+                let now = Instant::now();
+                interaction_boost.set(boost.is_active(now));
+                if matches!(event, RuntimeEvent::MainEventsCleared) {
+                    start_frame(
+                        &window_subs,
+                        &mut runtime,
+                        &mut tasks,
+                        &mut messages,
+                        &mut reported_cursor,
+                        state.cursor(),
+                        &mut clipboard_poller,
+                        &clipboard,
+                        now,
+                    );
                 }
 
                 if !did_process_event
@@ -381,6 +702,8 @@ async fn run_instance<A, C>(
                 }
                 did_process_event = false;
 
+                let _ = backlog.observe(&mut events, messages.len());
+
                 if !events.is_empty() {
                     let interact_time = iced_debug::interact(window_id);
                     let (interface_state, statuses) = user_interface.update(
@@ -390,19 +713,44 @@ async fn run_instance<A, C>(
                         &mut clipboard,
                         &mut messages,
                     );
+                    modifiers = state.modifiers();
+                    crate::interaction::set_modifiers(modifiers);
 
                     needs_update |= matches!(interface_state, user_interface::State::Outdated,);
 
+                    // The cursor only moves with input, so this doesn't need to happen every
+                    // frame. Walking the widget tree is skipped entirely for applications that
+                    // don't track the hovered widget.
+                    if let Some(on_hover_changed) = &window_subs.on_hover_changed {
+                        let now_hovered = state.cursor().position().and_then(|cursor| {
+                            let mut find_hovered = crate::hover::FindHovered::new(cursor);
+                            user_interface.operate(&renderer, &mut find_hovered);
+                            find_hovered.into_hovered()
+                        });
+                        if now_hovered != hovered {
+                            hovered = now_hovered;
+                            if let Some(task) = on_hover_changed(hovered.clone()) {
+                                run_callback_task(task, &mut runtime, &mut tasks, &mut messages);
+                            }
+                        }
+                    } else {
+                        hovered = None;
+                    }
+
+                    let mut dropped_files = Vec::new();
                     for (event, status) in events.drain(..).zip(statuses.into_iter()) {
+                        boost.observe(&event, status, now);
+                        uncaptured_drop(&event, status, &mut dropped_files);
+
                         // Check for resize events and call on_resize callback
                         if let iced_runtime::core::Event::Window(
                             iced_runtime::core::window::Event::Resized(size),
                         ) = &event
                         {
-                            if let Some(on_resize) = &window_subs.on_resize {
-                                if let Some(message) = on_resize(*size) {
-                                    messages.push(message);
-                                }
+                            if let Some(task) =
+                                window_subs.on_resize.as_ref().and_then(|f| f(*size))
+                            {
+                                run_callback_task(task, &mut runtime, &mut tasks, &mut messages);
                             }
                         }
 
@@ -412,30 +760,100 @@ async fn run_instance<A, C>(
                             status,
                         });
                     }
+                    if !dropped_files.is_empty() {
+                        if let Some(task) = window_subs
+                            .on_files_dropped
+                            .as_ref()
+                            .and_then(|f| f(dropped_files))
+                        {
+                            run_callback_task(task, &mut runtime, &mut tasks, &mut messages);
+                        }
+                    }
                     interact_time.finish();
                 }
 
+                if let Some(child_interface) = child_interface.as_mut() {
+                    if !child.events.is_empty() {
+                        crate::interaction::set_modifiers(child_modifiers);
+                        let (interface_state, statuses) = child_interface.update(
+                            &child.events,
+                            child.cursor(),
+                            &mut renderer,
+                            &mut clipboard,
+                            &mut messages,
+                        );
+                        child_modifiers = child.modifiers;
+                        crate::interaction::set_modifiers(modifiers);
+
+                        needs_update |= matches!(interface_state, user_interface::State::Outdated);
+
+                        for (event, status) in child.events.drain(..).zip(statuses) {
+                            runtime.broadcast(crate::futures::subscription::Event::Interaction {
+                                window: child.window_id,
+                                event,
+                                status,
+                            });
+                        }
+                    }
+                }
+                child.events.clear();
+                interaction_boost.set(boost.is_active(now));
+
                 // The user interface update may have pushed a new message onto the stack
                 needs_update |= !messages.is_empty() || settings.always_redraw;
 
                 if needs_update {
                     needs_update = false;
 
-                    let cache = ManuallyDrop::into_inner(user_interface).into_cache();
+                    let mut cache = ManuallyDrop::into_inner(user_interface).into_cache();
+                    let mut child_cache = child_interface
+                        .map(UserInterface::into_cache)
+                        .unwrap_or_default();
+
+                    if state.scale_factor() != scale_factor {
+                        scale_factor = state.scale_factor();
+                        application.scale_factor_changed(scale_factor);
+                    }
+
+                    if let Some(status) = child.lifecycle.take_status_change() {
+                        application.child_window_status_changed(status);
+                    }
+
+                    #[cfg(feature = "debug")]
+                    crate::ui_cache::set_stats(cache_stats);
+                    crate::hover::set_hovered(hovered.clone());
 
                     // Update application
+                    let update_span = profiler.span(Stage::Update);
                     update(
                         &mut application,
                         &mut runtime,
+                        &mut tasks,
                         &mut messages,
                         &mut window_subs,
                         //&mut window_queue,
                     );
+                    drop(update_span);
+
+                    // The application may have asked for the user interface to be rebuilt from
+                    // scratch, which needs to happen before it's drawn again
+                    let invalidated = crate::ui_cache::take_invalidation();
+                    if invalidated {
+                        cache = user_interface::Cache::default();
+                        child_cache = user_interface::Cache::default();
+                    }
+                    #[cfg(feature = "debug")]
+                    cache_stats.record(invalidated);
 
                     // Update window
-                    state.synchronize(&application);
+                    let changes = state.synchronize(&application);
+                    // The new scale factor is reported to the application during the next update
+                    if changes.relayout {
+                        needs_update = true;
+                        did_process_event = true;
+                    }
 
-                    let should_exit = false; // FIXME
+                    let should_exit = application.should_exit();
 
                     user_interface = ManuallyDrop::new(build_user_interface(
                         &application,
@@ -443,14 +861,66 @@ async fn run_instance<A, C>(
                         &mut renderer,
                         state.logical_size(),
                         window_id,
+                        &profiler,
                     ));
+                    child_interface = build_child_interface(
+                        &application,
+                        &mut child,
+                        child_cache,
+                        &mut renderer,
+                        &mut window_queue,
+                        &settings,
+                        state.scale_factor(),
+                    );
 
+                    // Frames requested during the update are rendered right away so they match the
+                    // state the application was in when it requested them
+                    for request in crate::render::take_requests() {
+                        crate::scale::set_drawing(
+                            request.scale_factor,
+                            settings.snap_text_to_pixel,
+                        );
+                        let frame = crate::render::render(
+                            application.view(),
+                            &mut renderer,
+                            state.theme(),
+                            &renderer::Style {
+                                text_color: state.text_color(),
+                            },
+                            request.size,
+                            request.scale_factor,
+                            |renderer, viewport| {
+                                compositor.screenshot(renderer, viewport, state.background_color())
+                            },
+                        );
+                        let _ = request.sender.send(frame);
+                    }
+                    crate::scale::set_drawing(state.scale_factor(), settings.snap_text_to_pixel);
+
+                    // Opening or closing the child window may need to be reported back to the
+                    // application
+                    if child.lifecycle.has_status_change() {
+                        needs_update = true;
+                        did_process_event = true;
+                    }
+
+                    // The window is closed at the end of this iteration, see below
                     if should_exit {
-                        break;
+                        let _ = window_queue.close_window();
                     }
                 }
 
+                // Time-dependent widget state advances once per drawn frame. Drawing the widgets
+                // again after a relayout doesn't advance it a second time.
+                let mut ticks = crate::tick::RunTicks::new(now);
+                user_interface.operate(&renderer, &mut ticks);
+                if let Some(child_interface) = child_interface.as_mut() {
+                    child_interface.operate(&renderer, &mut ticks);
+                }
+
                 render_span = Some(iced_debug::draw(window_id));
+                idle.drew(now);
+                let draw_span = profiler.span(Stage::Draw);
                 user_interface.draw(
                     &mut renderer,
                     state.theme(),
@@ -459,10 +929,17 @@ async fn run_instance<A, C>(
                     },
                     state.cursor(),
                 );
+                drop(draw_span);
 
                 redraw_requested = true;
             }
             RuntimeEvent::UserEvent(message) => {
+                // Messages also bring back the caches, see `IcedBaseviewSettings::idle_trim`
+                if !rendering_paused && idle.wake(Instant::now()) {
+                    needs_update = true;
+                    did_process_event = true;
+                    redraw_requested = true;
+                }
                 run_action::<A, C>(
                     message,
                     &mut compositor,
@@ -471,11 +948,15 @@ async fn run_instance<A, C>(
                     &mut clipboard,
                     &mut user_interface,
                     &mut window_queue,
+                    &settings,
+                    &mut fonts_changed,
                 );
             }
             RuntimeEvent::RedrawRequested => {
-                #[cfg(feature = "trace")]
-                let _ = info_span!("Application", "FRAME").entered();
+                // The final frame after a resize needs to be rendered at full resolution again
+                if resize_scaler.needs_full_frame(Instant::now()) {
+                    redraw_requested = true;
+                }
 
                 // Set whenever a baseview event or message gets handled. Or as a stopgap workaround
                 // we can also just always redraw.
@@ -495,13 +976,16 @@ async fn run_instance<A, C>(
                     let logical_size = state.logical_size();
 
                     let layout_span = iced_debug::layout(window_id);
+                    let profiler_span = profiler.span(Stage::Layout);
                     user_interface = ManuallyDrop::new(
                         ManuallyDrop::into_inner(user_interface)
                             .relayout(logical_size, &mut renderer),
                     );
+                    drop(profiler_span);
                     layout_span.finish();
 
                     let draw_span = iced_debug::draw(window_id);
+                    let profiler_span = profiler.span(Stage::Draw);
                     user_interface.draw(
                         &mut renderer,
                         state.theme(),
@@ -510,24 +994,40 @@ async fn run_instance<A, C>(
                         },
                         state.cursor(),
                     );
+                    drop(profiler_span);
                     draw_span.finish();
 
-                    compositor.configure_surface(
-                        &mut surface,
-                        physical_size.width,
-                        physical_size.height,
-                    );
-
+                    resize_scaler.resized(Instant::now());
                     viewport_version = current_viewport_version;
                 }
 
-                match compositor.present(
+                let (frame_size, frame_scale_factor) =
+                    resize_scaler.frame(physical_size, state.scale_factor(), Instant::now());
+                if frame_size != surface_size {
+                    compositor.configure_surface(&mut surface, frame_size.width, frame_size.height);
+                    surface_size = frame_size;
+                }
+
+                let reduced_viewport;
+                let viewport = if frame_size == physical_size {
+                    state.viewport()
+                } else {
+                    reduced_viewport = Viewport::with_physical_size(frame_size, frame_scale_factor);
+                    &reduced_viewport
+                };
+
+                let present_span = profiler.span(Stage::Present);
+                let presented = compositor.present(
                     &mut renderer,
                     &mut surface,
-                    state.viewport(),
+                    viewport,
                     state.background_color(),
                     || {},
-                ) {
+                );
+                drop(present_span);
+                profiler.frame_presented();
+
+                match presented {
                     Ok(()) => {
                         // TODO: Handle animations!
                         // Maybe we can use `ControlFlow::WaitUntil` for this.
@@ -546,14 +1046,56 @@ async fn run_instance<A, C>(
                         }
                     },
                 }
+
+                if let Some(child_interface) = child_interface.as_mut() {
+                    // The child window may be on a monitor with a different scale factor
+                    crate::scale::set_drawing(
+                        child.viewport().scale_factor(),
+                        settings.snap_text_to_pixel,
+                    );
+                    child.redraw(
+                        &mut compositor,
+                        &mut renderer,
+                        child_interface,
+                        state.theme(),
+                        &renderer::Style {
+                            text_color: state.text_color(),
+                        },
+                        state.background_color(),
+                    );
+                }
             }
-            RuntimeEvent::Baseview((event, do_send_status)) => {
+            RuntimeEvent::Baseview((mut event, do_send_status)) => {
                 state.update(&event);
+                // The first event after the window became visible again brings back its caches,
+                // see `IcedBaseviewSettings::idle_trim`
+                if !rendering_paused && idle.wake(Instant::now()) {
+                    needs_update = true;
+                    did_process_event = true;
+                    redraw_requested = true;
+                }
+
+                // The state above is still kept up to date so the window renders correctly
+                let event_filter = application.event_filter().unwrap_or(settings.event_filter);
+                if rendering_paused || !event_filter.allows(&event) {
+                    if do_send_status {
+                        *event_status.borrow_mut() = EventStatus::Ignored;
+                    }
+                    continue;
+                }
+
+                momentum.apply(&mut event, Instant::now());
+
+                // The application is notified about scale factor changes the next time it's
+                // updated
+                needs_update |= state.scale_factor() != scale_factor;
 
                 let ignore_non_modifier_keys = application
                     .ignore_non_modifier_keys()
                     .unwrap_or(settings.ignore_non_modifier_keys);
 
+                let accepts_drop = crate::conversion::is_file_drag(&event);
+                let first_new_event = events.len();
                 crate::conversion::baseview_to_iced_events(
                     event,
                     &mut events,
@@ -561,50 +1103,268 @@ async fn run_instance<A, C>(
                     ignore_non_modifier_keys,
                 );
 
-                if events.is_empty() {
-                    if do_send_status {
-                        *event_status.borrow_mut() = EventStatus::Ignored;
-                    }
+                let keys_consumed = crate::keymap::apply(
+                    |event| application.resolve_key(event),
+                    || is_text_input_focused(&mut user_interface, &renderer),
+                    &mut events,
+                    first_new_event,
+                    &mut messages,
+                );
+                if do_send_status {
+                    *event_status.borrow_mut() = if keys_consumed {
+                        EventStatus::Captured
+                    } else if accepts_drop {
+                        EventStatus::AcceptDrop(baseview::DropEffect::Copy)
+                    } else {
+                        EventStatus::Ignored
+                    };
+                }
+
+                if events.is_empty() && !keys_consumed {
                     continue;
                 }
 
                 did_process_event = true;
             }
-            RuntimeEvent::WillClose => {
-                if let Some(message) = &window_subs.on_window_will_close {
-                    // Send message to user before exiting the loop.
+            RuntimeEvent::ChildBaseview(mut event) => {
+                if !child.lifecycle.is_open() {
+                    continue;
+                }
+                // The first event after the window became visible again brings back its caches,
+                // see `IcedBaseviewSettings::idle_trim`
+                if !rendering_paused && idle.wake(Instant::now()) {
+                    needs_update = true;
+                    did_process_event = true;
+                    redraw_requested = true;
+                }
+                child_momentum.apply(&mut event, Instant::now());
 
-                    if let Some(message) = message() {
-                        messages.push(message);
-                    }
-                    let cache = ManuallyDrop::into_inner(user_interface).into_cache();
+                // The child window's user interface is rebuilt for its new size
+                let viewport_version = child.viewport_version();
+                child.update(&event, application.scale_policy());
+                needs_update |= child.viewport_version() != viewport_version;
 
-                    update(
-                        &mut application,
-                        &mut runtime,
+                let event_filter = application.event_filter().unwrap_or(settings.event_filter);
+                if rendering_paused || !event_filter.allows(&event) {
+                    continue;
+                }
+
+                let ignore_non_modifier_keys = application
+                    .ignore_non_modifier_keys()
+                    .unwrap_or(settings.ignore_non_modifier_keys);
+
+                let first_new_event = child.events.len();
+                crate::conversion::baseview_to_iced_events(
+                    event,
+                    &mut child.events,
+                    &mut child.modifiers,
+                    ignore_non_modifier_keys,
+                );
+
+                if let Some(child_interface) = child_interface.as_mut() {
+                    let _ = crate::keymap::apply(
+                        |event| application.resolve_key(event),
+                        || is_text_input_focused(child_interface, &renderer),
+                        &mut child.events,
+                        first_new_event,
                         &mut messages,
-                        &mut window_subs,
                     );
+                }
 
-                    // Update window
-                    state.synchronize(&application);
-
-                    user_interface = ManuallyDrop::new(build_user_interface(
-                        &application,
-                        cache,
-                        &mut renderer,
-                        state.logical_size(),
-                        window_id,
+                did_process_event = true;
+            }
+            RuntimeEvent::ChildOpened(window) => {
+                // If the application stopped requesting the child window before it was created,
+                // then a request to close it has already been sent
+                if child.lifecycle.opened() {
+                    let physical_size = child.viewport().physical_size();
+                    child.surface = Some(compositor.create_surface(
+                        window,
+                        physical_size.width,
+                        physical_size.height,
                     ));
+                    child.surface_version = Some(child.viewport_version());
+
+                    needs_update = true;
+                    did_process_event = true;
                 }
+            }
+            RuntimeEvent::ChildClosed => {
+                child.lifecycle.closed();
+                child.surface = None;
 
-                break;
+                needs_update = true;
+                did_process_event = true;
             }
+            // Handled below together with the application closing the window itself
+            RuntimeEvent::WillClose => {}
+        }
+
+        if should_close(host_closing, &window_queue) {
+            // The user interface borrows the application, which is updated one last time
+            let _ = ManuallyDrop::into_inner(user_interface);
+            // The child window is closed together with the main window
+            drop(child_interface);
+
+            shut_down(
+                &mut application,
+                &mut runtime,
+                &mut tasks,
+                &mut messages,
+                &mut window_subs,
+                &profiler,
+            );
+
+            break;
         }
     }
 
-    // Manually drop the user interface
-    let _ = ManuallyDrop::into_inner(user_interface);
+    // The surfaces belong to the windows, but the compositor and renderer can be reused by the
+    // next window. Dropping the guard afterwards lets that window start.
+    if let Some(guard) = instance_guard {
+        drop(child);
+        drop(surface);
+        guard.cache().release((compositor, renderer));
+    }
+
+    profiler.finish();
+}
+
+/// Whether a text field in the user interface is focused. The keymap is skipped while this is the
+/// case.
+fn is_text_input_focused<Message, Theme>(
+    user_interface: &mut UserInterface<'_, Message, Theme, Renderer>,
+    renderer: &Renderer,
+) -> bool {
+    let mut operation = crate::keymap::FocusedTextInput::default();
+    user_interface.operate(renderer, &mut operation);
+
+    operation.is_focused
+}
+
+/// Runs the window callbacks that are called once per frame, before the frame's events and
+/// messages are handled. This also starts the tasks that were waiting for the task limit, and
+/// polls the clipboard for [`WindowSubs::on_clipboard_change`].
+#[allow(clippy::too_many_arguments)]
+fn start_frame<Message: Send + 'static, E: Executor>(
+    window_subs: &WindowSubs<Message>,
+    runtime: &mut Runtime<E, Proxy<Message>, Action<Message>>,
+    tasks: &mut TaskLimiter<Action<Message>>,
+    messages: &mut Vec<Message>,
+    reported_cursor: &mut Option<Point>,
+    cursor: mouse::Cursor,
+    clipboard_poller: &mut ClipboardPoller,
+    clipboard: &dyn crate::core::Clipboard,
+    now: Instant,
+) {
+    if let Some(task) = window_subs.on_frame.as_ref().and_then(|f| f()) {
+        run_callback_task(task, runtime, tasks, messages);
+    }
+    if let Some(position) = cursor_moved(reported_cursor, cursor) {
+        if let Some(task) = window_subs
+            .on_cursor_moved
+            .as_ref()
+            .and_then(|f| f(position))
+        {
+            run_callback_task(task, runtime, tasks, messages);
+        }
+    }
+
+    for stream in tasks.start_queued() {
+        runtime.run(stream);
+    }
+
+    if let Some(message) =
+        clipboard_poller.poll(window_subs.on_clipboard_change.as_ref(), clipboard, now)
+    {
+        messages.push(message);
+    }
+}
+
+/// Returns the position to pass to [`WindowSubs::on_cursor_moved`] if the cursor moved since the
+/// `reported` position. This is called once per frame.
+fn cursor_moved(reported: &mut Option<Point>, cursor: mouse::Cursor) -> Option<Option<Point>> {
+    let position = cursor.position();
+    if position == *reported {
+        return None;
+    }
+    *reported = position;
+
+    Some(position)
+}
+
+/// Adds the file from `event` to `dropped` if it's a dropped file that no widget captured. The
+/// files that were collected while handling a batch of events are passed to
+/// [`WindowSubs::on_files_dropped`] together.
+fn uncaptured_drop(
+    event: &crate::core::Event,
+    status: crate::core::event::Status,
+    dropped: &mut Vec<PathBuf>,
+) {
+    if let crate::core::Event::Window(crate::core::window::Event::FileDropped(path)) = event {
+        if status == crate::core::event::Status::Ignored {
+            dropped.push(path.clone());
+        }
+    }
+}
+
+/// How often [`run_callback_task()`] polls a task before leaving the rest to the executor.
+const MAX_IMMEDIATE_POLLS: usize = 64;
+
+/// Runs a task returned by a [`WindowSubs`] callback. The messages the task produces right away
+/// are added after the messages that are already waiting, so they're handled in this frame in the
+/// order the callbacks were called in. The rest of the task runs like a task returned from
+/// [`Application::update()`].
+fn run_callback_task<Message: Send + 'static, E: Executor>(
+    task: Task<Message>,
+    runtime: &mut Runtime<E, Proxy<Message>, Action<Message>>,
+    tasks: &mut TaskLimiter<Action<Message>>,
+    messages: &mut Vec<Message>,
+) {
+    use futures::stream::{self, StreamExt};
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+    use std::task::{Context, Poll, Wake, Waker};
+
+    /// Tells apart tasks that yield, which iced's tasks do before they start, from tasks that are
+    /// waiting for something.
+    #[derive(Default)]
+    struct Woken(AtomicBool);
+
+    impl Wake for Woken {
+        fn wake(self: Arc<Self>) {
+            self.0.store(true, Ordering::Relaxed);
+        }
+    }
+
+    let Some(mut stream) = crate::runtime::task::into_stream(task) else {
+        return;
+    };
+
+    // The task's future may need the executor's context, just like in `update()`
+    let remaining = runtime.enter(|| {
+        let woken = Arc::new(Woken::default());
+        let waker = Waker::from(woken.clone());
+        let mut context = Context::from_waker(&waker);
+        for _ in 0..MAX_IMMEDIATE_POLLS {
+            woken.0.store(false, Ordering::Relaxed);
+            match stream.poll_next_unpin(&mut context) {
+                Poll::Ready(Some(Action::Output(message))) => messages.push(message),
+                Poll::Ready(Some(action)) => {
+                    return Some(stream::once(async { action }).chain(stream).boxed());
+                }
+                Poll::Ready(None) => return None,
+                Poll::Pending if woken.0.load(Ordering::Relaxed) => {}
+                Poll::Pending => return Some(stream),
+            }
+        }
+
+        Some(stream)
+    });
+
+    if let Some(stream) = remaining.and_then(|stream| tasks.admit(stream)) {
+        runtime.run(stream);
+    }
 }
 
 /// Builds a [`UserInterface`] for the provided [`Application`], logging
@@ -615,38 +1375,138 @@ pub fn build_user_interface<'a, A: Application>(
     renderer: &mut Renderer,
     size: Size,
     window_id: crate::window::Id,
+    profiler: &Profiler,
 ) -> UserInterface<'a, A::Message, A::Theme, Renderer>
 where
     A::Theme: DefaultStyle,
 {
-    #[cfg(feature = "trace")]
-    let view_span = info_span!("Application", "VIEW").entered();
-
     let view_span = iced_debug::view(window_id);
+    let profiler_span = profiler.span(Stage::View);
     let view = application.view();
+    drop(profiler_span);
     view_span.finish();
 
-    #[cfg(feature = "trace")]
-    let _ = view_span.exit();
-
-    #[cfg(feature = "trace")]
-    let layout_span = info_span!("Application", "LAYOUT").entered();
-
     let layout_span = iced_debug::layout(window_id);
+    let profiler_span = profiler.span(Stage::Layout);
     let user_interface = UserInterface::build(view, size, cache, renderer);
+    drop(profiler_span);
     layout_span.finish();
 
-    #[cfg(feature = "trace")]
-    let _ = layout_span.exit();
-
     user_interface
 }
 
+/// Builds the [`UserInterface`] for the [`Application`]'s child window, and opens or closes the
+/// child window depending on whether the application currently has a child view. See the
+/// [`child_window`][crate::child_window] module.
+fn build_child_interface<'a, A: Application, S>(
+    application: &'a A,
+    child: &mut ChildState<S>,
+    cache: user_interface::Cache,
+    renderer: &mut Renderer,
+    window_queue: &mut WindowQueue,
+    settings: &crate::settings::IcedBaseviewSettings,
+    scale_factor: f32,
+) -> Option<UserInterface<'a, A::Message, A::Theme, Renderer>>
+where
+    A::Theme: DefaultStyle,
+{
+    let view_span = iced_debug::view(child.window_id);
+    let view = application.child_view();
+    view_span.finish();
+
+    match child
+        .lifecycle
+        .sync(view.is_some(), settings.allow_child_window)
+    {
+        Some(ChildWindowRequest::Open) => {
+            let window_settings = application.child_window_settings();
+            child.reset(window_settings.size, scale_factor);
+
+            if window_queue
+                .open_child_window(window_settings, application.scale_policy())
+                .is_err()
+            {
+                child.lifecycle.failed();
+            }
+        }
+        Some(ChildWindowRequest::Close) => {
+            // The surface needs to be dropped before its window is
+            child.surface = None;
+            let _ = window_queue.close_child_window();
+        }
+        None => {}
+    }
+
+    if !child.lifecycle.is_active() {
+        return None;
+    }
+
+    let layout_span = iced_debug::layout(child.window_id);
+    let user_interface =
+        UserInterface::build(view?, child.viewport().logical_size(), cache, renderer);
+    layout_span.finish();
+
+    Some(user_interface)
+}
+
+/// Cancels the tasks that are still running and calls the
+/// [`on_window_will_close`][WindowSubs::on_window_will_close] callback, for a window that's about
+/// to close. The callback is taken out of `window_subs` so it can't run twice. Returns whether the
+/// callback was set, in which case the application should be updated with `messages` one last
+/// time before the run loop stops.
+fn window_will_close<Message: Send + 'static, E: Executor>(
+    window_subs: &mut WindowSubs<Message>,
+    runtime: &mut Runtime<E, Proxy<Message>, Action<Message>>,
+    tasks: &mut TaskLimiter<Action<Message>>,
+    messages: &mut Vec<Message>,
+) -> bool {
+    // Nothing will receive the messages of tasks that are still pending, and they should not keep
+    // running after the window is gone
+    tasks.cancel_all();
+
+    let Some(on_window_will_close) = window_subs.on_window_will_close.take() else {
+        return false;
+    };
+    if let Some(task) = on_window_will_close() {
+        run_callback_task(task, runtime, tasks, messages);
+    }
+
+    true
+}
+
+/// Whether the window needs to close after handling an event. The host closing the window,
+/// `Action::Exit`, `window::close()`, and [`Application::should_exit()`] all end up here, so the
+/// application is shut down the same way no matter who closed the window.
+fn should_close(host_closing: bool, window_queue: &WindowQueue) -> bool {
+    host_closing || window_queue.is_close_requested()
+}
+
+/// Shuts the application down once [`should_close()`] returns `true`. This runs
+/// [`WindowSubs::on_window_will_close`], and updates the application one last time with its
+/// messages. The user interface borrows the application, so it needs to be dropped first.
+fn shut_down<A: Application, E: Executor>(
+    application: &mut A,
+    runtime: &mut Runtime<E, Proxy<A::Message>, Action<A::Message>>,
+    tasks: &mut TaskLimiter<Action<A::Message>>,
+    messages: &mut Vec<A::Message>,
+    window_subs: &mut WindowSubs<A::Message>,
+    profiler: &Profiler,
+) where
+    A::Theme: DefaultStyle,
+{
+    if window_will_close(window_subs, runtime, tasks, messages) {
+        let update_span = profiler.span(Stage::Update);
+        update(application, runtime, tasks, messages, window_subs);
+        drop(update_span);
+    }
+}
+
 /// Updates an [`Application`] by feeding it the provided messages, spawning any
-/// resulting [`Command`], and tracking its [`Subscription`].
-pub fn update<A: Application, E: Executor>(
+/// resulting [`Command`] within the task limit, and tracking its [`Subscription`].
+pub(crate) fn update<A: Application, E: Executor>(
     application: &mut A,
     runtime: &mut Runtime<E, Proxy<A::Message>, iced_runtime::Action<A::Message>>,
+    tasks: &mut TaskLimiter<iced_runtime::Action<A::Message>>,
     messages: &mut Vec<A::Message>,
     window_subs: &mut WindowSubs<A::Message>,
     //window_queue: &mut WindowQueue,
@@ -654,26 +1514,31 @@ pub fn update<A: Application, E: Executor>(
     A::Theme: DefaultStyle,
 {
     for message in messages.drain(..) {
-        #[cfg(feature = "trace")]
-        let update_span = info_span!("Application", "UPDATE").entered();
+        if !application.pre_update(&message) {
+            continue;
+        }
 
         let task = runtime.enter(|| application.update(message));
 
-        #[cfg(feature = "trace")]
-        let _ = update_span.exit();
-
         if let Some(stream) = crate::runtime::task::into_stream(task) {
-            runtime.run(stream);
+            if let Some(stream) = tasks.admit(stream) {
+                runtime.run(stream);
+            }
         }
     }
 
+    // The window callbacks are rebuilt from scratch on every pass, so a callback the application no
+    // longer sets stops firing right away
+    *window_subs = WindowSubs::default();
     let subscription = runtime.enter(|| application.subscription(window_subs));
     runtime.track(crate::futures::subscription::into_recipes(
         subscription.map(Action::Output),
     ));
 }
 
-/// Runs the actions of a [`Command`].
+/// Runs the actions of a [`Command`]. `fonts_changed` is set when the action loaded a font, which
+/// means the window needs to be laid out again.
+#[allow(clippy::too_many_arguments)]
 pub fn run_action<A, C>(
     action: Action<A::Message>,
     compositor: &mut C,
@@ -682,6 +1547,8 @@ pub fn run_action<A, C>(
     clipboard: &mut Clipboard,
     interface: &mut UserInterface<'_, A::Message, A::Theme, Renderer>,
     window_queue: &mut WindowQueue,
+    settings: &crate::settings::IcedBaseviewSettings,
+    fonts_changed: &mut bool,
 ) where
     C: Compositor<Renderer = Renderer> + 'static,
     A: Application + 'static,
@@ -706,8 +1573,9 @@ pub fn run_action<A, C>(
                 let _ = window_queue.close_window();
             }
             IWindowAction::Resize(_, size) => {
-                nih_plug::nih_log!("Action::Window::Resize received - size: {}x{}", size.width, size.height);
-                let _ = window_queue.resize_window(size);
+                #[cfg(feature = "verbose-logging")]
+                log::trace!("Resizing the window to {}x{}", size.width, size.height);
+                let _ = window_queue.resize_window(settings.clamp_window_size(size));
             }
             IWindowAction::GainFocus(_) => {
                 let _ = window_queue.focus();
@@ -747,6 +1615,8 @@ pub fn run_action<A, C>(
         Action::LoadFont { bytes, channel } => {
             // TODO: Error handling (?)
             compositor.load_font(bytes.clone());
+            // The window is laid out again with the new font before the next frame
+            *fonts_changed = true;
 
             let _ = channel.send(Ok(()));
         }
@@ -757,3 +1627,432 @@ pub fn run_action<A, C>(
         Action::Reload => todo!(),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::futures::backend::null;
+
+    #[derive(Debug)]
+    enum Message {
+        ShowMeters(bool),
+        Frame,
+        SaveState,
+        Quit,
+    }
+
+    struct FrameCounter {
+        meters_visible: bool,
+    }
+
+    impl Application for FrameCounter {
+        type Message = Message;
+        type Theme = Theme;
+        type Executor = null::Executor;
+        type Flags = ();
+
+        fn new(_flags: Self::Flags) -> (Self, Task<Self::Message>) {
+            (
+                Self {
+                    meters_visible: true,
+                },
+                Task::none(),
+            )
+        }
+
+        fn update(&mut self, message: Self::Message) -> Task<Self::Message> {
+            if let Message::ShowMeters(visible) = message {
+                self.meters_visible = visible;
+            }
+
+            Task::none()
+        }
+
+        fn view(&self) -> Element<'_, Self::Message, Self::Theme, Renderer> {
+            crate::widget::Column::new().into()
+        }
+
+        fn theme(&self) -> Self::Theme {
+            Theme::Dark
+        }
+
+        fn subscription(
+            &self,
+            window_subs: &mut WindowSubs<Self::Message>,
+        ) -> Subscription<Self::Message> {
+            if self.meters_visible {
+                window_subs.on_frame_message(|| Some(Message::Frame));
+            }
+
+            Subscription::none()
+        }
+    }
+
+    /// Starts a frame with [`start_frame()`] like `run_instance()` does for `MainEventsCleared`,
+    /// and returns the messages for the frame.
+    fn run_frame(
+        window_subs: &WindowSubs<Message>,
+        runtime: &mut Runtime<null::Executor, Proxy<Message>, Action<Message>>,
+        tasks: &mut TaskLimiter<Action<Message>>,
+    ) -> Vec<Message> {
+        let mut messages = Vec::new();
+        start_frame(
+            window_subs,
+            runtime,
+            tasks,
+            &mut messages,
+            &mut None,
+            mouse::Cursor::Unavailable,
+            &mut ClipboardPoller::default(),
+            &Clipboard::unconnected(),
+            Instant::now(),
+        );
+
+        messages
+    }
+
+    #[test]
+    fn clearing_on_frame_stops_frame_messages() {
+        let (runtime_tx, _runtime_rx) = mpsc::unbounded();
+        let mut runtime = Runtime::new(null::Executor::new().unwrap(), Proxy::new(runtime_tx));
+        let mut tasks = TaskLimiter::new(None);
+        let (mut application, _) = FrameCounter::new(());
+        let mut window_subs = WindowSubs::default();
+        let mut messages = Vec::new();
+
+        let mut frame_messages = 0;
+        let mut count_frames = |messages: Vec<Message>| {
+            frame_messages += messages
+                .iter()
+                .filter(|message| matches!(message, Message::Frame))
+                .count();
+        };
+
+        update(
+            &mut application,
+            &mut runtime,
+            &mut tasks,
+            &mut messages,
+            &mut window_subs,
+        );
+        count_frames(run_frame(&window_subs, &mut runtime, &mut tasks));
+        count_frames(run_frame(&window_subs, &mut runtime, &mut tasks));
+
+        messages.push(Message::ShowMeters(false));
+        update(
+            &mut application,
+            &mut runtime,
+            &mut tasks,
+            &mut messages,
+            &mut window_subs,
+        );
+        count_frames(run_frame(&window_subs, &mut runtime, &mut tasks));
+        count_frames(run_frame(&window_subs, &mut runtime, &mut tasks));
+
+        assert_eq!(frame_messages, 2);
+        assert!(window_subs.on_frame.is_none());
+    }
+
+    #[test]
+    fn callback_tasks_keep_message_order() {
+        let (runtime_tx, _runtime_rx) = mpsc::unbounded();
+        let mut runtime = Runtime::new(null::Executor::new().unwrap(), Proxy::new(runtime_tx));
+        let mut tasks = TaskLimiter::new(None);
+        let mut messages = vec![Message::ShowMeters(true)];
+
+        // Messages that are available right away are handled after the waiting messages
+        let task = Task::done(Message::Frame).chain(Task::done(Message::ShowMeters(false)));
+        run_callback_task(task, &mut runtime, &mut tasks, &mut messages);
+        // Tasks that need to wait run on the executor instead
+        let task = Task::perform(std::future::pending::<()>(), |()| Message::Frame);
+        run_callback_task(task, &mut runtime, &mut tasks, &mut messages);
+
+        assert_eq!(
+            format!("{messages:?}"),
+            "[ShowMeters(true), Frame, ShowMeters(false)]"
+        );
+    }
+
+    #[test]
+    fn cursor_moves_are_reported_once_per_frame() {
+        let mut reported = None;
+        let mut frame = |cursor| cursor_moved(&mut reported, cursor);
+
+        let position = Point::new(12.5, 40.0);
+        assert_eq!(frame(mouse::Cursor::Unavailable), None);
+        assert_eq!(
+            frame(mouse::Cursor::Available(position)),
+            Some(Some(position))
+        );
+        assert_eq!(frame(mouse::Cursor::Available(position)), None);
+        assert_eq!(frame(mouse::Cursor::Unavailable), Some(None));
+        assert_eq!(frame(mouse::Cursor::Unavailable), None);
+    }
+
+    #[test]
+    fn only_uncaptured_drops_reach_the_fallback() {
+        use crate::core::event::Status;
+        use crate::core::{window, Event};
+
+        let dropped = |path: &str| Event::Window(window::Event::FileDropped(PathBuf::from(path)));
+        let mut window_subs = WindowSubs::<Message>::default();
+        window_subs.on_files_dropped_message(|paths| {
+            Some(Message::ShowMeters(
+                paths == [PathBuf::from("/samples/snare.wav")],
+            ))
+        });
+
+        // A widget captured the first file, so only the second one is passed on
+        let mut dropped_files = Vec::new();
+        for (event, status) in [
+            (dropped("/samples/kick.wav"), Status::Captured),
+            (dropped("/samples/snare.wav"), Status::Ignored),
+            (
+                Event::Window(window::Event::FilesHoveredLeft),
+                Status::Ignored,
+            ),
+        ] {
+            uncaptured_drop(&event, status, &mut dropped_files);
+        }
+        let (runtime_tx, _runtime_rx) = mpsc::unbounded();
+        let mut runtime = Runtime::new(null::Executor::new().unwrap(), Proxy::new(runtime_tx));
+        let mut tasks = TaskLimiter::new(None);
+        let mut messages = Vec::new();
+        let task = window_subs.on_files_dropped.as_ref().unwrap()(dropped_files).unwrap();
+        run_callback_task(task, &mut runtime, &mut tasks, &mut messages);
+        assert!(matches!(messages[..], [Message::ShowMeters(true)]));
+
+        // When every file is captured, there's nothing to fall back to
+        let mut dropped_files = Vec::new();
+        uncaptured_drop(
+            &dropped("/samples/kick.wav"),
+            Status::Captured,
+            &mut dropped_files,
+        );
+        assert!(dropped_files.is_empty());
+    }
+
+    /// This loads a font into iced's `font_system()`, which is shared by every test in this binary
+    /// and can't be reset. No other test may use the Noto Sans family, and all other tests measure
+    /// their text with the `()` renderer. Otherwise their layouts would depend on whether this
+    /// test ran before them.
+    #[test]
+    fn loading_a_font_changes_the_measured_layout() {
+        use crate::core::text::{self, Difference, Paragraph as _};
+        use crate::core::{alignment, Font};
+        use crate::graphics::text::{font_system, Paragraph};
+
+        let label = || text::Text {
+            content: "Resonance",
+            bounds: Size::INFINITE,
+            size: 16.0.into(),
+            line_height: text::LineHeight::default(),
+            font: Font {
+                weight: crate::core::font::Weight::Thin,
+                ..Font::with_name("Noto Sans")
+            },
+            align_x: text::Alignment::Default,
+            align_y: alignment::Vertical::Top,
+            shaping: text::Shaping::Advanced,
+            wrapping: text::Wrapping::None,
+        };
+
+        // Before the font is loaded, the label is measured with a fallback font
+        let fallback = Paragraph::with_text(label());
+        font_system()
+            .write()
+            .unwrap()
+            .load_font(nih_plug_assets::fonts::NOTO_SANS_THIN.into());
+
+        // Laying out the widgets again shapes the label with the new font
+        assert_eq!(
+            fallback.compare(label().with_content(())),
+            Difference::Shape
+        );
+        let loaded = Paragraph::with_text(label());
+        assert_ne!(fallback.min_bounds().width, loaded.min_bounds().width);
+    }
+
+    /// Vetoes frame messages, and records the messages that reach `update()`.
+    struct Filtered(Vec<String>);
+
+    impl Application for Filtered {
+        type Message = Message;
+        type Theme = Theme;
+        type Executor = null::Executor;
+        type Flags = ();
+
+        fn new(_flags: Self::Flags) -> (Self, Task<Self::Message>) {
+            (Self(Vec::new()), Task::none())
+        }
+
+        fn pre_update(&mut self, message: &Self::Message) -> bool {
+            !matches!(message, Message::Frame)
+        }
+
+        fn update(&mut self, message: Self::Message) -> Task<Self::Message> {
+            self.0.push(format!("{message:?}"));
+
+            Task::none()
+        }
+
+        fn view(&self) -> Element<'_, Self::Message, Self::Theme, Renderer> {
+            crate::widget::Column::new().into()
+        }
+
+        fn theme(&self) -> Self::Theme {
+            Theme::Dark
+        }
+    }
+
+    #[test]
+    fn vetoed_messages_dont_reach_update() {
+        let (runtime_tx, _runtime_rx) = mpsc::unbounded();
+        let mut runtime = Runtime::new(null::Executor::new().unwrap(), Proxy::new(runtime_tx));
+        let mut tasks = TaskLimiter::new(None);
+        let (mut application, _) = Filtered::new(());
+        let mut window_subs = WindowSubs::default();
+        let mut messages = vec![
+            Message::ShowMeters(true),
+            Message::Frame,
+            Message::ShowMeters(false),
+        ];
+
+        update(
+            &mut application,
+            &mut runtime,
+            &mut tasks,
+            &mut messages,
+            &mut window_subs,
+        );
+
+        assert_eq!(application.0, ["ShowMeters(true)", "ShowMeters(false)"]);
+        assert!(messages.is_empty());
+    }
+
+    /// Saves its state when the window closes, and records the messages that reach `update()`. It
+    /// wants to exit after receiving `Quit`.
+    struct SavesOnClose(Vec<String>);
+
+    impl Application for SavesOnClose {
+        type Message = Message;
+        type Theme = Theme;
+        type Executor = null::Executor;
+        type Flags = ();
+
+        fn new(_flags: Self::Flags) -> (Self, Task<Self::Message>) {
+            (Self(Vec::new()), Task::none())
+        }
+
+        fn update(&mut self, message: Self::Message) -> Task<Self::Message> {
+            self.0.push(format!("{message:?}"));
+
+            Task::none()
+        }
+
+        fn view(&self) -> Element<'_, Self::Message, Self::Theme, Renderer> {
+            crate::widget::Column::new().into()
+        }
+
+        fn theme(&self) -> Self::Theme {
+            Theme::Dark
+        }
+
+        fn subscription(
+            &self,
+            window_subs: &mut WindowSubs<Self::Message>,
+        ) -> Subscription<Self::Message> {
+            window_subs.on_window_will_close_message(|| Some(Message::SaveState));
+
+            Subscription::none()
+        }
+
+        fn should_exit(&self) -> bool {
+            self.0.iter().any(|message| message == "Quit")
+        }
+    }
+
+    /// How a window gets closed in [`close_window_with()`].
+    enum Close {
+        /// The host sends `WillClose`.
+        Host,
+        /// The application runs `Action::Exit` or `window::close()`, after which the host still
+        /// sends `WillClose` once the window is gone.
+        Application,
+        /// The application returns `true` from `should_exit()` after an update. The host doesn't
+        /// send `WillClose` until the window is gone, so the close has to happen without it.
+        ShouldExit,
+    }
+
+    /// Closes a window through [`should_close()`] and [`shut_down()`] like `run_instance()`
+    /// does, and returns the messages the application received. `Action::Exit` and
+    /// `should_exit()` request the close through the [`WindowQueue`] like `run_action()` and the
+    /// update in `run_instance()` do.
+    fn close_window_with(close: Close) -> Vec<String> {
+        let (runtime_tx, _runtime_rx) = mpsc::unbounded();
+        let mut runtime = Runtime::new(null::Executor::new().unwrap(), Proxy::new(runtime_tx));
+        let mut tasks = TaskLimiter::new(None);
+        let (mut application, _) = SavesOnClose::new(());
+        let (mut window_queue, _window_queue_rx) = WindowQueue::new();
+        let mut window_subs = WindowSubs::default();
+        let mut messages = vec![Message::Frame];
+
+        // Hosts may also report the close more than once
+        let mut events: Vec<RuntimeEvent<Message>> = match close {
+            Close::Host => vec![],
+            Close::Application => vec![RuntimeEvent::UserEvent(Action::Exit)],
+            Close::ShouldExit => {
+                messages.push(Message::Quit);
+                vec![RuntimeEvent::MainEventsCleared]
+            }
+        };
+        if !matches!(close, Close::ShouldExit) {
+            events.extend([RuntimeEvent::WillClose, RuntimeEvent::WillClose]);
+        }
+
+        update(
+            &mut application,
+            &mut runtime,
+            &mut tasks,
+            &mut messages,
+            &mut window_subs,
+        );
+        if application.should_exit() {
+            let _ = window_queue.close_window();
+        }
+        for event in events {
+            let host_closing = matches!(event, RuntimeEvent::WillClose);
+            if let RuntimeEvent::UserEvent(Action::Exit) = event {
+                let _ = window_queue.close_window();
+            }
+
+            if should_close(host_closing, &window_queue) {
+                shut_down(
+                    &mut application,
+                    &mut runtime,
+                    &mut tasks,
+                    &mut messages,
+                    &mut window_subs,
+                    &Profiler::default(),
+                );
+
+                break;
+            }
+        }
+
+        application.0
+    }
+
+    #[test]
+    fn closing_saves_state_once() {
+        assert_eq!(close_window_with(Close::Host), ["Frame", "SaveState"]);
+        assert_eq!(
+            close_window_with(Close::Application),
+            ["Frame", "SaveState"]
+        );
+        assert_eq!(
+            close_window_with(Close::ShouldExit),
+            ["Frame", "Quit", "SaveState"]
+        );
+    }
+}
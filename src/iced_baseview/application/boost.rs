@@ -0,0 +1,93 @@
+use std::time::{Duration, Instant};
+
+use crate::core::{event, mouse, touch, Event};
+
+/// Detects active drags so the runtime can redraw right after every input event instead of waiting
+/// for the next frame. See
+/// [`IcedBaseviewSettings::boost_during_interaction`][crate::settings::IcedBaseviewSettings::boost_during_interaction].
+///
+/// A drag is active while a mouse button or finger is held down after a widget captured the press.
+/// The boost stays active for a short while after the drag ends so the final frames of the
+/// interaction are still drawn immediately.
+#[derive(Debug)]
+pub(crate) struct InteractionBoost {
+    enabled: bool,
+    linger: Duration,
+    /// Whether a widget captured a press that hasn't been released yet.
+    dragging: bool,
+    /// When the last drag ended.
+    released_at: Option<Instant>,
+}
+
+impl InteractionBoost {
+    pub fn new(enabled: bool, linger: Duration) -> Self {
+        Self {
+            enabled,
+            linger,
+            dragging: false,
+            released_at: None,
+        }
+    }
+
+    /// Update the drag state for an event the user interface has handled.
+    pub fn observe(&mut self, event: &Event, status: event::Status, now: Instant) {
+        if !self.enabled {
+            return;
+        }
+
+        match event {
+            Event::Mouse(mouse::Event::ButtonPressed(_))
+            | Event::Touch(touch::Event::FingerPressed { .. }) => {
+                self.dragging |= status == event::Status::Captured;
+            }
+            Event::Mouse(mouse::Event::ButtonReleased(_) | mouse::Event::CursorLeft)
+            | Event::Touch(touch::Event::FingerLifted { .. } | touch::Event::FingerLost { .. })
+                if self.dragging =>
+            {
+                self.dragging = false;
+                self.released_at = Some(now);
+            }
+            _ => {}
+        }
+    }
+
+    /// Whether input events should currently be drawn immediately.
+    pub fn is_active(&self, now: Instant) -> bool {
+        self.dragging
+            || self
+                .released_at
+                .is_some_and(|released_at| now.saturating_duration_since(released_at) < self.linger)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn boost_follows_captured_drags() {
+        let start = Instant::now();
+        let press = Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left));
+        let release = Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left));
+
+        let mut boost = InteractionBoost::new(true, Duration::from_millis(300));
+        assert!(!boost.is_active(start));
+
+        // Presses no widget cares about don't count as drags
+        boost.observe(&press, event::Status::Ignored, start);
+        assert!(!boost.is_active(start));
+
+        boost.observe(&press, event::Status::Captured, start);
+        assert!(boost.is_active(start + Duration::from_secs(10)));
+
+        let released = start + Duration::from_secs(1);
+        boost.observe(&release, event::Status::Ignored, released);
+        assert!(boost.is_active(released + Duration::from_millis(299)));
+        assert!(!boost.is_active(released + Duration::from_millis(300)));
+
+        // The boost is opt-in
+        let mut boost = InteractionBoost::new(false, Duration::from_millis(300));
+        boost.observe(&press, event::Status::Captured, start);
+        assert!(!boost.is_active(start));
+    }
+}
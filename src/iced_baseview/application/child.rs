@@ -0,0 +1,147 @@
+use baseview::WindowScalePolicy;
+
+use crate::child_window::ChildWindowLifecycle;
+use crate::core::{mouse, renderer, Color, Point, Size};
+use crate::graphics::compositor::{self, Compositor};
+use crate::graphics::Viewport;
+use crate::runtime::user_interface::UserInterface;
+use crate::Renderer;
+
+/// The runtime's state for the optional child window. The child window's user interface lives in
+/// `run_instance()` next to the main window's since both borrow the application.
+pub(crate) struct ChildState<S> {
+    pub lifecycle: ChildWindowLifecycle,
+    pub window_id: crate::window::Id,
+    /// Created when the child window has been opened.
+    pub surface: Option<S>,
+    /// The viewport version the surface was last configured for.
+    pub surface_version: Option<usize>,
+    pub events: Vec<crate::core::Event>,
+    pub modifiers: crate::core::keyboard::Modifiers,
+
+    viewport: Viewport,
+    viewport_version: usize,
+    cursor_position: Option<Point>,
+}
+
+impl<S> ChildState<S> {
+    pub fn new() -> Self {
+        Self {
+            lifecycle: ChildWindowLifecycle::new(),
+            window_id: crate::window::Id::unique(),
+            surface: None,
+            surface_version: None,
+            events: Vec::new(),
+            modifiers: Default::default(),
+
+            viewport: Viewport::with_physical_size(Size::new(0, 0), 1.0),
+            viewport_version: 0,
+            cursor_position: None,
+        }
+    }
+
+    /// Resets the state for a new child window with the given logical size.
+    pub fn reset(&mut self, size: Size, scale_factor: f32) {
+        self.surface = None;
+        self.surface_version = None;
+        self.events.clear();
+        self.modifiers = Default::default();
+        self.cursor_position = None;
+        self.set_viewport(Viewport::with_physical_size(
+            Size::new(
+                (size.width * scale_factor) as u32,
+                (size.height * scale_factor) as u32,
+            ),
+            scale_factor,
+        ));
+    }
+
+    pub fn viewport(&self) -> &Viewport {
+        &self.viewport
+    }
+
+    pub fn viewport_version(&self) -> usize {
+        self.viewport_version
+    }
+
+    pub fn cursor(&self) -> mouse::Cursor {
+        self.cursor_position
+            .map(mouse::Cursor::Available)
+            .unwrap_or(mouse::Cursor::Unavailable)
+    }
+
+    /// Processes a child window event, see [`State::update()`][super::State::update()].
+    pub fn update(&mut self, event: &baseview::Event, scale_policy: WindowScalePolicy) {
+        match event {
+            baseview::Event::Window(baseview::WindowEvent::Resized(window_info)) => {
+                let scale = match scale_policy {
+                    WindowScalePolicy::ScaleFactor(scale) => scale,
+                    WindowScalePolicy::SystemScaleFactor => window_info.scale(),
+                };
+
+                self.set_viewport(Viewport::with_physical_size(
+                    Size::new(
+                        window_info.physical_size().width,
+                        window_info.physical_size().height,
+                    ),
+                    scale as f32,
+                ));
+            }
+            baseview::Event::Mouse(baseview::MouseEvent::CursorMoved { position, .. }) => {
+                self.cursor_position = Some(Point {
+                    x: position.x as f32,
+                    y: position.y as f32,
+                });
+            }
+            baseview::Event::Mouse(baseview::MouseEvent::CursorLeft) => {
+                self.cursor_position = None;
+            }
+            _ => {}
+        }
+    }
+
+    /// Draws the child window's user interface and presents it to the child window's surface.
+    pub fn redraw<C, Message, Theme>(
+        &mut self,
+        compositor: &mut C,
+        renderer: &mut Renderer,
+        interface: &mut UserInterface<'_, Message, Theme, Renderer>,
+        theme: &Theme,
+        style: &renderer::Style,
+        background_color: Color,
+    ) where
+        C: Compositor<Renderer = Renderer, Surface = S>,
+    {
+        let cursor = self.cursor();
+        let Some(surface) = &mut self.surface else {
+            return;
+        };
+
+        let physical_size = self.viewport.physical_size();
+        if physical_size.width == 0 || physical_size.height == 0 {
+            return;
+        }
+
+        if self.surface_version != Some(self.viewport_version) {
+            compositor.configure_surface(surface, physical_size.width, physical_size.height);
+            self.surface_version = Some(self.viewport_version);
+        }
+
+        interface.draw(renderer, theme, style, cursor);
+        match compositor.present(renderer, surface, &self.viewport, background_color, || {}) {
+            Ok(()) => {}
+            // This is an unrecoverable error.
+            Err(compositor::SurfaceError::OutOfMemory) => {
+                panic!("{:?}", compositor::SurfaceError::OutOfMemory);
+            }
+            // The child window is redrawn together with the main window, so this will be retried
+            // on the next frame
+            Err(_) => {}
+        }
+    }
+
+    fn set_viewport(&mut self, viewport: Viewport) {
+        self.viewport = viewport;
+        self.viewport_version = self.viewport_version.wrapping_add(1);
+    }
+}
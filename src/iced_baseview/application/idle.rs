@@ -0,0 +1,132 @@
+use std::time::Instant;
+
+use crate::settings::IdleTrim;
+
+/// Decides when the renderer's caches should be released for a window nobody is looking at, see
+/// [`IcedBaseviewSettings::idle_trim`][crate::settings::IcedBaseviewSettings::idle_trim].
+///
+/// Baseview doesn't tell the editor when the host hides its window, so the window counts as hidden
+/// once the [`Application`][crate::Application] has paused rendering for a while, or when nothing
+/// was drawn for a longer while. Trimming only happens at the start of a frame, so a frame that was
+/// drawn with the old renderer has always been presented before the renderer is replaced.
+#[derive(Debug)]
+pub(crate) struct IdleTrimmer {
+    settings: Option<IdleTrim>,
+    /// When the user interface was last drawn, or when the trimmer was last woken up.
+    last_draw: Instant,
+    /// When the application started pausing rendering, if it currently is.
+    paused_since: Option<Instant>,
+    trimmed: bool,
+}
+
+impl IdleTrimmer {
+    pub fn new(settings: Option<IdleTrim>, now: Instant) -> Self {
+        Self {
+            settings,
+            last_draw: now,
+            paused_since: None,
+            trimmed: false,
+        }
+    }
+
+    /// Record that the user interface was drawn.
+    pub fn drew(&mut self, now: Instant) {
+        self.last_draw = now;
+    }
+
+    /// Record whether the application pauses rendering.
+    pub fn set_paused(&mut self, paused: bool, now: Instant) {
+        self.paused_since = if paused {
+            Some(self.paused_since.unwrap_or(now))
+        } else {
+            None
+        };
+    }
+
+    /// Whether the caches should be released now. This returns true once when one of the
+    /// thresholds is reached, and the runtime then stops drawing and calling
+    /// [`WindowSubs::on_frame`][crate::WindowSubs::on_frame] until [`wake()`][Self::wake()] is
+    /// called.
+    pub fn should_trim(&mut self, now: Instant) -> bool {
+        let Some(settings) = self.settings else {
+            return false;
+        };
+        if self.trimmed {
+            return false;
+        }
+
+        let paused_for = self
+            .paused_since
+            .map(|since| now.saturating_duration_since(since));
+        let hidden = paused_for.is_some_and(|paused_for| paused_for >= settings.paused_for)
+            || now.saturating_duration_since(self.last_draw) >= settings.undrawn_for;
+        self.trimmed = hidden;
+
+        hidden
+    }
+
+    /// Whether the caches have been released and the window hasn't been woken up since.
+    pub fn is_trimmed(&self) -> bool {
+        self.trimmed
+    }
+
+    /// Leave the trimmed state because the window received an event. Returns true if it was
+    /// trimmed, in which case the whole window needs to be updated and redrawn.
+    pub fn wake(&mut self, now: Instant) -> bool {
+        if !self.trimmed {
+            return false;
+        }
+
+        self.trimmed = false;
+        self.last_draw = now;
+        self.paused_since = self.paused_since.map(|_| now);
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn trims_once_until_woken() {
+        let start = Instant::now();
+        let at = |ms: u64| start + Duration::from_millis(ms);
+        let settings = IdleTrim {
+            paused_for: Duration::from_millis(100),
+            undrawn_for: Duration::from_millis(1_000),
+        };
+
+        let mut disabled = IdleTrimmer::new(None, start);
+        assert!(!disabled.should_trim(at(60_000)));
+
+        // Drawing keeps the window from being trimmed
+        let mut idle = IdleTrimmer::new(Some(settings), start);
+        idle.drew(at(500));
+        assert!(!idle.should_trim(at(1_400)));
+        assert!(idle.should_trim(at(1_500)));
+        assert!(idle.is_trimmed());
+        // The caches are only released once
+        assert!(!idle.should_trim(at(1_600)));
+
+        assert!(idle.wake(at(2_000)));
+        assert!(!idle.wake(at(2_000)));
+        assert!(!idle.should_trim(at(2_900)));
+
+        // Pausing counts as hidden much sooner, and waking up restarts the wait
+        idle.drew(at(3_000));
+        idle.set_paused(true, at(3_000));
+        idle.set_paused(true, at(3_050));
+        assert!(!idle.should_trim(at(3_099)));
+        assert!(idle.should_trim(at(3_100)));
+        assert!(idle.wake(at(3_200)));
+        assert!(!idle.should_trim(at(3_250)));
+        assert!(idle.should_trim(at(3_300)));
+
+        assert!(idle.wake(at(4_000)));
+        idle.set_paused(false, at(4_000));
+        assert!(!idle.should_trim(at(4_500)));
+    }
+}
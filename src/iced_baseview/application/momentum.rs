@@ -0,0 +1,133 @@
+use std::time::{Duration, Instant};
+
+/// Pixel scroll events further apart than this are never part of the same momentum tail.
+const MAX_EVENT_GAP: Duration = Duration::from_millis(50);
+
+/// The number of consecutive non-increasing scroll deltas after which the following deltas are
+/// considered to be momentum scrolling.
+const MOMENTUM_RUN_LENGTH: u32 = 3;
+
+/// Dampens inertial scrolling from trackpads, see
+/// [`IcedBaseviewSettings::momentum_scroll_damping`][crate::settings::IcedBaseviewSettings::momentum_scroll_damping].
+///
+/// baseview does not report the scroll phase, so momentum scrolling is detected heuristically. After
+/// the user lifts their fingers the platform keeps sending pixel deltas in the same direction that
+/// steadily shrink, while the deltas from active scrolling fluctuate. Once a run of shrinking
+/// deltas is long enough, every following delta in the run is scaled down by an additional factor
+/// of `1 - damping`. Line based deltas from regular mouse wheels are never touched.
+#[derive(Debug)]
+pub(crate) struct MomentumDamping {
+    damping: f32,
+    /// The last undamped pixel delta and when it was received.
+    previous: Option<((f32, f32), Instant)>,
+    /// The number of consecutive non-increasing deltas in the same direction.
+    run: u32,
+}
+
+impl MomentumDamping {
+    pub fn new(damping: f32) -> Self {
+        Self {
+            damping: damping.clamp(0.0, 1.0),
+            previous: None,
+            run: 0,
+        }
+    }
+
+    /// Dampen the event's delta if it's part of a momentum scroll.
+    pub fn apply(&mut self, event: &mut baseview::Event, now: Instant) {
+        if self.damping <= 0.0 {
+            return;
+        }
+
+        if let baseview::Event::Mouse(baseview::MouseEvent::WheelScrolled {
+            delta: baseview::ScrollDelta::Pixels { x, y },
+            ..
+        }) = event
+        {
+            (*x, *y) = self.damp((*x, *y), now);
+        }
+    }
+
+    fn damp(&mut self, delta: (f32, f32), now: Instant) -> (f32, f32) {
+        let continues_run = self.previous.is_some_and(|(previous, time)| {
+            now.saturating_duration_since(time) <= MAX_EVENT_GAP
+                && same_direction(previous.0, delta.0)
+                && same_direction(previous.1, delta.1)
+                && magnitude(delta) <= magnitude(previous)
+        });
+
+        self.run = if continues_run { self.run + 1 } else { 0 };
+        self.previous = Some((delta, now));
+
+        if self.run < MOMENTUM_RUN_LENGTH {
+            return delta;
+        }
+
+        let scale = (1.0 - self.damping).powi((self.run - MOMENTUM_RUN_LENGTH + 1) as i32);
+        (delta.0 * scale, delta.1 * scale)
+    }
+}
+
+fn same_direction(a: f32, b: f32) -> bool {
+    a == 0.0 && b == 0.0 || a * b > 0.0
+}
+
+fn magnitude((x, y): (f32, f32)) -> f32 {
+    x.hypot(y)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn damps_momentum_tail() {
+        let start = Instant::now();
+        let mut damping = MomentumDamping::new(0.5);
+        let mut damp_sequence = |deltas: &[f32], offset_ms: u64| -> Vec<f32> {
+            deltas
+                .iter()
+                .enumerate()
+                .map(|(i, &y)| {
+                    let time = start + Duration::from_millis(offset_ms + i as u64 * 16);
+                    damping.damp((0.0, y), time).1
+                })
+                .collect()
+        };
+
+        // Active scrolling fluctuates and is left alone, while the tail is damped more and more
+        assert_eq!(
+            damp_sequence(&[10.0, 12.0, 9.0, 11.0, 8.0, 6.0, 5.0, 4.0, 3.0], 0),
+            [10.0, 12.0, 9.0, 11.0, 8.0, 6.0, 2.5, 1.0, 0.375]
+        );
+
+        // A pause or a change of direction starts a new gesture
+        assert_eq!(
+            damp_sequence(&[3.0, 2.0, 2.0, 1.0], 1000),
+            [3.0, 2.0, 2.0, 0.5]
+        );
+        assert_eq!(damp_sequence(&[-3.0], 1064), [-3.0]);
+    }
+
+    #[test]
+    fn disabled_by_default() {
+        let mut damping = MomentumDamping::new(0.0);
+        let mut event = baseview::Event::Mouse(baseview::MouseEvent::WheelScrolled {
+            delta: baseview::ScrollDelta::Pixels { x: 0.0, y: 1.0 },
+            modifiers: Default::default(),
+        });
+
+        let now = Instant::now();
+        for _ in 0..10 {
+            damping.apply(&mut event, now);
+        }
+
+        assert!(matches!(
+            event,
+            baseview::Event::Mouse(baseview::MouseEvent::WheelScrolled {
+                delta: baseview::ScrollDelta::Pixels { y, .. },
+                ..
+            }) if y == 1.0
+        ));
+    }
+}
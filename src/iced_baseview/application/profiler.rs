@@ -1,92 +1,210 @@
 //! A simple profiler for Iced.
-use std::ffi::OsStr;
-use std::path::Path;
-use std::time::Duration;
-use tracing_subscriber::prelude::*;
-use tracing_subscriber::Registry;
-#[cfg(feature = "chrome-trace")]
-use {
-    tracing_chrome::FlushGuard,
-    tracing_subscriber::fmt::{format::DefaultFields, FormattedFields},
-};
-
-/// Profiler state. This will likely need to be updated or reworked when adding new tracing backends.
+
+#[cfg(feature = "trace")]
+use std::path::{Path, PathBuf};
+#[cfg(feature = "trace")]
+use std::time::{Duration, Instant};
+
+use crate::settings::IcedBaseviewSettings;
+
+/// How often the trace is flushed to its file while the window is open.
+#[cfg(feature = "trace")]
+const FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Records how long each part of a window's frames takes.
+///
+/// With the `trace` feature, every window writes a trace in the [Chrome trace event
+/// format](https://docs.google.com/document/d/1CvAClvFfyA5R-PhYUmn5OOQtYMH4h6I0nSsKchNAySU) while
+/// it's open. Every frame gets an `update`, `view`, `layout`, `draw`, and `present` span, which
+/// record the index of the frame they belong to. Editors run inside of a plugin host where there's
+/// no console to print to, so the trace is written to a file. That's the first of:
+///
+/// - The path in the `CHROME_TRACE_FILE` environment variable. This needs to be set in the
+///   environment the host is started from.
+/// - The `trace_file` path in the window's [`IcedBaseviewSettings`].
+/// - A new file in the `nih_plug_iced_traces` directory in the system's temporary directory, named
+///   after the host's executable, its process ID, and the time the window was opened.
+///
+/// A fixed path is overwritten every time a window opens. The trace is written on a background
+/// thread, and it's flushed once per second and when the window closes. If the host crashes before
+/// that, the file is missing its closing bracket, which the trace viewers below accept.
+///
+/// To look at a trace, open <https://ui.perfetto.dev> and drop the file onto it. Each window's
+/// thread shows the spans of its frames, and selecting a span shows its frame index in its
+/// arguments. `chrome://tracing` in Chromium based browsers can open the files as well.
+///
+/// With the `tracy` feature, the spans are also sent to a running
+/// [Tracy](https://github.com/wolfpld/tracy) profiler.
+///
+/// Without the `trace` feature, the profiler and its spans are empty and never touch the file
+/// system.
+#[derive(Default)]
 #[allow(missing_debug_implementations)]
 pub struct Profiler {
-    #[cfg(feature = "chrome-trace")]
-    /// [`FlushGuard`] must not be dropped until the application scope is dropped for accurate tracing.
-    _guard: FlushGuard,
+    #[cfg(feature = "trace")]
+    session: Option<Session>,
+}
+
+#[cfg(feature = "trace")]
+struct Session {
+    /// The subscriber that writes to this window's trace file. This is only used for the window's
+    /// own spans, so multiple windows in the same process each get their own file.
+    dispatch: tracing::Dispatch,
+    /// Closes the file when it's dropped.
+    guard: tracing_chrome::FlushGuard,
+    /// The index of the frame that's currently being prepared.
+    frame: u64,
+    last_flush: Instant,
+}
+
+/// The parts of a frame that get their own span.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    /// Handling the messages for the frame.
+    Update,
+    /// Building the view.
+    View,
+    /// Laying out the view.
+    Layout,
+    /// Drawing the widgets.
+    Draw,
+    /// Presenting the frame to the window's surface.
+    Present,
+}
+
+/// A span that ends when it's dropped. This is empty without the `trace` feature.
+#[must_use]
+#[allow(missing_debug_implementations)]
+pub struct Span {
+    #[cfg(feature = "trace")]
+    _span: Option<tracing::span::EnteredSpan>,
 }
 
 impl Profiler {
-    /// Initializes the [`Profiler`].
-    pub fn init() -> Self {
-        // Registry stores the spans & generates unique span IDs
-        let subscriber = Registry::default();
-
-        let default_path = Path::new(env!("CARGO_MANIFEST_DIR"));
-        let curr_exe = std::env::current_exe().unwrap_or_else(|_| default_path.to_path_buf());
-        let out_dir = curr_exe.parent().unwrap_or(default_path).join("traces");
-
-        #[cfg(feature = "chrome-trace")]
-        let (chrome_layer, guard) = {
-            let mut layer = tracing_chrome::ChromeLayerBuilder::new();
-
-            // Optional configurable env var: CHROME_TRACE_FILE=/path/to/trace_file/file.json,
-            // for uploading to chrome://tracing (old) or ui.perfetto.dev (new).
-            if let Ok(path) = std::env::var("CHROME_TRACE_FILE") {
-                layer = layer.file(path);
-            } else if std::fs::create_dir_all(&out_dir).is_ok() {
-                let time = std::time::SystemTime::now()
-                    .duration_since(std::time::UNIX_EPOCH)
-                    .unwrap_or(Duration::from_millis(0))
-                    .as_millis();
-
-                let curr_exe_name = curr_exe
-                    .file_name()
-                    .unwrap_or_else(|| OsStr::new("trace"))
-                    .to_str()
-                    .unwrap_or("trace");
-
-                let path = out_dir.join(format!("{curr_exe_name}_trace_{time}.json"));
-
-                layer = layer.file(path);
-            } else {
-                layer = layer.file(env!("CARGO_MANIFEST_DIR"))
-            }
+    /// Starts writing a trace for a newly opened window. Failing to create the trace file is
+    /// logged, and the window then runs without a trace.
+    #[cfg_attr(not(feature = "trace"), allow(unused_variables))]
+    pub fn start(settings: &IcedBaseviewSettings) -> Self {
+        #[cfg(feature = "trace")]
+        {
+            let path = std::env::var_os("CHROME_TRACE_FILE")
+                .map(PathBuf::from)
+                .or_else(|| settings.trace_file.map(Path::to_path_buf))
+                .unwrap_or_else(default_trace_file);
 
-            let (chrome_layer, guard) = layer
-                .name_fn(Box::new(|event_or_span| match event_or_span {
-                    tracing_chrome::EventOrSpan::Event(event) => event.metadata().name().into(),
-                    tracing_chrome::EventOrSpan::Span(span) => {
-                        if let Some(fields) =
-                            span.extensions().get::<FormattedFields<DefaultFields>>()
-                        {
-                            format!("{}: {}", span.metadata().name(), fields.fields.as_str())
-                        } else {
-                            span.metadata().name().into()
-                        }
+            match Session::start(&path) {
+                Ok(session) => {
+                    log::info!("Writing a trace to {}", path.display());
+                    Self {
+                        session: Some(session),
                     }
-                }))
-                .build();
+                }
+                Err(error) => {
+                    log::warn!(
+                        "Could not create the trace file {}: {error}",
+                        path.display()
+                    );
+                    Self::default()
+                }
+            }
+        }
 
-            (chrome_layer, guard)
-        };
+        #[cfg(not(feature = "trace"))]
+        Self::default()
+    }
 
-        let fmt_layer = tracing_subscriber::fmt::Layer::default();
-        let subscriber = subscriber.with(fmt_layer);
+    /// Enters the span for `stage` of the current frame.
+    #[inline]
+    #[cfg_attr(not(feature = "trace"), allow(unused_variables))]
+    pub fn span(&self, stage: Stage) -> Span {
+        #[cfg(feature = "trace")]
+        {
+            let span = self.session.as_ref().map(|session| {
+                let frame = session.frame;
+                tracing::dispatcher::with_default(&session.dispatch, || match stage {
+                    Stage::Update => tracing::info_span!("update", frame),
+                    Stage::View => tracing::info_span!("view", frame),
+                    Stage::Layout => tracing::info_span!("layout", frame),
+                    Stage::Draw => tracing::info_span!("draw", frame),
+                    Stage::Present => tracing::info_span!("present", frame),
+                })
+                .entered()
+            });
 
-        #[cfg(feature = "chrome-trace")]
-        let subscriber = subscriber.with(chrome_layer);
+            Span { _span: span }
+        }
 
-        // create dispatcher which will forward span events to the subscriber
-        // this can only be set once or will panic
-        tracing::subscriber::set_global_default(subscriber)
-            .expect("Tracer could not set the global default subscriber.");
+        #[cfg(not(feature = "trace"))]
+        Span {}
+    }
+
+    /// Moves on to the next frame after the current one has been presented.
+    #[inline]
+    pub fn frame_presented(&mut self) {
+        #[cfg(feature = "trace")]
+        if let Some(session) = &mut self.session {
+            session.frame += 1;
 
-        Profiler {
-            #[cfg(feature = "chrome-trace")]
-            _guard: guard,
+            let now = Instant::now();
+            if now.duration_since(session.last_flush) >= FLUSH_INTERVAL {
+                session.guard.flush();
+                session.last_flush = now;
+            }
         }
     }
+
+    /// Writes the rest of the trace and closes the file when the window closes. Dropping the
+    /// profiler does the same, for when the window is destroyed without closing normally.
+    pub fn finish(self) {
+        #[cfg(feature = "trace")]
+        drop(self.session);
+    }
+}
+
+#[cfg(feature = "trace")]
+impl Session {
+    fn start(path: &Path) -> std::io::Result<Self> {
+        use tracing_subscriber::prelude::*;
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        // The chrome layer would panic if it couldn't create the file itself
+        let file = std::fs::File::create(path)?;
+        let (chrome_layer, guard) = tracing_chrome::ChromeLayerBuilder::new()
+            .writer(file)
+            .include_args(true)
+            .build();
+
+        let subscriber = tracing_subscriber::Registry::default().with(chrome_layer);
+        #[cfg(feature = "tracy")]
+        let subscriber = subscriber.with(tracing_tracy::TracyLayer::default());
+
+        Ok(Self {
+            dispatch: tracing::Dispatch::new(subscriber),
+            guard,
+            frame: 0,
+            last_flush: Instant::now(),
+        })
+    }
+}
+
+/// A new file in the temporary directory, for when no path was configured.
+#[cfg(feature = "trace")]
+fn default_trace_file() -> PathBuf {
+    let exe_name = std::env::current_exe()
+        .ok()
+        .and_then(|exe| {
+            exe.file_stem()
+                .map(|name| name.to_string_lossy().into_owned())
+        })
+        .unwrap_or_else(|| String::from("host"));
+    let time = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis();
+
+    std::env::temp_dir()
+        .join("nih_plug_iced_traces")
+        .join(format!("{exe_name}_{}_{time}.json", std::process::id()))
 }
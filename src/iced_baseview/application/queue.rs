@@ -0,0 +1,205 @@
+use std::cell::Cell;
+
+use crate::core::{mouse, Event};
+use crate::settings::{EventQueueLimit, EventQueueOverflow};
+
+thread_local! {
+    static DEPTH: Cell<EventQueueDepth> = const { Cell::new(EventQueueDepth::EMPTY) };
+}
+
+/// How much work was waiting for the runtime when the current frame started, see
+/// [`queue_depth()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct EventQueueDepth {
+    /// The number of iced events waiting for the widgets, after applying the
+    /// [`EventQueueLimit`].
+    pub events: usize,
+    /// The number of messages waiting for the application's `update()`.
+    pub messages: usize,
+    /// The number of window events the runtime handled in a row without having to wait for the
+    /// next one. This grows when the host delivers events faster than the editor handles them.
+    pub pending: usize,
+    /// The number of events removed by the [`EventQueueLimit`] since the window was opened.
+    pub shed: usize,
+}
+
+impl EventQueueDepth {
+    const EMPTY: Self = Self {
+        events: 0,
+        messages: 0,
+        pending: 0,
+        shed: 0,
+    };
+}
+
+/// How much work was waiting for the runtime when the current frame started. This is meant for
+/// debugging overlays and logging in editors that may be falling behind. It's only meaningful
+/// while the runtime is calling into the application, and it describes the editor that's currently
+/// being updated or drawn.
+pub fn queue_depth() -> EventQueueDepth {
+    DEPTH.with(Cell::get)
+}
+
+/// Watches the number of events waiting for the widgets and applies the [`EventQueueLimit`], see
+/// [`IcedBaseviewSettings::event_queue_limit`][crate::settings::IcedBaseviewSettings::event_queue_limit].
+///
+/// Only cursor movement counts as non-critical. A cursor movement can be removed when it's directly
+/// followed by another one, since the widgets then only need to see the later position. Button
+/// presses, keys, scrolling, window events, and messages are never removed.
+#[derive(Debug)]
+pub(crate) struct EventBacklog {
+    limit: Option<EventQueueLimit>,
+    /// Whether the events were above the high-water mark last frame, so crossing it is only
+    /// reported once.
+    above: bool,
+    /// See [`EventQueueDepth::shed`].
+    shed: usize,
+    /// See [`EventQueueDepth::pending`].
+    pending: usize,
+}
+
+impl EventBacklog {
+    pub fn new(limit: Option<EventQueueLimit>) -> Self {
+        Self {
+            limit,
+            above: false,
+            shed: 0,
+            pending: 0,
+        }
+    }
+
+    /// Record whether the runtime had to wait for the event it's about to handle.
+    pub fn received(&mut self, without_waiting: bool) {
+        self.pending = if without_waiting { self.pending + 1 } else { 0 };
+    }
+
+    /// Apply the limit to the events collected for this frame, and publish the resulting depth
+    /// for [`queue_depth()`].
+    pub fn observe(&mut self, events: &mut Vec<Event>, messages: usize) -> EventQueueDepth {
+        if let Some(limit) = self.limit {
+            let above = events.len() > limit.high_water_mark;
+            if above && !self.above {
+                log::warn!(
+                    "{} events are waiting for the editor, which is more than the high-water mark \
+                     of {}",
+                    events.len(),
+                    limit.high_water_mark
+                );
+            }
+            self.above = above;
+
+            if above {
+                let before = events.len();
+                match limit.overflow {
+                    EventQueueOverflow::Warn => {}
+                    EventQueueOverflow::Coalesce => shed_cursor_moves(events, 0),
+                    EventQueueOverflow::DropOldest => {
+                        shed_cursor_moves(events, limit.high_water_mark)
+                    }
+                }
+                self.shed += before - events.len();
+            }
+        }
+
+        let depth = EventQueueDepth {
+            events: events.len(),
+            messages,
+            pending: self.pending,
+            shed: self.shed,
+        };
+        DEPTH.with(|cell| cell.set(depth));
+
+        depth
+    }
+}
+
+/// Remove cursor movements that are directly followed by another cursor movement, oldest first,
+/// until at most `target` events are left or no such movements remain.
+fn shed_cursor_moves(events: &mut Vec<Event>, target: usize) {
+    let is_cursor_move =
+        |event: &Event| matches!(event, Event::Mouse(mouse::Event::CursorMoved { .. }));
+
+    let mut excess = events.len().saturating_sub(target);
+    let mut index = 0;
+    while excess > 0 && index + 1 < events.len() {
+        if is_cursor_move(&events[index]) && is_cursor_move(&events[index + 1]) {
+            let _ = events.remove(index);
+            excess -= 1;
+        } else {
+            index += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::keyboard::{self, Modifiers};
+    use crate::core::Point;
+
+    fn cursor_moved(x: f32) -> Event {
+        Event::Mouse(mouse::Event::CursorMoved {
+            position: Point::new(x, 0.0),
+        })
+    }
+
+    fn events() -> Vec<Event> {
+        vec![
+            cursor_moved(1.0),
+            cursor_moved(2.0),
+            cursor_moved(3.0),
+            Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)),
+            cursor_moved(4.0),
+            cursor_moved(5.0),
+            Event::Keyboard(keyboard::Event::ModifiersChanged(Modifiers::SHIFT)),
+            cursor_moved(6.0),
+        ]
+    }
+
+    #[test]
+    fn exceeding_the_mark() {
+        let limit = |overflow| {
+            Some(EventQueueLimit {
+                high_water_mark: 6,
+                overflow,
+            })
+        };
+
+        // Below the mark nothing happens
+        let mut backlog = EventBacklog::new(limit(EventQueueOverflow::Coalesce));
+        let mut few = events()[..6].to_vec();
+        assert_eq!(backlog.observe(&mut few, 0).events, 6);
+        assert!(!backlog.above);
+
+        // Warnings leave the events alone
+        let mut backlog = EventBacklog::new(limit(EventQueueOverflow::Warn));
+        let mut warned = events();
+        let depth = backlog.observe(&mut warned, 3);
+        assert!(backlog.above);
+        assert_eq!(warned, events());
+        assert_eq!((depth.events, depth.messages, depth.shed), (8, 3, 0));
+        assert_eq!(queue_depth(), depth);
+
+        // Coalescing keeps the last position before every other event
+        let mut backlog = EventBacklog::new(limit(EventQueueOverflow::Coalesce));
+        let mut coalesced = events();
+        assert_eq!(backlog.observe(&mut coalesced, 0).shed, 3);
+        assert_eq!(
+            coalesced,
+            [
+                cursor_moved(3.0),
+                events()[3].clone(),
+                cursor_moved(5.0),
+                events()[6].clone(),
+                cursor_moved(6.0),
+            ]
+        );
+
+        // Dropping only removes the oldest movements until the events fit again
+        let mut backlog = EventBacklog::new(limit(EventQueueOverflow::DropOldest));
+        let mut dropped = events();
+        assert_eq!(backlog.observe(&mut dropped, 0).events, 6);
+        assert_eq!(dropped[..2], [cursor_moved(3.0), events()[3].clone()]);
+        assert_eq!(dropped[2..4], [cursor_moved(4.0), cursor_moved(5.0)]);
+    }
+}
@@ -0,0 +1,134 @@
+use std::time::Instant;
+
+use crate::core::Size;
+use crate::settings::ResizeResolution;
+
+/// Decides the resolution to render at while the window is being resized. See
+/// [`IcedBaseviewSettings::resize_resolution`][crate::settings::IcedBaseviewSettings::resize_resolution].
+///
+/// A resize is active from the moment the window's size changes until no further changes have
+/// happened for the settle time. This covers resizes by the host as well as resizes requested by
+/// the application, for instance through a [`ResizeHandle`][crate::widgets::ResizeHandle].
+#[derive(Debug)]
+pub(crate) struct ResizeScaler {
+    settings: Option<ResizeResolution>,
+    /// When the window's size last changed.
+    resized_at: Option<Instant>,
+    /// Whether the last presented frame was rendered at a reduced resolution.
+    presented_reduced: bool,
+}
+
+impl ResizeScaler {
+    pub fn new(settings: Option<ResizeResolution>) -> Self {
+        Self {
+            settings,
+            resized_at: None,
+            presented_reduced: false,
+        }
+    }
+
+    /// Report that the window's size changed.
+    pub fn resized(&mut self, now: Instant) {
+        self.resized_at = Some(now);
+    }
+
+    /// The factor the next frame's resolution should be scaled by. This is `1.0` unless a resize
+    /// is active.
+    pub fn scale(&self, now: Instant) -> f32 {
+        let Some(settings) = self.settings else {
+            return 1.0;
+        };
+
+        let is_resizing = self.resized_at.is_some_and(|resized_at| {
+            now.saturating_duration_since(resized_at) < settings.settle_time
+        });
+        if is_resizing {
+            sanitize_scale(settings.scale)
+        } else {
+            1.0
+        }
+    }
+
+    /// The physical size of the next frame, and the scale factor that maps the window's logical
+    /// size to that frame. Call this right before presenting a frame.
+    pub fn frame(
+        &mut self,
+        physical_size: Size<u32>,
+        scale_factor: f32,
+        now: Instant,
+    ) -> (Size<u32>, f32) {
+        let scale = self.scale(now);
+        self.presented_reduced = scale < 1.0;
+        if !self.presented_reduced {
+            return (physical_size, scale_factor);
+        }
+
+        let reduced_size = Size::new(
+            ((physical_size.width as f32 * scale).round() as u32).max(1),
+            ((physical_size.height as f32 * scale).round() as u32).max(1),
+        );
+
+        (reduced_size, scale_factor * scale)
+    }
+
+    /// Whether a frame needs to be presented at full resolution because the resize has settled
+    /// since the last frame was presented at a reduced resolution.
+    pub fn needs_full_frame(&self, now: Instant) -> bool {
+        self.presented_reduced && self.scale(now) >= 1.0
+    }
+}
+
+/// Clamp the configured scale to `(0, 1]`. Invalid values disable the reduction.
+fn sanitize_scale(scale: f32) -> f32 {
+    if scale.is_finite() && scale > 0.0 {
+        scale.min(1.0)
+    } else {
+        1.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn reduces_resolution_until_settled() {
+        let start = Instant::now();
+        let settings = ResizeResolution {
+            scale: 0.5,
+            settle_time: Duration::from_millis(100),
+        };
+        let full_size = Size::new(801, 600);
+        let mut scaler = ResizeScaler::new(Some(settings));
+
+        // Nothing changes before the window is resized
+        assert_eq!(scaler.frame(full_size, 2.0, start), (full_size, 2.0));
+        assert!(!scaler.needs_full_frame(start));
+
+        scaler.resized(start);
+        let during = start + Duration::from_millis(50);
+        assert_eq!(
+            scaler.frame(full_size, 2.0, during),
+            (Size::new(401, 300), 1.0)
+        );
+        assert!(!scaler.needs_full_frame(during));
+
+        // Once the resize settles, a final frame is rendered at full resolution
+        let settled = start + Duration::from_millis(100);
+        assert!(scaler.needs_full_frame(settled));
+        assert_eq!(scaler.frame(full_size, 2.0, settled), (full_size, 2.0));
+        assert!(!scaler.needs_full_frame(settled));
+
+        // Disabled scalers and invalid scales always render at full resolution
+        let mut disabled = ResizeScaler::new(None);
+        disabled.resized(start);
+        assert_eq!(disabled.frame(full_size, 2.0, during), (full_size, 2.0));
+        let mut invalid = ResizeScaler::new(Some(ResizeResolution {
+            scale: f32::NAN,
+            ..settings
+        }));
+        invalid.resized(start);
+        assert_eq!(invalid.frame(full_size, 2.0, during), (full_size, 2.0));
+    }
+}
@@ -4,9 +4,23 @@ use crate::application::{Appearance, Application, DefaultStyle};
 use crate::core::mouse;
 use crate::core::{Color, Size};
 use crate::graphics::Viewport;
+use crate::scale::ScaleConversion;
 
 use std::marker::PhantomData;
 
+/// What [`State::synchronize()`] found to have changed in the [`Application`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Changes {
+    /// The application's title, if it differs from the title it returned the last time.
+    pub title: Option<String>,
+    /// The [`Appearance`] changed, for instance because the theme changed, so the window needs to
+    /// be redrawn.
+    pub redraw: bool,
+    /// The scale policy changed the [`Viewport`], so the user interface needs to be laid out
+    /// again.
+    pub relayout: bool,
+}
+
 /// The state of a windowed [`Application`].
 #[allow(missing_debug_implementations)]
 pub struct State<A: Application>
@@ -79,6 +93,26 @@ where
         self.viewport.logical_size()
     }
 
+    /// Returns the scale factor used to convert between the logical coordinates iced works with and
+    /// the window's physical pixels.
+    pub fn scale_factor(&self) -> f32 {
+        self.viewport.scale_factor()
+    }
+
+    /// Converts a logical [`Point`][crate::Point], [`Size`], or [`Rectangle`][crate::Rectangle] to
+    /// physical coordinates using the current scale factor. See the [`scale`][crate::scale] module
+    /// for the rounding behavior.
+    pub fn to_physical<T: ScaleConversion>(&self, value: T) -> T {
+        value.to_physical(self.scale_factor())
+    }
+
+    /// Converts a physical [`Point`][crate::Point], [`Size`], or [`Rectangle`][crate::Rectangle]
+    /// to logical coordinates using the current scale factor. See the [`scale`][crate::scale]
+    /// module for the rounding behavior.
+    pub fn to_logical<T: ScaleConversion>(&self, value: T) -> T {
+        value.to_logical(self.scale_factor())
+    }
+
     /// Returns the current cursor position of the [`State`].
     pub fn cursor(&self) -> mouse::Cursor {
         self.cursor_position
@@ -86,6 +120,11 @@ where
             .unwrap_or(mouse::Cursor::Unavailable)
     }
 
+    /// Returns the current title of the [`State`].
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
     /// Returns the current theme of the [`State`].
     pub fn theme(&self) -> &A::Theme {
         &self.theme
@@ -124,16 +163,21 @@ where
 
                 self.viewport_version = self.viewport_version.wrapping_add(1);
             }
-            baseview::Event::Mouse(baseview::MouseEvent::CursorMoved {
-                position,
-                modifiers: _,
-            }) => {
+            baseview::Event::Mouse(
+                baseview::MouseEvent::CursorMoved { position, .. }
+                | baseview::MouseEvent::DragEntered { position, .. }
+                | baseview::MouseEvent::DragMoved { position, .. }
+                | baseview::MouseEvent::DragDropped { position, .. },
+            ) => {
                 self.cursor_position = Some(crate::core::Point {
                     x: position.x as f32,
                     y: position.y as f32,
                 });
-
-                // TODO: Encode cursor moving outside of the window.
+            }
+            baseview::Event::Mouse(
+                baseview::MouseEvent::CursorLeft | baseview::MouseEvent::DragLeft,
+            ) => {
+                self.cursor_position = None;
             }
             #[allow(unused_variables)]
             baseview::Event::Keyboard(event) => {
@@ -156,20 +200,24 @@ where
     }
 
     /// Synchronizes the [`State`] with its [`Application`] and its respective
-    /// window.
+    /// window, and returns what changed.
     ///
     /// Normally an [`Application`] should be synchronized with its [`State`]
-    /// and window after calling [`Application::update`].
+    /// and window after calling [`Application::update`]. Themes don't need to
+    /// be comparable, so a theme change is detected through the [`Appearance`]
+    /// it results in.
     ///
     /// [`Application::update`]: crate::Application::update
-    pub fn synchronize(&mut self, application: &A) {
-        // Update window title
-        let new_title = application.title();
+    pub fn synchronize(&mut self, application: &A) -> Changes {
+        let mut changes = Changes::default();
 
-        if self.title != new_title {
-            // window.set_title(&new_title); // TODO?
+        // Update title
+        let title = application.title();
+        if title != self.title {
+            // window.set_title(&title); // TODO?
 
-            self.title = new_title;
+            self.title.clone_from(&title);
+            changes.title = Some(title);
         }
 
         // Update scale policy
@@ -187,6 +235,7 @@ where
                     );
 
                     self.viewport_version = self.viewport_version.wrapping_add(1);
+                    changes.relayout = true;
                 }
             },
             WindowScalePolicy::ScaleFactor(new_scale) => {
@@ -200,20 +249,139 @@ where
                 if !matches {
                     self.scale_policy = WindowScalePolicy::ScaleFactor(*new_scale);
 
-                    self.viewport =
-                        Viewport::with_physical_size(self.viewport.physical_size(), *new_scale as f32);
+                    self.viewport = Viewport::with_physical_size(
+                        self.viewport.physical_size(),
+                        *new_scale as f32,
+                    );
 
                     self.viewport_version = self.viewport_version.wrapping_add(1);
+                    changes.relayout = true;
                 }
             }
         }
 
-        // Update theme and appearance
+        // Update theme and appearance. The appearance can change without the theme changing, for
+        // instance when `style()` depends on the application's state.
         self.theme = application.theme();
-        self.appearance = application.style(&self.theme);
+        let appearance = application.style(&self.theme);
+        if appearance != self.appearance {
+            self.appearance = appearance;
+            changes.redraw = true;
+        }
+
+        changes
+    }
+
+    pub(crate) fn modifiers(&self) -> iced_runtime::core::keyboard::Modifiers {
+        self.modifiers
     }
 
     pub(crate) fn modifiers_mut(&mut self) -> &mut iced_runtime::core::keyboard::Modifiers {
         &mut self.modifiers
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::futures::backend::null;
+    use crate::futures::Subscription;
+    use crate::{Element, Renderer, Task, Theme, WindowSubs};
+
+    struct Stub {
+        title: &'static str,
+        theme: Theme,
+        background_color: Color,
+        scale: f64,
+    }
+
+    impl Application for Stub {
+        type Message = ();
+        type Theme = Theme;
+        type Executor = null::Executor;
+        type Flags = ();
+
+        fn new(_flags: Self::Flags) -> (Self, Task<Self::Message>) {
+            (
+                Self {
+                    title: "Stub",
+                    theme: Theme::Dark,
+                    background_color: Color::BLACK,
+                    scale: 1.0,
+                },
+                Task::none(),
+            )
+        }
+
+        fn title(&self) -> String {
+            self.title.into()
+        }
+
+        fn update(&mut self, _message: Self::Message) -> Task<Self::Message> {
+            Task::none()
+        }
+
+        fn view(&self) -> Element<'_, Self::Message, Self::Theme, Renderer> {
+            crate::widget::Column::new().into()
+        }
+
+        fn theme(&self) -> Self::Theme {
+            self.theme.clone()
+        }
+
+        fn style(&self, theme: &Self::Theme) -> Appearance {
+            Appearance {
+                background_color: self.background_color,
+                ..theme.default_style()
+            }
+        }
+
+        fn scale_policy(&self) -> WindowScalePolicy {
+            WindowScalePolicy::ScaleFactor(self.scale)
+        }
+
+        fn subscription(
+            &self,
+            _window_subs: &mut WindowSubs<Self::Message>,
+        ) -> Subscription<Self::Message> {
+            Subscription::none()
+        }
+    }
+
+    #[test]
+    fn synchronize_reports_changes() {
+        let (mut application, _) = Stub::new(());
+        let viewport = Viewport::with_physical_size(Size::new(200, 100), 1.0);
+        let mut state = State::new(&application, viewport);
+        let version = state.viewport_version();
+
+        assert_eq!(state.synchronize(&application), Changes::default());
+
+        application.title = "Renamed";
+        let changes = state.synchronize(&application);
+        assert_eq!(changes.title.as_deref(), Some("Renamed"));
+        assert!(!changes.redraw && !changes.relayout);
+        // The title is only reported once
+        assert_eq!(state.synchronize(&application), Changes::default());
+        assert_eq!(state.title(), "Renamed");
+
+        application.theme = Theme::Light;
+        let changes = state.synchronize(&application);
+        assert!(changes.redraw && !changes.relayout && changes.title.is_none());
+        assert_eq!(state.theme(), &Theme::Light);
+        assert_eq!(state.synchronize(&application), Changes::default());
+
+        // The appearance can change on its own
+        application.background_color = Color::WHITE;
+        let changes = state.synchronize(&application);
+        assert!(changes.redraw && !changes.relayout);
+        assert_eq!(state.background_color(), Color::WHITE);
+
+        application.scale = 2.0;
+        let changes = state.synchronize(&application);
+        assert!(changes.relayout && !changes.redraw);
+        assert_eq!(state.logical_size(), Size::new(100.0, 50.0));
+        assert_eq!(state.viewport_version(), version.wrapping_add(1));
+        assert_eq!(state.synchronize(&application), Changes::default());
+    }
+}
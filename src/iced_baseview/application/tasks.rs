@@ -0,0 +1,304 @@
+use std::collections::VecDeque;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Weak};
+use std::task::{Context, Poll};
+
+use crate::futures::futures::stream::{AbortHandle, Abortable, BoxStream, Stream, StreamExt};
+use crate::settings::{TaskLimit, TaskOverflow};
+
+/// Applies the [`TaskLimit`] to the tasks returned by the application. See
+/// [`IcedBaseviewSettings::task_limit`][crate::settings::IcedBaseviewSettings::task_limit].
+///
+/// Every admitted task's stream is wrapped so the number of running tasks goes down again once the
+/// stream ends or is dropped, which may happen on the executor's threads. The streams can also be
+/// cancelled all at once when the window closes, see [`cancel_all()`][Self::cancel_all()].
+pub(crate) struct TaskLimiter<T> {
+    limit: Option<TaskLimit>,
+    in_flight: Arc<AtomicUsize>,
+    queued: VecDeque<BoxStream<'static, T>>,
+    dropped: usize,
+    running: Vec<Running>,
+}
+
+/// An admitted task's stream that may still be running.
+struct Running {
+    handle: AbortHandle,
+    /// Dangles once the stream has been dropped.
+    alive: Weak<()>,
+}
+
+impl<T: Send + 'static> TaskLimiter<T> {
+    pub fn new(limit: Option<TaskLimit>) -> Self {
+        Self {
+            limit,
+            in_flight: Arc::new(AtomicUsize::new(0)),
+            queued: VecDeque::new(),
+            dropped: 0,
+            running: Vec::new(),
+        }
+    }
+
+    /// The number of admitted tasks that are still running.
+    pub fn in_flight(&self) -> usize {
+        self.in_flight.load(Ordering::Acquire)
+    }
+
+    /// Returns the task's stream if it can run right away. Otherwise it's queued or dropped
+    /// depending on the limit's [`TaskOverflow`] policy.
+    pub fn admit(&mut self, stream: BoxStream<'static, T>) -> Option<BoxStream<'static, T>> {
+        let Some(limit) = self.limit else {
+            return Some(self.cancellable(stream));
+        };
+
+        // Queued tasks go first so tasks still start in the order they were returned
+        if self.queued.is_empty() && self.in_flight() < limit.max_in_flight {
+            return Some(self.track(stream));
+        }
+
+        match limit.overflow {
+            TaskOverflow::Queue => self.queued.push_back(stream),
+            TaskOverflow::Drop => {
+                self.dropped += 1;
+                if self.dropped.is_power_of_two() {
+                    log::warn!(
+                        "Dropped {} tasks because more than {} were running at the same time",
+                        self.dropped,
+                        limit.max_in_flight
+                    );
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Take the queued tasks that can run now that other tasks have finished.
+    pub fn start_queued(&mut self) -> Vec<BoxStream<'static, T>> {
+        let Some(limit) = self.limit else {
+            return Vec::new();
+        };
+
+        let mut streams = Vec::new();
+        while self.in_flight() < limit.max_in_flight {
+            let Some(stream) = self.queued.pop_front() else {
+                break;
+            };
+
+            streams.push(self.track(stream));
+        }
+
+        streams
+    }
+
+    /// Cancel every running and queued task. This is done when the window closes, since nothing
+    /// would receive the tasks' messages anymore. A cancelled task's stream ends the next time it's
+    /// polled, so its future on the executor finishes without producing more messages. Work a task
+    /// started on another thread, like a blocking task or a file dialog, keeps running until it's
+    /// done, and its result is then discarded.
+    pub fn cancel_all(&mut self) {
+        let cancelled = self.running.len() + self.queued.len();
+        if cancelled > 0 {
+            log::debug!("Cancelling {cancelled} tasks that are still pending");
+        }
+
+        for running in self.running.drain(..) {
+            running.handle.abort();
+        }
+        self.queued.clear();
+    }
+
+    fn track(&mut self, stream: BoxStream<'static, T>) -> BoxStream<'static, T> {
+        let _ = self.in_flight.fetch_add(1, Ordering::AcqRel);
+
+        let stream = Tracked {
+            stream,
+            slot: Some(Slot(self.in_flight.clone())),
+        }
+        .boxed();
+
+        self.cancellable(stream)
+    }
+
+    fn cancellable(&mut self, stream: BoxStream<'static, T>) -> BoxStream<'static, T> {
+        // Forget about streams that have already been dropped so this doesn't keep growing
+        self.running
+            .retain(|running| running.alive.strong_count() > 0);
+
+        let (handle, registration) = AbortHandle::new_pair();
+        let alive = Arc::new(());
+        self.running.push(Running {
+            handle,
+            alive: Arc::downgrade(&alive),
+        });
+
+        Cancellable {
+            stream: Abortable::new(stream, registration),
+            _alive: alive,
+        }
+        .boxed()
+    }
+}
+
+/// Frees up its spot in the limit when dropped.
+struct Slot(Arc<AtomicUsize>);
+
+impl Drop for Slot {
+    fn drop(&mut self) {
+        let _ = self.0.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+struct Tracked<T> {
+    stream: BoxStream<'static, T>,
+    slot: Option<Slot>,
+}
+
+impl<T> Stream for Tracked<T> {
+    type Item = T;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        let this = self.get_mut();
+        let item = this.stream.poll_next_unpin(cx);
+        if let Poll::Ready(None) = item {
+            this.slot = None;
+        }
+
+        item
+    }
+}
+
+/// A stream that ends early when its task is cancelled.
+struct Cancellable<T> {
+    stream: Abortable<BoxStream<'static, T>>,
+    _alive: Arc<()>,
+}
+
+impl<T> Stream for Cancellable<T> {
+    type Item = T;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        self.get_mut().stream.poll_next_unpin(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::futures::futures::stream;
+    use crate::futures::futures::task::noop_waker_ref;
+
+    /// Run a stream that never has to wait to completion.
+    fn run<T>(mut stream: BoxStream<'static, T>) -> Vec<T> {
+        let mut context = Context::from_waker(noop_waker_ref());
+        let mut items = Vec::new();
+        while let Poll::Ready(Some(item)) = stream.poll_next_unpin(&mut context) {
+            items.push(item);
+        }
+
+        items
+    }
+
+    fn limiter(overflow: TaskOverflow) -> TaskLimiter<u32> {
+        TaskLimiter::new(Some(TaskLimit {
+            max_in_flight: 2,
+            overflow,
+        }))
+    }
+
+    #[test]
+    fn tasks_beyond_the_limit_are_queued() {
+        let mut tasks = limiter(TaskOverflow::Queue);
+
+        let first = tasks.admit(stream::iter([1]).boxed()).unwrap();
+        let second = tasks.admit(stream::iter([2]).boxed()).unwrap();
+        assert!(tasks.admit(stream::iter([3]).boxed()).is_none());
+        assert!(tasks.admit(stream::iter([4]).boxed()).is_none());
+        assert_eq!((tasks.in_flight(), tasks.queued.len()), (2, 2));
+        assert!(tasks.start_queued().is_empty());
+
+        // Running a task to completion frees up its spot
+        assert_eq!(run(first), [1]);
+        assert_eq!(tasks.in_flight(), 1);
+        let started = tasks.start_queued();
+        assert_eq!(started.len(), 1);
+        assert_eq!((tasks.in_flight(), tasks.queued.len()), (2, 1));
+
+        // New tasks wait for the queued ones, and dropped streams also free up their spot
+        drop(second);
+        assert!(tasks.admit(stream::iter([5]).boxed()).is_none());
+        let started = tasks.start_queued();
+        assert_eq!(started.into_iter().flat_map(run).collect::<Vec<_>>(), [4]);
+        assert_eq!(tasks.queued.len(), 1);
+    }
+
+    #[test]
+    fn tasks_beyond_the_limit_are_dropped() {
+        let mut tasks = limiter(TaskOverflow::Drop);
+
+        let _first = tasks.admit(stream::iter([1]).boxed()).unwrap();
+        let _second = tasks.admit(stream::iter([2]).boxed()).unwrap();
+        assert!(tasks.admit(stream::iter([3]).boxed()).is_none());
+        assert_eq!((tasks.in_flight(), tasks.queued.len()), (2, 0));
+        assert!(tasks.start_queued().is_empty());
+    }
+
+    #[test]
+    fn pending_tasks_are_cancelled_on_close() {
+        use crate::futures::futures::channel::{mpsc, oneshot};
+        use crate::futures::futures::{future, FutureExt};
+        use crate::Proxy;
+        use iced_runtime::Action;
+        use std::time::Duration;
+
+        // A blocking task that's still sleeping on another thread when the window closes
+        let (sender, receiver) = oneshot::channel();
+        let worker = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(200));
+            sender.send(Action::Output(1u32)).is_ok()
+        });
+        let mut tasks = TaskLimiter::new(None);
+        let stream = tasks
+            .admit(
+                stream::once(receiver)
+                    .filter_map(|result| future::ready(result.ok()))
+                    .boxed(),
+            )
+            .unwrap();
+
+        // This mimics `Runtime::run()` after the window's event loop is gone
+        let (runtime_tx, runtime_rx) = mpsc::unbounded();
+        drop(runtime_rx);
+        tasks.cancel_all();
+        let mut context = Context::from_waker(noop_waker_ref());
+        let mut forward = stream.map(Ok).forward(Proxy::new(runtime_tx));
+        assert!(matches!(
+            forward.poll_unpin(&mut context),
+            Poll::Ready(Ok(()))
+        ));
+
+        // The worker's result is discarded without any errors
+        drop(forward);
+        assert!(!worker.join().unwrap());
+
+        // A task that finishes after the window closed is a no-op
+        let (runtime_tx, runtime_rx) = mpsc::unbounded();
+        drop(runtime_rx);
+        let mut forward = stream::iter([Ok(Action::Output(2u32))]).forward(Proxy::new(runtime_tx));
+        assert!(matches!(
+            forward.poll_unpin(&mut context),
+            Poll::Ready(Ok(()))
+        ));
+    }
+
+    #[test]
+    fn no_limit_by_default() {
+        let mut tasks = TaskLimiter::new(None);
+
+        let streams: Vec<_> = (0..100)
+            .map(|i| tasks.admit(stream::iter([i]).boxed()).unwrap())
+            .collect();
+        assert_eq!(streams.len(), 100);
+        assert_eq!(tasks.in_flight(), 0);
+    }
+}
@@ -0,0 +1,396 @@
+//! Support for a single optional child window, for instance to pop out a large editor panel.
+//!
+//! An [`Application`] opens the child window by returning `Some` from
+//! [`Application::child_view()`], and the window is closed again as soon as it returns `None`. The
+//! child window is driven by the same runtime as the main window. It has its own user interface and
+//! rendering surface, and every message produced by its widgets is passed to the application's
+//! regular [`Application::update()`] function.
+//!
+//! Whenever the child window is opened, is closed by the user, or can't be opened at all, the
+//! application is notified through [`Application::child_window_status_changed()`]. Once the user
+//! closes the child window the runtime won't open it again until `child_view()` has returned `None`
+//! at least once, so the application should clear its own flag when it receives
+//! [`ChildWindowStatus::Closed`].
+//!
+//! # Host limitations
+//!
+//! Plugin hosts generally only expect a plugin to draw into the single window they provide. Many
+//! hosts don't allow plugins to create additional top level windows, and on some platforms such a
+//! window would end up behind the host's window or would not receive keyboard input. baseview
+//! can only open windows that are embedded in a parent window, so the child window is embedded in
+//! the main window and is not a free floating window. Because of that, child windows can be
+//! disabled altogether with [`IcedBaseviewSettings::allow_child_window`]. In that case the
+//! application receives [`ChildWindowStatus::Unavailable`] and it should render the panel inside
+//! of the main window instead.
+//!
+//! Events for the child window are handled during the next frame of the main window.
+//!
+//! # Example
+//!
+//! ```ignore
+//! struct Editor {
+//!     detached: bool,
+//!     child_window_status: ChildWindowStatus,
+//! }
+//!
+//! impl Editor {
+//!     fn panel(&self) -> Element<'_, Message> {
+//!         // ...
+//!     }
+//! }
+//!
+//! impl Application for Editor {
+//!     fn update(&mut self, message: Message) -> Task<Message> {
+//!         match message {
+//!             Message::Detach(detached) => self.detached = detached,
+//!             // Messages from the child window's widgets end up here as well
+//!             // ...
+//!         }
+//!
+//!         Task::none()
+//!     }
+//!
+//!     fn view(&self) -> Element<'_, Message> {
+//!         let mut content = Column::new().push(self.toolbar());
+//!         // Fall back to an in-window panel if the child window could not be opened
+//!         if !self.detached || self.child_window_status == ChildWindowStatus::Unavailable {
+//!             content = content.push(self.panel());
+//!         }
+//!
+//!         content.into()
+//!     }
+//!
+//!     fn child_view(&self) -> Option<Element<'_, Message>> {
+//!         self.detached.then(|| self.panel())
+//!     }
+//!
+//!     fn child_window_settings(&self) -> ChildWindowSettings {
+//!         ChildWindowSettings {
+//!             title: String::from("Panel"),
+//!             size: Size::new(600.0, 400.0),
+//!         }
+//!     }
+//!
+//!     fn child_window_status_changed(&mut self, status: ChildWindowStatus) {
+//!         self.child_window_status = status;
+//!         if status == ChildWindowStatus::Closed {
+//!             self.detached = false;
+//!         }
+//!     }
+//! }
+//! ```
+//!
+//! [`Application`]: crate::Application
+//! [`Application::child_view()`]: crate::Application::child_view()
+//! [`Application::update()`]: crate::Application::update()
+//! [`Application::child_window_status_changed()`]: crate::Application::child_window_status_changed()
+//! [`IcedBaseviewSettings::allow_child_window`]: crate::settings::IcedBaseviewSettings::allow_child_window
+
+use baseview::{Event, EventStatus, Window, WindowHandler};
+use iced_runtime::futures::futures::channel::mpsc;
+
+use crate::core::Size;
+use crate::window::{requests_exit, RuntimeEvent};
+
+/// The settings used when opening the child window.
+#[derive(Debug, Clone)]
+pub struct ChildWindowSettings {
+    /// The child window's title.
+    pub title: String,
+    /// The child window's initial size in logical pixels.
+    pub size: Size,
+}
+
+impl Default for ChildWindowSettings {
+    fn default() -> Self {
+        Self {
+            title: String::from("iced_baseview"),
+            size: Size::new(500.0, 300.0),
+        }
+    }
+}
+
+/// The status of the child window, passed to
+/// [`Application::child_window_status_changed()`][crate::Application::child_window_status_changed()].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ChildWindowStatus {
+    /// There is no child window. This is also sent when the user closes the child window.
+    #[default]
+    Closed,
+    /// The child window is open and displays [`Application::child_view()`][crate::Application::child_view()].
+    Open,
+    /// The child window could not be opened. The application should display the child window's
+    /// contents in the main window instead.
+    Unavailable,
+}
+
+/// What the runtime should do with the child window after the application has been updated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ChildWindowRequest {
+    Open,
+    Close,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Phase {
+    Closed,
+    /// The window has been requested, but it has not been created yet.
+    Opening,
+    Open,
+    Unavailable,
+}
+
+/// Tracks the child window's lifecycle. This decides when the window should be opened or closed
+/// and which status changes the application needs to be notified about.
+#[derive(Debug)]
+pub(crate) struct ChildWindowLifecycle {
+    phase: Phase,
+    /// Set when the user closed the child window. The window is not reopened until the application
+    /// stops requesting it, since it would otherwise pop up again immediately.
+    dismissed: bool,
+    /// The last status the application was notified about, or `None` if the next status should be
+    /// reported regardless.
+    notified: Option<ChildWindowStatus>,
+    pending: Option<ChildWindowStatus>,
+}
+
+impl ChildWindowLifecycle {
+    pub fn new() -> Self {
+        Self {
+            phase: Phase::Closed,
+            dismissed: false,
+            notified: Some(ChildWindowStatus::Closed),
+            pending: None,
+        }
+    }
+
+    /// Whether the application's child view should be built and displayed.
+    pub fn is_active(&self) -> bool {
+        matches!(self.phase, Phase::Opening | Phase::Open)
+    }
+
+    /// Whether the child window has been created and can be drawn to.
+    pub fn is_open(&self) -> bool {
+        self.phase == Phase::Open
+    }
+
+    /// Called after every update with whether the application currently has a child view.
+    pub fn sync(&mut self, wants_child: bool, allowed: bool) -> Option<ChildWindowRequest> {
+        match (wants_child, self.phase) {
+            (true, Phase::Closed) if !self.dismissed => {
+                if allowed {
+                    self.set_phase(Phase::Opening);
+                    Some(ChildWindowRequest::Open)
+                } else {
+                    self.set_phase(Phase::Unavailable);
+                    None
+                }
+            }
+            (true, _) => None,
+            (false, Phase::Opening | Phase::Open) => {
+                self.set_phase(Phase::Closed);
+                Some(ChildWindowRequest::Close)
+            }
+            (false, _) => {
+                self.set_phase(Phase::Closed);
+                self.dismissed = false;
+                None
+            }
+        }
+    }
+
+    /// Called when opening the window failed before it was created.
+    pub fn failed(&mut self) {
+        self.set_phase(Phase::Unavailable);
+    }
+
+    /// Called when the window has been created. Returns `false` if the window is no longer needed,
+    /// in which case it should be closed again.
+    pub fn opened(&mut self) -> bool {
+        if self.phase == Phase::Opening {
+            self.set_phase(Phase::Open);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Called when the window has been closed by the user or by the platform.
+    pub fn closed(&mut self) {
+        if self.is_active() {
+            self.set_phase(Phase::Closed);
+            self.dismissed = true;
+            // The window may be closed before the application was told it was open, but the
+            // application still needs to know that it should stop requesting it
+            self.notified = None;
+        }
+    }
+
+    /// Whether [`take_status_change()`][Self::take_status_change()] would return a new status.
+    pub fn has_status_change(&self) -> bool {
+        self.pending
+            .is_some_and(|status| Some(status) != self.notified)
+    }
+
+    /// Returns the status the application should be notified about, if it changed.
+    pub fn take_status_change(&mut self) -> Option<ChildWindowStatus> {
+        let status = self.pending.take()?;
+        if Some(status) != self.notified {
+            self.notified = Some(status);
+            Some(status)
+        } else {
+            None
+        }
+    }
+
+    fn set_phase(&mut self, phase: Phase) {
+        self.phase = phase;
+        self.pending = match phase {
+            // The application is only told about the window once it actually exists
+            Phase::Opening => None,
+            Phase::Closed => Some(ChildWindowStatus::Closed),
+            Phase::Open => Some(ChildWindowStatus::Open),
+            Phase::Unavailable => Some(ChildWindowStatus::Unavailable),
+        };
+    }
+}
+
+/// The baseview window handler for the child window. This forwards everything to the main
+/// window's runtime, which does the actual event handling and rendering.
+pub(crate) struct ChildWindowHandler<Message: 'static + Send> {
+    sender: mpsc::UnboundedSender<RuntimeEvent<Message>>,
+    closed: bool,
+}
+
+impl<Message: 'static + Send> ChildWindowHandler<Message> {
+    pub fn new(
+        window: &mut Window<'_>,
+        sender: mpsc::UnboundedSender<RuntimeEvent<Message>>,
+    ) -> Self {
+        let _ = sender.unbounded_send(RuntimeEvent::ChildOpened(
+            crate::conversion::convert_window(window),
+        ));
+
+        Self {
+            sender,
+            closed: false,
+        }
+    }
+}
+
+impl<Message: 'static + Send> WindowHandler for ChildWindowHandler<Message> {
+    fn on_frame(&mut self, _window: &mut Window<'_>) {}
+
+    fn on_event(&mut self, _window: &mut Window<'_>, event: Event) -> EventStatus {
+        if self.closed {
+            return EventStatus::Ignored;
+        }
+
+        if requests_exit(&event) {
+            self.closed = true;
+            let _ = self.sender.unbounded_send(RuntimeEvent::ChildClosed);
+
+            return EventStatus::Ignored;
+        }
+
+        // The runtime only handles this event during the main window's next frame, so there's no
+        // way to know whether a widget captured it. Keyboard events are left to the host.
+        let status = match event {
+            Event::Keyboard(_) => EventStatus::Ignored,
+            _ => EventStatus::Captured,
+        };
+        let _ = self
+            .sender
+            .unbounded_send(RuntimeEvent::ChildBaseview(event));
+
+        status
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lifecycle() {
+        let mut lifecycle = ChildWindowLifecycle::new();
+        assert_eq!(lifecycle.sync(false, true), None);
+        assert_eq!(lifecycle.take_status_change(), None);
+
+        // The application is only notified once the window exists
+        assert_eq!(lifecycle.sync(true, true), Some(ChildWindowRequest::Open));
+        assert!(lifecycle.is_active() && !lifecycle.is_open());
+        assert_eq!(lifecycle.take_status_change(), None);
+        assert!(lifecycle.opened());
+        assert_eq!(
+            lifecycle.take_status_change(),
+            Some(ChildWindowStatus::Open)
+        );
+        assert_eq!(lifecycle.sync(true, true), None);
+
+        // Closing the window from the application
+        assert_eq!(lifecycle.sync(false, true), Some(ChildWindowRequest::Close));
+        assert_eq!(
+            lifecycle.take_status_change(),
+            Some(ChildWindowStatus::Closed)
+        );
+
+        // A window that's created after the application stopped requesting it is closed again
+        assert_eq!(lifecycle.sync(true, true), Some(ChildWindowRequest::Open));
+        assert_eq!(lifecycle.sync(false, true), Some(ChildWindowRequest::Close));
+        assert!(!lifecycle.opened());
+        assert_eq!(lifecycle.take_status_change(), None);
+    }
+
+    #[test]
+    fn user_close_is_not_reopened() {
+        let mut lifecycle = ChildWindowLifecycle::new();
+        assert_eq!(lifecycle.sync(true, true), Some(ChildWindowRequest::Open));
+        assert!(lifecycle.opened());
+        assert_eq!(
+            lifecycle.take_status_change(),
+            Some(ChildWindowStatus::Open)
+        );
+        lifecycle.closed();
+        assert_eq!(
+            lifecycle.take_status_change(),
+            Some(ChildWindowStatus::Closed)
+        );
+
+        // The application is also told about windows that were closed before it knew they were
+        // open
+        assert_eq!(lifecycle.sync(false, true), None);
+        assert_eq!(lifecycle.sync(true, true), Some(ChildWindowRequest::Open));
+        assert!(lifecycle.opened());
+        lifecycle.closed();
+        assert_eq!(
+            lifecycle.take_status_change(),
+            Some(ChildWindowStatus::Closed)
+        );
+
+        // The application still requests the window, but it stays closed until the request is
+        // dropped
+        assert_eq!(lifecycle.sync(true, true), None);
+        assert_eq!(lifecycle.sync(false, true), None);
+        assert_eq!(lifecycle.sync(true, true), Some(ChildWindowRequest::Open));
+    }
+
+    #[test]
+    fn falls_back_when_unavailable() {
+        let mut lifecycle = ChildWindowLifecycle::new();
+        assert_eq!(lifecycle.sync(true, false), None);
+        assert!(!lifecycle.is_active());
+        assert_eq!(
+            lifecycle.take_status_change(),
+            Some(ChildWindowStatus::Unavailable)
+        );
+        assert_eq!(lifecycle.sync(true, false), None);
+        assert_eq!(lifecycle.take_status_change(), None);
+
+        assert_eq!(lifecycle.sync(false, false), None);
+        assert_eq!(
+            lifecycle.take_status_change(),
+            Some(ChildWindowStatus::Closed)
+        );
+    }
+}
@@ -1,6 +1,35 @@
 //! Access the clipboard.
+//!
+//! # Paste availability
+//!
+//! A paste button should usually only be enabled while the clipboard contains something that can
+//! be pasted. Clipboards don't notify applications when their contents change, so the runtime
+//! polls the clipboard instead when the application sets a [`ClipboardWatch`] in
+//! [`WindowSubs::on_clipboard_change`][crate::WindowSubs::on_clipboard_change]. The clipboard is
+//! read at most once per [`interval`][ClipboardWatch::interval], and the watch's callback is only
+//! called when the availability changes:
+//!
+//! ```ignore
+//! fn subscription(&self, window_subs: &mut WindowSubs<Message>) -> Subscription<Message> {
+//!     window_subs.on_clipboard_change = Some(
+//!         ClipboardWatch::new(|available| Some(Message::PasteAvailable(available)))
+//!             .accepts(|text| text.starts_with("FooPreset:")),
+//!     );
+//!
+//!     Subscription::none()
+//! }
+//! ```
+//!
+//! Only text can be read from the clipboard, so checking for a specific type of content means
+//! checking the text with [`ClipboardWatch::accepts()`]. Every poll reads the clipboard's full
+//! contents. This is cheap on Windows and macOS, but on X11 and Wayland reading the clipboard asks
+//! the application that owns the clipboard for its contents, which can take a while if that
+//! application is busy or copied a lot of text. Keep the interval at a few hundred milliseconds
+//! or more, and unset the watch while no paste button is visible.
 
 use std::cell::RefCell;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use crate::core::clipboard::Kind as ClipboardKind;
 
@@ -98,3 +127,167 @@ impl crate::core::Clipboard for Clipboard {
         self.write(kind, contents)
     }
 }
+
+/// Polls the clipboard to find out whether it contains something that can be pasted. See the
+/// [module documentation][self].
+#[allow(missing_debug_implementations)]
+pub struct ClipboardWatch<Message> {
+    kind: ClipboardKind,
+    interval: Duration,
+    accepts: Arc<dyn Fn(&str) -> bool>,
+    on_change: Arc<dyn Fn(bool) -> Option<Message>>,
+}
+
+impl<Message> ClipboardWatch<Message> {
+    /// The default value for [`interval()`][Self::interval()].
+    pub const DEFAULT_INTERVAL: Duration = Duration::from_millis(500);
+
+    /// Watch the standard clipboard for non-empty text. `on_change` is called with the
+    /// availability right after the watch is set, and then every time the availability changes.
+    pub fn new(on_change: impl Fn(bool) -> Option<Message> + 'static) -> Self {
+        Self {
+            kind: ClipboardKind::Standard,
+            interval: Self::DEFAULT_INTERVAL,
+            accepts: Arc::new(|text| !text.is_empty()),
+            on_change: Arc::new(on_change),
+        }
+    }
+
+    /// Watch a different clipboard, like the primary selection on X11 and Wayland.
+    pub fn kind(mut self, kind: ClipboardKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    /// Set the minimum amount of time between two clipboard reads.
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Only consider the clipboard's contents available if `accepts` returns `true` for its text.
+    pub fn accepts(mut self, accepts: impl Fn(&str) -> bool + 'static) -> Self {
+        self.accepts = Arc::new(accepts);
+        self
+    }
+
+    /// Convert the watch's messages using `f`, for instance to wrap them in a parent message.
+    pub fn map<B>(self, f: impl Fn(Message) -> B + 'static) -> ClipboardWatch<B>
+    where
+        Message: 'static,
+    {
+        let on_change = self.on_change;
+
+        ClipboardWatch {
+            kind: self.kind,
+            interval: self.interval,
+            accepts: self.accepts,
+            on_change: Arc::new(move |available| on_change(available).map(&f)),
+        }
+    }
+}
+
+/// Keeps track of when the clipboard was last read for a [`ClipboardWatch`], and what its
+/// availability was.
+#[derive(Debug, Default)]
+pub(crate) struct ClipboardPoller {
+    last_poll: Option<Instant>,
+    available: Option<bool>,
+}
+
+impl ClipboardPoller {
+    /// Read the clipboard if the watch's interval has passed since the last read, and return the
+    /// watch's message if the availability changed. Passing `None` because the application unset
+    /// the watch resets the poller, so setting a watch again reports the availability right away.
+    pub fn poll<Message>(
+        &mut self,
+        watch: Option<&ClipboardWatch<Message>>,
+        clipboard: &dyn crate::core::Clipboard,
+        now: Instant,
+    ) -> Option<Message> {
+        let Some(watch) = watch else {
+            *self = Self::default();
+            return None;
+        };
+
+        if self
+            .last_poll
+            .is_some_and(|last_poll| now.duration_since(last_poll) < watch.interval)
+        {
+            return None;
+        }
+        self.last_poll = Some(now);
+
+        let available = clipboard
+            .read(watch.kind)
+            .is_some_and(|text| (watch.accepts)(&text));
+        if self.available == Some(available) {
+            return None;
+        }
+        self.available = Some(available);
+
+        (watch.on_change)(available)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    /// A clipboard that counts how often it has been read.
+    #[derive(Default)]
+    struct StubClipboard {
+        contents: Option<String>,
+        reads: Cell<usize>,
+    }
+
+    impl crate::core::Clipboard for StubClipboard {
+        fn read(&self, _kind: ClipboardKind) -> Option<String> {
+            self.reads.set(self.reads.get() + 1);
+            self.contents.clone()
+        }
+
+        fn write(&mut self, _kind: ClipboardKind, contents: String) {
+            self.contents = Some(contents);
+        }
+    }
+
+    #[test]
+    fn polls_are_throttled_and_only_changes_are_reported() {
+        let watch = ClipboardWatch::new(Some)
+            .interval(Duration::from_millis(100))
+            .accepts(|text| text.starts_with("preset:"));
+        let mut clipboard = StubClipboard::default();
+        let mut poller = ClipboardPoller::default();
+        let start = Instant::now();
+        let at = |ms| start + Duration::from_millis(ms);
+
+        // The initial availability is always reported
+        assert_eq!(poller.poll(Some(&watch), &clipboard, at(0)), Some(false));
+        assert_eq!(poller.poll(Some(&watch), &clipboard, at(50)), None);
+        assert_eq!(clipboard.reads.get(), 1);
+
+        // Unchanged and rejected contents don't produce messages
+        assert_eq!(poller.poll(Some(&watch), &clipboard, at(100)), None);
+        crate::core::Clipboard::write(&mut clipboard, ClipboardKind::Standard, "hello".into());
+        assert_eq!(poller.poll(Some(&watch), &clipboard, at(200)), None);
+        assert_eq!(clipboard.reads.get(), 3);
+
+        // A change is only noticed once the interval has passed
+        crate::core::Clipboard::write(&mut clipboard, ClipboardKind::Standard, "preset:a".into());
+        assert_eq!(poller.poll(Some(&watch), &clipboard, at(250)), None);
+        assert_eq!(poller.poll(Some(&watch), &clipboard, at(300)), Some(true));
+        crate::core::Clipboard::write(&mut clipboard, ClipboardKind::Standard, "preset:b".into());
+        assert_eq!(poller.poll(Some(&watch), &clipboard, at(400)), None);
+        assert_eq!(clipboard.reads.get(), 5);
+
+        // Unsetting the watch stops polling, and setting it again reports the availability again
+        assert_eq!(
+            poller.poll(None::<&ClipboardWatch<bool>>, &clipboard, at(500)),
+            None
+        );
+        assert_eq!(clipboard.reads.get(), 5);
+        assert_eq!(poller.poll(Some(&watch), &clipboard, at(510)), Some(true));
+    }
+}
@@ -63,6 +63,60 @@ pub fn baseview_to_iced_events(
                     }));
                 }
             },
+            baseview::MouseEvent::DragEntered {
+                position,
+                modifiers,
+                data,
+            } => {
+                if let Some(event) = update_modifiers(iced_modifiers, modifiers) {
+                    iced_events.push(event);
+                }
+                iced_events.push(IcedEvent::Mouse(IcedMouseEvent::CursorMoved {
+                    position: Point::new(position.x as f32, position.y as f32),
+                }));
+                if let baseview::DropData::Files(paths) = data {
+                    iced_events.extend(
+                        paths
+                            .into_iter()
+                            .map(|path| IcedEvent::Window(IcedWindowEvent::FileHovered(path))),
+                    );
+                }
+            }
+            baseview::MouseEvent::DragMoved {
+                position,
+                modifiers,
+                data: _,
+            } => {
+                if let Some(event) = update_modifiers(iced_modifiers, modifiers) {
+                    iced_events.push(event);
+                }
+                iced_events.push(IcedEvent::Mouse(IcedMouseEvent::CursorMoved {
+                    position: Point::new(position.x as f32, position.y as f32),
+                }));
+            }
+            baseview::MouseEvent::DragLeft => {
+                iced_events.push(IcedEvent::Window(IcedWindowEvent::FilesHoveredLeft));
+                iced_events.push(IcedEvent::Mouse(IcedMouseEvent::CursorLeft));
+            }
+            baseview::MouseEvent::DragDropped {
+                position,
+                modifiers,
+                data,
+            } => {
+                if let Some(event) = update_modifiers(iced_modifiers, modifiers) {
+                    iced_events.push(event);
+                }
+                iced_events.push(IcedEvent::Mouse(IcedMouseEvent::CursorMoved {
+                    position: Point::new(position.x as f32, position.y as f32),
+                }));
+                if let baseview::DropData::Files(paths) = data {
+                    iced_events.extend(
+                        paths
+                            .into_iter()
+                            .map(|path| IcedEvent::Window(IcedWindowEvent::FileDropped(path))),
+                    );
+                }
+            }
             _ => {}
         },
 
@@ -142,13 +196,36 @@ pub fn baseview_to_iced_events(
                 )));
             }
             baseview::WindowEvent::Unfocused => {
-                *iced_modifiers = IcedModifiers::empty();
+                // The window won't see the modifiers being released anymore
+                if let Some(event) = update_modifiers(iced_modifiers, BaseviewModifiers::empty()) {
+                    iced_events.push(event);
+                }
             }
             _ => {}
         },
     }
 }
 
+/// Whether `event` drags files over the window. The window needs to accept these drags, or the
+/// host won't let the files be dropped onto it.
+pub fn is_file_drag(event: &BaseEvent) -> bool {
+    matches!(
+        event,
+        BaseEvent::Mouse(
+            baseview::MouseEvent::DragEntered {
+                data: baseview::DropData::Files(_),
+                ..
+            } | baseview::MouseEvent::DragMoved {
+                data: baseview::DropData::Files(_),
+                ..
+            } | baseview::MouseEvent::DragDropped {
+                data: baseview::DropData::Files(_),
+                ..
+            }
+        )
+    )
+}
+
 fn update_modifiers(
     iced_modifiers: &mut IcedModifiers,
     baseview_modifiers: BaseviewModifiers,
@@ -825,3 +902,107 @@ impl raw_window_handle_06::HasDisplayHandle for WindowWrapper {
 
 unsafe impl Send for WindowWrapper {}
 unsafe impl Sync for WindowWrapper {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn convert(event: BaseEvent, iced_modifiers: &mut IcedModifiers) -> Vec<IcedEvent> {
+        let mut iced_events = Vec::new();
+        baseview_to_iced_events(event, &mut iced_events, iced_modifiers, false);
+
+        iced_events
+    }
+
+    #[test]
+    fn modifiers_held_before_entering_the_window() {
+        // Shift was pressed while another window had keyboard focus, so the cursor entering the
+        // window is the first event that shows it
+        let mut iced_modifiers = IcedModifiers::empty();
+        let events = convert(
+            BaseEvent::Mouse(baseview::MouseEvent::CursorMoved {
+                position: baseview::Point::new(10.0, 20.0),
+                modifiers: BaseviewModifiers::SHIFT,
+            }),
+            &mut iced_modifiers,
+        );
+        assert_eq!(
+            events,
+            [
+                IcedEvent::Keyboard(IcedKeyEvent::ModifiersChanged(IcedModifiers::SHIFT)),
+                IcedEvent::Mouse(IcedMouseEvent::CursorMoved {
+                    position: Point::new(10.0, 20.0),
+                }),
+            ]
+        );
+        assert_eq!(iced_modifiers, IcedModifiers::SHIFT);
+
+        // The change is only reported once
+        let events = convert(
+            BaseEvent::Mouse(baseview::MouseEvent::ButtonPressed {
+                button: baseview::MouseButton::Left,
+                modifiers: BaseviewModifiers::SHIFT,
+            }),
+            &mut iced_modifiers,
+        );
+        assert_eq!(
+            events,
+            [IcedEvent::Mouse(IcedMouseEvent::ButtonPressed(
+                IcedMouseButton::Left
+            ))]
+        );
+
+        // Releasing Shift after the window lost focus can't be observed anymore
+        let events = convert(
+            BaseEvent::Window(baseview::WindowEvent::Unfocused),
+            &mut iced_modifiers,
+        );
+        assert_eq!(
+            events,
+            [IcedEvent::Keyboard(IcedKeyEvent::ModifiersChanged(
+                IcedModifiers::empty()
+            ))]
+        );
+    }
+
+    #[test]
+    fn dropped_files_become_window_events() {
+        let mut iced_modifiers = IcedModifiers::empty();
+        let paths = vec![
+            std::path::PathBuf::from("/samples/kick.wav"),
+            std::path::PathBuf::from("/samples/snare.wav"),
+        ];
+        let drop = BaseEvent::Mouse(baseview::MouseEvent::DragDropped {
+            position: baseview::Point::new(10.0, 20.0),
+            modifiers: BaseviewModifiers::empty(),
+            data: baseview::DropData::Files(paths.clone()),
+        });
+        assert!(is_file_drag(&drop));
+
+        // Widgets see where the files were dropped before they see the files
+        assert_eq!(
+            convert(drop, &mut iced_modifiers),
+            [
+                IcedEvent::Mouse(IcedMouseEvent::CursorMoved {
+                    position: Point::new(10.0, 20.0),
+                }),
+                IcedEvent::Window(IcedWindowEvent::FileDropped(paths[0].clone())),
+                IcedEvent::Window(IcedWindowEvent::FileDropped(paths[1].clone())),
+            ]
+        );
+
+        // Dragging something other than files only moves the cursor, and isn't accepted
+        let drag = BaseEvent::Mouse(baseview::MouseEvent::DragMoved {
+            position: baseview::Point::new(30.0, 40.0),
+            modifiers: BaseviewModifiers::empty(),
+            data: baseview::DropData::None,
+        });
+        assert!(!is_file_drag(&drag));
+        assert_eq!(
+            convert(drag, &mut iced_modifiers),
+            [IcedEvent::Mouse(IcedMouseEvent::CursorMoved {
+                position: Point::new(30.0, 40.0),
+            })]
+        );
+    }
+}
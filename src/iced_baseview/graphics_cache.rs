@@ -0,0 +1,242 @@
+//! Reusing the compositor and renderer when a window is reopened shortly after it was closed.
+//!
+//! Some hosts open and close plugin editors several times in quick succession, for instance while
+//! loading a project or browsing presets. Creating the compositor means setting up a graphics
+//! adapter and device, which can take a couple hundred milliseconds every time. When a window is
+//! opened with a [`GraphicsCache`] in its [`Settings`][crate::Settings], its compositor and renderer
+//! are kept around for a short grace period after the window closes, and the next window opened
+//! during that period uses them instead of creating new ones. The window surface is always created
+//! from scratch since it belongs to the window.
+//!
+//! The cache also serializes windows: a new window waits for the previous window's runtime to fully
+//! shut down before it starts, so two instances never initialize and tear down graphics resources
+//! at the same time.
+
+use std::any::Any;
+use std::sync::{Arc, Condvar, Mutex, Weak};
+use std::thread::{self, ThreadId};
+use std::time::{Duration, Instant};
+
+/// Keeps the graphics resources of a closed window alive for a short while so they can be reused.
+/// See the [module documentation][self].
+#[derive(Debug)]
+pub struct GraphicsCache {
+    grace_period: Duration,
+    cached: Mutex<Cached>,
+    instance: Mutex<Instance>,
+    instance_stopped: Condvar,
+}
+
+#[derive(Debug, Default)]
+struct Cached {
+    /// The compositor and renderer of the last closed window.
+    graphics: Option<Box<dyn Any + Send>>,
+    /// Incremented every time graphics are stored, so the cleanup thread of an earlier release
+    /// doesn't drop later graphics.
+    generation: u64,
+}
+
+/// Tracks the window currently using this cache.
+#[derive(Debug, Default)]
+struct Instance {
+    running: bool,
+    /// The thread the window's runtime runs on, once it has started.
+    thread: Option<ThreadId>,
+}
+
+impl GraphicsCache {
+    /// The default time graphics resources are kept after their window closes.
+    pub const DEFAULT_GRACE_PERIOD: Duration = Duration::from_secs(2);
+
+    /// How long a new window waits for the previous window to shut down at most.
+    pub const INSTANCE_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(1);
+
+    /// Create a cache that keeps graphics resources for `grace_period` after their window closes.
+    pub fn new(grace_period: Duration) -> Arc<Self> {
+        Arc::new(Self {
+            grace_period,
+            cached: Mutex::new(Cached::default()),
+            instance: Mutex::new(Instance::default()),
+            instance_stopped: Condvar::new(),
+        })
+    }
+
+    /// Drop any cached graphics resources right away.
+    pub fn clear(&self) {
+        let graphics = self.cached.lock().unwrap().graphics.take();
+        drop(graphics);
+    }
+
+    /// Whether graphics resources are currently cached.
+    pub fn is_cached(&self) -> bool {
+        self.cached.lock().unwrap().graphics.is_some()
+    }
+
+    /// Take the cached graphics resources if there are any of the right type.
+    pub(crate) fn take<T: 'static>(&self) -> Option<T> {
+        let graphics = self.cached.lock().unwrap().graphics.take()?;
+
+        graphics.downcast().ok().map(|graphics| *graphics)
+    }
+
+    /// Store the graphics resources of a closed window. They are dropped after the grace period
+    /// unless they're taken by a new window before then.
+    pub(crate) fn release<T: Send + 'static>(self: &Arc<Self>, graphics: T) {
+        if self.grace_period.is_zero() {
+            return;
+        }
+
+        let generation = {
+            let mut cached = self.cached.lock().unwrap();
+            cached.graphics = Some(Box::new(graphics));
+            cached.generation = cached.generation.wrapping_add(1);
+            cached.generation
+        };
+
+        let cache = Arc::downgrade(self);
+        let grace_period = self.grace_period;
+        let _ = thread::Builder::new()
+            .name(String::from("iced-graphics-cache"))
+            .spawn(move || {
+                thread::sleep(grace_period);
+                Self::expire(&cache, generation);
+            });
+    }
+
+    fn expire(cache: &Weak<Self>, generation: u64) {
+        let Some(cache) = cache.upgrade() else {
+            return;
+        };
+
+        let mut cached = cache.cached.lock().unwrap();
+        if cached.generation == generation {
+            let graphics = cached.graphics.take();
+            drop(cached);
+            drop(graphics);
+        }
+    }
+
+    /// Called before a new window is opened. This waits until the previous window's runtime has
+    /// shut down, for at most `timeout`. Returns `false` if it timed out.
+    ///
+    /// If the previous runtime runs on the current thread, then it can't shut down until the
+    /// current thread returns to its event loop, so this doesn't wait for it.
+    pub(crate) fn wait_for_previous_instance(&self, timeout: Duration) -> bool {
+        let deadline = Instant::now() + timeout;
+        let current_thread = thread::current().id();
+
+        let mut instance = self.instance.lock().unwrap();
+        while instance.running && instance.thread != Some(current_thread) {
+            let now = Instant::now();
+            if now >= deadline {
+                return false;
+            }
+
+            instance = self
+                .instance_stopped
+                .wait_timeout(instance, deadline - now)
+                .unwrap()
+                .0;
+        }
+
+        instance.running = true;
+        instance.thread = None;
+        true
+    }
+
+    /// Called when a window's runtime starts on the current thread. The returned guard marks the
+    /// runtime as stopped when it's dropped.
+    pub(crate) fn instance_started(self: &Arc<Self>) -> InstanceGuard {
+        let mut instance = self.instance.lock().unwrap();
+        instance.running = true;
+        instance.thread = Some(thread::current().id());
+
+        InstanceGuard(self.clone())
+    }
+}
+
+/// Marks a window's runtime as stopped when dropped, see [`GraphicsCache::instance_started()`].
+#[derive(Debug)]
+pub(crate) struct InstanceGuard(Arc<GraphicsCache>);
+
+impl InstanceGuard {
+    pub fn cache(&self) -> &Arc<GraphicsCache> {
+        &self.0
+    }
+}
+
+impl Drop for InstanceGuard {
+    fn drop(&mut self) {
+        let mut instance = self.0.instance.lock().unwrap();
+        instance.running = false;
+        instance.thread = None;
+        self.0.instance_stopped.notify_all();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn graphics_are_reused_within_grace_period() {
+        let cache = GraphicsCache::new(Duration::from_millis(50));
+
+        cache.release(String::from("compositor"));
+        assert!(cache.is_cached());
+        // Cached graphics of the wrong type are discarded
+        assert_eq!(cache.take::<u32>(), None);
+        assert!(!cache.is_cached());
+
+        cache.release(String::from("compositor"));
+        assert_eq!(cache.take::<String>().as_deref(), Some("compositor"));
+        assert_eq!(cache.take::<String>(), None);
+
+        // Graphics that weren't reused in time are dropped
+        cache.release(String::from("compositor"));
+        thread::sleep(Duration::from_millis(200));
+        assert!(!cache.is_cached());
+
+        // A grace period of zero disables the cache
+        let cache = GraphicsCache::new(Duration::ZERO);
+        cache.release(String::from("compositor"));
+        assert!(!cache.is_cached());
+    }
+
+    #[test]
+    fn new_instances_wait_for_previous_instance() {
+        let cache = GraphicsCache::new(GraphicsCache::DEFAULT_GRACE_PERIOD);
+        assert!(cache.wait_for_previous_instance(Duration::ZERO));
+
+        // The previous instance runs on another thread and stops after a short while
+        let (started_tx, started_rx) = std::sync::mpsc::channel();
+        let handle = thread::spawn({
+            let cache = cache.clone();
+            move || {
+                let guard = cache.instance_started();
+                started_tx.send(()).unwrap();
+                thread::sleep(Duration::from_millis(50));
+                drop(guard);
+            }
+        });
+        started_rx.recv().unwrap();
+
+        let start = Instant::now();
+        assert!(cache.wait_for_previous_instance(Duration::from_secs(5)));
+        assert!(start.elapsed() >= Duration::from_millis(20));
+        handle.join().unwrap();
+
+        // An instance on the current thread can't stop while we're waiting for it
+        let guard = cache.instance_started();
+        assert!(cache.wait_for_previous_instance(Duration::from_secs(5)));
+        drop(guard);
+
+        // Waiting for an instance that doesn't stop times out
+        let _guard = thread::spawn({
+            let cache = cache.clone();
+            move || std::mem::forget(cache.instance_started())
+        })
+        .join();
+        assert!(!cache.wait_for_previous_instance(Duration::from_millis(10)));
+    }
+}
@@ -0,0 +1,196 @@
+//! Find out which widget the cursor is over, for coordinated effects like highlighting all controls
+//! that are linked to the hovered one.
+//!
+//! Only widgets with an [`Id`] take part. These are the widgets that report their id to
+//! [`Operation`]s, like a [`Container`][crate::widget::Container] or
+//! [`Scrollable`][crate::widget::Scrollable] with an `.id()`. Wrap a widget in a container with
+//! an id to make it hoverable, or report the id from a custom widget's `operate()` implementation
+//! with [`Operation::container()`]. When widgets with ids are nested, the innermost one the cursor
+//! is over is the hovered widget. Between overlapping siblings, the one that comes last in the view
+//! wins, since that's the one drawn on top. Widgets scrolled out of a scrollable's view are never
+//! hovered.
+//!
+//! The runtime looks for the hovered widget after handling input, but only while the application
+//! sets [`WindowSubs::on_hover_changed`][crate::window::WindowSubs::on_hover_changed], since that
+//! means visiting every widget. When the hovered widget changes, the application receives the
+//! callback's message, and [`hovered()`] returns the new widget. While the callback isn't set,
+//! [`hovered()`] returns `None`. Bounds are in logical coordinates relative to the window,
+//! the same coordinates the widgets are laid out in, with scrolling already applied.
+
+use std::cell::RefCell;
+
+use crate::core::widget::{operation, Id, Operation};
+use crate::core::{Point, Rectangle, Vector};
+
+/// A widget the cursor is over.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HoveredWidget {
+    /// The widget's id.
+    pub id: Id,
+    /// Where the widget is shown in the window, in logical coordinates.
+    pub bounds: Rectangle,
+}
+
+thread_local! {
+    static HOVERED: RefCell<Option<HoveredWidget>> = const { RefCell::new(None) };
+}
+
+/// The widget the cursor was over when the window whose messages are currently being handled last
+/// handled input. Outside of the runtime this returns the last widget hovered in any window on
+/// this thread.
+pub fn hovered() -> Option<HoveredWidget> {
+    HOVERED.with(|hovered| hovered.borrow().clone())
+}
+
+/// Set the widget returned by [`hovered()`]. The runtime does this before every update.
+pub(crate) fn set_hovered(widget: Option<HoveredWidget>) {
+    HOVERED.with(|hovered| *hovered.borrow_mut() = widget);
+}
+
+/// An [`Operation`] that finds the innermost widget with an id under the cursor.
+#[derive(Debug)]
+pub(crate) struct FindHovered {
+    cursor: Point,
+    hovered: Option<HoveredWidget>,
+    /// The scroll offset of the widgets being visited. Subtracting this from their bounds gives
+    /// their position in the window.
+    offset: Vector,
+    /// The visible part of the scrollables the widgets being visited are in.
+    clip: Option<Rectangle>,
+    /// The translation and visible bounds of the last scrollable, applied to its children.
+    pending_scroll: Option<(Vector, Rectangle)>,
+}
+
+impl FindHovered {
+    pub fn new(cursor: Point) -> Self {
+        Self {
+            cursor,
+            hovered: None,
+            offset: Vector::ZERO,
+            clip: None,
+            pending_scroll: None,
+        }
+    }
+
+    /// The hovered widget, once the operation has visited every widget.
+    pub fn into_hovered(self) -> Option<HoveredWidget> {
+        self.hovered
+    }
+
+    fn visit(&mut self, id: Option<&Id>, bounds: Rectangle) {
+        let Some(id) = id else {
+            return;
+        };
+
+        let bounds = bounds - self.offset;
+        let is_visible = self.clip.is_none_or(|clip| clip.contains(self.cursor));
+        if is_visible && bounds.contains(self.cursor) {
+            self.hovered = Some(HoveredWidget {
+                id: id.clone(),
+                bounds,
+            });
+        }
+    }
+}
+
+impl Operation for FindHovered {
+    fn traverse(&mut self, operate: &mut dyn FnMut(&mut dyn Operation)) {
+        let (offset, clip) = (self.offset, self.clip);
+        if let Some((translation, bounds)) = self.pending_scroll.take() {
+            self.clip = Some(match clip {
+                Some(clip) => clip.intersection(&bounds).unwrap_or_default(),
+                None => bounds,
+            });
+            self.offset = offset + translation;
+        }
+
+        operate(self);
+
+        self.offset = offset;
+        self.clip = clip;
+        self.pending_scroll = None;
+    }
+
+    fn container(&mut self, id: Option<&Id>, bounds: Rectangle) {
+        self.visit(id, bounds);
+    }
+
+    fn scrollable(
+        &mut self,
+        id: Option<&Id>,
+        bounds: Rectangle,
+        _content_bounds: Rectangle,
+        translation: Vector,
+        _state: &mut dyn operation::Scrollable,
+    ) {
+        self.visit(id, bounds);
+        self.pending_scroll = Some((translation, bounds - self.offset));
+    }
+
+    fn focusable(
+        &mut self,
+        id: Option<&Id>,
+        bounds: Rectangle,
+        _state: &mut dyn operation::Focusable,
+    ) {
+        self.visit(id, bounds);
+    }
+
+    fn text_input(
+        &mut self,
+        id: Option<&Id>,
+        bounds: Rectangle,
+        _state: &mut dyn operation::TextInput,
+    ) {
+        self.visit(id, bounds);
+    }
+
+    fn text(&mut self, id: Option<&Id>, bounds: Rectangle, _text: &str) {
+        self.visit(id, bounds);
+    }
+
+    fn custom(&mut self, id: Option<&Id>, bounds: Rectangle, _state: &mut dyn std::any::Any) {
+        self.visit(id, bounds);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Element, Length, Size, Theme};
+    use crate::runtime::user_interface::{Cache, UserInterface};
+    use crate::widget::{column, container, Space};
+
+    fn hovered_at(cursor: Point) -> Option<Id> {
+        let fixed = |size: f32| {
+            Space::new()
+                .width(Length::Fixed(size))
+                .height(Length::Fixed(size))
+        };
+        let view: Element<'_, (), Theme, ()> = column![
+            fixed(10.0),
+            container(container(fixed(20.0)).id("knob"))
+                .id("panel")
+                .padding(10)
+                .width(Length::Fill),
+            fixed(10.0),
+        ]
+        .into();
+
+        let mut user_interface =
+            UserInterface::build(view, Size::new(100.0, 100.0), Cache::default(), &mut ());
+        let mut operation = FindHovered::new(cursor);
+        user_interface.operate(&(), &mut operation);
+
+        operation.into_hovered().map(|hovered| hovered.id)
+    }
+
+    #[test]
+    fn finds_the_innermost_hovered_widget() {
+        // The knob sits 10 pixels into the panel, which starts 10 pixels down
+        assert_eq!(hovered_at(Point::new(25.0, 25.0)), Some(Id::new("knob")));
+        assert_eq!(hovered_at(Point::new(5.0, 25.0)), Some(Id::new("panel")));
+        assert_eq!(hovered_at(Point::new(80.0, 45.0)), Some(Id::new("panel")));
+        assert_eq!(hovered_at(Point::new(25.0, 5.0)), None);
+    }
+}
@@ -0,0 +1,466 @@
+//! Interaction timings and thresholds shared by all widgets.
+//!
+//! The runtime makes the [`InteractionSettings`] from
+//! [`IcedBaseviewSettings::interaction`][crate::settings::IcedBaseviewSettings::interaction], or
+//! from [`Application::interaction_settings()`][crate::Application::interaction_settings()] if it
+//! returns a value, available to widgets through [`current()`] before handling any events or
+//! building the view. Plugins can use this to, for instance, make double clicks easier to perform
+//! for users who need more time between the two clicks.
+//!
+//! The same way, [`is_input_locked()`] tells widgets whether the editor is currently read-only,
+//! see [`Application::is_input_locked()`][crate::Application::is_input_locked()]. Parameter
+//! widgets keep showing live values while locked, but they don't start new gestures, and they end
+//! the active gesture as soon as the editor gets locked.
+//!
+//! Keyboard modifiers reach widgets as
+//! [`ModifiersChanged`][crate::keyboard::Event::ModifiersChanged] events. The runtime also sends
+//! those when a mouse event shows that the modifiers changed while the window didn't receive
+//! keyboard input, for instance because Shift was pressed before the cursor entered the window.
+//! A widget that's created after a modifier was pressed hasn't seen that event, so widgets that
+//! track the modifiers themselves should start from [`modifiers()`].
+//!
+//! Pens and styluses are reported by baseview as regular mouse input. Baseview doesn't expose the
+//! pressure or tilt of a pen on any platform, so widgets only see whether the pen touches the
+//! tablet, as a left mouse button press. Widgets that would use pressure for velocity or intensity
+//! need another source for it, like the [`PianoKeyboard`][crate::widgets::PianoKeyboard] does by
+//! using the position of the click on a key.
+
+use std::cell::Cell;
+use std::time::{Duration, Instant};
+
+use crate::core::keyboard::Modifiers;
+use crate::core::mouse::ScrollDelta;
+use crate::core::Point;
+
+/// Timings and thresholds used by widgets to interpret mouse and touch input.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct InteractionSettings {
+    /// The maximum time between two clicks for them to count as a double click.
+    pub double_click_interval: Duration,
+    /// The maximum distance in logical pixels between two clicks for them to count as a double
+    /// click.
+    pub double_click_distance: f32,
+    /// How long a mouse button or finger needs to be held down without moving for it to count as a
+    /// long press.
+    pub long_press_duration: Duration,
+    /// How far in logical pixels the cursor needs to move while a button is held before a drag
+    /// starts. Zero means that drags start immediately.
+    pub drag_threshold: f32,
+    /// The number of rows a single line of mouse wheel scrolling moves in lists, like the
+    /// [`Dropdown`][crate::widgets::Dropdown]'s menu. Parameter widgets use
+    /// [`wheel`][Self::wheel] instead.
+    pub scroll_step: f32,
+    /// How the mouse wheel changes parameter values.
+    pub wheel: WheelSettings,
+    /// When parameter widgets change their value in response to the mouse wheel. Widgets can
+    /// override this individually.
+    pub scroll_to_adjust: ScrollToAdjust,
+    /// The modifiers that make parameter drags, scrolling, and keyboard nudges more granular.
+    /// Holding any one of them is enough, so several hosts' conventions can be supported at the
+    /// same time. This defaults to Shift, and on macOS also to Cmd. See
+    /// [`param_drag`][crate::widgets::param_drag] for how this interacts with Ctrl/Cmd+clicking to
+    /// reset a parameter. Alt+clicking always opens the text entry.
+    pub fine_adjust: Modifiers,
+}
+
+/// How the mouse wheel changes parameter values, see [`InteractionSettings::wheel`].
+///
+/// A step is the parameter's own step for stepped parameters, and a small fraction of the range
+/// for continuous parameters. Stepped parameters only move once the scrolling adds up to a whole
+/// step, which is tracked per widget with a [`WheelAccumulator`]. That way a slowly turned high
+/// resolution wheel still moves the value eventually.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WheelSettings {
+    /// Reverse the direction scrolling changes values in. Normally scrolling up or to the right
+    /// increases a value. With natural scrolling on macOS the system already reverses the
+    /// direction of the scroll events to make content follow the fingers, which makes value
+    /// adjustments feel backwards to some users. Baseview doesn't report whether that happened, so
+    /// this can't be detected and it's disabled by default on every platform. Plugins can offer it
+    /// as an option.
+    pub invert: bool,
+    /// How many lines of scrolling from a regular mouse wheel make up a step. High resolution
+    /// wheels that report several lines per notch can raise this so one notch doesn't change the
+    /// value as much.
+    pub lines_per_step: f32,
+    /// How many logical pixels of smooth scrolling, like from a touchpad, make up a step.
+    pub pixels_per_step: f32,
+}
+
+impl Default for WheelSettings {
+    fn default() -> Self {
+        Self {
+            invert: false,
+            lines_per_step: 1.0,
+            pixels_per_step: 20.0,
+        }
+    }
+}
+
+impl WheelSettings {
+    /// The number of steps a scroll event moves a value by, possibly fractional. Scrolling up or
+    /// to the right is positive unless [`invert`][Self::invert] is set.
+    pub fn steps(&self, delta: ScrollDelta) -> f32 {
+        let steps = match delta {
+            ScrollDelta::Lines { x, y } => (x + y) / self.lines_per_step,
+            ScrollDelta::Pixels { x, y } => (x + y) / self.pixels_per_step,
+        };
+        if !steps.is_finite() {
+            return 0.0;
+        }
+
+        if self.invert {
+            -steps
+        } else {
+            steps
+        }
+    }
+}
+
+/// Adds up fractional scroll amounts into whole steps. Store this in the widget's state so every
+/// widget keeps its own remainder.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct WheelAccumulator {
+    /// The part of a step that was scrolled but not applied yet.
+    pending: f32,
+}
+
+impl WheelAccumulator {
+    /// Add `amount` steps, and return the whole steps that should be applied now. The remainder is
+    /// kept for the next event. Changing the scroll direction discards the remainder, so scrolling
+    /// back doesn't first need to undo the partial step.
+    pub fn whole_steps(&mut self, amount: f32) -> i32 {
+        if !amount.is_finite() {
+            return 0;
+        }
+        if amount * self.pending < 0.0 {
+            self.pending = 0.0;
+        }
+
+        self.pending += amount;
+        let whole = self.pending.trunc();
+        self.pending -= whole;
+
+        whole as i32
+    }
+
+    /// Discard the remainder.
+    pub fn reset(&mut self) {
+        self.pending = 0.0;
+    }
+}
+
+/// When parameter widgets change their value in response to the mouse wheel.
+///
+/// Scrolling over a widget to change its value is convenient, but inside of a
+/// [`Scrollable`][crate::widget::Scrollable] it's easy to change a parameter by accident when the
+/// cursor passes over a widget while scrolling the panel. [`ScrollToAdjust::Guarded`] avoids that,
+/// at the cost of needing an extra click or a modifier before the wheel can be used to adjust a
+/// value.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ScrollToAdjust {
+    /// Scrolling over a widget changes its value. This is the default.
+    #[default]
+    Hover,
+    /// Scrolling only changes a widget's value after the widget has been clicked, until something
+    /// else is clicked, or while Ctrl/Cmd is held down. Other scroll events are left alone so a
+    /// surrounding scrollable can handle them.
+    Guarded,
+}
+
+impl InteractionSettings {
+    /// The default double click interval, matching iced's own.
+    pub const DEFAULT_DOUBLE_CLICK_INTERVAL: Duration = Duration::from_millis(300);
+    /// The default double click distance, matching iced's own.
+    pub const DEFAULT_DOUBLE_CLICK_DISTANCE: f32 = 6.0;
+    /// The default long press duration.
+    pub const DEFAULT_LONG_PRESS_DURATION: Duration = Duration::from_millis(500);
+    /// The default fine adjustment modifiers for the current platform.
+    pub const DEFAULT_FINE_ADJUST: Modifiers = if cfg!(target_os = "macos") {
+        Modifiers::SHIFT.union(Modifiers::LOGO)
+    } else {
+        Modifiers::SHIFT
+    };
+
+    /// Whether holding `modifiers` makes parameter adjustments more granular, see
+    /// [`fine_adjust`][Self::fine_adjust].
+    pub fn is_fine_adjust(&self, modifiers: Modifiers) -> bool {
+        modifiers.intersects(self.fine_adjust)
+    }
+
+    /// How far in logical pixels the cursor can move while a button is held for the press to
+    /// still count as a click. This is the drag threshold, or the double click distance if there
+    /// is no drag threshold.
+    pub fn click_tolerance(&self) -> f32 {
+        if self.drag_threshold > 0.0 {
+            self.drag_threshold
+        } else {
+            self.double_click_distance
+        }
+    }
+}
+
+impl Default for InteractionSettings {
+    fn default() -> Self {
+        Self {
+            double_click_interval: Self::DEFAULT_DOUBLE_CLICK_INTERVAL,
+            double_click_distance: Self::DEFAULT_DOUBLE_CLICK_DISTANCE,
+            long_press_duration: Self::DEFAULT_LONG_PRESS_DURATION,
+            drag_threshold: 0.0,
+            scroll_step: 1.0,
+            wheel: WheelSettings::default(),
+            scroll_to_adjust: ScrollToAdjust::Hover,
+            fine_adjust: Self::DEFAULT_FINE_ADJUST,
+        }
+    }
+}
+
+thread_local! {
+    static CURRENT: Cell<InteractionSettings> = Cell::new(InteractionSettings::default());
+    static INPUT_LOCKED: Cell<bool> = const { Cell::new(false) };
+    static MODIFIERS: Cell<Modifiers> = const { Cell::new(Modifiers::empty()) };
+}
+
+/// The [`InteractionSettings`] for the window whose events are currently being handled. Outside of
+/// the runtime this returns the default settings.
+pub fn current() -> InteractionSettings {
+    CURRENT.with(Cell::get)
+}
+
+/// Set the settings returned by [`current()`]. Multiple editors can share a GUI thread, so the
+/// runtime does this before it handles every event.
+pub(crate) fn set_current(settings: InteractionSettings) {
+    CURRENT.with(|current| current.set(settings));
+}
+
+/// Whether the parameter widgets of the window whose events are currently being handled should
+/// ignore input. Outside of the runtime this returns `false`. See the [module
+/// documentation][self].
+pub fn is_input_locked() -> bool {
+    INPUT_LOCKED.with(Cell::get)
+}
+
+/// Set the value returned by [`is_input_locked()`]. Like with [`set_current()`], the runtime does
+/// this before it handles every event.
+pub(crate) fn set_input_locked(locked: bool) {
+    INPUT_LOCKED.with(|input_locked| input_locked.set(locked));
+}
+
+/// The keyboard modifiers that were held after the widgets of the window whose events are
+/// currently being handled last handled input. Changes since then are delivered as events, so a
+/// widget that starts from this value and then follows the
+/// [`ModifiersChanged`][crate::keyboard::Event::ModifiersChanged] events always knows which
+/// modifiers are held. Outside of the runtime this returns no modifiers. See the [module
+/// documentation][self].
+pub fn modifiers() -> Modifiers {
+    MODIFIERS.with(Cell::get)
+}
+
+/// Set the value returned by [`modifiers()`]. Like with [`set_current()`], the runtime does this
+/// before it handles every event, and after the widgets have handled input.
+pub(crate) fn set_modifiers(modifiers: Modifiers) {
+    MODIFIERS.with(|current| current.set(modifiers));
+}
+
+/// The kind of a [`Click`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClickKind {
+    Single,
+    Double,
+    Triple,
+}
+
+/// A mouse click, used to detect double and triple clicks. This works like iced's
+/// [`mouse::Click`][crate::core::mouse::Click], but it uses the [`InteractionSettings`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Click {
+    kind: ClickKind,
+    position: Point,
+    time: Instant,
+}
+
+impl Click {
+    /// Create a new click at the current time using the [`current()`] settings.
+    pub fn new(position: Point, previous: Option<Click>) -> Self {
+        Self::at(position, Instant::now(), previous, &current())
+    }
+
+    /// Create a new click at a specific time.
+    pub fn at(
+        position: Point,
+        time: Instant,
+        previous: Option<Click>,
+        settings: &InteractionSettings,
+    ) -> Self {
+        let kind = match previous {
+            Some(previous) if previous.is_consecutive(position, time, settings) => {
+                match previous.kind {
+                    ClickKind::Single => ClickKind::Double,
+                    ClickKind::Double => ClickKind::Triple,
+                    ClickKind::Triple => ClickKind::Double,
+                }
+            }
+            _ => ClickKind::Single,
+        };
+
+        Self {
+            kind,
+            position,
+            time,
+        }
+    }
+
+    pub fn kind(&self) -> ClickKind {
+        self.kind
+    }
+
+    pub fn position(&self) -> Point {
+        self.position
+    }
+
+    fn is_consecutive(
+        &self,
+        position: Point,
+        time: Instant,
+        settings: &InteractionSettings,
+    ) -> bool {
+        time > self.time
+            && time - self.time <= settings.double_click_interval
+            && self.position.distance(position) < settings.double_click_distance
+    }
+}
+
+/// Tracks a held mouse button or finger to detect long presses.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LongPress {
+    position: Point,
+    time: Instant,
+    /// Set once the cursor has moved further than the drag threshold.
+    moved: bool,
+}
+
+impl LongPress {
+    /// Start tracking a press at the current time.
+    pub fn new(position: Point) -> Self {
+        Self::at(position, Instant::now())
+    }
+
+    /// Start tracking a press at a specific time.
+    pub fn at(position: Point, time: Instant) -> Self {
+        Self {
+            position,
+            time,
+            moved: false,
+        }
+    }
+
+    /// Update the cursor position. A press that moved further than the drag threshold, or further
+    /// than the double click distance if there is no drag threshold, is no longer a long press.
+    pub fn cursor_moved(&mut self, position: Point, settings: &InteractionSettings) {
+        self.moved |= self.position.distance(position) >= settings.click_tolerance();
+    }
+
+    /// Whether the press counts as a long press at the current time using the [`current()`]
+    /// settings.
+    pub fn is_long_press(&self) -> bool {
+        self.is_long_press_at(Instant::now(), &current())
+    }
+
+    /// Whether the press counts as a long press at a specific time.
+    pub fn is_long_press_at(&self, time: Instant, settings: &InteractionSettings) -> bool {
+        !self.moved && time.saturating_duration_since(self.time) >= settings.long_press_duration
+    }
+}
+
+/// Whether the cursor moved far enough from `start` for a drag to start.
+pub fn exceeds_drag_threshold(
+    start: Point,
+    position: Point,
+    settings: &InteractionSettings,
+) -> bool {
+    start.distance(position) >= settings.drag_threshold
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn double_click_interval() {
+        let start = Instant::now();
+        let position = Point::new(10.0, 10.0);
+        let slow = InteractionSettings {
+            double_click_interval: Duration::from_millis(800),
+            ..Default::default()
+        };
+
+        let first = Click::at(position, start, None, &slow);
+        assert_eq!(first.kind(), ClickKind::Single);
+
+        // 600 ms is too slow with the default settings, but it's a double click with the slower
+        // setting
+        let later = start + Duration::from_millis(600);
+        let default_second = Click::at(position, later, Some(first), &Default::default());
+        assert_eq!(default_second.kind(), ClickKind::Single);
+        let slow_second = Click::at(position, later, Some(first), &slow);
+        assert_eq!(slow_second.kind(), ClickKind::Double);
+
+        // The defaults match iced's own double click detection
+        let fast = start + Duration::from_millis(250);
+        let default_second = Click::at(position, fast, Some(first), &Default::default());
+        assert_eq!(default_second.kind(), ClickKind::Double);
+        let too_far = Click::at(
+            Point::new(16.0, 10.0),
+            fast,
+            Some(first),
+            &Default::default(),
+        );
+        assert_eq!(too_far.kind(), ClickKind::Single);
+    }
+
+    #[test]
+    fn long_press_duration() {
+        let start = Instant::now();
+        let position = Point::new(10.0, 10.0);
+        let slow = InteractionSettings {
+            long_press_duration: Duration::from_millis(1000),
+            ..Default::default()
+        };
+
+        let press = LongPress::at(position, start);
+        let later = start + Duration::from_millis(700);
+        assert!(press.is_long_press_at(later, &Default::default()));
+        assert!(!press.is_long_press_at(later, &slow));
+        assert!(press.is_long_press_at(start + Duration::from_millis(1000), &slow));
+
+        // Moving the cursor cancels the long press
+        let mut press = LongPress::at(position, start);
+        press.cursor_moved(Point::new(12.0, 10.0), &Default::default());
+        assert!(press.is_long_press_at(later, &Default::default()));
+        press.cursor_moved(Point::new(20.0, 10.0), &Default::default());
+        assert!(!press.is_long_press_at(later, &Default::default()));
+    }
+
+    #[test]
+    fn thread_local_settings() {
+        assert_eq!(current(), InteractionSettings::default());
+
+        let settings = InteractionSettings {
+            drag_threshold: 4.0,
+            ..Default::default()
+        };
+        set_current(settings);
+        assert_eq!(current(), settings);
+        assert!(!exceeds_drag_threshold(
+            Point::ORIGIN,
+            Point::new(3.0, 0.0),
+            &current()
+        ));
+        assert!(exceeds_drag_threshold(
+            Point::ORIGIN,
+            Point::new(4.0, 0.0),
+            &current()
+        ));
+
+        set_current(InteractionSettings::default());
+    }
+}
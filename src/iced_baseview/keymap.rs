@@ -0,0 +1,517 @@
+//! Mapping computer keyboard keys to messages.
+//!
+//! A [`Keymap`] translates key presses and releases into the application's messages before any
+//! widget sees them. This can be used for global shortcuts like Ctrl+Z, or for playing notes with
+//! the computer keyboard. Applications resolve keys through
+//! [`Application::resolve_key()`][crate::Application::resolve_key()], which usually forwards to
+//! [`Keymap::resolve()`].
+//!
+//! The keymap is consulted with these rules:
+//!
+//! - While a text field is focused, the keymap is skipped so typing always reaches the field. This
+//!   includes the value entry fields of the parameter widgets.
+//! - Bindings are checked in the order they were added, and the first match wins.
+//! - A key press only matches a binding if exactly the binding's modifiers are held, unless the
+//!   binding was created with [`Binding::any_modifiers()`]. A key release always matches the
+//!   binding its press matched, even if the modifiers changed while the key was held, and the
+//!   release of a key whose press didn't match anything doesn't match either.
+//! - Matched events are consumed by default. They are not passed on to the widgets, and the host is
+//!   told that the key was handled so it doesn't also act on it. Use [`Binding::pass_through()`] to
+//!   send the message and still let the widgets handle the event.
+//!
+//! Keys ignored because of
+//! [`IcedBaseviewSettings::ignore_non_modifier_keys`][crate::settings::IcedBaseviewSettings::ignore_non_modifier_keys]
+//! never reach the keymap.
+//!
+//! ```ignore
+//! let keymap = Keymap::new()
+//!     .bind(Binding::key(Key::Character("z".into())).modifiers(Modifiers::COMMAND).on_press(Message::Undo))
+//!     .bind(Binding::physical(Code::KeyA).on_press(Message::NoteOn(60)).on_release(Message::NoteOff(60)));
+//! ```
+
+use std::cell::RefCell;
+
+use crate::core::keyboard::{self, key, Key, Modifiers};
+use crate::core::widget::operation::{Focusable, Operation, TextInput};
+use crate::core::widget::Id;
+use crate::core::{Event, Rectangle};
+
+/// Creates a message for a [`Binding`].
+type MessageFn<Message> = Box<dyn Fn() -> Message + Send>;
+
+/// A list of key [`Binding`]s. See the [module documentation][self].
+pub struct Keymap<Message> {
+    bindings: Vec<Binding<Message>>,
+    /// The physical keys whose presses matched a binding, and the indices of those bindings. The
+    /// key's release matches the same binding.
+    held_keys: RefCell<Vec<(key::Physical, usize)>>,
+}
+
+impl<Message> Default for Keymap<Message> {
+    fn default() -> Self {
+        Self {
+            bindings: Vec::new(),
+            held_keys: RefCell::new(Vec::new()),
+        }
+    }
+}
+
+impl<Message> Keymap<Message> {
+    /// Create an empty keymap.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a binding. Bindings added earlier take precedence over later ones.
+    pub fn bind(mut self, binding: Binding<Message>) -> Self {
+        self.bindings.push(binding);
+        self
+    }
+
+    /// Resolve a keyboard event. Returns `None` if no binding matches the event.
+    pub fn resolve(&self, event: &keyboard::Event) -> Option<Resolution<Message>> {
+        let mut held_keys = self.held_keys.borrow_mut();
+
+        let (index, is_press) = match event {
+            keyboard::Event::KeyPressed {
+                key,
+                physical_key,
+                modifiers,
+                ..
+            } => {
+                let index = self.bindings.iter().position(|binding| {
+                    let modifiers_match = binding
+                        .modifiers
+                        .is_none_or(|expected| expected == *modifiers);
+
+                    modifiers_match && binding.key.matches(key, physical_key)
+                });
+
+                // Key repeats replace the previous press
+                held_keys.retain(|(held_key, _)| held_key != physical_key);
+                if let Some(index) = index {
+                    held_keys.push((*physical_key, index));
+                }
+
+                (index?, true)
+            }
+            keyboard::Event::KeyReleased { physical_key, .. } => {
+                let position = held_keys
+                    .iter()
+                    .position(|(held_key, _)| held_key == physical_key)?;
+
+                (held_keys.remove(position).1, false)
+            }
+            keyboard::Event::ModifiersChanged(_) => return None,
+        };
+
+        let binding = &self.bindings[index];
+        let message = if is_press {
+            binding.on_press.as_ref()
+        } else {
+            binding.on_release.as_ref()
+        };
+
+        Some(Resolution {
+            message: message.map(|message| message()),
+            consumed: binding.consume,
+        })
+    }
+}
+
+/// The key a [`Binding`] reacts to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BoundKey {
+    /// A key as it's labeled in the user's keyboard layout. Characters are matched case
+    /// insensitively.
+    Logical(Key),
+    /// A key at a physical position on the keyboard, regardless of the keyboard layout. Use this
+    /// for playing notes with the computer keyboard.
+    Physical(key::Code),
+}
+
+impl BoundKey {
+    fn matches(&self, key: &Key, physical_key: &key::Physical) -> bool {
+        match (self, key) {
+            (BoundKey::Logical(Key::Character(expected)), Key::Character(actual)) => {
+                expected.to_lowercase() == actual.to_lowercase()
+            }
+            (BoundKey::Logical(expected), actual) => expected == actual,
+            (BoundKey::Physical(expected), _) => {
+                matches!(physical_key, key::Physical::Code(actual) if actual == expected)
+            }
+        }
+    }
+}
+
+/// A single key binding in a [`Keymap`].
+pub struct Binding<Message> {
+    key: BoundKey,
+    /// The modifiers that need to be held while the key is pressed, or `None` to match any
+    /// modifiers.
+    modifiers: Option<Modifiers>,
+    on_press: Option<MessageFn<Message>>,
+    on_release: Option<MessageFn<Message>>,
+    consume: bool,
+}
+
+impl<Message> Binding<Message> {
+    /// Bind a key as it's labeled in the user's keyboard layout.
+    pub fn key(key: Key) -> Self {
+        Self::new(BoundKey::Logical(key))
+    }
+
+    /// Bind a key at a physical position on the keyboard.
+    pub fn physical(code: key::Code) -> Self {
+        Self::new(BoundKey::Physical(code))
+    }
+
+    fn new(key: BoundKey) -> Self {
+        Self {
+            key,
+            modifiers: Some(Modifiers::empty()),
+            on_press: None,
+            on_release: None,
+            consume: true,
+        }
+    }
+
+    /// Only match key presses while exactly these modifiers are held. By default no modifiers may
+    /// be held.
+    pub fn modifiers(mut self, modifiers: Modifiers) -> Self {
+        self.modifiers = Some(modifiers);
+        self
+    }
+
+    /// Match key presses regardless of which modifiers are held.
+    pub fn any_modifiers(mut self) -> Self {
+        self.modifiers = None;
+        self
+    }
+
+    /// Send this message when the key is pressed.
+    pub fn on_press(mut self, message: Message) -> Self
+    where
+        Message: Clone + Send + 'static,
+    {
+        self.on_press = Some(Box::new(move || message.clone()));
+        self
+    }
+
+    /// Send this message when the key is released.
+    pub fn on_release(mut self, message: Message) -> Self
+    where
+        Message: Clone + Send + 'static,
+    {
+        self.on_release = Some(Box::new(move || message.clone()));
+        self
+    }
+
+    /// Let the widgets and the host handle the event as well.
+    pub fn pass_through(mut self) -> Self {
+        self.consume = false;
+        self
+    }
+}
+
+/// The result of resolving a keyboard event with a [`Keymap`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Resolution<Message> {
+    /// The message to send, if the binding has one for this kind of event.
+    pub message: Option<Message>,
+    /// Whether the event should be kept away from the widgets and reported to the host as handled.
+    pub consumed: bool,
+}
+
+impl<Message> Resolution<Message> {
+    /// Convert the resolution's message.
+    pub fn map<B>(self, f: impl FnOnce(Message) -> B) -> Resolution<B> {
+        Resolution {
+            message: self.message.map(f),
+            consumed: self.consumed,
+        }
+    }
+}
+
+/// Resolve the keyboard events in `events` starting at index `first`, pushing their messages to
+/// `messages` and removing the consumed events. `text_input_focused` is only called if there are
+/// any key presses or releases. Returns whether any events were consumed.
+pub(crate) fn apply<Message>(
+    resolve: impl Fn(&keyboard::Event) -> Option<Resolution<Message>>,
+    text_input_focused: impl FnOnce() -> bool,
+    events: &mut Vec<Event>,
+    first: usize,
+    messages: &mut Vec<Message>,
+) -> bool {
+    let has_keys = events[first..].iter().any(|event| {
+        matches!(
+            event,
+            Event::Keyboard(
+                keyboard::Event::KeyPressed { .. } | keyboard::Event::KeyReleased { .. }
+            )
+        )
+    });
+    if !has_keys || text_input_focused() {
+        return false;
+    }
+
+    let mut resolutions = Vec::new();
+    for (index, event) in events.iter().enumerate().skip(first) {
+        if let Event::Keyboard(event) = event {
+            if let Some(resolution) = resolve(event) {
+                resolutions.push((index, resolution));
+            }
+        }
+    }
+
+    let mut consumed = Vec::new();
+    for (index, resolution) in resolutions {
+        if resolution.consumed {
+            consumed.push(index);
+        }
+
+        messages.extend(resolution.message);
+    }
+
+    // Removing the events from back to front keeps the other indices valid
+    for &index in consumed.iter().rev() {
+        let _ = events.remove(index);
+    }
+
+    !consumed.is_empty()
+}
+
+/// An [`Operation`] that checks whether a text field is focused. Text fields report themselves as
+/// both a text input and a focusable widget with the same bounds.
+#[derive(Debug, Default)]
+pub(crate) struct FocusedTextInput {
+    /// The bounds of the last text input, until the matching focusable is reported.
+    text_input_bounds: Option<Rectangle>,
+    pub is_focused: bool,
+}
+
+impl Operation for FocusedTextInput {
+    fn traverse(&mut self, operate: &mut dyn FnMut(&mut dyn Operation)) {
+        operate(self);
+    }
+
+    fn text_input(&mut self, _id: Option<&Id>, bounds: Rectangle, _state: &mut dyn TextInput) {
+        self.text_input_bounds = Some(bounds);
+    }
+
+    fn focusable(&mut self, _id: Option<&Id>, bounds: Rectangle, state: &mut dyn Focusable) {
+        let is_text_input = self.text_input_bounds.take() == Some(bounds);
+        self.is_focused |= is_text_input && state.is_focused();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::keyboard::key::{Code, Named, Physical};
+    use crate::core::keyboard::Location;
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    enum Message {
+        Undo,
+        Redo,
+        Play,
+        NoteOn(u8),
+        NoteOff(u8),
+    }
+
+    fn pressed(key: Key, code: Code, modifiers: Modifiers) -> keyboard::Event {
+        keyboard::Event::KeyPressed {
+            key: key.clone(),
+            modified_key: key,
+            physical_key: Physical::Code(code),
+            location: Location::Standard,
+            modifiers,
+            text: None,
+        }
+    }
+
+    fn released(key: Key, code: Code, modifiers: Modifiers) -> keyboard::Event {
+        keyboard::Event::KeyReleased {
+            key: key.clone(),
+            modified_key: key,
+            physical_key: Physical::Code(code),
+            location: Location::Standard,
+            modifiers,
+        }
+    }
+
+    fn keymap() -> Keymap<Message> {
+        Keymap::new()
+            .bind(
+                Binding::key(Key::Character("z".into()))
+                    .modifiers(Modifiers::CTRL)
+                    .on_press(Message::Undo),
+            )
+            .bind(
+                Binding::key(Key::Character("z".into()))
+                    .modifiers(Modifiers::CTRL | Modifiers::SHIFT)
+                    .on_press(Message::Redo),
+            )
+            .bind(
+                Binding::key(Key::Named(Named::Space))
+                    .any_modifiers()
+                    .on_press(Message::Play)
+                    .pass_through(),
+            )
+            .bind(
+                Binding::physical(Code::KeyZ)
+                    .on_press(Message::NoteOn(48))
+                    .on_release(Message::NoteOff(48)),
+            )
+    }
+
+    fn message(resolution: Option<Resolution<Message>>) -> Option<Message> {
+        resolution.and_then(|resolution| resolution.message)
+    }
+
+    #[test]
+    fn resolving_with_modifiers() {
+        let keymap = keymap();
+        let z = || Key::Character("z".into());
+        let shifted_z = || Key::Character("Z".into());
+
+        assert_eq!(
+            message(keymap.resolve(&pressed(z(), Code::KeyZ, Modifiers::CTRL))),
+            Some(Message::Undo)
+        );
+        assert_eq!(
+            message(keymap.resolve(&pressed(
+                shifted_z(),
+                Code::KeyZ,
+                Modifiers::CTRL | Modifiers::SHIFT
+            ))),
+            Some(Message::Redo)
+        );
+        // Alt is not part of any binding for Z
+        assert_eq!(
+            keymap.resolve(&pressed(z(), Code::KeyZ, Modifiers::ALT)),
+            None
+        );
+
+        // Without modifiers the physical binding matches
+        assert_eq!(
+            keymap.resolve(&pressed(z(), Code::KeyZ, Modifiers::empty())),
+            Some(Resolution {
+                message: Some(Message::NoteOn(48)),
+                consumed: true
+            })
+        );
+        // The physical binding works with other keyboard layouts
+        assert_eq!(
+            message(keymap.resolve(&pressed(
+                Key::Character("y".into()),
+                Code::KeyZ,
+                Modifiers::empty()
+            ))),
+            Some(Message::NoteOn(48))
+        );
+
+        // Releasing the key after pressing shift still ends the note, but releasing it again
+        // doesn't
+        assert_eq!(
+            message(keymap.resolve(&released(shifted_z(), Code::KeyZ, Modifiers::SHIFT))),
+            Some(Message::NoteOff(48))
+        );
+        assert_eq!(
+            keymap.resolve(&released(z(), Code::KeyZ, Modifiers::empty())),
+            None
+        );
+
+        // Releasing Z after Ctrl+Z doesn't end a note, even if Ctrl was released first
+        let _ = keymap.resolve(&pressed(z(), Code::KeyZ, Modifiers::CTRL));
+        assert_eq!(
+            keymap.resolve(&released(z(), Code::KeyZ, Modifiers::empty())),
+            Some(Resolution {
+                message: None,
+                consumed: true
+            })
+        );
+
+        // Space plays regardless of the modifiers, and lets the widgets see the event
+        assert_eq!(
+            keymap.resolve(&pressed(
+                Key::Named(Named::Space),
+                Code::Space,
+                Modifiers::SHIFT
+            )),
+            Some(Resolution {
+                message: Some(Message::Play),
+                consumed: false
+            })
+        );
+        assert_eq!(
+            keymap.resolve(&keyboard::Event::ModifiersChanged(Modifiers::SHIFT)),
+            None
+        );
+    }
+
+    #[test]
+    fn applying_to_events() {
+        let keymap = keymap();
+        let z = || Key::Character("z".into());
+        let space = || Key::Named(Named::Space);
+        let new_events = || {
+            vec![
+                Event::Keyboard(keyboard::Event::ModifiersChanged(Modifiers::empty())),
+                Event::Keyboard(pressed(z(), Code::KeyZ, Modifiers::empty())),
+                Event::Keyboard(pressed(space(), Code::Space, Modifiers::empty())),
+                Event::Keyboard(released(z(), Code::KeyZ, Modifiers::empty())),
+            ]
+        };
+
+        // Consumed events are removed, and the messages keep the order of the events
+        let mut events = new_events();
+        let mut messages = Vec::new();
+        let consumed = apply(
+            |event| keymap.resolve(event),
+            || false,
+            &mut events,
+            0,
+            &mut messages,
+        );
+        assert!(consumed);
+        assert_eq!(events.len(), 2);
+        assert!(matches!(
+            events[1],
+            Event::Keyboard(keyboard::Event::KeyPressed { .. })
+        ));
+        assert_eq!(
+            messages,
+            vec![Message::NoteOn(48), Message::Play, Message::NoteOff(48)]
+        );
+
+        // Events before `first` were already handled
+        let mut events = new_events();
+        let mut messages = Vec::new();
+        let _ = apply(
+            |event| keymap.resolve(event),
+            || false,
+            &mut events,
+            2,
+            &mut messages,
+        );
+        // The note was already released above, so releasing it again doesn't match anything
+        assert_eq!(events.len(), 4);
+        assert_eq!(messages, vec![Message::Play]);
+
+        // A focused text field gets all of the keys
+        let mut events = new_events();
+        let mut messages = Vec::new();
+        let consumed = apply(
+            |event| keymap.resolve(event),
+            || true,
+            &mut events,
+            0,
+            &mut messages,
+        );
+        assert!(!consumed);
+        assert_eq!(events.len(), 4);
+        assert!(messages.is_empty());
+    }
+}
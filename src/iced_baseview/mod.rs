@@ -33,15 +33,20 @@ pub use alignment::Vertical::{Bottom, Top};
 pub use Alignment::Center;
 pub use Length::{Fill, FillPortion, Shrink};
 
-pub mod task {
-    //! Create runtime tasks.
-    pub use crate::runtime::task::{Handle, Task};
-}
-
 pub mod application;
+pub mod child_window;
 pub mod clipboard;
 pub mod conversion;
+pub mod graphics_cache;
+pub mod hover;
+pub mod interaction;
+pub mod keymap;
+pub mod render;
+pub mod scale;
 pub mod settings;
+pub mod task;
+pub mod tick;
+pub mod ui_cache;
 pub mod window;
 
 #[cfg(feature = "system")]
@@ -52,17 +57,24 @@ mod position;
 mod proxy;
 
 pub use application::{Appearance, Application, DefaultStyle};
-pub use clipboard::Clipboard;
+pub use child_window::{ChildWindowSettings, ChildWindowStatus};
+pub use clipboard::{Clipboard, ClipboardWatch};
 pub use error::Error;
+pub use interaction::{InteractionSettings, ScrollToAdjust, WheelSettings};
+pub use keymap::Keymap;
 pub use event::Event;
 pub use executor::Executor;
 pub use font::Font;
+pub use graphics_cache::GraphicsCache;
 pub use position::Position;
-#[cfg(feature = "trace")]
-pub use program::Profiler;
+pub use application::Profiler;
 pub use proxy::Proxy;
 pub use renderer::Renderer;
-pub use settings::{GraphicsSettings, IcedBaseviewSettings, Settings};
+pub use scale::ScaleConversion;
+pub use settings::{
+    EventFilter, EventQueueLimit, EventQueueOverflow, GraphicsSettings, IcedBaseviewSettings,
+    IdleTrim, ResizeResolution, Settings, TaskLimit, TaskOverflow,
+};
 pub use task::Task;
 pub use window::WindowSubs;
 
@@ -155,12 +167,13 @@ pub type Element<'a, Message, Theme = crate::Theme, Renderer = crate::Renderer>
 /// The result of running an iced program.
 pub type Result = std::result::Result<(), Error>;
 
-/// Runs the [`Application`] in a child window.
+/// Runs the [`Application`] in a child window. This fails if the window could not be created, or
+/// if the application could not be started in it.
 pub fn open_parented<A, W>(
     parent: &W,
     flags: A::Flags,
     settings: Settings,
-) -> window::WindowHandle<A::Message>
+) -> std::result::Result<window::WindowHandle<A::Message>, Error>
 where
     A: Application + Send + 'static,
     A::Flags: Send,
@@ -169,7 +182,9 @@ where
     window::IcedWindow::<A>::open_parented::<W, Compositor>(parent, flags, settings)
 }
 
-pub fn open_blocking<A>(flags: A::Flags, settings: Settings)
+/// Runs the [`Application`] in a new window, and blocks until the window is closed. This fails if
+/// the window could not be created, or if the application could not be started in it.
+pub fn open_blocking<A>(flags: A::Flags, settings: Settings) -> Result
 where
     A: Application + Send + 'static,
     A::Flags: Send,
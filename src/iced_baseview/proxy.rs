@@ -5,17 +5,43 @@ use iced_runtime::futures::futures::{
 };
 use iced_runtime::Action;
 use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 
 /// An event loop proxy that implements `Sink`.
 #[derive(Debug)]
 pub struct Proxy<Message: 'static> {
     sender: mpsc::UnboundedSender<Action<Message>>,
+    wakeup: Wakeup,
+}
+
+/// Set whenever a task or subscription sends something through a [`Proxy`], so the window can
+/// pick it up right away instead of waiting for the next frame.
+///
+/// Tasks and subscriptions run on the application's executor, which has no way to wake up the
+/// window's event loop. baseview can't be asked to call into the window at a specific time
+/// either. The window therefore delivers the actions at the start of every frame, and when this
+/// is set, also after every input event. Messages from background streams are handled within one
+/// frame, and even sooner while the user is interacting with the window.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Wakeup(Arc<AtomicBool>);
+
+impl Wakeup {
+    fn wake(&self) {
+        self.0.store(true, Ordering::Release);
+    }
+
+    /// Whether anything has been sent since the last call.
+    pub fn take(&self) -> bool {
+        self.0.swap(false, Ordering::AcqRel)
+    }
 }
 
 impl<Message: 'static> Clone for Proxy<Message> {
     fn clone(&self) -> Self {
         Self {
             sender: self.sender.clone(),
+            wakeup: self.wakeup.clone(),
         }
     }
 }
@@ -23,7 +49,15 @@ impl<Message: 'static> Clone for Proxy<Message> {
 impl<Message: 'static> Proxy<Message> {
     /// Creates a new [`Proxy`] from an `mpsc::Sender`.
     pub fn new(sender: mpsc::UnboundedSender<Action<Message>>) -> Self {
-        Self { sender }
+        Self {
+            sender,
+            wakeup: Wakeup::default(),
+        }
+    }
+
+    /// The flag that's set when something is sent through this proxy or one of its clones.
+    pub(crate) fn wakeup(&self) -> Wakeup {
+        self.wakeup.clone()
     }
 }
 
@@ -35,9 +69,15 @@ impl<Message: 'static> Sink<Action<Message>> for Proxy<Message> {
     }
 
     fn start_send(mut self: Pin<&mut Self>, message: Action<Message>) -> Result<(), Self::Error> {
-        self.sender.start_send(message)?;
-
-        Ok(())
+        // Tasks can still finish after the window has closed. Their messages are silently discarded
+        // instead of ending the task with an error.
+        match self.sender.start_send(message) {
+            Err(error) if error.is_disconnected() => Ok(()),
+            result => {
+                self.wakeup.wake();
+                result
+            }
+        }
     }
 
     fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
@@ -48,3 +88,61 @@ impl<Message: 'static> Sink<Action<Message>> for Proxy<Message> {
         Poll::Ready(Ok(()))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::futures::{Executor, Runtime};
+    use iced_runtime::futures::futures::StreamExt;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn background_messages_arrive_within_a_frame() {
+        const FRAME: Duration = Duration::from_millis(16);
+        const TICKS: usize = 5;
+
+        let (sender, mut receiver) = mpsc::unbounded::<Action<Instant>>();
+        let proxy = Proxy::new(sender);
+        let wakeup = proxy.wakeup();
+        let mut window_proxy = proxy.clone();
+        let mut runtime = Runtime::new(crate::executor::Default::new().unwrap(), proxy);
+
+        // A timer stream that's driven by another thread, like a stream that awaits a channel. It
+        // never wakes up the window itself.
+        let (ticks, ticks_rx) = mpsc::unbounded();
+        let _ = std::thread::spawn(move || {
+            for _ in 0..TICKS {
+                std::thread::sleep(Duration::from_millis(7));
+                let _ = ticks.unbounded_send(Instant::now());
+            }
+        });
+        runtime.run(ticks_rx.map(Action::Output).boxed());
+
+        // This mimics `IcedWindow::on_frame()` without any input events in between
+        let mut received = 0;
+        for _ in 0..100 {
+            std::thread::sleep(FRAME);
+            let frame = Instant::now();
+            let _ = wakeup.take();
+            while let Ok(Action::Output(sent_at)) = receiver.try_recv() {
+                // Allow for the scheduling jitter of a busy test machine
+                let latency = frame.saturating_duration_since(sent_at);
+                assert!(latency < FRAME * 2, "{latency:?}");
+                received += 1;
+            }
+
+            if received == TICKS {
+                break;
+            }
+        }
+        assert_eq!(received, TICKS);
+
+        // The window checks the flag after input events to handle messages even sooner
+        assert!(!wakeup.take());
+        Pin::new(&mut window_proxy)
+            .start_send(Action::Exit)
+            .unwrap();
+        assert!(wakeup.take());
+        assert!(!wakeup.take());
+    }
+}
@@ -0,0 +1,230 @@
+//! Render the application's view to an image, for instance to export the current view as a PNG.
+//!
+//! [`render_frame()`] returns a [`Task`] that renders the view at a requested size and scale
+//! factor. The frame is rendered right after the [`Application::update()`][crate::Application::update()]
+//! call that returned the task, before any other messages are handled, so it always reflects the
+//! application's state at that point. This happens independently of the window's own redraws, and
+//! the window doesn't need to be visible or have the same size.
+//!
+//! Rendering runs on the editor's GUI thread, and it blocks that thread until the GPU has finished
+//! drawing and the pixels have been read back. This usually takes a couple of milliseconds, but it
+//! should not be done every frame.
+//!
+//! The view is built from scratch for the frame, so widget state that's not part of the
+//! application like scroll positions, hover effects, and focused text inputs is not included.
+//!
+//! ```ignore
+//! Message::Export => {
+//!     return render::render_frame(Size::new(800.0, 600.0), 2.0).map(Message::Exported);
+//! }
+//! Message::Exported(Some(frame)) => {
+//!     image::save_buffer(path, &frame.rgba, frame.width, frame.height, image::ColorType::Rgba8);
+//! }
+//! ```
+
+use std::cell::RefCell;
+
+use crate::core::{mouse, renderer, Element, Size};
+use crate::futures::futures::channel::oneshot;
+use crate::graphics::Viewport;
+use crate::runtime::user_interface::{self, UserInterface};
+use crate::Task;
+
+/// A frame rendered by [`render_frame()`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenderedFrame {
+    /// The frame's width in physical pixels.
+    pub width: u32,
+    /// The frame's height in physical pixels.
+    pub height: u32,
+    /// The scale factor the frame was rendered at.
+    pub scale_factor: f32,
+    /// The frame's pixels as `RGBA` bytes in the sRGB color space, row by row without padding.
+    pub rgba: Vec<u8>,
+}
+
+/// A pending [`render_frame()`] request.
+pub(crate) struct FrameRequest {
+    pub size: Size,
+    pub scale_factor: f32,
+    pub sender: oneshot::Sender<Option<RenderedFrame>>,
+}
+
+thread_local! {
+    static REQUESTS: RefCell<Vec<FrameRequest>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Render the application's current view at a logical `size` and `scale_factor`. The resulting
+/// frame is `size * scale_factor` physical pixels large. The task produces `None` if the size is
+/// empty or if the editor closed before the frame could be rendered.
+///
+/// This must be called from [`Application::update()`][crate::Application::update()]. See the
+/// [module documentation][self].
+pub fn render_frame(size: Size, scale_factor: f32) -> Task<Option<RenderedFrame>> {
+    let (sender, receiver) = oneshot::channel();
+    REQUESTS.with(|requests| {
+        requests.borrow_mut().push(FrameRequest {
+            size,
+            scale_factor,
+            sender,
+        })
+    });
+
+    Task::future(async move { receiver.await.ok().flatten() })
+}
+
+/// Take the requests made since the last call. The runtime calls this after every update.
+pub(crate) fn take_requests() -> Vec<FrameRequest> {
+    REQUESTS.with(|requests| std::mem::take(&mut *requests.borrow_mut()))
+}
+
+/// Lay out and draw `element` for a request, and read the pixels back using `screenshot`. This is
+/// [`Compositor::screenshot()`][crate::graphics::compositor::Compositor::screenshot()] in the
+/// runtime.
+pub(crate) fn render<Message, Theme, Renderer>(
+    element: Element<'_, Message, Theme, Renderer>,
+    renderer: &mut Renderer,
+    theme: &Theme,
+    style: &renderer::Style,
+    size: Size,
+    scale_factor: f32,
+    screenshot: impl FnOnce(&mut Renderer, &Viewport) -> Vec<u8>,
+) -> Option<RenderedFrame>
+where
+    Renderer: crate::core::Renderer,
+{
+    if scale_factor.is_nan() || scale_factor <= 0.0 {
+        return None;
+    }
+
+    let physical_size = Size::new(
+        (size.width * scale_factor).round() as u32,
+        (size.height * scale_factor).round() as u32,
+    );
+    if physical_size.width == 0 || physical_size.height == 0 {
+        return None;
+    }
+
+    let viewport = Viewport::with_physical_size(physical_size, scale_factor);
+    let mut interface = UserInterface::build(
+        element,
+        viewport.logical_size(),
+        user_interface::Cache::default(),
+        renderer,
+    );
+    interface.draw(renderer, theme, style, mouse::Cursor::Unavailable);
+    drop(interface);
+
+    let rgba = screenshot(renderer, &viewport);
+
+    Some(RenderedFrame {
+        width: physical_size.width,
+        height: physical_size.height,
+        scale_factor,
+        rgba,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::layout::{self, Layout};
+    use crate::core::widget::{Tree, Widget};
+    use crate::core::{Color, Length, Rectangle};
+    use std::cell::Cell;
+
+    /// Fills its bounds and remembers where it was drawn.
+    struct Fill<'a>(&'a Cell<Option<Rectangle>>);
+
+    impl<Message, Theme, Renderer: crate::core::Renderer> Widget<Message, Theme, Renderer>
+        for Fill<'_>
+    {
+        fn size(&self) -> Size<Length> {
+            Size::new(Length::Fill, Length::Fill)
+        }
+
+        fn layout(
+            &mut self,
+            _tree: &mut Tree,
+            _renderer: &Renderer,
+            limits: &layout::Limits,
+        ) -> layout::Node {
+            layout::Node::new(limits.max())
+        }
+
+        fn draw(
+            &self,
+            _tree: &Tree,
+            renderer: &mut Renderer,
+            _theme: &Theme,
+            _style: &renderer::Style,
+            layout: Layout<'_>,
+            _cursor: mouse::Cursor,
+            _viewport: &Rectangle,
+        ) {
+            renderer.fill_quad(
+                renderer::Quad {
+                    bounds: layout.bounds(),
+                    ..Default::default()
+                },
+                Color::WHITE,
+            );
+            self.0.set(Some(layout.bounds()));
+        }
+    }
+
+    #[test]
+    fn render_simple_view() {
+        let drawn_bounds = Cell::new(None);
+        let style = renderer::Style {
+            text_color: Color::BLACK,
+        };
+
+        // The null renderer doesn't produce pixels, so the screenshot is faked with a buffer of the
+        // viewport's size
+        let frame = render::<(), (), ()>(
+            Element::new(Fill(&drawn_bounds)),
+            &mut (),
+            &(),
+            &style,
+            Size::new(100.0, 50.0),
+            2.0,
+            |_, viewport| {
+                let size = viewport.physical_size();
+                vec![255; (size.width * size.height * 4) as usize]
+            },
+        )
+        .expect("The frame should have been rendered");
+
+        assert_eq!((frame.width, frame.height), (200, 100));
+        assert_eq!(frame.scale_factor, 2.0);
+        assert_eq!(frame.rgba.len(), 200 * 100 * 4);
+        assert_eq!(
+            drawn_bounds.get(),
+            Some(Rectangle::new(Default::default(), Size::new(100.0, 50.0)))
+        );
+
+        // Empty frames are not rendered
+        let frame = render::<(), (), ()>(
+            Element::new(Fill(&drawn_bounds)),
+            &mut (),
+            &(),
+            &style,
+            Size::new(0.0, 50.0),
+            2.0,
+            |_, _| unreachable!(),
+        );
+        assert_eq!(frame, None);
+    }
+
+    #[test]
+    fn requests_are_queued_until_taken() {
+        let _ = take_requests();
+
+        let _task = render_frame(Size::new(10.0, 10.0), 1.0);
+        let requests = take_requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].size, Size::new(10.0, 10.0));
+        assert!(take_requests().is_empty());
+    }
+}
@@ -0,0 +1,491 @@
+//! Conversions between logical and physical coordinates.
+//!
+//! iced lays out and draws everything in logical pixels, while the window itself and any custom
+//! rendering work with physical pixels. The scale factor used by the runtime is exposed through
+//! [`State::scale_factor()`][crate::application::State::scale_factor()] and, for editors, through
+//! [`IcedState::scale_factor()`][crate::IcedState::scale_factor()].
+//!
+//! The conversions do not round. A logical coordinate converted to physical space at a fractional
+//! scale factor can land between two physical pixels, and converting it back results in the
+//! original value up to floating point precision. This matches the runtime's own viewport, which
+//! computes the logical window size by dividing the physical size by the scale factor without
+//! rounding. Round the physical values yourself if you need to snap to the pixel grid.
+//!
+//! Quads are a different story. A one pixel border whose edges land between two physical pixels is
+//! drawn as two half-transparent lines, and a thin line can disappear entirely on one side. Inside
+//! of `Widget::draw()`, [`scale_factor()`] returns the scale factor of the window that's being
+//! drawn, and [`pixel_snap()`] and [`snap_stroke()`] align a quad's edges and border width to that
+//! window's physical pixel grid. The built-in widgets use these for their borders, meter segments,
+//! and ruler ticks.
+//!
+//! Text is the exception. With `snap_text_to_pixel` enabled in the
+//! [`IcedBaseviewSettings`][crate::settings::IcedBaseviewSettings], the built-in widgets pass the position of their text through [`snap_text()`], which
+//! moves the text's anchor and the top of its line onto the physical pixel grid. Since a line's
+//! baseline sits at a fixed distance from its top, this keeps glyphs at the same vertical subpixel
+//! offset everywhere, and it places left-aligned text on a whole pixel. The spacing between the
+//! glyphs within a line is decided by the renderer's text shaping and is not rounded, and iced's
+//! own text widgets are not affected.
+
+use std::cell::Cell;
+
+use crate::core::alignment;
+use crate::core::text::Text;
+use crate::core::{Point, Rectangle, Size, Vector};
+
+thread_local! {
+    /// The scale factor of the window that's being drawn.
+    static SCALE_FACTOR: Cell<f32> = const { Cell::new(1.0) };
+    /// The scale factor to snap text to, or `None` if text should not be snapped.
+    static TEXT_SNAPPING: Cell<Option<f32>> = const { Cell::new(None) };
+}
+
+/// The scale factor of the window that's currently being drawn, or 1.0 outside of the runtime.
+/// Together with [`pixel_snap()`] this lets `Widget::draw()` work with physical pixels.
+pub fn scale_factor() -> f32 {
+    SCALE_FACTOR.with(Cell::get)
+}
+
+/// Move `rect`'s edges to the nearest physical pixel boundaries of the window that's currently
+/// being drawn. A rectangle that's not empty stays at least one physical pixel large, so thin lines
+/// never disappear. See the [module documentation][self].
+pub fn pixel_snap(rect: Rectangle) -> Rectangle {
+    pixel_snap_at(rect, scale_factor())
+}
+
+/// Like [`pixel_snap()`], but for an explicit scale factor.
+pub fn pixel_snap_at(rect: Rectangle, scale_factor: f32) -> Rectangle {
+    if !(scale_factor.is_finite() && scale_factor > 0.0) {
+        return rect;
+    }
+
+    let snap = |value: f32| (value * scale_factor).round() / scale_factor;
+    let snap_span = |start: f32, length: f32| {
+        let snapped_start = snap(start);
+        let snapped_length = snap(start + length) - snapped_start;
+        if length > 0.0 {
+            (snapped_start, snapped_length.max(1.0 / scale_factor))
+        } else {
+            (snapped_start, 0.0)
+        }
+    };
+
+    let (x, width) = snap_span(rect.x, rect.width);
+    let (y, height) = snap_span(rect.y, rect.height);
+
+    Rectangle {
+        x,
+        y,
+        width,
+        height,
+    }
+}
+
+/// Round a border or line width in logical pixels to a whole number of physical pixels of the
+/// window that's currently being drawn. A width that's not zero stays at least one physical pixel
+/// wide.
+pub fn snap_stroke(width: f32) -> f32 {
+    let scale_factor = scale_factor();
+    if width <= 0.0 || !(scale_factor.is_finite() && scale_factor > 0.0) {
+        return width;
+    }
+
+    (width * scale_factor).round().max(1.0) / scale_factor
+}
+
+/// Snap the position a single line of `text` is drawn at to the physical pixel grid, if that's
+/// enabled for the window that's currently being drawn. Use this for the position passed to
+/// [`fill_text()`][crate::core::text::Renderer::fill_text()]. See the [module
+/// documentation][self].
+pub fn snap_text<Content, Font>(text: &Text<Content, Font>, position: Point) -> Point {
+    match TEXT_SNAPPING.with(Cell::get) {
+        Some(scale_factor) => snap_text_at(text, position, scale_factor),
+        None => position,
+    }
+}
+
+/// Set the scale factor returned by [`scale_factor()`], and whether [`snap_text()`] snaps to it.
+/// The runtime does this before handling every event, and while drawing at a different scale
+/// factor.
+pub(crate) fn set_drawing(scale_factor: f32, snap_text: bool) {
+    SCALE_FACTOR.with(|current| current.set(scale_factor));
+    TEXT_SNAPPING.with(|snapping| snapping.set(snap_text.then_some(scale_factor)));
+}
+
+fn snap_text_at<Content, Font>(
+    text: &Text<Content, Font>,
+    position: Point,
+    scale_factor: f32,
+) -> Point {
+    let snap = |value: f32| (value * scale_factor).round() / scale_factor;
+
+    // The renderer places the line relative to the position according to its alignment
+    let line_height = f32::from(text.line_height.to_absolute(text.size));
+    let top = match text.align_y {
+        alignment::Vertical::Top => position.y,
+        alignment::Vertical::Center => position.y - (line_height / 2.0),
+        alignment::Vertical::Bottom => position.y - line_height,
+    };
+
+    Point::new(snap(position.x), position.y + (snap(top) - top))
+}
+
+/// A value that can be converted between logical and physical coordinates.
+pub trait ScaleConversion: Sized {
+    /// Convert this value from logical coordinates to physical coordinates.
+    fn to_physical(self, scale_factor: f32) -> Self;
+
+    /// Convert this value from physical coordinates to logical coordinates.
+    fn to_logical(self, scale_factor: f32) -> Self;
+}
+
+impl ScaleConversion for Point {
+    fn to_physical(self, scale_factor: f32) -> Self {
+        Point::new(self.x * scale_factor, self.y * scale_factor)
+    }
+
+    fn to_logical(self, scale_factor: f32) -> Self {
+        Point::new(self.x / scale_factor, self.y / scale_factor)
+    }
+}
+
+impl ScaleConversion for Vector {
+    fn to_physical(self, scale_factor: f32) -> Self {
+        self * scale_factor
+    }
+
+    fn to_logical(self, scale_factor: f32) -> Self {
+        Vector::new(self.x / scale_factor, self.y / scale_factor)
+    }
+}
+
+impl ScaleConversion for Size {
+    fn to_physical(self, scale_factor: f32) -> Self {
+        Size::new(self.width * scale_factor, self.height * scale_factor)
+    }
+
+    fn to_logical(self, scale_factor: f32) -> Self {
+        Size::new(self.width / scale_factor, self.height / scale_factor)
+    }
+}
+
+impl ScaleConversion for Rectangle {
+    fn to_physical(self, scale_factor: f32) -> Self {
+        Rectangle::new(
+            self.position().to_physical(scale_factor),
+            self.size().to_physical(scale_factor),
+        )
+    }
+
+    fn to_logical(self, scale_factor: f32) -> Self {
+        Rectangle::new(
+            self.position().to_logical(scale_factor),
+            self.size().to_logical(scale_factor),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(a: f32, b: f32) {
+        assert!((a - b).abs() < 1e-4, "{a} != {b}");
+    }
+
+    #[test]
+    fn fractional_scale_round_trip() {
+        for scale_factor in [1.25f32, 1.5, 1.75] {
+            let rect = Rectangle::new(Point::new(10.0, 3.0), Size::new(101.0, 33.3));
+
+            let physical = rect.to_physical(scale_factor);
+            assert_close(physical.x, 10.0 * scale_factor);
+            assert_close(physical.y, 3.0 * scale_factor);
+            assert_close(physical.width, 101.0 * scale_factor);
+            assert_close(physical.height, 33.3 * scale_factor);
+
+            let logical = physical.to_logical(scale_factor);
+            assert_close(logical.x, rect.x);
+            assert_close(logical.y, rect.y);
+            assert_close(logical.width, rect.width);
+            assert_close(logical.height, rect.height);
+        }
+    }
+
+    #[test]
+    fn text_snaps_to_physical_pixels() {
+        let text = Text {
+            content: "-6.0 dB",
+            bounds: Size::new(50.0, 12.0),
+            size: crate::Pixels(11.0),
+            line_height: crate::core::text::LineHeight::Relative(1.0),
+            font: (),
+            align_x: crate::core::text::Alignment::Center,
+            align_y: alignment::Vertical::Center,
+            shaping: Default::default(),
+            wrapping: Default::default(),
+        };
+        // The center of a value readout in a slider at (10.3, 20.3) with a 1 pixel border
+        let position = Point::new(10.3 + 26.0, 20.3 + 7.0);
+
+        // Without snapping, the line's top edge ends up between two physical pixels at 1.25x
+        set_drawing(1.25, false);
+        let unsnapped = snap_text(&text, position);
+        assert_eq!(unsnapped, position);
+        let top = (unsnapped.y - 5.5) * 1.25;
+        assert!((top - top.round()).abs() > 0.1, "{top}");
+
+        set_drawing(1.25, true);
+        let snapped = snap_text(&text, position);
+        let top = (snapped.y - 5.5) * 1.25;
+        assert_close(top, top.round());
+        assert_close(snapped.x * 1.25, (snapped.x * 1.25).round());
+        assert!(snapped.distance(position) <= 0.5 / 1.25 * 2f32.sqrt());
+
+        set_drawing(1.0, false);
+    }
+
+    #[test]
+    fn snapped_text_positions() {
+        let text = |align_y| Text {
+            content: "-6.0 dB",
+            bounds: Size::new(50.0, 12.0),
+            size: crate::Pixels(11.0),
+            line_height: crate::core::text::LineHeight::Relative(1.0),
+            font: (),
+            align_x: crate::core::text::Alignment::Center,
+            align_y,
+            shaping: Default::default(),
+            wrapping: Default::default(),
+        };
+        let position = Point::new(36.3, 27.3);
+
+        // The line's top edge is at 21.8 when centered and at 16.3 when bottom aligned. It moves
+        // to the nearest physical pixel, and the position moves along with it.
+        for (scale_factor, align_y, expected) in [
+            (1.25, alignment::Vertical::Center, Point::new(36.0, 27.1)),
+            (1.25, alignment::Vertical::Bottom, Point::new(36.0, 27.0)),
+            (1.5, alignment::Vertical::Center, Point::new(36.0, 27.5)),
+            (1.5, alignment::Vertical::Bottom, Point::new(36.0, 27.0)),
+        ] {
+            set_drawing(scale_factor, true);
+            let snapped = snap_text(&text(align_y), position);
+            assert_close(snapped.x, expected.x);
+            assert_close(snapped.y, expected.y);
+        }
+
+        set_drawing(1.0, false);
+    }
+
+    #[test]
+    fn rectangles_snap_to_physical_pixels() {
+        let rect = Rectangle::new(Point::new(2.5, 10.2), Size::new(20.0, 0.3));
+
+        assert_eq!(
+            pixel_snap_at(rect, 1.0),
+            Rectangle::new(Point::new(3.0, 10.0), Size::new(20.0, 1.0))
+        );
+
+        // The edges are snapped separately, and thin lines stay one physical pixel tall
+        let snapped = pixel_snap_at(rect, 1.5);
+        for value in [snapped.x, snapped.y, snapped.width, snapped.height] {
+            assert_close(value * 1.5, (value * 1.5).round());
+        }
+        assert_close(snapped.height * 1.5, 1.0);
+
+        let empty = Rectangle::new(Point::new(0.4, 0.4), Size::ZERO);
+        assert_eq!(pixel_snap_at(empty, 1.0).size(), Size::ZERO);
+    }
+
+    /// Rasterizes the quads drawn by a widget with exact pixel coverage, so a snapshot shows which
+    /// physical pixels are only partially covered.
+    struct Raster {
+        scale_factor: f32,
+        width: usize,
+        coverage: Vec<f32>,
+    }
+
+    impl Raster {
+        fn new(size: Size, scale_factor: f32) -> Self {
+            let width = (size.width * scale_factor).round() as usize;
+            let height = (size.height * scale_factor).round() as usize;
+
+            Self {
+                scale_factor,
+                width,
+                coverage: vec![0.0; width * height],
+            }
+        }
+
+        fn fill(&mut self, area: Rectangle, sign: f32) {
+            for (index, coverage) in self.coverage.iter_mut().enumerate() {
+                let pixel = Rectangle::new(
+                    Point::new((index % self.width) as f32, (index / self.width) as f32),
+                    Size::new(1.0, 1.0),
+                );
+                if let Some(overlap) = pixel.intersection(&area) {
+                    *coverage += overlap.area() * sign;
+                }
+            }
+        }
+    }
+
+    impl crate::core::Renderer for Raster {
+        fn start_layer(&mut self, _bounds: Rectangle) {}
+
+        fn end_layer(&mut self) {}
+
+        fn start_transformation(&mut self, _transformation: crate::core::Transformation) {}
+
+        fn end_transformation(&mut self) {}
+
+        fn reset(&mut self, _new_bounds: Rectangle) {}
+
+        /// Only the border is rasterized.
+        fn fill_quad(
+            &mut self,
+            quad: crate::core::renderer::Quad,
+            _background: impl Into<crate::core::Background>,
+        ) {
+            let outer = quad.bounds.to_physical(self.scale_factor);
+            let inner = outer.shrink(quad.border.width * self.scale_factor);
+            self.fill(outer, 1.0);
+            self.fill(inner, -1.0);
+        }
+
+        fn allocate_image(
+            &mut self,
+            _handle: &crate::core::image::Handle,
+            _callback: impl FnOnce(Result<crate::core::image::Allocation, crate::core::image::Error>)
+                + Send
+                + 'static,
+        ) {
+            unreachable!()
+        }
+    }
+
+    /// A widget with a one pixel border drawn inset between two pixels, with or without snapping.
+    struct Outline {
+        snapped: bool,
+    }
+
+    impl crate::core::Widget<(), (), Raster> for Outline {
+        fn size(&self) -> Size<crate::Length> {
+            Size::new(crate::Length::Fill, crate::Length::Fill)
+        }
+
+        fn layout(
+            &mut self,
+            _tree: &mut crate::core::widget::Tree,
+            _renderer: &Raster,
+            limits: &crate::core::layout::Limits,
+        ) -> crate::core::layout::Node {
+            crate::core::layout::Node::new(limits.max())
+        }
+
+        fn draw(
+            &self,
+            _tree: &crate::core::widget::Tree,
+            renderer: &mut Raster,
+            _theme: &(),
+            _style: &crate::core::renderer::Style,
+            layout: crate::core::Layout<'_>,
+            _cursor: crate::core::mouse::Cursor,
+            _viewport: &Rectangle,
+        ) {
+            let bounds = layout.bounds().shrink(2.5);
+            let (bounds, width) = if self.snapped {
+                (pixel_snap(bounds), snap_stroke(1.0))
+            } else {
+                (bounds, 1.0)
+            };
+
+            crate::core::Renderer::fill_quad(
+                renderer,
+                crate::core::renderer::Quad {
+                    bounds,
+                    border: crate::core::Border {
+                        width,
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+                crate::Color::TRANSPARENT,
+            );
+        }
+    }
+
+    /// Render [`Outline`] offscreen, and return the alpha values of the resulting frame's rows.
+    fn snapshot(snapped: bool, scale_factor: f32) -> Vec<Vec<u8>> {
+        let size = Size::new(20.0, 12.0);
+        set_drawing(scale_factor, false);
+        let frame = crate::render::render(
+            crate::core::Element::new(Outline { snapped }),
+            &mut Raster::new(size, scale_factor),
+            &(),
+            &crate::core::renderer::Style {
+                text_color: crate::Color::BLACK,
+            },
+            size,
+            scale_factor,
+            |raster, _| {
+                raster
+                    .coverage
+                    .iter()
+                    .flat_map(|coverage| {
+                        [0, 0, 0, (coverage.clamp(0.0, 1.0) * 255.0).round() as u8]
+                    })
+                    .collect()
+            },
+        )
+        .expect("The frame should have been rendered");
+        set_drawing(1.0, false);
+
+        frame
+            .rgba
+            .chunks(frame.width as usize * 4)
+            .map(|row| row.chunks(4).map(|pixel| pixel[3]).collect())
+            .collect()
+    }
+
+    #[test]
+    fn hairline_borders_are_sharp() {
+        let is_partial = |alpha: &u8| *alpha != 0 && *alpha != 255;
+
+        // Without snapping the border straddles two pixels on every side
+        let blurry = snapshot(false, 1.0);
+        assert!(blurry.iter().flatten().any(is_partial));
+
+        let sharp = snapshot(true, 1.0);
+        assert!(!sharp.iter().flatten().any(is_partial));
+        // One pixel on either side of the middle row, with the half pixel edges rounded up
+        let middle_row: Vec<_> = sharp[6].iter().map(|&alpha| alpha == 255).collect();
+        assert_eq!(middle_row.iter().filter(|lit| **lit).count(), 2);
+        assert!(middle_row[3] && middle_row[17]);
+
+        // At 1.5x the border becomes two whole pixels wide instead of one and a half
+        let sharp = snapshot(true, 1.5);
+        assert_eq!(sharp.len(), 18);
+        assert!(!sharp.iter().flatten().any(is_partial));
+        let middle_row = &sharp[9];
+        assert_eq!(middle_row.iter().filter(|alpha| **alpha == 255).count(), 4);
+        let middle_column: Vec<_> = sharp.iter().map(|row| row[15]).collect();
+        assert_eq!(
+            middle_column.iter().filter(|alpha| **alpha == 255).count(),
+            4
+        );
+    }
+
+    #[test]
+    fn matches_viewport_logical_size() {
+        // The runtime's viewport is created from the physical window size
+        let physical_size = Size::new(1001u32, 757u32);
+        for scale_factor in [1.25f32, 1.5] {
+            let viewport = crate::Viewport::with_physical_size(physical_size, scale_factor);
+            let logical = Size::new(physical_size.width as f32, physical_size.height as f32)
+                .to_logical(scale_factor);
+
+            assert_close(logical.width, viewport.logical_size().width);
+            assert_close(logical.height, viewport.logical_size().height);
+        }
+    }
+}
@@ -1,8 +1,11 @@
 //! Configure your application.
-use std::{borrow::Cow, fmt::Debug};
+use std::{borrow::Cow, fmt::Debug, sync::Arc, time::Duration};
 
 use baseview::{Size, WindowOpenOptions, WindowScalePolicy};
 
+use crate::graphics_cache::GraphicsCache;
+use crate::interaction::InteractionSettings;
+
 pub use crate::graphics::Settings as GraphicsSettings;
 
 /// The settings of an application.
@@ -23,6 +26,12 @@ pub struct Settings {
 
     /// The fonts to load on boot.
     pub fonts: Vec<Cow<'static, [u8]>>,
+
+    /// Reuse the compositor and renderer of a window that was closed shortly before this one was
+    /// opened, and wait for that window to shut down before starting. Pass the same cache to every
+    /// window that should share resources. This is disabled by default. See the
+    /// [`graphics_cache`][crate::graphics_cache] module.
+    pub graphics_cache: Option<Arc<GraphicsCache>>,
 }
 
 impl Default for Settings {
@@ -36,12 +45,13 @@ impl Default for Settings {
             iced_baseview: IcedBaseviewSettings::default(),
             graphics_settings: GraphicsSettings::default(),
             fonts: Default::default(),
+            graphics_cache: None,
         }
     }
 }
 
 /// Any settings specific to `iced_baseview`.
-#[derive(Debug, Clone, Copy, Default)]
+#[derive(Debug, Clone, Copy)]
 pub struct IcedBaseviewSettings {
     /// Ignore key inputs, except for modifier keys such as SHIFT and ALT
     pub ignore_non_modifier_keys: bool,
@@ -52,4 +62,336 @@ pub struct IcedBaseviewSettings {
     /// reopening the editor) and an iced limitation where it's not possible to have animations
     /// without using an asynchronous timer stream to send redraw messages to the application.
     pub always_redraw: bool,
+
+    /// Whether the [`Application`][crate::Application] may open a child window. Many hosts don't
+    /// handle additional plugin windows well. When this is disabled, the application is told that
+    /// the child window is unavailable and it should show its contents in the main window instead.
+    /// See the [`child_window`][crate::child_window] module.
+    pub allow_child_window: bool,
+
+    /// Double click, long press, drag, and scroll settings used by the widgets. These can be
+    /// overridden per [`Application`][crate::Application] with
+    /// [`Application::interaction_settings()`][crate::Application::interaction_settings()].
+    pub interaction: InteractionSettings,
+
+    /// Redraw the window right after every input event while a widget is being dragged, instead of
+    /// waiting for the next frame. This lowers the latency between moving the mouse and seeing a
+    /// knob or slider move. The boost ends [`boost_linger`][Self::boost_linger] after the drag.
+    ///
+    /// The compositor cannot change its present mode without recreating the surface, so with vsync
+    /// enabled in the [`GraphicsSettings`] presenting the frame still waits for the display. Disable
+    /// vsync as well to get rid of that wait, at the cost of possible tearing. This is disabled by
+    /// default.
+    pub boost_during_interaction: bool,
+
+    /// How long the interaction boost stays active after a drag ends.
+    pub boost_linger: Duration,
+
+    /// How strongly to dampen momentum scrolling from trackpads, from `0.0` to `1.0`. Once the
+    /// scroll deltas after the user lifts their fingers are detected as momentum scrolling, each
+    /// following delta is scaled down by another factor of `1 - momentum_scroll_damping`. `1.0`
+    /// cuts the momentum off entirely. This is disabled by default.
+    ///
+    /// baseview does not report the scroll phase on any platform, so momentum scrolling is
+    /// recognized heuristically as a steadily shrinking series of pixel deltas in the same
+    /// direction. The first few deltas of the momentum phase are therefore passed through unchanged,
+    /// and very slow active scrolling may occasionally be damped as well. Line based deltas from
+    /// regular mouse wheels are never affected.
+    pub momentum_scroll_damping: f32,
+
+    /// The categories of baseview events that are converted and forwarded to iced. Events that
+    /// are filtered out are reported back to the host as ignored right away, so widgets never see
+    /// them and can't accidentally capture them. This can be overridden per
+    /// [`Application`][crate::Application] with
+    /// [`Application::event_filter()`][crate::Application::event_filter()]. All events are
+    /// forwarded by default.
+    pub event_filter: EventFilter,
+
+    /// Limits how many [`Task`][crate::Task]s returned by the [`Application`][crate::Application]
+    /// can run at the same time. Plugin hosts run every plugin instance's editor on the same
+    /// threads, so an editor that returns a task for every one of a rapid series of messages can
+    /// slow down every other editor. Subscriptions are not affected by this limit. This is `None`,
+    /// so unbounded, by default.
+    pub task_limit: Option<TaskLimit>,
+
+    /// What to do when more events are waiting for the widgets at the start of a frame than the
+    /// editor can be expected to handle, for instance when the host delivers a flood of cursor
+    /// movements while the editor is busy. The number of waiting events is always available
+    /// through [`application::queue_depth()`][crate::application::queue_depth()]. This is `None`,
+    /// so unlimited, by default.
+    pub event_queue_limit: Option<EventQueueLimit>,
+
+    /// Render at a reduced resolution while the window is being resized, and at full resolution
+    /// again once the size stops changing. Relaying out and redrawing a complex user interface for
+    /// every step of a resize can stutter, and the reduced resolution keeps resizing smooth. The
+    /// tradeoff is that the window looks blurry while it's being resized. This is `None`, so
+    /// disabled, by default.
+    ///
+    /// The reduced frames are stretched to the window's size by the platform's compositor. This
+    /// works on macOS and Windows, but on Linux it depends on the graphics driver whether the
+    /// frames are stretched or shown at their reduced size in the window's corner.
+    pub resize_resolution: Option<ResizeResolution>,
+
+    /// Release the renderer's glyph and image atlases and its staging buffers while the window is
+    /// hidden but still open, for instance when the host switched to another tab. With many plugin
+    /// instances those caches add up for editors nobody is looking at. While the caches are
+    /// released the window isn't drawn and [`WindowSubs::on_frame`][crate::WindowSubs::on_frame] is
+    /// not called. The first event afterwards, like the cursor entering the window or a message
+    /// from a subscription, brings everything back and redraws the whole window. This is `None`, so
+    /// disabled, by default.
+    ///
+    /// Baseview doesn't report when the host hides a window, so a window counts as hidden when the
+    /// [`Application`][crate::Application] pauses rendering with
+    /// [`Application::is_rendering_paused()`][crate::Application::is_rendering_paused()], or when
+    /// nothing was drawn for a while. Editors that only animate in response to
+    /// [`WindowSubs::on_frame`][crate::WindowSubs::on_frame] without ever receiving a message
+    /// should not rely on the second threshold, since they'd stay frozen until the next input
+    /// event.
+    pub idle_trim: Option<IdleTrim>,
+
+    /// Move the text drawn by the built-in widgets so it starts on a physical pixel. At fractional
+    /// scale factors like 1.25 or 1.5, text laid out in logical pixels usually lands between two
+    /// physical pixels, which makes small text look blurry. Snapping can move text by up to half a
+    /// physical pixel, so designs that need exact positioning can disable it. See
+    /// [`scale::snap_text()`][crate::scale::snap_text()]. This is enabled by default.
+    pub snap_text_to_pixel: bool,
+
+    /// The logical size of the area the window needs to fit in, usually the work area of the
+    /// display it's shown on. Baseview doesn't report display sizes, so this needs to be provided
+    /// by the application. See [`clamp_to_display`][Self::clamp_to_display].
+    pub display_size: Option<crate::core::Size>,
+
+    /// Shrink the window so it fits in [`display_size`][Self::display_size] when it's opened, for
+    /// instance with a saved size from a larger display, and when the application resizes it. This
+    /// has no effect when `display_size` is not set. Editors embedded in a host's window can't
+    /// control their position, so for those this only limits the window's size. This is enabled by
+    /// default.
+    pub clamp_to_display: bool,
+
+    /// The file to write the window's trace to. The `CHROME_TRACE_FILE` environment variable takes
+    /// precedence over this, and a new file in the system's temporary directory is used when
+    /// neither is set. See [`Profiler`][crate::application::Profiler].
+    #[cfg(feature = "trace")]
+    pub trace_file: Option<&'static std::path::Path>,
+}
+
+/// Settings for rendering at a reduced resolution during resizes, see
+/// [`IcedBaseviewSettings::resize_resolution`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ResizeResolution {
+    /// The factor the resolution is multiplied by during a resize, between `0.0` and `1.0`. Values
+    /// outside of that range disable the reduction.
+    pub scale: f32,
+    /// How long the window's size needs to stay the same for the resize to be considered finished.
+    pub settle_time: Duration,
+}
+
+impl Default for ResizeResolution {
+    fn default() -> Self {
+        Self {
+            scale: 0.5,
+            settle_time: Duration::from_millis(150),
+        }
+    }
+}
+
+/// When to release the renderer's caches for a hidden window, see
+/// [`IcedBaseviewSettings::idle_trim`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IdleTrim {
+    /// How long the [`Application`][crate::Application] needs to have paused rendering before the
+    /// caches are released.
+    pub paused_for: Duration,
+    /// How long the window needs to go without being drawn before the caches are released. This
+    /// should be well above the time between two changes of an editor that's in use.
+    pub undrawn_for: Duration,
+}
+
+impl Default for IdleTrim {
+    fn default() -> Self {
+        Self {
+            paused_for: Duration::from_secs(1),
+            undrawn_for: Duration::from_secs(30),
+        }
+    }
+}
+
+/// A limit on the number of concurrently running tasks, see
+/// [`IcedBaseviewSettings::task_limit`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TaskLimit {
+    /// The maximum number of tasks that may run at the same time.
+    pub max_in_flight: usize,
+    /// What happens to tasks returned while the maximum number of tasks is running.
+    pub overflow: TaskOverflow,
+}
+
+/// What to do with a task that would exceed the [`TaskLimit`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TaskOverflow {
+    /// Start the task once another task has finished. Queued tasks are started in the order they
+    /// were returned in. The queue itself is not limited.
+    #[default]
+    Queue,
+    /// Drop the task without running it. A warning is logged when this happens.
+    Drop,
+}
+
+/// A high-water mark for the events waiting for the widgets, see
+/// [`IcedBaseviewSettings::event_queue_limit`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EventQueueLimit {
+    /// The number of events that can be waiting at the start of a frame before the `overflow`
+    /// policy kicks in. A warning is logged every time this is exceeded after having been below
+    /// it.
+    pub high_water_mark: usize,
+    /// What happens to the events while there are more than `high_water_mark` of them.
+    pub overflow: EventQueueOverflow,
+}
+
+/// What to do with the events above the [`EventQueueLimit`]'s high-water mark.
+///
+/// Only cursor movements that are directly followed by another cursor movement are ever removed,
+/// since the widgets only need to see the latest position before the next button press, key, or
+/// scroll. Those critical events, window events, and the application's messages are never dropped,
+/// so the queue can stay above the mark.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EventQueueOverflow {
+    /// Only log the warning.
+    #[default]
+    Warn,
+    /// Remove every superseded cursor movement, even if that brings the queue well below the mark.
+    Coalesce,
+    /// Remove superseded cursor movements, oldest first, until the queue is back at the mark.
+    DropOldest,
+}
+
+bitflags::bitflags! {
+    /// The categories of baseview events forwarded to iced, see
+    /// [`IcedBaseviewSettings::event_filter`].
+    ///
+    /// The runtime still keeps track of the window's size, scale factor, and cursor position when
+    /// their events are filtered out, so the window keeps rendering at the right size. Filtering
+    /// out keyboard events also means widgets won't see modifier key changes.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct EventFilter: u8 {
+        /// Cursor movement, mouse buttons, and scrolling.
+        const MOUSE = 1 << 0;
+        /// Key presses and releases, including modifier keys.
+        const KEYBOARD = 1 << 1;
+        /// Resizing, focus, and the other window events.
+        const WINDOW = 1 << 2;
+        /// Touch events. baseview doesn't report touch input yet, so this currently has no effect.
+        const TOUCH = 1 << 3;
+    }
+}
+
+impl EventFilter {
+    /// Whether `event` should be forwarded to iced.
+    pub fn allows(&self, event: &baseview::Event) -> bool {
+        let category = match event {
+            baseview::Event::Mouse(_) => Self::MOUSE,
+            baseview::Event::Keyboard(_) => Self::KEYBOARD,
+            baseview::Event::Window(_) => Self::WINDOW,
+        };
+
+        self.contains(category)
+    }
+}
+
+impl Default for EventFilter {
+    fn default() -> Self {
+        Self::all()
+    }
+}
+
+impl IcedBaseviewSettings {
+    /// The default value for [`boost_linger`][Self::boost_linger].
+    pub const DEFAULT_BOOST_LINGER: Duration = Duration::from_millis(300);
+
+    /// Shrink a logical window size so it fits in the [`display_size`][Self::display_size], if
+    /// that's set and [`clamp_to_display`][Self::clamp_to_display] is enabled.
+    pub fn clamp_window_size(&self, size: crate::core::Size) -> crate::core::Size {
+        match self.display_size {
+            Some(display_size) if self.clamp_to_display => crate::core::Size::new(
+                size.width.min(display_size.width),
+                size.height.min(display_size.height),
+            ),
+            _ => size,
+        }
+    }
+}
+
+impl Default for IcedBaseviewSettings {
+    fn default() -> Self {
+        Self {
+            ignore_non_modifier_keys: false,
+            always_redraw: false,
+            allow_child_window: true,
+            interaction: InteractionSettings::default(),
+            boost_during_interaction: false,
+            boost_linger: Self::DEFAULT_BOOST_LINGER,
+            momentum_scroll_damping: 0.0,
+            event_filter: EventFilter::default(),
+            task_limit: None,
+            event_queue_limit: None,
+            resize_resolution: None,
+            idle_trim: None,
+            snap_text_to_pixel: true,
+            display_size: None,
+            clamp_to_display: true,
+            #[cfg(feature = "trace")]
+            trace_file: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn event_filter_categories() {
+        let mouse = baseview::Event::Mouse(baseview::MouseEvent::CursorLeft);
+        let keyboard = baseview::Event::Keyboard(keyboard_types::KeyboardEvent::default());
+        let window = baseview::Event::Window(baseview::WindowEvent::Focused);
+
+        let filter = IcedBaseviewSettings::default().event_filter;
+        assert!(filter.allows(&mouse) && filter.allows(&keyboard) && filter.allows(&window));
+
+        let filter = EventFilter::all() - EventFilter::KEYBOARD;
+        assert!(filter.allows(&mouse));
+        assert!(!filter.allows(&keyboard));
+        assert!(filter.allows(&window));
+
+        let filter = EventFilter::empty();
+        assert!(!filter.allows(&mouse) && !filter.allows(&keyboard) && !filter.allows(&window));
+    }
+
+    #[test]
+    fn clamp_to_display() {
+        use crate::core::Size;
+
+        let mut settings = IcedBaseviewSettings::default();
+        let oversized = Size::new(1920.0, 1200.0);
+        assert_eq!(settings.clamp_window_size(oversized), oversized);
+
+        // A size saved on a larger display is shrunk to fit on a 1280x720 display
+        settings.display_size = Some(Size::new(1280.0, 720.0));
+        assert_eq!(
+            settings.clamp_window_size(oversized),
+            Size::new(1280.0, 720.0)
+        );
+        assert_eq!(
+            settings.clamp_window_size(Size::new(1000.0, 900.0)),
+            Size::new(1000.0, 720.0)
+        );
+        assert_eq!(
+            settings.clamp_window_size(Size::new(640.0, 480.0)),
+            Size::new(640.0, 480.0)
+        );
+
+        settings.clamp_to_display = false;
+        assert_eq!(settings.clamp_window_size(oversized), oversized);
+    }
 }
@@ -0,0 +1,408 @@
+//! Create runtime tasks.
+
+use std::thread;
+use std::time::Duration;
+
+use std::any::Any;
+
+use crate::core::widget::operation::{self, Outcome};
+use crate::core::widget::{Id, Operation};
+use crate::core::{Rectangle, Vector};
+use crate::futures::futures::channel::oneshot;
+use crate::futures::futures::{future, stream, StreamExt};
+use crate::futures::MaybeSend;
+use crate::runtime::Action;
+
+pub use crate::runtime::task::{Handle, Task};
+
+/// A [`Task`] that produces `message` once `delay` has passed, for instance to hide a panel again
+/// after a while. This saves writing a subscription for a one-off timer.
+///
+/// Like any other task, this runs on the application's executor and the message is delivered to
+/// `update()`. The waiting itself happens on a short-lived thread so this works the same with
+/// every executor. Every call schedules its own independent timer, and rebuilding the view doesn't
+/// affect it. Use [`Task::abortable()`] to be able to cancel the message. Pending messages are
+/// dropped when the window closes.
+pub fn after<T>(delay: Duration, message: T) -> Task<T>
+where
+    T: MaybeSend + 'static,
+{
+    let (sender, receiver) = oneshot::channel();
+    let spawned = thread::Builder::new()
+        .name(String::from("nih_plug_iced timer"))
+        .spawn(move || {
+            thread::sleep(delay);
+
+            // The receiving end is gone if the task was aborted or the window was closed
+            let _ = sender.send(message);
+        });
+    if let Err(err) = spawned {
+        log::error!("Could not spawn a timer thread, the delayed message is dropped: {err}");
+        return Task::none();
+    }
+
+    Task::stream(stream::once(receiver).filter_map(|result| future::ready(result.ok())))
+}
+
+/// A [`Task`] that applies several widget operations in a single pass over the widget tree, for
+/// instance to restore the focused text field and the scroll positions of a few scrollables when
+/// an editor is reopened. Returning the operations as separate tasks would traverse the whole
+/// widget tree once per operation.
+///
+/// See [`OperationBatch`] for the order the operations are applied in.
+///
+/// ```ignore
+/// use nih_plug_iced::core::widget::operation::{focusable, scrollable};
+///
+/// task::operate_batch([
+///     Box::new(focusable::focus(self.focused.clone())) as Box<dyn Operation>,
+///     Box::new(scrollable::scroll_to(LIST_ID.clone(), self.list_offset)),
+/// ])
+/// ```
+pub fn operate_batch<T>(operations: impl IntoIterator<Item = Box<dyn Operation>>) -> Task<T> {
+    crate::runtime::task::effect(Action::Widget(Box::new(OperationBatch::new(operations))))
+}
+
+/// Applies several [`Operation`]s in a single pass over the widget tree. See [`operate_batch()`].
+///
+/// The operations visit the widgets in the same order as they would on their own. Every widget is
+/// offered to the operations in the order they were added to the batch, so when two operations
+/// change the same widget the later one wins, just like when they run one after the other. An
+/// operation that skips a widget's children doesn't see them in the batch either.
+///
+/// Some operations need more than one pass, like
+/// [`focus_next()`][operation::focusable::focus_next()] which first counts the focusable widgets.
+/// Such an operation returns a follow-up operation when it finishes. The follow-ups of all
+/// operations in the batch are applied together in the next pass, again in batch order, so
+/// operations that need more passes fall back to being applied after the single-pass ones.
+#[allow(missing_debug_implementations)]
+pub struct OperationBatch {
+    operations: Vec<Box<dyn Operation>>,
+}
+
+impl OperationBatch {
+    /// Batch the operations, in the order they should be applied.
+    pub fn new(operations: impl IntoIterator<Item = Box<dyn Operation>>) -> Self {
+        Self {
+            operations: operations.into_iter().collect(),
+        }
+    }
+}
+
+impl Operation for OperationBatch {
+    fn traverse(&mut self, operate: &mut dyn FnMut(&mut dyn Operation)) {
+        let mut operations: Vec<&mut dyn Operation> = self
+            .operations
+            .iter_mut()
+            .map(|operation| operation.as_mut() as &mut dyn Operation)
+            .collect();
+
+        traverse_each(&mut operations, Vec::new(), operate);
+    }
+
+    fn container(&mut self, id: Option<&Id>, bounds: Rectangle) {
+        for operation in &mut self.operations {
+            operation.container(id, bounds);
+        }
+    }
+
+    fn scrollable(
+        &mut self,
+        id: Option<&Id>,
+        bounds: Rectangle,
+        content_bounds: Rectangle,
+        translation: Vector,
+        state: &mut dyn operation::Scrollable,
+    ) {
+        for operation in &mut self.operations {
+            operation.scrollable(id, bounds, content_bounds, translation, state);
+        }
+    }
+
+    fn focusable(
+        &mut self,
+        id: Option<&Id>,
+        bounds: Rectangle,
+        state: &mut dyn operation::Focusable,
+    ) {
+        for operation in &mut self.operations {
+            operation.focusable(id, bounds, state);
+        }
+    }
+
+    fn text_input(
+        &mut self,
+        id: Option<&Id>,
+        bounds: Rectangle,
+        state: &mut dyn operation::TextInput,
+    ) {
+        for operation in &mut self.operations {
+            operation.text_input(id, bounds, state);
+        }
+    }
+
+    fn text(&mut self, id: Option<&Id>, bounds: Rectangle, text: &str) {
+        for operation in &mut self.operations {
+            operation.text(id, bounds, text);
+        }
+    }
+
+    fn custom(&mut self, id: Option<&Id>, bounds: Rectangle, state: &mut dyn Any) {
+        for operation in &mut self.operations {
+            operation.custom(id, bounds, state);
+        }
+    }
+
+    fn finish(&self) -> Outcome<()> {
+        let follow_ups: Vec<_> = self
+            .operations
+            .iter()
+            .filter_map(|operation| match operation.finish() {
+                Outcome::Chain(next) => Some(next),
+                Outcome::None | Outcome::Some(()) => None,
+            })
+            .collect();
+
+        if follow_ups.is_empty() {
+            Outcome::None
+        } else {
+            Outcome::Chain(Box::new(OperationBatch::new(follow_ups)))
+        }
+    }
+}
+
+/// The operations in a batch that descend into a widget's children. Widget callbacks are
+/// forwarded the same way as in [`OperationBatch`].
+struct Descending<'a> {
+    operations: Vec<&'a mut dyn Operation>,
+}
+
+impl Operation for Descending<'_> {
+    fn traverse(&mut self, operate: &mut dyn FnMut(&mut dyn Operation)) {
+        traverse_each(&mut self.operations, Vec::new(), operate);
+    }
+
+    fn container(&mut self, id: Option<&Id>, bounds: Rectangle) {
+        for operation in &mut self.operations {
+            operation.container(id, bounds);
+        }
+    }
+
+    fn scrollable(
+        &mut self,
+        id: Option<&Id>,
+        bounds: Rectangle,
+        content_bounds: Rectangle,
+        translation: Vector,
+        state: &mut dyn operation::Scrollable,
+    ) {
+        for operation in &mut self.operations {
+            operation.scrollable(id, bounds, content_bounds, translation, state);
+        }
+    }
+
+    fn focusable(
+        &mut self,
+        id: Option<&Id>,
+        bounds: Rectangle,
+        state: &mut dyn operation::Focusable,
+    ) {
+        for operation in &mut self.operations {
+            operation.focusable(id, bounds, state);
+        }
+    }
+
+    fn text_input(
+        &mut self,
+        id: Option<&Id>,
+        bounds: Rectangle,
+        state: &mut dyn operation::TextInput,
+    ) {
+        for operation in &mut self.operations {
+            operation.text_input(id, bounds, state);
+        }
+    }
+
+    fn text(&mut self, id: Option<&Id>, bounds: Rectangle, text: &str) {
+        for operation in &mut self.operations {
+            operation.text(id, bounds, text);
+        }
+    }
+
+    fn custom(&mut self, id: Option<&Id>, bounds: Rectangle, state: &mut dyn Any) {
+        for operation in &mut self.operations {
+            operation.custom(id, bounds, state);
+        }
+    }
+}
+
+/// Asks each of the `remaining` operations whether it wants to descend into the current widget's
+/// children, and then descends once with all operations that do. An operation may hand a different
+/// operation to its children, which is what ends up in `descending`.
+fn traverse_each(
+    remaining: &mut [&mut dyn Operation],
+    descending: Vec<&mut dyn Operation>,
+    operate: &mut dyn FnMut(&mut dyn Operation),
+) {
+    let Some((operation, remaining)) = remaining.split_first_mut() else {
+        if !descending.is_empty() {
+            operate(&mut Descending {
+                operations: descending,
+            });
+        }
+
+        return;
+    };
+
+    let mut descending = Some(descending);
+    operation.traverse(&mut |child_operation| {
+        let mut operations: Vec<&mut dyn Operation> = descending
+            .take()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|operation| operation as &mut dyn Operation)
+            .collect();
+        operations.push(child_operation);
+
+        traverse_each(remaining, operations, operate);
+    });
+
+    if let Some(descending) = descending {
+        traverse_each(remaining, descending, operate);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::widget::operation::scrollable::AbsoluteOffset;
+    use std::sync::Arc;
+    use std::task::{Context, Poll, Wake};
+    use std::time::Instant;
+
+    struct ThreadWaker(thread::Thread);
+
+    impl Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    #[test]
+    fn delayed_messages_arrive_after_the_delay() {
+        let start = Instant::now();
+        let mut stream = crate::runtime::task::into_stream(after(Duration::from_millis(50), 42))
+            .expect("The task should produce a message");
+
+        let waker = Arc::new(ThreadWaker(thread::current())).into();
+        let mut context = Context::from_waker(&waker);
+        let message = loop {
+            match stream.poll_next_unpin(&mut context) {
+                Poll::Ready(Some(Action::Output(message))) => break message,
+                Poll::Ready(_) => panic!("The task should produce a message"),
+                Poll::Pending => thread::park(),
+            }
+        };
+
+        assert_eq!(message, 42);
+        assert!(start.elapsed() >= Duration::from_millis(50));
+    }
+
+    #[derive(Default)]
+    struct Field {
+        focused: bool,
+    }
+
+    impl operation::Focusable for Field {
+        fn is_focused(&self) -> bool {
+            self.focused
+        }
+
+        fn focus(&mut self) {
+            self.focused = true;
+        }
+
+        fn unfocus(&mut self) {
+            self.focused = false;
+        }
+    }
+
+    #[derive(Default)]
+    struct List {
+        offset: AbsoluteOffset<Option<f32>>,
+    }
+
+    impl operation::Scrollable for List {
+        fn snap_to(&mut self, _offset: operation::scrollable::RelativeOffset<Option<f32>>) {}
+
+        fn scroll_to(&mut self, offset: AbsoluteOffset<Option<f32>>) {
+            self.offset = offset;
+        }
+
+        fn scroll_by(&mut self, _offset: AbsoluteOffset, _bounds: Rectangle, _content: Rectangle) {}
+    }
+
+    /// Two text fields next to a scrollable, operated on the way the widgets would.
+    #[derive(Default)]
+    struct Tree {
+        name: Field,
+        search: Field,
+        list: List,
+        passes: usize,
+    }
+
+    impl Tree {
+        /// Applies the operation, including its follow-ups, the same way `run_action()` does.
+        fn run(&mut self, operation: Box<dyn Operation>) {
+            let mut current_operation = Some(operation);
+            while let Some(mut operation) = current_operation.take() {
+                self.operate(operation.as_mut());
+                if let Outcome::Chain(next) = operation.finish() {
+                    current_operation = Some(next);
+                }
+            }
+        }
+
+        fn operate(&mut self, operation: &mut dyn Operation) {
+            self.passes += 1;
+
+            let bounds = Rectangle::default();
+            operation.container(None, bounds);
+            operation.traverse(&mut |operation| {
+                operation.focusable(Some(&Id::new("name")), bounds, &mut self.name);
+                operation.focusable(Some(&Id::new("search")), bounds, &mut self.search);
+
+                let id = Id::new("list");
+                operation.scrollable(Some(&id), bounds, bounds, Vector::ZERO, &mut self.list);
+                operation.traverse(&mut |_| {});
+            });
+        }
+    }
+
+    #[test]
+    fn batched_operations_share_a_pass() {
+        let offset = AbsoluteOffset {
+            x: None,
+            y: Some(40.0),
+        };
+
+        let mut tree = Tree::default();
+        tree.run(Box::new(OperationBatch::new([
+            Box::new(operation::focusable::focus(Id::new("search"))) as Box<dyn Operation>,
+            Box::new(operation::scrollable::scroll_to(Id::new("list"), offset)),
+        ])));
+        assert_eq!(tree.passes, 1);
+        assert!(!tree.name.focused && tree.search.focused);
+        assert_eq!(tree.list.offset, offset);
+
+        // `focus_next()` needs to count the fields first, so it takes a second pass
+        let mut tree = Tree::default();
+        tree.run(Box::new(OperationBatch::new([
+            Box::new(operation::focusable::focus_next()) as Box<dyn Operation>,
+            Box::new(operation::scrollable::scroll_to(Id::new("list"), offset)),
+        ])));
+        assert_eq!(tree.passes, 2);
+        assert!(tree.name.focused && !tree.search.focused);
+        assert_eq!(tree.list.offset, offset);
+    }
+}
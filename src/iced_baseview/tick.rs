@@ -0,0 +1,184 @@
+//! A per-frame tick stage for widgets with time-dependent state, like meter ballistics and peak
+//! hold timers.
+//!
+//! A widget's `draw()` can run more than once per frame, for instance when a resize lays out the
+//! user interface again and draws it a second time, and it doesn't run at all when the runtime
+//! skips a frame. State that advances with time therefore doesn't belong in `draw()`. Widgets
+//! implementing [`FrameTick`] advance that state in [`FrameTick::tick()`] instead, and `draw()`
+//! only reads it. They hook into the stage by calling [`operate()`] from their
+//! [`Widget::operate()`][crate::core::Widget::operate()] implementation.
+//!
+//! The runtime runs the stage exactly once for every frame it draws, right before drawing the
+//! widgets, and passes every widget the same frame time. `tick()` receives the time instead of
+//! measuring it, so ballistics should be computed from the time elapsed since the last tick rather
+//! than assuming a fixed frame rate.
+
+use std::time::Instant;
+
+use crate::core::widget::{Operation, Tree};
+use crate::core::Rectangle;
+
+/// A widget with state that advances once per frame.
+pub trait FrameTick {
+    /// Advance the widget's time-dependent state to `now`, the time of the frame that's about to be
+    /// drawn. This is called exactly once per drawn frame.
+    fn tick(&mut self, tree: &mut Tree, now: Instant);
+}
+
+/// Tick `widget` if `operation` is the runtime's tick stage. Call this from the widget's
+/// `operate()` implementation.
+pub fn operate<W: FrameTick + ?Sized>(
+    widget: &mut W,
+    tree: &mut Tree,
+    bounds: Rectangle,
+    operation: &mut dyn Operation,
+) {
+    let mut request = TickRequest(None);
+    operation.custom(None, bounds, &mut request);
+
+    if let Some(now) = request.0 {
+        widget.tick(tree, now);
+    }
+}
+
+/// The payload [`operate()`] passes to [`Operation::custom()`]. Only [`RunTicks`] fills in the
+/// frame time, every other operation leaves it alone.
+struct TickRequest(Option<Instant>);
+
+/// The tick stage. Running this operation on a user interface ticks every [`FrameTick`] widget in
+/// it.
+#[derive(Debug)]
+pub(crate) struct RunTicks {
+    now: Instant,
+}
+
+impl RunTicks {
+    pub fn new(now: Instant) -> Self {
+        Self { now }
+    }
+}
+
+impl Operation for RunTicks {
+    fn traverse(&mut self, operate: &mut dyn FnMut(&mut dyn Operation)) {
+        operate(self);
+    }
+
+    fn custom(
+        &mut self,
+        _id: Option<&crate::core::widget::Id>,
+        _bounds: Rectangle,
+        state: &mut dyn std::any::Any,
+    ) {
+        if let Some(request) = state.downcast_mut::<TickRequest>() {
+            request.0 = Some(self.now);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::widget::tree;
+    use crate::core::{layout, mouse, renderer, Color, Element, Layout, Length, Size, Widget};
+    use crate::runtime::user_interface::{Cache, UserInterface};
+    use crate::Theme;
+    use std::cell::Cell;
+    use std::time::Duration;
+
+    /// Counts its ticks, and remembers the tick count it was last drawn with.
+    struct Ballistics<'a> {
+        drawn: &'a Cell<Option<usize>>,
+    }
+
+    #[derive(Default)]
+    struct State {
+        ticks: usize,
+        last_tick: Option<Instant>,
+    }
+
+    impl Widget<(), Theme, ()> for Ballistics<'_> {
+        fn tag(&self) -> tree::Tag {
+            tree::Tag::of::<State>()
+        }
+
+        fn state(&self) -> tree::State {
+            tree::State::new(State::default())
+        }
+
+        fn size(&self) -> Size<Length> {
+            Size::new(Length::Fill, Length::Fill)
+        }
+
+        fn layout(
+            &mut self,
+            _tree: &mut Tree,
+            _renderer: &(),
+            limits: &layout::Limits,
+        ) -> layout::Node {
+            layout::Node::new(limits.max())
+        }
+
+        fn operate(
+            &mut self,
+            tree: &mut Tree,
+            layout: Layout<'_>,
+            _renderer: &(),
+            operation: &mut dyn Operation,
+        ) {
+            operate(self, tree, layout.bounds(), operation);
+        }
+
+        fn draw(
+            &self,
+            tree: &Tree,
+            _renderer: &mut (),
+            _theme: &Theme,
+            _style: &renderer::Style,
+            _layout: Layout<'_>,
+            _cursor: mouse::Cursor,
+            _viewport: &Rectangle,
+        ) {
+            self.drawn
+                .set(Some(tree.state.downcast_ref::<State>().ticks));
+        }
+    }
+
+    impl FrameTick for Ballistics<'_> {
+        fn tick(&mut self, tree: &mut Tree, now: Instant) {
+            let state = tree.state.downcast_mut::<State>();
+            // Every tick is for a new frame
+            assert!(state.last_tick.is_none_or(|last_tick| now > last_tick));
+            state.ticks += 1;
+            state.last_tick = Some(now);
+        }
+    }
+
+    #[test]
+    fn ticks_once_per_frame() {
+        let start = Instant::now();
+        let drawn = Cell::new(None);
+        let style = renderer::Style {
+            text_color: Color::BLACK,
+        };
+
+        let mut cache = Cache::default();
+        for frame in 1..=3 {
+            let view = Element::new(Ballistics { drawn: &drawn });
+            let mut interface = UserInterface::build(view, Size::new(100.0, 100.0), cache, &mut ());
+
+            // The runtime's frame, followed by a resize drawing the same frame again
+            let now = start + Duration::from_millis(16 * frame as u64);
+            interface.operate(&(), &mut RunTicks::new(now));
+            interface.draw(&mut (), &Theme::Dark, &style, mouse::Cursor::Unavailable);
+            assert_eq!(drawn.get(), Some(frame));
+
+            let mut interface = interface.relayout(Size::new(200.0, 100.0), &mut ());
+            interface.draw(&mut (), &Theme::Dark, &style, mouse::Cursor::Unavailable);
+            assert_eq!(drawn.get(), Some(frame));
+
+            // Other operations don't tick the widget
+            interface.operate(&(), &mut crate::hover::FindHovered::new((0.0, 0.0).into()));
+            cache = interface.into_cache();
+        }
+    }
+}
@@ -0,0 +1,118 @@
+//! Control over the cache used to rebuild the user interface.
+//!
+//! After every update the runtime rebuilds the user interface from the application's new view. The
+//! [`Cache`][crate::runtime::user_interface::Cache] of the previous user interface is passed along
+//! so the layout doesn't need to be recomputed from scratch when the view didn't change much.
+//!
+//! When the view does change drastically, for instance after loading a preset that shows a
+//! completely different set of controls, calling [`invalidate()`] from
+//! [`Application::update()`][crate::Application::update()] makes the runtime start the next
+//! rebuild from an empty cache instead. That rebuild happens right after the update, before the
+//! next frame is drawn. Invalidating the cache throws away work the runtime would otherwise reuse,
+//! so it should only be done when widgets are drawn in the wrong place otherwise, not on every
+//! update.
+//!
+//! With the `debug` feature enabled, [`stats()`] returns how often the cache has been reused.
+
+use std::cell::Cell;
+
+use crate::Task;
+
+thread_local! {
+    static INVALIDATED: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Make the runtime rebuild the user interface from an empty cache after the current update. The
+/// returned task doesn't do anything on its own, it can be batched with other tasks.
+///
+/// This must be called from [`Application::update()`][crate::Application::update()]. See the
+/// [module documentation][self].
+pub fn invalidate<T>() -> Task<T> {
+    INVALIDATED.with(|invalidated| invalidated.set(true));
+
+    Task::none()
+}
+
+/// Whether [`invalidate()`] was called since the last call. The runtime calls this after every
+/// update.
+pub(crate) fn take_invalidation() -> bool {
+    INVALIDATED.with(|invalidated| invalidated.replace(false))
+}
+
+/// How often the user interface was rebuilt with and without the previous cache. See [`stats()`].
+#[cfg(feature = "debug")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    /// The number of rebuilds that reused the previous user interface's cache.
+    pub hits: u64,
+    /// The number of times the user interface was built from an empty cache, including the first
+    /// build and rebuilds after [`invalidate()`].
+    pub rebuilds: u64,
+}
+
+#[cfg(feature = "debug")]
+impl CacheStats {
+    /// Count a build of the user interface.
+    pub(crate) fn record(&mut self, from_empty_cache: bool) {
+        if from_empty_cache {
+            self.rebuilds += 1;
+        } else {
+            self.hits += 1;
+        }
+    }
+}
+
+#[cfg(feature = "debug")]
+thread_local! {
+    static STATS: Cell<CacheStats> = const {
+        Cell::new(CacheStats {
+            hits: 0,
+            rebuilds: 0,
+        })
+    };
+}
+
+/// The [`CacheStats`] of the window whose messages are currently being handled. Outside of the
+/// runtime this returns the stats of the last window that was updated on this thread.
+#[cfg(feature = "debug")]
+pub fn stats() -> CacheStats {
+    STATS.with(Cell::get)
+}
+
+/// Set the stats returned by [`stats()`]. The runtime does this before every update.
+#[cfg(feature = "debug")]
+pub(crate) fn set_stats(stats: CacheStats) {
+    STATS.with(|current| current.set(stats));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn invalidation_is_taken_once() {
+        let _ = take_invalidation();
+
+        let _task = invalidate::<()>();
+        assert!(take_invalidation());
+        assert!(!take_invalidation());
+    }
+
+    #[cfg(feature = "debug")]
+    #[test]
+    fn stats_count_builds() {
+        let mut counted = CacheStats::default();
+        counted.record(true);
+        counted.record(false);
+        counted.record(false);
+        set_stats(counted);
+
+        assert_eq!(
+            stats(),
+            CacheStats {
+                hits: 2,
+                rebuilds: 1,
+            }
+        );
+    }
+}
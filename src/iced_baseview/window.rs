@@ -1,4 +1,11 @@
-use std::{cell::RefCell, pin::Pin, rc::Rc, sync::Arc};
+use std::{
+    cell::{Cell, RefCell},
+    panic::{self, AssertUnwindSafe},
+    path::PathBuf,
+    pin::Pin,
+    rc::Rc,
+    sync::{Arc, Mutex},
+};
 
 use iced_graphics::Compositor;
 pub use iced_runtime::core::window::Id;
@@ -12,20 +19,34 @@ use iced_runtime::futures::futures::{
     channel::mpsc::{self, SendError},
 };
 use iced_runtime::Task;
-use iced_widget::core::Size;
+use iced_widget::core::{Point, Size};
 use raw_window_handle::{HasRawWindowHandle, RawWindowHandle};
 
+use super::proxy::Wakeup;
 use crate::{
     application::{run, Application, DefaultStyle},
-    Renderer, Settings,
+    child_window::{ChildWindowHandler, ChildWindowSettings},
+    clipboard::ClipboardWatch,
+    hover::HoveredWidget,
+    Error, Renderer, Settings,
 };
 
 pub enum RuntimeEvent<Message: 'static + Send> {
     Baseview((baseview::Event, bool)),
     UserEvent(iced_runtime::Action<Message>),
     MainEventsCleared,
+    /// Like [`MainEventsCleared`][Self::MainEventsCleared], but sent right after an input event
+    /// while a drag is boosted. This does not count as a frame for the
+    /// [`WindowSubs::on_frame`] callback.
+    InteractionFrame,
     RedrawRequested,
     WillClose,
+    /// An event for the child window, see the [`child_window`][crate::child_window] module.
+    ChildBaseview(baseview::Event),
+    /// The child window has been created.
+    ChildOpened(crate::conversion::WindowWrapper),
+    /// The child window has been closed by the user or the platform.
+    ChildClosed,
 }
 
 pub(crate) struct IcedWindow<A>
@@ -40,8 +61,18 @@ where
     pub runtime_rx: mpsc::UnboundedReceiver<iced_runtime::Action<A::Message>>,
     pub window_queue_rx: mpsc::UnboundedReceiver<WindowCommand>,
     pub event_status: Rc<RefCell<EventStatus>>,
+    /// Set by the runtime while input events should be drawn immediately, see
+    /// [`IcedBaseviewSettings::boost_during_interaction`][crate::settings::IcedBaseviewSettings::boost_during_interaction].
+    pub interaction_boost: Rc<Cell<bool>>,
+    /// The optional child window, see the [`child_window`][crate::child_window] module.
+    pub child_window: Option<baseview::WindowHandle>,
 
     pub processed_close_signal: bool,
+    /// Set once the run loop has stopped, which happens when the window is closing. The run loop
+    /// must not be polled again after that.
+    pub finished: bool,
+    /// Set when tasks or subscriptions sent something through `runtime_rx`.
+    pub wakeup: Wakeup,
 }
 
 impl<A> IcedWindow<A>
@@ -62,45 +93,91 @@ where
     /// Open a new window that blocks the current thread until the window is destroyed.
     ///
     /// * `settings` - The settings of the window.
-    pub fn open_blocking<C>(flags: A::Flags, settings: Settings)
+    pub fn open_blocking<C>(flags: A::Flags, mut settings: Settings) -> crate::Result
     where
-        C: Compositor<Renderer = Renderer> + 'static,
+        C: Compositor<Renderer = Renderer> + Send + 'static,
     {
         let (sender, receiver) = mpsc::unbounded();
-
-        Window::open_blocking(
-            Self::clone_window_options(&settings.window),
-            move |window: &mut baseview::Window<'_>| -> IcedWindow<A> {
-                run::<A, C>(window, flags, settings, sender, receiver).expect("Launch window")
-            },
-        );
+        Self::clamp_initial_size(&mut settings);
+
+        let failure = Arc::new(Mutex::new(None));
+        let build_failure = failure.clone();
+        panic::catch_unwind(AssertUnwindSafe(|| {
+            Window::open_blocking(
+                Self::clone_window_options(&settings.window),
+                move |window: &mut baseview::Window<'_>| -> OpenedWindow<A> {
+                    OpenedWindow::launch(
+                        run::<A, C>(window, flags, settings, sender, receiver),
+                        &build_failure,
+                    )
+                },
+            )
+        }))
+        .map_err(|_| Error::WindowCreationFailed)?;
+
+        let error = failure.lock().unwrap().take();
+        match error {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
     }
 
     /// Open a new child window.
     ///
     /// * `parent` - The parent window.
     /// * `settings` - The settings of the window.
+    ///
+    /// Baseview creates the window before the application is started in it, so if the application
+    /// fails to start, then the empty window is closed again and the error is returned. Baseview
+    /// panics when it can't create the window itself, which is returned as
+    /// [`Error::WindowCreationFailed`].
     pub fn open_parented<W, C>(
         parent: &W,
         flags: A::Flags,
-        settings: Settings,
-    ) -> WindowHandle<A::Message>
+        mut settings: Settings,
+    ) -> Result<WindowHandle<A::Message>, Error>
     where
         W: HasRawWindowHandle,
-        C: Compositor<Renderer = Renderer> + 'static,
+        C: Compositor<Renderer = Renderer> + Send + 'static,
     {
         let (sender, receiver) = mpsc::unbounded();
+        Self::clamp_initial_size(&mut settings);
         let sender_clone = sender.clone();
 
-        let bv_handle = Window::open_parented(
-            parent,
-            Self::clone_window_options(&settings.window),
-            move |window: &mut baseview::Window<'_>| -> IcedWindow<A> {
-                run::<A, C>(window, flags, settings, sender_clone, receiver).expect("Launch window")
-            },
-        );
+        // Baseview calls the build closure before `open_parented()` returns on every platform
+        let failure = Arc::new(Mutex::new(None));
+        let build_failure = failure.clone();
+        let mut bv_handle = panic::catch_unwind(AssertUnwindSafe(|| {
+            Window::open_parented(
+                parent,
+                Self::clone_window_options(&settings.window),
+                move |window: &mut baseview::Window<'_>| -> OpenedWindow<A> {
+                    OpenedWindow::launch(
+                        run::<A, C>(window, flags, settings, sender_clone, receiver),
+                        &build_failure,
+                    )
+                },
+            )
+        }))
+        .map_err(|_| Error::WindowCreationFailed)?;
+
+        if let Some(error) = failure.lock().unwrap().take() {
+            bv_handle.close();
+            return Err(error);
+        }
+
+        Ok(WindowHandle::new(bv_handle, sender))
+    }
 
-        WindowHandle::new(bv_handle, sender)
+    /// A restored window size may not fit on the current display, see
+    /// [`IcedBaseviewSettings::clamp_to_display`][crate::settings::IcedBaseviewSettings::clamp_to_display].
+    fn clamp_initial_size(settings: &mut Settings) {
+        let size = settings.iced_baseview.clamp_window_size(Size::new(
+            settings.window.size.width as f32,
+            settings.window.size.height as f32,
+        ));
+
+        settings.window.size = baseview::Size::new(size.width as f64, size.height as f64);
     }
 
     fn drain_window_commands(&mut self, window: &mut Window<'_>) {
@@ -137,6 +214,68 @@ where
                 WindowCommand::SetCursorIcon(cursor) => {
                     window.set_mouse_cursor(cursor);
                 }
+                WindowCommand::OpenChildWindow(settings, scale) => {
+                    self.close_child_window();
+
+                    let sender = self.sender.clone();
+                    self.child_window = Some(Window::open_parented(
+                        window,
+                        WindowOpenOptions {
+                            title: settings.title,
+                            size: baseview::Size::new(
+                                settings.size.width as f64,
+                                settings.size.height as f64,
+                            ),
+                            scale,
+                        },
+                        move |window: &mut baseview::Window<'_>| {
+                            ChildWindowHandler::new(window, sender)
+                        },
+                    ));
+                }
+                WindowCommand::CloseChildWindow => {
+                    self.close_child_window();
+                }
+            }
+        }
+    }
+
+    /// Let the run loop handle the events that were sent to it. This blocks until it's waiting for
+    /// the next event again, or until it has stopped because the window is closing.
+    fn poll_instance(&mut self) {
+        if self.finished {
+            return;
+        }
+
+        if self
+            .instance
+            .as_mut()
+            .poll(&mut self.runtime_context)
+            .is_ready()
+        {
+            self.finished = true;
+        }
+    }
+
+    /// Hand the actions produced by tasks and subscriptions to the run loop.
+    fn send_actions(&mut self) {
+        let _ = self.wakeup.take();
+        while let Ok(Some(action)) = self.runtime_rx.try_next() {
+            self.send(RuntimeEvent::UserEvent(action));
+        }
+    }
+
+    /// Send an event to the run loop, unless it has already stopped.
+    fn send(&mut self, event: RuntimeEvent<A::Message>) {
+        if !self.finished {
+            self.sender.start_send(event).expect("Send event");
+        }
+    }
+
+    fn close_child_window(&mut self) {
+        if let Some(mut child_window) = self.child_window.take() {
+            if child_window.is_open() {
+                child_window.close();
             }
         }
     }
@@ -150,60 +289,66 @@ where
     <A as Application>::Flags: std::marker::Send,
 {
     fn on_frame(&mut self, window: &mut Window<'_>) {
-        if self.processed_close_signal {
+        // When the application closed the window itself, the run loop has already stopped
+        if self.processed_close_signal || self.finished {
             return;
         }
 
         // Flush all messages. This will block until the instance is finished.
-        let _ = self.instance.as_mut().poll(&mut self.runtime_context);
+        self.poll_instance();
 
         // Poll subscriptions and send the corresponding messages.
-        while let Ok(Some(message)) = self.runtime_rx.try_next() {
-            self.sender
-                .start_send(RuntimeEvent::UserEvent(message))
-                .expect("Send event");
-        }
+        self.send_actions();
 
         // Send the event to the instance.
-        self.sender
-            .start_send(RuntimeEvent::MainEventsCleared)
-            .expect("Send event");
+        self.send(RuntimeEvent::MainEventsCleared);
 
         // Send event to render the frame.
-        self.sender
-            .start_send(RuntimeEvent::RedrawRequested)
-            .expect("Send event");
+        self.send(RuntimeEvent::RedrawRequested);
 
         // Flush all messages. This will block until the instance is finished.
-        let _ = self.instance.as_mut().poll(&mut self.runtime_context);
+        self.poll_instance();
 
         self.drain_window_commands(window);
     }
 
     fn on_event(&mut self, window: &mut Window<'_>, event: Event) -> EventStatus {
-        if self.processed_close_signal {
+        if self.processed_close_signal || self.finished {
             return EventStatus::Ignored;
         }
 
         let status = if requests_exit(&event) {
             self.processed_close_signal = true;
+            self.close_child_window();
 
-            self.sender
-                .start_send(RuntimeEvent::WillClose)
-                .expect("Send event");
+            self.send(RuntimeEvent::WillClose);
 
             // Flush all messages so the application receives the close event. This will block until the instance is finished.
-            let _ = self.instance.as_mut().poll(&mut self.runtime_context);
+            self.poll_instance();
 
             EventStatus::Ignored
         } else {
             // Send the event to the instance.
-            self.sender
-                .start_send(RuntimeEvent::Baseview((event, true)))
-                .expect("Send event");
+            self.send(RuntimeEvent::Baseview((event, true)));
 
             // Flush all messages so the application receives the event. This will block until the instance is finished.
-            let _ = self.instance.as_mut().poll(&mut self.runtime_context);
+            self.poll_instance();
+
+            // Messages from tasks and subscriptions that arrived since the last frame are handled
+            // right away, see `Wakeup`
+            let woken = self.wakeup.take();
+            if woken {
+                self.send_actions();
+            }
+
+            // While a widget is being dragged, draw the result right away instead of waiting for
+            // the next frame
+            if self.interaction_boost.get() || woken {
+                self.send(RuntimeEvent::InteractionFrame);
+                self.send(RuntimeEvent::RedrawRequested);
+
+                self.poll_instance();
+            }
 
             // TODO: make this Copy
             *self.event_status.borrow()
@@ -217,6 +362,58 @@ where
     }
 }
 
+/// The window handler baseview runs. If the application could not be started, then this closes the
+/// empty window again.
+pub(crate) enum OpenedWindow<A>
+where
+    A: Application + Send + 'static,
+{
+    Running(IcedWindow<A>),
+    Failed,
+}
+
+impl<A> OpenedWindow<A>
+where
+    A: Application + Send + 'static,
+    <A as Application>::Theme: DefaultStyle,
+    <A as Application>::Executor: iced_runtime::futures::Executor + 'static,
+    <A as Application>::Flags: std::marker::Send,
+{
+    /// Handle the outcome of starting the application in the new window. Errors are stored in
+    /// `failure` so the code that opened the window can return them.
+    fn launch(launched: Result<IcedWindow<A>, Error>, failure: &Mutex<Option<Error>>) -> Self {
+        match launched {
+            Ok(window) => OpenedWindow::Running(window),
+            Err(error) => {
+                *failure.lock().unwrap() = Some(error);
+                OpenedWindow::Failed
+            }
+        }
+    }
+}
+
+impl<A> WindowHandler for OpenedWindow<A>
+where
+    A: Application + Send + 'static,
+    <A as Application>::Theme: DefaultStyle,
+    <A as Application>::Executor: iced_runtime::futures::Executor + 'static,
+    <A as Application>::Flags: std::marker::Send,
+{
+    fn on_frame(&mut self, window: &mut Window<'_>) {
+        match self {
+            OpenedWindow::Running(iced_window) => iced_window.on_frame(window),
+            OpenedWindow::Failed => window.close(),
+        }
+    }
+
+    fn on_event(&mut self, window: &mut Window<'_>, event: Event) -> EventStatus {
+        match self {
+            OpenedWindow::Running(iced_window) => iced_window.on_event(window, event),
+            OpenedWindow::Failed => EventStatus::Ignored,
+        }
+    }
+}
+
 /// Closes the application window.
 pub fn close<T>() -> Task<T> {
     iced_runtime::window::close(Id::unique())
@@ -319,18 +516,28 @@ pub enum WindowCommand {
     ResizeWindow(crate::core::Size),
     Focus,
     SetCursorIcon(baseview::MouseCursor),
+    OpenChildWindow(ChildWindowSettings, baseview::WindowScalePolicy),
+    CloseChildWindow,
 }
 
 /// Used to request things from the `baseview` window.
 pub struct WindowQueue {
     tx: mpsc::UnboundedSender<WindowCommand>,
+    /// Set once [`close_window()`][Self::close_window()] has been called.
+    close_requested: bool,
 }
 
 impl WindowQueue {
     pub fn new() -> (Self, mpsc::UnboundedReceiver<WindowCommand>) {
         let (tx, rx) = mpsc::unbounded();
 
-        (Self { tx }, rx)
+        (
+            Self {
+                tx,
+                close_requested: false,
+            },
+            rx,
+        )
     }
 
     /// Resize the current application window.
@@ -338,11 +545,19 @@ impl WindowQueue {
         self.tx.start_send(WindowCommand::ResizeWindow(size))
     }
 
-    /// Quit the current application and close the window.
+    /// Quit the current application and close the window. The run loop shuts down the
+    /// application the same way as when the host closes the window, see
+    /// [`WindowSubs::on_window_will_close`].
     pub fn close_window(&mut self) -> Result<(), SendError> {
+        self.close_requested = true;
         self.tx.start_send(WindowCommand::CloseWindow)
     }
 
+    /// Whether [`close_window()`][Self::close_window()] has been called.
+    pub(crate) fn is_close_requested(&self) -> bool {
+        self.close_requested
+    }
+
     /// Request to focus the application window.
     pub fn focus(&mut self) -> Result<(), SendError> {
         self.tx.start_send(WindowCommand::Focus)
@@ -351,18 +566,125 @@ impl WindowQueue {
     pub fn set_mouse_cursor(&mut self, cursor: baseview::MouseCursor) -> Result<(), SendError> {
         self.tx.start_send(WindowCommand::SetCursorIcon(cursor))
     }
+
+    /// Open the child window, closing the previous one if it's still open. This is normally
+    /// managed by the runtime, see the [`child_window`][crate::child_window] module.
+    pub(crate) fn open_child_window(
+        &mut self,
+        settings: ChildWindowSettings,
+        scale: baseview::WindowScalePolicy,
+    ) -> Result<(), SendError> {
+        self.tx
+            .start_send(WindowCommand::OpenChildWindow(settings, scale))
+    }
+
+    /// Close the child window if it is open.
+    pub(crate) fn close_child_window(&mut self) -> Result<(), SendError> {
+        self.tx.start_send(WindowCommand::CloseChildWindow)
+    }
 }
 
 /// This struct creates subscriptions for common window events.
+///
+/// The callbacks are reset before every call to
+/// [`Application::subscription()`][crate::Application::subscription()], so they only stay active
+/// for as long as the application keeps setting them. Returning `None` from a callback suppresses
+/// the task, but the runtime will still wake up for the event. Unset a callback instead if it's
+/// not needed for a while, for instance to stop `on_frame` messages while no meters are visible.
+///
+/// The callbacks return a [`Task`], so a single event can lead to several messages or to other
+/// work like a widget operation. [`Task::done()`] sends a single message. The messages a task
+/// produces right away, like the ones from [`Task::done()`] and chains or batches of those, are
+/// handled in the same frame, after the messages that were already waiting and in the order the
+/// callbacks were called in. Everything else runs on the executor like the tasks returned from
+/// [`Application::update()`][crate::Application::update()], and counts towards the
+/// [`task_limit`][crate::settings::IcedBaseviewSettings::task_limit].
+///
+/// Callbacks that return a message, as they did before callbacks returned tasks, can be set with
+/// the `*_message()` functions instead:
+///
+/// ```ignore
+/// // Before
+/// window_subs.on_frame = Some(Arc::new(|| Some(Message::Frame)));
+/// // After
+/// window_subs.on_frame = Some(Arc::new(|| Some(Task::done(Message::Frame))));
+/// // Or
+/// window_subs.on_frame_message(|| Some(Message::Frame));
+/// ```
 #[allow(missing_debug_implementations)]
 pub struct WindowSubs<Message> {
-    /// The message to send right before each rendering frame.
-    pub on_frame: Option<Arc<dyn Fn() -> Option<Message>>>,
-    /// The message to send when the window is about to close.
-    pub on_window_will_close: Option<Arc<dyn Fn() -> Option<Message>>>,
-    /// The message to send when the window is resized.
+    /// The task to run right before each rendering frame.
+    pub on_frame: Option<Arc<dyn Fn() -> Option<Task<Message>>>>,
+    /// The task to run when the window is about to close. This runs exactly once, both when the
+    /// host closes the window and when the application closes it with [`close()`] or an exit
+    /// task. Tasks that are still running at this point are cancelled before the task's messages
+    /// are handled. Tasks returned in response to those messages still run, but their messages are
+    /// discarded since the window is gone by then.
+    pub on_window_will_close: Option<Arc<dyn Fn() -> Option<Task<Message>>>>,
+    /// The task to run when the window is resized.
     /// The function receives the new size in logical pixels.
-    pub on_resize: Option<Arc<dyn Fn(Size) -> Option<Message>>>,
+    pub on_resize: Option<Arc<dyn Fn(Size) -> Option<Task<Message>>>>,
+    /// Polls the clipboard and sends a message when it starts or stops containing something that
+    /// can be pasted. See the [`clipboard`][crate::clipboard] module. Unlike the other callbacks,
+    /// this one still produces a message.
+    pub on_clipboard_change: Option<ClipboardWatch<Message>>,
+    /// The task to run when the cursor moves onto or off of a widget with an id. The function
+    /// receives the new hovered widget, or `None` if the cursor isn't over any. See the
+    /// [`hover`][crate::hover] module.
+    pub on_hover_changed: Option<Arc<dyn Fn(Option<HoveredWidget>) -> Option<Task<Message>>>>,
+    /// The task to run when the cursor has moved. The function receives the cursor position in
+    /// the same logical coordinates as the widget layout, or `None` once the cursor has left the
+    /// window. This is checked once per frame, so a burst of mouse movement results in a single
+    /// call with the latest position. Only the main window's cursor is reported.
+    pub on_cursor_moved: Option<Arc<dyn Fn(Option<Point>) -> Option<Task<Message>>>>,
+    /// The task to run when files are dropped onto the window and no widget handled them. The
+    /// function receives the paths of the dropped files that weren't captured by a widget. Widgets
+    /// see a drop as one [`FileDropped`][iced_runtime::core::window::Event::FileDropped] event per
+    /// file, and a widget that captures one of those events keeps that file from being passed
+    /// here.
+    pub on_files_dropped: Option<Arc<dyn Fn(Vec<PathBuf>) -> Option<Task<Message>>>>,
+}
+
+impl<Message: Send + 'static> WindowSubs<Message> {
+    /// Set [`on_frame`][Self::on_frame] to a callback that returns a message.
+    pub fn on_frame_message(&mut self, f: impl Fn() -> Option<Message> + 'static) {
+        self.on_frame = Some(Arc::new(move || f().map(Task::done)));
+    }
+
+    /// Set [`on_window_will_close`][Self::on_window_will_close] to a callback that returns a
+    /// message.
+    pub fn on_window_will_close_message(&mut self, f: impl Fn() -> Option<Message> + 'static) {
+        self.on_window_will_close = Some(Arc::new(move || f().map(Task::done)));
+    }
+
+    /// Set [`on_resize`][Self::on_resize] to a callback that returns a message.
+    pub fn on_resize_message(&mut self, f: impl Fn(Size) -> Option<Message> + 'static) {
+        self.on_resize = Some(Arc::new(move |size| f(size).map(Task::done)));
+    }
+
+    /// Set [`on_hover_changed`][Self::on_hover_changed] to a callback that returns a message.
+    pub fn on_hover_changed_message(
+        &mut self,
+        f: impl Fn(Option<HoveredWidget>) -> Option<Message> + 'static,
+    ) {
+        self.on_hover_changed = Some(Arc::new(move |hovered| f(hovered).map(Task::done)));
+    }
+
+    /// Set [`on_cursor_moved`][Self::on_cursor_moved] to a callback that returns a message.
+    pub fn on_cursor_moved_message(
+        &mut self,
+        f: impl Fn(Option<Point>) -> Option<Message> + 'static,
+    ) {
+        self.on_cursor_moved = Some(Arc::new(move |position| f(position).map(Task::done)));
+    }
+
+    /// Set [`on_files_dropped`][Self::on_files_dropped] to a callback that returns a message.
+    pub fn on_files_dropped_message(
+        &mut self,
+        f: impl Fn(Vec<PathBuf>) -> Option<Message> + 'static,
+    ) {
+        self.on_files_dropped = Some(Arc::new(move |paths| f(paths).map(Task::done)));
+    }
 }
 
 impl<Message> Default for WindowSubs<Message> {
@@ -371,6 +693,10 @@ impl<Message> Default for WindowSubs<Message> {
             on_frame: None,
             on_window_will_close: None,
             on_resize: None,
+            on_clipboard_change: None,
+            on_hover_changed: None,
+            on_cursor_moved: None,
+            on_files_dropped: None,
         }
     }
 }
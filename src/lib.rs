@@ -1,11 +1,12 @@
 //! [iced](https://github.com/iced-rs/iced) editor support for NIH plug.
 //!
 //! This integration requires you to pass your parameters to your editor object through the
-//! [`IcedEditor::InitializationFlags`], and to add a message type for your editor to handle
-//! parmater updates. This is a minimal example:
+//! [`IcedEditor::InitializationFlags`], and to route the [`ParamMessage`]s the parameter widgets
+//! emit to the editor with [`IcedEditor::param_message()`]. The [`prelude`] contains everything an
+//! editor typically needs. This is a minimal example:
 //!
 //! ```ignore
-//! use nih_plug_iced::*;
+//! use nih_plug_iced::prelude::*;
 //!
 //! pub(crate) fn default_state() -> Arc<IcedState> {
 //!     IcedState::from_size(200, 150)
@@ -15,37 +16,25 @@
 //!     params: Arc<FooParams>,
 //!     editor_state: Arc<IcedState>,
 //! ) -> Option<Box<dyn Editor>> {
-//!     create_iced_editor::<Foo>(editor_state, params)
+//!     create_iced_editor::<FooEditor>(editor_state, params, Vec::new())
 //! }
 //!
 //! struct FooEditor {
 //!     params: Arc<FooParams>,
 //!     context: Arc<dyn GuiContext>,
-//!
-//!     foo_slider_state: nih_widgets::param_slider::State,
-//! }
-//!
-//! #[derive(Debug, Clone, Copy)]
-//! enum Message {
-//!     /// Update a parameter's value.
-//!     ParamUpdate(nih_widgets::ParamMessage),
 //! }
 //!
 //! impl IcedEditor for FooEditor {
 //!     type Executor = executor::Default;
-//!     type Message = Message;
+//!     type Message = ParamMessage;
 //!     type InitializationFlags = Arc<FooParams>;
+//!     type Theme = Theme;
 //!
 //!     fn new(
 //!         params: Self::InitializationFlags,
 //!         context: Arc<dyn GuiContext>,
 //!     ) -> (Self, Task<Self::Message>) {
-//!         let editor = FooEditor {
-//!             params,
-//!             context,
-//!
-//!             foo_slider_state: Default::default(),
-//!         };
+//!         let editor = FooEditor { params, context };
 //!
 //!         (editor, Task::none())
 //!     }
@@ -54,44 +43,43 @@
 //!         self.context.as_ref()
 //!     }
 //!
-//!     fn update(
-//!         &mut self,
-//!         _window: &mut WindowQueue,
-//!         message: Self::Message,
-//!     ) -> Task<Self::Message> {
-//!         match message {
-//!             Message::ParamUpdate(message) => self.handle_param_message(message),
-//!         }
+//!     fn param_message(message: &ParamMessage) -> Option<ParamMessage> {
+//!         Some(*message)
+//!     }
 //!
+//!     fn update(&mut self, _message: Self::Message) -> Task<Self::Message> {
 //!         Task::none()
 //!     }
 //!
-//!     fn view(&mut self) -> Element<'_, Self::Message> {
+//!     fn view(&self) -> Element<'_, Self::Message> {
 //!         Column::new()
-//!             .align_items(Alignment::Center)
-//!             .push(
-//!                 Text::new("Foo")
-//!                     .height(20.into())
-//!                     .width(Length::Fill)
-//!                     .horizontal_alignment(alignment::Horizontal::Center)
-//!                     .vertical_alignment(alignment::Vertical::Center),
-//!             )
-//!             .push(
-//!                 nih_widgets::ParamSlider::new(
-//!                     &mut self.foo_slider_state,
-//!                     &self.params.foo,
-//!                     self.context.as_ref(),
-//!                 )
-//!                 .map(Message::ParamUpdate),
-//!             )
+//!             .align_x(Center)
+//!             .push(text("Foo").height(20).width(Fill).center())
+//!             .push(ParamSlider::new(&self.params.foo))
 //!             .into()
 //!     }
+//!
+//!     fn theme(&self) -> Self::Theme {
+//!         Theme::Dark
+//!     }
+//! }
+//! ```
+//!
+//! Editors with messages of their own wrap [`ParamMessage`] in one of their variants, map the
+//! widgets to it with `.map(Message::ParamUpdate)`, and return the wrapped message from
+//! `param_message()`:
+//!
+//! ```ignore
+//! fn param_message(message: &Message) -> Option<ParamMessage> {
+//!     match message {
+//!         Message::ParamUpdate(message) => Some(*message),
+//!         _ => None,
+//!     }
 //! }
 //! ```
 
 use ::baseview::WindowScalePolicy;
 use crossbeam::atomic::AtomicCell;
-use crossbeam::channel;
 use nih_plug::params::persist::PersistentField;
 use nih_plug::prelude::{Editor, GuiContext};
 use serde::{Deserialize, Serialize};
@@ -100,10 +88,13 @@ use std::borrow::Cow;
 // `hidden_glob_reexports`
 pub use std::fmt::Debug;
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
+use crate::automation::AutomationWrite;
+use crate::gui_channel::{GuiChannels, GuiClosed};
 use crate::iced_baseview::futures::Subscription;
-use crate::widgets::ParamMessage;
+use crate::param_updates::{ParamUpdateStats, ParamUpdateSuppression, ParameterUpdate};
+use crate::widgets::ParamBoundsMap;
 
 // Internal iced_baseview module (merged from separate crate)
 pub mod iced_baseview;
@@ -113,8 +104,18 @@ pub mod iced_baseview;
 #[doc(no_inline)]
 pub use iced_baseview::*;
 
+pub use crate::widgets::ParamMessage;
+
+pub mod about;
 pub mod assets;
+pub mod automation;
+pub mod comm;
 mod editor;
+pub mod gui_channel;
+pub mod param_batch;
+pub mod param_updates;
+pub mod prelude;
+pub mod theme;
 pub mod widgets;
 mod wrapper;
 
@@ -133,28 +134,11 @@ pub fn create_iced_editor<E: IcedEditor>(
     initialization_flags: E::InitializationFlags,
     fonts: Vec<Cow<'static, [u8]>>,
 ) -> Option<Box<dyn Editor>> {
-    // We need some way to communicate parameter changes to the `IcedEditor` since parameter updates
-    // come from outside of the editor's reactive model. This contains only capacity to store only
-    // one parameter update, since we're only storing _that_ a parameter update has happened and not
-    // which parameter so we'd need to redraw the entire GUI either way.
-    let (parameter_updates_sender, parameter_updates_receiver) = channel::bounded(1);
-
-    Some(Box::new(editor::IcedEditorWrapper::<E> {
+    Some(Box::new(editor::IcedEditorWrapper::<E>::new(
         iced_state,
         initialization_flags,
-
-        // iced_baseview with iced 0.13 properly handles scale factors through WindowScalePolicy,
-        // but we still need to be careful about host-provided scaling vs system scaling.
-        // Some hosts (like Ableton Live) may provide their own scale factors that conflict
-        // with baseview's scaling. When a host provides a scale factor, we use it explicitly.
-        // Otherwise, we let iced_baseview use the system scale factor, which it now handles
-        // correctly on all platforms.
-        scaling_factor: AtomicCell::new(None),
-
-        parameter_updates_sender,
-        parameter_updates_receiver: Arc::new(parameter_updates_receiver),
         fonts,
-    }))
+    )))
 }
 
 /// A plugin editor using `iced`. This wraps around [`Application`] with the only change being that
@@ -165,7 +149,8 @@ pub fn create_iced_editor<E: IcedEditor>(
 pub trait IcedEditor: 'static + Send + Sync + Sized {
     /// See [`Application::Executor`]. You'll likely want to use [`crate::executor::Default`].
     type Executor: Executor;
-    /// See [`Application::Message`]. You should have one variant containing a [`ParamMessage`].
+    /// See [`Application::Message`]. This should either be [`ParamMessage`] itself or have one
+    /// variant containing a [`ParamMessage`], see [`param_message()`][Self::param_message()].
     type Message: 'static + Clone + Debug + Send;
     /// See [`Application::Flags`].
     type InitializationFlags: 'static + Clone + Send + Sync;
@@ -183,11 +168,40 @@ pub trait IcedEditor: 'static + Send + Sync + Sized {
     /// parameters.
     fn context(&self) -> &dyn GuiContext;
 
-    /// See [`Application::update`]. When receiving the variant that contains a
-    /// [`widgets::ParamMessage`] you can call
-    /// [`handle_param_message()`][Self::handle_param_message()] to handle the parameter update.
+    /// See [`Application::update`]. Messages that [`param_message()`][Self::param_message()]
+    /// recognizes never reach this function. Editors that don't route them that way can call
+    /// [`handle_param_message()`][Self::handle_param_message()] here instead.
     fn update(&mut self, message: Self::Message) -> Task<Self::Message>;
 
+    /// The [`ParamMessage`] contained in `message`, if any. These messages are passed to
+    /// [`handle_param_message()`][Self::handle_param_message()] instead of
+    /// [`update()`][Self::update()], so the editor doesn't need to handle parameter updates itself.
+    /// They still go through [`pre_update()`][Self::pre_update()] first. An editor that uses
+    /// [`ParamMessage`] as its [`Message`][Self::Message] can return `Some(*message)`.
+    ///
+    /// By default, this returns `None` and every message is passed to `update()`.
+    fn param_message(_message: &Self::Message) -> Option<ParamMessage> {
+        None
+    }
+
+    /// See [`Application::pre_update`]. Returning `false` drops the message before it reaches
+    /// [`update()`][Self::update()]. Parameter update notifications are handled internally and are
+    /// not passed to this function.
+    fn pre_update(&mut self, _message: &Self::Message) -> bool {
+        true
+    }
+
+    /// Called for every parameter change the host reported since the last time the editor was
+    /// updated, right before the editor is redrawn with the parameters' current values. Editors
+    /// with expensive views can use this to only recompute the state that depends on the parameters
+    /// that changed. Changes are not reported while the window is closed. See the
+    /// [`param_updates`] module.
+    ///
+    /// By default, this does nothing.
+    fn handle_param_update(&mut self, _update: &ParameterUpdate) -> Task<Self::Message> {
+        Task::none()
+    }
+
     /// See [`Application::subscription`].
     fn subscription(
         &self,
@@ -199,6 +213,48 @@ pub trait IcedEditor: 'static + Send + Sync + Sized {
     /// See [`Application::view`].
     fn view(&self) -> Element<'_, Self::Message, Self::Theme, Renderer>;
 
+    /// See [`Application::child_view`]. Keep in mind that many hosts don't handle additional plugin
+    /// windows well, so the editor should be able to show this in its main window as well.
+    fn child_view(&self) -> Option<Element<'_, Self::Message, Self::Theme, Renderer>> {
+        None
+    }
+
+    /// See [`Application::interaction_settings`]. Use this to, for instance, allow more time for
+    /// double clicks.
+    fn interaction_settings(&self) -> Option<InteractionSettings> {
+        None
+    }
+
+    /// See [`Application::event_filter`]. Use this to, for instance, keep keyboard events away from
+    /// a widget that does its own input handling.
+    fn event_filter(&self) -> Option<EventFilter> {
+        None
+    }
+
+    /// See [`Application::should_exit`]. Use this to close the editor's window from
+    /// [`update()`][Self::update()]. The host may open the editor again later.
+    fn should_exit(&self) -> bool {
+        false
+    }
+
+    /// The [`Keymap`] used to translate key presses to messages before the widgets see them. See
+    /// the [`keymap`] module.
+    fn keymap(&self) -> Option<&Keymap<Self::Message>> {
+        None
+    }
+
+    /// See [`Application::child_window_settings`].
+    fn child_window_settings(&self) -> ChildWindowSettings {
+        ChildWindowSettings::default()
+    }
+
+    /// See [`Application::child_window_status_changed`].
+    fn child_window_status_changed(&mut self, _status: ChildWindowStatus) {}
+
+    /// Called when the plugin reports that the host started or stopped writing automation. See the
+    /// [`automation`] module.
+    fn automation_write_changed(&mut self, _state: AutomationWrite) {}
+
     /// See [`Application::background_color`].
     fn background_color(&self) -> Color {
         Color::WHITE
@@ -218,7 +274,8 @@ pub trait IcedEditor: 'static + Send + Sync + Sized {
         WindowScalePolicy::SystemScaleFactor
     }
 
-    /// Handle a parameter update using the GUI context.
+    /// Handle a parameter update using the GUI context. This is called for every message
+    /// [`param_message()`][Self::param_message()] recognizes.
     fn handle_param_message(&self, message: ParamMessage) {
         // We can't use the fancy ParamSetter here because this needs to be type erased
         let context = self.context();
@@ -241,6 +298,38 @@ pub struct IcedState {
     /// Whether the editor's window is currently open.
     #[serde(skip)]
     open: AtomicBool,
+    /// The scale factor the editor's window is currently rendered at.
+    #[serde(skip, default = "default_scale_factor")]
+    pub(crate) scale_factor: AtomicCell<f32>,
+    /// Holds back parameter update notifications while parameters are being dragged.
+    #[serde(skip)]
+    pub(crate) param_update_suppression: ParamUpdateSuppression,
+    /// Whether the host is writing automation, as reported by the plugin.
+    #[serde(skip)]
+    automation_write: AtomicCell<AutomationWrite>,
+    /// Whether the editor's render loop is paused, see [`IcedState::pause_rendering()`].
+    #[serde(skip)]
+    rendering_paused: AtomicBool,
+    /// Whether the editor's parameter widgets ignore input, see [`IcedState::input_lock()`].
+    #[serde(skip)]
+    input_lock: Arc<AtomicBool>,
+    /// The room the window has on the display, see [`IcedState::set_display_size()`].
+    #[serde(skip)]
+    display_size: AtomicCell<Option<(u32, u32)>>,
+    /// The editor's [`gui_channel()`][gui_channel::gui_channel()] subscriptions, see
+    /// [`IcedState::send_to_gui()`].
+    #[serde(skip)]
+    pub(crate) gui_channels: Arc<GuiChannels>,
+    /// Where the parameter widgets are drawn, see [`IcedState::param_bounds()`].
+    #[serde(skip)]
+    param_bounds: ParamBoundsMap,
+    /// Why the editor's window failed to open, see [`IcedState::last_open_error()`].
+    #[serde(skip)]
+    pub(crate) last_open_error: Mutex<Option<Arc<Error>>>,
+}
+
+fn default_scale_factor() -> AtomicCell<f32> {
+    AtomicCell::new(1.0)
 }
 
 impl<'a> PersistentField<'a, IcedState> for Arc<IcedState> {
@@ -263,6 +352,15 @@ impl IcedState {
         Arc::new(IcedState {
             size: AtomicCell::new((width, height)),
             open: AtomicBool::new(false),
+            scale_factor: default_scale_factor(),
+            param_update_suppression: Default::default(),
+            automation_write: Default::default(),
+            rendering_paused: AtomicBool::new(false),
+            input_lock: Arc::new(AtomicBool::new(false)),
+            display_size: AtomicCell::new(None),
+            gui_channels: Default::default(),
+            param_bounds: Default::default(),
+            last_open_error: Default::default(),
         })
     }
 
@@ -277,12 +375,169 @@ impl IcedState {
         self.size.store((width, height));
     }
 
+    /// Tell the editor how much room its window has on the display, as a `(width, height)` pair in
+    /// logical pixels. Baseview can't find this out by itself. When the editor is opened, a saved
+    /// size that doesn't fit is shrunk to fit, and resizes requested by the editor are limited to
+    /// this size. See
+    /// [`IcedBaseviewSettings::clamp_to_display`][crate::settings::IcedBaseviewSettings::clamp_to_display].
+    pub fn set_display_size(&self, width: u32, height: u32) {
+        self.display_size.store(Some((width, height)));
+    }
+
+    /// The display size set with [`set_display_size()`][Self::set_display_size()], if any.
+    pub fn display_size(&self) -> Option<(u32, u32)> {
+        self.display_size.load()
+    }
+
+    /// The size the window is opened at, which is [`size()`][Self::size()] shrunk to fit in the
+    /// [`display_size()`][Self::display_size()].
+    pub(crate) fn clamped_size(&self) -> (u32, u32) {
+        let (width, height) = self.size();
+        match self.display_size() {
+            Some((display_width, display_height)) => {
+                (width.min(display_width), height.min(display_height))
+            }
+            None => (width, height),
+        }
+    }
+
+    /// The scale factor used to convert between the logical coordinates iced works with and the
+    /// window's physical pixels. This is updated whenever the window's scale factor changes, and it
+    /// is `1.0` until the window is opened for the first time.
+    pub fn scale_factor(&self) -> f32 {
+        self.scale_factor.load()
+    }
+
+    /// Converts a logical [`Point`], [`Size`], or [`Rectangle`] to physical coordinates using the
+    /// window's current scale factor. See the [`scale`] module for the rounding behavior.
+    pub fn to_physical<T: ScaleConversion>(&self, value: T) -> T {
+        value.to_physical(self.scale_factor())
+    }
+
+    /// Converts a physical [`Point`], [`Size`], or [`Rectangle`] to logical coordinates using the
+    /// window's current scale factor. See the [`scale`] module for the rounding behavior.
+    pub fn to_logical<T: ScaleConversion>(&self, value: T) -> T {
+        value.to_logical(self.scale_factor())
+    }
+
+    /// Hold back the redraws caused by parameter changes until
+    /// [`end_param_update_suppression()`][Self::end_param_update_suppression()] is called. This
+    /// already happens automatically while a parameter widget is being dragged. Calls can be
+    /// nested. See the [`param_updates`] module for more information.
+    pub fn begin_param_update_suppression(&self) {
+        self.param_update_suppression.begin();
+    }
+
+    /// End a suppression started with
+    /// [`begin_param_update_suppression()`][Self::begin_param_update_suppression()]. If any
+    /// parameter updates were held back and this was the last active suppression, then the editor
+    /// is updated once.
+    pub fn end_param_update_suppression(&self) {
+        self.param_update_suppression.end();
+    }
+
+    /// How many parameter update notifications have been sent to the editor, and how many were
+    /// folded into one that was already queued. See the [`param_updates`] module.
+    pub fn param_update_stats(&self) -> ParamUpdateStats {
+        self.param_update_suppression.stats()
+    }
+
+    /// Whether parameter update redraws are currently being held back.
+    pub fn param_updates_suppressed(&self) -> bool {
+        self.param_update_suppression.is_active()
+    }
+
+    /// Whether the host is writing automation. This is [`AutomationWrite::Unknown`] unless the
+    /// plugin reports the state with [`set_automation_write()`][Self::set_automation_write()].
+    pub fn automation_write(&self) -> AutomationWrite {
+        self.automation_write.load()
+    }
+
+    /// Report whether the host is writing automation. NIH-plug doesn't provide this information, so
+    /// the plugin needs to find out in some other way. The editor is updated when the state
+    /// changes, even while a parameter is being dragged. This does not block, so it can be called
+    /// from any thread. See the [`automation`] module.
+    pub fn set_automation_write(&self, state: AutomationWrite) {
+        if self.automation_write.swap(state) != state {
+            self.param_update_suppression.notify_unsuppressed();
+        }
+    }
+
+    /// Pause the editor's render loop. The window stays open, but the editor is no longer updated
+    /// or drawn, and mouse and keyboard input is dropped. Messages sent to the editor in the
+    /// meantime are handled after [`resume_rendering()`][Self::resume_rendering()] is called. See
+    /// [`Application::is_rendering_paused()`] for the details.
+    ///
+    /// This is useful for hosts that offload rendering or to save power while the editor is known
+    /// to be hidden. Closing the editor resumes rendering so the next window starts out drawn. This
+    /// does not block, so it can be called from any thread.
+    pub fn pause_rendering(&self) {
+        self.rendering_paused.store(true, Ordering::Release);
+    }
+
+    /// Resume a render loop paused with [`pause_rendering()`][Self::pause_rendering()]. The editor
+    /// is updated and redrawn on the next frame.
+    pub fn resume_rendering(&self) {
+        self.rendering_paused.store(false, Ordering::Release);
+    }
+
+    /// Whether the editor's render loop is paused.
+    pub fn is_rendering_paused(&self) -> bool {
+        self.rendering_paused.load(Ordering::Acquire)
+    }
+
+    /// The switch that makes the editor read-only. While it's set, the built-in parameter widgets
+    /// keep showing live values but ignore input, and a drag that's in progress ends right away.
+    /// See [`Application::is_input_locked()`] for the details.
+    ///
+    /// This is shared, so the plugin can store it and lock the editor from any thread, for instance
+    /// when a preset is locked or when the host reports the project as read-only. Unlike
+    /// [`pause_rendering()`][Self::pause_rendering()], the lock stays set when the editor is closed
+    /// and reopened.
+    pub fn input_lock(&self) -> Arc<AtomicBool> {
+        self.input_lock.clone()
+    }
+
+    /// Lock or unlock the editor's parameter widgets. See [`input_lock()`][Self::input_lock()].
+    pub fn set_input_locked(&self, locked: bool) {
+        self.input_lock.store(locked, Ordering::Release);
+    }
+
+    /// Whether the editor's parameter widgets are locked. See [`input_lock()`][Self::input_lock()].
+    pub fn is_input_locked(&self) -> bool {
+        self.input_lock.load(Ordering::Acquire)
+    }
+
+    /// Send `value` to the open editor, which receives it through its `gui_channel::<T>()`
+    /// subscription. This can be called from any thread except for the audio thread. If the window
+    /// is closed or the editor is not subscribed to `T`, then the value is dropped and
+    /// [`GuiClosed`] is returned. See the [`gui_channel`] module.
+    pub fn send_to_gui<T: Send + 'static>(&self, value: T) -> std::result::Result<(), GuiClosed> {
+        if !self.is_open() {
+            return Err(GuiClosed);
+        }
+
+        self.gui_channels.send(value)
+    }
+
+    /// Where the parameter widgets built with `.report_bounds(true)` were last drawn. Pass this to
+    /// a [`HighlightOverlay`][widgets::HighlightOverlay] to highlight the controls for a parameter.
+    /// See the [`param_bounds`][widgets::param_bounds] module.
+    pub fn param_bounds(&self) -> ParamBoundsMap {
+        self.param_bounds.clone()
+    }
+
+    /// Why the editor's window could not be opened the last time the host tried to open it, if it
+    /// failed. This is cleared once the window opens. NIH-plug's [`Editor`] interface has no way to
+    /// tell the host that opening the editor failed, so the host will act as if the editor is open.
+    /// The error is also logged when it happens.
+    pub fn last_open_error(&self) -> Option<Arc<Error>> {
+        self.last_open_error.lock().unwrap().clone()
+    }
+
     /// Whether the GUI is currently visible.
     // Called `is_open()` instead of `open()` to avoid the ambiguity.
     pub fn is_open(&self) -> bool {
         self.open.load(Ordering::Acquire)
     }
 }
-
-/// A marker struct to indicate that a parameter update has happened.
-pub(crate) struct ParameterUpdate;
@@ -0,0 +1,209 @@
+//! Setting many parameters at once, for instance when loading a preset from the editor.
+//!
+//! Setting a parameter from the GUI normally means beginning a gesture, setting the value, and
+//! ending the gesture again. Doing that for every parameter in a preset makes some hosts record
+//! an undo step per parameter. A [`ParamBatch`] groups the changes instead. A gesture is begun the
+//! first time a parameter is set in the batch, setting the same parameter again only changes its
+//! value, and all gestures are ended together when the batch ends, in the order the parameters
+//! were first set. This also happens if the closure passed to [`ParamSetterBatch::batch()`]
+//! panics.
+//!
+//! Since the gestures overlap, the editor only receives a single parameter update once the batch
+//! has ended, see the [`param_updates`][crate::param_updates] module. NIH-plug's
+//! [`GuiContext`] doesn't have a way to tell the host that a preset is being loaded, so hosts
+//! still see the individual parameter changes.
+//!
+//! ```ignore
+//! fn load_preset(&self, preset: &Preset) {
+//!     ParamSetter::new(self.context()).batch(|batch| {
+//!         batch.set_parameter(&self.params.gain, preset.gain);
+//!         batch.set_parameter(&self.params.cutoff, preset.cutoff);
+//!         batch.set_parameter(&self.params.resonance, preset.resonance);
+//!     });
+//! }
+//! ```
+
+use nih_plug::prelude::{GuiContext, Param, ParamPtr, ParamSetter};
+
+/// Groups parameter changes so every parameter gets a single gesture. See the [module
+/// documentation][self].
+pub struct ParamBatch<'a> {
+    context: &'a dyn GuiContext,
+    /// The parameters with an active gesture, in the order they were first set.
+    touched: Vec<ParamPtr>,
+}
+
+/// Adds [`batch()`][Self::batch()] to NIH-plug's [`ParamSetter`].
+pub trait ParamSetterBatch {
+    /// Set many parameters with a single gesture per parameter. See the [module
+    /// documentation][self].
+    fn batch<R>(&self, f: impl FnOnce(&mut ParamBatch<'_>) -> R) -> R;
+}
+
+impl ParamSetterBatch for ParamSetter<'_> {
+    fn batch<R>(&self, f: impl FnOnce(&mut ParamBatch<'_>) -> R) -> R {
+        f(&mut ParamBatch::new(self.raw_context))
+    }
+}
+
+impl<'a> ParamBatch<'a> {
+    /// Start a batch. The gestures are ended when the batch is dropped. Prefer
+    /// [`ParamSetterBatch::batch()`], which makes it clear where the batch ends.
+    pub fn new(context: &'a dyn GuiContext) -> Self {
+        Self {
+            context,
+            touched: Vec::new(),
+        }
+    }
+
+    /// Set a parameter to a new plain value.
+    pub fn set_parameter<P: Param>(&mut self, param: &P, value: P::Plain) {
+        self.set_parameter_normalized(param, param.preview_normalized(value));
+    }
+
+    /// Set a parameter to a new normalized value.
+    pub fn set_parameter_normalized<P: Param>(&mut self, param: &P, normalized: f32) {
+        self.raw_set_parameter_normalized(param.as_ptr(), normalized);
+    }
+
+    /// Set a parameter to a new normalized value through its [`ParamPtr`]. Like with
+    /// [`GuiContext`]'s raw functions, the parameter needs to belong to the plugin.
+    pub fn raw_set_parameter_normalized(&mut self, param: ParamPtr, normalized: f32) {
+        if !self.touched.contains(&param) {
+            self.touched.push(param);
+            unsafe { self.context.raw_begin_set_parameter(param) };
+        }
+
+        unsafe { self.context.raw_set_parameter_normalized(param, normalized) };
+    }
+
+    /// The number of different parameters set in this batch.
+    pub fn len(&self) -> usize {
+        self.touched.len()
+    }
+
+    /// Whether no parameters have been set in this batch.
+    pub fn is_empty(&self) -> bool {
+        self.touched.is_empty()
+    }
+}
+
+impl Drop for ParamBatch<'_> {
+    fn drop(&mut self) {
+        for param in self.touched.drain(..) {
+            unsafe { self.context.raw_end_set_parameter(param) };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use nih_plug::prelude::{FloatParam, FloatRange, PluginApi, PluginState};
+
+    use super::*;
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    enum Call {
+        Begin(ParamPtr),
+        Set(ParamPtr, f32),
+        End(ParamPtr),
+    }
+
+    /// Records the parameter calls made through it.
+    #[derive(Default)]
+    struct MockContext {
+        calls: Mutex<Vec<Call>>,
+    }
+
+    // The parameter pointers are only compared, never dereferenced
+    unsafe impl Send for MockContext {}
+    unsafe impl Sync for MockContext {}
+
+    impl GuiContext for MockContext {
+        fn plugin_api(&self) -> PluginApi {
+            PluginApi::Clap
+        }
+
+        fn request_resize(&self) -> bool {
+            false
+        }
+
+        unsafe fn raw_begin_set_parameter(&self, param: ParamPtr) {
+            self.calls.lock().unwrap().push(Call::Begin(param));
+        }
+
+        unsafe fn raw_set_parameter_normalized(&self, param: ParamPtr, normalized: f32) {
+            self.calls
+                .lock()
+                .unwrap()
+                .push(Call::Set(param, normalized));
+        }
+
+        unsafe fn raw_end_set_parameter(&self, param: ParamPtr) {
+            self.calls.lock().unwrap().push(Call::End(param));
+        }
+
+        fn get_state(&self) -> PluginState {
+            unimplemented!()
+        }
+
+        fn set_state(&self, _state: PluginState) {
+            unimplemented!()
+        }
+    }
+
+    #[test]
+    fn one_gesture_per_parameter() {
+        let gain = FloatParam::new("Gain", 0.5, FloatRange::Linear { min: 0.0, max: 1.0 });
+        let cutoff = FloatParam::new("Cutoff", 0.5, FloatRange::Linear { min: 0.0, max: 1.0 });
+        let (gain_ptr, cutoff_ptr) = (gain.as_ptr(), cutoff.as_ptr());
+
+        let context = MockContext::default();
+        let touched = ParamSetter::new(&context).batch(|batch| {
+            batch.set_parameter(&cutoff, 0.1);
+            batch.set_parameter(&gain, 0.2);
+            batch.set_parameter(&cutoff, 0.3);
+            batch.set_parameter_normalized(&cutoff, 0.4);
+            batch.set_parameter(&gain, 0.5);
+
+            batch.len()
+        });
+        assert_eq!(touched, 2);
+
+        // Each parameter is begun once when it's first set, and the gestures end in the same order
+        assert_eq!(
+            *context.calls.lock().unwrap(),
+            [
+                Call::Begin(cutoff_ptr),
+                Call::Set(cutoff_ptr, 0.1),
+                Call::Begin(gain_ptr),
+                Call::Set(gain_ptr, 0.2),
+                Call::Set(cutoff_ptr, 0.3),
+                Call::Set(cutoff_ptr, 0.4),
+                Call::Set(gain_ptr, 0.5),
+                Call::End(cutoff_ptr),
+                Call::End(gain_ptr),
+            ]
+        );
+
+        // The gestures also end when the batch is abandoned halfway through
+        let context = MockContext::default();
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            ParamSetter::new(&context).batch(|batch| {
+                batch.set_parameter(&gain, 0.6);
+                panic!("failed to read the preset");
+            })
+        }));
+        assert!(result.is_err());
+        assert_eq!(
+            *context.calls.lock().unwrap(),
+            [
+                Call::Begin(gain_ptr),
+                Call::Set(gain_ptr, 0.6),
+                Call::End(gain_ptr),
+            ]
+        );
+    }
+}
@@ -0,0 +1,581 @@
+//! Suppressing parameter update redraws while a parameter is being dragged.
+//!
+//! Every parameter change reported by the host causes the editor to be updated and redrawn. While
+//! the user drags a control, most of those changes are echoes of the drag itself, and handling them
+//! only adds redundant updates. Parameter update notifications are therefore held back while a
+//! parameter gesture is active. This happens automatically for every widget that uses
+//! [`ParamMessage::BeginSetParameter`][crate::widgets::ParamMessage::BeginSetParameter] and
+//! [`ParamMessage::EndSetParameter`][crate::widgets::ParamMessage::EndSetParameter], and editors can
+//! do the same through [`IcedState::begin_param_update_suppression()`][crate::IcedState::begin_param_update_suppression()].
+//!
+//! Only the notifications are suppressed, and this applies to all parameters at once. The dragged
+//! widget still publishes its own messages, so its displayed value keeps following the drag. A
+//! single update is sent after the last gesture ends if any notifications were held back, so
+//! values changed by automation during the drag are shown again right away.
+//!
+//! The editor window is currently opened with
+//! [`always_redraw`][crate::settings::IcedBaseviewSettings::always_redraw] enabled, so it still
+//! redraws every frame. In that case only the parameter update messages themselves are avoided.
+//!
+//! ## Queue depth
+//!
+//! The editor reads the current values from the parameters themselves when it's updated, so a
+//! single notification is enough to show the latest values no matter how many parameter changes
+//! happened in between. The channel that carries them to the GUI therefore only has room for
+//! [`PARAMETER_UPDATES_CAPACITY`] notifications, and any further changes are folded into the one
+//! that's already queued. A GUI that stalls while automation keeps changing parameters, for
+//! instance behind a modal file dialog, catches up with a single update when it resumes. Sending
+//! never blocks or allocates, so the host can report changes from the audio thread.
+//! [`IcedState::param_update_stats()`][crate::IcedState::param_update_stats()] shows how many
+//! notifications have been folded into a queued one.
+//!
+//! Editors that only want to do work for the parameters that actually changed can implement
+//! [`IcedEditor::handle_param_update()`][crate::IcedEditor::handle_param_update()]. It's called
+//! with a [`ParameterUpdate`] for every change the host reported, right before the editor is
+//! redrawn. Those are stored separately in a queue with room for
+//! [`PARAMETER_UPDATE_QUEUE_CAPACITY`] updates, including the ones that are held back during a
+//! gesture. If more changes arrive before the editor handles them, then they are all replaced by a
+//! single [`ParameterUpdate::All`].
+//!
+//! ## Repeated values
+//!
+//! Widgets send a new value for every cursor movement during a drag, and many of those movements
+//! don't change the value, for instance when a stepped parameter is dragged by less than a step or
+//! when the cursor moves past the end of a slider. Setting a parameter goes through the host, which
+//! can be surprisingly expensive with dozens of controls on screen. While a parameter's gesture is
+//! active, setting the parameter to the value it was last set to during that gesture is therefore
+//! skipped. Every parameter is tracked separately, so overlapping gestures like the two axes of an
+//! XY pad are filtered as well. Values set outside of a gesture for that parameter are always
+//! passed on, since the host or automation may have changed the parameter in the meantime.
+
+use crossbeam::channel;
+use crossbeam::queue::ArrayQueue;
+use nih_plug::prelude::ParamPtr;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+/// The number of parameter update notifications that can be queued for the GUI. See the [module
+/// documentation][self].
+pub const PARAMETER_UPDATES_CAPACITY: usize = 1;
+
+/// The number of [`ParameterUpdate`]s that can be queued for
+/// [`IcedEditor::handle_param_update()`][crate::IcedEditor::handle_param_update()]. See the [module
+/// documentation][self].
+pub const PARAMETER_UPDATE_QUEUE_CAPACITY: usize = 256;
+
+/// How close a normalized value needs to be to the previously set value to count as the same value.
+const REPEATED_VALUE_EPSILON: f32 = 1.0e-6;
+
+/// Statistics about the parameter update notifications sent to an editor. See
+/// [`IcedState::param_update_stats()`][crate::IcedState::param_update_stats()].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ParamUpdateStats {
+    /// The number of notifications that were queued for the GUI.
+    pub sent: u64,
+    /// The number of notifications that were dropped because the queue was already full. The GUI
+    /// still shows the changes they were sent for when it handles the queued notification.
+    pub coalesced: u64,
+}
+
+/// A parameter change reported by the host, passed to
+/// [`IcedEditor::handle_param_update()`][crate::IcedEditor::handle_param_update()]. Parameters are
+/// identified by a hash of their string ID, see [`ParameterUpdate::hash_id()`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ParameterUpdate {
+    /// The parameter's value changed, for instance through automation or because the host set it.
+    Value {
+        /// The [hash][ParameterUpdate::hash_id()] of the parameter's ID.
+        id_hash: u32,
+        /// The parameter's new normalized value.
+        normalized_value: f32,
+    },
+    /// The parameter's monophonic modulation offset changed.
+    Modulation {
+        /// The [hash][ParameterUpdate::hash_id()] of the parameter's ID.
+        id_hash: u32,
+        /// The parameter's new normalized modulation offset.
+        modulation_offset: f32,
+    },
+    /// Any number of parameters may have changed, for instance after the host loaded a preset or
+    /// restored the plugin's state. This is also sent in place of the updates that didn't fit in
+    /// the queue.
+    All,
+}
+
+impl ParameterUpdate {
+    /// A hash of the parameter ID `id`, the one from the parameter's `#[id = "..."]` attribute.
+    /// This doesn't allocate, so the host can report changes from the audio thread. Editors can use
+    /// this to build a lookup table from the hashes to the parameters they care about. Different
+    /// IDs can share a hash, so in rare cases an update can also match another parameter.
+    pub fn hash_id(id: &str) -> u32 {
+        // 32-bit FNV-1a
+        id.bytes().fold(0x811c_9dc5, |hash: u32, byte| {
+            (hash ^ byte as u32).wrapping_mul(0x0100_0193)
+        })
+    }
+
+    /// Whether this update may concern the parameter with the ID `id`.
+    pub fn affects(&self, id: &str) -> bool {
+        match *self {
+            ParameterUpdate::Value { id_hash, .. }
+            | ParameterUpdate::Modulation { id_hash, .. } => id_hash == Self::hash_id(id),
+            ParameterUpdate::All => true,
+        }
+    }
+}
+
+/// Tracks whether parameter update notifications should currently be suppressed.
+#[derive(Debug)]
+pub(crate) struct ParamUpdateSuppression {
+    /// The number of active gestures and manual suppressions.
+    active: AtomicUsize,
+    /// Set when a notification was suppressed.
+    pending: AtomicBool,
+    /// Used to send the held back notification once the suppression ends.
+    sender: Mutex<Option<channel::Sender<()>>>,
+    /// The number of notifications sent to the GUI.
+    sent: AtomicU64,
+    /// The number of notifications dropped because the channel was full.
+    coalesced: AtomicU64,
+    /// The updates the editor has yet to handle.
+    updates: ArrayQueue<ParameterUpdate>,
+    /// Set when an update didn't fit in `updates`.
+    overflowed: AtomicBool,
+}
+
+impl Default for ParamUpdateSuppression {
+    fn default() -> Self {
+        Self {
+            active: AtomicUsize::new(0),
+            pending: AtomicBool::new(false),
+            sender: Mutex::new(None),
+            sent: AtomicU64::new(0),
+            coalesced: AtomicU64::new(0),
+            updates: ArrayQueue::new(PARAMETER_UPDATE_QUEUE_CAPACITY),
+            overflowed: AtomicBool::new(false),
+        }
+    }
+}
+
+impl ParamUpdateSuppression {
+    pub fn set_sender(&self, sender: channel::Sender<()>) {
+        *self.sender.lock().unwrap() = Some(sender);
+    }
+
+    pub fn begin(&self) {
+        self.active.fetch_add(1, Ordering::AcqRel);
+    }
+
+    pub fn end(&self) {
+        let previous = self
+            .active
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |active| {
+                Some(active.saturating_sub(1))
+            })
+            .unwrap_or_default();
+
+        if previous == 1 && self.pending.swap(false, Ordering::AcqRel) {
+            if let Some(sender) = &*self.sender.lock().unwrap() {
+                self.send(sender);
+            }
+        }
+    }
+
+    /// Forget about all active gestures and queued updates. Used when the editor closes, possibly
+    /// in the middle of a drag.
+    pub fn reset(&self) {
+        self.active.store(0, Ordering::Release);
+        self.pending.store(false, Ordering::Release);
+        while self.updates.pop().is_some() {}
+        self.overflowed.store(false, Ordering::Release);
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.active.load(Ordering::Acquire) > 0
+    }
+
+    /// Send a notification even while notifications are suppressed, for changes that are not caused
+    /// by the gesture itself. This does not block, so the notification is skipped if the sender is
+    /// being replaced at the same time.
+    pub fn notify_unsuppressed(&self) {
+        if let Ok(sender) = self.sender.try_lock() {
+            if let Some(sender) = &*sender {
+                self.send(sender);
+            }
+        }
+    }
+
+    /// Queue `update` for the editor and send a parameter update notification unless notifications
+    /// are currently suppressed. This does not block or allocate, so it can be called from any
+    /// thread.
+    pub fn notify(&self, sender: &channel::Sender<()>, update: ParameterUpdate) {
+        if self.updates.push(update).is_err() {
+            self.overflowed.store(true, Ordering::Release);
+        }
+
+        if self.is_active() {
+            self.pending.store(true, Ordering::Release);
+        } else {
+            self.send(sender);
+        }
+    }
+
+    pub fn stats(&self) -> ParamUpdateStats {
+        ParamUpdateStats {
+            sent: self.sent.load(Ordering::Relaxed),
+            coalesced: self.coalesced.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Pass the updates that were queued since the last call to `f`, in the order they were
+    /// reported. If any of them didn't fit in the queue, then `f` is only called once with
+    /// [`ParameterUpdate::All`].
+    pub fn take_updates(&self, mut f: impl FnMut(ParameterUpdate)) {
+        if self.overflowed.swap(false, Ordering::AcqRel) {
+            while self.updates.pop().is_some() {}
+            f(ParameterUpdate::All);
+        } else {
+            while let Some(update) = self.updates.pop() {
+                f(update);
+            }
+        }
+    }
+
+    fn send(&self, sender: &channel::Sender<()>) {
+        // If there's already a notification in the channel then we don't need to do anything
+        // else. This avoids queueing up redundant GUI redraws.
+        match sender.try_send(()) {
+            Ok(()) => self.sent.fetch_add(1, Ordering::Relaxed),
+            Err(channel::TrySendError::Full(_)) => self.coalesced.fetch_add(1, Ordering::Relaxed),
+            Err(channel::TrySendError::Disconnected(_)) => 0,
+        };
+    }
+}
+
+/// Skips setting a parameter to the value it was last set to during its current gesture. See the
+/// [module documentation][self].
+#[derive(Debug, Default)]
+pub(crate) struct RepeatedValueFilter {
+    /// The parameters that are currently being changed. Gestures on different parameters can
+    /// overlap, for instance while dragging an XY pad, so every parameter remembers its own last
+    /// value. This rarely holds more than two entries.
+    gestures: Mutex<Vec<Gesture>>,
+}
+
+#[derive(Debug)]
+struct Gesture {
+    param: ParamPtr,
+    /// The number of active gestures for `param`.
+    depth: usize,
+    /// The normalized value `param` was last set to during the gesture.
+    last: Option<f32>,
+}
+
+// The parameter pointer is only compared, never dereferenced
+unsafe impl Send for Gesture {}
+
+impl RepeatedValueFilter {
+    pub fn begin(&self, param: ParamPtr) {
+        let mut gestures = self.gestures.lock().unwrap();
+        match gestures.iter_mut().find(|gesture| gesture.param == param) {
+            Some(gesture) => {
+                gesture.depth += 1;
+                gesture.last = None;
+            }
+            None => gestures.push(Gesture {
+                param,
+                depth: 1,
+                last: None,
+            }),
+        }
+    }
+
+    pub fn end(&self, param: ParamPtr) {
+        let mut gestures = self.gestures.lock().unwrap();
+        if let Some(index) = gestures.iter().position(|gesture| gesture.param == param) {
+            gestures[index].depth -= 1;
+            if gestures[index].depth == 0 {
+                gestures.swap_remove(index);
+            } else {
+                gestures[index].last = None;
+            }
+        }
+    }
+
+    /// Whether `param` needs to be set to `normalized`. This is false when a gesture for `param`
+    /// is active and the parameter was already set to that value during it.
+    pub fn should_set(&self, param: ParamPtr, normalized: f32) -> bool {
+        let mut gestures = self.gestures.lock().unwrap();
+        let Some(gesture) = gestures.iter_mut().find(|gesture| gesture.param == param) else {
+            return true;
+        };
+
+        // This compares against the last value that was actually set, so a value that creeps in
+        // steps smaller than the epsilon is still set once it has moved far enough
+        let repeated = gesture
+            .last
+            .is_some_and(|last| (last - normalized).abs() <= REPEATED_VALUE_EPSILON);
+        if !repeated {
+            gesture.last = Some(normalized);
+        }
+
+        !repeated
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nih_plug::prelude::{FloatParam, FloatRange, Param};
+
+    use super::*;
+
+    #[test]
+    fn suppression_toggle() {
+        let (sender, receiver) = channel::bounded(1);
+        let suppression = ParamUpdateSuppression::default();
+        suppression.set_sender(sender.clone());
+
+        suppression.notify(&sender, ParameterUpdate::All);
+        assert!(receiver.try_recv().is_ok());
+
+        // Nested gestures, for instance from a widget drag while the editor suppresses updates
+        suppression.begin();
+        suppression.begin();
+        suppression.notify(&sender, ParameterUpdate::All);
+        suppression.notify(&sender, ParameterUpdate::All);
+        assert!(receiver.try_recv().is_err());
+        suppression.end();
+        assert!(suppression.is_active());
+        assert!(receiver.try_recv().is_err());
+
+        // The held back notification is sent once all of them have ended
+        suppression.end();
+        assert!(!suppression.is_active());
+        assert!(receiver.try_recv().is_ok());
+        assert!(receiver.try_recv().is_err());
+
+        // Nothing is sent if there was nothing to hold back, and unbalanced ends are ignored
+        suppression.begin();
+        suppression.end();
+        suppression.end();
+        assert!(!suppression.is_active());
+        assert!(receiver.try_recv().is_err());
+        suppression.notify(&sender, ParameterUpdate::All);
+        assert!(receiver.try_recv().is_ok());
+    }
+
+    #[test]
+    fn bursts_are_coalesced() {
+        let (sender, receiver) = channel::bounded(PARAMETER_UPDATES_CAPACITY);
+        let suppression = ParamUpdateSuppression::default();
+        suppression.set_sender(sender.clone());
+
+        // Automation changes a value much faster than a stalled GUI handles the notifications
+        let value = crossbeam::atomic::AtomicCell::new(0.0f32);
+        let burst = PARAMETER_UPDATES_CAPACITY * 10_000;
+        for i in 0..burst {
+            value.store(i as f32);
+            suppression.notify(&sender, ParameterUpdate::All);
+        }
+
+        // The queue never grows past its capacity, and the GUI sees the final value as soon as it
+        // handles the first notification
+        assert_eq!(receiver.len(), PARAMETER_UPDATES_CAPACITY);
+        assert!(receiver.try_recv().is_ok());
+        assert_eq!(value.load(), (burst - 1) as f32);
+        assert_eq!(
+            suppression.stats(),
+            ParamUpdateStats {
+                sent: PARAMETER_UPDATES_CAPACITY as u64,
+                coalesced: (burst - PARAMETER_UPDATES_CAPACITY) as u64,
+            }
+        );
+
+        // A change after the GUI caught up is sent again
+        value.store(-1.0);
+        suppression.notify(&sender, ParameterUpdate::All);
+        assert!(receiver.try_recv().is_ok());
+        assert_eq!(value.load(), -1.0);
+    }
+
+    #[test]
+    fn updates_reach_the_editor_in_order() {
+        let (sender, receiver) = channel::bounded(PARAMETER_UPDATES_CAPACITY);
+        let suppression = ParamUpdateSuppression::default();
+        suppression.set_sender(sender.clone());
+        let take = || {
+            let mut updates = Vec::new();
+            suppression.take_updates(|update| updates.push(update));
+            updates
+        };
+        let gain = |normalized_value| ParameterUpdate::Value {
+            id_hash: ParameterUpdate::hash_id("gain"),
+            normalized_value,
+        };
+
+        // Updates held back during a gesture are still queued, and they all share one notification
+        suppression.notify(&sender, gain(0.25));
+        suppression.begin();
+        suppression.notify(&sender, ParameterUpdate::All);
+        suppression.end();
+        assert_eq!(receiver.len(), 1);
+        assert_eq!(take(), [gain(0.25), ParameterUpdate::All]);
+        assert_eq!(take(), []);
+
+        assert!(gain(0.5).affects("gain"));
+        assert!(!gain(0.5).affects("cutoff"));
+        assert!(ParameterUpdate::All.affects("cutoff"));
+
+        // A GUI that falls behind gets a single update for everything instead
+        for i in 0..=PARAMETER_UPDATE_QUEUE_CAPACITY {
+            suppression.notify(&sender, gain(i as f32));
+        }
+        assert_eq!(take(), [ParameterUpdate::All]);
+        assert_eq!(take(), []);
+
+        // Closing the editor throws away what it didn't handle
+        suppression.notify(&sender, gain(1.0));
+        suppression.reset();
+        assert_eq!(take(), []);
+    }
+
+    #[test]
+    fn repeated_values_are_skipped_during_gestures() {
+        let gain = FloatParam::new("Gain", 0.5, FloatRange::Linear { min: 0.0, max: 1.0 });
+        let cutoff = FloatParam::new("Cutoff", 0.5, FloatRange::Linear { min: 0.0, max: 1.0 });
+        let (gain, cutoff) = (gain.as_ptr(), cutoff.as_ptr());
+        let filter = RepeatedValueFilter::default();
+
+        // Outside of gestures the host may have changed the value in the meantime
+        assert!(filter.should_set(gain, 0.5));
+        assert!(filter.should_set(gain, 0.5));
+
+        filter.begin(gain);
+        assert!(filter.should_set(gain, 0.5));
+        assert!(!filter.should_set(gain, 0.5));
+        assert!(!filter.should_set(gain, 0.5 + REPEATED_VALUE_EPSILON / 2.0));
+        assert!(filter.should_set(gain, 0.6));
+        // Only parameters with an active gesture are filtered
+        assert!(filter.should_set(cutoff, 0.6));
+        assert!(filter.should_set(cutoff, 0.6));
+        assert!(!filter.should_set(gain, 0.6));
+        filter.end(gain);
+
+        // Values that creep in steps below the epsilon are compared to the last value that was set
+        filter.begin(gain);
+        let step = REPEATED_VALUE_EPSILON * 0.75;
+        assert!(filter.should_set(gain, 0.6));
+        assert!(!filter.should_set(gain, 0.6 + step));
+        assert!(filter.should_set(gain, 0.6 + step * 2.0));
+        assert!(!filter.should_set(gain, 0.6 + step * 3.0));
+        filter.end(gain);
+
+        // Every gesture starts over
+        filter.begin(gain);
+        assert!(filter.should_set(gain, 0.6));
+        filter.end(gain);
+        assert!(filter.should_set(gain, 0.6));
+    }
+
+    #[test]
+    fn overlapping_gestures_are_filtered_per_parameter() {
+        let x = FloatParam::new("X", 0.5, FloatRange::Linear { min: 0.0, max: 1.0 });
+        let y = FloatParam::new("Y", 0.5, FloatRange::Linear { min: 0.0, max: 1.0 });
+        let (x, y) = (x.as_ptr(), y.as_ptr());
+        let filter = RepeatedValueFilter::default();
+
+        // An XY pad sets both of its parameters for every cursor movement
+        filter.begin(x);
+        filter.begin(y);
+        assert!(filter.should_set(x, 0.25));
+        assert!(filter.should_set(y, 0.75));
+        assert!(!filter.should_set(x, 0.25));
+        assert!(!filter.should_set(y, 0.75));
+        assert!(filter.should_set(x, 0.3));
+        assert!(!filter.should_set(y, 0.75));
+
+        // Ending one gesture doesn't affect the other
+        filter.end(x);
+        assert!(filter.should_set(x, 0.3));
+        assert!(!filter.should_set(y, 0.75));
+
+        // Nested gestures on the same parameter start over, and end together with the outer one
+        filter.begin(y);
+        assert!(filter.should_set(y, 0.75));
+        filter.end(y);
+        assert!(!filter.should_set(y, 0.75));
+        filter.end(y);
+        assert!(filter.should_set(y, 0.75));
+
+        // Unbalanced ends are ignored
+        filter.end(y);
+        assert!(filter.should_set(y, 0.75));
+    }
+
+    /// Compares the cost of an XY pad drag where most cursor movements don't change the values,
+    /// with and without the [`RepeatedValueFilter`]. Run with
+    /// `cargo test --release -- --ignored --nocapture repeated_values_benchmark`.
+    #[test]
+    #[ignore]
+    fn repeated_values_benchmark() {
+        use std::hint::black_box;
+        use std::time::{Duration, Instant};
+
+        /// Stands in for the host's `GuiContext`, which is called through a trait object and
+        /// usually takes a lock or queues an event.
+        struct Host(Mutex<Vec<f32>>);
+        trait SetParameter {
+            fn set(&self, param: ParamPtr, normalized: f32);
+        }
+        impl SetParameter for Host {
+            fn set(&self, _param: ParamPtr, normalized: f32) {
+                let mut values = self.0.lock().unwrap();
+                values.push(normalized);
+                values.clear();
+            }
+        }
+
+        const EVENTS: usize = 1_000_000;
+        let x = FloatParam::new("X", 0.5, FloatRange::Linear { min: 0.0, max: 1.0 });
+        let y = FloatParam::new("Y", 0.5, FloatRange::Linear { min: 0.0, max: 1.0 });
+        let (x, y) = (x.as_ptr(), y.as_ptr());
+        let host: &dyn SetParameter = &Host(Mutex::new(Vec::with_capacity(1)));
+        // Two stepped parameters where only one in every 20 cursor movements changes a value
+        let values: Vec<f32> = (0..EVENTS).map(|i| (i / 20) as f32 / 50_000.0).collect();
+
+        let start = Instant::now();
+        for &value in &values {
+            host.set(x, black_box(value));
+            host.set(y, black_box(1.0 - value));
+        }
+        let unfiltered = start.elapsed();
+
+        let filter = RepeatedValueFilter::default();
+        filter.begin(x);
+        filter.begin(y);
+        let mut host_calls = 0;
+        let start = Instant::now();
+        for &value in &values {
+            if filter.should_set(x, black_box(value)) {
+                host.set(x, value);
+                host_calls += 1;
+            }
+            if filter.should_set(y, black_box(1.0 - value)) {
+                host.set(y, 1.0 - value);
+                host_calls += 1;
+            }
+        }
+        let filtered = start.elapsed();
+        filter.end(y);
+        filter.end(x);
+
+        assert_eq!(host_calls, EVENTS / 20 * 2);
+        let per_event = |elapsed: Duration| elapsed.as_nanos() as f64 / EVENTS as f64;
+        println!(
+            "unfiltered: {:.1} ns/event, filtered: {:.1} ns/event, {host_calls} of {} host calls",
+            per_event(unfiltered),
+            per_event(filtered),
+            EVENTS * 2
+        );
+    }
+}
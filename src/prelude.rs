@@ -0,0 +1,157 @@
+//! Everything an editor typically needs, in one import. Without this an editor needs imports from
+//! this crate, its [`iced_baseview`][crate::iced_baseview] re-exports, and `nih_plug`, and it's
+//! easy to end up with an `Element` or `Length` from the wrong crate.
+//!
+//! ```ignore
+//! use nih_plug::prelude::*;
+//! use nih_plug_iced::prelude::*;
+//!
+//! fn view(&self) -> Element<'_, Message> {
+//!     Column::new()
+//!         .push(text("Gain"))
+//!         .push(ParamSlider::new(&self.params.gain).map(Message::ParamUpdate))
+//!         .align_x(Center)
+//!         .into()
+//! }
+//! ```
+//!
+//! The `column!` and `row!` macros are not part of the prelude because a glob import would make
+//! them ambiguous with the standard library's `column!()` macro. Use them through the [`widget`]
+//! module instead, like `widget::column![...]`, or import them by name.
+//!
+//! The [`Element`] alias fills in this crate's [`Theme`] and [`Renderer`], so `Element<'_,
+//! Message>` is exactly what [`IcedEditor::view()`] returns when the editor uses the default
+//! theme. Editors with a custom theme can pass it as the third type argument.
+//!
+//! The [`button()`], [`column()`], [`container()`], [`row()`], [`scrollable()`], and [`text()`]
+//! helpers are the ones from the [`widget`] module with the renderer filled in. Iced's own helpers
+//! are generic over the renderer, so a widget stored in a `let` binding or collected into a
+//! `Vec` before it ends up in a view otherwise needs a type annotation that names the renderer.
+
+pub use nih_plug::prelude::{Editor, GuiContext, Param, ParamSetter};
+pub use std::sync::Arc;
+
+pub use crate::executor;
+pub use crate::futures::Subscription;
+pub use crate::gui_channel::gui_channel;
+pub use crate::param_batch::ParamSetterBatch;
+pub use crate::param_updates::ParameterUpdate;
+pub use crate::widget::{self, Button, Column, Container, Row, Scrollable, Space, Text};
+pub use crate::widgets::{
+    self as nih_widgets, ParamDropdown, ParamKnob, ParamMessage, ParamRing, ParamSlider,
+    ParamToggle, PeakMeter, ResizeHandle,
+};
+pub use crate::{
+    alignment, create_iced_editor, Alignment, Center, Color, Fill, FillPortion, Font, IcedEditor,
+    IcedState, Length, Padding, Pixels, Renderer, Shrink, Size, Task, Theme, WindowSubs,
+};
+
+/// An [`Element`][crate::Element] using this crate's [`Renderer`]. The theme defaults to
+/// [`Theme`], which is also the default for the built-in widgets. This keeps `view()`
+/// signatures short without getting in the way of type inference.
+pub type Element<'a, Message, Theme = crate::Theme> =
+    crate::Element<'a, Message, Theme, crate::Renderer>;
+
+/// Creates a [`Button`] with this crate's [`Renderer`], see [`widget::button()`].
+pub fn button<'a, Message, Theme>(
+    content: impl Into<Element<'a, Message, Theme>>,
+) -> Button<'a, Message, Theme, Renderer>
+where
+    Theme: widget::button::Catalog + 'a,
+{
+    Button::new(content)
+}
+
+/// Creates a [`Column`] with this crate's [`Renderer`], see [`widget::column()`].
+pub fn column<'a, Message, Theme>(
+    children: impl IntoIterator<Item = Element<'a, Message, Theme>>,
+) -> Column<'a, Message, Theme, Renderer> {
+    Column::with_children(children)
+}
+
+/// Creates a [`Container`] with this crate's [`Renderer`], see [`widget::container()`].
+pub fn container<'a, Message, Theme>(
+    content: impl Into<Element<'a, Message, Theme>>,
+) -> Container<'a, Message, Theme, Renderer>
+where
+    Theme: widget::container::Catalog + 'a,
+{
+    Container::new(content)
+}
+
+/// Creates a [`Row`] with this crate's [`Renderer`], see [`widget::row()`].
+pub fn row<'a, Message, Theme>(
+    children: impl IntoIterator<Item = Element<'a, Message, Theme>>,
+) -> Row<'a, Message, Theme, Renderer> {
+    Row::with_children(children)
+}
+
+/// Creates a [`Scrollable`] with this crate's [`Renderer`], see [`widget::scrollable()`].
+pub fn scrollable<'a, Message, Theme>(
+    content: impl Into<Element<'a, Message, Theme>>,
+) -> Scrollable<'a, Message, Theme, Renderer>
+where
+    Theme: widget::scrollable::Catalog + 'a,
+{
+    Scrollable::new(content)
+}
+
+/// Creates a [`Text`] with this crate's [`Renderer`], see [`widget::text()`].
+pub fn text<'a, Theme>(fragment: impl widget::text::IntoFragment<'a>) -> Text<'a, Theme, Renderer>
+where
+    Theme: widget::text::Catalog + 'a,
+{
+    Text::new(fragment)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nih_plug::prelude::{FloatParam, FloatRange};
+
+    #[derive(Debug, Clone)]
+    #[allow(dead_code)]
+    enum Message {
+        ParamUpdate(ParamMessage),
+        Toggle,
+    }
+
+    fn view(param: &FloatParam) -> Element<'_, Message> {
+        widget::column![
+            text("Gain").width(Fill),
+            Row::new()
+                .push(ParamSlider::new(param).map(Message::ParamUpdate))
+                .push(ParamRing::new(param).map(Message::ParamUpdate))
+                .spacing(4),
+            button("Toggle").on_press(Message::Toggle),
+            container(Space::new().height(Length::Fixed(8.0))).padding(Padding::new(2.0)),
+        ]
+        .align_x(Center)
+        .into()
+    }
+
+    #[test]
+    fn views_infer_their_types() {
+        // This is mostly a compile test for the type aliases and re-exports
+        let param = FloatParam::new("Gain", 0.0, FloatRange::Linear { min: 0.0, max: 1.0 });
+        let element = view(&param);
+        // The column fills the width because of the text
+        assert_eq!(element.as_widget().size().width, Length::Fill);
+    }
+
+    #[test]
+    fn helpers_infer_the_renderer() {
+        // None of these bindings name the renderer, which iced's generic helpers would need
+        let labels: Vec<_> = ["Low", "Mid", "High"]
+            .into_iter()
+            .map(|label| text(label).into())
+            .collect();
+        let header = row(labels).spacing(4);
+        let footer = container(button("Reset").on_press(Message::Toggle)).width(Fill);
+        let body = scrollable(column([header.into(), footer.into()]));
+
+        // The footer fills the width, and the column and the scrollable follow it
+        let element: Element<'_, Message> = body.into();
+        assert_eq!(element.as_widget().size().width, Length::Fill);
+    }
+}
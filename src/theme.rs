@@ -0,0 +1,395 @@
+//! Themes, and exporting their colors for use outside of the editor.
+//!
+//! This module contains everything from iced's own `theme` module, plus [`ThemeData`]. That's a
+//! theme's palette and extended palette as plain data that can be serialized with any
+//! [`serde`](https://serde.rs) format, like JSON for design tools, or stored in a `#[persist]`
+//! field to save a custom theme with the plugin's state. [`ThemeData::to_theme()`] turns the data
+//! back into a [`Theme`].
+//!
+//! The exported fields are the theme's name, its [`Palette`] (background, text, primary, success,
+//! warning, and danger colors), and every color pair in its [`palette::Extended`] palette along
+//! with whether it's a dark theme. The editor's [`Appearance`][crate::Appearance] is derived from
+//! the extended palette's base background pair, so it's covered as well. Colors are written as
+//! `#rrggbb` hex strings, or `#rrggbbaa` for translucent colors.
+//!
+//! Hex strings store 8 bits per channel. Not all of iced's colors fit into that, especially in the
+//! extended palettes iced generates, so the first export can move a color by up to half a step
+//! (1/510) per channel. Importing and exporting again after that produces exactly the same data,
+//! and converting between a [`Theme`] and [`ThemeData`] without serializing never changes a color.
+//! An imported theme is always a custom theme, also when a built-in theme like [`Theme::Dark`] was
+//! exported.
+//!
+//! ```ignore
+//! let json = serde_json::to_string_pretty(&ThemeData::from_theme(&self.theme()))?;
+//!
+//! let theme = serde_json::from_str::<ThemeData>(&json)?.to_theme();
+//! ```
+//!
+//! Custom widgets can match the built-in widgets' look with [`colors()`]. It flattens the parts of
+//! the extended palette the built-in widgets' default styles are drawn with into a
+//! [`WidgetColors`], so a widget's `draw()` doesn't need to know how iced's palette is laid out:
+//!
+//! ```ignore
+//! let colors = theme::colors(theme);
+//! renderer.fill_quad(quad, if is_hovered { colors.surface_hover } else { colors.surface });
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::Color;
+use crate::widgets::util;
+
+pub use crate::iced_baseview::theme::*;
+
+/// The minimum contrast ratio between [`WidgetColors`]' text colors and the colors they're drawn
+/// on, from WCAG 2's AA level for regular text.
+const TEXT_CONTRAST: f32 = 4.5;
+
+/// The minimum contrast ratio between [`WidgetColors`]' accent and status colors and the
+/// background, from WCAG 2's requirements for user interface components.
+const GRAPHICS_CONTRAST: f32 = 3.0;
+
+/// The colors of a [`Theme`] as plain, serializable data. See the [module documentation][self].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ThemeData {
+    /// The theme's name.
+    pub name: String,
+    /// The theme's base palette.
+    #[serde(with = "PaletteDef")]
+    pub palette: Palette,
+    /// The extended palette the widgets are drawn with.
+    #[serde(with = "ExtendedDef")]
+    pub extended: palette::Extended,
+}
+
+impl ThemeData {
+    /// Get the colors of `theme`.
+    pub fn from_theme(theme: &Theme) -> Self {
+        Self {
+            name: theme.to_string(),
+            palette: theme.palette(),
+            extended: *theme.extended_palette(),
+        }
+    }
+
+    /// Create a custom theme with these colors. The extended palette is used as is instead of
+    /// being generated from the base palette.
+    pub fn to_theme(&self) -> Theme {
+        let extended = self.extended;
+
+        Theme::custom_with_fn(self.name.clone(), self.palette, move |_| extended)
+    }
+}
+
+impl From<&Theme> for ThemeData {
+    fn from(theme: &Theme) -> Self {
+        Self::from_theme(theme)
+    }
+}
+
+impl From<ThemeData> for Theme {
+    fn from(data: ThemeData) -> Self {
+        data.to_theme()
+    }
+}
+
+/// The semantic colors the built-in widgets are drawn with, see [`colors()`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WidgetColors {
+    /// The window's background.
+    pub background: Color,
+    /// Text on the background and on surfaces.
+    pub text: Color,
+    /// Less important text like units and placeholders. In iced's light and dark themes this still
+    /// has a contrast ratio of at least 4.5:1 with the background and with surfaces.
+    pub text_muted: Color,
+    /// The background of raised elements like tooltips and readouts.
+    pub surface: Color,
+    /// A surface that's hovered or being dragged.
+    pub surface_hover: Color,
+    /// Borders, tick marks, and other lines.
+    pub outline: Color,
+    /// Filled parts of parameter widgets, focus borders, and highlighted items.
+    pub accent: Color,
+    /// Text drawn on top of [`accent`][Self::accent].
+    pub accent_text: Color,
+    /// Values out of range, clipping, and errors.
+    pub danger: Color,
+    /// Confirmations and values in a safe range.
+    pub success: Color,
+}
+
+/// The [`WidgetColors`] for `theme`, derived from its extended palette:
+///
+/// - `background`, `text`, `surface`, and `surface_hover` are the background pair's base, its
+///   text, and the weak and strong background colors.
+/// - `outline` and `text_muted` mix the background towards the text by 60% and 75%.
+/// - `accent`, `danger`, and `success` are the base colors of the primary, danger, and success
+///   pairs. If a base color has a contrast ratio below 3:1 with the background, the pair's strong
+///   color is used instead, which is usually the case for dark themes.
+/// - `accent_text` is the text color of the pair `accent` was taken from. If that has a contrast
+///   ratio below 4.5:1 with `accent`, black or white is used instead, whichever contrasts more.
+pub fn colors(theme: &Theme) -> WidgetColors {
+    let palette = theme.extended_palette();
+    let background = palette.background.base.color;
+    let text = palette.background.base.text;
+
+    // The strong color of a pair moves away from the background, so it's used when the base
+    // color blends in
+    let readable = |base: palette::Pair, strong: palette::Pair| {
+        if contrast_ratio(base.color, background) >= GRAPHICS_CONTRAST {
+            base
+        } else {
+            strong
+        }
+    };
+    let accent = readable(palette.primary.base, palette.primary.strong);
+
+    WidgetColors {
+        background,
+        text,
+        text_muted: util::mix_colors(background, text, 0.75),
+        surface: palette.background.weak.color,
+        surface_hover: palette.background.strong.color,
+        outline: util::mix_colors(background, text, 0.6),
+        accent: accent.color,
+        accent_text: if contrast_ratio(accent.text, accent.color) >= TEXT_CONTRAST {
+            accent.text
+        } else if contrast_ratio(Color::WHITE, accent.color)
+            >= contrast_ratio(Color::BLACK, accent.color)
+        {
+            Color::WHITE
+        } else {
+            Color::BLACK
+        },
+        danger: readable(palette.danger.base, palette.danger.strong).color,
+        success: readable(palette.success.base, palette.success.strong).color,
+    }
+}
+
+/// The contrast ratio between two colors as defined by WCAG 2, from 1:1 for identical colors to
+/// 21:1 for black on white. Alpha channels are ignored.
+///
+/// This is `(L1 + 0.05) / (L2 + 0.05)`, where `L1` is the relative luminance of the lighter color
+/// and `L2` that of the darker one. The relative luminance is `0.2126 R + 0.7152 G + 0.0722 B`
+/// with each sRGB channel linearized first.
+pub fn contrast_ratio(a: Color, b: Color) -> f32 {
+    let luminance = |color: Color| {
+        let linear = |c: f32| {
+            if c <= 0.03928 {
+                c / 12.92
+            } else {
+                ((c + 0.055) / 1.055).powf(2.4)
+            }
+        };
+
+        0.2126 * linear(color.r) + 0.7152 * linear(color.g) + 0.0722 * linear(color.b)
+    };
+
+    let (a, b) = (luminance(a), luminance(b));
+    (a.max(b) + 0.05) / (a.min(b) + 0.05)
+}
+
+/// (De)serializes a [`Color`] as a hex string.
+mod hex {
+    use serde::{de, Deserialize, Deserializer, Serializer};
+
+    use crate::core::Color;
+
+    pub fn serialize<S: Serializer>(color: &Color, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(color)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Color, D::Error> {
+        let hex = String::deserialize(deserializer)?;
+
+        hex.parse()
+            .map_err(|err| de::Error::custom(format!("invalid color '{hex}': {err}")))
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "Palette")]
+struct PaletteDef {
+    #[serde(with = "hex")]
+    background: Color,
+    #[serde(with = "hex")]
+    text: Color,
+    #[serde(with = "hex")]
+    primary: Color,
+    #[serde(with = "hex")]
+    success: Color,
+    #[serde(with = "hex")]
+    warning: Color,
+    #[serde(with = "hex")]
+    danger: Color,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "palette::Extended")]
+struct ExtendedDef {
+    #[serde(with = "BackgroundDef")]
+    background: palette::Background,
+    #[serde(with = "PrimaryDef")]
+    primary: palette::Primary,
+    #[serde(with = "SecondaryDef")]
+    secondary: palette::Secondary,
+    #[serde(with = "SuccessDef")]
+    success: palette::Success,
+    #[serde(with = "WarningDef")]
+    warning: palette::Warning,
+    #[serde(with = "DangerDef")]
+    danger: palette::Danger,
+    is_dark: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "palette::Pair")]
+struct PairDef {
+    #[serde(with = "hex")]
+    color: Color,
+    #[serde(with = "hex")]
+    text: Color,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "palette::Background")]
+struct BackgroundDef {
+    #[serde(with = "PairDef")]
+    base: palette::Pair,
+    #[serde(with = "PairDef")]
+    weakest: palette::Pair,
+    #[serde(with = "PairDef")]
+    weaker: palette::Pair,
+    #[serde(with = "PairDef")]
+    weak: palette::Pair,
+    #[serde(with = "PairDef")]
+    neutral: palette::Pair,
+    #[serde(with = "PairDef")]
+    strong: palette::Pair,
+    #[serde(with = "PairDef")]
+    stronger: palette::Pair,
+    #[serde(with = "PairDef")]
+    strongest: palette::Pair,
+}
+
+/// The remaining parts of the extended palette all have the same three pairs.
+macro_rules! pairs_def {
+    ($name:ident, $remote:literal) => {
+        #[derive(Serialize, Deserialize)]
+        #[serde(remote = $remote)]
+        struct $name {
+            #[serde(with = "PairDef")]
+            base: palette::Pair,
+            #[serde(with = "PairDef")]
+            weak: palette::Pair,
+            #[serde(with = "PairDef")]
+            strong: palette::Pair,
+        }
+    };
+}
+
+pairs_def!(PrimaryDef, "palette::Primary");
+pairs_def!(SecondaryDef, "palette::Secondary");
+pairs_def!(SuccessDef, "palette::Success");
+pairs_def!(WarningDef, "palette::Warning");
+pairs_def!(DangerDef, "palette::Danger");
+
+#[cfg(test)]
+mod tests {
+    use serde::de::value::{Error, StrDeserializer};
+    use serde::de::IntoDeserializer;
+
+    use super::*;
+
+    /// Write a color as a hex string and read it back the way [`ThemeData`] is serialized.
+    fn hex_round_trip(color: Color) -> Color {
+        let hex = color.to_string();
+        let deserializer: StrDeserializer<'_, Error> = hex.as_str().into_deserializer();
+
+        hex::deserialize(deserializer).unwrap()
+    }
+
+    #[test]
+    fn round_trip() {
+        for theme in [Theme::Dark, Theme::Light, Theme::Nord] {
+            let data = ThemeData::from_theme(&theme);
+            let imported = data.to_theme();
+            assert_eq!(ThemeData::from_theme(&imported), data);
+            assert_eq!(imported.to_string(), theme.to_string());
+            assert_eq!(
+                crate::application::default(&imported),
+                crate::application::default(&theme)
+            );
+
+            // Colors are rounded to 8 bits per channel once, after which they're stable
+            for color in [
+                data.palette.background,
+                data.palette.text,
+                data.palette.primary,
+                data.palette.danger,
+                data.extended.background.weak.color,
+                data.extended.primary.strong.color,
+                data.extended.secondary.base.text,
+                data.extended.danger.weak.color,
+            ] {
+                let exported = hex_round_trip(color);
+                let steps = |a: f32, b: f32| (a - b).abs() * 255.0;
+                assert!(steps(exported.r, color.r) <= 0.501);
+                assert!(steps(exported.g, color.g) <= 0.501);
+                assert!(steps(exported.b, color.b) <= 0.501);
+                assert_eq!(hex_round_trip(exported), exported);
+            }
+        }
+
+        let translucent = Color::from_rgba8(0x12, 0x34, 0x56, 0.5);
+        assert_eq!(translucent.to_string().len(), 9);
+        assert_eq!(
+            hex_round_trip(translucent),
+            hex_round_trip(hex_round_trip(translucent))
+        );
+
+        let deserializer: StrDeserializer<'_, Error> = "#12345".into_deserializer();
+        assert!(hex::deserialize(deserializer).is_err());
+    }
+
+    #[test]
+    fn widget_colors_are_accessible() {
+        assert!((contrast_ratio(Color::BLACK, Color::WHITE) - 21.0).abs() < 1e-4);
+        assert_eq!(
+            contrast_ratio(Color::WHITE, Color::BLACK),
+            contrast_ratio(Color::BLACK, Color::WHITE)
+        );
+
+        for theme in [Theme::Light, Theme::Dark] {
+            let colors = colors(&theme);
+
+            for surface in [colors.background, colors.surface, colors.surface_hover] {
+                assert!(
+                    contrast_ratio(colors.text, surface) >= TEXT_CONTRAST,
+                    "{theme}"
+                );
+            }
+            for surface in [colors.background, colors.surface] {
+                assert!(
+                    contrast_ratio(colors.text_muted, surface) >= TEXT_CONTRAST,
+                    "{theme}"
+                );
+            }
+            assert!(
+                contrast_ratio(colors.accent_text, colors.accent) >= TEXT_CONTRAST,
+                "{theme}"
+            );
+
+            for color in [colors.outline, colors.accent, colors.danger, colors.success] {
+                assert!(
+                    contrast_ratio(color, colors.background) >= GRAPHICS_CONTRAST,
+                    "{theme}"
+                );
+            }
+
+            // Muted text is still distinguishable from regular text
+            assert_ne!(colors.text_muted, colors.text);
+        }
+    }
+}
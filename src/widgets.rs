@@ -7,21 +7,58 @@
 
 use nih_plug::prelude::ParamPtr;
 
+pub mod auto_scroll;
+pub mod axis;
+pub mod dropdown;
+pub mod ellipsis_text;
+pub mod expression;
 pub mod generic_ui;
+pub mod highlight;
+pub mod mapping;
+pub mod options;
+pub mod param_bounds;
+pub mod param_drag;
+pub mod param_dropdown;
+pub mod param_id;
+pub mod param_knob;
+pub mod param_ring;
 pub mod param_slider;
+pub mod param_toggle;
 pub mod peak_meter;
+pub mod piano_keyboard;
+pub mod playhead;
+pub mod range;
+pub mod readout_grid;
 pub mod resize_handle;
+pub mod ruler;
+pub mod tooltip;
 pub mod util;
+pub mod viewport_2d;
 
+pub use dropdown::Dropdown;
+pub use ellipsis_text::EllipsisText;
+pub use highlight::HighlightOverlay;
+pub use param_bounds::ParamBoundsMap;
+pub use param_dropdown::ParamDropdown;
+pub use param_knob::ParamKnob;
+pub use param_ring::ParamRing;
 pub use param_slider::ParamSlider;
+pub use param_toggle::ParamToggle;
 pub use peak_meter::PeakMeter;
+pub use piano_keyboard::PianoKeyboard;
+pub use range::ParamRange;
+pub use readout_grid::ReadoutGrid;
 pub use resize_handle::ResizeHandle;
+pub use ruler::{DbRuler, FrequencyRuler};
+pub use tooltip::Tooltip;
 
 /// A message to update a parameter value. Since NIH-plug manages the parameters, interacting with
-/// parameter values with iced works a little different from updating any other state. This main
-/// [`IcedEditor`][super::IcedEditor] should have a [`Message`][super::IcedEditor::Message] variant
-/// containing this `ParamMessage`. When it receives one of those messages, it can pass it through
-/// to [`self.handle_param_message()`][super::IcedEditor::handle_param_message].
+/// parameter values with iced works a little different from updating any other state. The main
+/// [`IcedEditor`][super::IcedEditor] should either use this as its
+/// [`Message`][super::IcedEditor::Message] or have a variant containing this `ParamMessage`. When
+/// [`IcedEditor::param_message()`][super::IcedEditor::param_message] returns these messages, they
+/// are passed to [`handle_param_message()`][super::IcedEditor::handle_param_message] for the
+/// editor.
 #[derive(Debug, Clone, Copy)]
 pub enum ParamMessage {
     /// Begin an automation gesture for a parameter.
@@ -32,3 +69,322 @@ pub enum ParamMessage {
     /// End an automation gesture for a parameter.
     EndSetParameter(ParamPtr),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::renderer;
+    use crate::core::theme::Palette;
+    use crate::core::{Background, Color, Element, Point, Rectangle, Size, Theme};
+    use crate::theme::contrast_ratio as contrast;
+
+    fn themes() -> [Theme; 3] {
+        [
+            Theme::Light,
+            Theme::Dark,
+            Theme::custom(
+                String::from("High Contrast"),
+                Palette {
+                    background: Color::BLACK,
+                    text: Color::WHITE,
+                    primary: Color::from_rgb(1.0, 1.0, 0.0),
+                    success: Color::from_rgb(0.0, 1.0, 0.0),
+                    warning: Color::from_rgb(1.0, 0.5, 0.0),
+                    danger: Color::from_rgb(1.0, 0.0, 0.0),
+                },
+            ),
+        ]
+    }
+
+    #[test]
+    fn default_styles_are_readable() {
+        for theme in themes() {
+            let background = theme.extended_palette().background.base.color;
+
+            for status in [
+                param_slider::Status::Active,
+                param_slider::Status::Hovered,
+                param_slider::Status::Dragged,
+            ] {
+                let style = param_slider::default(&theme, status);
+                assert!(contrast(style.text_color, background) >= 4.5, "{theme}");
+                assert!(
+                    contrast(style.filled_text_color, style.fill) >= 3.0,
+                    "{theme}"
+                );
+                assert!(contrast(style.border_color, background) >= 1.5, "{theme}");
+            }
+
+            for status in [
+                param_ring::Status::Active,
+                param_ring::Status::Hovered,
+                param_ring::Status::Dragged,
+            ] {
+                let style = param_ring::default(&theme, status);
+                assert!(contrast(style.text_color, background) >= 4.5, "{theme}");
+                assert!(contrast(style.fill, style.track) >= 1.5, "{theme}");
+            }
+
+            for status in [
+                param_knob::Status::Active,
+                param_knob::Status::Hovered,
+                param_knob::Status::Dragged,
+            ] {
+                let style = param_knob::default(&theme, status);
+                assert!(contrast(style.indicator, style.track) >= 3.0, "{theme}");
+                assert!(contrast(style.fill, style.track) >= 1.5, "{theme}");
+            }
+
+            for status in [
+                param_toggle::Status::Active,
+                param_toggle::Status::Hovered,
+                param_toggle::Status::Focused,
+            ] {
+                let style = param_toggle::default(&theme, status);
+                assert!(contrast(style.text_color, background) >= 4.5, "{theme}");
+                assert!(
+                    contrast(style.checked_text_color, style.checked) >= 3.0,
+                    "{theme}"
+                );
+            }
+
+            for status in [
+                readout_grid::Status::Active,
+                readout_grid::Status::Hovered,
+                readout_grid::Status::Focused,
+                readout_grid::Status::Dragged,
+            ] {
+                let style = readout_grid::default(&theme, status);
+                let Background::Color(cell_color) = style.background else {
+                    panic!("Expected a solid readout cell color");
+                };
+                assert!(contrast(style.text_color, cell_color) >= 4.5, "{theme}");
+            }
+
+            let style = peak_meter::default(&theme);
+            assert!(contrast(style.text_color, background) >= 4.5, "{theme}");
+            assert!(contrast(style.peak_hold, background) >= 3.0, "{theme}");
+            assert_ne!(style.clip, style.level_high, "{theme}");
+
+            let Background::Color(handle_color) =
+                resize_handle::default(&theme, resize_handle::Status::Dragged).background
+            else {
+                panic!("Expected a solid resize handle color");
+            };
+            assert!(contrast(handle_color, background) >= 1.5, "{theme}");
+        }
+    }
+
+    /// Paints every quad's border and background into a frame, without rounded corners. There's no
+    /// text renderer without a GPU, so only the widgets that don't draw text can be rendered.
+    struct Paint {
+        width: usize,
+        pixels: Vec<Color>,
+    }
+
+    impl Paint {
+        fn new(size: Size, background: Color) -> Self {
+            let width = size.width as usize;
+
+            Self {
+                width,
+                pixels: vec![background; width * size.height as usize],
+            }
+        }
+
+        /// Blend `color` over the pixels whose centers are inside `area` but not inside `hole`.
+        fn fill(&mut self, area: Rectangle, hole: Option<Rectangle>, color: Color) {
+            for (index, pixel) in self.pixels.iter_mut().enumerate() {
+                let center = Point::new(
+                    (index % self.width) as f32 + 0.5,
+                    (index / self.width) as f32 + 0.5,
+                );
+                if area.contains(center) && !hole.is_some_and(|hole| hole.contains(center)) {
+                    *pixel = util::mix_colors(*pixel, Color { a: 1.0, ..color }, color.a);
+                }
+            }
+        }
+    }
+
+    impl crate::core::Renderer for Paint {
+        fn start_layer(&mut self, _bounds: Rectangle) {}
+
+        fn end_layer(&mut self) {}
+
+        fn start_transformation(&mut self, _transformation: crate::core::Transformation) {}
+
+        fn end_transformation(&mut self) {}
+
+        fn reset(&mut self, _new_bounds: Rectangle) {}
+
+        fn fill_quad(&mut self, quad: renderer::Quad, background: impl Into<Background>) {
+            let inner = quad.bounds.shrink(quad.border.width);
+            if let Background::Color(color) = background.into() {
+                self.fill(inner, None, color);
+            }
+            if quad.border.width > 0.0 {
+                self.fill(quad.bounds, Some(inner), quad.border.color);
+            }
+        }
+
+        fn allocate_image(
+            &mut self,
+            _handle: &crate::core::image::Handle,
+            _callback: impl FnOnce(Result<crate::core::image::Allocation, crate::core::image::Error>)
+                + Send
+                + 'static,
+        ) {
+            unreachable!()
+        }
+    }
+
+    /// Encodes an RGBA frame as an uncompressed PNG. The encoding is deterministic, so two
+    /// snapshots are identical if and only if their pixels are.
+    fn encode_png(frame: &crate::render::RenderedFrame) -> Vec<u8> {
+        fn crc32(bytes: &[u8]) -> u32 {
+            !bytes.iter().fold(!0u32, |crc, &byte| {
+                (0..8).fold(crc ^ byte as u32, |crc, _| {
+                    (crc >> 1) ^ (0xedb8_8320 & (crc & 1).wrapping_neg())
+                })
+            })
+        }
+
+        fn chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+            png.extend((data.len() as u32).to_be_bytes());
+            let start = png.len();
+            png.extend(kind);
+            png.extend(data);
+            let crc = crc32(&png[start..]);
+            png.extend(crc.to_be_bytes());
+        }
+
+        // Every row starts with the `None` filter type
+        let raw: Vec<u8> = frame
+            .rgba
+            .chunks(frame.width as usize * 4)
+            .flat_map(|row| std::iter::once(0).chain(row.iter().copied()))
+            .collect();
+
+        // A zlib stream made of stored deflate blocks
+        let mut zlib = vec![0x78, 0x01];
+        let blocks = raw.chunks(u16::MAX as usize);
+        let block_count = blocks.len();
+        for (index, block) in blocks.enumerate() {
+            zlib.push((index + 1 == block_count) as u8);
+            zlib.extend((block.len() as u16).to_le_bytes());
+            zlib.extend((!(block.len() as u16)).to_le_bytes());
+            zlib.extend(block);
+        }
+        let (a, b) = raw.iter().fold((1u32, 0u32), |(a, b), &byte| {
+            let a = (a + byte as u32) % 65521;
+            (a, (b + a) % 65521)
+        });
+        zlib.extend(((b << 16) | a).to_be_bytes());
+
+        let mut header = Vec::new();
+        header.extend(frame.width.to_be_bytes());
+        header.extend(frame.height.to_be_bytes());
+        // 8 bits per channel RGBA, no interlacing
+        header.extend([8, 6, 0, 0, 0]);
+
+        let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+        chunk(&mut png, b"IHDR", &header);
+        chunk(&mut png, b"IDAT", &zlib);
+        chunk(&mut png, b"IEND", &[]);
+        png
+    }
+
+    /// Compares `frame` to the reference snapshot `src/widgets/snapshots/{name}.png`. A missing
+    /// snapshot is recorded, and setting `UPDATE_SNAPSHOTS=1` overwrites the existing snapshots
+    /// after an intentional style change. On a mismatch the new frame is written next to the
+    /// reference as `{name}.new.png` so the two can be compared.
+    fn assert_snapshot(name: &str, frame: &crate::render::RenderedFrame) {
+        let directory =
+            std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("src/widgets/snapshots");
+        let file_name = name.to_lowercase().replace(' ', "_");
+        let reference_path = directory.join(format!("{file_name}.png"));
+        let new_path = directory.join(format!("{file_name}.new.png"));
+        let png = encode_png(frame);
+
+        let update = std::env::var_os("UPDATE_SNAPSHOTS").is_some_and(|value| value != "0");
+        match std::fs::read(&reference_path) {
+            Ok(reference) if reference == png => {
+                let _ = std::fs::remove_file(&new_path);
+            }
+            Ok(_) if !update => {
+                std::fs::write(&new_path, &png).expect("Could not write the new snapshot");
+                panic!(
+                    "The {name} panel no longer matches {}, see {} for the new rendering. Run \
+                     the tests with UPDATE_SNAPSHOTS=1 if the change was intentional.",
+                    reference_path.display(),
+                    new_path.display()
+                );
+            }
+            _ => {
+                std::fs::create_dir_all(&directory)
+                    .expect("Could not create the snapshot directory");
+                std::fs::write(&reference_path, &png).expect("Could not write the snapshot");
+                let _ = std::fs::remove_file(&new_path);
+            }
+        }
+    }
+
+    #[test]
+    fn default_styles_render_under_every_theme() {
+        let size = Size::new(320.0, 100.0);
+        let frames: Vec<_> = themes()
+            .iter()
+            .map(|theme| {
+                let background = theme.extended_palette().background.base.color;
+                let panel = crate::widget::column![
+                    PianoKeyboard::new(|_| ()),
+                    ResizeHandle::new(size, |_| ()),
+                ];
+                let frame = crate::render::render(
+                    Element::<'_, (), Theme, Paint>::from(panel),
+                    &mut Paint::new(size, background),
+                    theme,
+                    &renderer::Style {
+                        text_color: Color::BLACK,
+                    },
+                    size,
+                    1.0,
+                    |paint, _| {
+                        paint
+                            .pixels
+                            .iter()
+                            .flat_map(|pixel| pixel.into_rgba8())
+                            .collect()
+                    },
+                )
+                .expect("The panel should have been rendered");
+
+                let pixel = |x: usize, y: usize| {
+                    let index = (y * frame.width as usize + x) * 4;
+                    let [r, g, b, a] = frame.rgba[index..index + 4] else {
+                        unreachable!()
+                    };
+                    Color::from_rgba8(r, g, b, a as f32 / 255.0)
+                };
+
+                // The keyboard's border and the resize handle's triangle in the bottom right corner
+                // of its 20 by 20 pixel bounds below the keyboard stand out from the window
+                assert!(contrast(pixel(0, 40), background) >= 3.0, "{theme}");
+                assert!(contrast(pixel(17, 97), background) >= 1.5, "{theme}");
+                // The other half of the handle's bounds is left alone
+                assert_eq!(pixel(2, 82), pixel(319, 99), "{theme}");
+
+                // And any other change to the panel's pixels needs to be approved by updating the
+                // theme's snapshot
+                assert_snapshot(&theme.to_string(), &frame);
+
+                frame.rgba
+            })
+            .collect();
+
+        // Every theme's panel looks different
+        assert_ne!(frames[0], frames[1]);
+        assert_ne!(frames[1], frames[2]);
+        assert_ne!(frames[0], frames[2]);
+    }
+}
@@ -0,0 +1,192 @@
+//! Scrolling a scrollable while something is dragged near its edges.
+//!
+//! When reordering items in a long list, like presets in a browser or rows in a modulation matrix,
+//! the list should scroll by itself once the dragged item gets close to its top or bottom edge.
+//! [`AutoScroll`] computes how far to scroll. Within [`AutoScrollSettings::edge_zone`] logical
+//! pixels of an edge the list scrolls at a speed proportional to how close the cursor is to that
+//! edge, reaching [`AutoScrollSettings::max_speed`] at the edge itself and beyond it. Both axes are
+//! handled, so this also works for horizontal lists.
+//!
+//! The scrolling is driven by the frame loop so the list keeps moving while the cursor is held
+//! still. Set [`WindowSubs::on_frame`][crate::WindowSubs::on_frame] while a drag is in progress,
+//! call [`AutoScroll::tick()`] for every frame, and scroll the list by the returned offset:
+//!
+//! ```ignore
+//! Message::Frame => {
+//!     if let Some(drag) = &self.drag {
+//!         let offset = self.auto_scroll.tick(self.list_bounds, drag.cursor, Instant::now());
+//!         if offset != Vector::ZERO {
+//!             let offset = AbsoluteOffset { x: offset.x, y: offset.y };
+//!             return operation::scroll_by(LIST_ID.clone(), offset);
+//!         }
+//!     }
+//!
+//!     Task::none()
+//! }
+//! ```
+//!
+//! Auto-scrolling should only start once the press turned into a drag, so clicking an item close to
+//! the edge doesn't scroll the list. Start calling [`tick()`][AutoScroll::tick()] after
+//! [`exceeds_drag_threshold()`][crate::interaction::exceeds_drag_threshold()] returned true for the
+//! press, and call [`stop()`][AutoScroll::stop()] when the drag ends. The first frame after that
+//! doesn't scroll yet since there's no previous frame to measure the elapsed time against.
+
+use std::time::{Duration, Instant};
+
+use crate::core::{Point, Rectangle, Vector};
+
+/// The longest time [`AutoScroll::tick()`] scrolls for in a single frame. This keeps the list from
+/// jumping after the editor stalled for a moment.
+const MAX_FRAME_TIME: Duration = Duration::from_millis(100);
+
+/// Configures when and how fast [`AutoScroll`] scrolls.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AutoScrollSettings {
+    /// How close in logical pixels the cursor needs to be to an edge of the scrollable region
+    /// before it starts scrolling. Zero disables auto-scrolling.
+    pub edge_zone: f32,
+    /// The scrolling speed in logical pixels per second when the cursor is at or past an edge.
+    pub max_speed: f32,
+}
+
+impl Default for AutoScrollSettings {
+    fn default() -> Self {
+        Self {
+            edge_zone: 24.0,
+            max_speed: 600.0,
+        }
+    }
+}
+
+impl AutoScrollSettings {
+    /// The scrolling velocity in logical pixels per second for a cursor at `cursor` while dragging
+    /// in a scrollable region with `bounds`. Negative values scroll up or left.
+    pub fn velocity(&self, bounds: Rectangle, cursor: Point) -> Vector {
+        Vector::new(
+            self.axis_velocity(cursor.x - bounds.x, bounds.x + bounds.width - cursor.x),
+            self.axis_velocity(cursor.y - bounds.y, bounds.y + bounds.height - cursor.y),
+        )
+    }
+
+    /// The velocity along one axis, given the cursor's distance to the start and the end of the
+    /// region. These distances are negative when the cursor is outside of the region.
+    fn axis_velocity(&self, to_start: f32, to_end: f32) -> f32 {
+        let finite = self.edge_zone.is_finite() && to_start.is_finite() && to_end.is_finite();
+        if !finite || self.edge_zone <= 0.0 {
+            return 0.0;
+        }
+
+        // In regions smaller than two edge zones the closest edge wins
+        let proximity = |distance: f32| (1.0 - distance / self.edge_zone).clamp(0.0, 1.0);
+        if to_start < to_end {
+            -proximity(to_start) * self.max_speed
+        } else {
+            proximity(to_end) * self.max_speed
+        }
+    }
+}
+
+/// Turns the cursor position during a drag into per-frame scroll offsets. See the [module
+/// documentation][self].
+#[derive(Debug, Clone, Default)]
+pub struct AutoScroll {
+    settings: AutoScrollSettings,
+    /// When the last frame was, or `None` if the drag just started.
+    last_tick: Option<Instant>,
+}
+
+impl AutoScroll {
+    /// Create an auto-scroller with the given settings.
+    pub fn new(settings: AutoScrollSettings) -> Self {
+        Self {
+            settings,
+            last_tick: None,
+        }
+    }
+
+    /// The settings this auto-scroller was created with.
+    pub fn settings(&self) -> AutoScrollSettings {
+        self.settings
+    }
+
+    /// How far to scroll the region with `bounds` in this frame, in logical pixels, for a drag
+    /// that's currently at `cursor`.
+    pub fn tick(&mut self, bounds: Rectangle, cursor: Point, now: Instant) -> Vector {
+        let elapsed = match self.last_tick.replace(now) {
+            Some(last_tick) => now.saturating_duration_since(last_tick).min(MAX_FRAME_TIME),
+            None => Duration::ZERO,
+        };
+
+        self.settings.velocity(bounds, cursor) * elapsed.as_secs_f32()
+    }
+
+    /// End the drag. The next call to [`tick()`][Self::tick()] starts a new one.
+    pub fn stop(&mut self) {
+        self.last_tick = None;
+    }
+
+    /// Whether [`tick()`][Self::tick()] has been called since the drag started.
+    pub fn is_active(&self) -> bool {
+        self.last_tick.is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn speed_depends_on_edge_distance() {
+        let settings = AutoScrollSettings {
+            edge_zone: 20.0,
+            max_speed: 400.0,
+        };
+        let bounds = Rectangle::new(Point::new(0.0, 100.0), crate::Size::new(200.0, 300.0));
+        let speed_at = |y: f32| settings.velocity(bounds, Point::new(100.0, y)).y;
+
+        // Nothing happens in the middle of the list
+        assert_eq!(speed_at(250.0), 0.0);
+        assert_eq!(speed_at(120.0), 0.0);
+        // Getting closer to the edge scrolls faster
+        assert_eq!(speed_at(115.0), -100.0);
+        assert_eq!(speed_at(105.0), -300.0);
+        assert_eq!(speed_at(100.0), -400.0);
+        assert_eq!(speed_at(395.0), 300.0);
+        // Past the edge the speed is capped
+        assert_eq!(speed_at(50.0), -400.0);
+        assert_eq!(speed_at(1000.0), 400.0);
+        // The cursor is away from the left and right edges
+        assert_eq!(settings.velocity(bounds, Point::new(100.0, 105.0)).x, 0.0);
+
+        let disabled = AutoScrollSettings {
+            edge_zone: 0.0,
+            ..settings
+        };
+        assert_eq!(
+            disabled.velocity(bounds, Point::new(100.0, 50.0)),
+            Vector::ZERO
+        );
+    }
+
+    #[test]
+    fn scrolls_by_elapsed_time() {
+        let bounds = Rectangle::new(Point::ORIGIN, crate::Size::new(200.0, 300.0));
+        let cursor = Point::new(100.0, 300.0);
+        let mut auto_scroll = AutoScroll::default();
+        let start = Instant::now();
+
+        assert_eq!(auto_scroll.tick(bounds, cursor, start), Vector::ZERO);
+        let offset = auto_scroll.tick(bounds, cursor, start + Duration::from_millis(50));
+        assert!((offset.y - 30.0).abs() < 1e-3);
+        // A stalled editor doesn't make the list jump
+        let offset = auto_scroll.tick(bounds, cursor, start + Duration::from_secs(5));
+        assert!((offset.y - 60.0).abs() < 1e-3);
+
+        auto_scroll.stop();
+        assert!(!auto_scroll.is_active());
+        assert_eq!(
+            auto_scroll.tick(bounds, cursor, start + Duration::from_secs(6)),
+            Vector::ZERO
+        );
+    }
+}
@@ -0,0 +1,411 @@
+//! Mapping values to positions along a frequency or decibel axis, and deciding which of the axis'
+//! tick labels fit.
+//!
+//! These functions are shared by the [`FrequencyRuler`][super::ruler::FrequencyRuler] and
+//! [`DbRuler`][super::ruler::DbRuler] widgets and by plot widgets, so a ruler placed next to a plot
+//! lines up with it as long as both use the same range. Frequencies are mapped logarithmically and
+//! decibels linearly. All mapping functions return values in `[0, 1]`, even for non-finite inputs.
+
+pub use super::util::db_to_t;
+
+/// A point on an axis where a tick is drawn.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tick {
+    /// The tick's value, in Hertz or decibel.
+    pub value: f32,
+    /// How important the tick is.
+    pub level: TickLevel,
+}
+
+/// The importance of a [`Tick`]. When there isn't enough room for all labels, the labels of
+/// [`Medium`][TickLevel::Medium] ticks are dropped before those of [`Major`][TickLevel::Major]
+/// ticks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum TickLevel {
+    /// A labeled tick that's kept for as long as possible, like 100 Hz or 0 dB.
+    Major,
+    /// A labeled tick whose label is dropped first, like 20 Hz or 50 Hz.
+    Medium,
+    /// An unlabeled tick.
+    Minor,
+}
+
+impl Tick {
+    /// Whether the tick has a label.
+    pub fn is_labeled(&self) -> bool {
+        self.level != TickLevel::Minor
+    }
+}
+
+/// Remap a frequency to a `[0, 1]` value on a logarithmic axis spanning `[min_hz, max_hz]`.
+/// Frequencies outside of the range are clamped. Returns 0 for `NaN` and for invalid ranges.
+pub fn frequency_to_t(frequency: f32, min_hz: f32, max_hz: f32) -> f32 {
+    if !is_valid_frequency_range(min_hz, max_hz) || frequency.is_nan() {
+        return 0.0;
+    }
+
+    let frequency = frequency.clamp(min_hz, max_hz);
+    ((frequency / min_hz).ln() / (max_hz / min_hz).ln()).clamp(0.0, 1.0)
+}
+
+/// The inverse of [`frequency_to_t()`].
+pub fn t_to_frequency(t: f32, min_hz: f32, max_hz: f32) -> f32 {
+    if !is_valid_frequency_range(min_hz, max_hz) {
+        return min_hz.max(0.0);
+    }
+
+    let t = if t.is_nan() { 0.0 } else { t.clamp(0.0, 1.0) };
+    min_hz * (max_hz / min_hz).powf(t)
+}
+
+/// The highest frequency that should be shown for a range, which is the Nyquist frequency if it's
+/// below `max_hz`.
+pub fn visible_max_frequency(max_hz: f32, sample_rate: Option<f32>) -> f32 {
+    match sample_rate {
+        Some(sample_rate) if sample_rate.is_finite() && sample_rate > 0.0 => {
+            max_hz.min(sample_rate / 2.0)
+        }
+        _ => max_hz,
+    }
+}
+
+/// The ticks of a frequency axis. The 1, 2 and 5 multiples of every power of ten get labels, and
+/// the multiples in between get unlabeled minor ticks.
+pub fn frequency_ticks(min_hz: f32, max_hz: f32) -> Vec<Tick> {
+    if !is_valid_frequency_range(min_hz, max_hz) {
+        return Vec::new();
+    }
+
+    let first_decade = min_hz.log10().floor() as i32;
+    let last_decade = max_hz.log10().ceil() as i32;
+
+    let mut ticks = Vec::new();
+    for decade in first_decade..=last_decade {
+        let base = 10f32.powi(decade);
+        for multiple in 1..=9 {
+            let value = base * multiple as f32;
+            if value < min_hz || value > max_hz {
+                continue;
+            }
+
+            let level = match multiple {
+                1 => TickLevel::Major,
+                2 | 5 => TickLevel::Medium,
+                _ => TickLevel::Minor,
+            };
+            ticks.push(Tick { value, level });
+        }
+    }
+
+    ticks
+}
+
+/// The ticks of a decibel axis, labeled every `step` decibel. Every other label, including 0 dB, is
+/// a major label, and there are minor ticks halfway between the labels.
+pub fn db_ticks(min_db: f32, max_db: f32, step: f32) -> Vec<Tick> {
+    let is_valid_range = min_db.is_finite() && max_db.is_finite() && min_db < max_db;
+    let is_valid_step = step.is_finite() && step > 0.0;
+    if !(is_valid_range && is_valid_step) {
+        return Vec::new();
+    }
+
+    // Minor ticks are at odd multiples of half a step
+    let half_step = step / 2.0;
+    let first = (min_db / half_step).ceil() as i64;
+    let last = (max_db / half_step).floor() as i64;
+
+    (first..=last)
+        .map(|index| Tick {
+            value: index as f32 * half_step,
+            level: match index.rem_euclid(4) {
+                0 => TickLevel::Major,
+                2 => TickLevel::Medium,
+                _ => TickLevel::Minor,
+            },
+        })
+        .collect()
+}
+
+/// The level in decibel where segment `index` starts on a meter made of `count` equally sized
+/// segments spanning `[min_db, max_db]`. Index `count` is the end of the last segment.
+pub fn segment_db(index: usize, count: usize, min_db: f32, max_db: f32) -> f32 {
+    if count == 0 {
+        return min_db;
+    }
+
+    min_db + (max_db - min_db) * (index.min(count) as f32 / count as f32)
+}
+
+/// How many segments are lit for a level in decibel on a meter made of `count` equally sized
+/// segments spanning `[min_db, max_db]`. A segment lights up once the level is above the
+/// [`segment_db()`] it starts at, so a level exactly on the boundary between two segments only
+/// lights the lower one. `NaN` and levels at or below `min_db` don't light anything.
+pub fn lit_segments(db: f32, count: usize, min_db: f32, max_db: f32) -> usize {
+    let db = super::util::sanitize_db(db);
+
+    (0..count)
+        .take_while(|&index| db > segment_db(index, count, min_db, max_db))
+        .count()
+}
+
+/// Format a frequency for a label, using a `k` suffix from 1 kHz onwards.
+pub fn format_frequency(frequency: f32) -> String {
+    if frequency >= 1000.0 {
+        let khz = frequency / 1000.0;
+        if khz.fract() == 0.0 {
+            format!("{khz}k")
+        } else {
+            format!("{khz:.1}k")
+        }
+    } else {
+        format!("{}", frequency.round())
+    }
+}
+
+/// Format a decibel value for a label.
+pub fn format_db(db: f32) -> String {
+    // Avoid showing -0
+    format!("{}", db.round() + 0.0)
+}
+
+/// A tick label that needs to be placed along an axis.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LabelPlacement {
+    /// The position along the axis the label is centered on, in pixels.
+    pub position: f32,
+    /// The size of the label along the axis, in pixels.
+    pub size: f32,
+    /// The level of the label's tick.
+    pub level: TickLevel,
+}
+
+impl LabelPlacement {
+    /// The label's start and end along an axis `length` pixels long. Labels that would stick out of
+    /// the axis are moved inwards.
+    pub fn span(&self, length: f32) -> (f32, f32) {
+        let start = (self.position - (self.size / 2.0))
+            .min(length - self.size)
+            .max(0.0);
+
+        (start, start + self.size)
+    }
+}
+
+/// Decide which labels can be drawn on an axis `length` pixels long without overlapping. Labels need
+/// to be at least `gap` pixels apart. Major labels are placed first, and medium labels are only
+/// placed if all major labels fit. Labels of the same level are placed from the start of the axis.
+/// Returns whether each label is visible.
+pub fn visible_labels(labels: &[LabelPlacement], length: f32, gap: f32) -> Vec<bool> {
+    let mut order: Vec<usize> = (0..labels.len()).collect();
+    order.sort_by(|&a, &b| {
+        labels[a]
+            .level
+            .cmp(&labels[b].level)
+            .then(labels[a].position.total_cmp(&labels[b].position))
+    });
+
+    let mut visible = vec![false; labels.len()];
+    let mut placed: Vec<(f32, f32)> = Vec::new();
+    let mut dropped_level = None;
+    for index in order {
+        let label = &labels[index];
+        if label.level == TickLevel::Minor {
+            continue;
+        }
+
+        // Less important labels are only shown if all more important labels fit
+        if dropped_level.is_some_and(|dropped| label.level > dropped) {
+            break;
+        }
+        if label.size > length {
+            dropped_level = Some(label.level);
+            continue;
+        }
+
+        let (start, end) = label.span(length);
+        let overlaps = placed
+            .iter()
+            .any(|&(other_start, other_end)| start < other_end + gap && other_start < end + gap);
+        if overlaps {
+            dropped_level = Some(label.level);
+        } else {
+            placed.push((start, end));
+            visible[index] = true;
+        }
+    }
+
+    visible
+}
+
+fn is_valid_frequency_range(min_hz: f32, max_hz: f32) -> bool {
+    min_hz.is_finite() && max_hz.is_finite() && min_hz > 0.0 && min_hz < max_hz
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Place the labels of the audible frequency range on an axis `width` pixels wide, assuming
+    /// every character is 6 pixels wide.
+    fn frequency_labels(width: f32) -> Vec<(String, LabelPlacement)> {
+        frequency_ticks(20.0, 20_000.0)
+            .into_iter()
+            .filter(Tick::is_labeled)
+            .map(|tick| {
+                let text = format_frequency(tick.value);
+                let placement = LabelPlacement {
+                    position: frequency_to_t(tick.value, 20.0, 20_000.0) * width,
+                    size: text.len() as f32 * 6.0,
+                    level: tick.level,
+                };
+
+                (text, placement)
+            })
+            .collect()
+    }
+
+    fn shown_labels(width: f32) -> Vec<String> {
+        let labels = frequency_labels(width);
+        let placements: Vec<_> = labels.iter().map(|(_, placement)| *placement).collect();
+        let visible = visible_labels(&placements, width, 4.0);
+
+        labels
+            .into_iter()
+            .zip(visible)
+            .filter(|(_, visible)| *visible)
+            .map(|((text, _), _)| text)
+            .collect()
+    }
+
+    #[test]
+    fn frequency_mapping() {
+        assert_eq!(frequency_to_t(20.0, 20.0, 20_000.0), 0.0);
+        assert_eq!(frequency_to_t(20_000.0, 20.0, 20_000.0), 1.0);
+        assert!((frequency_to_t(200.0, 20.0, 20_000.0) - (1.0 / 3.0)).abs() < 1e-6);
+        assert!((t_to_frequency(2.0 / 3.0, 20.0, 20_000.0) - 2000.0).abs() < 0.1);
+
+        assert_eq!(frequency_to_t(f32::NAN, 20.0, 20_000.0), 0.0);
+        assert_eq!(frequency_to_t(f32::INFINITY, 20.0, 20_000.0), 1.0);
+        assert_eq!(frequency_to_t(-5.0, 20.0, 20_000.0), 0.0);
+        assert_eq!(frequency_to_t(100.0, 0.0, 20_000.0), 0.0);
+        assert_eq!(t_to_frequency(f32::NAN, 20.0, 20_000.0), 20.0);
+
+        assert_eq!(visible_max_frequency(20_000.0, Some(44_100.0)), 20_000.0);
+        assert_eq!(visible_max_frequency(20_000.0, Some(22_050.0)), 11_025.0);
+        assert_eq!(visible_max_frequency(20_000.0, Some(0.0)), 20_000.0);
+    }
+
+    #[test]
+    fn tick_generation() {
+        let ticks = frequency_ticks(20.0, 20_000.0);
+        let labeled: Vec<_> = ticks
+            .iter()
+            .filter(|tick| tick.is_labeled())
+            .map(|tick| format_frequency(tick.value))
+            .collect();
+        assert_eq!(
+            labeled,
+            ["20", "50", "100", "200", "500", "1k", "2k", "5k", "10k", "20k"]
+        );
+        assert_eq!(ticks.first().unwrap().value, 20.0);
+        assert_eq!(ticks.last().unwrap().value, 20_000.0);
+
+        // Labels stop at the Nyquist frequency
+        let ticks = frequency_ticks(20.0, visible_max_frequency(20_000.0, Some(16_000.0)));
+        assert_eq!(ticks.last().unwrap().value, 8000.0);
+
+        let ticks = db_ticks(-24.0, 12.0, 6.0);
+        let labels: Vec<_> = ticks
+            .iter()
+            .filter(|tick| tick.is_labeled())
+            .map(|tick| (format_db(tick.value), tick.level))
+            .collect();
+        assert_eq!(
+            labels,
+            [
+                (String::from("-24"), TickLevel::Major),
+                (String::from("-18"), TickLevel::Medium),
+                (String::from("-12"), TickLevel::Major),
+                (String::from("-6"), TickLevel::Medium),
+                (String::from("0"), TickLevel::Major),
+                (String::from("6"), TickLevel::Medium),
+                (String::from("12"), TickLevel::Major),
+            ]
+        );
+        assert_eq!(ticks.len(), 13);
+        assert!(db_ticks(0.0, -10.0, 6.0).is_empty());
+        assert!(db_ticks(-10.0, 0.0, f32::NAN).is_empty());
+    }
+
+    #[test]
+    fn labels_at_different_widths() {
+        // With enough room every label fits
+        assert_eq!(
+            shown_labels(600.0),
+            ["20", "50", "100", "200", "500", "1k", "2k", "5k", "10k", "20k"]
+        );
+
+        // Medium labels are dropped first
+        assert_eq!(shown_labels(150.0), ["20", "100", "1k", "10k"]);
+        assert_eq!(shown_labels(100.0), ["100", "1k", "10k"]);
+
+        // Eventually not even the major labels fit
+        assert_eq!(shown_labels(60.0), ["100", "1k"]);
+        assert_eq!(shown_labels(40.0), ["100"]);
+        assert!(shown_labels(10.0).is_empty());
+
+        for width in [40.0, 60.0, 100.0, 150.0, 200.0, 300.0, 600.0, 1200.0] {
+            let labels = frequency_labels(width);
+            let placements: Vec<_> = labels.iter().map(|(_, placement)| *placement).collect();
+            let visible = visible_labels(&placements, width, 4.0);
+
+            // Visible labels never overlap and stay within the axis
+            let spans: Vec<_> = placements
+                .iter()
+                .zip(&visible)
+                .filter(|(_, visible)| **visible)
+                .map(|(placement, _)| placement.span(width))
+                .collect();
+            for (index, &(start, end)) in spans.iter().enumerate() {
+                assert!(start >= 0.0 && end <= width, "{width}");
+                for &(other_start, other_end) in &spans[index + 1..] {
+                    assert!(
+                        end + 4.0 <= other_start || other_end + 4.0 <= start,
+                        "{width}"
+                    );
+                }
+            }
+
+            // A medium label is only shown if all major labels are
+            let all_major_shown = placements
+                .iter()
+                .zip(&visible)
+                .all(|(placement, visible)| placement.level != TickLevel::Major || *visible);
+            let any_medium_shown = placements
+                .iter()
+                .zip(&visible)
+                .any(|(placement, visible)| placement.level == TickLevel::Medium && *visible);
+            assert!(all_major_shown || !any_medium_shown, "{width}");
+        }
+    }
+
+    #[test]
+    fn segment_quantization() {
+        let lit = |db| lit_segments(db, 10, -90.0, 10.0);
+
+        assert_eq!(lit(f32::NEG_INFINITY), 0);
+        assert_eq!(lit(f32::NAN), 0);
+        assert_eq!(lit(-90.0), 0);
+        assert_eq!(lit(-89.9), 1);
+        assert_eq!(lit(100.0), 10);
+
+        // Levels exactly on a boundary light the segment below it, but not the one above it
+        for index in 1..10 {
+            let boundary = segment_db(index, 10, -90.0, 10.0);
+            assert_eq!(lit(boundary), index);
+            assert_eq!(lit(boundary + 0.001), index + 1);
+        }
+        assert_eq!(segment_db(3, 10, -90.0, 10.0), -60.0);
+        assert_eq!(lit_segments(0.0, 0, -90.0, 10.0), 0);
+    }
+}
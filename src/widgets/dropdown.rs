@@ -0,0 +1,904 @@
+//! A dropdown for picking one option out of a list, with full keyboard navigation.
+
+use std::borrow::Cow;
+
+use crate::core::keyboard::key;
+use crate::core::text::{self, Renderer as TextRenderer};
+use crate::core::widget::operation::{Focusable, Operation};
+use crate::core::widget::tree::{self, Tree};
+use crate::core::widget::Id;
+use crate::core::{
+    alignment, keyboard, layout, mouse, overlay, renderer, touch, Background, Border, Clipboard,
+    Color, Element, Event, Font, Layout, Length, Padding, Pixels, Point, Rectangle, Shell, Size,
+    Theme, Vector, Widget,
+};
+use crate::interaction;
+use crate::scale;
+
+use super::util;
+
+/// The thickness of this widget's borders.
+const BORDER_WIDTH: f32 = 1.0;
+/// The size of the little arrow drawn on the right side of the button.
+const HANDLE_SIZE: f32 = 7.0;
+
+/// A button showing the currently selected option that opens a list of options when it's clicked,
+/// or when Enter, Space or the arrow keys are pressed while the button has keyboard focus.
+///
+/// While the list is open the arrow keys move the highlight (wrapping around at the ends), Home and
+/// End jump to the first and last option, Enter or Space select the highlighted option, and Escape
+/// closes the list again. The button keeps its keyboard focus after the list is closed.
+pub struct Dropdown<'a, T, Message, Theme = crate::Theme>
+where
+    T: ToString + PartialEq + Clone,
+    Theme: Catalog,
+{
+    options: Cow<'a, [T]>,
+    selected: Option<T>,
+    on_select: OnSelect<'a, T, Message>,
+    placeholder: Option<String>,
+
+    id: Option<Id>,
+    width: Length,
+    padding: Padding,
+    max_menu_height: f32,
+    text_size: Option<Pixels>,
+    font: Option<Font>,
+    class: Theme::Class<'a>,
+}
+
+/// Publishes the messages for a selected option. [`ParamDropdown`][super::ParamDropdown]s need to
+/// publish an entire automation gesture, so this gets the [`Shell`] instead of returning a single
+/// message.
+type OnSelect<'a, T, Message> = Box<dyn Fn(T, &mut Shell<'_, Message>) + 'a>;
+
+/// State for a [`Dropdown`].
+#[derive(Debug, Default)]
+struct State {
+    /// Whether the button currently has keyboard focus.
+    is_focused: bool,
+    /// Whether the list of options is currently shown.
+    is_open: bool,
+    /// The index of the option that would be selected when pressing Enter.
+    highlighted: Option<usize>,
+    /// The index of the first visible option in the menu.
+    scroll_offset: usize,
+    /// The number of options that fit in the menu, computed when laying out the overlay.
+    visible_rows: usize,
+    /// Scrolling that didn't add up to a whole row yet.
+    wheel: interaction::WheelAccumulator,
+}
+
+impl State {
+    fn open(&mut self, selected: Option<usize>) {
+        self.is_open = true;
+        self.is_focused = true;
+        self.highlighted = selected;
+        self.scroll_offset = selected.unwrap_or(0);
+        self.wheel.reset();
+    }
+
+    /// Closes the menu. Focus is returned to the button so keyboard navigation can continue from
+    /// there.
+    fn close(&mut self) {
+        self.is_open = false;
+        self.is_focused = true;
+        self.highlighted = None;
+    }
+}
+
+impl Focusable for State {
+    fn is_focused(&self) -> bool {
+        self.is_focused
+    }
+
+    fn focus(&mut self) {
+        self.is_focused = true;
+    }
+
+    fn unfocus(&mut self) {
+        self.is_focused = false;
+        self.is_open = false;
+    }
+}
+
+/// A keyboard navigation step within the list of options.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Navigation {
+    Next,
+    Previous,
+    First,
+    Last,
+}
+
+impl<'a, T, Message, Theme> Dropdown<'a, T, Message, Theme>
+where
+    T: ToString + PartialEq + Clone,
+    Theme: Catalog,
+{
+    /// The default width of the [`Dropdown`].
+    pub const DEFAULT_WIDTH: Length = Length::Fixed(180.0);
+    /// The default maximum height of the opened list, in logical pixels.
+    pub const DEFAULT_MAX_MENU_HEIGHT: f32 = 200.0;
+
+    /// Creates a new [`Dropdown`] for a list of options and the currently selected option, if any.
+    /// The options can be borrowed or owned by the dropdown. `on_select` is called with the new
+    /// option whenever the user picks one.
+    pub fn new(
+        options: impl Into<Cow<'a, [T]>>,
+        selected: Option<T>,
+        on_select: impl Fn(T) -> Message + 'a,
+    ) -> Self {
+        Self::with_publisher(options, selected, move |option, shell| {
+            shell.publish(on_select(option));
+        })
+    }
+
+    /// The same as [`new()`][Self::new()], but `on_select` publishes the messages for the selected
+    /// option itself.
+    pub(crate) fn with_publisher(
+        options: impl Into<Cow<'a, [T]>>,
+        selected: Option<T>,
+        on_select: impl Fn(T, &mut Shell<'_, Message>) + 'a,
+    ) -> Self {
+        Self {
+            options: options.into(),
+            selected,
+            on_select: Box::new(on_select),
+            placeholder: None,
+
+            id: None,
+            width: Self::DEFAULT_WIDTH,
+            padding: Padding::from([4, 8]),
+            max_menu_height: Self::DEFAULT_MAX_MENU_HEIGHT,
+            text_size: None,
+            font: None,
+            class: Theme::default(),
+        }
+    }
+
+    /// Sets the text shown when nothing has been selected yet.
+    pub fn placeholder(mut self, placeholder: impl Into<String>) -> Self {
+        self.placeholder = Some(placeholder.into());
+        self
+    }
+
+    /// Sets the [`Id`] of the [`Dropdown`] so it can be focused with a widget operation.
+    pub fn id(mut self, id: impl Into<Id>) -> Self {
+        self.id = Some(id.into());
+        self
+    }
+
+    /// Sets the width of the [`Dropdown`].
+    pub fn width(mut self, width: impl Into<Length>) -> Self {
+        self.width = width.into();
+        self
+    }
+
+    /// Sets the padding of the button and of every option in the list.
+    pub fn padding(mut self, padding: impl Into<Padding>) -> Self {
+        self.padding = padding.into();
+        self
+    }
+
+    /// Sets the maximum height of the opened list. Longer lists can be scrolled.
+    pub fn max_menu_height(mut self, height: f32) -> Self {
+        self.max_menu_height = height;
+        self
+    }
+
+    /// Sets the text size of the [`Dropdown`].
+    pub fn text_size(mut self, size: impl Into<Pixels>) -> Self {
+        self.text_size = Some(size.into());
+        self
+    }
+
+    /// Sets the font of the [`Dropdown`].
+    pub fn font(mut self, font: Font) -> Self {
+        self.font = Some(font);
+        self
+    }
+
+    /// Sets the style of the [`Dropdown`].
+    pub fn style(mut self, style: impl Fn(&Theme, Status) -> Style + 'a) -> Self
+    where
+        Theme::Class<'a>: From<StyleFn<'a, Theme>>,
+    {
+        self.class = (Box::new(style) as StyleFn<'a, Theme>).into();
+        self
+    }
+
+    /// Sets the style class of the [`Dropdown`].
+    pub fn class(mut self, class: impl Into<Theme::Class<'a>>) -> Self {
+        self.class = class.into();
+        self
+    }
+
+    fn selected_index(&self) -> Option<usize> {
+        let selected = self.selected.as_ref()?;
+        self.options.iter().position(|option| option == selected)
+    }
+
+    fn resolved_text_size<Renderer: TextRenderer>(&self, renderer: &Renderer) -> Pixels {
+        self.text_size.unwrap_or_else(|| renderer.default_size())
+    }
+
+    fn row_height<Renderer: TextRenderer>(&self, renderer: &Renderer) -> f32 {
+        row_height(self.resolved_text_size(renderer), self.padding)
+    }
+}
+
+impl<'a, T, Message, Theme, Renderer> Widget<Message, Theme, Renderer>
+    for Dropdown<'a, T, Message, Theme>
+where
+    T: ToString + PartialEq + Clone + 'a,
+    Message: 'a,
+    Theme: Catalog + 'a,
+    Renderer: TextRenderer + 'a,
+    Renderer::Font: From<crate::Font>,
+{
+    fn tag(&self) -> tree::Tag {
+        tree::Tag::of::<State>()
+    }
+
+    fn state(&self) -> tree::State {
+        tree::State::new(State::default())
+    }
+
+    fn size(&self) -> Size<Length> {
+        Size {
+            width: self.width,
+            height: Length::Shrink,
+        }
+    }
+
+    fn layout(
+        &mut self,
+        _tree: &mut Tree,
+        renderer: &Renderer,
+        limits: &layout::Limits,
+    ) -> layout::Node {
+        layout::atomic(limits, self.width, self.row_height(renderer))
+    }
+
+    fn draw(
+        &self,
+        tree: &Tree,
+        renderer: &mut Renderer,
+        theme: &Theme,
+        _style: &renderer::Style,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        viewport: &Rectangle,
+    ) {
+        let state = tree.state.downcast_ref::<State>();
+        let bounds = layout.bounds();
+
+        let status = if state.is_open {
+            Status::Opened
+        } else if cursor.is_over(bounds) {
+            Status::Hovered
+        } else if state.is_focused {
+            Status::Focused
+        } else {
+            Status::Active
+        };
+        let appearance = theme.style(&self.class, status);
+
+        renderer.fill_quad(
+            renderer::Quad {
+                bounds: scale::pixel_snap(bounds),
+                border: util::snap_border(appearance.border),
+                ..Default::default()
+            },
+            appearance.background,
+        );
+
+        // A small downwards pointing arrow made out of stacked quads, so we don't need an icon font
+        let handle_x = bounds.x + bounds.width - self.padding.right - HANDLE_SIZE;
+        let handle_y = bounds.center_y() - (HANDLE_SIZE / 4.0);
+        for row in 0..(HANDLE_SIZE / 2.0).ceil() as usize {
+            let inset = row as f32;
+            renderer.fill_quad(
+                renderer::Quad {
+                    bounds: scale::pixel_snap(Rectangle {
+                        x: handle_x + inset,
+                        y: handle_y + inset,
+                        width: HANDLE_SIZE - (inset * 2.0),
+                        height: 1.0,
+                    }),
+                    ..Default::default()
+                },
+                appearance.handle_color,
+            );
+        }
+
+        let (content, text_color) = match (&self.selected, &self.placeholder) {
+            (Some(selected), _) => (selected.to_string(), appearance.text_color),
+            (None, Some(placeholder)) => (placeholder.clone(), appearance.placeholder_color),
+            (None, None) => return,
+        };
+
+        let text_bounds = Rectangle {
+            x: bounds.x + self.padding.left,
+            width: (bounds.width - self.padding.left - self.padding.right - HANDLE_SIZE).max(0.0),
+            ..bounds
+        };
+        let text = text::Text {
+            content,
+            font: self
+                .font
+                .map(Renderer::Font::from)
+                .unwrap_or_else(|| renderer.default_font()),
+            size: self.resolved_text_size(renderer),
+            bounds: text_bounds.size(),
+            align_x: alignment::Horizontal::Left.into(),
+            align_y: alignment::Vertical::Center,
+            line_height: Default::default(),
+            shaping: Default::default(),
+            wrapping: text::Wrapping::None,
+        };
+        let position = scale::snap_text(&text, Point::new(text_bounds.x, text_bounds.center_y()));
+        renderer.fill_text(
+            text,
+            position,
+            text_color,
+            text_bounds.intersection(viewport).unwrap_or(text_bounds),
+        );
+    }
+
+    fn update(
+        &mut self,
+        tree: &mut Tree,
+        event: &Event,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        _renderer: &Renderer,
+        _clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Message>,
+        _viewport: &Rectangle,
+    ) {
+        let state = tree.state.downcast_mut::<State>();
+
+        match event {
+            Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left))
+            | Event::Touch(touch::Event::FingerPressed { .. }) => {
+                if cursor.is_over(layout.bounds()) {
+                    if state.is_open {
+                        state.close();
+                    } else {
+                        state.open(self.selected_index());
+                    }
+
+                    shell.capture_event();
+                } else {
+                    // Clicks outside of an open menu are handled by the overlay
+                    state.is_focused = false;
+                }
+            }
+            Event::Keyboard(keyboard::Event::KeyPressed { key, .. }) if state.is_focused => {
+                let keyboard::Key::Named(named) = key else {
+                    return;
+                };
+
+                if state.is_open {
+                    let navigation = match named {
+                        key::Named::ArrowDown => Some(Navigation::Next),
+                        key::Named::ArrowUp => Some(Navigation::Previous),
+                        key::Named::Home => Some(Navigation::First),
+                        key::Named::End => Some(Navigation::Last),
+                        key::Named::Enter | key::Named::Space => {
+                            if let Some(option) =
+                                state.highlighted.and_then(|index| self.options.get(index))
+                            {
+                                (self.on_select)(option.clone(), shell);
+                            }
+
+                            state.close();
+                            None
+                        }
+                        key::Named::Escape => {
+                            state.close();
+                            None
+                        }
+                        key::Named::Tab => {
+                            // Let focus move on to the next widget
+                            state.is_open = false;
+                            return;
+                        }
+                        _ => return,
+                    };
+
+                    if let Some(navigation) = navigation {
+                        state.highlighted =
+                            navigate(state.highlighted, self.options.len(), navigation);
+                        if let Some(highlighted) = state.highlighted {
+                            state.scroll_offset = scroll_to_visible(
+                                state.scroll_offset,
+                                highlighted,
+                                state.visible_rows,
+                            );
+                        }
+                    }
+
+                    shell.capture_event();
+                } else if matches!(
+                    named,
+                    key::Named::Enter
+                        | key::Named::Space
+                        | key::Named::ArrowDown
+                        | key::Named::ArrowUp
+                ) {
+                    state.open(self.selected_index());
+                    shell.capture_event();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn mouse_interaction(
+        &self,
+        _tree: &Tree,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        _viewport: &Rectangle,
+        _renderer: &Renderer,
+    ) -> mouse::Interaction {
+        if cursor.is_over(layout.bounds()) {
+            mouse::Interaction::Pointer
+        } else {
+            mouse::Interaction::default()
+        }
+    }
+
+    fn operate(
+        &mut self,
+        tree: &mut Tree,
+        layout: Layout<'_>,
+        _renderer: &Renderer,
+        operation: &mut dyn Operation,
+    ) {
+        let state = tree.state.downcast_mut::<State>();
+        operation.focusable(self.id.as_ref(), layout.bounds(), state);
+    }
+
+    fn overlay<'b>(
+        &'b mut self,
+        tree: &'b mut Tree,
+        layout: Layout<'b>,
+        renderer: &Renderer,
+        _viewport: &Rectangle,
+        translation: Vector,
+    ) -> Option<overlay::Element<'b, Message, Theme, Renderer>> {
+        let state = tree.state.downcast_mut::<State>();
+        if !state.is_open {
+            return None;
+        }
+
+        let bounds = layout.bounds() + translation;
+        let text_size = self.resolved_text_size(renderer);
+        let font = self
+            .font
+            .map(Renderer::Font::from)
+            .unwrap_or_else(|| renderer.default_font());
+
+        Some(overlay::Element::new(Box::new(Menu {
+            state,
+            options: &self.options,
+            on_select: &self.on_select,
+            button_bounds: bounds,
+            max_height: self.max_menu_height,
+            row_height: row_height(text_size, self.padding),
+            padding: self.padding,
+            text_size,
+            font,
+            class: &self.class,
+        })))
+    }
+}
+
+impl<'a, T, Message, Theme, Renderer> From<Dropdown<'a, T, Message, Theme>>
+    for Element<'a, Message, Theme, Renderer>
+where
+    T: ToString + PartialEq + Clone + 'a,
+    Message: 'a,
+    Theme: Catalog + 'a,
+    Renderer: TextRenderer + 'a,
+    Renderer::Font: From<crate::Font>,
+{
+    fn from(widget: Dropdown<'a, T, Message, Theme>) -> Self {
+        Element::new(widget)
+    }
+}
+
+/// The opened list of a [`Dropdown`], drawn on iced's overlay layer so it's shown on top of every
+/// other widget in the window.
+struct Menu<'a, 'b, T, Message, Theme, Renderer>
+where
+    Theme: Catalog,
+    Renderer: TextRenderer,
+{
+    state: &'a mut State,
+    options: &'a [T],
+    on_select: &'a dyn Fn(T, &mut Shell<'_, Message>),
+    /// The absolute bounds of the button the menu belongs to.
+    button_bounds: Rectangle,
+    max_height: f32,
+    row_height: f32,
+    padding: Padding,
+    text_size: Pixels,
+    font: Renderer::Font,
+    class: &'a Theme::Class<'b>,
+}
+
+impl<T, Message, Theme, Renderer> Menu<'_, '_, T, Message, Theme, Renderer>
+where
+    Theme: Catalog,
+    Renderer: TextRenderer,
+{
+    /// The index of the option under the cursor, if any.
+    fn option_at(&self, bounds: Rectangle, cursor: mouse::Cursor) -> Option<usize> {
+        let position = cursor.position_over(bounds)?;
+        let index =
+            self.state.scroll_offset + ((position.y - bounds.y) / self.row_height).floor() as usize;
+
+        (index < self.options.len()).then_some(index)
+    }
+}
+
+impl<T, Message, Theme, Renderer> overlay::Overlay<Message, Theme, Renderer>
+    for Menu<'_, '_, T, Message, Theme, Renderer>
+where
+    T: ToString + Clone,
+    Theme: Catalog,
+    Renderer: TextRenderer,
+{
+    fn layout(&mut self, _renderer: &Renderer, bounds: Size) -> layout::Node {
+        let content_height = self.options.len() as f32 * self.row_height;
+        let menu_bounds = menu_bounds(
+            self.button_bounds,
+            Size::new(
+                self.button_bounds.width,
+                content_height.min(self.max_height),
+            ),
+            bounds,
+        );
+
+        self.state.visible_rows = ((menu_bounds.height / self.row_height).floor() as usize).max(1);
+        self.state.scroll_offset = self
+            .state
+            .scroll_offset
+            .min(self.options.len().saturating_sub(self.state.visible_rows));
+
+        layout::Node::new(menu_bounds.size()).move_to(menu_bounds.position())
+    }
+
+    fn draw(
+        &self,
+        renderer: &mut Renderer,
+        theme: &Theme,
+        _style: &renderer::Style,
+        layout: Layout<'_>,
+        _cursor: mouse::Cursor,
+    ) {
+        let bounds = layout.bounds();
+        let appearance = theme.style(self.class, Status::Opened);
+
+        renderer.fill_quad(
+            renderer::Quad {
+                bounds: scale::pixel_snap(bounds),
+                border: util::snap_border(appearance.menu_border),
+                ..Default::default()
+            },
+            appearance.menu_background,
+        );
+
+        renderer.with_layer(bounds, |renderer| {
+            let visible_options = self
+                .options
+                .iter()
+                .enumerate()
+                .skip(self.state.scroll_offset)
+                .take(self.state.visible_rows + 1);
+            for (row, (index, option)) in visible_options.enumerate() {
+                let row_bounds = Rectangle {
+                    x: bounds.x,
+                    y: bounds.y + (row as f32 * self.row_height),
+                    width: bounds.width,
+                    height: self.row_height,
+                };
+
+                let text_color = if self.state.highlighted == Some(index) {
+                    renderer.fill_quad(
+                        renderer::Quad {
+                            bounds: row_bounds.shrink(BORDER_WIDTH),
+                            ..Default::default()
+                        },
+                        appearance.highlighted_background,
+                    );
+
+                    appearance.highlighted_text_color
+                } else {
+                    appearance.menu_text_color
+                };
+
+                let text = text::Text {
+                    content: option.to_string(),
+                    font: self.font,
+                    size: self.text_size,
+                    bounds: Size::new(
+                        row_bounds.width - self.padding.left - self.padding.right,
+                        row_bounds.height,
+                    ),
+                    align_x: alignment::Horizontal::Left.into(),
+                    align_y: alignment::Vertical::Center,
+                    line_height: Default::default(),
+                    shaping: Default::default(),
+                    wrapping: text::Wrapping::None,
+                };
+                let position = scale::snap_text(
+                    &text,
+                    Point::new(row_bounds.x + self.padding.left, row_bounds.center_y()),
+                );
+                renderer.fill_text(text, position, text_color, bounds);
+            }
+        });
+    }
+
+    fn update(
+        &mut self,
+        event: &Event,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        _renderer: &Renderer,
+        _clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Message>,
+    ) {
+        let bounds = layout.bounds();
+
+        match event {
+            Event::Mouse(mouse::Event::CursorMoved { .. }) => {
+                if let Some(index) = self.option_at(bounds, cursor) {
+                    self.state.highlighted = Some(index);
+                }
+            }
+            Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left))
+            | Event::Touch(touch::Event::FingerPressed { .. }) => {
+                if cursor.is_over(bounds) {
+                    if let Some(index) = self.option_at(bounds, cursor) {
+                        (self.on_select)(self.options[index].clone(), shell);
+                        self.state.close();
+                    }
+
+                    shell.capture_event();
+                } else if !cursor.is_over(self.button_bounds) {
+                    // Clicking on the button itself toggles the menu in the widget's own update
+                    self.state.close();
+                    shell.capture_event();
+                }
+            }
+            Event::Mouse(mouse::Event::WheelScrolled { delta }) if cursor.is_over(bounds) => {
+                let rows = match *delta {
+                    mouse::ScrollDelta::Lines { y, .. } => y * interaction::current().scroll_step,
+                    mouse::ScrollDelta::Pixels { y, .. } => y / self.row_height,
+                };
+                let rows = self.state.wheel.whole_steps(rows);
+
+                let max_offset = self.options.len().saturating_sub(self.state.visible_rows);
+                self.state.scroll_offset = (self.state.scroll_offset as i64 - rows as i64)
+                    .clamp(0, max_offset as i64)
+                    as usize;
+
+                shell.capture_event();
+            }
+            _ => {}
+        }
+    }
+
+    fn mouse_interaction(
+        &self,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        _renderer: &Renderer,
+    ) -> mouse::Interaction {
+        if cursor.is_over(layout.bounds()) {
+            mouse::Interaction::Pointer
+        } else {
+            mouse::Interaction::default()
+        }
+    }
+}
+
+/// The height of the button and of every option in the list.
+fn row_height(text_size: Pixels, padding: Padding) -> f32 {
+    text::LineHeight::default().to_absolute(text_size).0 + padding.top + padding.bottom
+}
+
+/// Compute where the opened menu should go. The menu is placed below the button if it fits there,
+/// and above it if there's more room above. It's always clamped to the window.
+fn menu_bounds(button: Rectangle, menu_size: Size, window: Size) -> Rectangle {
+    let width = menu_size.width.min(window.width);
+    let x = button.x.clamp(0.0, window.width - width);
+
+    let space_below = (window.height - (button.y + button.height)).max(0.0);
+    let space_above = button.y.max(0.0);
+    if menu_size.height <= space_below || space_below >= space_above {
+        Rectangle {
+            x,
+            y: (button.y + button.height).min(window.height),
+            width,
+            height: menu_size.height.min(space_below),
+        }
+    } else {
+        let height = menu_size.height.min(space_above);
+
+        Rectangle {
+            x,
+            y: button.y - height,
+            width,
+            height,
+        }
+    }
+}
+
+/// Move the highlighted option in a list of `len` options, wrapping around at either end.
+fn navigate(current: Option<usize>, len: usize, navigation: Navigation) -> Option<usize> {
+    if len == 0 {
+        return None;
+    }
+
+    Some(match (navigation, current) {
+        (Navigation::First, _) | (Navigation::Next, None) => 0,
+        (Navigation::Last, _) | (Navigation::Previous, None) => len - 1,
+        (Navigation::Next, Some(current)) => (current + 1) % len,
+        (Navigation::Previous, Some(current)) => (current.min(len - 1) + len - 1) % len,
+    })
+}
+
+/// Adjust the scroll offset so the row at `index` is visible.
+fn scroll_to_visible(offset: usize, index: usize, visible_rows: usize) -> usize {
+    if index < offset {
+        index
+    } else if visible_rows > 0 && index >= offset + visible_rows {
+        index + 1 - visible_rows
+    } else {
+        offset
+    }
+}
+
+/// The possible status of a [`Dropdown`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    /// The [`Dropdown`] can be interacted with.
+    Active,
+    /// The [`Dropdown`] is being hovered.
+    Hovered,
+    /// The [`Dropdown`] has keyboard focus.
+    Focused,
+    /// The [`Dropdown`]'s list of options is open.
+    Opened,
+}
+
+/// The appearance of a [`Dropdown`] and its list of options.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Style {
+    /// The [`Background`] of the button.
+    pub background: Background,
+    /// The [`Border`] of the button.
+    pub border: Border,
+    /// The color of the selected option's text.
+    pub text_color: Color,
+    /// The color of the placeholder text.
+    pub placeholder_color: Color,
+    /// The color of the arrow on the right side of the button.
+    pub handle_color: Color,
+    /// The [`Background`] of the opened list.
+    pub menu_background: Background,
+    /// The [`Border`] of the opened list.
+    pub menu_border: Border,
+    /// The color of the options' text.
+    pub menu_text_color: Color,
+    /// The [`Background`] of the highlighted option.
+    pub highlighted_background: Background,
+    /// The color of the highlighted option's text.
+    pub highlighted_text_color: Color,
+}
+
+/// The theme catalog of a [`Dropdown`].
+pub trait Catalog: Sized {
+    /// The item class of the [`Catalog`].
+    type Class<'a>;
+
+    /// The default class produced by the [`Catalog`].
+    fn default<'a>() -> Self::Class<'a>;
+
+    /// The [`Style`] of a class with the given status.
+    fn style(&self, class: &Self::Class<'_>, status: Status) -> Style;
+}
+
+/// A styling function for a [`Dropdown`].
+pub type StyleFn<'a, Theme> = Box<dyn Fn(&Theme, Status) -> Style + 'a>;
+
+impl Catalog for Theme {
+    type Class<'a> = StyleFn<'a, Self>;
+
+    fn default<'a>() -> Self::Class<'a> {
+        Box::new(default)
+    }
+
+    fn style(&self, class: &Self::Class<'_>, status: Status) -> Style {
+        class(self, status)
+    }
+}
+
+/// The default style of a [`Dropdown`].
+pub fn default(theme: &Theme, status: Status) -> Style {
+    let colors = crate::theme::colors(theme);
+
+    let (background, border_color) = match status {
+        Status::Active => (colors.background, colors.outline),
+        Status::Hovered => (colors.surface, colors.outline),
+        Status::Focused | Status::Opened => (colors.background, colors.accent),
+    };
+    let border = Border {
+        color: border_color,
+        width: BORDER_WIDTH,
+        radius: 2.0.into(),
+    };
+
+    Style {
+        background: Background::Color(background),
+        border,
+        text_color: colors.text,
+        placeholder_color: colors.text_muted,
+        handle_color: colors.text,
+        menu_background: Background::Color(colors.background),
+        menu_border: border,
+        menu_text_color: colors.text,
+        highlighted_background: Background::Color(colors.accent),
+        highlighted_text_color: colors.accent_text,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keyboard_navigation_wraps() {
+        assert_eq!(navigate(None, 3, Navigation::Next), Some(0));
+        assert_eq!(navigate(None, 3, Navigation::Previous), Some(2));
+        assert_eq!(navigate(Some(0), 3, Navigation::Next), Some(1));
+        assert_eq!(navigate(Some(2), 3, Navigation::Next), Some(0));
+        assert_eq!(navigate(Some(0), 3, Navigation::Previous), Some(2));
+        assert_eq!(navigate(Some(1), 3, Navigation::First), Some(0));
+        assert_eq!(navigate(Some(1), 3, Navigation::Last), Some(2));
+
+        // The list of options may have shrunk since the option was highlighted
+        assert_eq!(navigate(Some(5), 3, Navigation::Previous), Some(1));
+        assert_eq!(navigate(Some(5), 3, Navigation::Next), Some(0));
+        assert_eq!(navigate(Some(0), 0, Navigation::Next), None);
+    }
+
+    #[test]
+    fn menu_is_clamped_to_window() {
+        let window = Size::new(400.0, 300.0);
+
+        // Fits below the button
+        let button = Rectangle::new(Point::new(10.0, 10.0), Size::new(100.0, 20.0));
+        let menu = menu_bounds(button, Size::new(100.0, 100.0), window);
+        assert_eq!(
+            menu,
+            Rectangle::new(Point::new(10.0, 30.0), Size::new(100.0, 100.0))
+        );
+
+        // Not enough room below, so it flips above the button
+        let button = Rectangle::new(Point::new(350.0, 250.0), Size::new(100.0, 20.0));
+        let menu = menu_bounds(button, Size::new(100.0, 100.0), window);
+        assert_eq!(
+            menu,
+            Rectangle::new(Point::new(300.0, 150.0), Size::new(100.0, 100.0))
+        );
+    }
+}
@@ -0,0 +1,522 @@
+//! A single line of text that's shortened with an ellipsis when it doesn't fit.
+//!
+//! Long parameter names overflow narrow label columns, and iced's own text widget then cuts them
+//! off in the middle of a glyph. [`EllipsisText`] measures its content during layout, and when it's
+//! wider than the available space, replaces the end or the middle of the text with "…". Hovering a
+//! shortened text shows the full text in a [`Tooltip`][super::Tooltip]-styled overlay after the
+//! [delay][EllipsisText::delay].
+//!
+//! The text is only ever cut between grapheme clusters, so accented letters written with combining
+//! characters, emoji sequences, and similar clusters stay intact. The text is shaped with
+//! [`Shaping::Advanced`][text::Shaping::Advanced] for the same reason. The shortened text is
+//! computed again whenever the available width, the window's scale factor, the text size, or the
+//! content changes.
+
+use std::time::{Duration, Instant};
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::core::text::{self, Paragraph, Renderer as TextRenderer};
+use crate::core::widget::tree::{self, Tree};
+use crate::core::{
+    alignment, layout, mouse, overlay, renderer, Clipboard, Color, Element, Event, Font, Layout,
+    Length, Pixels, Point, Rectangle, Shell, Size, Vector, Widget,
+};
+use crate::scale;
+use crate::widget::text as text_widget;
+
+use super::tooltip::{self, Position};
+
+/// The character that replaces the part of the text that doesn't fit.
+pub const ELLIPSIS: &str = "…";
+
+/// Which part of an [`EllipsisText`]'s content is replaced with the ellipsis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Truncation {
+    /// Keep the start of the text, like "Oscillator 2 Pha…".
+    #[default]
+    End,
+    /// Keep both the start and the end of the text, like "Oscillator…n Amount". This keeps
+    /// labels that only differ in their last words apart.
+    Middle,
+}
+
+/// A single line of text that's shortened with an ellipsis when it doesn't fit. See the [module
+/// documentation][self].
+pub struct EllipsisText<'a, Message, Theme = crate::Theme, Renderer = crate::Renderer>
+where
+    Theme: tooltip::Catalog,
+{
+    content: String,
+    /// The full text shown while hovering a shortened text.
+    full_text: Element<'a, Message, Theme, Renderer>,
+
+    width: Length,
+    height: Length,
+    size: Option<Pixels>,
+    font: Option<Font>,
+    align_x: alignment::Horizontal,
+    color: Option<Color>,
+    truncation: Truncation,
+    delay: Duration,
+    tooltip_class: Theme::Class<'a>,
+}
+
+/// State for an [`EllipsisText`].
+#[derive(Debug, Default)]
+struct State {
+    /// The text that was laid out last, and what it was laid out for.
+    measured: Option<Measured>,
+    /// When the cursor started hovering the text, if it's hovering it now.
+    hovered_since: Option<Instant>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Measured {
+    content: String,
+    max_width: f32,
+    scale_factor: f32,
+    size: f32,
+    font: Option<Font>,
+    /// The shortened text, or `None` if the whole text fits.
+    shortened: Option<String>,
+}
+
+impl<'a, Message, Theme, Renderer> EllipsisText<'a, Message, Theme, Renderer>
+where
+    Message: 'a,
+    Theme: tooltip::Catalog + text_widget::Catalog + 'a,
+    Renderer: TextRenderer + 'a,
+    Renderer::Font: From<crate::Font>,
+{
+    /// The default hover delay before the full text is shown.
+    pub const DEFAULT_DELAY: Duration = Duration::from_millis(500);
+
+    /// Creates a new [`EllipsisText`] with the given content.
+    pub fn new(content: impl Into<String>) -> Self {
+        let content = content.into();
+
+        Self {
+            full_text: Self::full_text(&content, None, None),
+            content,
+
+            width: Length::Fill,
+            height: Length::Shrink,
+            size: None,
+            font: None,
+            align_x: alignment::Horizontal::Left,
+            color: None,
+            truncation: Truncation::End,
+            delay: Self::DEFAULT_DELAY,
+            tooltip_class: <Theme as tooltip::Catalog>::default(),
+        }
+    }
+
+    /// Sets the text size of the [`EllipsisText`].
+    pub fn size(mut self, size: impl Into<Pixels>) -> Self {
+        self.size = Some(size.into());
+        self.full_text = Self::full_text(&self.content, self.size, self.font);
+        self
+    }
+
+    /// Sets the font of the [`EllipsisText`].
+    pub fn font(mut self, font: Font) -> Self {
+        self.font = Some(font);
+        self.full_text = Self::full_text(&self.content, self.size, self.font);
+        self
+    }
+
+    /// The text shown in the overlay, which uses the same size and font.
+    fn full_text(
+        content: &str,
+        size: Option<Pixels>,
+        font: Option<Font>,
+    ) -> Element<'a, Message, Theme, Renderer> {
+        let mut full_text = text_widget(content.to_owned()).shaping(text::Shaping::Advanced);
+        if let Some(size) = size {
+            full_text = full_text.size(size);
+        }
+        if let Some(font) = font {
+            full_text = full_text.font(font);
+        }
+
+        full_text.into()
+    }
+}
+
+impl<'a, Message, Theme, Renderer> EllipsisText<'a, Message, Theme, Renderer>
+where
+    Theme: tooltip::Catalog,
+{
+    /// Sets the width of the [`EllipsisText`]. The text fills the available width by default.
+    pub fn width(mut self, width: impl Into<Length>) -> Self {
+        self.width = width.into();
+        self
+    }
+
+    /// Sets the height of the [`EllipsisText`].
+    pub fn height(mut self, height: impl Into<Length>) -> Self {
+        self.height = height.into();
+        self
+    }
+
+    /// Sets the horizontal alignment of the text within the [`EllipsisText`]'s bounds.
+    pub fn align_x(mut self, align_x: alignment::Horizontal) -> Self {
+        self.align_x = align_x;
+        self
+    }
+
+    /// Sets the color of the text. The window's text color is used by default.
+    pub fn color(mut self, color: Color) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    /// Sets which part of the text is replaced with the ellipsis.
+    pub fn truncation(mut self, truncation: Truncation) -> Self {
+        self.truncation = truncation;
+        self
+    }
+
+    /// Sets how long a shortened text needs to be hovered before the full text is shown.
+    pub fn delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// Sets the style class of the overlay that shows the full text.
+    pub fn tooltip_class(mut self, class: impl Into<Theme::Class<'a>>) -> Self {
+        self.tooltip_class = class.into();
+        self
+    }
+
+    fn text<Content, F: From<Font> + Copy>(
+        &self,
+        content: Content,
+        size: Pixels,
+        font: F,
+        bounds: Size,
+    ) -> text::Text<Content, F> {
+        text::Text {
+            content,
+            font,
+            size,
+            bounds,
+            align_x: self.align_x.into(),
+            align_y: alignment::Vertical::Center,
+            line_height: text::LineHeight::default(),
+            shaping: text::Shaping::Advanced,
+            wrapping: text::Wrapping::None,
+        }
+    }
+}
+
+impl<'a, Message, Theme, Renderer> Widget<Message, Theme, Renderer>
+    for EllipsisText<'a, Message, Theme, Renderer>
+where
+    Theme: tooltip::Catalog,
+    Renderer: TextRenderer,
+    Renderer::Font: From<crate::Font>,
+{
+    fn tag(&self) -> tree::Tag {
+        tree::Tag::of::<State>()
+    }
+
+    fn state(&self) -> tree::State {
+        tree::State::new(State::default())
+    }
+
+    fn children(&self) -> Vec<Tree> {
+        vec![Tree::new(&self.full_text)]
+    }
+
+    fn diff(&self, tree: &mut Tree) {
+        tree.diff_children(std::slice::from_ref(&self.full_text));
+    }
+
+    fn size(&self) -> Size<Length> {
+        Size::new(self.width, self.height)
+    }
+
+    fn layout(
+        &mut self,
+        tree: &mut Tree,
+        renderer: &Renderer,
+        limits: &layout::Limits,
+    ) -> layout::Node {
+        let size = self.size.unwrap_or_else(|| renderer.default_size());
+        let font = self
+            .font
+            .map(Renderer::Font::from)
+            .unwrap_or_else(|| renderer.default_font());
+        let measure = |content: &str| {
+            Renderer::Paragraph::with_text(self.text(content, size, font, Size::INFINITE))
+                .min_bounds()
+        };
+
+        let limits = limits.width(self.width).height(self.height);
+        let max_width = limits.max().width;
+        let state = tree.state.downcast_mut::<State>();
+        let is_current = state.measured.as_ref().is_some_and(|measured| {
+            measured.content == self.content
+                && measured.max_width == max_width
+                && measured.scale_factor == scale::scale_factor()
+                && measured.size == size.0
+                && measured.font == self.font
+        });
+        if !is_current {
+            state.measured = Some(Measured {
+                content: self.content.clone(),
+                max_width,
+                scale_factor: scale::scale_factor(),
+                size: size.0,
+                font: self.font,
+                shortened: shorten(&self.content, max_width, self.truncation, |content| {
+                    measure(content).width
+                }),
+            });
+        }
+
+        let shown = state
+            .measured
+            .as_ref()
+            .and_then(|measured| measured.shortened.as_deref())
+            .unwrap_or(&self.content);
+        let text_size = Size {
+            height: text::LineHeight::default().to_absolute(size).0,
+            ..measure(shown)
+        };
+
+        layout::Node::new(limits.resolve(self.width, self.height, text_size))
+    }
+
+    fn draw(
+        &self,
+        tree: &Tree,
+        renderer: &mut Renderer,
+        _theme: &Theme,
+        style: &renderer::Style,
+        layout: Layout<'_>,
+        _cursor: mouse::Cursor,
+        viewport: &Rectangle,
+    ) {
+        let state = tree.state.downcast_ref::<State>();
+        let bounds = layout.bounds();
+        let content = state
+            .measured
+            .as_ref()
+            .and_then(|measured| measured.shortened.clone())
+            .unwrap_or_else(|| self.content.clone());
+
+        let size = self.size.unwrap_or_else(|| renderer.default_size());
+        let font = self
+            .font
+            .map(Renderer::Font::from)
+            .unwrap_or_else(|| renderer.default_font());
+        let x = match self.align_x {
+            alignment::Horizontal::Left => bounds.x,
+            alignment::Horizontal::Center => bounds.center_x(),
+            alignment::Horizontal::Right => bounds.x + bounds.width,
+        };
+
+        let text = self.text(content, size, font, bounds.size());
+        let position = scale::snap_text(&text, Point::new(x, bounds.center_y()));
+        renderer.fill_text(
+            text,
+            position,
+            self.color.unwrap_or(style.text_color),
+            *viewport,
+        );
+    }
+
+    fn update(
+        &mut self,
+        tree: &mut Tree,
+        event: &Event,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        _renderer: &Renderer,
+        _clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Message>,
+        _viewport: &Rectangle,
+    ) {
+        let state = tree.state.downcast_mut::<State>();
+        if let Event::Mouse(_) = event {
+            if !cursor.is_over(layout.bounds()) {
+                state.hovered_since = None;
+            } else if state.hovered_since.is_none() {
+                let now = Instant::now();
+                state.hovered_since = Some(now);
+                shell.request_redraw_at(now + self.delay);
+            }
+        }
+    }
+
+    fn overlay<'b>(
+        &'b mut self,
+        tree: &'b mut Tree,
+        layout: Layout<'b>,
+        _renderer: &Renderer,
+        _viewport: &Rectangle,
+        translation: Vector,
+    ) -> Option<overlay::Element<'b, Message, Theme, Renderer>> {
+        let state = tree.state.downcast_ref::<State>();
+        let is_shortened = state
+            .measured
+            .as_ref()
+            .is_some_and(|measured| measured.shortened.is_some());
+        let hovered_long_enough = state
+            .hovered_since
+            .is_some_and(|since| since.elapsed() >= self.delay);
+        if !(is_shortened && hovered_long_enough) {
+            return None;
+        }
+
+        Some(overlay::Element::new(Box::new(tooltip::Overlay {
+            tooltip: &mut self.full_text,
+            tree: &mut tree.children[0],
+            target: layout.bounds() + translation,
+            position: Position::Top,
+            gap: tooltip::DEFAULT_GAP,
+            padding: tooltip::DEFAULT_PADDING,
+            class: &self.tooltip_class,
+        })))
+    }
+}
+
+impl<'a, Message, Theme, Renderer> From<EllipsisText<'a, Message, Theme, Renderer>>
+    for Element<'a, Message, Theme, Renderer>
+where
+    Message: 'a,
+    Theme: tooltip::Catalog + 'a,
+    Renderer: TextRenderer + 'a,
+    Renderer::Font: From<crate::Font>,
+{
+    fn from(widget: EllipsisText<'a, Message, Theme, Renderer>) -> Self {
+        Element::new(widget)
+    }
+}
+
+/// Shorten `content` with an [`ELLIPSIS`] so it's at most `max_width` wide according to `measure`.
+/// Returns `None` if the content already fits. The content is only cut between grapheme clusters,
+/// and whitespace next to the ellipsis is removed. If not even a single cluster fits next to the
+/// ellipsis, then only the ellipsis is returned.
+pub fn shorten(
+    content: &str,
+    max_width: f32,
+    truncation: Truncation,
+    measure: impl Fn(&str) -> f32,
+) -> Option<String> {
+    if measure(content) <= max_width {
+        return None;
+    }
+
+    let graphemes: Vec<&str> = content.graphemes(true).collect();
+    let shortened = |kept: usize| {
+        let (head, tail) = match truncation {
+            Truncation::End => (kept, 0),
+            Truncation::Middle => (kept - kept / 2, kept / 2),
+        };
+        let head = graphemes[..head].concat();
+        let tail = graphemes[graphemes.len() - tail..].concat();
+
+        format!("{}{ELLIPSIS}{}", head.trim_end(), tail.trim_start())
+    };
+
+    // Find the most clusters that still fit, assuming that keeping more never makes the text
+    // narrower
+    let (mut fits, mut too_wide) = (0, graphemes.len());
+    while too_wide - fits > 1 {
+        let kept = (fits + too_wide) / 2;
+        if measure(&shortened(kept)) <= max_width {
+            fits = kept;
+        } else {
+            too_wide = kept;
+        }
+    }
+
+    Some(shortened(fits))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Every character is one unit wide, and CJK characters are two units wide. Combining
+    /// characters and joiners count as well, so cutting through a cluster would produce a text
+    /// that's narrower than one that keeps the cluster intact.
+    fn measure(content: &str) -> f32 {
+        content
+            .chars()
+            .map(|c| if c >= '\u{2E80}' { 2.0 } else { 1.0 })
+            .sum()
+    }
+
+    fn shorten_end(content: &str, max_width: f32) -> Option<String> {
+        shorten(content, max_width, Truncation::End, measure)
+    }
+
+    fn shorten_middle(content: &str, max_width: f32) -> Option<String> {
+        shorten(content, max_width, Truncation::Middle, measure)
+    }
+
+    #[test]
+    fn shortening_long_labels() {
+        let label = "Oscillator 2 Phase Randomization Amount";
+        assert_eq!(shorten_end(label, 100.0), None);
+        assert_eq!(shorten_end(label, 39.0), None);
+        assert_eq!(
+            shorten_end(label, 38.0).as_deref(),
+            Some("Oscillator 2 Phase Randomization Amou…")
+        );
+        // The space before the ellipsis is dropped
+        assert_eq!(shorten_end(label, 12.0).as_deref(), Some("Oscillator…"));
+        assert_eq!(
+            shorten_middle(label, 16.0).as_deref(),
+            Some("Oscillat…Amount")
+        );
+
+        // Too narrow for anything but the ellipsis
+        assert_eq!(shorten_end(label, 1.0).as_deref(), Some(ELLIPSIS));
+        assert_eq!(shorten_middle(label, 0.0).as_deref(), Some(ELLIPSIS));
+    }
+
+    #[test]
+    fn clusters_stay_intact() {
+        // The accent is a combining character, so "Cafe…" would fit but splits the "é"
+        let cafe = "Cafe\u{301} Noir";
+        assert_eq!(shorten_end(cafe, 5.0).as_deref(), Some("Caf…"));
+        assert_eq!(shorten_end(cafe, 6.0).as_deref(), Some("Cafe\u{301}…"));
+        assert_eq!(shorten_middle(cafe, 8.0).as_deref(), Some("Caf…oir"));
+        assert_eq!(
+            shorten_middle(cafe, 9.0).as_deref(),
+            Some("Cafe\u{301}…oir")
+        );
+
+        // A family emoji is five characters joined into a single cluster
+        let family = "\u{1F469}\u{200D}\u{1F469}\u{200D}\u{1F467} Family";
+        assert_eq!(shorten_end(family, 3.0).as_deref(), Some(ELLIPSIS));
+
+        // Decomposed Hangul syllables are clusters of jamo
+        let syllable = "\u{1100}\u{1161}\u{11A8}";
+        let hangul = syllable.repeat(3);
+        assert_eq!(
+            shorten_end(&hangul, 8.0),
+            Some(format!("{}…", syllable.repeat(2)))
+        );
+    }
+
+    #[test]
+    fn wide_characters() {
+        let envelope = "音量エンベロープ";
+        assert_eq!(shorten_end(envelope, 16.0), None);
+        assert_eq!(shorten_end(envelope, 9.0).as_deref(), Some("音量エン…"));
+        // An odd width can't be filled with two unit wide characters
+        assert_eq!(shorten_end(envelope, 10.0).as_deref(), Some("音量エン…"));
+        assert_eq!(shorten_middle(envelope, 9.0).as_deref(), Some("音量…ープ"));
+        assert_eq!(
+            shorten_middle("Filter カットオフ", 10.0).as_deref(),
+            Some("Fil…トオフ")
+        );
+    }
+}
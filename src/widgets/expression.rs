@@ -0,0 +1,342 @@
+//! A tiny expression parser for typed-in parameter values.
+//!
+//! This lets users type things like `1k`, `-3.5dB`, `120*2`, or `+3` into a parameter's value
+//! entry field. The following is supported:
+//!
+//! - Numbers with an optional unit suffix: `k` (times 1000), `%` (hundredths, unless the
+//!   parameter's unit is already a percentage), `dB`, `Hz`, `kHz`, `s`, `ms`, and `m` for
+//!   milliseconds. Because `m` could just as well mean minutes or meters it's only accepted when
+//!   the parameter's unit is seconds. Time and frequency suffixes are converted to the parameter's
+//!   own unit, and they're rejected if they don't match that unit.
+//! - Basic arithmetic with `+`, `-`, `*`, and `/`, with the usual precedence rules, unary minus,
+//!   and parentheses.
+//! - Relative entry. An expression starting with `+`, `*`, or `/` is applied to the parameter's
+//!   current value, so `+3` adds three to the current value and `*2` doubles it. Since `-3` is a
+//!   perfectly normal absolute value, subtracting is done with `+-3`.
+
+/// The kind of quantity a unit describes. Suffixes can only be converted between units of the
+/// same kind.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Quantity {
+    Frequency,
+    Time,
+    Decibels,
+}
+
+/// Known units, their kind, and how many base units (hertz, seconds) they're worth.
+const UNITS: [(&str, Quantity, f64); 5] = [
+    ("hz", Quantity::Frequency, 1.0),
+    ("khz", Quantity::Frequency, 1000.0),
+    ("s", Quantity::Time, 1.0),
+    ("ms", Quantity::Time, 0.001),
+    ("db", Quantity::Decibels, 1.0),
+];
+
+fn lookup_unit(unit: &str) -> Option<(Quantity, f64)> {
+    let unit = unit.trim().to_lowercase();
+    UNITS
+        .iter()
+        .find(|(name, _, _)| *name == unit)
+        .map(|(_, quantity, scale)| (*quantity, *scale))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Suffix(String),
+    Plus,
+    Minus,
+    Star,
+    Slash,
+    LeftParen,
+    RightParen,
+}
+
+fn tokenize(input: &str) -> Option<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '0'..='9' | '.' => {
+                let mut number = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_ascii_digit() || c == '.' {
+                        number.push(c);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+
+                tokens.push(Token::Number(number.parse().ok()?));
+            }
+            c if c.is_alphabetic() || c == '%' => {
+                let mut suffix = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_alphabetic() || c == '%' {
+                        suffix.push(c);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+
+                tokens.push(Token::Suffix(suffix));
+            }
+            _ => {
+                tokens.push(match c {
+                    '+' => Token::Plus,
+                    // Also accept the proper minus sign and multiplication and division signs
+                    '-' | '\u{2212}' => Token::Minus,
+                    '*' | '\u{00d7}' => Token::Star,
+                    '/' | '\u{00f7}' => Token::Slash,
+                    '(' => Token::LeftParen,
+                    ')' => Token::RightParen,
+                    _ => return None,
+                });
+                chars.next();
+            }
+        }
+    }
+
+    Some(tokens)
+}
+
+/// A recursive descent parser over the tokens produced by [`tokenize()`].
+struct Parser<'a> {
+    tokens: &'a [Token],
+    position: usize,
+    /// The parameter's unit, used to interpret suffixes.
+    unit: &'a str,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<&Token> {
+        let token = self.tokens.get(self.position);
+        self.position += 1;
+        token
+    }
+
+    /// `expression := term (('+' | '-') term)*`
+    fn expression(&mut self) -> Option<f64> {
+        let mut value = self.term()?;
+        loop {
+            match self.peek() {
+                Some(Token::Plus) => {
+                    self.position += 1;
+                    value += self.term()?;
+                }
+                Some(Token::Minus) => {
+                    self.position += 1;
+                    value -= self.term()?;
+                }
+                _ => return Some(value),
+            }
+        }
+    }
+
+    /// `term := factor (('*' | '/') factor)*`
+    fn term(&mut self) -> Option<f64> {
+        let mut value = self.factor()?;
+        loop {
+            match self.peek() {
+                Some(Token::Star) => {
+                    self.position += 1;
+                    value *= self.factor()?;
+                }
+                Some(Token::Slash) => {
+                    self.position += 1;
+                    value /= self.factor()?;
+                }
+                _ => return Some(value),
+            }
+        }
+    }
+
+    /// `factor := ('-' | '+') factor | number suffix? | '(' expression ')'`
+    fn factor(&mut self) -> Option<f64> {
+        match self.next()? {
+            Token::Minus => Some(-self.factor()?),
+            Token::Plus => self.factor(),
+            Token::Number(number) => {
+                let number = *number;
+                match self.peek() {
+                    Some(Token::Suffix(suffix)) => {
+                        let scale = suffix_scale(suffix, self.unit)?;
+                        self.position += 1;
+
+                        Some(number * scale)
+                    }
+                    _ => Some(number),
+                }
+            }
+            Token::LeftParen => {
+                let value = self.expression()?;
+                match self.next()? {
+                    Token::RightParen => Some(value),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
+/// The factor a number with a suffix should be multiplied by to end up in the parameter's unit.
+/// Returns `None` if the suffix doesn't make sense for this unit.
+fn suffix_scale(suffix: &str, unit: &str) -> Option<f64> {
+    let unit_info = lookup_unit(unit);
+
+    match suffix.to_lowercase().as_str() {
+        "k" => return Some(1000.0),
+        "%" if unit.trim() == "%" => return Some(1.0),
+        "%" => return Some(0.01),
+        "m" => {
+            // This is only unambiguous for parameters that are in seconds
+            return match unit_info {
+                Some((Quantity::Time, 1.0)) => Some(0.001),
+                _ => None,
+            };
+        }
+        _ => (),
+    }
+
+    let (suffix_quantity, suffix_scale) = lookup_unit(suffix)?;
+    match unit_info {
+        Some((unit_quantity, unit_scale)) if unit_quantity == suffix_quantity => {
+            Some(suffix_scale / unit_scale)
+        }
+        // Decibels can be typed for any parameter without a known unit, but time or frequency
+        // values can't be converted to an unknown unit
+        None if suffix_quantity == Quantity::Decibels => Some(1.0),
+        _ => None,
+    }
+}
+
+/// Evaluate a typed-in value for a parameter with the given unit. `current_value` is the
+/// parameter's current plain value and it's needed for relative entry (for instance, `+3`). Returns
+/// `None` if the input is not a valid expression, if it's relative and there is no current value,
+/// or if the result is not a finite number.
+pub fn evaluate(input: &str, unit: &str, current_value: Option<f32>) -> Option<f32> {
+    let tokens = tokenize(input)?;
+
+    // Relative entry is only possible at the very start of the input
+    let (relative_op, tokens) = match tokens.first()? {
+        op @ (Token::Plus | Token::Star | Token::Slash) => (Some(op.clone()), &tokens[1..]),
+        _ => (None, &tokens[..]),
+    };
+
+    let mut parser = Parser {
+        tokens,
+        position: 0,
+        unit,
+    };
+    let value = parser.expression()?;
+    if parser.position != tokens.len() {
+        return None;
+    }
+
+    let value = match relative_op {
+        Some(op) => {
+            let current_value = current_value? as f64;
+            match op {
+                Token::Plus => current_value + value,
+                Token::Star => current_value * value,
+                Token::Slash => current_value / value,
+                _ => unreachable!(),
+            }
+        }
+        None => value,
+    };
+
+    let value = value as f32;
+    value.is_finite().then_some(value)
+}
+
+/// Whether the input uses relative entry, i.e. whether it starts with `+`, `*`, or `/`.
+pub fn is_relative(input: &str) -> bool {
+    input.trim_start().starts_with(['+', '*', '/'])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arithmetic_precedence() {
+        assert_eq!(evaluate("120*2", "", None), Some(240.0));
+        assert_eq!(evaluate("1 + 2 * 3", "", None), Some(7.0));
+        assert_eq!(evaluate("(1 + 2) * 3", "", None), Some(9.0));
+        assert_eq!(evaluate("10 - 4 - 3", "", None), Some(3.0));
+        assert_eq!(evaluate("12 / 4 / 3", "", None), Some(1.0));
+        assert_eq!(evaluate("-2 * -3", "", None), Some(6.0));
+        assert_eq!(evaluate("--1", "", None), Some(1.0));
+        assert_eq!(evaluate("2 \u{2212} 3", "", None), Some(-1.0));
+        assert_eq!(evaluate("1k / 4", "", None), Some(250.0));
+    }
+
+    #[test]
+    fn invalid_input() {
+        assert_eq!(evaluate("", "", None), None);
+        assert_eq!(evaluate("1 2", "", None), None);
+        assert_eq!(evaluate("(1 + 2", "", None), None);
+        assert_eq!(evaluate("1 +", "", None), None);
+        assert_eq!(evaluate("1.2.3", "", None), None);
+        assert_eq!(evaluate("1 / 0", "", None), None);
+        assert_eq!(evaluate("Sine", "", None), None);
+        assert_eq!(evaluate("3 foo", "", None), None);
+    }
+
+    #[test]
+    fn unit_suffixes() {
+        assert_eq!(evaluate("1k", " Hz", None), Some(1000.0));
+        assert_eq!(evaluate("2.5kHz", " Hz", None), Some(2500.0));
+        assert_eq!(evaluate("2.5 khz", " Hz", None), Some(2500.0));
+        assert_eq!(evaluate("500 Hz", " kHz", None), Some(0.5));
+        assert_eq!(evaluate("-3.5dB", " dB", None), Some(-3.5));
+        assert_eq!(evaluate("-3.5 dB", "", None), Some(-3.5));
+        assert_eq!(evaluate("50%", "", None), Some(0.5));
+        assert_eq!(evaluate("50%", "%", None), Some(50.0));
+        assert_eq!(evaluate("10ms", " s", None), Some(0.01));
+        assert_eq!(evaluate("10ms", " ms", None), Some(10.0));
+        assert_eq!(evaluate("1.5s", " ms", None), Some(1500.0));
+    }
+
+    #[test]
+    fn suffix_ambiguity() {
+        // `m` means milliseconds for parameters in seconds, and it's ambiguous everywhere else
+        assert_eq!(evaluate("10m", " s", None), Some(0.01));
+        assert_eq!(evaluate("10m", " ms", None), None);
+        assert_eq!(evaluate("10m", " Hz", None), None);
+        assert_eq!(evaluate("10m", "", None), None);
+
+        // Time and frequency suffixes must match the parameter's unit
+        assert_eq!(evaluate("5 Hz", " s", None), None);
+        assert_eq!(evaluate("5 ms", "", None), None);
+        assert_eq!(evaluate("5 dB", " Hz", None), None);
+    }
+
+    #[test]
+    fn relative_entry() {
+        assert!(is_relative("+3"));
+        assert!(is_relative(" *2"));
+        assert!(!is_relative("-3"));
+
+        assert_eq!(evaluate("+3", " dB", Some(-6.0)), Some(-3.0));
+        assert_eq!(evaluate("+-3", " dB", Some(-6.0)), Some(-9.0));
+        assert_eq!(evaluate("*2", " Hz", Some(440.0)), Some(880.0));
+        assert_eq!(evaluate("/2", " Hz", Some(440.0)), Some(220.0));
+        assert_eq!(evaluate("+1k", " Hz", Some(440.0)), Some(1440.0));
+        assert_eq!(evaluate("-3", " dB", Some(-6.0)), Some(-3.0));
+
+        // Relative entry requires a current value
+        assert_eq!(evaluate("+3", " dB", None), None);
+    }
+}
@@ -1,21 +1,46 @@
-//! A simple generic UI widget that renders all parameters in a [`Params`] object as a scrollable
-//! list of sliders and labels.
-
-use crate::core::widget::{Id, Operation, Tree};
-use crate::core::{
-    alignment, layout, renderer, text, Element, Layout, Length, Rectangle,
-    Size, Widget,
-};
-use crate::widget::{self, row, scrollable, Column, Scrollable, Space};
+//! A generic UI that renders all parameters in a [`Params`] object as a scrollable list of rows,
+//! with the parameter's name on the left and a widget for it on the right.
+//!
+//! This is the quickest way to get a working editor for a plugin with many parameters:
+//!
+//! ```ignore
+//! fn view(&self) -> Element<'_, Self::Message> {
+//!     GenericUi::<GenericSlider>::new(self.params.as_ref()).into()
+//! }
+//! ```
+//!
+//! The [`ParamWidget`] type decides which widget each row gets, so a plugin can swap in its own
+//! parameter widgets by implementing that trait on an empty struct. Parameters with the
+//! [`HIDE_IN_GENERIC_UI`][ParamFlags::HIDE_IN_GENERIC_UI] flag are left out.
+//!
+//! The rows are built once every time the view is built, and every row has the same height, so
+//! laying out the list stays cheap even for plugins with hundreds of parameters.
+
+use nih_plug::params::enums::EnumParamInner;
+use nih_plug::prelude::{BoolParam, Param, ParamFlags, ParamPtr, Params};
 use std::marker::PhantomData;
-use std::sync::Arc;
 
-use nih_plug::prelude::{Param, ParamFlags, ParamPtr, Params};
+use crate::core::widget::Id;
+use crate::core::{alignment, text, Element, Length};
+use crate::widget::{self, container, row, scrollable, Column};
 
-use super::{ParamMessage, ParamSlider};
+use super::{ParamDropdown, ParamMessage, ParamSlider, ParamToggle};
+
+/// The height of a row in the list. Row widgets should fit in this height.
+const ROW_HEIGHT: f32 = 30.0;
+/// The space between two rows, and between a row's name and its widget.
+const SPACING: f32 = 4.0;
+/// The space around the list.
+const PADDING: f32 = 8.0;
+/// The space kept free for the scroll bar with [`GenericUi::pad_scrollbar()`].
+const SCROLLBAR_SPACING: f32 = 4.0;
 
 /// A widget that can be used to create a generic UI with. This is used in conjuction with empty
 /// structs to emulate existential types.
+///
+/// Only [`into_widget_element()`][Self::into_widget_element()] needs to be implemented. The
+/// widgets for [`BoolParam`]s and for enum parameters default to the same widget, but they can be
+/// overridden separately to use for instance a toggle or a dropdown instead.
 pub trait ParamWidget<Theme = crate::Theme, Renderer = crate::Renderer> {
     /// Create an [`Element`] for a widget for the specified parameter.
     fn into_widget_element<'a, P: Param>(
@@ -25,38 +50,101 @@ pub trait ParamWidget<Theme = crate::Theme, Renderer = crate::Renderer> {
         Theme: 'a,
         Renderer: 'a;
 
+    /// Create an [`Element`] for a widget for a [`BoolParam`].
+    fn bool_widget_element<'a>(param: &'a BoolParam) -> Element<'a, ParamMessage, Theme, Renderer>
+    where
+        Theme: 'a,
+        Renderer: 'a,
+    {
+        Self::into_widget_element(param)
+    }
+
+    /// Create an [`Element`] for a widget for an enum parameter. The generic UI only has access to
+    /// the type erased [`EnumParamInner`], not to the original `EnumParam<T>`.
+    fn enum_widget_element<'a>(
+        param: &'a EnumParamInner,
+    ) -> Element<'a, ParamMessage, Theme, Renderer>
+    where
+        Theme: 'a,
+        Renderer: 'a,
+    {
+        Self::into_widget_element(param)
+    }
+
     /// The same as [`into_widget_element()`][Self::into_widget_element()], but for a `ParamPtr`.
+    /// This picks the widget for the parameter's type.
     ///
     /// # Safety
     ///
-    /// Undefined behavior of the `ParamPtr` does not point to a valid parameter.
+    /// Undefined behavior if the `ParamPtr` does not point to a valid parameter that outlives `'a`.
     unsafe fn into_widget_element_raw<'a>(
-        param: &'a ParamPtr,
+        param: ParamPtr,
     ) -> Element<'a, ParamMessage, Theme, Renderer>
     where
         Theme: 'a,
         Renderer: 'a,
     {
         match param {
-            ParamPtr::FloatParam(p) => Self::into_widget_element(&**p),
-            ParamPtr::IntParam(p) => Self::into_widget_element(&**p),
-            ParamPtr::BoolParam(p) => Self::into_widget_element(&**p),
-            ParamPtr::EnumParam(p) => Self::into_widget_element(&**p),
+            ParamPtr::FloatParam(p) => Self::into_widget_element(&*p),
+            ParamPtr::IntParam(p) => Self::into_widget_element(&*p),
+            ParamPtr::BoolParam(p) => Self::bool_widget_element(&*p),
+            ParamPtr::EnumParam(p) => Self::enum_widget_element(&*p),
         }
     }
 }
 
-/// Create a generic UI using [`ParamSlider`]s.
+/// Create a generic UI using [`ParamSlider`]s for numeric parameters, [`ParamToggle`] checkboxes
+/// for [`BoolParam`]s, and [`ParamDropdown`]s for enum parameters.
 #[derive(Default)]
 pub struct GenericSlider;
 
-/// A list of scrollable widgets for every paramter in a [`Params`] object. The [`ParamWidget`] type
-/// determines what widget to use for this.
-///
-/// TODO: There's no way to configure the individual widgets.
-pub struct GenericUi<W, Theme = crate::Theme, Renderer = crate::Renderer> {
-    // Hacky work around so we can borrow &ParamPtr and ensure references
-    // stay alive for the lifetime of this object.
+impl<Theme, Renderer> ParamWidget<Theme, Renderer> for GenericSlider
+where
+    Theme: widget::text_input::Catalog
+        + super::param_slider::Catalog
+        + super::param_toggle::Catalog
+        + super::dropdown::Catalog,
+    Renderer: text::Renderer,
+    Renderer::Font: From<crate::Font>,
+{
+    fn into_widget_element<'a, P: Param>(param: &'a P) -> Element<'a, ParamMessage, Theme, Renderer>
+    where
+        Theme: 'a,
+        Renderer: 'a,
+    {
+        ParamSlider::new(param)
+            .height(Length::Fixed(ROW_HEIGHT))
+            .into()
+    }
+
+    fn bool_widget_element<'a>(param: &'a BoolParam) -> Element<'a, ParamMessage, Theme, Renderer>
+    where
+        Theme: 'a,
+        Renderer: 'a,
+    {
+        // The row already shows the parameter's name
+        ParamToggle::new(param)
+            .label("")
+            .height(Length::Fixed(ROW_HEIGHT))
+            .into()
+    }
+
+    fn enum_widget_element<'a>(
+        param: &'a EnumParamInner,
+    ) -> Element<'a, ParamMessage, Theme, Renderer>
+    where
+        Theme: 'a,
+        Renderer: 'a,
+    {
+        ParamDropdown::new(param).into()
+    }
+}
+
+/// A scrollable list with a row for every parameter in a [`Params`] object. The [`ParamWidget`]
+/// type determines what widget to use for each row. See the [module documentation][self].
+pub struct GenericUi<'a, W, Theme = crate::Theme, Renderer = crate::Renderer> {
+    /// The visible parameters, in the order of the [`Params`] object. These point into the
+    /// parameters object borrowed for `'a`.
     params: Vec<ParamPtr>,
 
     id: Option<Id>,
@@ -67,23 +155,19 @@ pub struct GenericUi<W, Theme = crate::Theme, Renderer = crate::Renderer> {
 
     pad_scrollbar: bool,
 
-    /// We don't emit any messages or store the actual widgets, but iced requires us to define some
-    /// message type anyways.
-    _phantom: PhantomData<(W, Theme, Renderer)>,
+    /// We don't store the actual widgets, they're only created when converting this into an
+    /// [`Element`].
+    _phantom: PhantomData<(&'a (), W, Theme, Renderer)>,
 }
 
-impl<W, Theme, Renderer> GenericUi<W, Theme, Renderer> {
-    /// Creates a new [`GenericUi`] for all provided parameters.
-    pub fn new(params: Arc<dyn Params>) -> Self {
-        let params = params
-            .param_map()
-            .into_iter()
-            .map(|(_, ptr, _)| ptr)
-            .collect();
+impl<'a, W, Theme, Renderer> GenericUi<'a, W, Theme, Renderer> {
+    /// Creates a new [`GenericUi`] for all provided parameters that aren't hidden from generic
+    /// UIs.
+    pub fn new(params: &'a (impl Params + ?Sized)) -> Self {
         Self {
-            id: None,
-            params,
+            params: visible_params(params),
 
+            id: None,
             width: Length::Fill,
             height: Length::Fill,
             max_width: u32::MAX,
@@ -94,9 +178,10 @@ impl<W, Theme, Renderer> GenericUi<W, Theme, Renderer> {
         }
     }
 
-    /// Sets the [`Id`] of the [`Container`].
-    pub fn id(mut self, id: Id) -> Self {
-        self.id = Some(id);
+    /// Sets the [`Id`] of the [`GenericUi`]'s scrollable, so it can be scrolled with scrollable
+    /// operations.
+    pub fn id(mut self, id: impl Into<Id>) -> Self {
+        self.id = Some(id.into());
         self
     }
 
@@ -112,7 +197,8 @@ impl<W, Theme, Renderer> GenericUi<W, Theme, Renderer> {
         self
     }
 
-    /// Sets the maximum width of the [`GenericUi`].
+    /// Sets the maximum width of the [`GenericUi`]'s rows. The rows are centered when the
+    /// [`GenericUi`] is wider than this.
     pub fn max_width(mut self, width: u32) -> Self {
         self.max_width = width;
         self
@@ -124,201 +210,153 @@ impl<W, Theme, Renderer> GenericUi<W, Theme, Renderer> {
         self
     }
 
-    /// Include additional room on the right for the scroll bar.
+    /// Keep room free for the scroll bar on the right, instead of drawing the scroll bar on top of
+    /// the rows.
     pub fn pad_scrollbar(mut self) -> Self {
         self.pad_scrollbar = true;
         self
     }
 }
 
-impl<'a, W, Theme, Renderer> GenericUi<W, Theme, Renderer>
+impl<'a, W, Theme, Renderer> GenericUi<'a, W, Theme, Renderer>
 where
-    W: ParamWidget<Theme, Renderer>,
-    Theme: scrollable::Catalog + widget::text::Catalog + 'a,
+    W: ParamWidget<Theme, Renderer> + 'a,
+    Theme: scrollable::Catalog + container::Catalog + widget::text::Catalog + 'a,
     Renderer: text::Renderer + 'a,
 {
-    fn content(
-        &'a self,
-        renderer: Option<&Renderer>,
-    ) -> Scrollable<'a, ParamMessage, Theme, Renderer> {
-        let (spacing, padding) = match renderer {
-            Some(renderer) => (
-                (renderer.default_size() * 0.2).0.round(),
-                (renderer.default_size() * 0.5).0.round(),
-            ),
-            None => (0.0, 0.0),
-        };
-
-        let content = Column::with_children(
-            self.params
-                .iter()
-                .filter(|param| is_hidden(*param))
-                .map(|param| {
-                    let row = row![
-                        widget::text(unsafe { param.name() })
-                            .height(20)
-                            .width(Length::Fill)
-                            .align_x(alignment::Horizontal::Right)
-                            .align_y(alignment::Vertical::Center),
-                        unsafe { W::into_widget_element_raw(param) }
-                    ]
-                    .width(Length::Fill)
-                    .align_y(alignment::Vertical::Center)
-                    .spacing(spacing * 2.0);
-
-                    if self.pad_scrollbar {
-                        row.push(Space::new().width(0))
-                    } else {
-                        row
-                    }
-                })
-                .map(Element::from),
-        )
-        .align_x(alignment::Horizontal::Center)
-        .spacing(spacing)
-        .padding(padding)
-        .width(self.width)
-        .height(self.height)
-        .max_width(self.max_width);
-
-        scrollable(content).spacing(spacing)
+    /// Convert this [`GenericUi`] into an [`Element`] with the correct message. You should have a
+    /// variant on your own message type that wraps around [`ParamMessage`] so you can forward those
+    /// messages to
+    /// [`IcedEditor::handle_param_message()`][crate::IcedEditor::handle_param_message()].
+    pub fn map<Message, F>(self, f: F) -> Element<'a, Message, Theme, Renderer>
+    where
+        Message: 'static,
+        F: Fn(ParamMessage) -> Message + 'static,
+    {
+        Element::from(self).map(f)
     }
 }
 
-impl<'a, W, Theme, Renderer> Widget<ParamMessage, Theme, Renderer> for GenericUi<W, Theme, Renderer>
+impl<'a, W, Theme, Renderer> From<GenericUi<'a, W, Theme, Renderer>>
+    for Element<'a, ParamMessage, Theme, Renderer>
 where
-    W: ParamWidget<Theme, Renderer>,
-    Theme: scrollable::Catalog + widget::text::Catalog + 'a,
+    W: ParamWidget<Theme, Renderer> + 'a,
+    Theme: scrollable::Catalog + container::Catalog + widget::text::Catalog + 'a,
     Renderer: text::Renderer + 'a,
 {
-    fn size(&self) -> crate::iced_baseview::Size<Length> {
-        Size {
-            width: self.width,
-            height: self.height,
-        }
-    }
+    fn from(ui: GenericUi<'a, W, Theme, Renderer>) -> Self {
+        let rows = ui.params.iter().map(|&param| {
+            // SAFETY: The pointers were taken from a parameters object that's borrowed for `'a`
+            let (name, widget) = unsafe { (param_name(param), W::into_widget_element_raw(param)) };
 
-    fn children(&self) -> Vec<Tree> {
-        let content = self.content(None);
+            row![
+                widget::text(name)
+                    .width(Length::Fill)
+                    .align_x(alignment::Horizontal::Right),
+                widget,
+            ]
+            .height(Length::Fixed(ROW_HEIGHT))
+            .align_y(alignment::Vertical::Center)
+            .spacing(SPACING * 2.0)
+            .into()
+        });
+        let content = Column::with_children(rows)
+            .spacing(SPACING)
+            .padding(PADDING)
+            .width(Length::Fill)
+            .max_width(ui.max_width);
+        let content = container(content).center_x(Length::Fill);
+
+        let mut list = scrollable(content).width(Length::Fill).height(Length::Fill);
+        if ui.pad_scrollbar {
+            list = list.spacing(SCROLLBAR_SPACING);
+        }
+        if let Some(id) = ui.id {
+            list = list.id(id);
+        }
 
-        vec![Tree::new(
-            &content as &dyn Widget<ParamMessage, Theme, Renderer>,
-        )]
+        container(list)
+            .width(ui.width)
+            .height(ui.height)
+            .max_height(f32::from(ui.max_height))
+            .into()
     }
+}
 
-    fn layout(
-        &mut self,
-        tree: &mut Tree,
-        renderer: &Renderer,
-        limits: &layout::Limits,
-    ) -> layout::Node {
-        self.content(Some(renderer))
-            .layout(&mut tree.children[0], renderer, limits)
-    }
+/// The parameters in `params` that should be shown in a generic UI, in order.
+fn visible_params(params: &(impl Params + ?Sized)) -> Vec<ParamPtr> {
+    params
+        .param_map()
+        .into_iter()
+        .map(|(_, param, _)| param)
+        // SAFETY: The parameter map only contains pointers to `params`' own parameters
+        .filter(|param| !unsafe { param.flags() }.contains(ParamFlags::HIDE_IN_GENERIC_UI))
+        .collect()
+}
 
-    fn draw(
-        &self,
-        tree: &crate::iced_baseview::core::widget::Tree,
-        renderer: &mut Renderer,
-        theme: &Theme,
-        style: &renderer::Style,
-        layout: Layout<'_>,
-        cursor: crate::iced_baseview::core::mouse::Cursor,
-        viewport: &Rectangle,
-    ) {
-        self.content(Some(renderer)).draw(
-            &tree.children[0],
-            renderer,
-            theme,
-            style,
-            layout,
-            cursor,
-            viewport,
-        )
+/// The name of the parameter `param` points to.
+///
+/// # Safety
+///
+/// Undefined behavior if the `ParamPtr` does not point to a valid parameter that outlives `'a`.
+unsafe fn param_name<'a>(param: ParamPtr) -> &'a str {
+    match param {
+        ParamPtr::FloatParam(p) => (*p).name(),
+        ParamPtr::IntParam(p) => (*p).name(),
+        ParamPtr::BoolParam(p) => (*p).name(),
+        ParamPtr::EnumParam(p) => (*p).name(),
     }
+}
 
-    fn operate(
-        &mut self,
-        tree: &mut Tree,
-        layout: Layout<'_>,
-        renderer: &Renderer,
-        operation: &mut dyn Operation,
-    ) {
-        operation.container(self.id.as_ref(), layout.bounds());
-        self.content(Some(renderer)).operate(
-            tree,
-            layout.children().next().unwrap(),
-            renderer,
-            operation,
-        );
-    }
+#[cfg(test)]
+mod tests {
+    use nih_plug::prelude::{FloatParam, FloatRange, IntParam, IntRange};
 
+    use super::*;
 
-    fn mouse_interaction(
-        &self,
-        tree: &Tree,
-        layout: Layout<'_>,
-        cursor: crate::iced_baseview::core::mouse::Cursor,
-        viewport: &Rectangle,
-        renderer: &Renderer,
-    ) -> crate::iced_baseview::core::mouse::Interaction {
-        self.content(Some(renderer)).mouse_interaction(
-            &tree.children[0],
-            layout.children().next().unwrap(),
-            cursor,
-            viewport,
-            renderer,
-        )
+    struct TestParams {
+        gain: FloatParam,
+        voices: IntParam,
+        bypass: BoolParam,
+        internal: FloatParam,
     }
-}
 
-fn is_hidden(param_ptr: &ParamPtr) -> bool {
-    let flags = unsafe { param_ptr.flags() };
-    flags.contains(ParamFlags::HIDE_IN_GENERIC_UI)
-}
-
-impl<Theme, Renderer> ParamWidget<Theme, Renderer> for GenericSlider
-where
-    Theme: widget::text_input::Catalog,
-    Renderer: text::Renderer,
-    Renderer::Font: From<crate::Font>,
-{
-    fn into_widget_element<'a, P: Param>(param: &'a P) -> Element<'a, ParamMessage, Theme, Renderer>
-    where
-        Theme: 'a,
-        Renderer: 'a,
-    {
-        ParamSlider::new(param).into()
+    unsafe impl Params for TestParams {
+        fn param_map(&self) -> Vec<(String, ParamPtr, String)> {
+            [
+                ("gain", self.gain.as_ptr()),
+                ("voices", self.voices.as_ptr()),
+                ("bypass", self.bypass.as_ptr()),
+                ("internal", self.internal.as_ptr()),
+            ]
+            .into_iter()
+            .map(|(id, param)| (String::from(id), param, String::new()))
+            .collect()
+        }
     }
-}
 
-impl<'a, W, Theme, Renderer> GenericUi<W, Theme, Renderer>
-where
-    W: ParamWidget<Theme, Renderer> + 'a,
-    Theme: scrollable::Catalog + widget::text::Catalog + 'a,
-    Renderer: text::Renderer + 'a,
-{
-    /// Convert this [`GenericUi`] into an [`Element`] with the correct message. You should have a
-    /// variant on your own message type that wraps around [`ParamMessage`] so you can forward those
-    /// messages to
-    /// [`IcedEditor::handle_param_message()`][crate::IcedEditor::handle_param_message()].
-    pub fn map<Message, F>(self, f: F) -> Element<'a, Message, Theme, Renderer>
-    where
-        Message: 'static,
-        F: Fn(ParamMessage) -> Message + 'static,
-    {
-        Element::from(self).map(f)
+    fn params() -> TestParams {
+        TestParams {
+            gain: FloatParam::new("Gain", 0.5, FloatRange::Linear { min: 0.0, max: 1.0 }),
+            voices: IntParam::new("Voices", 4, IntRange::Linear { min: 1, max: 16 }),
+            bypass: BoolParam::new("Bypass", false),
+            internal: FloatParam::new("Internal", 0.0, FloatRange::Linear { min: 0.0, max: 1.0 })
+                .with_flags(ParamFlags::HIDE_IN_GENERIC_UI),
+        }
     }
-}
 
-impl<'a, W, Theme, Renderer> From<GenericUi<W, Theme, Renderer>>
-    for Element<'a, ParamMessage, Theme, Renderer>
-where
-    W: ParamWidget<Theme, Renderer> + 'a,
-    Theme: scrollable::Catalog + widget::text::Catalog + 'a,
-    Renderer: text::Renderer + 'a,
-{
-    fn from(widget: GenericUi<W, Theme, Renderer>) -> Self {
-        Element::new(widget)
+    #[test]
+    fn hidden_params_are_left_out() {
+        let params = params();
+
+        assert_eq!(
+            visible_params(&params),
+            [
+                params.gain.as_ptr(),
+                params.voices.as_ptr(),
+                params.bypass.as_ptr()
+            ]
+        );
+        assert_eq!(unsafe { param_name(params.voices.as_ptr()) }, "Voices");
     }
 }
@@ -0,0 +1,469 @@
+//! A temporary glow over a parameter's widgets, for instance when the host asks the editor to show
+//! which control belongs to the parameter the user is touching on a hardware controller.
+//!
+//! [`HighlightOverlay`] wraps the editor's content, and draws the glow on top of every widget for
+//! the highlighted parameter that reports its bounds to the [`ParamBoundsMap`], see the
+//! [`param_bounds`][super::param_bounds] module. The glow fades out over the
+//! [duration][HighlightOverlay::duration] after the highlight started. Plugin editors redraw every
+//! frame, but with [`always_redraw`][crate::IcedBaseviewSettings::always_redraw] disabled the glow
+//! only fades with the next event.
+//!
+//! The overlay should always wrap the content, even while nothing is highlighted. Wrapping the
+//! content only while highlighting would change the widget tree, which resets the content's state.
+
+use nih_plug::prelude::ParamPtr;
+use std::time::{Duration, Instant};
+
+use crate::core::widget::tree::Tree;
+use crate::core::widget::Operation;
+use crate::core::{
+    layout, mouse, overlay, renderer, Border, Clipboard, Color, Element, Event, Layout, Length,
+    Rectangle, Shadow, Shell, Size, Theme, Vector, Widget,
+};
+
+use super::param_bounds::ParamBoundsMap;
+
+/// Wraps the editor's content and draws a glow over the widgets of a highlighted parameter. See the
+/// [module documentation][self].
+pub struct HighlightOverlay<'a, Message, Theme = crate::Theme, Renderer = crate::Renderer>
+where
+    Theme: Catalog,
+{
+    content: Element<'a, Message, Theme, Renderer>,
+    map: ParamBoundsMap,
+    highlight: Option<(ParamPtr, Instant)>,
+    duration: Duration,
+    class: Theme::Class<'a>,
+}
+
+impl<'a, Message, Theme, Renderer> HighlightOverlay<'a, Message, Theme, Renderer>
+where
+    Theme: Catalog,
+    Renderer: crate::core::Renderer,
+{
+    /// The default time it takes for the glow to fade out.
+    pub const DEFAULT_DURATION: Duration = Duration::from_millis(1500);
+
+    /// Creates a new [`HighlightOverlay`] around `content`. `highlight` is the parameter to
+    /// highlight and the time the highlight started, if there is one.
+    pub fn new(
+        content: impl Into<Element<'a, Message, Theme, Renderer>>,
+        map: &ParamBoundsMap,
+        highlight: Option<(ParamPtr, Instant)>,
+    ) -> Self {
+        Self {
+            content: content.into(),
+            map: map.clone(),
+            highlight,
+            duration: Self::DEFAULT_DURATION,
+            class: Theme::default(),
+        }
+    }
+
+    /// Sets how long it takes for the glow to fade out after the highlight started.
+    pub fn duration(mut self, duration: Duration) -> Self {
+        self.duration = duration;
+        self
+    }
+
+    /// Sets the style of the [`HighlightOverlay`].
+    pub fn style(mut self, style: impl Fn(&Theme) -> Style + 'a) -> Self
+    where
+        Theme::Class<'a>: From<StyleFn<'a, Theme>>,
+    {
+        self.class = (Box::new(style) as StyleFn<'a, Theme>).into();
+        self
+    }
+
+    /// Sets the style class of the [`HighlightOverlay`].
+    pub fn class(mut self, class: impl Into<Theme::Class<'a>>) -> Self {
+        self.class = class.into();
+        self
+    }
+
+    /// How strongly the glow is shown at `now`, from 1.0 when the highlight starts to 0.0 once it
+    /// has faded out.
+    fn strength(&self, now: Instant) -> f32 {
+        let Some((_, since)) = self.highlight else {
+            return 0.0;
+        };
+        if self.duration.is_zero() {
+            return 0.0;
+        }
+
+        let elapsed = now.saturating_duration_since(since);
+        (1.0 - elapsed.as_secs_f32() / self.duration.as_secs_f32()).max(0.0)
+    }
+}
+
+impl<'a, Message, Theme, Renderer> Widget<Message, Theme, Renderer>
+    for HighlightOverlay<'a, Message, Theme, Renderer>
+where
+    Theme: Catalog,
+    Renderer: crate::core::Renderer,
+{
+    fn children(&self) -> Vec<Tree> {
+        vec![Tree::new(&self.content)]
+    }
+
+    fn diff(&self, tree: &mut Tree) {
+        tree.diff_children(std::slice::from_ref(&self.content));
+    }
+
+    fn size(&self) -> Size<Length> {
+        self.content.as_widget().size()
+    }
+
+    fn size_hint(&self) -> Size<Length> {
+        self.content.as_widget().size_hint()
+    }
+
+    fn layout(
+        &mut self,
+        tree: &mut Tree,
+        renderer: &Renderer,
+        limits: &layout::Limits,
+    ) -> layout::Node {
+        self.content
+            .as_widget_mut()
+            .layout(&mut tree.children[0], renderer, limits)
+    }
+
+    fn draw(
+        &self,
+        tree: &Tree,
+        renderer: &mut Renderer,
+        theme: &Theme,
+        style: &renderer::Style,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        viewport: &Rectangle,
+    ) {
+        // The content updates the map while it's drawn, so the glow always uses this frame's bounds
+        self.content.as_widget().draw(
+            &tree.children[0],
+            renderer,
+            theme,
+            style,
+            layout,
+            cursor,
+            viewport,
+        );
+
+        let strength = self.strength(Instant::now());
+        let Some((param, _)) = self.highlight.filter(|_| strength > 0.0) else {
+            return;
+        };
+
+        let appearance = theme.style(&self.class);
+        let fade = |color: Color| Color {
+            a: color.a * strength,
+            ..color
+        };
+        renderer.with_layer(*viewport, |renderer| {
+            for bounds in self.map.get(param) {
+                renderer.fill_quad(
+                    renderer::Quad {
+                        bounds: crate::scale::pixel_snap(bounds),
+                        border: Border {
+                            color: fade(appearance.border.color),
+                            ..super::util::snap_border(appearance.border)
+                        },
+                        shadow: Shadow {
+                            color: fade(appearance.shadow.color),
+                            ..appearance.shadow
+                        },
+                        ..Default::default()
+                    },
+                    Color::TRANSPARENT,
+                );
+            }
+        });
+    }
+
+    fn update(
+        &mut self,
+        tree: &mut Tree,
+        event: &Event,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        renderer: &Renderer,
+        clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Message>,
+        viewport: &Rectangle,
+    ) {
+        self.content.as_widget_mut().update(
+            &mut tree.children[0],
+            event,
+            layout,
+            cursor,
+            renderer,
+            clipboard,
+            shell,
+            viewport,
+        );
+
+        // Keep the glow fading out
+        if self.strength(Instant::now()) > 0.0 {
+            shell.request_redraw();
+        }
+    }
+
+    fn mouse_interaction(
+        &self,
+        tree: &Tree,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        viewport: &Rectangle,
+        renderer: &Renderer,
+    ) -> mouse::Interaction {
+        self.content.as_widget().mouse_interaction(
+            &tree.children[0],
+            layout,
+            cursor,
+            viewport,
+            renderer,
+        )
+    }
+
+    fn operate(
+        &mut self,
+        tree: &mut Tree,
+        layout: Layout<'_>,
+        renderer: &Renderer,
+        operation: &mut dyn Operation,
+    ) {
+        self.content
+            .as_widget_mut()
+            .operate(&mut tree.children[0], layout, renderer, operation);
+    }
+
+    fn overlay<'b>(
+        &'b mut self,
+        tree: &'b mut Tree,
+        layout: Layout<'b>,
+        renderer: &Renderer,
+        viewport: &Rectangle,
+        translation: Vector,
+    ) -> Option<overlay::Element<'b, Message, Theme, Renderer>> {
+        self.content.as_widget_mut().overlay(
+            &mut tree.children[0],
+            layout,
+            renderer,
+            viewport,
+            translation,
+        )
+    }
+}
+
+impl<'a, Message, Theme, Renderer> From<HighlightOverlay<'a, Message, Theme, Renderer>>
+    for Element<'a, Message, Theme, Renderer>
+where
+    Message: 'a,
+    Theme: Catalog + 'a,
+    Renderer: crate::core::Renderer + 'a,
+{
+    fn from(widget: HighlightOverlay<'a, Message, Theme, Renderer>) -> Self {
+        Element::new(widget)
+    }
+}
+
+/// The appearance of a [`HighlightOverlay`]'s glow at the start of the highlight. The colors fade
+/// out from there.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Style {
+    /// The [`Border`] drawn around the highlighted widgets.
+    pub border: Border,
+    /// The [`Shadow`] that makes the border glow.
+    pub shadow: Shadow,
+}
+
+/// The theme catalog of a [`HighlightOverlay`].
+pub trait Catalog: Sized {
+    /// The item class of the [`Catalog`].
+    type Class<'a>;
+
+    /// The default class produced by the [`Catalog`].
+    fn default<'a>() -> Self::Class<'a>;
+
+    /// The [`Style`] of a class.
+    fn style(&self, class: &Self::Class<'_>) -> Style;
+}
+
+/// A styling function for a [`HighlightOverlay`].
+pub type StyleFn<'a, Theme> = Box<dyn Fn(&Theme) -> Style + 'a>;
+
+impl Catalog for Theme {
+    type Class<'a> = StyleFn<'a, Self>;
+
+    fn default<'a>() -> Self::Class<'a> {
+        Box::new(default)
+    }
+
+    fn style(&self, class: &Self::Class<'_>) -> Style {
+        class(self)
+    }
+}
+
+/// The default style of a [`HighlightOverlay`].
+pub fn default(theme: &Theme) -> Style {
+    let colors = crate::theme::colors(theme);
+
+    Style {
+        border: Border {
+            color: colors.accent,
+            width: 2.0,
+            radius: 3.0.into(),
+        },
+        shadow: Shadow {
+            color: colors.accent,
+            offset: Vector::ZERO,
+            blur_radius: 12.0,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nih_plug::prelude::{FloatParam, FloatRange, Param};
+
+    use super::*;
+    use crate::core::renderer::Quad;
+    use crate::core::{Background, Point};
+    use crate::runtime::user_interface::{Cache, UserInterface};
+    use crate::widget::row;
+    use crate::widgets::param_bounds::ReportedBounds;
+
+    /// Records the glows, which are the only quads with a shadow.
+    #[derive(Default)]
+    struct Glows(Vec<Quad>);
+
+    impl crate::core::Renderer for Glows {
+        fn start_layer(&mut self, _bounds: Rectangle) {}
+
+        fn end_layer(&mut self) {}
+
+        fn start_transformation(&mut self, _transformation: crate::core::Transformation) {}
+
+        fn end_transformation(&mut self) {}
+
+        fn reset(&mut self, _new_bounds: Rectangle) {}
+
+        fn fill_quad(&mut self, quad: Quad, _background: impl Into<Background>) {
+            if quad.shadow.blur_radius > 0.0 {
+                self.0.push(quad);
+            }
+        }
+
+        fn allocate_image(
+            &mut self,
+            _handle: &crate::core::image::Handle,
+            _callback: impl FnOnce(Result<crate::core::image::Allocation, crate::core::image::Error>)
+                + Send
+                + 'static,
+        ) {
+            unreachable!()
+        }
+    }
+
+    /// A parameter widget that always reports its bounds.
+    struct Control<'a> {
+        param: &'a FloatParam,
+        map: ParamBoundsMap,
+    }
+
+    impl Widget<(), Theme, Glows> for Control<'_> {
+        fn tag(&self) -> crate::core::widget::tree::Tag {
+            crate::core::widget::tree::Tag::of::<ReportedBounds>()
+        }
+
+        fn state(&self) -> crate::core::widget::tree::State {
+            crate::core::widget::tree::State::new(ReportedBounds::default())
+        }
+
+        fn size(&self) -> Size<Length> {
+            Size::new(Length::Fixed(40.0), Length::Fixed(20.0))
+        }
+
+        fn layout(
+            &mut self,
+            _tree: &mut Tree,
+            _renderer: &Glows,
+            limits: &layout::Limits,
+        ) -> layout::Node {
+            layout::atomic(limits, Length::Fixed(40.0), Length::Fixed(20.0))
+        }
+
+        fn draw(
+            &self,
+            tree: &Tree,
+            _renderer: &mut Glows,
+            _theme: &Theme,
+            _style: &renderer::Style,
+            layout: Layout<'_>,
+            _cursor: mouse::Cursor,
+            _viewport: &Rectangle,
+        ) {
+            tree.state.downcast_ref::<ReportedBounds>().report(
+                Some(&self.map),
+                self.param.as_ptr(),
+                &crate::widgets::param_id::param_widget_id(self.param.as_ptr()),
+                layout.bounds(),
+            );
+        }
+    }
+
+    #[test]
+    fn glows_over_every_widget_and_fades() {
+        let param = FloatParam::new("Gain", 0.5, FloatRange::Linear { min: 0.0, max: 1.0 });
+        let other = FloatParam::new("Mix", 0.5, FloatRange::Linear { min: 0.0, max: 1.0 });
+        let map = ParamBoundsMap::default();
+        let style = renderer::Style {
+            text_color: Color::BLACK,
+        };
+
+        let mut cache = Cache::default();
+        let mut draw = |highlight: Option<(ParamPtr, Instant)>| {
+            let control = |param| {
+                Element::new(Control {
+                    param,
+                    map: map.clone(),
+                })
+            };
+            let content = row![control(&param), control(&other), control(&param)];
+            let view = Element::new(HighlightOverlay::new(content, &map, highlight));
+
+            let mut renderer = Glows::default();
+            let mut interface = UserInterface::build(
+                view,
+                Size::new(200.0, 100.0),
+                std::mem::take(&mut cache),
+                &mut renderer,
+            );
+            interface.draw(
+                &mut renderer,
+                &Theme::Dark,
+                &style,
+                mouse::Cursor::Unavailable,
+            );
+            cache = interface.into_cache();
+
+            renderer.0
+        };
+
+        assert!(draw(None).is_empty());
+
+        // Both widgets for the parameter glow, at the bounds they were drawn at in the same frame
+        let now = Instant::now();
+        let glows = draw(Some((param.as_ptr(), now)));
+        let positions: Vec<Point> = glows.iter().map(|glow| glow.bounds.position()).collect();
+        assert_eq!(positions, [Point::new(0.0, 0.0), Point::new(80.0, 0.0)]);
+        assert!(glows[0].border.color.a > 0.5);
+
+        // Halfway through the glow has faded, and after the duration it's gone
+        let halfway = draw(Some((param.as_ptr(), now - Duration::from_millis(750))));
+        assert!(halfway[0].border.color.a < glows[0].border.color.a);
+        assert!(halfway[0].border.color.a > 0.0);
+        assert!(draw(Some((param.as_ptr(), now - Duration::from_secs(2)))).is_empty());
+    }
+}
@@ -0,0 +1,193 @@
+//! Mapping between a parameter's normalized value and where that value is shown on a widget.
+//!
+//! Parameter widgets draw and interpret input in terms of a display position in `[0, 1]`, where
+//! `0` is the start of the widget (the left side of a slider, the counterclockwise end of a knob)
+//! and `1` is the end. Normally the display position is simply the parameter's normalized value.
+//! With [`DisplayMapping::inverted()`] the display is flipped, so the parameter's maximum value is
+//! shown at the start of the widget. This is useful for parameters like a damping amount where
+//! users expect turning a knob all the way up to mean the least damping.
+//!
+//! Only the presentation is inverted. Widgets always write the parameter's true normalized value
+//! to the host, and the value's text and typed-in values always use the parameter's real value.
+//! All widgets should go through these functions instead of flipping values themselves so the
+//! fill, angle, drag direction, ticks, and bipolar origin stay consistent with each other.
+
+/// Maps between normalized parameter values and display positions. See the [module
+/// documentation][self].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DisplayMapping {
+    inverted: bool,
+}
+
+impl DisplayMapping {
+    /// The regular mapping where the display position is the normalized value.
+    pub const fn new() -> Self {
+        Self { inverted: false }
+    }
+
+    /// A mapping that shows the parameter's maximum value at the start of the widget.
+    pub const fn inverted() -> Self {
+        Self { inverted: true }
+    }
+
+    /// Whether this mapping flips the display.
+    pub const fn is_inverted(&self) -> bool {
+        self.inverted
+    }
+
+    /// The display position for a normalized value. The result is clamped to `[0, 1]`.
+    pub fn to_display(&self, normalized_value: f32) -> f32 {
+        let value = normalized_value.clamp(0.0, 1.0);
+        if self.inverted {
+            1.0 - value
+        } else {
+            value
+        }
+    }
+
+    /// The normalized value for a display position, for instance where the user clicked. The
+    /// result is clamped to `[0, 1]`.
+    pub fn from_display(&self, position: f32) -> f32 {
+        // The mapping is its own inverse
+        self.to_display(position)
+    }
+
+    /// The change in normalized value for a change in display position, used for relative drags
+    /// and scrolling. Moving towards the end of the widget always moves the display forwards, so
+    /// this decreases the parameter's value when the display is inverted.
+    pub fn delta_from_display(&self, delta: f32) -> f32 {
+        if self.inverted {
+            -delta
+        } else {
+            delta
+        }
+    }
+
+    /// The display positions the fill for a value starts and ends at. The fill of a unipolar
+    /// parameter starts at the start of the widget, and the fill of a bipolar parameter starts at
+    /// `bipolar_origin`'s display position, which is usually the parameter's default value. The
+    /// start may come after the end.
+    pub fn fill_range(&self, normalized_value: f32, bipolar_origin: Option<f32>) -> (f32, f32) {
+        let start = match bipolar_origin {
+            Some(origin) => self.to_display(origin),
+            None => 0.0,
+        };
+
+        (start, self.to_display(normalized_value))
+    }
+
+    /// The display positions and normalized values of the ticks for a parameter with
+    /// `step_count + 1` discrete values, ordered from the start of the widget to the end.
+    /// Continuous parameters only get ticks at their two ends.
+    pub fn ticks(&self, step_count: Option<usize>) -> Vec<(f32, f32)> {
+        let steps = step_count.unwrap_or(1).max(1);
+        (0..=steps)
+            .map(|step| {
+                let position = step as f32 / steps as f32;
+                (position, self.from_display(position))
+            })
+            .collect()
+    }
+}
+
+impl From<bool> for DisplayMapping {
+    /// Create a mapping that's inverted if `inverted` is true.
+    fn from(inverted: bool) -> Self {
+        Self { inverted }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAPPINGS: [DisplayMapping; 2] = [DisplayMapping::new(), DisplayMapping::inverted()];
+
+    #[test]
+    fn mapping_matrix() {
+        // (normalized value, display position when not inverted, display position when inverted)
+        let cases = [
+            (0.0, 0.0, 1.0),
+            (0.25, 0.25, 0.75),
+            (0.5, 0.5, 0.5),
+            (1.0, 1.0, 0.0),
+            (-0.5, 0.0, 1.0),
+            (1.5, 1.0, 0.0),
+        ];
+
+        for (value, regular, inverted) in cases {
+            for (mapping, expected) in MAPPINGS.into_iter().zip([regular, inverted]) {
+                let position = mapping.to_display(value);
+                assert_eq!(position, expected, "{mapping:?} {value}");
+
+                // Clicking where a value is shown selects that value again
+                assert_eq!(
+                    mapping.from_display(position),
+                    value.clamp(0.0, 1.0),
+                    "{mapping:?} {value}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn drag_direction() {
+        for mapping in MAPPINGS {
+            // Dragging towards the end of the widget always moves the shown position forwards
+            let start = 0.4;
+            let moved = mapping.to_display(start + mapping.delta_from_display(0.1));
+            assert!(
+                (moved - (mapping.to_display(start) + 0.1)).abs() < 1e-6,
+                "{mapping:?}"
+            );
+        }
+
+        assert_eq!(DisplayMapping::new().delta_from_display(0.1), 0.1);
+        assert_eq!(DisplayMapping::inverted().delta_from_display(0.1), -0.1);
+    }
+
+    #[test]
+    fn fill_and_bipolar_origin() {
+        // (value, bipolar origin, regular fill, inverted fill)
+        let cases = [
+            (0.75, None, (0.0, 0.75), (0.0, 0.25)),
+            (0.0, None, (0.0, 0.0), (0.0, 1.0)),
+            (0.75, Some(0.5), (0.5, 0.75), (0.5, 0.25)),
+            (0.25, Some(0.5), (0.5, 0.25), (0.5, 0.75)),
+            (0.5, Some(0.25), (0.25, 0.5), (0.75, 0.5)),
+        ];
+
+        for (value, origin, regular, inverted) in cases {
+            for (mapping, expected) in MAPPINGS.into_iter().zip([regular, inverted]) {
+                assert_eq!(
+                    mapping.fill_range(value, origin),
+                    expected,
+                    "{mapping:?} {value} {origin:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn tick_order() {
+        assert_eq!(
+            DisplayMapping::new().ticks(Some(2)),
+            [(0.0, 0.0), (0.5, 0.5), (1.0, 1.0)]
+        );
+        assert_eq!(
+            DisplayMapping::inverted().ticks(Some(2)),
+            [(0.0, 1.0), (0.5, 0.5), (1.0, 0.0)]
+        );
+        assert_eq!(
+            DisplayMapping::inverted().ticks(None),
+            [(0.0, 1.0), (1.0, 0.0)]
+        );
+
+        // Every tick is shown where its value is shown
+        for mapping in MAPPINGS {
+            for (position, value) in mapping.ticks(Some(4)) {
+                assert_eq!(mapping.to_display(value), position, "{mapping:?}");
+            }
+        }
+    }
+}
@@ -0,0 +1,241 @@
+//! The discrete values of stepped parameters, for widgets that show all of a parameter's values at
+//! once like [`Dropdown`][super::Dropdown]s.
+//!
+//! [`EnumParam`]s are listed using their variant metadata through [`EnumParamWidgetExt`], so every
+//! variant gets its own option even if several variants have the same display name. Other
+//! parameters, including the type erased [`EnumParamInner`] a generic UI sees, are listed by
+//! converting each of their steps to a string.
+
+use nih_plug::params::enums::EnumParamInner;
+use nih_plug::prelude::{BoolParam, Enum, EnumParam, IntParam, Param};
+
+/// One of a parameter's discrete values. Options are identified by their index, so two options
+/// with the same name are still different options.
+#[derive(Debug, Clone)]
+pub struct ParamOption {
+    /// The option's position in the parameter's list of values.
+    pub index: usize,
+    /// The variant's stable ID, if the parameter is an [`EnumParam`] with IDs.
+    pub id: Option<&'static str>,
+    /// The option's display name.
+    pub name: String,
+    /// The normalized value to set the parameter to when this option is selected.
+    pub normalized_value: f32,
+}
+
+impl PartialEq for ParamOption {
+    fn eq(&self, other: &Self) -> bool {
+        self.index == other.index
+    }
+}
+
+impl std::fmt::Display for ParamOption {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.name)
+    }
+}
+
+/// Gives widgets direct access to an [`EnumParam`]'s variants without going through the
+/// parameter's string conversion.
+pub trait EnumParamWidgetExt {
+    /// The number of variants.
+    fn variant_count(&self) -> usize;
+    /// The display names of all variants, in order.
+    fn variant_names(&self) -> &'static [&'static str];
+    /// The stable IDs of all variants, if the enum defines them.
+    fn variant_ids(&self) -> Option<&'static [&'static str]>;
+    /// The index of the currently selected variant, including modulation.
+    fn variant_index(&self) -> usize;
+    /// The normalized value that selects the variant at `index`.
+    fn variant_normalized_value(&self, index: usize) -> f32;
+}
+
+impl<T: Enum + PartialEq + 'static> EnumParamWidgetExt for EnumParam<T> {
+    fn variant_count(&self) -> usize {
+        T::variants().len()
+    }
+
+    fn variant_names(&self) -> &'static [&'static str] {
+        T::variants()
+    }
+
+    fn variant_ids(&self) -> Option<&'static [&'static str]> {
+        T::ids()
+    }
+
+    fn variant_index(&self) -> usize {
+        self.modulated_plain_value().to_index()
+    }
+
+    fn variant_normalized_value(&self, index: usize) -> f32 {
+        self.preview_normalized(T::from_index(index))
+    }
+}
+
+/// Parameters whose values can be listed as [`ParamOption`]s.
+pub trait ParamOptions: Param {
+    /// All of the parameter's values, in order.
+    fn options(&self) -> Vec<ParamOption>;
+    /// The index of the option for the parameter's current value, including modulation.
+    fn selected_index(&self) -> usize;
+
+    /// The option for the parameter's current value.
+    fn selected_option(&self) -> Option<ParamOption> {
+        self.options().into_iter().nth(self.selected_index())
+    }
+}
+
+impl<T: Enum + PartialEq + 'static> ParamOptions for EnumParam<T> {
+    fn options(&self) -> Vec<ParamOption> {
+        let ids = self.variant_ids();
+
+        self.variant_names()
+            .iter()
+            .enumerate()
+            .map(|(index, name)| ParamOption {
+                index,
+                id: ids.and_then(|ids| ids.get(index).copied()),
+                name: (*name).to_owned(),
+                normalized_value: self.variant_normalized_value(index),
+            })
+            .collect()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.variant_index()
+    }
+}
+
+impl ParamOptions for IntParam {
+    fn options(&self) -> Vec<ParamOption> {
+        string_options(self)
+    }
+
+    fn selected_index(&self) -> usize {
+        string_selected_index(self)
+    }
+}
+
+impl ParamOptions for EnumParamInner {
+    fn options(&self) -> Vec<ParamOption> {
+        string_options(self)
+    }
+
+    fn selected_index(&self) -> usize {
+        string_selected_index(self)
+    }
+}
+
+impl ParamOptions for BoolParam {
+    fn options(&self) -> Vec<ParamOption> {
+        string_options(self)
+    }
+
+    fn selected_index(&self) -> usize {
+        string_selected_index(self)
+    }
+}
+
+/// List a stepped parameter's values by converting every step to a string.
+fn string_options<P: Param>(param: &P) -> Vec<ParamOption> {
+    let steps = param.step_count().unwrap_or(0);
+
+    (0..=steps)
+        .map(|index| {
+            let normalized_value = if steps == 0 {
+                0.0
+            } else {
+                index as f32 / steps as f32
+            };
+
+            ParamOption {
+                index,
+                id: None,
+                name: param.normalized_value_to_string(normalized_value, true),
+                normalized_value,
+            }
+        })
+        .collect()
+}
+
+fn string_selected_index<P: Param>(param: &P) -> usize {
+    let steps = param.step_count().unwrap_or(0);
+
+    (param.modulated_normalized_value() * steps as f32).round() as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nih_plug::prelude::IntRange;
+
+    #[derive(Debug, PartialEq)]
+    enum Wave {
+        Sine,
+        SawUp,
+        SawDown,
+    }
+
+    impl Enum for Wave {
+        fn variants() -> &'static [&'static str] {
+            // The two saw variants deliberately share a display name
+            &["Sine", "Saw", "Saw"]
+        }
+
+        fn ids() -> Option<&'static [&'static str]> {
+            Some(&["sine", "saw_up", "saw_down"])
+        }
+
+        fn to_index(self) -> usize {
+            self as usize
+        }
+
+        fn from_index(index: usize) -> Self {
+            match index {
+                0 => Wave::Sine,
+                1 => Wave::SawUp,
+                _ => Wave::SawDown,
+            }
+        }
+    }
+
+    #[test]
+    fn enum_variants_with_identical_names() {
+        let param = EnumParam::new("Wave", Wave::SawDown);
+        let options = param.options();
+
+        assert_eq!(options.len(), 3);
+        assert_eq!(options[1].name, options[2].name);
+        assert_ne!(options[1], options[2]);
+        assert_eq!(options[2].id, Some("saw_down"));
+        assert_eq!(options[2].normalized_value, 1.0);
+
+        // Going through the display name would pick the first "Saw" instead
+        let selected = param.selected_option().unwrap();
+        assert_eq!(selected.index, 2);
+        assert_eq!(selected.id, Some("saw_down"));
+        assert_eq!(
+            options
+                .iter()
+                .position(|option| option.name == param.to_string()),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn int_param_uses_strings() {
+        let param = IntParam::new("Voices", 3, IntRange::Linear { min: 1, max: 4 });
+        let options = param.options();
+
+        assert_eq!(
+            options
+                .iter()
+                .map(|option| &*option.name)
+                .collect::<Vec<_>>(),
+            ["1", "2", "3", "4"]
+        );
+        assert!(options.iter().all(|option| option.id.is_none()));
+        assert_eq!(param.selected_index(), 2);
+        assert_eq!(param.preview_plain(options[3].normalized_value), 4);
+    }
+}
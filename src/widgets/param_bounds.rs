@@ -0,0 +1,287 @@
+//! Finding out where a parameter's widgets are on screen.
+//!
+//! Some hosts highlight the control that belongs to a parameter when the user touches it on a
+//! hardware controller. To do that, the editor needs to know where that control is. Parameter
+//! widgets built with `.report_bounds(true)` write their bounds into the [`ParamBoundsMap`] from
+//! [`IcedState::param_bounds()`][crate::IcedState::param_bounds()] every time they're drawn, and
+//! [`HighlightOverlay`][super::HighlightOverlay] uses that map to draw a glow over them.
+//!
+//! The entries follow these rules:
+//!
+//! - A widget's entry holds the logical bounds it was last drawn at. After a relayout, for instance
+//!   when the window is resized, the entry is updated with the next frame.
+//! - A widget's entry is removed when the widget disappears from the editor's view, when it stops
+//!   reporting its bounds, and when the window closes.
+//! - Every widget has its own entry, so a parameter that's shown by multiple widgets has multiple
+//!   bounds. They are returned in the order the widgets first reported them.
+//!
+//! The bounds are in the same coordinates as the widget's layout. For widgets inside of a
+//! scrollable those don't account for the scroll offset.
+//!
+//! The widgets don't know the string IDs NIH-plug uses for their parameters, so the map is keyed by
+//! [`ParamPtr`]. [`ParamBoundsMap::get_by_id()`] looks up the parameter for an ID first. A single
+//! widget can also be looked up by its widget [`Id`] with [`ParamBoundsMap::get_widget()`]. See the
+//! [`param_id`][super::param_id] module for the ids parameter widgets get by default.
+
+use nih_plug::prelude::{ParamPtr, Params};
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+
+use crate::core::widget::Id;
+use crate::core::Rectangle;
+
+thread_local! {
+    /// The map for the editor whose view is currently being built.
+    static CURRENT: RefCell<Option<ParamBoundsMap>> = const { RefCell::new(None) };
+}
+
+/// Where the parameter widgets that report their bounds were last drawn. This is shared, so the
+/// editor can store it and the widgets can write to it. See the [module documentation][self].
+#[derive(Debug, Clone, Default)]
+pub struct ParamBoundsMap {
+    inner: Arc<Mutex<Entries>>,
+}
+
+#[derive(Debug, Default)]
+struct Entries {
+    /// The ID for the next widget that reports its bounds. These only ever increase, so iterating
+    /// over the entries visits the widgets in the order they first reported their bounds.
+    next_slot: u64,
+    entries: BTreeMap<u64, (ParamPtr, Id, Rectangle)>,
+}
+
+impl ParamBoundsMap {
+    /// The map of the editor whose view is currently being built, if any. Widgets call this when
+    /// they're told to report their bounds. This only returns a map from within
+    /// [`IcedEditor::view()`][crate::IcedEditor::view()] and
+    /// [`IcedEditor::child_view()`][crate::IcedEditor::child_view()].
+    pub fn current() -> Option<Self> {
+        CURRENT.with(|current| current.borrow().clone())
+    }
+
+    /// The bounds of every widget for `param`, in the order they first reported them.
+    pub fn get(&self, param: ParamPtr) -> Vec<Rectangle> {
+        self.inner
+            .lock()
+            .unwrap()
+            .entries
+            .values()
+            .filter(|(entry_param, _, _)| *entry_param == param)
+            .map(|(_, _, bounds)| *bounds)
+            .collect()
+    }
+
+    /// The bounds of the widget with the [`Id`] `id`, if it reports its bounds. If multiple widgets
+    /// share that `id`, then this returns the bounds of the one that reported them first.
+    pub fn get_widget(&self, id: &Id) -> Option<Rectangle> {
+        self.inner
+            .lock()
+            .unwrap()
+            .entries
+            .values()
+            .find(|(_, entry_id, _)| entry_id == id)
+            .map(|(_, _, bounds)| *bounds)
+    }
+
+    /// The bounds of every widget for the parameter with the ID `id` in `params`, in the order they
+    /// first reported them. This is empty if there's no parameter with that ID.
+    pub fn get_by_id(&self, params: &dyn Params, id: &str) -> Vec<Rectangle> {
+        params
+            .param_map()
+            .into_iter()
+            .find(|(param_id, _, _)| param_id == id)
+            .map(|(_, param, _)| self.get(param))
+            .unwrap_or_default()
+    }
+
+    /// Claim an entry for a new widget.
+    fn slot(&self) -> Slot {
+        let mut inner = self.inner.lock().unwrap();
+        let id = inner.next_slot;
+        inner.next_slot += 1;
+
+        Slot {
+            map: self.clone(),
+            id,
+        }
+    }
+}
+
+/// Make `map` available to [`ParamBoundsMap::current()`] while `f` builds the editor's view.
+pub(crate) fn with_map<R>(map: &ParamBoundsMap, f: impl FnOnce() -> R) -> R {
+    let previous = CURRENT.with(|current| current.replace(Some(map.clone())));
+    let result = f();
+    CURRENT.with(|current| *current.borrow_mut() = previous);
+
+    result
+}
+
+/// A widget's entry in a [`ParamBoundsMap`]. This is stored in the widget's state, so the entry is
+/// removed together with the state.
+#[derive(Debug, Default)]
+pub struct ReportedBounds {
+    slot: RefCell<Option<Slot>>,
+}
+
+impl ReportedBounds {
+    /// Write `bounds` to `map` as the bounds of the widget with the [`Id`] `id` for `param`. Call
+    /// this from the widget's `draw()` implementation. If `map` is `None` then the widget's entry
+    /// is removed.
+    pub fn report(
+        &self,
+        map: Option<&ParamBoundsMap>,
+        param: ParamPtr,
+        id: &Id,
+        bounds: Rectangle,
+    ) {
+        let mut slot = self.slot.borrow_mut();
+        let Some(map) = map else {
+            *slot = None;
+            return;
+        };
+
+        if !slot
+            .as_ref()
+            .is_some_and(|slot| Arc::ptr_eq(&slot.map.inner, &map.inner))
+        {
+            *slot = Some(map.slot());
+        }
+
+        let slot = slot.as_ref().unwrap();
+        map.inner
+            .lock()
+            .unwrap()
+            .entries
+            .insert(slot.id, (param, id.clone(), bounds));
+    }
+}
+
+/// Removes its entry when it's dropped.
+#[derive(Debug)]
+struct Slot {
+    map: ParamBoundsMap,
+    id: u64,
+}
+
+impl Drop for Slot {
+    fn drop(&mut self) {
+        self.map.inner.lock().unwrap().entries.remove(&self.id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nih_plug::prelude::{FloatParam, FloatRange, Param};
+
+    use super::*;
+    use crate::core::{mouse, renderer, Color, Element, Length, Size, Theme};
+    use crate::runtime::user_interface::{Cache, UserInterface};
+    use crate::widget::{column, Space};
+    use crate::widgets::param_id::{self, param_widget_id};
+    use crate::widgets::{ParamMessage, ParamRing, ParamSlider};
+
+    fn param(name: &str) -> FloatParam {
+        FloatParam::new(name, 0.5, FloatRange::Linear { min: 0.0, max: 1.0 })
+    }
+
+    #[test]
+    fn entries_follow_the_widgets() {
+        let (gain, cutoff) = (param("Gain"), param("Cutoff"));
+        let map = ParamBoundsMap::default();
+        let style = renderer::Style {
+            text_color: Color::BLACK,
+        };
+
+        // The view is rebuilt for every frame, like the runtime does after every update
+        let mut cache = Cache::default();
+        let mut frame = |spacer: f32, show_ring: bool, report_ring: bool| {
+            let view: Element<'_, ParamMessage, Theme, ()> = with_map(&map, || {
+                let column = column![
+                    Space::new().height(Length::Fixed(spacer)),
+                    ParamSlider::new(&gain).report_bounds(true),
+                    ParamSlider::new(&cutoff),
+                ];
+                if show_ring {
+                    column
+                        .push(ParamRing::new(&gain).report_bounds(report_ring))
+                        .into()
+                } else {
+                    column.into()
+                }
+            });
+
+            let mut interface = UserInterface::build(
+                view,
+                Size::new(200.0, 200.0),
+                std::mem::take(&mut cache),
+                &mut (),
+            );
+            interface.draw(&mut (), &Theme::Dark, &style, mouse::Cursor::Unavailable);
+            cache = interface.into_cache();
+        };
+
+        // Both widgets for the same parameter have their own entry, and widgets that don't report
+        // their bounds don't have one
+        frame(10.0, true, true);
+        let slider = Rectangle::new((0.0, 10.0).into(), Size::new(180.0, 30.0));
+        let ring = Rectangle::new((0.0, 70.0).into(), Size::new(56.0, 56.0));
+        assert_eq!(map.get(gain.as_ptr()), [slider, ring]);
+        assert_eq!(map.get(cutoff.as_ptr()), []);
+
+        // Moved widgets are updated, and removed widgets lose their entries
+        frame(20.0, false, true);
+        assert_eq!(
+            map.get(gain.as_ptr()),
+            [slider + crate::core::Vector::new(0.0, 10.0)]
+        );
+
+        // So do widgets that stop reporting their bounds
+        frame(20.0, true, true);
+        assert_eq!(map.get(gain.as_ptr()).len(), 2);
+        frame(20.0, true, false);
+        assert_eq!(map.get(gain.as_ptr()).len(), 1);
+
+        // Outside of the view, there's no map to report to
+        assert!(ParamBoundsMap::current().is_none());
+        drop(cache);
+        assert_eq!(map.get(gain.as_ptr()), []);
+    }
+
+    #[test]
+    fn widgets_are_found_by_id() {
+        let (gain, cutoff) = (param("Gain"), param("Cutoff"));
+        let map = ParamBoundsMap::default();
+        let style = renderer::Style {
+            text_color: Color::BLACK,
+        };
+
+        // Two sliders for the same parameter, and one with an explicit id
+        let view: Element<'_, ParamMessage, Theme, ()> = with_map(&map, || {
+            param_id::with_occurrences(|| {
+                column![
+                    ParamSlider::new(&gain).report_bounds(true),
+                    ParamSlider::new(&gain).report_bounds(true),
+                    ParamSlider::new(&cutoff).id("cutoff").report_bounds(true),
+                ]
+                .into()
+            })
+        });
+        let mut interface =
+            UserInterface::build(view, Size::new(200.0, 200.0), Cache::default(), &mut ());
+        interface.draw(&mut (), &Theme::Dark, &style, mouse::Cursor::Unavailable);
+
+        // The same view hands out the same ids every time it's built
+        let [first, second] = param_id::with_occurrences(|| {
+            [
+                param_widget_id(gain.as_ptr()),
+                param_widget_id(gain.as_ptr()),
+            ]
+        });
+        let slider = |y| Rectangle::new((0.0, y).into(), Size::new(180.0, 30.0));
+        assert_eq!(map.get_widget(&first), Some(slider(0.0)));
+        assert_eq!(map.get_widget(&second), Some(slider(30.0)));
+        assert_eq!(map.get_widget(&Id::new("cutoff")), Some(slider(60.0)));
+        assert_eq!(map.get_widget(&param_widget_id(cutoff.as_ptr())), None);
+    }
+}
@@ -0,0 +1,1413 @@
+//! Mouse, touch, scroll wheel, and text entry handling shared by the parameter widgets.
+//!
+//! [`ParamSlider`][super::ParamSlider], [`ParamRing`][super::ParamRing], and any other widget that
+//! edits a single [`Param`] should handle input through a [`ParamDrag`] and a [`TextEntry`] stored
+//! in their widget state. That way they all send the same automation gestures, and share the same
+//! conventions:
+//!
+//! - Dragging sets the value, either by following the cursor ([`DragBehavior::Absolute`] and
+//!   [`DragBehavior::Circular`]) or by moving relative to where the drag started
+//!   ([`DragBehavior::Vertical`] and [`DragBehavior::Horizontal`]).
+//! - Holding one of the [`fine_adjust`][crate::InteractionSettings::fine_adjust] modifiers while
+//!   dragging makes the drag more granular. By default that's Shift, and on macOS also Cmd.
+//!   Pressing or releasing those modifiers during a drag re-anchors it so the value doesn't jump.
+//! - Double clicking or Ctrl/Cmd+clicking resets the parameter to its default value. When
+//!   Ctrl/Cmd is also a fine adjustment modifier, the parameter is only reset if the cursor
+//!   doesn't move before the button is released, and dragging with it makes a granular drag.
+//! - Alt+clicking or long-pressing opens a [`TextEntry`] for typing in a value.
+//! - Scrolling moves the value by one step, or by a small amount for continuous parameters, see
+//!   [`WheelSettings`][crate::WheelSettings] for the direction and sensitivity. With
+//!   [`ScrollToAdjust::Guarded`] this only happens after the widget has been clicked or while
+//!   Ctrl/Cmd is held.
+//!
+//! Every drag, reset, scroll, and submitted value is wrapped in a single
+//! [`BeginSetParameter`][ParamMessage::BeginSetParameter] and
+//! [`EndSetParameter`][ParamMessage::EndSetParameter] pair.
+//!
+//! While the editor's input is locked (see [`interaction::is_input_locked()`]), none of this
+//! happens. A drag that's still in progress when the editor gets locked is ended on the next event,
+//! and an open text entry is closed without submitting its value.
+
+use nih_plug::prelude::Param;
+use std::borrow::Borrow;
+
+use crate::core::text::{Paragraph, Renderer as TextRenderer, Text};
+use crate::core::widget::tree::Tree;
+use crate::core::widget::{Id, Operation, Widget};
+use crate::core::{
+    alignment, keyboard, layout, mouse, touch, Clipboard, Event, Font, Layout, Pixels, Point,
+    Radians, Rectangle, Shell, Size, Vector,
+};
+use crate::interaction::{self, Click, ClickKind, LongPress, ScrollToAdjust, WheelAccumulator};
+use crate::widget::text_input;
+use crate::widget::text_input::TextInput;
+
+use super::mapping::DisplayMapping;
+use super::range::ParamRange;
+use super::{expression, util, ParamMessage};
+
+/// When dragging a parameter with a fine adjustment modifier held, the value changes this much
+/// slower than during a regular drag.
+pub const GRANULAR_DRAG_MULTIPLIER: f32 = 0.1;
+
+/// How much one step of scrolling changes the display position of a continuous parameter. Stepped
+/// parameters move by one of their own steps instead.
+pub const SCROLL_LINE_DELTA: f32 = 0.05;
+
+/// How dragging changes a parameter's value.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DragBehavior {
+    /// The value jumps to the cursor's horizontal position within the widget's bounds, like a
+    /// slider.
+    Absolute,
+    /// Dragging upwards increases the value, like a knob. Clicking doesn't change the value, and
+    /// dragging by this many logical pixels sweeps the entire range.
+    Vertical(f32),
+    /// Dragging to the right increases the value. Clicking doesn't change the value, and dragging
+    /// by this many logical pixels sweeps the entire range.
+    Horizontal(f32),
+    /// The value jumps to the angle of the cursor around the center of the widget's bounds, like
+    /// turning a knob by its pointer. Angles are measured clockwise from the top, and the range
+    /// starts at `start_angle` and sweeps `sweep` radians. A negative sweep goes counterclockwise.
+    /// The cursor's angle in the gap between the end and the start of the sweep counts as
+    /// whichever end is closer.
+    Circular {
+        start_angle: Radians,
+        sweep: Radians,
+    },
+}
+
+impl DragBehavior {
+    /// The cursor's position along the drag axis. This increases in the direction that increases
+    /// the display position.
+    fn coordinate(self, bounds: &Rectangle, position: Point) -> f32 {
+        match self {
+            DragBehavior::Absolute | DragBehavior::Horizontal(_) => position.x,
+            DragBehavior::Vertical(_) => -position.y,
+            DragBehavior::Circular { .. } => self.absolute_position(bounds, position),
+        }
+    }
+
+    /// The distance along the drag axis that covers the entire range.
+    fn range(self, bounds: &Rectangle) -> f32 {
+        match self {
+            DragBehavior::Absolute => bounds.width,
+            DragBehavior::Vertical(range) | DragBehavior::Horizontal(range) => range,
+            DragBehavior::Circular { .. } => 1.0,
+        }
+    }
+
+    /// Whether the value jumps to the cursor's position, instead of moving relative to where the
+    /// drag started.
+    fn is_absolute(self) -> bool {
+        matches!(self, DragBehavior::Absolute | DragBehavior::Circular { .. })
+    }
+
+    /// The display position an absolute drag sets for the cursor's position, in `[0, 1]`.
+    fn absolute_position(self, bounds: &Rectangle, position: Point) -> f32 {
+        let DragBehavior::Circular { start_angle, sweep } = self else {
+            return util::remap_rect_x_coordinate(bounds, position.x);
+        };
+
+        let sweep_length = sweep.0.abs().min(std::f32::consts::TAU);
+        if sweep_length == 0.0 {
+            return 0.0;
+        }
+
+        // Clockwise from the top, like `util::point_on_circle()`
+        let offset = position - bounds.center();
+        let angle = offset.x.atan2(-offset.y);
+        let travelled =
+            ((angle - start_angle.0) * sweep.0.signum()).rem_euclid(std::f32::consts::TAU);
+        if travelled <= sweep_length {
+            travelled / sweep_length
+        } else if travelled - sweep_length < std::f32::consts::TAU - travelled {
+            1.0
+        } else {
+            0.0
+        }
+    }
+}
+
+/// The point a relative drag moves from.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Anchor {
+    /// The cursor's coordinate along the drag axis.
+    coordinate: f32,
+    /// The display position at that coordinate.
+    position: f32,
+    /// Whether this is a granular drag. Switching between regular and granular drags re-anchors
+    /// the drag at the current cursor position.
+    granular: bool,
+}
+
+/// The widget state for dragging, clicking, and scrolling a parameter. See the [module
+/// documentation][self].
+#[derive(Debug, Clone)]
+pub struct ParamDrag {
+    keyboard_modifiers: keyboard::Modifiers,
+    /// Will be set to `true` if we're dragging the parameter. Resetting the parameter or entering a
+    /// text value should not initiate a drag.
+    drag_active: bool,
+    /// Relative and granular drags move from this point. This is `None` while the value follows
+    /// the cursor.
+    anchor: Option<Anchor>,
+    /// Track clicks for double clicks.
+    last_click: Option<Click>,
+    /// Touch input can't use the Alt key to open the text entry, so long-pressing the widget does
+    /// that instead.
+    long_press: Option<LongPress>,
+    /// If the interaction settings have a drag threshold, then the value only starts changing once
+    /// the cursor has moved that far away from this point. An absolute drag that never got that
+    /// far sets the value at this point when the button is released.
+    pending_drag_start: Option<Point>,
+    /// Set when the widget was Ctrl/Cmd+clicked while that's also a fine adjustment modifier. The
+    /// parameter is reset when the button is released before the cursor moved away from this
+    /// point, and otherwise this turns into a granular drag.
+    pending_reset: Option<Point>,
+    /// Whether the widget was the last thing clicked on. Guarded widgets only respond to scrolling
+    /// while focused.
+    focused: bool,
+    /// Scrolling that didn't add up to a whole step of a stepped parameter yet.
+    wheel: WheelAccumulator,
+}
+
+impl Default for ParamDrag {
+    fn default() -> Self {
+        Self {
+            // A widget that's created while a modifier is held won't get an event for it
+            keyboard_modifiers: interaction::modifiers(),
+            drag_active: false,
+            anchor: None,
+            last_click: None,
+            long_press: None,
+            pending_drag_start: None,
+            pending_reset: None,
+            focused: false,
+            wheel: WheelAccumulator::default(),
+        }
+    }
+}
+
+impl ParamDrag {
+    /// Whether the parameter is currently being dragged.
+    pub fn is_dragging(&self) -> bool {
+        self.drag_active
+    }
+
+    /// Whether the widget was clicked, and nothing else was clicked since.
+    pub fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    /// The keyboard modifiers as of the last modifier change, or as of when the widget was created.
+    /// See [`interaction::modifiers()`].
+    pub fn keyboard_modifiers(&self) -> keyboard::Modifiers {
+        self.keyboard_modifiers
+    }
+
+    /// Handle a mouse, touch, or modifier event for a widget covering `bounds`. Returns `true` if
+    /// the widget should open its [`TextEntry`].
+    #[allow(clippy::too_many_arguments)]
+    #[must_use]
+    pub fn update<P: Param>(
+        &mut self,
+        param: &P,
+        mapping: DisplayMapping,
+        behavior: DragBehavior,
+        event: &Event,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+        shell: &mut Shell<'_, ParamMessage>,
+    ) -> bool {
+        if interaction::is_input_locked() {
+            if let Event::Keyboard(keyboard::Event::ModifiersChanged(modifiers)) = event {
+                self.keyboard_modifiers = *modifiers;
+            }
+
+            self.cancel(param, shell);
+            return false;
+        }
+
+        match event {
+            Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left))
+            | Event::Touch(touch::Event::FingerPressed { .. }) => {
+                let Some(cursor_position) = cursor.position_over(bounds) else {
+                    self.focused = false;
+                    return false;
+                };
+
+                self.focused = true;
+                let click = Click::new(cursor_position, self.last_click);
+                self.last_click = Some(click);
+                self.long_press =
+                    matches!(event, Event::Touch(_)).then(|| LongPress::new(cursor_position));
+                self.pending_drag_start = None;
+                self.pending_reset = None;
+                self.anchor = None;
+
+                let granular = is_fine_adjust(self.keyboard_modifiers);
+                let reset = self.keyboard_modifiers.command();
+                if self.keyboard_modifiers.alt() {
+                    // Alt+click should not start a drag, instead it should show the text entry
+                    // widget
+                    self.drag_active = false;
+                    return true;
+                } else if (reset && !granular) || click.kind() == ClickKind::Double {
+                    // Likewise resetting a parameter should not let you immediately drag it to a new
+                    // value
+                    self.drag_active = false;
+
+                    shell.publish(ParamMessage::BeginSetParameter(param.as_ptr()));
+                    set_normalized_value(param, shell, param.default_normalized_value());
+                    shell.publish(ParamMessage::EndSetParameter(param.as_ptr()));
+                } else {
+                    shell.publish(ParamMessage::BeginSetParameter(param.as_ptr()));
+                    self.drag_active = true;
+
+                    if reset {
+                        // Whether this is a reset or a granular drag is only known once the
+                        // cursor moves or the button is released
+                        self.pending_reset = Some(cursor_position);
+                    } else if granular {
+                        // When holding down a fine adjustment modifier while clicking on a
+                        // parameter we want to granuarly edit the parameter without jumping to a
+                        // new value
+                        self.anchor = Some(self.anchor_at(
+                            param,
+                            mapping,
+                            behavior,
+                            &bounds,
+                            cursor_position,
+                        ));
+                    } else if interaction::current().drag_threshold > 0.0 {
+                        self.pending_drag_start = Some(cursor_position);
+                    } else if behavior.is_absolute() {
+                        set_normalized_value(
+                            param,
+                            shell,
+                            mapping
+                                .from_display(behavior.absolute_position(&bounds, cursor_position)),
+                        );
+                    } else {
+                        self.anchor = Some(self.anchor_at(
+                            param,
+                            mapping,
+                            behavior,
+                            &bounds,
+                            cursor_position,
+                        ));
+                    }
+                }
+            }
+            Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left))
+            | Event::Touch(touch::Event::FingerLifted { .. } | touch::Event::FingerLost { .. }) => {
+                let long_press = self.long_press.take();
+                if !self.drag_active {
+                    return false;
+                }
+
+                if self.pending_reset.take().is_some() {
+                    set_normalized_value(param, shell, param.default_normalized_value());
+                }
+
+                // A click that never got past the drag threshold still sets the value
+                if let Some(drag_start) = self.pending_drag_start.take() {
+                    if behavior.is_absolute() {
+                        set_normalized_value(
+                            param,
+                            shell,
+                            mapping.from_display(behavior.absolute_position(&bounds, drag_start)),
+                        );
+                    }
+                }
+
+                shell.publish(ParamMessage::EndSetParameter(param.as_ptr()));
+                self.drag_active = false;
+                self.anchor = None;
+
+                return long_press.is_some_and(|long_press| long_press.is_long_press());
+            }
+            Event::Mouse(mouse::Event::CursorMoved { .. })
+            | Event::Touch(touch::Event::FingerMoved { .. }) => {
+                // Don't do anything when we just reset the parameter because that would be weird
+                if !self.drag_active {
+                    return false;
+                }
+                let Some(cursor_position) = cursor.position() else {
+                    return false;
+                };
+
+                let interaction = interaction::current();
+                if let Some(long_press) = &mut self.long_press {
+                    long_press.cursor_moved(cursor_position, &interaction);
+                }
+
+                if let Some(press) = self.pending_reset {
+                    if press.distance(cursor_position) < interaction.click_tolerance() {
+                        return false;
+                    }
+
+                    // The drag continues from the current value instead of from the press
+                    self.pending_reset = None;
+                    self.anchor = None;
+                }
+
+                if let Some(drag_start) = self.pending_drag_start {
+                    if !interaction::exceeds_drag_threshold(
+                        drag_start,
+                        cursor_position,
+                        &interaction,
+                    ) {
+                        return false;
+                    }
+
+                    self.pending_drag_start = None;
+                }
+
+                self.drag_to(param, mapping, behavior, bounds, cursor_position, shell);
+            }
+            Event::Keyboard(keyboard::Event::ModifiersChanged(modifiers)) => {
+                self.keyboard_modifiers = *modifiers;
+
+                // Starting or stopping a granular drag re-anchors the drag at the current cursor
+                // position so the value doesn't jump
+                let granular = self.anchor.is_some_and(|anchor| anchor.granular);
+                if !self.drag_active
+                    || self.pending_drag_start.is_some()
+                    || self.pending_reset.is_some()
+                    || granular == is_fine_adjust(*modifiers)
+                {
+                    return false;
+                }
+                let Some(cursor_position) = cursor.position() else {
+                    return false;
+                };
+
+                if behavior.is_absolute() && !is_fine_adjust(*modifiers) {
+                    // Absolute drags instead snap back to reality uh I mean the current screen
+                    // position
+                    self.anchor = None;
+                    self.drag_to(param, mapping, behavior, bounds, cursor_position, shell);
+                } else {
+                    self.anchor =
+                        Some(self.anchor_at(param, mapping, behavior, &bounds, cursor_position));
+                }
+            }
+            _ => {}
+        }
+
+        false
+    }
+
+    /// Handle a scroll wheel event for a widget covering `bounds`. Scrolling up or to the right
+    /// moves the display position forwards, by the number of steps from the
+    /// [`WheelSettings`][crate::WheelSettings]. This does nothing while the
+    /// parameter is being dragged, or if the scroll is guarded and the widget isn't focused.
+    /// `scroll_to_adjust` is the widget's own setting, and `None` uses the
+    /// [`InteractionSettings`][crate::InteractionSettings]' setting.
+    #[allow(clippy::too_many_arguments)]
+    pub fn scroll<P: Param>(
+        &mut self,
+        param: &P,
+        mapping: DisplayMapping,
+        scroll_to_adjust: Option<ScrollToAdjust>,
+        event: &Event,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+        shell: &mut Shell<'_, ParamMessage>,
+    ) {
+        let Event::Mouse(mouse::Event::WheelScrolled { delta }) = event else {
+            return;
+        };
+        if self.drag_active || !cursor.is_over(bounds) || interaction::is_input_locked() {
+            return;
+        }
+
+        let interaction = interaction::current();
+        let is_guarded =
+            scroll_to_adjust.unwrap_or(interaction.scroll_to_adjust) == ScrollToAdjust::Guarded;
+        if is_guarded && !self.focused && !self.keyboard_modifiers.command() {
+            return;
+        }
+
+        let steps = interaction.wheel.steps(*delta);
+        if steps == 0.0 {
+            return;
+        }
+
+        let position_delta = match ParamRange::of(param).step_size() {
+            Some(step_size) => {
+                let steps = self.wheel.whole_steps(steps);
+                if steps == 0 {
+                    // The scroll still belongs to this widget even if it didn't move the value
+                    shell.capture_event();
+                    return;
+                }
+
+                steps as f32 * step_size
+            }
+            None if interaction.is_fine_adjust(self.keyboard_modifiers) => {
+                steps * SCROLL_LINE_DELTA * GRANULAR_DRAG_MULTIPLIER
+            }
+            None => steps * SCROLL_LINE_DELTA,
+        };
+        let normalized_value =
+            param.unmodulated_normalized_value() + mapping.delta_from_display(position_delta);
+
+        shell.publish(ParamMessage::BeginSetParameter(param.as_ptr()));
+        set_normalized_value(param, shell, normalized_value.clamp(0.0, 1.0));
+        shell.publish(ParamMessage::EndSetParameter(param.as_ptr()));
+        shell.capture_event();
+    }
+
+    /// End the current drag, if any, without changing the parameter any further.
+    fn cancel<P: Param>(&mut self, param: &P, shell: &mut Shell<'_, ParamMessage>) {
+        if self.drag_active {
+            shell.publish(ParamMessage::EndSetParameter(param.as_ptr()));
+        }
+
+        self.drag_active = false;
+        self.anchor = None;
+        self.long_press = None;
+        self.pending_drag_start = None;
+        self.pending_reset = None;
+    }
+
+    /// Anchor a relative drag at the cursor position and the parameter's current value.
+    fn anchor_at<P: Param>(
+        &self,
+        param: &P,
+        mapping: DisplayMapping,
+        behavior: DragBehavior,
+        bounds: &Rectangle,
+        cursor_position: Point,
+    ) -> Anchor {
+        Anchor {
+            coordinate: behavior.coordinate(bounds, cursor_position),
+            position: mapping.to_display(param.modulated_normalized_value()),
+            granular: is_fine_adjust(self.keyboard_modifiers),
+        }
+    }
+
+    /// Set the value for the cursor's current position during a drag.
+    fn drag_to<P: Param>(
+        &mut self,
+        param: &P,
+        mapping: DisplayMapping,
+        behavior: DragBehavior,
+        bounds: Rectangle,
+        cursor_position: Point,
+        shell: &mut Shell<'_, ParamMessage>,
+    ) {
+        let granular = is_fine_adjust(self.keyboard_modifiers);
+        if behavior.is_absolute() && !granular {
+            self.anchor = None;
+            set_normalized_value(
+                param,
+                shell,
+                mapping.from_display(behavior.absolute_position(&bounds, cursor_position)),
+            );
+
+            return;
+        }
+
+        // The anchor is normally updated when the modifiers change, but that event may have been
+        // missed
+        let anchor = match self.anchor {
+            Some(anchor) if anchor.granular == granular => anchor,
+            _ => self.anchor_at(param, mapping, behavior, &bounds, cursor_position),
+        };
+        self.anchor = Some(anchor);
+
+        let range = behavior.range(&bounds);
+        if range <= 0.0 {
+            return;
+        }
+
+        let multiplier = if granular {
+            GRANULAR_DRAG_MULTIPLIER
+        } else {
+            1.0
+        };
+        let distance = behavior.coordinate(&bounds, cursor_position) - anchor.coordinate;
+        let position = anchor.position + (distance / range) * multiplier;
+
+        set_normalized_value(param, shell, mapping.from_display(position));
+    }
+}
+
+/// Whether `modifiers` make adjustments more granular with the
+/// [`current()`][interaction::current()] interaction settings.
+fn is_fine_adjust(modifiers: keyboard::Modifiers) -> bool {
+    interaction::current().is_fine_adjust(modifiers)
+}
+
+/// Set the normalized value for a parameter if that would change the parameter's plain value
+/// (to avoid unnecessary duplicate parameter changes). The begin- and end set parameter
+/// messages need to be sent before calling this function.
+pub fn set_normalized_value<P: Param>(
+    param: &P,
+    shell: &mut Shell<'_, ParamMessage>,
+    normalized_value: f32,
+) {
+    // This snaps to the nearest plain value if the parameter is stepped in some way.
+    // TODO: As an optimization, we could add a `const CONTINUOUS: bool` to the parameter to
+    //       avoid this normalized->plain->normalized conversion for parameters that don't need
+    //       it
+    let plain_value = param.preview_plain(normalized_value);
+    let current_plain_value = param.modulated_plain_value();
+    if plain_value != current_plain_value {
+        // For the aforementioned snapping
+        let normalized_plain_value = param.preview_normalized(plain_value);
+        shell.publish(ParamMessage::SetParameterNormalized(
+            param.as_ptr(),
+            normalized_plain_value,
+        ));
+    }
+}
+
+/// An internal message for intercep- I mean handling output from the embedded [`TextInput`] widget.
+#[derive(Debug, Clone)]
+enum TextInputMessage {
+    /// A new value was entered in the text input dialog.
+    Value(String),
+    /// Enter was pressed.
+    Submit,
+}
+
+/// A text field for typing in a parameter's value, shown on top of a parameter widget. The widget
+/// needs to store the field's state in a child [`Tree`] created with [`TextEntry::tree()`].
+#[derive(Debug, Clone)]
+pub struct TextEntry {
+    /// The text that's currently in the text input. If this is set to `None`, then the text input
+    /// is not visible.
+    value: Option<String>,
+    id: Id,
+}
+
+impl Default for TextEntry {
+    fn default() -> Self {
+        Self {
+            value: None,
+            id: Id::unique(),
+        }
+    }
+}
+
+impl TextEntry {
+    /// The child [`Tree`] for the text field's state.
+    pub fn tree<Theme, Renderer>() -> Tree
+    where
+        Theme: text_input::Catalog,
+        Renderer: TextRenderer,
+    {
+        let input = text_input::<TextInputMessage, Theme, Renderer>("", "");
+
+        Tree {
+            tag: input.tag(),
+            state: input.state(),
+            children: input.children(),
+        }
+    }
+
+    /// Whether the text field is visible.
+    pub fn is_open(&self) -> bool {
+        self.value.is_some()
+    }
+
+    /// The text that's currently in the text field, if it's visible.
+    pub fn value(&self) -> Option<&str> {
+        self.value.as_deref()
+    }
+
+    /// Whether the typed-in value is outside of the parameter's range, and will thus be clamped.
+    pub fn is_out_of_range<P: Param>(&self, param: &P) -> bool {
+        self.value
+            .as_deref()
+            .is_some_and(|value| entry_out_of_range(param, value))
+    }
+
+    /// Show the text field with the parameter's current value selected.
+    pub fn open<P: Param, Renderer: TextRenderer>(&mut self, param: &P, tree: &mut Tree) {
+        self.value = Some(param.to_string());
+
+        let text_input_state = tree
+            .state
+            .downcast_mut::<text_input::State<Renderer::Paragraph>>();
+        text_input_state.select_all();
+        text_input_state.move_cursor_to_end();
+        text_input_state.focus();
+    }
+
+    /// Report the text field to an [`Operation`] while it's visible, so the
+    /// [`keymap`][crate::keymap] knows to leave the keyboard alone while a value is being typed
+    /// in.
+    pub fn operate<Renderer: TextRenderer>(
+        &self,
+        tree: &mut Tree,
+        bounds: Rectangle,
+        operation: &mut dyn Operation,
+    ) {
+        if !self.is_open() {
+            return;
+        }
+
+        let text_input_state = tree
+            .state
+            .downcast_mut::<text_input::State<Renderer::Paragraph>>();
+        operation.text_input(Some(&self.id), bounds, text_input_state);
+        operation.focusable(Some(&self.id), bounds, text_input_state);
+    }
+
+    /// Draw the text field centered horizontally within `bounds`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn draw<Theme, Renderer>(
+        &self,
+        tree: &Tree,
+        renderer: &mut Renderer,
+        theme: &Theme,
+        bounds: Rectangle,
+        font: Option<Font>,
+        text_size: Option<Pixels>,
+        cursor: mouse::Cursor,
+        viewport: &Rectangle,
+    ) where
+        Theme: text_input::Catalog,
+        Renderer: TextRenderer,
+        Renderer::Font: From<crate::Font>,
+    {
+        let Some(current_value) = &self.value else {
+            return;
+        };
+
+        self.with_text_input(
+            bounds,
+            font,
+            text_size,
+            renderer,
+            current_value,
+            |text_input, layout, renderer| {
+                text_input.draw(tree, renderer, theme, layout, cursor, None, viewport);
+            },
+        );
+    }
+
+    /// Forward an event to the text field. Submitting a value sets the parameter and closes the
+    /// field, and unfocusing the field closes it without changing the parameter.
+    #[allow(clippy::too_many_arguments)]
+    pub fn update<P, Theme, Renderer>(
+        &mut self,
+        param: &P,
+        tree: &mut Tree,
+        event: &Event,
+        bounds: Rectangle,
+        font: Option<Font>,
+        text_size: Option<Pixels>,
+        cursor: mouse::Cursor,
+        renderer: &Renderer,
+        clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, ParamMessage>,
+        viewport: &Rectangle,
+    ) where
+        P: Param,
+        Theme: text_input::Catalog,
+        Renderer: TextRenderer,
+        Renderer::Font: From<crate::Font>,
+    {
+        let Some(current_value) = &self.value else {
+            return;
+        };
+        if interaction::is_input_locked() {
+            self.value = None;
+            shell.request_redraw();
+            return;
+        }
+
+        // FIMXE: This is super hacky, I have no idea how you can reuse the text input widget
+        //        otherwise. Widgets are not supposed to handle messages from other widgets, but
+        //        we'll do so anyways by using a special `TextInputMessage` type and our own
+        //        `Shell`.
+        let mut messages = Vec::new();
+        let mut text_input_shell = Shell::new(&mut messages);
+
+        self.with_text_input(
+            bounds,
+            font,
+            text_size,
+            renderer,
+            current_value,
+            |mut text_input: TextInput<TextInputMessage, Theme, Renderer>, layout, renderer| {
+                text_input.update(
+                    tree,
+                    event,
+                    layout,
+                    cursor,
+                    renderer,
+                    clipboard,
+                    &mut text_input_shell,
+                    viewport,
+                )
+            },
+        );
+
+        // Pressing escape will unfocus the text field, so we should propagate that change in
+        // our own model
+        let text_input_state = tree
+            .state
+            .downcast_ref::<text_input::State<Renderer::Paragraph>>();
+        if !text_input_state.is_focused() {
+            self.value = None;
+            return;
+        }
+
+        for message in messages {
+            match message {
+                TextInputMessage::Value(s) => self.value = Some(s),
+                TextInputMessage::Submit => {
+                    if let Some(normalized_value) = self
+                        .value
+                        .as_ref()
+                        .and_then(|s| entry_to_normalized_value(param, s))
+                    {
+                        shell.publish(ParamMessage::BeginSetParameter(param.as_ptr()));
+                        set_normalized_value(param, shell, normalized_value);
+                        shell.publish(ParamMessage::EndSetParameter(param.as_ptr()));
+                    }
+
+                    // And defocus the text input widget again
+                    self.value = None;
+                }
+            }
+        }
+    }
+
+    /// Create a temporary [`TextInput`] centered within `bounds` and outputting
+    /// [`TextInputMessage`] messages and do something with it.
+    fn with_text_input<T, Theme, Renderer, BorrowedRenderer, F>(
+        &self,
+        bounds: Rectangle,
+        font: Option<Font>,
+        text_size: Option<Pixels>,
+        renderer: BorrowedRenderer,
+        current_value: &str,
+        f: F,
+    ) -> T
+    where
+        F: FnOnce(TextInput<'_, TextInputMessage, Theme, Renderer>, Layout, BorrowedRenderer) -> T,
+        Theme: text_input::Catalog,
+        Renderer: TextRenderer,
+        Renderer::Font: From<crate::Font>,
+        BorrowedRenderer: Borrow<Renderer>,
+    {
+        let font = font
+            .map(Renderer::Font::from)
+            .unwrap_or_else(|| renderer.borrow().default_font());
+
+        let text_size = text_size.unwrap_or_else(|| renderer.borrow().default_size());
+        let text_width = Renderer::Paragraph::with_text(Text {
+            content: current_value,
+            bounds: bounds.size(),
+            size: text_size,
+            font,
+            line_height: Default::default(),
+            align_x: alignment::Horizontal::Center.into(),
+            align_y: alignment::Vertical::Center,
+            shaping: Default::default(),
+            wrapping: Default::default(),
+        })
+        .min_width();
+
+        let text_input = text_input("", current_value)
+            .id(self.id.clone())
+            .font(font)
+            .size(text_size)
+            .width(text_width)
+            .on_input(TextInputMessage::Value)
+            .on_submit(TextInputMessage::Submit);
+
+        // Center the text within the bounds
+        let offset_node = layout::Node::with_children(
+            Size {
+                width: text_width,
+                height: bounds.height,
+            },
+            vec![layout::Node::new(bounds.size())],
+        );
+        let offset_layout = Layout::with_offset(
+            Vector {
+                x: bounds.center_x() - (text_width / 2.0),
+                y: bounds.y,
+            },
+            &offset_node,
+        );
+
+        f(text_input, offset_layout, renderer)
+    }
+}
+
+/// Parse a typed-in value. This accepts anything the parameter itself can parse, as well as the
+/// expressions described in the [`expression`] module. Expressions work on the displayed values,
+/// and their results are passed through the parameter's own string conversion. Values outside of
+/// the parameter's range are clamped.
+fn entry_to_normalized_value<P: Param>(param: &P, input: &str) -> Option<f32> {
+    let evaluated = expression::evaluate(input, param.unit(), displayed_value(param))
+        .and_then(|value| param.string_to_normalized_value(&value.to_string()));
+
+    // A relative value like `+3` would otherwise be parsed as an absolute value
+    if expression::is_relative(input) {
+        evaluated
+    } else {
+        evaluated.or_else(|| param.string_to_normalized_value(input))
+    }
+}
+
+/// Whether a typed-in value is outside of the parameter's range, and will thus be clamped.
+fn entry_out_of_range<P: Param>(param: &P, input: &str) -> bool {
+    let Some(value) = expression::evaluate(input, param.unit(), displayed_value(param)) else {
+        return false;
+    };
+    let (Some(start), Some(end)) = (
+        displayed_value_at(param, 0.0),
+        displayed_value_at(param, 1.0),
+    ) else {
+        return false;
+    };
+
+    value < start.min(end) || value > start.max(end)
+}
+
+/// The parameter's current value as it's displayed, used for relative value entry.
+fn displayed_value<P: Param>(param: &P) -> Option<f32> {
+    displayed_value_at(param, param.unmodulated_normalized_value())
+}
+
+/// The displayed value at a normalized value, if it can be represented as a number.
+fn displayed_value_at<P: Param>(param: &P, normalized_value: f32) -> Option<f32> {
+    let string = param.normalized_value_to_string(normalized_value, false);
+    expression::evaluate(&string, param.unit(), None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nih_plug::prelude::{FloatParam, FloatRange, IntParam, IntRange};
+
+    const BOUNDS: Rectangle = Rectangle {
+        x: 0.0,
+        y: 0.0,
+        width: 100.0,
+        height: 20.0,
+    };
+
+    fn press() -> Event {
+        Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left))
+    }
+
+    fn release() -> Event {
+        Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left))
+    }
+
+    fn moved(position: Point) -> Event {
+        Event::Mouse(mouse::Event::CursorMoved { position })
+    }
+
+    fn modifiers(modifiers: keyboard::Modifiers) -> Event {
+        Event::Keyboard(keyboard::Event::ModifiersChanged(modifiers))
+    }
+
+    /// The values set by the messages, and the number of begin and end messages.
+    fn summarize(messages: &[ParamMessage]) -> (Vec<f32>, usize, usize) {
+        let mut values = Vec::new();
+        let (mut begins, mut ends) = (0, 0);
+        for message in messages {
+            match message {
+                ParamMessage::BeginSetParameter(_) => begins += 1,
+                ParamMessage::SetParameterNormalized(_, value) => values.push(*value),
+                ParamMessage::EndSetParameter(_) => ends += 1,
+            }
+        }
+
+        (values, begins, ends)
+    }
+
+    /// Feed events to a [`ParamDrag`], each with its own cursor position.
+    fn run<P: Param>(
+        drag: &mut ParamDrag,
+        param: &P,
+        behavior: DragBehavior,
+        events: &[(Event, Point)],
+    ) -> (Vec<ParamMessage>, bool) {
+        let mut messages = Vec::new();
+        let mut shell = Shell::new(&mut messages);
+        let mut open_text_entry = false;
+        for (event, position) in events {
+            open_text_entry |= drag.update(
+                param,
+                DisplayMapping::new(),
+                behavior,
+                event,
+                BOUNDS,
+                mouse::Cursor::Available(*position),
+                &mut shell,
+            );
+        }
+
+        (messages, open_text_entry)
+    }
+
+    #[test]
+    fn absolute_and_granular_drags() {
+        let param = FloatParam::new("Gain", 0.5, FloatRange::Linear { min: 0.0, max: 1.0 });
+        let mut drag = ParamDrag::default();
+
+        let (messages, open_text_entry) = run(
+            &mut drag,
+            &param,
+            DragBehavior::Absolute,
+            &[
+                (press(), Point::new(20.0, 10.0)),
+                (moved(Point::new(80.0, 10.0)), Point::new(80.0, 10.0)),
+                (release(), Point::new(80.0, 10.0)),
+            ],
+        );
+        let (values, begins, ends) = summarize(&messages);
+        assert!(!open_text_entry);
+        assert_eq!((begins, ends), (1, 1));
+        assert_eq!(values.len(), 2);
+        assert!((values[0] - 0.2).abs() < 1e-4);
+        assert!((values[1] - 0.8).abs() < 1e-4);
+
+        // Shift-dragging doesn't jump to the cursor, and moves ten times slower. This uses a new
+        // state so the click doesn't count as a double click.
+        let mut drag = ParamDrag::default();
+        let (messages, _) = run(
+            &mut drag,
+            &param,
+            DragBehavior::Absolute,
+            &[
+                (
+                    modifiers(keyboard::Modifiers::SHIFT),
+                    Point::new(20.0, 10.0),
+                ),
+                (press(), Point::new(20.0, 10.0)),
+                (moved(Point::new(70.0, 10.0)), Point::new(70.0, 10.0)),
+                (release(), Point::new(70.0, 10.0)),
+            ],
+        );
+        let (values, begins, ends) = summarize(&messages);
+        assert_eq!((begins, ends), (1, 1));
+        assert_eq!(values.len(), 1);
+        assert!((values[0] - 0.55).abs() < 1e-4);
+    }
+
+    #[test]
+    fn widgets_start_with_the_held_modifiers() {
+        let param = FloatParam::new("Gain", 0.5, FloatRange::Linear { min: 0.0, max: 1.0 });
+
+        // Shift was pressed before the widget was created, so it never gets an event for that
+        interaction::set_modifiers(keyboard::Modifiers::SHIFT);
+        let mut drag = ParamDrag::default();
+        interaction::set_modifiers(keyboard::Modifiers::empty());
+        assert!(drag.keyboard_modifiers().shift());
+
+        let (messages, _) = run(
+            &mut drag,
+            &param,
+            DragBehavior::Absolute,
+            &[
+                (press(), Point::new(20.0, 10.0)),
+                (moved(Point::new(70.0, 10.0)), Point::new(70.0, 10.0)),
+                (release(), Point::new(70.0, 10.0)),
+            ],
+        );
+        let (values, _, _) = summarize(&messages);
+        assert_eq!(values.len(), 1);
+        assert!((values[0] - 0.55).abs() < 1e-4);
+    }
+
+    #[test]
+    fn relative_drags_reanchor() {
+        let param = FloatParam::new("Gain", 0.5, FloatRange::Linear { min: 0.0, max: 1.0 });
+        let mut drag = ParamDrag::default();
+
+        // Clicking doesn't change the value, and dragging up by 25 out of 100 pixels moves it by
+        // a quarter of the range. Pressing and releasing shift during the drag continues from the
+        // current value instead of jumping to where the other kind of drag would have been.
+        let (messages, _) = run(
+            &mut drag,
+            &param,
+            DragBehavior::Vertical(100.0),
+            &[
+                (press(), Point::new(50.0, 10.0)),
+                (moved(Point::new(50.0, -15.0)), Point::new(50.0, -15.0)),
+                (
+                    modifiers(keyboard::Modifiers::SHIFT),
+                    Point::new(50.0, -15.0),
+                ),
+                (moved(Point::new(50.0, -25.0)), Point::new(50.0, -25.0)),
+                (
+                    modifiers(keyboard::Modifiers::empty()),
+                    Point::new(50.0, -25.0),
+                ),
+                (moved(Point::new(50.0, -35.0)), Point::new(50.0, -35.0)),
+                (release(), Point::new(50.0, -35.0)),
+            ],
+        );
+        let (values, begins, ends) = summarize(&messages);
+        assert_eq!((begins, ends), (1, 1));
+        assert_eq!(values.len(), 3);
+        assert!((values[0] - 0.75).abs() < 1e-4);
+        // The parameter's value is never actually updated here, so the re-anchored drags start at
+        // the default value again
+        assert!((values[1] - 0.51).abs() < 1e-4);
+        assert!((values[2] - 0.6).abs() < 1e-4);
+    }
+
+    #[test]
+    fn fine_adjust_modifiers() {
+        let param = FloatParam::new("Gain", 0.5, FloatRange::Linear { min: 0.0, max: 1.0 });
+        let fine_adjust = keyboard::Modifiers::SHIFT | keyboard::Modifiers::COMMAND;
+        interaction::set_current(crate::InteractionSettings {
+            fine_adjust,
+            ..Default::default()
+        });
+        let at = |y: f32| Point::new(50.0, y);
+
+        // Holding another fine adjustment modifier mid-drag doesn't re-anchor the drag
+        let mut drag = ParamDrag::default();
+        let (messages, _) = run(
+            &mut drag,
+            &param,
+            DragBehavior::Vertical(100.0),
+            &[
+                (modifiers(keyboard::Modifiers::SHIFT), at(10.0)),
+                (press(), at(10.0)),
+                (moved(at(-40.0)), at(-40.0)),
+                (modifiers(fine_adjust), at(-40.0)),
+                (moved(at(-90.0)), at(-90.0)),
+                (release(), at(-90.0)),
+            ],
+        );
+        let (values, begins, ends) = summarize(&messages);
+        assert_eq!((begins, ends), (1, 1));
+        assert_eq!(values.len(), 2);
+        assert!((values[0] - 0.55).abs() < 1e-4);
+        assert!((values[1] - 0.6).abs() < 1e-4);
+
+        // Ctrl/Cmd+clicking only resets the parameter once the button is released without moving,
+        // while dragging with it is granular from where the cursor left the click
+        let mut drag = ParamDrag::default();
+        let (messages, _) = run(
+            &mut drag,
+            &param,
+            DragBehavior::Vertical(100.0),
+            &[
+                (modifiers(keyboard::Modifiers::COMMAND), at(10.0)),
+                (press(), at(10.0)),
+                (moved(at(7.0)), at(7.0)),
+            ],
+        );
+        assert_eq!(summarize(&messages), (vec![], 1, 0));
+        assert!(drag.is_dragging());
+        let (messages, _) = run(
+            &mut drag,
+            &param,
+            DragBehavior::Vertical(100.0),
+            &[(release(), at(7.0))],
+        );
+        assert_eq!(summarize(&messages), (vec![], 0, 1));
+
+        let mut drag = ParamDrag::default();
+        let (messages, _) = run(
+            &mut drag,
+            &param,
+            DragBehavior::Vertical(100.0),
+            &[
+                (modifiers(keyboard::Modifiers::COMMAND), at(10.0)),
+                (press(), at(10.0)),
+                (moved(at(0.0)), at(0.0)),
+                (moved(at(-50.0)), at(-50.0)),
+                (release(), at(-50.0)),
+            ],
+        );
+        let (values, begins, ends) = summarize(&messages);
+        assert_eq!((begins, ends), (1, 1));
+        assert_eq!(values.len(), 1);
+        assert!((values[0] - 0.55).abs() < 1e-4);
+
+        // Without Ctrl/Cmd in the set, clicking with it resets the parameter right away
+        interaction::set_current(crate::InteractionSettings {
+            fine_adjust: keyboard::Modifiers::SHIFT,
+            ..Default::default()
+        });
+        let mut drag = ParamDrag::default();
+        let (messages, _) = run(
+            &mut drag,
+            &param,
+            DragBehavior::Vertical(100.0),
+            &[
+                (modifiers(keyboard::Modifiers::COMMAND), at(10.0)),
+                (press(), at(10.0)),
+            ],
+        );
+        assert_eq!(summarize(&messages), (vec![], 1, 1));
+        assert!(!drag.is_dragging());
+
+        interaction::set_current(Default::default());
+    }
+
+    #[test]
+    fn input_lock_ends_gestures() {
+        let param = FloatParam::new("Gain", 0.5, FloatRange::Linear { min: 0.0, max: 1.0 });
+        let mut drag = ParamDrag::default();
+
+        let (messages, _) = run(
+            &mut drag,
+            &param,
+            DragBehavior::Absolute,
+            &[(press(), Point::new(50.0, 10.0))],
+        );
+        assert_eq!(summarize(&messages), (vec![], 1, 0));
+        assert!(drag.is_dragging());
+
+        // Locking the editor mid-drag ends the gesture on the next event, and new presses are
+        // ignored
+        interaction::set_input_locked(true);
+        let (messages, _) = run(
+            &mut drag,
+            &param,
+            DragBehavior::Absolute,
+            &[
+                (
+                    modifiers(keyboard::Modifiers::empty()),
+                    Point::new(50.0, 10.0),
+                ),
+                (moved(Point::new(80.0, 10.0)), Point::new(80.0, 10.0)),
+                (press(), Point::new(80.0, 10.0)),
+                (release(), Point::new(80.0, 10.0)),
+            ],
+        );
+        assert_eq!(summarize(&messages), (vec![], 0, 1));
+        assert!(!drag.is_dragging());
+
+        interaction::set_input_locked(false);
+        let (messages, _) = run(
+            &mut drag,
+            &param,
+            DragBehavior::Absolute,
+            &[(press(), Point::new(80.0, 10.0))],
+        );
+        assert_eq!(summarize(&messages), (vec![0.8], 1, 0));
+    }
+
+    #[test]
+    fn resets_and_text_entry() {
+        let param = FloatParam::new("Gain", 0.25, FloatRange::Linear { min: 0.0, max: 1.0 });
+        let mut drag = ParamDrag::default();
+
+        // Alt+click opens the text entry without starting a gesture
+        let (messages, open_text_entry) = run(
+            &mut drag,
+            &param,
+            DragBehavior::Absolute,
+            &[
+                (modifiers(keyboard::Modifiers::ALT), Point::new(50.0, 10.0)),
+                (press(), Point::new(50.0, 10.0)),
+                (release(), Point::new(50.0, 10.0)),
+                (
+                    modifiers(keyboard::Modifiers::empty()),
+                    Point::new(50.0, 10.0),
+                ),
+            ],
+        );
+        assert!(open_text_entry);
+        assert!(messages.is_empty());
+        assert!(!drag.is_dragging());
+
+        // The second click of a double click resets the parameter, which is already at its
+        // default value
+        let (messages, open_text_entry) = run(
+            &mut drag,
+            &param,
+            DragBehavior::Absolute,
+            &[
+                (press(), Point::new(50.0, 10.0)),
+                (release(), Point::new(50.0, 10.0)),
+            ],
+        );
+        assert!(!open_text_entry);
+        assert_eq!(summarize(&messages), (vec![], 1, 1));
+
+        // Clicks outside of the widget are ignored
+        let (messages, _) = run(
+            &mut drag,
+            &param,
+            DragBehavior::Absolute,
+            &[
+                (press(), Point::new(150.0, 10.0)),
+                (release(), Point::new(150.0, 10.0)),
+            ],
+        );
+        assert!(messages.is_empty());
+    }
+
+    #[test]
+    fn scrolling_moves_by_steps() {
+        let param = IntParam::new("Voices", 2, IntRange::Linear { min: 0, max: 4 });
+        let mut drag = ParamDrag::default();
+        let mut messages = Vec::new();
+        let mut shell = Shell::new(&mut messages);
+        let scroll = |y| {
+            Event::Mouse(mouse::Event::WheelScrolled {
+                delta: mouse::ScrollDelta::Lines { x: 0.0, y },
+            })
+        };
+
+        let cursor = mouse::Cursor::Available(Point::new(50.0, 10.0));
+        drag.scroll(
+            &param,
+            DisplayMapping::new(),
+            None,
+            &scroll(1.0),
+            BOUNDS,
+            cursor,
+            &mut shell,
+        );
+        drag.scroll(
+            &param,
+            DisplayMapping::inverted(),
+            None,
+            &scroll(1.0),
+            BOUNDS,
+            cursor,
+            &mut shell,
+        );
+        assert!(shell.is_event_captured());
+
+        // Scrolling outside of the widget doesn't do anything
+        drag.scroll(
+            &param,
+            DisplayMapping::new(),
+            None,
+            &scroll(1.0),
+            BOUNDS,
+            mouse::Cursor::Available(Point::new(150.0, 10.0)),
+            &mut shell,
+        );
+
+        let (values, begins, ends) = summarize(&messages);
+        assert_eq!((begins, ends), (2, 2));
+        assert_eq!(values, vec![0.75, 0.25]);
+    }
+
+    #[test]
+    fn slow_scrolling_accumulates() {
+        let param = IntParam::new("Voices", 2, IntRange::Linear { min: 0, max: 4 });
+        let mut drag = ParamDrag::default();
+        let cursor = mouse::Cursor::Available(Point::new(50.0, 10.0));
+        let mut scroll = |delta| {
+            let mut messages = Vec::new();
+            let mut shell = Shell::new(&mut messages);
+            drag.scroll(
+                &param,
+                DisplayMapping::new(),
+                None,
+                &Event::Mouse(mouse::Event::WheelScrolled { delta }),
+                BOUNDS,
+                cursor,
+                &mut shell,
+            );
+            let is_captured = shell.is_event_captured();
+
+            (summarize(&messages).0, is_captured)
+        };
+        let lines = |y| mouse::ScrollDelta::Lines { x: 0.0, y };
+
+        // A high resolution wheel reports fractions of a line. The widget keeps those until they
+        // add up to a whole step, and still captures them so nothing else scrolls in the meantime.
+        assert_eq!(scroll(lines(0.3)), (vec![], true));
+        assert_eq!(scroll(lines(0.3)), (vec![], true));
+        assert_eq!(scroll(lines(0.3)), (vec![], true));
+        assert_eq!(scroll(lines(0.3)), (vec![0.75], true));
+        // Reversing the direction discards the leftover 0.2 instead of scrolling it back first
+        assert_eq!(scroll(lines(-0.6)), (vec![], true));
+        assert_eq!(scroll(lines(-0.6)), (vec![0.25], true));
+
+        // Touchpads are counted in pixels, and both settings can be inverted
+        interaction::set_current(crate::InteractionSettings {
+            wheel: crate::WheelSettings {
+                invert: true,
+                lines_per_step: 3.0,
+                pixels_per_step: 40.0,
+            },
+            ..Default::default()
+        });
+        assert_eq!(scroll(lines(2.0)), (vec![], true));
+        assert_eq!(scroll(lines(2.0)), (vec![0.25], true));
+        let pixels = |y| mouse::ScrollDelta::Pixels { x: 0.0, y };
+        assert_eq!(scroll(pixels(-30.0)), (vec![], true));
+        assert_eq!(scroll(pixels(-15.0)), (vec![0.75], true));
+
+        interaction::set_current(Default::default());
+    }
+
+    #[test]
+    fn guarded_scrolling() {
+        let param = IntParam::new("Voices", 2, IntRange::Linear { min: 0, max: 4 });
+        let mut drag = ParamDrag::default();
+        let inside = Point::new(50.0, 10.0);
+        let scroll = Event::Mouse(mouse::Event::WheelScrolled {
+            delta: mouse::ScrollDelta::Lines { x: 0.0, y: 1.0 },
+        });
+        let scroll_guarded = |drag: &mut ParamDrag| {
+            let mut messages = Vec::new();
+            let mut shell = Shell::new(&mut messages);
+            drag.scroll(
+                &param,
+                DisplayMapping::new(),
+                Some(ScrollToAdjust::Guarded),
+                &scroll,
+                BOUNDS,
+                mouse::Cursor::Available(inside),
+                &mut shell,
+            );
+            let is_captured = shell.is_event_captured();
+
+            (summarize(&messages).0, is_captured)
+        };
+
+        // An unfocused widget leaves the scroll to the scrollable underneath it
+        assert_eq!(scroll_guarded(&mut drag), (vec![], false));
+
+        // Holding Ctrl/Cmd or clicking the widget first allows scrolling
+        let _ = run(
+            &mut drag,
+            &param,
+            DragBehavior::Vertical(100.0),
+            &[(modifiers(keyboard::Modifiers::COMMAND), inside)],
+        );
+        assert_eq!(scroll_guarded(&mut drag), (vec![0.75], true));
+        let _ = run(
+            &mut drag,
+            &param,
+            DragBehavior::Vertical(100.0),
+            &[
+                (modifiers(keyboard::Modifiers::empty()), inside),
+                (press(), inside),
+                (release(), inside),
+            ],
+        );
+        assert!(drag.is_focused());
+        assert!(scroll_guarded(&mut drag).1);
+
+        // Clicking somewhere else removes the focus again
+        let _ = run(
+            &mut drag,
+            &param,
+            DragBehavior::Vertical(100.0),
+            &[(press(), Point::new(150.0, 10.0))],
+        );
+        assert_eq!(scroll_guarded(&mut drag), (vec![], false));
+    }
+}
@@ -0,0 +1,438 @@
+//! A [`Dropdown`] for picking one of a stepped parameter's values, most useful for [`EnumParam`]s.
+//!
+//! [`EnumParam`]: nih_plug::prelude::EnumParam
+
+use crate::core::text::Renderer as TextRenderer;
+use crate::core::widget::operation::Operation;
+use crate::core::widget::tree::{self, Tree};
+use crate::core::widget::Id;
+use crate::core::{
+    layout, mouse, overlay, renderer, Clipboard, Element, Event, Font, Layout, Length, Padding,
+    Pixels, Rectangle, Shell, Size, Vector, Widget,
+};
+use crate::interaction;
+
+use super::dropdown::{self, Catalog, Dropdown};
+use super::options::{ParamOption, ParamOptions};
+use super::param_bounds::{ParamBoundsMap, ReportedBounds};
+use super::param_drag::set_normalized_value;
+use super::param_id::param_widget_id;
+use super::ParamMessage;
+
+/// A dropdown listing all of a parameter's values. Picking one sets the parameter in a single
+/// automation gesture. This works for any parameter with [`ParamOptions`], so [`EnumParam`]s list
+/// their variant names and other stepped parameters list every step as a string.
+///
+/// The list is drawn on iced's overlay layer and always stays within the editor's window. It opens
+/// below the dropdown when there's room for it, above it when there's more room there, and it
+/// becomes scrollable when neither side has enough room. Styling and keyboard navigation work the
+/// same as for a regular [`Dropdown`].
+///
+/// [`EnumParam`]: nih_plug::prelude::EnumParam
+pub struct ParamDropdown<'a, P: ParamOptions, Theme = crate::Theme>
+where
+    Theme: Catalog,
+{
+    param: &'a P,
+    id: Id,
+    dropdown: Dropdown<'a, ParamOption, ParamMessage, Theme>,
+    bounds_map: Option<ParamBoundsMap>,
+}
+
+/// State for a [`ParamDropdown`]. The [`Dropdown`]'s own state is stored in the tree's only child.
+#[derive(Debug, Default)]
+struct State {
+    /// This widget's entry in the [`ParamBoundsMap`], if it reports its bounds.
+    bounds: ReportedBounds,
+}
+
+impl<'a, P, Theme> ParamDropdown<'a, P, Theme>
+where
+    P: ParamOptions,
+    Theme: Catalog,
+{
+    /// Creates a new [`ParamDropdown`] for the given parameter.
+    pub fn new(param: &'a P) -> Self {
+        let id = param_widget_id(param.as_ptr());
+        let dropdown = Dropdown::with_publisher(
+            param.options(),
+            param.selected_option(),
+            move |option: ParamOption, shell| {
+                shell.publish(ParamMessage::BeginSetParameter(param.as_ptr()));
+                set_normalized_value(param, shell, option.normalized_value);
+                shell.publish(ParamMessage::EndSetParameter(param.as_ptr()));
+            },
+        )
+        .id(id.clone());
+
+        Self {
+            param,
+            id,
+            dropdown,
+            bounds_map: None,
+        }
+    }
+
+    /// Sets the width of the [`ParamDropdown`].
+    pub fn width(mut self, width: Length) -> Self {
+        self.dropdown = self.dropdown.width(width);
+        self
+    }
+
+    /// Sets the padding of the button and of every option in the list.
+    pub fn padding(mut self, padding: impl Into<Padding>) -> Self {
+        self.dropdown = self.dropdown.padding(padding);
+        self
+    }
+
+    /// Sets the maximum height of the opened list. Longer lists can be scrolled.
+    pub fn max_menu_height(mut self, height: f32) -> Self {
+        self.dropdown = self.dropdown.max_menu_height(height);
+        self
+    }
+
+    /// Sets the text size of the [`ParamDropdown`].
+    pub fn text_size(mut self, size: Pixels) -> Self {
+        self.dropdown = self.dropdown.text_size(size);
+        self
+    }
+
+    /// Sets the font of the [`ParamDropdown`].
+    pub fn font(mut self, font: Font) -> Self {
+        self.dropdown = self.dropdown.font(font);
+        self
+    }
+
+    /// Sets the [`Id`] of the [`ParamDropdown`]. By default this is derived from the parameter,
+    /// see the [`param_id`][super::param_id] module.
+    pub fn id(mut self, id: impl Into<Id>) -> Self {
+        self.id = id.into();
+        self.dropdown = self.dropdown.id(self.id.clone());
+        self
+    }
+
+    /// Write this widget's bounds to the editor's [`ParamBoundsMap`] every time it's drawn, so the
+    /// editor can highlight it. See the [`param_bounds`][super::param_bounds] module.
+    pub fn report_bounds(mut self, report: bool) -> Self {
+        self.bounds_map = if report {
+            ParamBoundsMap::current()
+        } else {
+            None
+        };
+        self
+    }
+
+    /// Sets the style of the [`ParamDropdown`], see [`Dropdown::style()`].
+    pub fn style(mut self, style: impl Fn(&Theme, dropdown::Status) -> dropdown::Style + 'a) -> Self
+    where
+        Theme::Class<'a>: From<dropdown::StyleFn<'a, Theme>>,
+    {
+        self.dropdown = self.dropdown.style(style);
+        self
+    }
+
+    /// Sets the style class of the [`ParamDropdown`].
+    pub fn class(mut self, class: impl Into<Theme::Class<'a>>) -> Self {
+        self.dropdown = self.dropdown.class(class);
+        self
+    }
+}
+
+impl<'a, P, Theme, Renderer> Widget<ParamMessage, Theme, Renderer> for ParamDropdown<'a, P, Theme>
+where
+    P: ParamOptions,
+    Theme: Catalog + 'a,
+    Renderer: TextRenderer + 'a,
+    Renderer::Font: From<crate::Font>,
+{
+    fn tag(&self) -> tree::Tag {
+        tree::Tag::of::<State>()
+    }
+
+    fn state(&self) -> tree::State {
+        tree::State::new(State::default())
+    }
+
+    fn children(&self) -> Vec<Tree> {
+        vec![Tree::new(
+            &self.dropdown as &dyn Widget<ParamMessage, Theme, Renderer>,
+        )]
+    }
+
+    fn diff(&self, tree: &mut Tree) {
+        tree.children[0].diff(&self.dropdown as &dyn Widget<ParamMessage, Theme, Renderer>);
+    }
+
+    fn size(&self) -> Size<Length> {
+        Widget::<ParamMessage, Theme, Renderer>::size(&self.dropdown)
+    }
+
+    fn layout(
+        &mut self,
+        tree: &mut Tree,
+        renderer: &Renderer,
+        limits: &layout::Limits,
+    ) -> layout::Node {
+        let child = &mut tree.children[0];
+        self.dropdown.layout(child, renderer, limits)
+    }
+
+    fn operate(
+        &mut self,
+        tree: &mut Tree,
+        layout: Layout<'_>,
+        renderer: &Renderer,
+        operation: &mut dyn Operation,
+    ) {
+        let child = &mut tree.children[0];
+        self.dropdown.operate(child, layout, renderer, operation);
+    }
+
+    fn draw(
+        &self,
+        tree: &Tree,
+        renderer: &mut Renderer,
+        theme: &Theme,
+        style: &renderer::Style,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        viewport: &Rectangle,
+    ) {
+        let state = tree.state.downcast_ref::<State>();
+        state.bounds.report(
+            self.bounds_map.as_ref(),
+            self.param.as_ptr(),
+            &self.id,
+            layout.bounds(),
+        );
+
+        self.dropdown.draw(
+            &tree.children[0],
+            renderer,
+            theme,
+            style,
+            layout,
+            cursor,
+            viewport,
+        );
+    }
+
+    fn update(
+        &mut self,
+        tree: &mut Tree,
+        event: &Event,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        renderer: &Renderer,
+        clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, ParamMessage>,
+        viewport: &Rectangle,
+    ) {
+        if interaction::is_input_locked() {
+            return;
+        }
+
+        self.dropdown.update(
+            &mut tree.children[0],
+            event,
+            layout,
+            cursor,
+            renderer,
+            clipboard,
+            shell,
+            viewport,
+        );
+    }
+
+    fn mouse_interaction(
+        &self,
+        tree: &Tree,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        viewport: &Rectangle,
+        renderer: &Renderer,
+    ) -> mouse::Interaction {
+        if interaction::is_input_locked() {
+            if cursor.is_over(layout.bounds()) {
+                mouse::Interaction::NotAllowed
+            } else {
+                mouse::Interaction::default()
+            }
+        } else {
+            self.dropdown
+                .mouse_interaction(&tree.children[0], layout, cursor, viewport, renderer)
+        }
+    }
+
+    fn overlay<'b>(
+        &'b mut self,
+        tree: &'b mut Tree,
+        layout: Layout<'b>,
+        renderer: &Renderer,
+        viewport: &Rectangle,
+        translation: Vector,
+    ) -> Option<overlay::Element<'b, ParamMessage, Theme, Renderer>> {
+        // An open list disappears while the editor's input is locked
+        if interaction::is_input_locked() {
+            return None;
+        }
+
+        self.dropdown.overlay(
+            &mut tree.children[0],
+            layout,
+            renderer,
+            viewport,
+            translation,
+        )
+    }
+}
+
+impl<'a, P, Theme> ParamDropdown<'a, P, Theme>
+where
+    P: ParamOptions,
+    Theme: Catalog + 'a,
+{
+    /// Convert this [`ParamDropdown`] into an [`Element`] with the correct message. You should
+    /// have a variant on your own message type that wraps around [`ParamMessage`] so you can
+    /// forward those messages to
+    /// [`IcedEditor::handle_param_message()`][crate::IcedEditor::handle_param_message()].
+    pub fn map<Message, Renderer, F>(self, f: F) -> Element<'a, Message, Theme, Renderer>
+    where
+        Message: 'static,
+        F: Fn(ParamMessage) -> Message + 'static,
+        Renderer: TextRenderer + 'a,
+        Renderer::Font: From<crate::Font>,
+    {
+        Element::from(self).map(f)
+    }
+}
+
+impl<'a, P, Theme, Renderer> From<ParamDropdown<'a, P, Theme>>
+    for Element<'a, ParamMessage, Theme, Renderer>
+where
+    P: ParamOptions,
+    Theme: Catalog + 'a,
+    Renderer: TextRenderer + 'a,
+    Renderer::Font: From<crate::Font>,
+{
+    fn from(widget: ParamDropdown<'a, P, Theme>) -> Self {
+        Element::new(widget)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Point, Theme};
+    use crate::runtime::user_interface::{Cache, UserInterface};
+    use crate::widget::{column, Space};
+    use nih_plug::prelude::{Enum, EnumParam};
+
+    #[derive(Debug, PartialEq)]
+    enum Shape {
+        Sine,
+        Triangle,
+        Saw,
+        Square,
+        Noise,
+    }
+
+    impl Enum for Shape {
+        fn variants() -> &'static [&'static str] {
+            &["Sine", "Triangle", "Saw", "Square", "Noise"]
+        }
+
+        fn ids() -> Option<&'static [&'static str]> {
+            None
+        }
+
+        fn to_index(self) -> usize {
+            self as usize
+        }
+
+        fn from_index(index: usize) -> Self {
+            match index {
+                0 => Shape::Sine,
+                1 => Shape::Triangle,
+                2 => Shape::Saw,
+                3 => Shape::Square,
+                _ => Shape::Noise,
+            }
+        }
+    }
+
+    /// Put a dropdown at the bottom of a window with the given logical size, click it open, and
+    /// then click `option` in the opened list. Returns the normalized values that were set and
+    /// whether every value was wrapped in a gesture.
+    fn pick(param: &EnumParam<Shape>, window: Size, option: Point) -> (Vec<f32>, bool) {
+        // The button is a line of the default text size plus the default padding high
+        let button_height = 16.0 * 1.3 + 8.0;
+        let content = column![
+            Space::new().height(Length::Fixed(window.height - button_height)),
+            ParamDropdown::<_, Theme>::new(param),
+        ];
+        let mut interface = UserInterface::build(
+            Element::<'_, ParamMessage, Theme, ()>::from(content),
+            window,
+            Cache::default(),
+            &mut (),
+        );
+
+        let press = Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left));
+        let mut messages = Vec::new();
+        for cursor in [Point::new(10.0, window.height - 5.0), option] {
+            let _ = interface.update(
+                std::slice::from_ref(&press),
+                mouse::Cursor::Available(cursor),
+                &mut (),
+                &mut crate::core::clipboard::Null,
+                &mut messages,
+            );
+        }
+
+        let values = messages
+            .iter()
+            .filter_map(|message| match message {
+                ParamMessage::SetParameterNormalized(_, value) => Some(*value),
+                _ => None,
+            })
+            .collect();
+        let is_gesture = matches!(
+            messages.as_slice(),
+            [] | [
+                ParamMessage::BeginSetParameter(_),
+                ParamMessage::SetParameterNormalized(..),
+                ParamMessage::EndSetParameter(_),
+            ]
+        );
+
+        (values, is_gesture)
+    }
+
+    #[test]
+    fn list_stays_within_the_window() {
+        let param = EnumParam::new("Shape", Shape::Saw);
+        let row_height = 16.0 * 1.3 + 8.0;
+
+        // There's no room below the button, so the list opens upwards and its first option starts
+        // five rows above the button. Clicking above the list only closes it again.
+        let window = Size::new(200.0, 300.0);
+        let first_row = window.height - row_height * 6.0;
+        assert_eq!(
+            pick(&param, window, Point::new(10.0, first_row + 5.0)),
+            (vec![0.0], true)
+        );
+        assert_eq!(
+            pick(&param, window, Point::new(10.0, first_row - 5.0)),
+            (vec![], true)
+        );
+
+        // In a window that's too short for that the list starts at the top of the window instead,
+        // scrolled to the selected option, so the option after it is on the second row
+        let window = Size::new(200.0, 100.0);
+        assert_eq!(
+            pick(&param, window, Point::new(10.0, row_height + 5.0)),
+            (vec![0.75], true)
+        );
+    }
+}
@@ -0,0 +1,99 @@
+//! Stable widget [`Id`]s for parameter widgets.
+//!
+//! Every parameter widget gets an [`Id`] derived from the parameter it's bound to, so editors don't
+//! need to assign them by hand. Setting an `.id()` on the widget still overrides it. The id is
+//! reported to widget operations as a container, it's stored next to the widget's entry in the
+//! [`ParamBoundsMap`][super::ParamBoundsMap], and it stays the same for as long as the plugin's
+//! parameters exist, so it can also be used as a key for state the editor stores itself.
+//!
+//! When a parameter is shown by multiple widgets, the widgets are numbered in the order they're
+//! created in [`IcedEditor::view()`][crate::IcedEditor::view()]. The first widget gets the plain
+//! id for the parameter, and the later ones get the same id with an occurrence number attached. As
+//! long as the view creates its widgets in the same order, every widget keeps its id. That's also
+//! the order in which the ids are handed out, which makes it the default focus order for the
+//! parameter widgets. For editors built from the [`Params`][nih_plug::prelude::Params] struct, like
+//! [`GenericUi`][super::generic_ui::GenericUi], that's the order the parameters are declared in,
+//! and otherwise it's the order the editor lays them out in. Outside of the editor's view,
+//! duplicates are not numbered, so every widget for a parameter gets the same id.
+
+use nih_plug::prelude::ParamPtr;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+use crate::core::widget::Id;
+
+thread_local! {
+    /// How many widgets have been created for each parameter in the view that's currently being
+    /// built.
+    static OCCURRENCES: RefCell<Option<HashMap<ParamPtr, usize>>> = const { RefCell::new(None) };
+}
+
+/// The default [`Id`] for the next widget that's bound to `param`. See the [module
+/// documentation][self].
+pub fn param_widget_id(param: ParamPtr) -> Id {
+    let occurrence = OCCURRENCES.with(|occurrences| {
+        occurrences.borrow_mut().as_mut().map_or(0, |occurrences| {
+            let count = occurrences.entry(param).or_default();
+            *count += 1;
+            *count - 1
+        })
+    });
+
+    let mut hasher = DefaultHasher::new();
+    param.hash(&mut hasher);
+    let hash = hasher.finish();
+
+    if occurrence == 0 {
+        Id::from(format!("nih_plug_iced::param::{hash:016x}"))
+    } else {
+        Id::from(format!("nih_plug_iced::param::{hash:016x}#{occurrence}"))
+    }
+}
+
+/// Number the widgets for each parameter while `f` builds the editor's view.
+pub(crate) fn with_occurrences<R>(f: impl FnOnce() -> R) -> R {
+    let previous = OCCURRENCES.with(|occurrences| occurrences.replace(Some(HashMap::new())));
+    let result = f();
+    OCCURRENCES.with(|occurrences| *occurrences.borrow_mut() = previous);
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use nih_plug::prelude::{FloatParam, FloatRange, Param};
+
+    use super::*;
+
+    #[test]
+    fn duplicates_are_numbered_in_view_order() {
+        let param = |name| FloatParam::new(name, 0.5, FloatRange::Linear { min: 0.0, max: 1.0 });
+        let (gain, cutoff) = (param("Gain"), param("Cutoff"));
+        let (gain, cutoff) = (gain.as_ptr(), cutoff.as_ptr());
+
+        let view = || {
+            with_occurrences(|| {
+                [
+                    param_widget_id(gain),
+                    param_widget_id(cutoff),
+                    param_widget_id(gain),
+                    param_widget_id(gain),
+                ]
+            })
+        };
+        let ids = view();
+        assert_ne!(ids[0], ids[1]);
+        assert_ne!(ids[0], ids[2]);
+        assert_ne!(ids[2], ids[3]);
+
+        // Rebuilding the view hands out the same ids
+        assert_eq!(view(), ids);
+
+        // The first widget for a parameter has the same id inside and outside of the view, where
+        // duplicates aren't told apart
+        assert_eq!(param_widget_id(gain), ids[0]);
+        assert_eq!(param_widget_id(gain), ids[0]);
+        assert_eq!(param_widget_id(cutoff), ids[1]);
+    }
+}
@@ -0,0 +1,912 @@
+//! A rotary knob for a parameter.
+
+use nih_plug::prelude::Param;
+use std::sync::Arc;
+
+use crate::comm::ModulationRange;
+#[cfg(feature = "image")]
+use crate::core::image;
+use crate::core::text::Renderer as TextRenderer;
+use crate::core::widget::tree::{self, Tree};
+use crate::core::widget::{Id, Operation};
+use crate::core::{
+    layout, mouse, renderer, Clipboard, Color, Degrees, Element, Event, Font, Layout, Length,
+    Pixels, Point, Radians, Rectangle, Shell, Size, Theme, Widget,
+};
+use crate::interaction::{self, ScrollToAdjust};
+use crate::widget::text_input;
+
+use super::mapping::DisplayMapping;
+use super::param_bounds::{ParamBoundsMap, ReportedBounds};
+use super::param_drag::{DragBehavior, ParamDrag, TextEntry};
+use super::param_id::param_widget_id;
+use super::range::ParamRange;
+use super::{util, ParamMessage};
+
+/// A knob that shows a parameter's value as an arc filling up a track, with a line pointing at the
+/// value. How the knob is dragged is set with [`ParamKnob::drag_mode()`]. Like the other parameter
+/// widgets, it can also be fine-adjusted, scrolled, reset, and typed into, and every drag is sent
+/// to the host as a single automation gesture. See the [`param_drag`][super::param_drag] module.
+///
+/// With the `image` feature, the knob can also be drawn from a film strip image instead, see
+/// [`ParamKnob::with_image_strip()`].
+pub struct ParamKnob<'a, P: Param, Theme = crate::Theme>
+where
+    Theme: Catalog,
+{
+    param: &'a P,
+    id: Id,
+
+    width: Length,
+    height: Length,
+    thickness: f32,
+    indicator_width: f32,
+    start_angle: Radians,
+    sweep: Radians,
+    drag_mode: DragMode,
+    text_size: Option<Pixels>,
+    font: Option<Font>,
+    mapping: DisplayMapping,
+    bounds_map: Option<ParamBoundsMap>,
+    modulation_range: Option<Arc<ModulationRange>>,
+    scroll_to_adjust: Option<ScrollToAdjust>,
+    #[cfg(feature = "image")]
+    film_strip: Option<FilmStrip>,
+    class: Theme::Class<'a>,
+}
+
+/// How dragging a [`ParamKnob`] changes its value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DragMode {
+    /// Dragging upwards increases the value. Dragging by [`ParamKnob::DRAG_DISTANCE`] logical
+    /// pixels sweeps the entire range.
+    #[default]
+    Vertical,
+    /// Dragging to the right increases the value. Dragging by [`ParamKnob::DRAG_DISTANCE`]
+    /// logical pixels sweeps the entire range.
+    Horizontal,
+    /// The knob turns to point at the cursor. Clicking the knob sets the value it points at.
+    Circular,
+}
+
+/// A film strip of knob images, see [`ParamKnob::with_image_strip()`].
+#[cfg(feature = "image")]
+#[derive(Debug, Clone)]
+pub struct FilmStrip {
+    handle: image::Handle,
+    frame_count: usize,
+    frame_size: Size,
+}
+
+/// State for a [`ParamKnob`].
+#[derive(Debug, Default)]
+struct State {
+    /// Dragging, clicking, scrolling, and resetting the parameter.
+    drag: ParamDrag,
+    /// The text field for typing in a value. This stores its state in the widget's only child.
+    text_entry: TextEntry,
+    /// This widget's entry in the [`ParamBoundsMap`], if it reports its bounds.
+    bounds: ReportedBounds,
+}
+
+impl<'a, P, Theme> ParamKnob<'a, P, Theme>
+where
+    P: Param,
+    Theme: Catalog,
+{
+    pub const DEFAULT_WIDTH: Length = Length::Fixed(48.0);
+    pub const DEFAULT_HEIGHT: Length = Length::Fixed(48.0);
+    pub const DEFAULT_THICKNESS: f32 = 4.0;
+    pub const DEFAULT_INDICATOR_WIDTH: f32 = 2.0;
+    /// The knob's range starts at the bottom left by default.
+    pub const DEFAULT_START_ANGLE: Degrees = Degrees(-135.0);
+    /// The knob's range sweeps clockwise to the bottom right by default.
+    pub const DEFAULT_SWEEP: Degrees = Degrees(270.0);
+    /// How far the knob needs to be dragged to sweep the parameter's entire range with
+    /// [`DragMode::Vertical`] and [`DragMode::Horizontal`].
+    pub const DRAG_DISTANCE: f32 = 200.0;
+    /// Where the indicator line starts, as a fraction of the distance from the center to the
+    /// inside of the arc.
+    const INDICATOR_START: f32 = 0.35;
+
+    /// Creates a new [`ParamKnob`] for the given parameter.
+    pub fn new(param: &'a P) -> Self {
+        Self {
+            param,
+            id: param_widget_id(param.as_ptr()),
+
+            width: Self::DEFAULT_WIDTH,
+            height: Self::DEFAULT_HEIGHT,
+            thickness: Self::DEFAULT_THICKNESS,
+            indicator_width: Self::DEFAULT_INDICATOR_WIDTH,
+            start_angle: Self::DEFAULT_START_ANGLE.into(),
+            sweep: Self::DEFAULT_SWEEP.into(),
+            drag_mode: DragMode::default(),
+            text_size: None,
+            font: None,
+            mapping: DisplayMapping::new(),
+            bounds_map: None,
+            modulation_range: None,
+            scroll_to_adjust: None,
+            #[cfg(feature = "image")]
+            film_strip: None,
+            class: Theme::default(),
+        }
+    }
+
+    /// Sets the width of the [`ParamKnob`]. The knob is as large as the smaller of the width and
+    /// the height.
+    pub fn width(mut self, width: Length) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// Sets the height of the [`ParamKnob`]. The knob is as large as the smaller of the width and
+    /// the height.
+    pub fn height(mut self, height: Length) -> Self {
+        self.height = height;
+        self
+    }
+
+    /// Sets the thickness of the knob's arc in logical pixels.
+    pub fn thickness(mut self, thickness: f32) -> Self {
+        self.thickness = thickness.max(0.0);
+        self
+    }
+
+    /// Sets the width of the line pointing at the value in logical pixels. A width of zero hides
+    /// the line.
+    pub fn indicator_width(mut self, width: f32) -> Self {
+        self.indicator_width = width.max(0.0);
+        self
+    }
+
+    /// Sets the angle the knob's range starts at, measured clockwise from the top. Pass
+    /// [`Degrees`] or [`Radians`].
+    pub fn start_angle(mut self, angle: impl Into<Radians>) -> Self {
+        self.start_angle = angle.into();
+        self
+    }
+
+    /// Sets how far the knob turns from the start angle to the parameter's maximum value. A
+    /// negative sweep makes the knob turn counterclockwise. Pass [`Degrees`] or [`Radians`].
+    pub fn sweep(mut self, sweep: impl Into<Radians>) -> Self {
+        self.sweep = sweep.into();
+        self
+    }
+
+    /// Sets how dragging the [`ParamKnob`] changes its value. This is [`DragMode::Vertical`] by
+    /// default.
+    pub fn drag_mode(mut self, mode: DragMode) -> Self {
+        self.drag_mode = mode;
+        self
+    }
+
+    /// Draw the knob from a film strip instead of drawing an arc and an indicator. The image
+    /// stacks `frame_count` frames of `frame_size` pixels vertically, with the frame for the
+    /// parameter's minimum value at the top. The frame closest to the parameter's value is drawn,
+    /// as large as it fits within the knob's bounds. The knob's thickness and sweep still apply to
+    /// its [circular dragging][DragMode::Circular]. The knob's style is not used, and neither is
+    /// the parameter's [modulation range][Self::modulation_range()].
+    ///
+    /// ```ignore
+    /// // A strip of 128 frames that are 64 by 64 pixels large, making the image 64 by 8192 pixels
+    /// const KNOB_STRIP: &[u8] = include_bytes!("../assets/knob_strip.png");
+    ///
+    /// fn view(&self) -> Element<'_, ParamMessage> {
+    ///     ParamKnob::new(&self.params.cutoff)
+    ///         .with_image_strip(
+    ///             image::Handle::from_bytes(KNOB_STRIP),
+    ///             128,
+    ///             Size::new(64.0, 64.0),
+    ///         )
+    ///         .width(Length::Fixed(64.0))
+    ///         .height(Length::Fixed(64.0))
+    ///         .into()
+    /// }
+    /// ```
+    #[cfg(feature = "image")]
+    pub fn with_image_strip(
+        mut self,
+        handle: image::Handle,
+        frame_count: usize,
+        frame_size: Size,
+    ) -> Self {
+        self.film_strip = Some(FilmStrip {
+            handle,
+            frame_count,
+            frame_size,
+        });
+        self
+    }
+
+    /// Sets the text size of the [`ParamKnob`]'s text entry.
+    pub fn text_size(mut self, size: Pixels) -> Self {
+        self.text_size = Some(size);
+        self
+    }
+
+    /// Sets the font of the [`ParamKnob`]'s text entry.
+    pub fn font(mut self, font: Font) -> Self {
+        self.font = Some(font);
+        self
+    }
+
+    /// Show the parameter's maximum value at the start of the [`ParamKnob`]'s sweep instead of at
+    /// the end. See the [`mapping`][super::mapping] module.
+    pub fn invert_display(mut self, invert: bool) -> Self {
+        self.mapping = DisplayMapping::from(invert);
+        self
+    }
+
+    /// Sets when scrolling over the [`ParamKnob`] changes the parameter's value. This overrides
+    /// the [`InteractionSettings`][crate::InteractionSettings]' setting, see
+    /// [`ParamRing::scroll_to_adjust()`][super::ParamRing::scroll_to_adjust()].
+    pub fn scroll_to_adjust(mut self, scroll_to_adjust: ScrollToAdjust) -> Self {
+        self.scroll_to_adjust = Some(scroll_to_adjust);
+        self
+    }
+
+    /// Sets the [`Id`] of the [`ParamKnob`]. By default this is derived from the parameter, see
+    /// the [`param_id`][super::param_id] module.
+    pub fn id(mut self, id: impl Into<Id>) -> Self {
+        self.id = id.into();
+        self
+    }
+
+    /// Draw the range of values the parameter has across a polyphonic synth's voices as a band
+    /// behind the [`ParamKnob`]'s value. Nothing is drawn while the range is empty. See the
+    /// [`comm`][crate::comm] module.
+    pub fn modulation_range(mut self, range: Arc<ModulationRange>) -> Self {
+        self.modulation_range = Some(range);
+        self
+    }
+
+    /// Write this widget's bounds to the editor's [`ParamBoundsMap`] every time it's drawn, so the
+    /// editor can highlight it. See the [`param_bounds`][super::param_bounds] module.
+    pub fn report_bounds(mut self, report: bool) -> Self {
+        self.bounds_map = if report {
+            ParamBoundsMap::current()
+        } else {
+            None
+        };
+        self
+    }
+
+    /// Sets the style of the [`ParamKnob`].
+    pub fn style(mut self, style: impl Fn(&Theme, Status) -> Style + 'a) -> Self
+    where
+        Theme::Class<'a>: From<StyleFn<'a, Theme>>,
+    {
+        self.class = (Box::new(style) as StyleFn<'a, Theme>).into();
+        self
+    }
+
+    /// Sets the style class of the [`ParamKnob`].
+    pub fn class(mut self, class: impl Into<Theme::Class<'a>>) -> Self {
+        self.class = class.into();
+        self
+    }
+
+    /// The knob's center and the radius of the middle of its arc.
+    fn geometry(&self, bounds: &Rectangle) -> (Point, f32) {
+        let radius = ((bounds.width.min(bounds.height) - self.thickness) / 2.0).max(0.0);
+
+        (bounds.center(), radius)
+    }
+
+    /// The angle in radians a display position is shown at.
+    fn angle_at(&self, position: f32) -> f32 {
+        self.start_angle.0 + (self.sweep.0 * position)
+    }
+
+    /// The ends of the line pointing at a display position.
+    fn indicator(&self, bounds: &Rectangle, position: f32) -> (Point, Point) {
+        let (center, radius) = self.geometry(bounds);
+        let inner_radius = (radius - self.thickness).max(0.0);
+        let angle = self.angle_at(position);
+
+        (
+            util::point_on_circle(center, inner_radius * Self::INDICATOR_START, angle),
+            util::point_on_circle(center, inner_radius, angle),
+        )
+    }
+
+    /// The square inside of the knob's arc, used for the text entry.
+    fn inner_bounds(&self, bounds: &Rectangle) -> Rectangle {
+        let (center, radius) = self.geometry(bounds);
+        let size = ((radius - (self.thickness / 2.0)) * std::f32::consts::SQRT_2).max(0.0);
+
+        Rectangle {
+            x: center.x - (size / 2.0),
+            y: center.y - (size / 2.0),
+            width: size,
+            height: size,
+        }
+    }
+
+    /// Whether the knob is drawn from a film strip.
+    fn uses_film_strip(&self) -> bool {
+        #[cfg(feature = "image")]
+        return self.film_strip.is_some();
+
+        #[cfg(not(feature = "image"))]
+        false
+    }
+
+    /// How [`ParamDrag`] should handle dragging the knob.
+    fn drag_behavior(&self) -> DragBehavior {
+        match self.drag_mode {
+            DragMode::Vertical => DragBehavior::Vertical(Self::DRAG_DISTANCE),
+            DragMode::Horizontal => DragBehavior::Horizontal(Self::DRAG_DISTANCE),
+            DragMode::Circular => DragBehavior::Circular {
+                start_angle: self.start_angle,
+                sweep: self.sweep,
+            },
+        }
+    }
+}
+
+impl<'a, P, Theme, Renderer> Widget<ParamMessage, Theme, Renderer> for ParamKnob<'a, P, Theme>
+where
+    P: Param,
+    Theme: Catalog + text_input::Catalog,
+    Renderer: KnobRenderer,
+    Renderer::Font: From<crate::Font>,
+{
+    fn tag(&self) -> tree::Tag {
+        tree::Tag::of::<State>()
+    }
+
+    fn state(&self) -> tree::State {
+        tree::State::new(State::default())
+    }
+
+    fn children(&self) -> Vec<Tree> {
+        // One child to store text input state.
+        vec![TextEntry::tree::<Theme, Renderer>()]
+    }
+
+    fn size(&self) -> Size<Length> {
+        Size {
+            width: self.width,
+            height: self.height,
+        }
+    }
+
+    fn layout(
+        &mut self,
+        _tree: &mut Tree,
+        _renderer: &Renderer,
+        limits: &layout::Limits,
+    ) -> layout::Node {
+        layout::atomic(limits, self.width, self.height)
+    }
+
+    fn operate(
+        &mut self,
+        tree: &mut Tree,
+        layout: Layout<'_>,
+        _renderer: &Renderer,
+        operation: &mut dyn Operation,
+    ) {
+        operation.container(Some(&self.id), layout.bounds());
+        operation.traverse(&mut |operation| {
+            let state = tree.state.downcast_ref::<State>();
+            state.text_entry.operate::<Renderer>(
+                &mut tree.children[0],
+                self.inner_bounds(&layout.bounds()),
+                operation,
+            );
+        });
+    }
+
+    fn draw(
+        &self,
+        tree: &Tree,
+        renderer: &mut Renderer,
+        theme: &Theme,
+        _style: &renderer::Style,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        viewport: &Rectangle,
+    ) {
+        let state = tree.state.downcast_ref::<State>();
+        let bounds = layout.bounds();
+        state.bounds.report(
+            self.bounds_map.as_ref(),
+            self.param.as_ptr(),
+            &self.id,
+            bounds,
+        );
+
+        let status = if interaction::is_input_locked() {
+            Status::Disabled
+        } else if state.drag.is_dragging() || state.text_entry.is_open() {
+            Status::Dragged
+        } else if cursor.is_over(bounds) {
+            Status::Hovered
+        } else {
+            Status::Active
+        };
+        let appearance = <Theme as Catalog>::style(theme, &self.class, status);
+        let track_color = if state.text_entry.is_out_of_range(self.param) {
+            appearance.out_of_range_color
+        } else {
+            appearance.track
+        };
+
+        let current_value = self.param.modulated_normalized_value();
+        #[cfg(feature = "image")]
+        if let Some(strip) = &self.film_strip {
+            let frame = strip.frame_at(self.mapping.to_display(current_value));
+            renderer.draw_film_strip(strip, frame, strip.frame_bounds(&bounds));
+        }
+
+        if !self.uses_film_strip() {
+            let (center, radius) = self.geometry(&bounds);
+            util::fill_arc(
+                renderer,
+                center,
+                radius,
+                self.thickness,
+                self.start_angle.0,
+                self.sweep.0,
+                track_color,
+            );
+
+            // Like the ring, bipolar continuous parameters fill from their default value
+            let bipolar_origin = ParamRange::of(self.param).bipolar_origin();
+            let (fill_start, fill_end) = self.mapping.fill_range(current_value, bipolar_origin);
+
+            // The range of the values across the voices goes behind the fill
+            if let Some((min, max)) = self.modulation_range.as_ref().and_then(|range| range.get()) {
+                let (min, max) = (self.mapping.to_display(min), self.mapping.to_display(max));
+                util::fill_arc(
+                    renderer,
+                    center,
+                    radius,
+                    self.thickness,
+                    self.angle_at(min),
+                    self.sweep.0 * (max - min),
+                    appearance.modulation,
+                );
+            }
+
+            util::fill_arc(
+                renderer,
+                center,
+                radius,
+                self.thickness,
+                self.angle_at(fill_start),
+                self.sweep.0 * (fill_end - fill_start),
+                appearance.fill,
+            );
+        }
+
+        if state.text_entry.is_open() {
+            state.text_entry.draw(
+                &tree.children[0],
+                renderer,
+                theme,
+                self.inner_bounds(&bounds),
+                self.font,
+                self.text_size,
+                cursor,
+                viewport,
+            );
+        } else if !self.uses_film_strip() {
+            let (from, to) = self.indicator(&bounds, self.mapping.to_display(current_value));
+            util::fill_line(
+                renderer,
+                from,
+                to,
+                self.indicator_width,
+                appearance.indicator,
+            );
+        }
+    }
+
+    fn update(
+        &mut self,
+        tree: &mut Tree,
+        event: &Event,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        renderer: &Renderer,
+        clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, ParamMessage>,
+        viewport: &Rectangle,
+    ) {
+        let state = tree.state.downcast_mut::<State>();
+        let bounds = layout.bounds();
+
+        if state.text_entry.is_open() {
+            state.text_entry.update::<P, Theme, Renderer>(
+                self.param,
+                &mut tree.children[0],
+                event,
+                self.inner_bounds(&bounds),
+                self.font,
+                self.text_size,
+                cursor,
+                renderer,
+                clipboard,
+                shell,
+                viewport,
+            );
+
+            return;
+        }
+
+        let open_text_entry = state.drag.update(
+            self.param,
+            self.mapping,
+            self.drag_behavior(),
+            event,
+            bounds,
+            cursor,
+            shell,
+        );
+        if open_text_entry {
+            state
+                .text_entry
+                .open::<P, Renderer>(self.param, &mut tree.children[0]);
+        }
+
+        state.drag.scroll(
+            self.param,
+            self.mapping,
+            self.scroll_to_adjust,
+            event,
+            bounds,
+            cursor,
+            shell,
+        );
+    }
+
+    fn mouse_interaction(
+        &self,
+        _state: &Tree,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        _viewport: &Rectangle,
+        _renderer: &Renderer,
+    ) -> mouse::Interaction {
+        if !cursor.is_over(layout.bounds()) {
+            mouse::Interaction::default()
+        } else if interaction::is_input_locked() {
+            mouse::Interaction::NotAllowed
+        } else {
+            mouse::Interaction::Pointer
+        }
+    }
+}
+
+impl<'a, P, Theme> ParamKnob<'a, P, Theme>
+where
+    P: Param + 'a,
+    Theme: Catalog + text_input::Catalog + 'a,
+{
+    /// Convert this [`ParamKnob`] into an [`Element`] with the correct message. You should have a
+    /// variant on your own message type that wraps around [`ParamMessage`] so you can forward those
+    /// messages to
+    /// [`IcedEditor::handle_param_message()`][crate::IcedEditor::handle_param_message()].
+    pub fn map<Message, Renderer, F>(self, f: F) -> Element<'a, Message, Theme, Renderer>
+    where
+        Message: 'static,
+        F: Fn(ParamMessage) -> Message + 'static,
+        Renderer: KnobRenderer + 'a,
+        Renderer::Font: From<crate::Font>,
+    {
+        Element::from(self).map(f)
+    }
+}
+
+impl<'a, P, Theme, Renderer> From<ParamKnob<'a, P, Theme>>
+    for Element<'a, ParamMessage, Theme, Renderer>
+where
+    P: Param + 'a,
+    Theme: Catalog + text_input::Catalog + 'a,
+    Renderer: KnobRenderer + 'a,
+    Renderer::Font: From<crate::Font>,
+{
+    fn from(widget: ParamKnob<'a, P, Theme>) -> Self {
+        Element::new(widget)
+    }
+}
+
+/// A renderer that can draw a [`ParamKnob`]. With the `image` feature, this also needs to be able
+/// to draw the images for [film strips][ParamKnob::with_image_strip()]. This is implemented for
+/// every renderer that can.
+pub trait KnobRenderer: TextRenderer {
+    /// Draw frame `frame` of `strip` so it fills `bounds`.
+    #[cfg(feature = "image")]
+    fn draw_film_strip(&mut self, strip: &FilmStrip, frame: usize, bounds: Rectangle);
+}
+
+#[cfg(feature = "image")]
+impl<R> KnobRenderer for R
+where
+    R: TextRenderer + image::Renderer<Handle = image::Handle>,
+{
+    fn draw_film_strip(&mut self, strip: &FilmStrip, frame: usize, bounds: Rectangle) {
+        // The whole strip is drawn with the frame at `bounds`, and the other frames are clipped
+        let strip_bounds = Rectangle {
+            x: bounds.x,
+            y: bounds.y - (bounds.height * frame as f32),
+            width: bounds.width,
+            height: bounds.height * strip.frame_count as f32,
+        };
+
+        self.draw_image(
+            image::Image::new(strip.handle.clone()),
+            strip_bounds,
+            bounds,
+        );
+    }
+}
+
+#[cfg(not(feature = "image"))]
+impl<R: TextRenderer> KnobRenderer for R {}
+
+#[cfg(feature = "image")]
+impl FilmStrip {
+    /// The frame for a display position. Positions between two frames round to the nearest one,
+    /// and positions outside of `[0, 1]` use the first or the last frame.
+    fn frame_at(&self, position: f32) -> usize {
+        let last_frame = self.frame_count.saturating_sub(1);
+
+        // `NaN` is cast to zero
+        ((position.clamp(0.0, 1.0) * last_frame as f32).round() as usize).min(last_frame)
+    }
+
+    /// Where a frame is drawn within `bounds`. This is as large as possible while keeping the
+    /// frame's aspect ratio, centered within the bounds.
+    fn frame_bounds(&self, bounds: &Rectangle) -> Rectangle {
+        let Size { width, height } = self.frame_size;
+        if !(width.is_finite() && height.is_finite() && width > 0.0 && height > 0.0) {
+            return *bounds;
+        }
+
+        let scale = (bounds.width / width).min(bounds.height / height);
+        let size = Size::new(width * scale, height * scale);
+
+        Rectangle {
+            x: bounds.center_x() - (size.width / 2.0),
+            y: bounds.center_y() - (size.height / 2.0),
+            width: size.width,
+            height: size.height,
+        }
+    }
+}
+
+/// The possible status of a [`ParamKnob`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    /// The [`ParamKnob`] can be interacted with.
+    Active,
+    /// The [`ParamKnob`] is being hovered.
+    Hovered,
+    /// The [`ParamKnob`] is being dragged, or a value is being typed into it.
+    Dragged,
+    /// The editor's input is locked, see
+    /// [`interaction::is_input_locked()`][crate::interaction::is_input_locked()].
+    Disabled,
+}
+
+/// The appearance of a [`ParamKnob`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Style {
+    /// The color of the part of the arc that's not filled.
+    pub track: Color,
+    /// The color of the track while the typed-in value is outside of the parameter's range. The
+    /// value will be clamped when it's submitted.
+    pub out_of_range_color: Color,
+    /// The color of the filled part of the arc.
+    pub fill: Color,
+    /// The color of the band showing the parameter's values across the voices, see
+    /// [`ParamKnob::modulation_range()`].
+    pub modulation: Color,
+    /// The color of the line pointing at the value.
+    pub indicator: Color,
+}
+
+/// The theme catalog of a [`ParamKnob`].
+pub trait Catalog: Sized {
+    /// The item class of the [`Catalog`].
+    type Class<'a>;
+
+    /// The default class produced by the [`Catalog`].
+    fn default<'a>() -> Self::Class<'a>;
+
+    /// The [`Style`] of a class with the given status.
+    fn style(&self, class: &Self::Class<'_>, status: Status) -> Style;
+}
+
+/// A styling function for a [`ParamKnob`].
+pub type StyleFn<'a, Theme> = Box<dyn Fn(&Theme, Status) -> Style + 'a>;
+
+impl Catalog for Theme {
+    type Class<'a> = StyleFn<'a, Self>;
+
+    fn default<'a>() -> Self::Class<'a> {
+        Box::new(default)
+    }
+
+    fn style(&self, class: &Self::Class<'_>, status: Status) -> Style {
+        class(self, status)
+    }
+}
+
+/// The default style of a [`ParamKnob`]. This only uses the extended palette's semantic colors, so
+/// it works for both light and dark themes.
+pub fn default(theme: &Theme, status: Status) -> Style {
+    let colors = crate::theme::colors(theme);
+
+    let track = match status {
+        Status::Active | Status::Disabled => colors.surface,
+        Status::Hovered | Status::Dragged => colors.surface_hover,
+    };
+    let (fill, indicator) = match status {
+        Status::Disabled => (
+            util::mix_colors(colors.background, colors.accent, 0.5),
+            colors.text_muted,
+        ),
+        _ => (colors.accent, colors.text),
+    };
+
+    Style {
+        track,
+        out_of_range_color: colors.danger,
+        fill,
+        // The arc is drawn from overlapping dots, so this needs to be opaque
+        modulation: util::mix_colors(track, fill, 0.35),
+        indicator,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::Vector;
+    use crate::runtime::user_interface::{Cache, UserInterface};
+    use nih_plug::prelude::{FloatParam, FloatRange};
+
+    #[test]
+    fn indicator_points_at_the_value() {
+        let param = FloatParam::new("Cutoff", 0.5, FloatRange::Linear { min: 0.0, max: 1.0 });
+        let knob: ParamKnob<_> = ParamKnob::new(&param).thickness(4.0);
+        let bounds = Rectangle::new(Point::new(0.0, 0.0), Size::new(48.0, 48.0));
+
+        // The line stays inside of the arc, and points straight up in the middle of the range
+        let (from, to) = knob.indicator(&bounds, 0.5);
+        assert!((from.x - 24.0).abs() < 1e-3 && (to.x - 24.0).abs() < 1e-3);
+        assert!((to.y - 6.0).abs() < 1e-3);
+        assert!(from.y > to.y);
+
+        // And to the bottom left at the start of the default sweep
+        let (_, to) = knob.indicator(&bounds, 0.0);
+        assert!(to.x < 24.0 && to.y > 24.0);
+    }
+
+    /// Drag a knob covering `(0, 0)` to `(100, 100)` from `from` to `to` and return the value it
+    /// set last.
+    fn drag(mode: DragMode, from: Point, to: Point) -> (f32, Vec<ParamMessage>) {
+        let param = FloatParam::new("Cutoff", 0.5, FloatRange::Linear { min: 0.0, max: 1.0 });
+        let knob = ParamKnob::new(&param)
+            .width(Length::Fixed(100.0))
+            .height(Length::Fixed(100.0))
+            .drag_mode(mode);
+        let mut interface = UserInterface::build(
+            Element::<'_, ParamMessage, Theme, ()>::from(knob),
+            Size::new(100.0, 100.0),
+            Cache::default(),
+            &mut (),
+        );
+
+        let mut messages = Vec::new();
+        for (event, cursor) in [
+            (
+                Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)),
+                from,
+            ),
+            (Event::Mouse(mouse::Event::CursorMoved { position: to }), to),
+            (
+                Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)),
+                to,
+            ),
+        ] {
+            let _ = interface.update(
+                &[event],
+                mouse::Cursor::Available(cursor),
+                &mut (),
+                &mut crate::core::clipboard::Null,
+                &mut messages,
+            );
+        }
+
+        let value = messages
+            .iter()
+            .rev()
+            .find_map(|message| match message {
+                ParamMessage::SetParameterNormalized(_, value) => Some(*value),
+                _ => None,
+            })
+            .unwrap_or(f32::NAN);
+
+        (value, messages)
+    }
+
+    #[test]
+    fn drag_modes() {
+        let center = Point::new(50.0, 50.0);
+
+        // Dragging up or to the right by a quarter of the drag distance adds a quarter
+        let quarter = ParamKnob::<FloatParam>::DRAG_DISTANCE / 4.0;
+        let (value, messages) = drag(
+            DragMode::Vertical,
+            center,
+            center - Vector::new(0.0, quarter),
+        );
+        assert!((value - 0.75).abs() < 1e-4);
+        // The whole drag is a single gesture
+        assert!(matches!(
+            messages.first(),
+            Some(ParamMessage::BeginSetParameter(_))
+        ));
+        assert!(matches!(
+            messages.last(),
+            Some(ParamMessage::EndSetParameter(_))
+        ));
+        assert_eq!(messages.len(), 3);
+
+        let (value, _) = drag(
+            DragMode::Horizontal,
+            center,
+            center + Vector::new(quarter, 0.0),
+        );
+        assert!((value - 0.75).abs() < 1e-4);
+
+        // Circular drags follow the cursor's angle around the center. The default sweep starts at
+        // the bottom left, so the left is a sixth of the way and the top right is two thirds.
+        let left = Point::new(10.0, 50.0);
+        let (value, _) = drag(DragMode::Circular, left, left);
+        assert!((value - (1.0 / 6.0)).abs() < 1e-4);
+        let (value, _) = drag(DragMode::Circular, left, Point::new(90.0, 10.0));
+        assert!((value - (2.0 / 3.0)).abs() < 1e-4);
+
+        // The gap at the bottom snaps to the closer end
+        let (value, _) = drag(DragMode::Circular, center, Point::new(45.0, 95.0));
+        assert_eq!(value, 0.0);
+        let (value, _) = drag(DragMode::Circular, center, Point::new(55.0, 95.0));
+        assert_eq!(value, 1.0);
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn film_strip_frames() {
+        let strip = FilmStrip {
+            handle: image::Handle::from_rgba(1, 4, vec![0; 16]),
+            frame_count: 128,
+            frame_size: Size::new(64.0, 32.0),
+        };
+
+        // Values round to the nearest frame and are clamped at the ends
+        assert_eq!(strip.frame_at(0.0), 0);
+        assert_eq!(strip.frame_at(0.5), 64);
+        assert_eq!(strip.frame_at(0.3 / 127.0), 0);
+        assert_eq!(strip.frame_at(0.6 / 127.0), 1);
+        assert_eq!(strip.frame_at(1.0), 127);
+        assert_eq!(strip.frame_at(-0.5), 0);
+        assert_eq!(strip.frame_at(1.5), 127);
+        assert_eq!(strip.frame_at(f32::NAN), 0);
+
+        // The frames keep their aspect ratio within the knob's bounds
+        let bounds = Rectangle::new(Point::new(10.0, 10.0), Size::new(48.0, 48.0));
+        assert_eq!(
+            strip.frame_bounds(&bounds),
+            Rectangle::new(Point::new(10.0, 22.0), Size::new(48.0, 24.0))
+        );
+    }
+}
@@ -0,0 +1,616 @@
+//! A compact progress ring for a parameter.
+
+use nih_plug::prelude::Param;
+use std::sync::Arc;
+
+use crate::comm::ModulationRange;
+use crate::core::text::Renderer as TextRenderer;
+use crate::core::widget::tree::{self, Tree};
+use crate::core::widget::{Id, Operation};
+use crate::core::{
+    alignment, layout, mouse, renderer, text, Clipboard, Color, Degrees, Element, Event, Font,
+    Layout, Length, Pixels, Point, Radians, Rectangle, Shell, Size, Theme, Widget,
+};
+use crate::interaction::{self, ScrollToAdjust};
+use crate::scale;
+use crate::widget::text_input;
+
+use super::mapping::DisplayMapping;
+use super::param_bounds::{ParamBoundsMap, ReportedBounds};
+use super::param_drag::{DragBehavior, ParamDrag, TextEntry};
+use super::param_id::param_widget_id;
+use super::range::ParamRange;
+use super::{util, ParamMessage};
+
+/// A thin circular arc that fills up with a parameter's value, with the value's text in the
+/// middle. This is a more compact alternative to a knob. It can be dragged vertically, scrolled,
+/// reset, and typed into like the other parameter widgets, see the
+/// [`param_drag`][super::param_drag] module.
+pub struct ParamRing<'a, P: Param, Theme = crate::Theme>
+where
+    Theme: Catalog,
+{
+    param: &'a P,
+    id: Id,
+
+    width: Length,
+    height: Length,
+    thickness: f32,
+    start_angle: Radians,
+    sweep: Radians,
+    text_size: Option<Pixels>,
+    font: Option<Font>,
+    mapping: DisplayMapping,
+    bounds_map: Option<ParamBoundsMap>,
+    modulation_range: Option<Arc<ModulationRange>>,
+    scroll_to_adjust: Option<ScrollToAdjust>,
+    class: Theme::Class<'a>,
+}
+
+/// State for a [`ParamRing`].
+#[derive(Debug, Default)]
+struct State {
+    /// Dragging, clicking, scrolling, and resetting the parameter.
+    drag: ParamDrag,
+    /// The text field for typing in a value. This stores its state in the widget's only child.
+    text_entry: TextEntry,
+    /// This widget's entry in the [`ParamBoundsMap`], if it reports its bounds.
+    bounds: ReportedBounds,
+}
+
+impl<'a, P, Theme> ParamRing<'a, P, Theme>
+where
+    P: Param,
+    Theme: Catalog,
+{
+    pub const DEFAULT_WIDTH: Length = Length::Fixed(56.0);
+    pub const DEFAULT_HEIGHT: Length = Length::Fixed(56.0);
+    pub const DEFAULT_THICKNESS: f32 = 4.0;
+    /// The ring starts at the bottom left by default.
+    pub const DEFAULT_START_ANGLE: Degrees = Degrees(-135.0);
+    /// The ring sweeps clockwise to the bottom right by default.
+    pub const DEFAULT_SWEEP: Degrees = Degrees(270.0);
+    /// How far the ring needs to be dragged up or down to sweep the parameter's entire range.
+    pub const DRAG_DISTANCE: f32 = 200.0;
+
+    /// Creates a new [`ParamRing`] for the given parameter.
+    pub fn new(param: &'a P) -> Self {
+        Self {
+            param,
+            id: param_widget_id(param.as_ptr()),
+
+            width: Self::DEFAULT_WIDTH,
+            height: Self::DEFAULT_HEIGHT,
+            thickness: Self::DEFAULT_THICKNESS,
+            start_angle: Self::DEFAULT_START_ANGLE.into(),
+            sweep: Self::DEFAULT_SWEEP.into(),
+            text_size: None,
+            font: None,
+            mapping: DisplayMapping::new(),
+            bounds_map: None,
+            modulation_range: None,
+            scroll_to_adjust: None,
+            class: Theme::default(),
+        }
+    }
+
+    /// Sets the width of the [`ParamRing`]. The ring is as large as the smaller of the width and
+    /// the height.
+    pub fn width(mut self, width: Length) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// Sets the height of the [`ParamRing`]. The ring is as large as the smaller of the width and
+    /// the height.
+    pub fn height(mut self, height: Length) -> Self {
+        self.height = height;
+        self
+    }
+
+    /// Sets the thickness of the ring in logical pixels.
+    pub fn thickness(mut self, thickness: f32) -> Self {
+        self.thickness = thickness.max(0.0);
+        self
+    }
+
+    /// Sets the angle the ring starts at, measured clockwise from the top. Pass [`Degrees`] or
+    /// [`Radians`].
+    pub fn start_angle(mut self, angle: impl Into<Radians>) -> Self {
+        self.start_angle = angle.into();
+        self
+    }
+
+    /// Sets how far the ring sweeps from the start angle to the parameter's maximum value. A
+    /// negative sweep makes the ring fill up counterclockwise. Pass [`Degrees`] or [`Radians`].
+    pub fn sweep(mut self, sweep: impl Into<Radians>) -> Self {
+        self.sweep = sweep.into();
+        self
+    }
+
+    /// Sets the text size of the [`ParamRing`].
+    pub fn text_size(mut self, size: Pixels) -> Self {
+        self.text_size = Some(size);
+        self
+    }
+
+    /// Sets the font of the [`ParamRing`].
+    pub fn font(mut self, font: Font) -> Self {
+        self.font = Some(font);
+        self
+    }
+
+    /// Show the parameter's maximum value at the start of the [`ParamRing`]'s sweep instead of at
+    /// the end. See the [`mapping`][super::mapping] module.
+    pub fn invert_display(mut self, invert: bool) -> Self {
+        self.mapping = DisplayMapping::from(invert);
+        self
+    }
+
+    /// Sets when scrolling over the [`ParamRing`] changes the parameter's value. This overrides the
+    /// [`InteractionSettings`][crate::InteractionSettings]' setting, so it can be used to keep
+    /// hover scrolling for a widget even when [`ScrollToAdjust::Guarded`] is the default, or to
+    /// only guard the widgets inside of a scrollable panel.
+    pub fn scroll_to_adjust(mut self, scroll_to_adjust: ScrollToAdjust) -> Self {
+        self.scroll_to_adjust = Some(scroll_to_adjust);
+        self
+    }
+
+    /// Sets the [`Id`] of the [`ParamRing`]. By default this is derived from the parameter, see
+    /// the [`param_id`][super::param_id] module.
+    pub fn id(mut self, id: impl Into<Id>) -> Self {
+        self.id = id.into();
+        self
+    }
+
+    /// Draw the range of values the parameter has across a polyphonic synth's voices as a band
+    /// behind the [`ParamRing`]'s value. Nothing is drawn while the range is empty. See the
+    /// [`comm`][crate::comm] module.
+    pub fn modulation_range(mut self, range: Arc<ModulationRange>) -> Self {
+        self.modulation_range = Some(range);
+        self
+    }
+
+    /// Write this widget's bounds to the editor's [`ParamBoundsMap`] every time it's drawn, so the
+    /// editor can highlight it. See the [`param_bounds`][super::param_bounds] module.
+    pub fn report_bounds(mut self, report: bool) -> Self {
+        self.bounds_map = if report {
+            ParamBoundsMap::current()
+        } else {
+            None
+        };
+        self
+    }
+
+    /// Sets the style of the [`ParamRing`].
+    pub fn style(mut self, style: impl Fn(&Theme, Status) -> Style + 'a) -> Self
+    where
+        Theme::Class<'a>: From<StyleFn<'a, Theme>>,
+    {
+        self.class = (Box::new(style) as StyleFn<'a, Theme>).into();
+        self
+    }
+
+    /// Sets the style class of the [`ParamRing`].
+    pub fn class(mut self, class: impl Into<Theme::Class<'a>>) -> Self {
+        self.class = class.into();
+        self
+    }
+
+    /// The ring's center and the radius of the middle of its arc.
+    fn geometry(&self, bounds: &Rectangle) -> (Point, f32) {
+        let radius = ((bounds.width.min(bounds.height) - self.thickness) / 2.0).max(0.0);
+
+        (bounds.center(), radius)
+    }
+
+    /// The angle in radians a display position is shown at.
+    fn angle_at(&self, position: f32) -> f32 {
+        self.start_angle.0 + (self.sweep.0 * position)
+    }
+
+    /// The square inside of the ring, used for the value text and the text entry.
+    fn inner_bounds(&self, bounds: &Rectangle) -> Rectangle {
+        let (center, radius) = self.geometry(bounds);
+        let size = ((radius - (self.thickness / 2.0)) * std::f32::consts::SQRT_2).max(0.0);
+
+        Rectangle {
+            x: center.x - (size / 2.0),
+            y: center.y - (size / 2.0),
+            width: size,
+            height: size,
+        }
+    }
+}
+
+impl<'a, P, Theme, Renderer> Widget<ParamMessage, Theme, Renderer> for ParamRing<'a, P, Theme>
+where
+    P: Param,
+    Theme: Catalog + text_input::Catalog,
+    Renderer: TextRenderer,
+    Renderer::Font: From<crate::Font>,
+{
+    fn tag(&self) -> tree::Tag {
+        tree::Tag::of::<State>()
+    }
+
+    fn state(&self) -> tree::State {
+        tree::State::new(State::default())
+    }
+
+    fn children(&self) -> Vec<Tree> {
+        // One child to store text input state.
+        vec![TextEntry::tree::<Theme, Renderer>()]
+    }
+
+    fn size(&self) -> Size<Length> {
+        Size {
+            width: self.width,
+            height: self.height,
+        }
+    }
+
+    fn layout(
+        &mut self,
+        _tree: &mut Tree,
+        _renderer: &Renderer,
+        limits: &layout::Limits,
+    ) -> layout::Node {
+        layout::atomic(limits, self.width, self.height)
+    }
+
+    fn operate(
+        &mut self,
+        tree: &mut Tree,
+        layout: Layout<'_>,
+        _renderer: &Renderer,
+        operation: &mut dyn Operation,
+    ) {
+        operation.container(Some(&self.id), layout.bounds());
+        operation.traverse(&mut |operation| {
+            let state = tree.state.downcast_ref::<State>();
+            state.text_entry.operate::<Renderer>(
+                &mut tree.children[0],
+                self.inner_bounds(&layout.bounds()),
+                operation,
+            );
+        });
+    }
+
+    fn draw(
+        &self,
+        tree: &Tree,
+        renderer: &mut Renderer,
+        theme: &Theme,
+        _style: &renderer::Style,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        viewport: &Rectangle,
+    ) {
+        let state = tree.state.downcast_ref::<State>();
+        let bounds = layout.bounds();
+        state.bounds.report(
+            self.bounds_map.as_ref(),
+            self.param.as_ptr(),
+            &self.id,
+            bounds,
+        );
+
+        let status = if interaction::is_input_locked() {
+            Status::Disabled
+        } else if state.drag.is_dragging() || state.text_entry.is_open() {
+            Status::Dragged
+        } else if cursor.is_over(bounds) {
+            Status::Hovered
+        } else {
+            Status::Active
+        };
+        let appearance = <Theme as Catalog>::style(theme, &self.class, status);
+        let track_color = if state.text_entry.is_out_of_range(self.param) {
+            appearance.out_of_range_color
+        } else {
+            appearance.track
+        };
+
+        let (center, radius) = self.geometry(&bounds);
+        util::fill_arc(
+            renderer,
+            center,
+            radius,
+            self.thickness,
+            self.start_angle.0,
+            self.sweep.0,
+            track_color,
+        );
+
+        // Like the slider, bipolar continuous parameters fill from their default value
+        let current_value = self.param.modulated_normalized_value();
+        let bipolar_origin = ParamRange::of(self.param).bipolar_origin();
+        let (fill_start, fill_end) = self.mapping.fill_range(current_value, bipolar_origin);
+
+        // The range of the values across the voices goes behind the fill
+        if let Some((min, max)) = self.modulation_range.as_ref().and_then(|range| range.get()) {
+            let (min, max) = (self.mapping.to_display(min), self.mapping.to_display(max));
+            util::fill_arc(
+                renderer,
+                center,
+                radius,
+                self.thickness,
+                self.angle_at(min),
+                self.sweep.0 * (max - min),
+                appearance.modulation,
+            );
+        }
+
+        util::fill_arc(
+            renderer,
+            center,
+            radius,
+            self.thickness,
+            self.angle_at(fill_start),
+            self.sweep.0 * (fill_end - fill_start),
+            appearance.fill,
+        );
+
+        let inner_bounds = self.inner_bounds(&bounds);
+        if state.text_entry.is_open() {
+            state.text_entry.draw(
+                &tree.children[0],
+                renderer,
+                theme,
+                inner_bounds,
+                self.font,
+                self.text_size,
+                cursor,
+                viewport,
+            );
+        } else {
+            let text_size = self.text_size.unwrap_or_else(|| renderer.default_size());
+            let font = self
+                .font
+                .map(Renderer::Font::from)
+                .unwrap_or_else(|| renderer.default_font());
+
+            let text = text::Text {
+                content: self.param.to_string(),
+                font,
+                size: text_size,
+                bounds: inner_bounds.size(),
+                align_x: alignment::Horizontal::Center.into(),
+                align_y: alignment::Vertical::Center,
+                line_height: text::LineHeight::Relative(1.0),
+                shaping: Default::default(),
+                wrapping: Default::default(),
+            };
+            let position = scale::snap_text(&text, center);
+            renderer.fill_text(text, position, appearance.text_color, *viewport);
+        }
+    }
+
+    fn update(
+        &mut self,
+        tree: &mut Tree,
+        event: &Event,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        renderer: &Renderer,
+        clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, ParamMessage>,
+        viewport: &Rectangle,
+    ) {
+        let state = tree.state.downcast_mut::<State>();
+        let bounds = layout.bounds();
+
+        if state.text_entry.is_open() {
+            state.text_entry.update::<P, Theme, Renderer>(
+                self.param,
+                &mut tree.children[0],
+                event,
+                self.inner_bounds(&bounds),
+                self.font,
+                self.text_size,
+                cursor,
+                renderer,
+                clipboard,
+                shell,
+                viewport,
+            );
+
+            return;
+        }
+
+        let open_text_entry = state.drag.update(
+            self.param,
+            self.mapping,
+            DragBehavior::Vertical(Self::DRAG_DISTANCE),
+            event,
+            bounds,
+            cursor,
+            shell,
+        );
+        if open_text_entry {
+            state
+                .text_entry
+                .open::<P, Renderer>(self.param, &mut tree.children[0]);
+        }
+
+        state.drag.scroll(
+            self.param,
+            self.mapping,
+            self.scroll_to_adjust,
+            event,
+            bounds,
+            cursor,
+            shell,
+        );
+    }
+
+    fn mouse_interaction(
+        &self,
+        _state: &Tree,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        _viewport: &Rectangle,
+        _renderer: &Renderer,
+    ) -> mouse::Interaction {
+        if !cursor.is_over(layout.bounds()) {
+            mouse::Interaction::default()
+        } else if interaction::is_input_locked() {
+            mouse::Interaction::NotAllowed
+        } else {
+            mouse::Interaction::Pointer
+        }
+    }
+}
+
+impl<'a, P, Theme> ParamRing<'a, P, Theme>
+where
+    P: Param + 'a,
+    Theme: Catalog + text_input::Catalog + 'a,
+{
+    /// Convert this [`ParamRing`] into an [`Element`] with the correct message. You should have a
+    /// variant on your own message type that wraps around [`ParamMessage`] so you can forward those
+    /// messages to
+    /// [`IcedEditor::handle_param_message()`][crate::IcedEditor::handle_param_message()].
+    pub fn map<Message, Renderer, F>(self, f: F) -> Element<'a, Message, Theme, Renderer>
+    where
+        Message: 'static,
+        F: Fn(ParamMessage) -> Message + 'static,
+        Renderer: TextRenderer + 'a,
+        Renderer::Font: From<crate::Font>,
+    {
+        Element::from(self).map(f)
+    }
+}
+
+impl<'a, P, Theme, Renderer> From<ParamRing<'a, P, Theme>>
+    for Element<'a, ParamMessage, Theme, Renderer>
+where
+    P: Param + 'a,
+    Theme: Catalog + text_input::Catalog + 'a,
+    Renderer: TextRenderer + 'a,
+    Renderer::Font: From<crate::Font>,
+{
+    fn from(widget: ParamRing<'a, P, Theme>) -> Self {
+        Element::new(widget)
+    }
+}
+
+/// The possible status of a [`ParamRing`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    /// The [`ParamRing`] can be interacted with.
+    Active,
+    /// The [`ParamRing`] is being hovered.
+    Hovered,
+    /// The [`ParamRing`] is being dragged, or a value is being typed into it.
+    Dragged,
+    /// The editor's input is locked, see
+    /// [`interaction::is_input_locked()`][crate::interaction::is_input_locked()].
+    Disabled,
+}
+
+/// The appearance of a [`ParamRing`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Style {
+    /// The color of the part of the ring that's not filled.
+    pub track: Color,
+    /// The color of the track while the typed-in value is outside of the parameter's range. The
+    /// value will be clamped when it's submitted.
+    pub out_of_range_color: Color,
+    /// The color of the filled part of the ring.
+    pub fill: Color,
+    /// The color of the band showing the parameter's values across the voices, see
+    /// [`ParamRing::modulation_range()`].
+    pub modulation: Color,
+    /// The color of the value text.
+    pub text_color: Color,
+}
+
+/// The theme catalog of a [`ParamRing`].
+pub trait Catalog: Sized {
+    /// The item class of the [`Catalog`].
+    type Class<'a>;
+
+    /// The default class produced by the [`Catalog`].
+    fn default<'a>() -> Self::Class<'a>;
+
+    /// The [`Style`] of a class with the given status.
+    fn style(&self, class: &Self::Class<'_>, status: Status) -> Style;
+}
+
+/// A styling function for a [`ParamRing`].
+pub type StyleFn<'a, Theme> = Box<dyn Fn(&Theme, Status) -> Style + 'a>;
+
+impl Catalog for Theme {
+    type Class<'a> = StyleFn<'a, Self>;
+
+    fn default<'a>() -> Self::Class<'a> {
+        Box::new(default)
+    }
+
+    fn style(&self, class: &Self::Class<'_>, status: Status) -> Style {
+        class(self, status)
+    }
+}
+
+/// The default style of a [`ParamRing`]. This only uses the extended palette's semantic colors, so
+/// it works for both light and dark themes.
+pub fn default(theme: &Theme, status: Status) -> Style {
+    let colors = crate::theme::colors(theme);
+
+    let track = match status {
+        Status::Active | Status::Disabled => colors.surface,
+        Status::Hovered | Status::Dragged => colors.surface_hover,
+    };
+    let fill = match status {
+        Status::Disabled => util::mix_colors(colors.background, colors.accent, 0.5),
+        _ => colors.accent,
+    };
+
+    Style {
+        track,
+        out_of_range_color: colors.danger,
+        fill,
+        // The arc is drawn from overlapping dots, so this needs to be opaque
+        modulation: util::mix_colors(track, fill, 0.35),
+        text_color: colors.text,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nih_plug::prelude::{FloatParam, FloatRange};
+
+    #[test]
+    fn ring_geometry() {
+        let param = FloatParam::new("Mix", 1.0, FloatRange::Linear { min: 0.0, max: 1.0 });
+        let ring: ParamRing<_> = ParamRing::new(&param)
+            .thickness(6.0)
+            .start_angle(Degrees(-90.0))
+            .sweep(Degrees(180.0));
+        let bounds = Rectangle::new(Point::new(10.0, 10.0), Size::new(100.0, 60.0));
+
+        // The ring fits within the smaller of the two dimensions, including its thickness
+        let (center, radius) = ring.geometry(&bounds);
+        assert_eq!(center, Point::new(60.0, 40.0));
+        assert_eq!(radius, 27.0);
+
+        // The left side of the ring is the start, and the right side is the end
+        let start = util::point_on_circle(center, radius, ring.angle_at(0.0));
+        let end = util::point_on_circle(center, radius, ring.angle_at(1.0));
+        assert!(start.distance(Point::new(33.0, 40.0)) < 1e-3);
+        assert!(end.distance(Point::new(87.0, 40.0)) < 1e-3);
+
+        // The text fits inside of the ring
+        let inner_bounds = ring.inner_bounds(&bounds);
+        assert_eq!(inner_bounds.center(), center);
+        let corner = Point::new(inner_bounds.x, inner_bounds.y);
+        assert!(corner.distance(center) <= radius - 3.0 + 1e-3);
+
+        // A ring thicker than the widget doesn't end up with a negative radius
+        let (_, radius) = ring.thickness(100.0).geometry(&bounds);
+        assert_eq!(radius, 0.0);
+    }
+}
@@ -1,80 +1,65 @@
 use nih_plug::prelude::Param;
-use std::borrow::Borrow;
+use std::sync::Arc;
 
-use crate::core::text::{Paragraph, Renderer as TextRenderer, Text};
+use crate::comm::ModulationRange;
+use crate::core::text::Renderer as TextRenderer;
 use crate::core::widget::tree::{self, Tree};
+use crate::core::widget::{Id, Operation};
 use crate::core::{
-    alignment, keyboard, layout, mouse, renderer, text, touch, Border, Clipboard, Color,
-    Element, Event, Font, Layout, Length, Pixels, Rectangle, Shell, Size, Vector, Widget,
+    alignment, layout, mouse, renderer, text, Background, Border, Clipboard, Color, Element, Event,
+    Font, Layout, Length, Pixels, Rectangle, Shell, Size, Theme, Widget,
 };
+use crate::interaction::{self, ScrollToAdjust};
+use crate::scale;
 use crate::widget::text_input;
-use crate::widget::text_input::TextInput;
-use crate::core::widget::Id;
 
+use super::mapping::DisplayMapping;
+use super::param_bounds::{ParamBoundsMap, ReportedBounds};
+use super::param_drag::{DragBehavior, ParamDrag, TextEntry};
+use super::param_id::param_widget_id;
+use super::range::ParamRange;
 use super::{util, ParamMessage};
 
-/// When shift+dragging a parameter, one pixel dragged corresponds to this much change in the
-/// noramlized parameter.
-const GRANULAR_DRAG_MULTIPLIER: f32 = 0.1;
-
 /// The thickness of this widget's borders.
 const BORDER_WIDTH: f32 = 1.0;
 
-/// A slider that integrates with NIH-plug's [`Param`] types.
-///
-/// TODO: There are currently no styling options at all
-/// TODO: Handle scrolling for steps (and shift+scroll for smaller steps?)
-pub struct ParamSlider<'a, P: Param> {
+/// A slider that integrates with NIH-plug's [`Param`] types. It can be dragged, scrolled, reset,
+/// and typed into like the other parameter widgets, see the [`param_drag`][super::param_drag]
+/// module.
+pub struct ParamSlider<'a, P: Param, Theme = crate::Theme>
+where
+    Theme: Catalog,
+{
     param: &'a P,
+    id: Id,
 
     width: Length,
     height: Length,
     text_size: Option<Pixels>,
     font: Option<Font>,
+    mapping: DisplayMapping,
+    bounds_map: Option<ParamBoundsMap>,
+    modulation_range: Option<Arc<ModulationRange>>,
+    scroll_to_adjust: Option<ScrollToAdjust>,
+    class: Theme::Class<'a>,
 }
 
 /// State for a [`ParamSlider`].
-#[derive(Debug)]
+#[derive(Debug, Default)]
 struct State {
-    keyboard_modifiers: keyboard::Modifiers,
-    /// Will be set to `true` if we're dragging the parameter. Resetting the parameter or entering a
-    /// text value should not initiate a drag.
-    drag_active: bool,
-    /// We keep track of the start coordinate and normalized value holding down Shift while dragging
-    /// for higher precision dragging. This is a `None` value when granular dragging is not active.
-    granular_drag_start_x_value: Option<(f32, f32)>,
-    /// Track clicks for double clicks.
-    last_click: Option<mouse::Click>,
-
-    /// The text that's currently in the text input. If this is set to `None`, then the text input
-    /// is not visible.
-    text_input_value: Option<String>,
-    text_input_id: Id,
-}
-
-impl Default for State {
-    fn default() -> Self {
-        Self {
-            text_input_id: Id::unique(),
-            keyboard_modifiers: Default::default(),
-            drag_active: Default::default(),
-            granular_drag_start_x_value: Default::default(),
-            last_click: Default::default(),
-            text_input_value: Default::default(),
-        }
-    }
+    /// Dragging, clicking, scrolling, and resetting the parameter.
+    drag: ParamDrag,
+    /// The text field for typing in a value. This stores its state in the widget's only child.
+    text_entry: TextEntry,
+    /// This widget's entry in the [`ParamBoundsMap`], if it reports its bounds.
+    bounds: ReportedBounds,
 }
 
-/// An internal message for intercep- I mean handling output from the embedded [`TextInput`] widget.
-#[derive(Debug, Clone)]
-enum TextInputMessage {
-    /// A new value was entered in the text input dialog.
-    Value(String),
-    /// Enter was pressed.
-    Submit,
-}
-
-impl<'a, P: Param> ParamSlider<'a, P> {
+impl<'a, P, Theme> ParamSlider<'a, P, Theme>
+where
+    P: Param,
+    Theme: Catalog,
+{
     pub const DEFAULT_WIDTH: Length = Length::Fixed(180.0);
     pub const DEFAULT_HEIGHT: Length = Length::Fixed(30.0);
 
@@ -82,11 +67,17 @@ impl<'a, P: Param> ParamSlider<'a, P> {
     pub fn new(param: &'a P) -> Self {
         Self {
             param,
+            id: param_widget_id(param.as_ptr()),
 
             width: Self::DEFAULT_WIDTH,
             height: Self::DEFAULT_HEIGHT,
             text_size: None,
             font: None,
+            mapping: DisplayMapping::new(),
+            bounds_map: None,
+            modulation_range: None,
+            scroll_to_adjust: None,
+            class: Theme::default(),
         }
     }
 
@@ -114,96 +105,68 @@ impl<'a, P: Param> ParamSlider<'a, P> {
         self
     }
 
-    /// Create a temporary [`TextInput`] hooked up to [`State::text_input_value`] and outputting
-    /// [`TextInputMessage`] messages and do something with it. This can be used to
-    fn with_text_input<T, Theme, Renderer, BorrowedRenderer, F>(
-        &self,
-        layout: Layout,
-        renderer: BorrowedRenderer,
-        current_value: &str,
-        state: &State,
-        f: F,
-    ) -> T
+    /// Show the parameter's maximum value on the left side of the [`ParamSlider`] instead of on the
+    /// right side. This flips the fill and the drag direction, but the parameter's value and its
+    /// text stay the same. See the [`mapping`][super::mapping] module.
+    pub fn invert_display(mut self, invert: bool) -> Self {
+        self.mapping = DisplayMapping::from(invert);
+        self
+    }
+
+    /// Sets when scrolling over the [`ParamSlider`] changes the parameter's value. This overrides
+    /// the [`InteractionSettings`][crate::InteractionSettings]' setting, see
+    /// [`ParamRing::scroll_to_adjust()`][super::ParamRing::scroll_to_adjust()].
+    pub fn scroll_to_adjust(mut self, scroll_to_adjust: ScrollToAdjust) -> Self {
+        self.scroll_to_adjust = Some(scroll_to_adjust);
+        self
+    }
+
+    /// Sets the [`Id`] of the [`ParamSlider`]. By default this is derived from the parameter, see
+    /// the [`param_id`][super::param_id] module.
+    pub fn id(mut self, id: impl Into<Id>) -> Self {
+        self.id = id.into();
+        self
+    }
+
+    /// Draw the range of values the parameter has across a polyphonic synth's voices as a band
+    /// behind the [`ParamSlider`]'s value. Nothing is drawn while the range is empty. See the
+    /// [`comm`][crate::comm] module.
+    pub fn modulation_range(mut self, range: Arc<ModulationRange>) -> Self {
+        self.modulation_range = Some(range);
+        self
+    }
+
+    /// Write this widget's bounds to the editor's [`ParamBoundsMap`] every time it's drawn, so the
+    /// editor can highlight it. See the [`param_bounds`][super::param_bounds] module.
+    pub fn report_bounds(mut self, report: bool) -> Self {
+        self.bounds_map = if report {
+            ParamBoundsMap::current()
+        } else {
+            None
+        };
+        self
+    }
+
+    /// Sets the style of the [`ParamSlider`].
+    pub fn style(mut self, style: impl Fn(&Theme, Status) -> Style + 'a) -> Self
     where
-        F: FnOnce(TextInput<'_, TextInputMessage, Theme, Renderer>, Layout, BorrowedRenderer) -> T,
-        Theme: text_input::Catalog,
-        Renderer: TextRenderer,
-        Renderer::Font: From<crate::Font>,
-        BorrowedRenderer: Borrow<Renderer>,
+        Theme::Class<'a>: From<StyleFn<'a, Theme>>,
     {
-        let font = self
-            .font
-            .map(Renderer::Font::from)
-            .unwrap_or_else(|| renderer.borrow().default_font());
-
-        let text_size = self
-            .text_size
-            .unwrap_or_else(|| renderer.borrow().default_size());
-        let text_width = Renderer::Paragraph::with_text(Text {
-            content: current_value,
-            bounds: layout.bounds().size(),
-            size: text_size,
-            font,
-            line_height: Default::default(),
-            align_x: alignment::Horizontal::Center.into(),
-            align_y: alignment::Vertical::Center.into(),
-            shaping: Default::default(),
-            wrapping: Default::default(),
-        })
-        .min_width();
-
-        let text_input = text_input("", current_value)
-            .id(state.text_input_id.clone())
-            .font(font)
-            .size(text_size)
-            .width(text_width)
-            .on_input(TextInputMessage::Value)
-            .on_submit(TextInputMessage::Submit);
-
-        // Make sure to not draw over the borders, and center the text
-        let offset_node = layout::Node::with_children(
-            Size {
-                width: text_width,
-                height: layout.bounds().shrink(BORDER_WIDTH).size().height,
-            },
-            vec![layout::Node::new(layout.bounds().size())],
-        );
-        let offset_layout = Layout::with_offset(
-            Vector {
-                x: layout.bounds().center_x() - (text_width / 2.0),
-                y: layout.position().y + BORDER_WIDTH,
-            },
-            &offset_node,
-        );
-
-        f(text_input, offset_layout, renderer)
+        self.class = (Box::new(style) as StyleFn<'a, Theme>).into();
+        self
     }
 
-    /// Set the normalized value for a parameter if that would change the parameter's plain value
-    /// (to avoid unnecessary duplicate parameter changes). The begin- and end set parameter
-    /// messages need to be sent before calling this function.
-    fn set_normalized_value(&self, shell: &mut Shell<'_, ParamMessage>, normalized_value: f32) {
-        // This snaps to the nearest plain value if the parameter is stepped in some way.
-        // TODO: As an optimization, we could add a `const CONTINUOUS: bool` to the parameter to
-        //       avoid this normalized->plain->normalized conversion for parameters that don't need
-        //       it
-        let plain_value = self.param.preview_plain(normalized_value);
-        let current_plain_value = self.param.modulated_plain_value();
-        if plain_value != current_plain_value {
-            // For the aforementioned snapping
-            let normalized_plain_value = self.param.preview_normalized(plain_value);
-            shell.publish(ParamMessage::SetParameterNormalized(
-                self.param.as_ptr(),
-                normalized_plain_value,
-            ));
-        }
+    /// Sets the style class of the [`ParamSlider`].
+    pub fn class(mut self, class: impl Into<Theme::Class<'a>>) -> Self {
+        self.class = class.into();
+        self
     }
 }
 
-impl<'a, P, Theme, Renderer> Widget<ParamMessage, Theme, Renderer> for ParamSlider<'a, P>
+impl<'a, P, Theme, Renderer> Widget<ParamMessage, Theme, Renderer> for ParamSlider<'a, P, Theme>
 where
     P: Param,
-    Theme: text_input::Catalog,
+    Theme: Catalog + text_input::Catalog,
     Renderer: TextRenderer,
     Renderer::Font: From<crate::Font>,
 {
@@ -216,14 +179,8 @@ where
     }
 
     fn children(&self) -> Vec<Tree> {
-        let input = text_input::<TextInputMessage, Theme, Renderer>("", "");
-
         // One child to store text input state.
-        vec![Tree {
-            tag: input.tag(),
-            state: input.state(),
-            children: input.children(),
-        }]
+        vec![TextEntry::tree::<Theme, Renderer>()]
     }
 
     fn size(&self) -> Size<Length> {
@@ -242,91 +199,130 @@ where
         layout::atomic(limits, self.width, self.height)
     }
 
+    fn operate(
+        &mut self,
+        tree: &mut Tree,
+        layout: Layout<'_>,
+        _renderer: &Renderer,
+        operation: &mut dyn Operation,
+    ) {
+        operation.container(Some(&self.id), layout.bounds());
+        operation.traverse(&mut |operation| {
+            let state = tree.state.downcast_ref::<State>();
+            state.text_entry.operate::<Renderer>(
+                &mut tree.children[0],
+                layout.bounds().shrink(BORDER_WIDTH),
+                operation,
+            );
+        });
+    }
+
     fn draw(
         &self,
         tree: &Tree,
         renderer: &mut Renderer,
         theme: &Theme,
-        style: &renderer::Style,
+        _style: &renderer::Style,
         layout: Layout<'_>,
         cursor: mouse::Cursor,
         viewport: &Rectangle,
     ) {
         let state = tree.state.downcast_ref::<State>();
         let bounds = layout.bounds();
+        state.bounds.report(
+            self.bounds_map.as_ref(),
+            self.param.as_ptr(),
+            &self.id,
+            bounds,
+        );
 
-        let background_color =
-            if cursor.is_over(bounds) || state.drag_active || state.text_input_value.is_some() {
-                Color::from_rgba(0.5, 0.5, 0.5, 0.1)
-            } else {
-                Color::TRANSPARENT
-            };
+        let status = if interaction::is_input_locked() {
+            Status::Disabled
+        } else if state.drag.is_dragging() || state.text_entry.is_open() {
+            Status::Dragged
+        } else if cursor.is_over(bounds) {
+            Status::Hovered
+        } else {
+            Status::Active
+        };
+        let appearance = <Theme as Catalog>::style(theme, &self.class, status);
+        let border_color = if state.text_entry.is_out_of_range(self.param) {
+            appearance.out_of_range_color
+        } else {
+            appearance.border_color
+        };
 
+        // The border's edges need to sit on physical pixels to stay sharp at any scale factor
+        let bounds = scale::pixel_snap(bounds);
+        let border_width = scale::snap_stroke(BORDER_WIDTH);
         renderer.fill_quad(
             renderer::Quad {
                 bounds,
                 border: Border {
-                    color: Color::BLACK,
-                    width: BORDER_WIDTH,
+                    color: border_color,
+                    width: border_width,
                     radius: 0.0.into(),
                 },
                 ..Default::default()
             },
-            background_color,
+            appearance.background,
         );
 
         // Shrink bounds to inside of the border
-        let bounds = bounds.shrink(BORDER_WIDTH);
+        let bounds = bounds.shrink(border_width);
 
-        if let Some(current_value) = &state.text_input_value {
-            self.with_text_input(
-                layout,
+        if state.text_entry.is_open() {
+            state.text_entry.draw(
+                &tree.children[0],
                 renderer,
-                current_value,
-                state,
-                |text_input, layout, renderer| {
-                    text_input.draw(
-                        &tree.children[0],
-                        renderer,
-                        theme,
-                        layout,
-                        cursor,
-                        None,
-                        viewport,
-                    );
-                },
+                theme,
+                bounds,
+                self.font,
+                self.text_size,
+                cursor,
+                viewport,
             );
         } else {
             // We'll visualize the difference between the current value and the default value if the
             // default value lies somewhere in the middle and the parameter is continuous. Otherwise
             // this appraoch looks a bit jarring.
             let current_value = self.param.modulated_normalized_value();
-            let default_value = self.param.default_normalized_value();
-
-            let fill_start_x = util::remap_rect_x_t(
-                &bounds,
-                if self.param.step_count().is_none() && (0.45..=0.55).contains(&default_value) {
-                    default_value
-                } else {
-                    0.0
-                },
-            );
+            let bipolar_origin = ParamRange::of(self.param).bipolar_origin();
+            let (fill_start, fill_end) = self.mapping.fill_range(current_value, bipolar_origin);
+
+            // The range of the values across the voices goes behind the fill
+            if let Some((min, max)) = self.modulation_range.as_ref().and_then(|range| range.get()) {
+                let min_x = util::remap_rect_x_t(&bounds, self.mapping.to_display(min));
+                let max_x = util::remap_rect_x_t(&bounds, self.mapping.to_display(max));
+
+                renderer.fill_quad(
+                    renderer::Quad {
+                        bounds: scale::pixel_snap(Rectangle {
+                            x: min_x.min(max_x),
+                            width: (max_x - min_x).abs(),
+                            ..bounds
+                        }),
+                        ..Default::default()
+                    },
+                    appearance.modulation,
+                );
+            }
 
-            let fill_end_x = util::remap_rect_x_t(&bounds, current_value);
+            let fill_start_x = util::remap_rect_x_t(&bounds, fill_start);
+            let fill_end_x = util::remap_rect_x_t(&bounds, fill_end);
 
-            let fill_color = Color::from_rgb8(196, 196, 196);
-            let fill_rect = Rectangle {
+            let fill_rect = scale::pixel_snap(Rectangle {
                 x: fill_start_x.min(fill_end_x),
                 width: (fill_end_x - fill_start_x).abs(),
                 ..bounds
-            };
+            });
 
             renderer.fill_quad(
                 renderer::Quad {
                     bounds: fill_rect,
                     ..Default::default()
                 },
-                fill_color,
+                appearance.fill,
             );
 
             // To make it more readable (and because it looks cool), the parts that overlap with the
@@ -344,9 +340,24 @@ where
                 y: bounds.center_y(),
                 ..bounds
             };
-            renderer.fill_text(
-                text::Text {
-                    content: display_value.clone(),
+            let text = text::Text {
+                content: display_value.clone(),
+                font,
+                size: text_size,
+                bounds: text_bounds.size(),
+                align_x: alignment::Horizontal::Center.into(),
+                align_y: alignment::Vertical::Center.into(),
+                line_height: text::LineHeight::Relative(1.0),
+                shaping: Default::default(),
+                wrapping: Default::default(),
+            };
+            let position = scale::snap_text(&text, text_bounds.position());
+            renderer.fill_text(text, position, appearance.text_color, *viewport);
+
+            // This will clip to the filled area
+            renderer.with_layer(fill_rect, |renderer| {
+                let text = text::Text {
+                    content: display_value,
                     font,
                     size: text_size,
                     bounds: text_bounds.size(),
@@ -355,31 +366,9 @@ where
                     line_height: text::LineHeight::Relative(1.0),
                     shaping: Default::default(),
                     wrapping: Default::default(),
-                },
-                text_bounds.position(),
-                style.text_color,
-                *viewport,
-            );
-
-            // This will clip to the filled area
-            renderer.with_layer(fill_rect, |renderer| {
-                let filled_text_color = Color::from_rgb8(80, 80, 80);
-                renderer.fill_text(
-                    text::Text {
-                        content: display_value,
-                        font,
-                        size: text_size,
-                        bounds: text_bounds.size(),
-                        align_x: alignment::Horizontal::Center.into(),
-                        align_y: alignment::Vertical::Center.into(),
-                        line_height: text::LineHeight::Relative(1.0),
-                        shaping: Default::default(),
-                        wrapping: Default::default(),
-                    },
-                    text_bounds.position(),
-                    filled_text_color,
-                    *viewport,
-                );
+                };
+                let position = scale::snap_text(&text, text_bounds.position());
+                renderer.fill_text(text, position, appearance.filled_text_color, *viewport);
             });
         }
     }
@@ -397,191 +386,50 @@ where
     ) {
         let state = tree.state.downcast_mut::<State>();
 
-        // The pressence of a value in `self.state.text_input_value` indicates that the field should
-        // be focussed. The field handles defocussing by itself
-        // FIMXE: This is super hacky, I have no idea how you can reuse the text input widget
-        //        otherwise. Widgets are not supposed to handle messages from other widgets, but
-        //        we'll do so anyways by using a special `TextInputMessage` type and our own
-        //        `Shell`.
-        if let Some(current_value) = &state.text_input_value {
-            let mut messages = Vec::new();
-            let mut text_input_shell = Shell::new(&mut messages);
-
-            self.with_text_input(
-                layout,
+        // The pressence of a value in the text entry indicates that the field should be focussed.
+        // The field handles defocussing by itself.
+        if state.text_entry.is_open() {
+            state.text_entry.update::<P, Theme, Renderer>(
+                self.param,
+                &mut tree.children[0],
+                event,
+                layout.bounds().shrink(BORDER_WIDTH),
+                self.font,
+                self.text_size,
+                cursor,
                 renderer,
-                current_value,
-                &state,
-                |mut text_input: TextInput<TextInputMessage, Theme, Renderer>, layout, renderer| {
-                    text_input.update(
-                        &mut tree.children[0],
-                        event,
-                        layout,
-                        cursor,
-                        renderer,
-                        clipboard,
-                        &mut text_input_shell,
-                        viewport,
-                    )
-                },
+                clipboard,
+                shell,
+                viewport,
             );
 
-            // Check if text input is focused.
-            let text_input_state = tree.children[0]
-                .state
-                .downcast_ref::<text_input::State<Renderer::Paragraph>>();
-
-            // Pressing escape will unfocus the text field, so we should propagate that change in
-            // our own model
-            if text_input_state.is_focused() {
-                for message in messages {
-                    match message {
-                        TextInputMessage::Value(s) => state.text_input_value = Some(s),
-                        TextInputMessage::Submit => {
-                            if let Some(normalized_value) = state
-                                .text_input_value
-                                .as_ref()
-                                .and_then(|s| self.param.string_to_normalized_value(s))
-                            {
-                                shell.publish(ParamMessage::BeginSetParameter(self.param.as_ptr()));
-                                self.set_normalized_value(shell, normalized_value);
-                                shell.publish(ParamMessage::EndSetParameter(self.param.as_ptr()));
-                            }
-
-                            // And defocus the text input widget again
-                            state.text_input_value = None;
-                        }
-                    }
-                }
-            } else {
-                state.text_input_value = None;
-            }
-
             return;
         }
 
-        match event {
-            Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left))
-            | Event::Touch(touch::Event::FingerPressed { .. }) => {
-                let bounds = layout.bounds();
-
-                let Some(cursor_position) = cursor.position_over(bounds) else {
-                    return;
-                };
-
-                let click =
-                    mouse::Click::new(cursor_position, mouse::Button::Left, state.last_click);
-                state.last_click = Some(click);
-
-                if state.keyboard_modifiers.alt() {
-                    // Alt+click should not start a drag, instead it should show the text entry
-                    // widget
-                    state.drag_active = false;
-
-                    let current_value = self.param.to_string();
-                    state.text_input_value = Some(current_value.clone());
-
-                    let text_input_state = tree.children[0]
-                        .state
-                        .downcast_mut::<text_input::State<Renderer::Paragraph>>();
-                    text_input_state.select_all();
-                    text_input_state.move_cursor_to_end();
-                    text_input_state.focus();
-                } else if state.keyboard_modifiers.command()
-                    || matches!(click.kind(), mouse::click::Kind::Double)
-                {
-                    // Likewise resetting a parameter should not let you immediately drag it to a new value
-                    state.drag_active = false;
-
-                    shell.publish(ParamMessage::BeginSetParameter(self.param.as_ptr()));
-                    self.set_normalized_value(shell, self.param.default_normalized_value());
-                    shell.publish(ParamMessage::EndSetParameter(self.param.as_ptr()));
-                } else if state.keyboard_modifiers.shift() {
-                    shell.publish(ParamMessage::BeginSetParameter(self.param.as_ptr()));
-                    state.drag_active = true;
-
-                    // When holding down shift while clicking on a parameter we want to
-                    // granuarly edit the parameter without jumping to a new value
-                    state.granular_drag_start_x_value =
-                        Some((cursor_position.x, self.param.modulated_normalized_value()));
-                } else {
-                    shell.publish(ParamMessage::BeginSetParameter(self.param.as_ptr()));
-                    state.drag_active = true;
-
-                    self.set_normalized_value(
-                        shell,
-                        util::remap_rect_x_coordinate(&bounds, cursor_position.x),
-                    );
-                    state.granular_drag_start_x_value = None;
-                }
-            }
-            Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left))
-            | Event::Touch(touch::Event::FingerLifted { .. } | touch::Event::FingerLost { .. }) => {
-                if !state.drag_active {
-                    return;
-                }
-
-                shell.publish(ParamMessage::EndSetParameter(self.param.as_ptr()));
-                state.drag_active = false;
-            }
-            Event::Mouse(mouse::Event::CursorMoved { .. })
-            | Event::Touch(touch::Event::FingerMoved { .. }) => {
-                // Don't do anything when we just reset the parameter because that would be weird
-                if !state.drag_active {
-                    return;
-                }
-
-                let bounds = layout.bounds();
-
-                // If shift is being held then the drag should be more granular instead of
-                // absolute
-                if let Some(cursor_position) = cursor.position() {
-                    if state.keyboard_modifiers.shift() {
-                        let (drag_start_x, drag_start_value) =
-                            *state.granular_drag_start_x_value.get_or_insert_with(|| {
-                                (cursor_position.x, self.param.modulated_normalized_value())
-                            });
-
-                        self.set_normalized_value(
-                            shell,
-                            util::remap_rect_x_coordinate(
-                                &bounds,
-                                util::remap_rect_x_t(&bounds, drag_start_value)
-                                    + (cursor_position.x - drag_start_x) * GRANULAR_DRAG_MULTIPLIER,
-                            ),
-                        );
-                    } else {
-                        state.granular_drag_start_x_value = None;
-
-                        self.set_normalized_value(
-                            shell,
-                            util::remap_rect_x_coordinate(&bounds, cursor_position.x),
-                        );
-                    }
-                }
-            }
-            Event::Keyboard(keyboard::Event::ModifiersChanged(modifiers)) => {
-                state.keyboard_modifiers = *modifiers;
-                let bounds = layout.bounds();
-
-                // If this happens while dragging, snap back to reality uh I mean the current screen
-                // position
-                if state.drag_active
-                    && state.granular_drag_start_x_value.is_some()
-                    && !modifiers.shift()
-                {
-                    state.granular_drag_start_x_value = None;
-
-                    if let Some(cursor_position) = cursor.position() {
-                        self.set_normalized_value(
-                            shell,
-                            util::remap_rect_x_coordinate(&bounds, cursor_position.x),
-                        );
-                    }
-                }
-            }
-            _ => {}
+        let open_text_entry = state.drag.update(
+            self.param,
+            self.mapping,
+            DragBehavior::Absolute,
+            event,
+            layout.bounds(),
+            cursor,
+            shell,
+        );
+        if open_text_entry {
+            state
+                .text_entry
+                .open::<P, Renderer>(self.param, &mut tree.children[0]);
         }
+
+        state.drag.scroll(
+            self.param,
+            self.mapping,
+            self.scroll_to_adjust,
+            event,
+            layout.bounds(),
+            cursor,
+            shell,
+        );
     }
 
     fn mouse_interaction(
@@ -592,27 +440,29 @@ where
         _viewport: &Rectangle,
         _renderer: &Renderer,
     ) -> mouse::Interaction {
-        if cursor.is_over(layout.bounds()) {
-            mouse::Interaction::Pointer
-        } else {
+        if !cursor.is_over(layout.bounds()) {
             mouse::Interaction::default()
+        } else if interaction::is_input_locked() {
+            mouse::Interaction::NotAllowed
+        } else {
+            mouse::Interaction::Pointer
         }
     }
 }
 
-impl<'a, P> ParamSlider<'a, P>
+impl<'a, P, Theme> ParamSlider<'a, P, Theme>
 where
     P: Param + 'a,
+    Theme: Catalog + text_input::Catalog + 'a,
 {
     /// Convert this [`ParamSlider`] into an [`Element`] with the correct message. You should have a
     /// variant on your own message type that wraps around [`ParamMessage`] so you can forward those
     /// messages to
     /// [`IcedEditor::handle_param_message()`][crate::IcedEditor::handle_param_message()].
-    pub fn map<Message, Theme, Renderer, F>(self, f: F) -> Element<'a, Message, Theme, Renderer>
+    pub fn map<Message, Renderer, F>(self, f: F) -> Element<'a, Message, Theme, Renderer>
     where
         Message: 'static,
         F: Fn(ParamMessage) -> Message + 'static,
-        Theme: text_input::Catalog + 'a,
         Renderer: TextRenderer + 'a,
         Renderer::Font: From<crate::Font>,
     {
@@ -620,14 +470,148 @@ where
     }
 }
 
-impl<'a, P, Theme, Renderer> From<ParamSlider<'a, P>> for Element<'a, ParamMessage, Theme, Renderer>
+impl<'a, P, Theme, Renderer> From<ParamSlider<'a, P, Theme>>
+    for Element<'a, ParamMessage, Theme, Renderer>
 where
     P: Param + 'a,
-    Theme: text_input::Catalog + 'a,
+    Theme: Catalog + text_input::Catalog + 'a,
     Renderer: TextRenderer + 'a,
     Renderer::Font: From<crate::Font>,
 {
-    fn from(widget: ParamSlider<'a, P>) -> Self {
+    fn from(widget: ParamSlider<'a, P, Theme>) -> Self {
         Element::new(widget)
     }
 }
+
+/// The possible status of a [`ParamSlider`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    /// The [`ParamSlider`] can be interacted with.
+    Active,
+    /// The [`ParamSlider`] is being hovered.
+    Hovered,
+    /// The [`ParamSlider`] is being dragged, or a value is being typed into it.
+    Dragged,
+    /// The editor's input is locked, see
+    /// [`interaction::is_input_locked()`][crate::interaction::is_input_locked()].
+    Disabled,
+}
+
+/// The appearance of a [`ParamSlider`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Style {
+    /// The [`Background`] of the slider.
+    pub background: Background,
+    /// The color of the slider's border.
+    pub border_color: Color,
+    /// The color of the slider's border while the typed-in value is outside of the parameter's
+    /// range. The value will be clamped when it's submitted.
+    pub out_of_range_color: Color,
+    /// The color of the filled part of the slider.
+    pub fill: Color,
+    /// The color of the band showing the parameter's values across the voices, see
+    /// [`ParamSlider::modulation_range()`].
+    pub modulation: Color,
+    /// The color of the value text outside of the filled part.
+    pub text_color: Color,
+    /// The color of the value text where it overlaps with the filled part.
+    pub filled_text_color: Color,
+}
+
+/// The theme catalog of a [`ParamSlider`].
+pub trait Catalog: Sized {
+    /// The item class of the [`Catalog`].
+    type Class<'a>;
+
+    /// The default class produced by the [`Catalog`].
+    fn default<'a>() -> Self::Class<'a>;
+
+    /// The [`Style`] of a class with the given status.
+    fn style(&self, class: &Self::Class<'_>, status: Status) -> Style;
+}
+
+/// A styling function for a [`ParamSlider`].
+pub type StyleFn<'a, Theme> = Box<dyn Fn(&Theme, Status) -> Style + 'a>;
+
+impl Catalog for Theme {
+    type Class<'a> = StyleFn<'a, Self>;
+
+    fn default<'a>() -> Self::Class<'a> {
+        Box::new(default)
+    }
+
+    fn style(&self, class: &Self::Class<'_>, status: Status) -> Style {
+        class(self, status)
+    }
+}
+
+/// The default style of a [`ParamSlider`]. This only uses the extended palette's semantic colors,
+/// so it works for both light and dark themes.
+pub fn default(theme: &Theme, status: Status) -> Style {
+    let colors = crate::theme::colors(theme);
+
+    let background = match status {
+        Status::Active | Status::Disabled => Color::TRANSPARENT,
+        Status::Hovered => colors.surface,
+        Status::Dragged => colors.surface_hover,
+    };
+    // Locked sliders are faded out, but their values should still be readable
+    let (border_color, fill) = match status {
+        Status::Disabled => (
+            util::mix_colors(colors.background, colors.outline, 0.5),
+            util::mix_colors(colors.background, colors.accent, 0.5),
+        ),
+        _ => (colors.outline, colors.accent),
+    };
+
+    Style {
+        background: Background::Color(background),
+        border_color,
+        out_of_range_color: colors.danger,
+        fill,
+        modulation: fill.scale_alpha(0.35),
+        text_color: colors.text,
+        filled_text_color: colors.accent_text,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nih_plug::prelude::{IntParam, IntRange};
+
+    use super::*;
+    use crate::core::Point;
+    use crate::runtime::user_interface::{Cache, UserInterface};
+
+    #[test]
+    fn scrolling_steps_the_value() {
+        let param = IntParam::new("Voices", 2, IntRange::Linear { min: 0, max: 4 });
+        let mut interface = UserInterface::build(
+            Element::<'_, ParamMessage, Theme, ()>::from(ParamSlider::new(&param)),
+            Size::new(200.0, 200.0),
+            Cache::default(),
+            &mut (),
+        );
+
+        let mut messages = Vec::new();
+        let (_, statuses) = interface.update(
+            &[Event::Mouse(mouse::Event::WheelScrolled {
+                delta: mouse::ScrollDelta::Lines { x: 0.0, y: 1.0 },
+            })],
+            mouse::Cursor::Available(Point::new(90.0, 15.0)),
+            &mut (),
+            &mut crate::core::clipboard::Null,
+            &mut messages,
+        );
+
+        assert_eq!(statuses, [crate::core::event::Status::Captured]);
+        assert!(matches!(
+            messages[..],
+            [
+                ParamMessage::BeginSetParameter(_),
+                ParamMessage::SetParameterNormalized(_, 0.75),
+                ParamMessage::EndSetParameter(_),
+            ]
+        ));
+    }
+}
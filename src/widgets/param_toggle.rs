@@ -0,0 +1,644 @@
+//! A checkbox or latching button for a [`BoolParam`].
+
+use nih_plug::prelude::{BoolParam, Param};
+
+use crate::core::keyboard::key;
+use crate::core::text::Renderer as TextRenderer;
+use crate::core::widget::operation::{Focusable, Operation};
+use crate::core::widget::tree::{self, Tree};
+use crate::core::widget::Id;
+use crate::core::{
+    alignment, keyboard, layout, mouse, renderer, text, touch, Background, Border, Clipboard,
+    Color, Element, Event, Font, Layout, Length, Pixels, Point, Rectangle, Shell, Size, Theme,
+    Widget,
+};
+use crate::interaction;
+use crate::scale;
+
+use super::param_bounds::{ParamBoundsMap, ReportedBounds};
+use super::param_drag::set_normalized_value;
+use super::param_id::param_widget_id;
+use super::{util, ParamMessage};
+
+/// The thickness of this widget's borders.
+const BORDER_WIDTH: f32 = 1.0;
+/// The largest size of a checkbox's box. Smaller toggles shrink the box to fit.
+const CHECKBOX_SIZE: f32 = 16.0;
+/// How far the filled part of a checked box is inset from its border.
+const CHECK_INSET: f32 = 3.0;
+/// The space between a checkbox's box and its label.
+const LABEL_GAP: f32 = 6.0;
+
+/// A toggle for a [`BoolParam`], drawn as a checkbox with a label or as a latching button. Clicking
+/// it flips the parameter in a single automation gesture. After it's been clicked it has keyboard
+/// focus, and Space or Enter flip it again.
+///
+/// The toggle always shows the parameter's current value, so it follows changes made by the host
+/// or by automation without any state of its own.
+pub struct ParamToggle<'a, Theme = crate::Theme>
+where
+    Theme: Catalog,
+{
+    param: &'a BoolParam,
+    id: Id,
+
+    kind: ToggleKind,
+    label: Option<String>,
+    width: Length,
+    height: Length,
+    text_size: Option<Pixels>,
+    font: Option<Font>,
+    bounds_map: Option<ParamBoundsMap>,
+    class: Theme::Class<'a>,
+}
+
+/// How a [`ParamToggle`] is drawn.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ToggleKind {
+    /// A box that's filled while the parameter is on, followed by the label. This is the default.
+    #[default]
+    Checkbox,
+    /// A button with the label in the middle that stays lit while the parameter is on.
+    Button,
+}
+
+/// State for a [`ParamToggle`].
+#[derive(Debug, Default)]
+struct State {
+    /// Whether the toggle has keyboard focus.
+    is_focused: bool,
+    /// This widget's entry in the [`ParamBoundsMap`], if it reports its bounds.
+    bounds: ReportedBounds,
+}
+
+impl Focusable for State {
+    fn is_focused(&self) -> bool {
+        self.is_focused
+    }
+
+    fn focus(&mut self) {
+        self.is_focused = true;
+    }
+
+    fn unfocus(&mut self) {
+        self.is_focused = false;
+    }
+}
+
+impl<'a, Theme> ParamToggle<'a, Theme>
+where
+    Theme: Catalog,
+{
+    pub const DEFAULT_WIDTH: Length = Length::Fixed(180.0);
+    pub const DEFAULT_HEIGHT: Length = Length::Fixed(30.0);
+
+    /// Creates a new [`ParamToggle`] for the given parameter. It's labeled with the parameter's
+    /// name.
+    pub fn new(param: &'a BoolParam) -> Self {
+        Self {
+            param,
+            id: param_widget_id(param.as_ptr()),
+
+            kind: ToggleKind::default(),
+            label: None,
+            width: Self::DEFAULT_WIDTH,
+            height: Self::DEFAULT_HEIGHT,
+            text_size: None,
+            font: None,
+            bounds_map: None,
+            class: Theme::default(),
+        }
+    }
+
+    /// Sets whether the [`ParamToggle`] is drawn as a checkbox or as a latching button.
+    pub fn kind(mut self, kind: ToggleKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    /// Show `label` instead of the parameter's name. An empty label only leaves the checkbox's box
+    /// or an empty button.
+    pub fn label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    /// Sets the width of the [`ParamToggle`].
+    pub fn width(mut self, width: Length) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// Sets the height of the [`ParamToggle`].
+    pub fn height(mut self, height: Length) -> Self {
+        self.height = height;
+        self
+    }
+
+    /// Sets the text size of the [`ParamToggle`].
+    pub fn text_size(mut self, size: Pixels) -> Self {
+        self.text_size = Some(size);
+        self
+    }
+
+    /// Sets the font of the [`ParamToggle`].
+    pub fn font(mut self, font: Font) -> Self {
+        self.font = Some(font);
+        self
+    }
+
+    /// Sets the [`Id`] of the [`ParamToggle`]. By default this is derived from the parameter, see
+    /// the [`param_id`][super::param_id] module.
+    pub fn id(mut self, id: impl Into<Id>) -> Self {
+        self.id = id.into();
+        self
+    }
+
+    /// Write this widget's bounds to the editor's [`ParamBoundsMap`] every time it's drawn, so the
+    /// editor can highlight it. See the [`param_bounds`][super::param_bounds] module.
+    pub fn report_bounds(mut self, report: bool) -> Self {
+        self.bounds_map = if report {
+            ParamBoundsMap::current()
+        } else {
+            None
+        };
+        self
+    }
+
+    /// Sets the style of the [`ParamToggle`].
+    pub fn style(mut self, style: impl Fn(&Theme, Status) -> Style + 'a) -> Self
+    where
+        Theme::Class<'a>: From<StyleFn<'a, Theme>>,
+    {
+        self.class = (Box::new(style) as StyleFn<'a, Theme>).into();
+        self
+    }
+
+    /// Sets the style class of the [`ParamToggle`].
+    pub fn class(mut self, class: impl Into<Theme::Class<'a>>) -> Self {
+        self.class = class.into();
+        self
+    }
+
+    /// Flip the parameter, wrapped in its own automation gesture.
+    fn flip(&self, shell: &mut Shell<'_, ParamMessage>) {
+        let normalized_value = if self.param.modulated_plain_value() {
+            0.0
+        } else {
+            1.0
+        };
+
+        shell.publish(ParamMessage::BeginSetParameter(self.param.as_ptr()));
+        set_normalized_value(self.param, shell, normalized_value);
+        shell.publish(ParamMessage::EndSetParameter(self.param.as_ptr()));
+    }
+
+    /// The checkbox's box within the toggle's `bounds`, at the left and vertically centered.
+    fn checkbox_bounds(bounds: Rectangle) -> Rectangle {
+        let size = CHECKBOX_SIZE.min(bounds.height).min(bounds.width);
+
+        Rectangle {
+            x: bounds.x,
+            y: bounds.center_y() - (size / 2.0),
+            width: size,
+            height: size,
+        }
+    }
+}
+
+impl<Theme, Renderer> Widget<ParamMessage, Theme, Renderer> for ParamToggle<'_, Theme>
+where
+    Theme: Catalog,
+    Renderer: TextRenderer,
+    Renderer::Font: From<crate::Font>,
+{
+    fn tag(&self) -> tree::Tag {
+        tree::Tag::of::<State>()
+    }
+
+    fn state(&self) -> tree::State {
+        tree::State::new(State::default())
+    }
+
+    fn size(&self) -> Size<Length> {
+        Size {
+            width: self.width,
+            height: self.height,
+        }
+    }
+
+    fn layout(
+        &mut self,
+        _tree: &mut Tree,
+        _renderer: &Renderer,
+        limits: &layout::Limits,
+    ) -> layout::Node {
+        layout::atomic(limits, self.width, self.height)
+    }
+
+    fn operate(
+        &mut self,
+        tree: &mut Tree,
+        layout: Layout<'_>,
+        _renderer: &Renderer,
+        operation: &mut dyn Operation,
+    ) {
+        let state = tree.state.downcast_mut::<State>();
+        operation.focusable(Some(&self.id), layout.bounds(), state);
+    }
+
+    fn draw(
+        &self,
+        tree: &Tree,
+        renderer: &mut Renderer,
+        theme: &Theme,
+        _style: &renderer::Style,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        viewport: &Rectangle,
+    ) {
+        let state = tree.state.downcast_ref::<State>();
+        let bounds = layout.bounds();
+        state.bounds.report(
+            self.bounds_map.as_ref(),
+            self.param.as_ptr(),
+            &self.id,
+            bounds,
+        );
+
+        let status = if interaction::is_input_locked() {
+            Status::Disabled
+        } else if cursor.is_over(bounds) {
+            Status::Hovered
+        } else if state.is_focused {
+            Status::Focused
+        } else {
+            Status::Active
+        };
+        let appearance = theme.style(&self.class, status);
+        let is_on = self.param.modulated_plain_value();
+
+        let (text_bounds, align_x, text_color) = match self.kind {
+            ToggleKind::Checkbox => {
+                let box_bounds = Self::checkbox_bounds(bounds);
+                renderer.fill_quad(
+                    renderer::Quad {
+                        bounds: scale::pixel_snap(box_bounds),
+                        border: util::snap_border(appearance.border),
+                        ..Default::default()
+                    },
+                    appearance.background,
+                );
+                if is_on {
+                    renderer.fill_quad(
+                        renderer::Quad {
+                            bounds: scale::pixel_snap(box_bounds.shrink(CHECK_INSET)),
+                            ..Default::default()
+                        },
+                        Background::Color(appearance.checked),
+                    );
+                }
+
+                let label_x = box_bounds.x + box_bounds.width + LABEL_GAP;
+                let text_bounds = Rectangle {
+                    x: label_x,
+                    width: (bounds.x + bounds.width - label_x).max(0.0),
+                    ..bounds
+                };
+
+                (
+                    text_bounds,
+                    alignment::Horizontal::Left,
+                    appearance.text_color,
+                )
+            }
+            ToggleKind::Button => {
+                renderer.fill_quad(
+                    renderer::Quad {
+                        bounds: scale::pixel_snap(bounds),
+                        border: util::snap_border(appearance.border),
+                        ..Default::default()
+                    },
+                    if is_on {
+                        Background::Color(appearance.checked)
+                    } else {
+                        appearance.background
+                    },
+                );
+
+                let text_color = if is_on {
+                    appearance.checked_text_color
+                } else {
+                    appearance.text_color
+                };
+
+                (bounds, alignment::Horizontal::Center, text_color)
+            }
+        };
+
+        let content = match &self.label {
+            Some(label) => label.clone(),
+            None => self.param.name().to_owned(),
+        };
+        if content.is_empty() {
+            return;
+        }
+
+        let text = text::Text {
+            content,
+            font: self
+                .font
+                .map(Renderer::Font::from)
+                .unwrap_or_else(|| renderer.default_font()),
+            size: self.text_size.unwrap_or_else(|| renderer.default_size()),
+            bounds: text_bounds.size(),
+            align_x: align_x.into(),
+            align_y: alignment::Vertical::Center,
+            line_height: Default::default(),
+            shaping: Default::default(),
+            wrapping: text::Wrapping::None,
+        };
+        let anchor_x = match align_x {
+            alignment::Horizontal::Center => text_bounds.center_x(),
+            _ => text_bounds.x,
+        };
+        let position = scale::snap_text(&text, Point::new(anchor_x, text_bounds.center_y()));
+        renderer.fill_text(
+            text,
+            position,
+            text_color,
+            text_bounds.intersection(viewport).unwrap_or(text_bounds),
+        );
+    }
+
+    fn update(
+        &mut self,
+        tree: &mut Tree,
+        event: &Event,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        _renderer: &Renderer,
+        _clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, ParamMessage>,
+        _viewport: &Rectangle,
+    ) {
+        let state = tree.state.downcast_mut::<State>();
+
+        match event {
+            Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left))
+            | Event::Touch(touch::Event::FingerPressed { .. }) => {
+                if cursor.is_over(layout.bounds()) {
+                    state.is_focused = true;
+                    if !interaction::is_input_locked() {
+                        self.flip(shell);
+                    }
+
+                    shell.capture_event();
+                } else {
+                    state.is_focused = false;
+                }
+            }
+            Event::Keyboard(keyboard::Event::KeyPressed {
+                key: keyboard::Key::Named(key::Named::Space | key::Named::Enter),
+                ..
+            }) if state.is_focused && !interaction::is_input_locked() => {
+                self.flip(shell);
+                shell.capture_event();
+            }
+            _ => {}
+        }
+    }
+
+    fn mouse_interaction(
+        &self,
+        _tree: &Tree,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        _viewport: &Rectangle,
+        _renderer: &Renderer,
+    ) -> mouse::Interaction {
+        if !cursor.is_over(layout.bounds()) {
+            mouse::Interaction::default()
+        } else if interaction::is_input_locked() {
+            mouse::Interaction::NotAllowed
+        } else {
+            mouse::Interaction::Pointer
+        }
+    }
+}
+
+impl<'a, Theme> ParamToggle<'a, Theme>
+where
+    Theme: Catalog + 'a,
+{
+    /// Convert this [`ParamToggle`] into an [`Element`] with the correct message. You should have a
+    /// variant on your own message type that wraps around [`ParamMessage`] so you can forward those
+    /// messages to
+    /// [`IcedEditor::handle_param_message()`][crate::IcedEditor::handle_param_message()].
+    pub fn map<Message, Renderer, F>(self, f: F) -> Element<'a, Message, Theme, Renderer>
+    where
+        Message: 'static,
+        F: Fn(ParamMessage) -> Message + 'static,
+        Renderer: TextRenderer + 'a,
+        Renderer::Font: From<crate::Font>,
+    {
+        Element::from(self).map(f)
+    }
+}
+
+impl<'a, Theme, Renderer> From<ParamToggle<'a, Theme>>
+    for Element<'a, ParamMessage, Theme, Renderer>
+where
+    Theme: Catalog + 'a,
+    Renderer: TextRenderer + 'a,
+    Renderer::Font: From<crate::Font>,
+{
+    fn from(widget: ParamToggle<'a, Theme>) -> Self {
+        Element::new(widget)
+    }
+}
+
+/// The possible status of a [`ParamToggle`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    /// The [`ParamToggle`] can be interacted with.
+    Active,
+    /// The [`ParamToggle`] is being hovered.
+    Hovered,
+    /// The [`ParamToggle`] has keyboard focus.
+    Focused,
+    /// The editor's input is locked, see
+    /// [`interaction::is_input_locked()`][crate::interaction::is_input_locked()].
+    Disabled,
+}
+
+/// The appearance of a [`ParamToggle`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Style {
+    /// The [`Background`] of the checkbox's box, or of the button while the parameter is off.
+    pub background: Background,
+    /// The [`Border`] of the checkbox's box or of the button.
+    pub border: Border,
+    /// The color that fills the checkbox's box, or the button's background, while the parameter is
+    /// on.
+    pub checked: Color,
+    /// The color of the label.
+    pub text_color: Color,
+    /// The color of the button's label while the parameter is on.
+    pub checked_text_color: Color,
+}
+
+/// The theme catalog of a [`ParamToggle`].
+pub trait Catalog: Sized {
+    /// The item class of the [`Catalog`].
+    type Class<'a>;
+
+    /// The default class produced by the [`Catalog`].
+    fn default<'a>() -> Self::Class<'a>;
+
+    /// The [`Style`] of a class with the given status.
+    fn style(&self, class: &Self::Class<'_>, status: Status) -> Style;
+}
+
+/// A styling function for a [`ParamToggle`].
+pub type StyleFn<'a, Theme> = Box<dyn Fn(&Theme, Status) -> Style + 'a>;
+
+impl Catalog for Theme {
+    type Class<'a> = StyleFn<'a, Self>;
+
+    fn default<'a>() -> Self::Class<'a> {
+        Box::new(default)
+    }
+
+    fn style(&self, class: &Self::Class<'_>, status: Status) -> Style {
+        class(self, status)
+    }
+}
+
+/// The default style of a [`ParamToggle`]. This only uses the extended palette's semantic colors,
+/// so it works for both light and dark themes.
+pub fn default(theme: &Theme, status: Status) -> Style {
+    let colors = crate::theme::colors(theme);
+
+    let (background, border_color) = match status {
+        Status::Active | Status::Disabled => (colors.background, colors.outline),
+        Status::Hovered => (colors.surface, colors.outline),
+        Status::Focused => (colors.background, colors.accent),
+    };
+    let checked = match status {
+        Status::Disabled => util::mix_colors(colors.background, colors.accent, 0.5),
+        _ => colors.accent,
+    };
+
+    Style {
+        background: Background::Color(background),
+        border: Border {
+            color: border_color,
+            width: BORDER_WIDTH,
+            radius: 2.0.into(),
+        },
+        checked,
+        text_color: colors.text,
+        checked_text_color: colors.accent_text,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::keyboard::{key::Physical, Location, Modifiers};
+
+    /// Send `event` to a toggle for `param` with the cursor at `cursor`, and return the normalized
+    /// values it set and whether every value was wrapped in a gesture.
+    fn send(param: &BoolParam, tree: &mut Tree, event: Event, cursor: Point) -> (Vec<f32>, bool) {
+        let mut toggle = ParamToggle::<Theme>::new(param);
+        let node = layout::Node::new(Size::new(180.0, 30.0));
+        let mut messages = Vec::new();
+        let mut shell = Shell::new(&mut messages);
+        Widget::<ParamMessage, Theme, ()>::update(
+            &mut toggle,
+            tree,
+            &event,
+            Layout::new(&node),
+            mouse::Cursor::Available(cursor),
+            &(),
+            &mut crate::core::clipboard::Null,
+            &mut shell,
+            &node.bounds(),
+        );
+
+        let values = messages
+            .iter()
+            .filter_map(|message| match message {
+                ParamMessage::SetParameterNormalized(_, value) => Some(*value),
+                _ => None,
+            })
+            .collect();
+        let is_gesture = matches!(
+            messages.as_slice(),
+            [] | [
+                ParamMessage::BeginSetParameter(_),
+                ParamMessage::SetParameterNormalized(..),
+                ParamMessage::EndSetParameter(_),
+            ]
+        );
+
+        (values, is_gesture)
+    }
+
+    #[test]
+    fn clicks_and_keys_flip_the_param() {
+        let param = BoolParam::new("Bypass", false);
+        let mut tree =
+            Tree::new(&ParamToggle::<Theme>::new(&param) as &dyn Widget<ParamMessage, Theme, ()>);
+        let press = Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left));
+        let space = Event::Keyboard(keyboard::Event::KeyPressed {
+            key: keyboard::Key::Named(key::Named::Space),
+            modified_key: keyboard::Key::Named(key::Named::Space),
+            physical_key: Physical::Code(key::Code::Space),
+            location: Location::Standard,
+            modifiers: Modifiers::empty(),
+            text: None,
+        });
+
+        // Keys do nothing until the toggle has focus
+        assert_eq!(
+            send(&param, &mut tree, space.clone(), Point::ORIGIN),
+            (vec![], true)
+        );
+        assert_eq!(
+            send(&param, &mut tree, press.clone(), Point::new(10.0, 10.0)),
+            (vec![1.0], true)
+        );
+        // Nothing applies the messages to the parameter here, so it's flipped on again
+        assert_eq!(
+            send(&param, &mut tree, space.clone(), Point::ORIGIN),
+            (vec![1.0], true)
+        );
+
+        // Clicking somewhere else takes the focus away again
+        assert_eq!(
+            send(&param, &mut tree, press, Point::new(300.0, 10.0)),
+            (vec![], true)
+        );
+        assert_eq!(
+            send(&param, &mut tree, space, Point::ORIGIN),
+            (vec![], true)
+        );
+    }
+
+    #[test]
+    fn checkbox_fits_the_toggle() {
+        let bounds = Rectangle::new(Point::new(10.0, 10.0), Size::new(180.0, 30.0));
+        assert_eq!(
+            ParamToggle::<Theme>::checkbox_bounds(bounds),
+            Rectangle::new(Point::new(10.0, 17.0), Size::new(16.0, 16.0))
+        );
+
+        let bounds = Rectangle::new(Point::new(10.0, 10.0), Size::new(180.0, 10.0));
+        assert_eq!(
+            ParamToggle::<Theme>::checkbox_bounds(bounds),
+            Rectangle::new(Point::new(10.0, 10.0), Size::new(10.0, 10.0))
+        );
+    }
+}
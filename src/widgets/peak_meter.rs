@@ -1,85 +1,284 @@
-//! A super simple peak meter widget.
+//! A peak meter widget with a decibel scale, ballistics, and an optional held peak marker.
 
 use crossbeam::atomic::AtomicCell;
 use std::marker::PhantomData;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 use std::time::Duration;
 use std::time::Instant;
 
 use crate::core::text::{self, Paragraph, Renderer as TextRenderer};
 use crate::core::widget::tree::{self, Tree};
+use crate::core::widget::Operation;
 use crate::core::{
-    alignment, layout, mouse, padding, renderer, Background, Border, Color, Element, Font, Layout,
-    Length, Pixels, Point, Rectangle, Size, Widget,
+    alignment, layout, mouse, padding, renderer, Background, Border, Clipboard, Color, Element,
+    Event, Font, Layout, Length, Pixels, Point, Rectangle, Shell, Size, Theme, Widget,
 };
 
+use super::axis::{self, LabelPlacement, TickLevel};
+use super::util;
+use crate::comm::ClipFlag;
+use crate::scale;
+use crate::tick::{self, FrameTick};
+
 /// The thickness of this widget's borders.
 const BORDER_WIDTH: f32 = 1.0;
 /// The thickness of a tick inside of the peak meter's bar.
 const TICK_WIDTH: f32 = 1.0;
 
-/// A simple horizontal peak meter.
+/// How opaque segments that aren't lit are drawn in [`SegmentConfig`] mode.
+const UNLIT_SEGMENT_ALPHA: f32 = 0.15;
+/// The space between the bar and the clip indicator.
+const CLIP_INDICATOR_GAP: f32 = 2.0;
+
+/// The level at the low end of the bar. Everything at or below this level is drawn as silence.
+const MIN_DB: f32 = -90.0;
+/// The level at the high end of the bar.
+const MAX_DB: f32 = 20.0;
+/// The levels that get a labeled tick next to the bar. When the labels don't fit, the medium ones
+/// are left out first.
+const LABELED_TICKS: [(f32, &str, TickLevel); 5] = [
+    (-60.0, "-60", TickLevel::Major),
+    (-24.0, "-24", TickLevel::Major),
+    (-12.0, "-12", TickLevel::Medium),
+    (-6.0, "-6", TickLevel::Medium),
+    (0.0, "0", TickLevel::Major),
+];
+/// The minimum space between two tick labels, in logical pixels.
+const LABEL_GAP: f32 = 3.0;
+
+/// Set once a [`PeakMeter`] has received a `NaN` or infinite level, so that's only reported once.
+static REPORTED_NON_FINITE_LEVEL: AtomicBool = AtomicBool::new(false);
+
+/// A horizontal or vertical peak meter with a decibel scale.
+///
+/// The meter shows the level it's created with. The plugin usually measures the peak level on the
+/// audio thread and stores it in an `AtomicF32`, and the editor reads it every frame through
+/// [`WindowSubs::on_frame`][crate::WindowSubs::on_frame]. With
+/// [`decay()`][Self::decay()] the bar rises instantly but falls at a fixed rate, and
+/// [`hold_time()`][Self::hold_time()] adds a marker for the most recent peak:
 ///
-/// TODO: There are currently no styling options at all
-/// TODO: Vertical peak meter, this is just a proof of concept to fit the gain GUI example.
-pub struct PeakMeter<Message> {
+/// ```ignore
+/// PeakMeter::new(util::gain_to_db(self.peak_meter.load(Ordering::Relaxed)))
+///     .orientation(Orientation::Vertical)
+///     .decay(20.0)
+///     .hold_time(Duration::from_millis(600))
+/// ```
+///
+/// The ballistics advance with the time between frames rather than with every redraw, so they
+/// behave the same regardless of the frame rate and of how often the editor redraws.
+pub struct PeakMeter<'a, Message, Theme = crate::Theme>
+where
+    Theme: Catalog,
+{
     /// The current measured value in decibel.
     current_value_db: f32,
 
+    orientation: Orientation,
+    /// How fast the bar falls in decibel per second, if it doesn't follow the level directly.
+    decay: Option<f32>,
     /// The time the old peak value should remain visible.
     hold_time: Option<Duration>,
+    /// Draw the bar as discrete segments instead of a continuous bar.
+    segments: Option<SegmentConfig>,
+    /// Shows a clip indicator next to the bar that's also lit when this is set.
+    clip_flag: Option<Arc<ClipFlag>>,
 
-    height: Length,
-    width: Length,
+    /// The width, or the orientation's default width if it wasn't set.
+    width: Option<Length>,
+    /// The height, or the orientation's default height if it wasn't set.
+    height: Option<Length>,
     text_size: Option<Pixels>,
     font: Option<Font>,
+    class: Theme::Class<'a>,
 
     /// We don't emit any messages, but iced requires us to define some message type anyways.
     _phantom: PhantomData<Message>,
 }
 
+/// The direction a [`PeakMeter`]'s bar grows in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Orientation {
+    /// The bar grows from left to right, with the tick labels below it. This is the default.
+    #[default]
+    Horizontal,
+    /// The bar grows from the bottom to the top, with the tick labels to its right.
+    Vertical,
+}
+
+impl Orientation {
+    /// The size of a meter that doesn't set its own width or height.
+    fn default_size(self) -> (f32, f32) {
+        match self {
+            Orientation::Horizontal => (180.0, 30.0),
+            Orientation::Vertical => (40.0, 180.0),
+        }
+    }
+
+    /// The length of `bounds` along the meter's axis.
+    fn length(self, bounds: Rectangle) -> f32 {
+        match self {
+            Orientation::Horizontal => bounds.width,
+            Orientation::Vertical => bounds.height,
+        }
+    }
+
+    /// The part of `bounds` that starts `start` pixels from the bar's low end and that's `length`
+    /// pixels long.
+    fn slice(self, bounds: Rectangle, start: f32, length: f32) -> Rectangle {
+        match self {
+            Orientation::Horizontal => Rectangle {
+                x: bounds.x + start,
+                width: length,
+                ..bounds
+            },
+            Orientation::Vertical => Rectangle {
+                y: bounds.y + bounds.height - start - length,
+                height: length,
+                ..bounds
+            },
+        }
+    }
+}
+
 /// State for a [`PeakMeter`].
 #[derive(Debug, Default)]
 struct State {
+    /// The level the bar shows while it's falling at the decay rate, in decibel.
+    decayed_value_db: AtomicCell<Option<f32>>,
     /// The last peak value in decibel.
     held_peak_value_db: AtomicCell<f32>,
     /// When the last peak value was hit.
     last_held_peak_value: AtomicCell<Option<Instant>>,
+    /// The time of the last frame tick, used to compute how far the bar and the held peak fall.
+    last_tick: AtomicCell<Option<Instant>>,
+    /// Whether the meter has drawn a level above 0 dBFS since the clip indicator was last cleared.
+    clipped: AtomicCell<bool>,
+    /// The placement of the tick labels, which only needs to be measured again when the meter's
+    /// size changes.
+    labels: AtomicCell<Option<TickLabels>>,
+}
+
+impl State {
+    /// The level to draw for the current level. The level can only be above the decayed level if
+    /// the meter hasn't been ticked since it received the level.
+    fn value_db(&self, current_value_db: f32) -> f32 {
+        match self.decayed_value_db.load() {
+            Some(decayed_value_db) => decayed_value_db.max(current_value_db),
+            None => current_value_db,
+        }
+    }
+
+    /// The peak value to draw for the current level. The level can only be above the held value
+    /// if the meter hasn't been ticked since it received the level.
+    fn held_peak_value_db(&self, current_value_db: f32) -> f32 {
+        match self.last_held_peak_value.load() {
+            Some(_) => self.held_peak_value_db.load().max(current_value_db),
+            None => current_value_db,
+        }
+    }
+}
+
+/// The measured tick labels of a [`PeakMeter`] with a certain size. Labels that don't fit are
+/// hidden.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct TickLabels {
+    /// The meter's orientation and size, and the text size the labels were measured with.
+    key: (Orientation, Size, f32),
+    /// The size of each of the [`LABELED_TICKS`]' labels along the meter's axis.
+    sizes: [f32; LABELED_TICKS.len()],
+    /// Whether each label fits.
+    visible: [bool; LABELED_TICKS.len()],
 }
 
-impl<Message> PeakMeter<Message> {
+impl<'a, Message, Theme> PeakMeter<'a, Message, Theme>
+where
+    Theme: Catalog,
+{
     /// Creates a new [`PeakMeter`] using the current measurement in decibel. This measurement can
     /// already have some form of smoothing applied to it. This peak slider widget can draw the last
     /// hold value for you.
+    ///
+    /// `NaN` levels are drawn as silence and positive infinity is drawn as clipping. In debug builds
+    /// the first non-finite level is logged, since it usually means something went wrong in the
+    /// plugin's DSP code.
     pub fn new(value_db: f32) -> Self {
+        if (value_db.is_nan() || value_db == f32::INFINITY)
+            && !REPORTED_NON_FINITE_LEVEL.swap(true, Ordering::Relaxed)
+        {
+            nih_plug::nih_debug_assert_failure!(
+                "A peak meter received a non-finite level of {} dB",
+                value_db
+            );
+        }
+
         Self {
-            current_value_db: value_db,
+            current_value_db: util::sanitize_db(value_db),
 
+            orientation: Orientation::default(),
+            decay: None,
             hold_time: None,
+            segments: None,
+            clip_flag: None,
 
-            width: Length::Fixed(180.0),
-            height: Length::Fixed(30.0),
+            width: None,
+            height: None,
             text_size: None,
             font: None,
+            class: Theme::default(),
 
             _phantom: PhantomData,
         }
     }
 
-    /// Keep showing the peak value for a certain amount of time.
+    /// Sets the direction the [`PeakMeter`]'s bar grows in. A vertical meter defaults to being 40
+    /// pixels wide and 180 pixels tall, unless the size is set explicitly.
+    pub fn orientation(mut self, orientation: Orientation) -> Self {
+        self.orientation = orientation;
+        self
+    }
+
+    /// Let the bar fall at `db_per_second` decibel per second instead of following the level
+    /// directly. The bar still jumps up to louder levels immediately. After the hold time, the held
+    /// peak value falls at the same rate. Rates that are zero, negative, or not finite turn this
+    /// off again.
+    pub fn decay(mut self, db_per_second: f32) -> Self {
+        self.decay = (db_per_second.is_finite() && db_per_second > 0.0).then_some(db_per_second);
+        self
+    }
+
+    /// Keep showing the peak value for a certain amount of time. The held peak then falls at the
+    /// [decay rate][Self::decay()], or it drops to the current level if the bar doesn't decay.
     pub fn hold_time(mut self, time: Duration) -> Self {
         self.hold_time = Some(time);
         self
     }
 
+    /// Draw the meter as discrete LED-style segments, like on hardware. The held peak value is
+    /// shown as a single lit segment.
+    pub fn segments(mut self, config: SegmentConfig) -> Self {
+        self.segments = Some(config);
+        self
+    }
+
+    /// Show a clip indicator at the end of the bar. It lights up when the meter shows a level above
+    /// 0 dBFS, and when the audio thread sets `flag`. Because the flag outlives the editor's
+    /// window, the indicator stays lit when the output clipped while the editor was closed.
+    /// Clicking the indicator clears it and the flag. See the [`comm`][crate::comm] module.
+    pub fn clip_flag(mut self, flag: Arc<ClipFlag>) -> Self {
+        self.clip_flag = Some(flag);
+        self
+    }
+
     /// Sets the width of the [`PeakMeter`].
     pub fn width(mut self, width: impl Into<Length>) -> Self {
-        self.width = width.into();
+        self.width = Some(width.into());
         self
     }
 
     /// Sets the height of the [`PeakMeter`].
     pub fn height(mut self, height: impl Into<Length>) -> Self {
-        self.height = height.into();
+        self.height = Some(height.into());
         self
     }
 
@@ -94,11 +293,139 @@ impl<Message> PeakMeter<Message> {
         self.font = Some(font);
         self
     }
+
+    /// Sets the style of the [`PeakMeter`].
+    pub fn style(mut self, style: impl Fn(&Theme) -> Style + 'a) -> Self
+    where
+        Theme::Class<'a>: From<StyleFn<'a, Theme>>,
+    {
+        self.class = (Box::new(style) as StyleFn<'a, Theme>).into();
+        self
+    }
+
+    /// Sets the style class of the [`PeakMeter`].
+    pub fn class(mut self, class: impl Into<Theme::Class<'a>>) -> Self {
+        self.class = class.into();
+        self
+    }
+}
+
+impl<Message, Theme> PeakMeter<'_, Message, Theme>
+where
+    Theme: Catalog,
+{
+    /// The meter's width and height, using the orientation's default size if they weren't set.
+    fn lengths(&self) -> (Length, Length) {
+        let (width, height) = self.orientation.default_size();
+        (
+            self.width.unwrap_or(Length::Fixed(width)),
+            self.height.unwrap_or(Length::Fixed(height)),
+        )
+    }
+
+    /// Splits the meter's `bounds` into the area with the bar and the area with the tick labels.
+    fn split(&self, bounds: Rectangle) -> (Rectangle, Rectangle) {
+        match self.orientation {
+            Orientation::Horizontal => (
+                bounds.shrink(padding::bottom(bounds.height / 2.0)),
+                bounds.shrink(padding::top(bounds.height / 2.0)),
+            ),
+            Orientation::Vertical => (
+                bounds.shrink(padding::right(bounds.width / 2.0)),
+                bounds.shrink(padding::left(bounds.width / 2.0)),
+            ),
+        }
+    }
+
+    /// The bounds of the bar and of the clip indicator, if the meter has one, within the meter's
+    /// `bounds`. The indicator is a square at the loud end of the bar.
+    fn bar_layout(&self, bounds: Rectangle) -> (Rectangle, Option<Rectangle>) {
+        let (bar_bounds, _) = self.split(bounds);
+        if self.clip_flag.is_none() {
+            return (bar_bounds, None);
+        }
+
+        let size = bar_bounds.height.min(bar_bounds.width);
+        match self.orientation {
+            Orientation::Horizontal => {
+                let indicator_bounds = Rectangle {
+                    x: bar_bounds.x + bar_bounds.width - size,
+                    width: size,
+                    ..bar_bounds
+                };
+                let bar_bounds = bar_bounds.shrink(padding::right(
+                    (size + CLIP_INDICATOR_GAP).min(bar_bounds.width),
+                ));
+
+                (bar_bounds, Some(indicator_bounds))
+            }
+            Orientation::Vertical => {
+                let indicator_bounds = Rectangle {
+                    height: size,
+                    ..bar_bounds
+                };
+                let bar_bounds = bar_bounds.shrink(padding::top(
+                    (size + CLIP_INDICATOR_GAP).min(bar_bounds.height),
+                ));
+
+                (bar_bounds, Some(indicator_bounds))
+            }
+        }
+    }
+
+    /// The sizes of the tick labels along the meter's axis and which of them fit, for a tick label
+    /// area of `ticks_bounds`. `positions` are the positions of the [`LABELED_TICKS`] relative to
+    /// the start of that area. The labels are only measured again when the meter's size or the
+    /// text size changed, so drawing the meter every frame doesn't lay out any text.
+    fn tick_labels<Renderer>(
+        &self,
+        state: &State,
+        ticks_bounds: Rectangle,
+        positions: [f32; LABELED_TICKS.len()],
+        font: Renderer::Font,
+        text_size: Pixels,
+    ) -> TickLabels
+    where
+        Renderer: TextRenderer,
+    {
+        let key = (self.orientation, ticks_bounds.size(), text_size.0);
+        if let Some(labels) = state.labels.load().filter(|labels| labels.key == key) {
+            return labels;
+        }
+
+        let sizes = LABELED_TICKS.map(|(_, label, _)| {
+            let size =
+                Renderer::Paragraph::with_text(label_text(label, font, text_size)).min_bounds();
+            match self.orientation {
+                Orientation::Horizontal => size.width,
+                Orientation::Vertical => size.height,
+            }
+        });
+        let length = self.orientation.length(ticks_bounds);
+        let visible = axis::visible_labels(&label_placements(positions, sizes), length, LABEL_GAP)
+            .try_into()
+            .unwrap_or_default();
+
+        let labels = TickLabels {
+            key,
+            sizes,
+            visible,
+        };
+        state.labels.store(Some(labels));
+
+        labels
+    }
+
+    /// Whether the clip indicator is lit.
+    fn is_clipped(&self, state: &State) -> bool {
+        state.clipped.load() || self.clip_flag.as_ref().is_some_and(|flag| flag.is_set())
+    }
 }
 
-impl<Message, Theme, Renderer> Widget<Message, Theme, Renderer> for PeakMeter<Message>
+impl<Message, Theme, Renderer> Widget<Message, Theme, Renderer> for PeakMeter<'_, Message, Theme>
 where
     Message: Clone,
+    Theme: Catalog,
     Renderer: TextRenderer,
     Renderer::Font: From<crate::Font>,
 {
@@ -111,7 +438,7 @@ where
     }
 
     fn size(&self) -> Size<Length> {
-        (self.width, self.height).into()
+        self.lengths().into()
     }
 
     fn layout(
@@ -120,105 +447,187 @@ where
         _renderer: &Renderer,
         limits: &layout::Limits,
     ) -> layout::Node {
-        layout::atomic(limits, self.width, self.height)
+        let (width, height) = self.lengths();
+        layout::atomic(limits, width, height)
+    }
+
+    fn operate(
+        &mut self,
+        tree: &mut Tree,
+        layout: Layout<'_>,
+        _renderer: &Renderer,
+        operation: &mut dyn Operation,
+    ) {
+        tick::operate(self, tree, layout.bounds(), operation);
+    }
+
+    fn update(
+        &mut self,
+        tree: &mut Tree,
+        event: &Event,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        _renderer: &Renderer,
+        _clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Message>,
+        _viewport: &Rectangle,
+    ) {
+        let Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) = event else {
+            return;
+        };
+        let (_, Some(indicator_bounds)) = self.bar_layout(layout.bounds()) else {
+            return;
+        };
+
+        let state = tree.state.downcast_ref::<State>();
+        if cursor.is_over(indicator_bounds) && self.is_clipped(state) {
+            state.clipped.store(false);
+            if let Some(flag) = &self.clip_flag {
+                flag.clear();
+            }
+
+            shell.request_redraw();
+            shell.capture_event();
+        }
+    }
+
+    fn mouse_interaction(
+        &self,
+        tree: &Tree,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        _viewport: &Rectangle,
+        _renderer: &Renderer,
+    ) -> mouse::Interaction {
+        let state = tree.state.downcast_ref::<State>();
+        match self.bar_layout(layout.bounds()) {
+            (_, Some(indicator_bounds))
+                if cursor.is_over(indicator_bounds) && self.is_clipped(state) =>
+            {
+                mouse::Interaction::Pointer
+            }
+            _ => mouse::Interaction::default(),
+        }
     }
 
     fn draw(
         &self,
         tree: &Tree,
         renderer: &mut Renderer,
-        _theme: &Theme,
-        style: &renderer::Style,
+        theme: &Theme,
+        _style: &renderer::Style,
         layout: Layout<'_>,
         _cursor: mouse::Cursor,
         viewport: &Rectangle,
     ) {
         let state = tree.state.downcast_ref::<State>();
+        let appearance = theme.style(&self.class);
+        // With ballistics the bar can be above the current level while it falls. That's advanced in
+        // `FrameTick::tick()`.
+        let value_db = state.value_db(self.current_value_db);
+        if self.current_value_db > 0.0 {
+            state.clipped.store(true);
+        }
 
+        let orientation = self.orientation;
         let bounds = layout.bounds();
-        let bar_bounds = bounds.shrink(padding::bottom(bounds.height / 2.0));
-        let ticks_bounds = bounds.shrink(padding::top(bounds.height / 2.0));
-
-        // We'll draw a simple horizontal for [-90, 20] dB where we'll treat -80 as -infinity, with
-        // a label containing the tick markers below it. If `.hold_time()` was called then we'll
-        // also display the last held value
-        const MIN_TICK: f32 = -90.0;
-        const MAX_TICK: f32 = 20.0;
-        let text_ticks = [-80i32, -60, -40, -20, 0];
+        let (_, ticks_bounds) = self.split(bounds);
+        let (bar_bounds, indicator_bounds) = self.bar_layout(bounds);
+        // The bar's border and ticks sit on physical pixels so they stay sharp at any scale factor
+        let bar_bounds = scale::pixel_snap(bar_bounds);
+        let border_width = scale::snap_stroke(BORDER_WIDTH);
+        // The part of the bar the ticks are drawn in, inside of the border on both sides of the axis
+        let track = match orientation {
+            Orientation::Horizontal => bar_bounds.shrink(padding::vertical(border_width)),
+            Orientation::Vertical => bar_bounds.shrink(padding::horizontal(border_width)),
+        };
+
+        // We'll draw a simple bar for [-90, 20] dB, with the tick markers next to it. If
+        // `.hold_time()` was called then we'll also display the last held value
+        renderer.fill_quad(
+            renderer::Quad {
+                bounds: bar_bounds,
+                ..Default::default()
+            },
+            appearance.background,
+        );
+
         // Draw a tick with one pixel in between, otherwise the bilinear interpolation makes
-        // everything a smeary mess
-        let bar_ticks_start = (bar_bounds.x + BORDER_WIDTH).round() as i32;
-        let bar_ticks_end = (bar_bounds.x + bar_bounds.width - (BORDER_WIDTH * 2.0)).ceil() as i32;
-        let bar_tick_coordinates =
-            (bar_ticks_start..bar_ticks_end).step_by((TICK_WIDTH + 1.0).round() as usize);
-        let db_to_x_coord = |db: f32| {
-            let tick_fraction = (db - MIN_TICK) / (MAX_TICK - MIN_TICK);
+        // everything a smeary mess. Ticks are positioned in pixels from the bar's quiet end.
+        let bar_length = orientation.length(bar_bounds);
+        let bar_ticks_start = BORDER_WIDTH.round() as i32;
+        let bar_ticks_end = (bar_length - (BORDER_WIDTH * 2.0)).ceil() as i32;
+        let db_to_offset = |db: f32| {
+            let tick_fraction = util::db_to_t(db, MIN_DB, MAX_DB);
             bar_ticks_start as f32
                 + ((bar_ticks_end - bar_ticks_start) as f32 * tick_fraction).round()
         };
 
-        for tick_x in bar_tick_coordinates {
-            let tick_fraction =
-                (tick_x - bar_ticks_start) as f32 / (bar_ticks_end - bar_ticks_start) as f32;
-            let tick_db = (tick_fraction * (MAX_TICK - MIN_TICK)) + MIN_TICK;
-            if tick_db > self.current_value_db {
-                break;
-            }
-
-            let tick_bounds = Rectangle {
-                x: tick_x as f32,
-                y: bar_bounds.y + BORDER_WIDTH,
-                width: TICK_WIDTH,
-                height: bar_bounds.height - (BORDER_WIDTH * 2.0),
-            };
+        // The held peak value, if the hold time option has been set. This is advanced in
+        // `FrameTick::tick()`.
+        let held_peak_value_db = self
+            .hold_time
+            .map(|_| state.held_peak_value_db(self.current_value_db));
 
-            let grayscale_color = 0.3 + ((1.0 - tick_fraction) * 0.5);
-            let tick_color = Color::from_rgb(grayscale_color, grayscale_color, grayscale_color);
-            renderer.fill_quad(
-                renderer::Quad {
-                    bounds: tick_bounds,
-                    border: Border {
-                        color: Color::TRANSPARENT,
-                        width: 0.0,
-                        radius: 0.0.into(),
-                    },
-                    ..Default::default()
-                },
-                Background::Color(tick_color),
+        if let Some(segments) = &self.segments {
+            let inner_bounds = bar_bounds.shrink(border_width);
+            segments.draw(
+                renderer,
+                inner_bounds,
+                orientation,
+                (MIN_DB, MAX_DB),
+                value_db,
+                held_peak_value_db,
+                |db| tick_color(&appearance, db, MIN_DB),
             );
-        }
+        } else {
+            let bar_tick_offsets =
+                (bar_ticks_start..bar_ticks_end).step_by((TICK_WIDTH + 1.0).round() as usize);
+            for tick_offset in bar_tick_offsets {
+                let tick_fraction = (tick_offset - bar_ticks_start) as f32
+                    / (bar_ticks_end - bar_ticks_start) as f32;
+                let tick_db = (tick_fraction * (MAX_DB - MIN_DB)) + MIN_DB;
+                if tick_db > value_db {
+                    break;
+                }
 
-        // Draw the hold peak value if the hold time option has been set
-        if let Some(hold_time) = self.hold_time {
-            let now = Instant::now();
-            let mut held_peak_value_db = state.held_peak_value_db.load();
-            let last_peak_value = state.last_held_peak_value.load();
-            if self.current_value_db >= held_peak_value_db
-                || last_peak_value.is_none()
-                || now > last_peak_value.unwrap() + hold_time
-            {
-                state.held_peak_value_db.store(self.current_value_db);
-                state.last_held_peak_value.store(Some(now));
-                held_peak_value_db = self.current_value_db;
+                renderer.fill_quad(
+                    renderer::Quad {
+                        bounds: scale::pixel_snap(orientation.slice(
+                            track,
+                            tick_offset as f32,
+                            TICK_WIDTH,
+                        )),
+                        border: Border {
+                            color: Color::TRANSPARENT,
+                            width: 0.0,
+                            radius: 0.0.into(),
+                        },
+                        ..Default::default()
+                    },
+                    Background::Color(tick_color(&appearance, tick_db, MIN_DB)),
+                );
             }
 
-            renderer.fill_quad(
-                renderer::Quad {
-                    bounds: Rectangle {
-                        x: db_to_x_coord(held_peak_value_db),
-                        y: bar_bounds.y + BORDER_WIDTH,
-                        width: TICK_WIDTH,
-                        height: bar_bounds.height - (BORDER_WIDTH * 2.0),
+            // Draw the hold peak value if the hold time option has been set
+            if let Some(held_peak_value_db) = held_peak_value_db {
+                renderer.fill_quad(
+                    renderer::Quad {
+                        bounds: scale::pixel_snap(orientation.slice(
+                            track,
+                            db_to_offset(held_peak_value_db),
+                            TICK_WIDTH,
+                        )),
+                        border: Border {
+                            color: Color::TRANSPARENT,
+                            width: 0.0,
+                            radius: 0.0.into(),
+                        },
+                        ..Default::default()
                     },
-                    border: Border {
-                        color: Color::TRANSPARENT,
-                        width: 0.0,
-                        radius: 0.0.into(),
-                    },
-                    ..Default::default()
-                },
-                Background::Color(Color::from_rgb(0.3, 0.3, 0.3)),
-            );
+                    Background::Color(appearance.peak_hold),
+                );
+            }
         }
 
         // Draw the bar after the ticks since the first and last tick may overlap with the borders
@@ -226,8 +635,8 @@ where
             renderer::Quad {
                 bounds: bar_bounds,
                 border: Border {
-                    color: Color::BLACK,
-                    width: BORDER_WIDTH,
+                    color: appearance.border_color,
+                    width: border_width,
                     radius: 0.0.into(),
                 },
                 ..Default::default()
@@ -235,6 +644,27 @@ where
             Background::Color(Color::TRANSPARENT),
         );
 
+        if let Some(indicator_bounds) = indicator_bounds {
+            let clipped = self.is_clipped(state);
+
+            renderer.fill_quad(
+                renderer::Quad {
+                    bounds: scale::pixel_snap(indicator_bounds),
+                    border: Border {
+                        color: appearance.border_color,
+                        width: border_width,
+                        radius: 0.0.into(),
+                    },
+                    ..Default::default()
+                },
+                if clipped {
+                    Background::Color(appearance.clip)
+                } else {
+                    appearance.background
+                },
+            );
+        }
+
         let text_size = self
             .text_size
             .unwrap_or_else(|| Pixels((renderer.default_size().0 * 0.7).round()));
@@ -243,18 +673,51 @@ where
             .map(Renderer::Font::from)
             .unwrap_or_else(|| renderer.default_font());
 
-        // Beneath the bar we want to draw the names of the ticks
-        for tick_db in text_ticks {
-            let x_coordinate = db_to_x_coord(tick_db as f32);
+        // Next to the bar we want to draw the names of the ticks. Their positions are relative to
+        // the start of the tick label area, which is its top edge for vertical meters.
+        let positions = LABELED_TICKS.map(|(tick_db, _, _)| match orientation {
+            Orientation::Horizontal => bar_bounds.x + db_to_offset(tick_db) - ticks_bounds.x,
+            Orientation::Vertical => {
+                bar_bounds.y + bar_bounds.height - db_to_offset(tick_db) - ticks_bounds.y
+            }
+        });
+        let labels = self.tick_labels::<Renderer>(state, ticks_bounds, positions, font, text_size);
+        let placements = label_placements(positions, labels.sizes);
+        let labels_length = orientation.length(ticks_bounds);
 
-            renderer.fill_quad(
-                renderer::Quad {
-                    bounds: Rectangle {
-                        x: x_coordinate,
+        for (((_, label, _), placement), visible) in
+            LABELED_TICKS.iter().zip(&placements).zip(labels.visible)
+        {
+            let (tick_bounds, label_position) = match orientation {
+                Orientation::Horizontal => (
+                    Rectangle {
+                        x: ticks_bounds.x + placement.position,
                         y: ticks_bounds.y,
                         width: TICK_WIDTH,
                         height: ticks_bounds.height * 0.3,
                     },
+                    Point {
+                        x: ticks_bounds.x + placement.span(labels_length).0,
+                        y: ticks_bounds.y + (ticks_bounds.height * 0.35),
+                    },
+                ),
+                Orientation::Vertical => (
+                    Rectangle {
+                        x: ticks_bounds.x,
+                        y: ticks_bounds.y + placement.position - TICK_WIDTH,
+                        width: ticks_bounds.width * 0.3,
+                        height: TICK_WIDTH,
+                    },
+                    Point {
+                        x: ticks_bounds.x + (ticks_bounds.width * 0.35),
+                        y: ticks_bounds.y + placement.span(labels_length).0,
+                    },
+                ),
+            };
+
+            renderer.fill_quad(
+                renderer::Quad {
+                    bounds: scale::pixel_snap(tick_bounds),
                     border: Border {
                         color: Color::TRANSPARENT,
                         width: 0.0,
@@ -262,83 +725,535 @@ where
                     },
                     ..Default::default()
                 },
-                Background::Color(Color::from_rgb(0.3, 0.3, 0.3)),
+                Background::Color(appearance.ticks),
             );
 
-            let tick_text = if tick_db == text_ticks[0] {
-                String::from("-inf")
-            } else {
-                tick_db.to_string()
-            };
-            renderer.fill_text(
-                text::Text {
-                    content: tick_text,
-                    font,
-                    size: text_size,
-                    bounds: ticks_bounds.size(),
-                    align_x: alignment::Horizontal::Center.into(),
-                    align_y: alignment::Vertical::Top,
-                    line_height: Default::default(),
-                    shaping: Default::default(),
-                    wrapping: text::Wrapping::None,
-                },
+            if visible {
+                let text = label_text(String::from(*label), font, text_size);
+                let position = scale::snap_text(&text, label_position);
+                renderer.fill_text(text, position, appearance.text_color, *viewport);
+            }
+        }
+
+        // Every proper graph needs a unit label. Horizontal meters have room for it after the 0 dB
+        // label, which is the last one.
+        let zero_db_label = LABELED_TICKS.len() - 1;
+        let zero_db_placement = placements[zero_db_label];
+        if orientation == Orientation::Horizontal && labels.visible[zero_db_label] {
+            // The spacing looks a bit off if we start with a space here so we'll add a little
+            // offset to the x-coordinate instead
+            let text = label_text(String::from("dBFS"), font, text_size);
+            let position = scale::snap_text(
+                &text,
                 Point {
-                    x: x_coordinate,
+                    x: ticks_bounds.x
+                        + zero_db_placement.span(labels_length).1
+                        + (text_size.0 * 0.2),
                     y: ticks_bounds.y + (ticks_bounds.height * 0.35),
                 },
-                style.text_color,
-                *viewport,
             );
+            renderer.fill_text(text, position, appearance.text_color, *viewport);
         }
+    }
+}
 
-        // Every proper graph needs a unit label
-        let zero_db_x_coordinate = db_to_x_coord(0.0);
-
-        let zero_db_text_width = Renderer::Paragraph::with_text(text::Text {
-            content: "0",
-            font,
-            size: text_size,
-            bounds: ticks_bounds.size(),
-            align_x: alignment::Horizontal::Center.into(),
-            align_y: alignment::Vertical::Top,
-            line_height: Default::default(),
-            shaping: Default::default(),
-            wrapping: text::Wrapping::None,
-        })
-        .min_width();
-
-        renderer.fill_text(
-            text::Text {
-                // The spacing looks a bit off if we start with a space here so we'll add a little
-                // offset to the x-coordinate instead
-                content: "dBFS".into(),
-                font,
-                size: text_size,
-                bounds: ticks_bounds.size(),
-                align_x: alignment::Horizontal::Left.into(),
-                align_y: alignment::Vertical::Top,
-                line_height: Default::default(),
-                shaping: Default::default(),
-                wrapping: text::Wrapping::None,
-            },
-            Point {
-                x: zero_db_x_coordinate + (zero_db_text_width / 2.0) + (text_size.0 * 0.2),
-                y: ticks_bounds.y + (ticks_bounds.height * 0.35),
-            },
-            style.text_color,
-            *viewport,
-        );
+impl<Message, Theme> FrameTick for PeakMeter<'_, Message, Theme>
+where
+    Theme: Catalog,
+{
+    /// Lets the bar fall at the decay rate, and starts holding the level when it reaches a new peak
+    /// or when the held peak expired. Both fall by the time elapsed since the last tick.
+    fn tick(&mut self, tree: &mut Tree, now: Instant) {
+        let state = tree.state.downcast_ref::<State>();
+        let last_tick = state.last_tick.swap(Some(now));
+        let fallen_db = |since: Instant| match (self.decay, last_tick) {
+            (Some(decay), Some(last_tick)) => {
+                decay
+                    * now
+                        .saturating_duration_since(since.max(last_tick))
+                        .as_secs_f32()
+            }
+            _ => 0.0,
+        };
+
+        if self.decay.is_some() {
+            let decayed_value_db = match (state.decayed_value_db.load(), last_tick) {
+                (Some(decayed_value_db), Some(last_tick)) => {
+                    (decayed_value_db - fallen_db(last_tick)).max(self.current_value_db)
+                }
+                _ => self.current_value_db,
+            };
+            state.decayed_value_db.store(Some(decayed_value_db));
+        } else {
+            state.decayed_value_db.store(None);
+        }
+
+        let Some(hold_time) = self.hold_time else {
+            return;
+        };
+
+        let held_peak_value_db = state.held_peak_value_db.load();
+        let new_peak = match state.last_held_peak_value.load() {
+            Some(_) if self.current_value_db >= held_peak_value_db => true,
+            // The held peak only starts falling once the hold time is over
+            Some(last_peak) if now > last_peak + hold_time && self.decay.is_some() => {
+                let fallen_peak_db = held_peak_value_db - fallen_db(last_peak + hold_time);
+                if fallen_peak_db > self.current_value_db {
+                    state.held_peak_value_db.store(fallen_peak_db);
+                    false
+                } else {
+                    true
+                }
+            }
+            // Without a decay rate the held peak drops to the current level once it expires
+            Some(last_peak) => now > last_peak + hold_time,
+            None => true,
+        };
+        if new_peak {
+            state.held_peak_value_db.store(self.current_value_db);
+            state.last_held_peak_value.store(Some(now));
+        }
     }
 }
 
-impl<'a, Theme, Message, Renderer> From<PeakMeter<Message>>
+impl<'a, Theme, Message, Renderer> From<PeakMeter<'a, Message, Theme>>
     for Element<'a, Message, Theme, Renderer>
 where
     Message: Clone + 'a,
+    Theme: Catalog + 'a,
     Renderer: TextRenderer + 'a,
     Renderer::Font: From<crate::Font>,
 {
-    fn from(widget: PeakMeter<Message>) -> Self {
+    fn from(widget: PeakMeter<'a, Message, Theme>) -> Self {
         Element::new(widget)
     }
 }
+
+/// A tick label's text with the meter's font and text size.
+fn label_text<Content, Font>(
+    content: Content,
+    font: Font,
+    size: Pixels,
+) -> text::Text<Content, Font> {
+    text::Text {
+        content,
+        font,
+        size,
+        bounds: Size::INFINITE,
+        align_x: alignment::Horizontal::Left.into(),
+        align_y: alignment::Vertical::Top,
+        line_height: Default::default(),
+        shaping: Default::default(),
+        wrapping: text::Wrapping::None,
+    }
+}
+
+/// The placements of the [`LABELED_TICKS`]' labels at `positions` along the meter's axis, with
+/// `sizes` as their sizes along that axis.
+fn label_placements(
+    positions: [f32; LABELED_TICKS.len()],
+    sizes: [f32; LABELED_TICKS.len()],
+) -> [LabelPlacement; LABELED_TICKS.len()] {
+    std::array::from_fn(|index| LabelPlacement {
+        position: positions[index],
+        size: sizes[index],
+        level: LABELED_TICKS[index].2,
+    })
+}
+
+/// Everything above 0 dBFS is clipping, the rest gets a gradient between the low and the high level
+/// colors.
+fn tick_color(appearance: &Style, tick_db: f32, min_db: f32) -> Color {
+    if tick_db > 0.0 {
+        appearance.clip
+    } else {
+        util::mix_colors(
+            appearance.level_low,
+            appearance.level_high,
+            (tick_db - min_db) / -min_db,
+        )
+    }
+}
+
+/// Draws a [`PeakMeter`] as discrete LED-style segments, see [`PeakMeter::segments()`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SegmentConfig {
+    /// The number of segments.
+    pub count: usize,
+    /// The gap between two segments in logical pixels.
+    pub gap: f32,
+    /// The colors of the segments, as the level in decibel where a zone starts and the color of
+    /// the segments in that zone, sorted from low to high. A segment gets the color of the zone
+    /// its lower edge is in, and segments below the first zone get the first zone's color. Without
+    /// any zones the segments use the [`Style`]'s level colors.
+    pub zones: Vec<(f32, Color)>,
+}
+
+impl Default for SegmentConfig {
+    /// Green segments, turning yellow from -18 dBFS and red above 0 dBFS.
+    fn default() -> Self {
+        Self {
+            count: 30,
+            gap: 1.0,
+            zones: vec![
+                (f32::NEG_INFINITY, Color::from_rgb8(0x3c, 0xc8, 0x5a)),
+                (-18.0, Color::from_rgb8(0xf0, 0xc8, 0x28)),
+                (0.0, Color::from_rgb8(0xe8, 0x3c, 0x32)),
+            ],
+        }
+    }
+}
+
+impl SegmentConfig {
+    /// The color of the segment at `index` in a meter spanning `range` in decibel, or `None` if
+    /// there are no zones.
+    fn zone_color(&self, index: usize, (min_db, max_db): (f32, f32)) -> Option<Color> {
+        let start_db = axis::segment_db(index, self.count, min_db, max_db);
+        let (_, first) = self.zones.first()?;
+
+        Some(
+            self.zones
+                .iter()
+                .take_while(|(zone_db, _)| *zone_db <= start_db)
+                .last()
+                .map_or(*first, |(_, color)| *color),
+        )
+    }
+
+    /// Draw the segments within `bounds` for a meter spanning `range` in decibel, lighting them up
+    /// to `level_db` and at `held_peak_db`. The first segment is at the quiet end of the
+    /// `orientation`. `level_color` is used when there are no zones.
+    #[allow(clippy::too_many_arguments)]
+    fn draw<Renderer: renderer::Renderer>(
+        &self,
+        renderer: &mut Renderer,
+        bounds: Rectangle,
+        orientation: Orientation,
+        range: (f32, f32),
+        level_db: f32,
+        held_peak_db: Option<f32>,
+        level_color: impl Fn(f32) -> Color,
+    ) {
+        if self.count == 0 {
+            return;
+        }
+
+        let (min_db, max_db) = range;
+        let gap = util::finite_or(self.gap, 0.0).max(0.0);
+        let segment_length =
+            (orientation.length(bounds) - gap * (self.count - 1) as f32) / self.count as f32;
+        if segment_length <= 0.0 {
+            return;
+        }
+
+        let lit = axis::lit_segments(level_db, self.count, min_db, max_db);
+        let peak = held_peak_db
+            .map(|db| axis::lit_segments(db, self.count, min_db, max_db))
+            .and_then(|lit| lit.checked_sub(1));
+        for index in 0..self.count {
+            let color = self.zone_color(index, range).unwrap_or_else(|| {
+                level_color(axis::segment_db(index, self.count, min_db, max_db))
+            });
+            let is_lit = index < lit || Some(index) == peak;
+            let color = if is_lit {
+                color
+            } else {
+                Color {
+                    a: color.a * UNLIT_SEGMENT_ALPHA,
+                    ..color
+                }
+            };
+
+            // Snapping both edges keeps the segments and the gaps between them sharp
+            renderer.fill_quad(
+                renderer::Quad {
+                    bounds: scale::pixel_snap(orientation.slice(
+                        bounds,
+                        index as f32 * (segment_length + gap),
+                        segment_length,
+                    )),
+                    ..Default::default()
+                },
+                Background::Color(color),
+            );
+        }
+    }
+}
+
+/// The appearance of a [`PeakMeter`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Style {
+    /// The [`Background`] of the meter's bar.
+    pub background: Background,
+    /// The color of the bar's border.
+    pub border_color: Color,
+    /// The color of the lowest level ticks. The ticks fade towards `level_high` as they approach
+    /// 0 dBFS.
+    pub level_low: Color,
+    /// The color of the ticks right below 0 dBFS.
+    pub level_high: Color,
+    /// The color of the ticks above 0 dBFS.
+    pub clip: Color,
+    /// The color of the held peak marker.
+    pub peak_hold: Color,
+    /// The color of the tick markers beneath the bar.
+    pub ticks: Color,
+    /// The color of the tick labels.
+    pub text_color: Color,
+}
+
+/// The theme catalog of a [`PeakMeter`].
+pub trait Catalog: Sized {
+    /// The item class of the [`Catalog`].
+    type Class<'a>;
+
+    /// The default class produced by the [`Catalog`].
+    fn default<'a>() -> Self::Class<'a>;
+
+    /// The [`Style`] of a class.
+    fn style(&self, class: &Self::Class<'_>) -> Style;
+}
+
+/// A styling function for a [`PeakMeter`].
+pub type StyleFn<'a, Theme> = Box<dyn Fn(&Theme) -> Style + 'a>;
+
+impl Catalog for Theme {
+    type Class<'a> = StyleFn<'a, Self>;
+
+    fn default<'a>() -> Self::Class<'a> {
+        Box::new(default)
+    }
+
+    fn style(&self, class: &Self::Class<'_>) -> Style {
+        class(self)
+    }
+}
+
+/// The default style of a [`PeakMeter`]. The level gradient fades from the background towards the
+/// primary color, so it stays readable on both light and dark themes.
+pub fn default(theme: &Theme) -> Style {
+    let palette = theme.extended_palette();
+    let colors = crate::theme::colors(theme);
+
+    Style {
+        background: Background::Color(Color::TRANSPARENT),
+        border_color: colors.outline,
+        level_low: util::mix_colors(colors.surface_hover, colors.accent, 0.25),
+        level_high: palette.primary.strong.color,
+        clip: colors.danger,
+        peak_hold: colors.text,
+        ticks: colors.outline,
+        text_color: colors.text,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn non_finite_levels_are_sanitized() {
+        let level = |value_db| PeakMeter::<(), Theme>::new(value_db).current_value_db;
+
+        assert_eq!(level(f32::NAN), f32::NEG_INFINITY);
+        assert_eq!(level(f32::INFINITY), f32::MAX);
+        assert_eq!(level(f32::NEG_INFINITY), f32::NEG_INFINITY);
+        assert_eq!(level(-0.0), 0.0);
+        assert!(REPORTED_NON_FINITE_LEVEL.load(Ordering::Relaxed));
+    }
+
+    #[test]
+    fn peak_hold_advances_on_ticks() {
+        let start = Instant::now();
+        let at = |ms: u64| start + Duration::from_millis(ms);
+        let hold = Duration::from_millis(100);
+        let mut tree = Tree {
+            state: tree::State::new(State::default()),
+            ..Tree::empty()
+        };
+        let tick = |tree: &mut Tree, value_db, now| {
+            let mut meter = PeakMeter::<(), Theme>::new(value_db).hold_time(hold);
+            meter.tick(tree, now);
+        };
+        let held = |tree: &Tree, value_db| {
+            tree.state
+                .downcast_ref::<State>()
+                .held_peak_value_db(value_db)
+        };
+
+        // Before the first tick the level itself is the peak
+        assert_eq!(held(&tree, -12.0), -12.0);
+
+        tick(&mut tree, -6.0, at(0));
+        tick(&mut tree, -20.0, at(50));
+        assert_eq!(held(&tree, -20.0), -6.0);
+        // A louder level shows up before the next tick
+        assert_eq!(held(&tree, -3.0), -3.0);
+
+        tick(&mut tree, -20.0, at(101));
+        assert_eq!(held(&tree, -20.0), -20.0);
+    }
+
+    #[test]
+    fn ballistics_follow_the_frame_time() {
+        let start = Instant::now();
+        let at = |ms: u64| start + Duration::from_millis(ms);
+        let mut tree = Tree {
+            state: tree::State::new(State::default()),
+            ..Tree::empty()
+        };
+        // Returns the level and the held peak drawn after the tick
+        let tick = |tree: &mut Tree, value_db, now| {
+            let mut meter = PeakMeter::<(), Theme>::new(value_db)
+                .decay(20.0)
+                .hold_time(Duration::from_millis(100));
+            meter.tick(tree, now);
+
+            let state = tree.state.downcast_ref::<State>();
+            (state.value_db(value_db), state.held_peak_value_db(value_db))
+        };
+        let assert_near = |(value_db, peak_db): (f32, f32), expected: (f32, f32)| {
+            assert!(
+                (value_db - expected.0).abs() < 1e-3,
+                "{value_db} != {}",
+                expected.0
+            );
+            assert!(
+                (peak_db - expected.1).abs() < 1e-3,
+                "{peak_db} != {}",
+                expected.1
+            );
+        };
+
+        assert_near(tick(&mut tree, -6.0, at(0)), (-6.0, -6.0));
+        // The bar falls at 20 dB/s while the peak is held
+        assert_near(tick(&mut tree, -40.0, at(50)), (-7.0, -6.0));
+        // The peak only falls for the 50 ms after the hold time
+        assert_near(tick(&mut tree, -40.0, at(150)), (-9.0, -7.0));
+        assert_near(tick(&mut tree, -40.0, at(1150)), (-29.0, -27.0));
+        // Neither falls below the level, and a louder level is shown right away
+        assert_near(tick(&mut tree, -40.0, at(2150)), (-40.0, -40.0));
+        assert_near(tick(&mut tree, -3.0, at(2166)), (-3.0, -3.0));
+    }
+
+    #[test]
+    fn vertical_layout() {
+        let meter = PeakMeter::<(), Theme>::new(-20.0)
+            .orientation(Orientation::Vertical)
+            .clip_flag(Arc::new(ClipFlag::new()));
+        assert_eq!(meter.lengths(), (Length::Fixed(40.0), Length::Fixed(180.0)));
+
+        // The bar fills the left half from the bottom, with the clip indicator at the top
+        let bounds = Rectangle::new(Point::new(10.0, 10.0), Size::new(40.0, 180.0));
+        let (bar_bounds, indicator_bounds) = meter.bar_layout(bounds);
+        assert_eq!(
+            indicator_bounds,
+            Some(Rectangle::new(
+                Point::new(10.0, 10.0),
+                Size::new(20.0, 20.0)
+            ))
+        );
+        assert_eq!(
+            bar_bounds,
+            Rectangle::new(Point::new(10.0, 32.0), Size::new(20.0, 158.0))
+        );
+        assert_eq!(
+            meter.split(bounds).1,
+            Rectangle::new(Point::new(30.0, 10.0), Size::new(20.0, 180.0))
+        );
+        assert_eq!(
+            Orientation::Vertical.slice(bar_bounds, 10.0, 2.0),
+            Rectangle::new(Point::new(10.0, 178.0), Size::new(20.0, 2.0))
+        );
+
+        // Setting a size overrides the orientation's default
+        let meter = meter.width(Length::Fill);
+        assert_eq!(meter.lengths(), (Length::Fill, Length::Fixed(180.0)));
+    }
+
+    #[test]
+    fn clip_flag_outlives_the_window() {
+        let flag = Arc::new(ClipFlag::new());
+        let node = layout::Node::new(Size::new(180.0, 30.0));
+        let layout = Layout::new(&node);
+        let style = renderer::Style {
+            text_color: Color::BLACK,
+        };
+        let meter = |value_db| PeakMeter::<(), Theme>::new(value_db).clip_flag(flag.clone());
+        let draw = |meter: &PeakMeter<'_, (), Theme>, tree: &Tree| {
+            Widget::<(), Theme, ()>::draw(
+                meter,
+                tree,
+                &mut (),
+                &Theme::Dark,
+                &style,
+                layout,
+                mouse::Cursor::Unavailable,
+                &node.bounds(),
+            );
+            meter.is_clipped(tree.state.downcast_ref::<State>())
+        };
+        let click = |meter: &mut PeakMeter<'_, (), Theme>, tree: &mut Tree, x| {
+            let mut messages = Vec::new();
+            let mut shell = Shell::new(&mut messages);
+            Widget::<(), Theme, ()>::update(
+                meter,
+                tree,
+                &Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)),
+                layout,
+                mouse::Cursor::Available(Point::new(x, 5.0)),
+                &(),
+                &mut crate::core::clipboard::Null,
+                &mut shell,
+                &node.bounds(),
+            );
+            shell.is_event_captured()
+        };
+
+        // The output clips while the editor is closed
+        flag.check(1.5);
+
+        // The meter in the reopened editor starts with fresh state, and still shows the clip
+        let mut meter = meter(-20.0);
+        let mut tree = Tree::new(&meter as &dyn Widget<(), Theme, ()>);
+        assert!(draw(&meter, &tree));
+
+        // Clicking the bar does nothing, clicking the indicator at the end clears it
+        assert!(!click(&mut meter, &mut tree, 10.0));
+        assert!(flag.is_set());
+        assert!(click(&mut meter, &mut tree, 170.0));
+        assert!(!flag.is_set());
+        assert!(!draw(&meter, &tree));
+
+        // Levels above 0 dBFS light up the indicator by themselves
+        assert!(draw(&PeakMeter::new(3.0).clip_flag(flag.clone()), &tree));
+        assert!(draw(&meter, &tree));
+        assert!(!flag.is_set());
+    }
+
+    #[test]
+    fn segment_zones() {
+        let (green, yellow, red) = (Color::from_rgb(0.0, 1.0, 0.0), Color::WHITE, Color::BLACK);
+        let config = SegmentConfig {
+            count: 10,
+            gap: 1.0,
+            zones: vec![(-60.0, green), (-20.0, yellow), (0.0, red)],
+        };
+        let range = (-90.0, 10.0);
+        let colors: Vec<_> = (0..10)
+            .map(|index| config.zone_color(index, range).unwrap())
+            .collect();
+
+        // Segments start every 10 dB, and a zone starting exactly at a segment's lower edge
+        // includes that segment
+        assert_eq!(colors[..7], [green; 7]);
+        assert_eq!(colors[7..9], [yellow; 2]);
+        assert_eq!(colors[9], red);
+
+        let no_zones = SegmentConfig {
+            zones: Vec::new(),
+            ..config
+        };
+        assert_eq!(no_zones.zone_color(0, range), None);
+    }
+}
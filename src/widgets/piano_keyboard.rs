@@ -0,0 +1,737 @@
+//! An on-screen piano keyboard for playing notes from the editor.
+//!
+//! NIH-plug's [`GuiContext`][nih_plug::prelude::GuiContext] can only change parameters, so the
+//! keyboard emits [`NoteMessage`]s instead of talking to the host directly. The editor should send
+//! those to the plugin's audio thread, for instance through a lock-free channel, where
+//! [`NoteMessage::to_note_event()`] turns them into [`NoteEvent`]s that can be processed together
+//! with the host's note events or sent to the host with
+//! [`ProcessContext::send_event()`][nih_plug::prelude::ProcessContext::send_event()].
+
+use nih_plug::prelude::NoteEvent;
+
+use crate::core::keyboard::key::{Code, Physical};
+use crate::core::widget::operation::{Focusable, Operation};
+use crate::core::widget::tree::{self, Tree};
+use crate::core::widget::Id;
+use crate::core::{
+    keyboard, layout, mouse, renderer, touch, Background, Border, Clipboard, Color, Element, Event,
+    Layout, Length, Point, Rectangle, Shell, Size, Theme, Widget,
+};
+
+use super::util;
+
+/// The thickness of this widget's borders.
+const BORDER_WIDTH: f32 = 1.0;
+/// The height of the black keys relative to the white keys.
+const BLACK_KEY_HEIGHT: f32 = 0.6;
+/// The width of the black keys relative to the white keys.
+const BLACK_KEY_WIDTH: f32 = 0.6;
+/// The velocity of a click at the very top of a key. Clicking further down plays louder notes.
+const MIN_CLICK_VELOCITY: f32 = 0.1;
+
+/// A note played or released on a [`PianoKeyboard`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NoteMessage {
+    /// A key was pressed. The velocity is in `[0, 1]`.
+    NoteOn { note: u8, velocity: f32 },
+    /// A key was released.
+    NoteOff { note: u8 },
+}
+
+impl NoteMessage {
+    /// The MIDI note number of the key.
+    pub fn note(&self) -> u8 {
+        match *self {
+            NoteMessage::NoteOn { note, .. } | NoteMessage::NoteOff { note } => note,
+        }
+    }
+
+    /// Convert this message to a note event at sample `timing` within the current buffer, on a MIDI
+    /// `channel` in `[0, 16)`.
+    pub fn to_note_event<S>(self, timing: u32, channel: u8) -> NoteEvent<S> {
+        match self {
+            NoteMessage::NoteOn { note, velocity } => NoteEvent::NoteOn {
+                timing,
+                voice_id: None,
+                channel,
+                note,
+                velocity,
+            },
+            NoteMessage::NoteOff { note } => NoteEvent::NoteOff {
+                timing,
+                voice_id: None,
+                channel,
+                note,
+                velocity: 0.0,
+            },
+        }
+    }
+}
+
+/// A piano keyboard spanning one or more octaves that plays notes when its keys are clicked.
+/// Dragging across the keys plays every key the cursor passes over. Clicking further down on a key
+/// plays the note with a higher velocity, unless a fixed velocity is set.
+///
+/// While it has keyboard focus, the keyboard can also be played with a computer keyboard: the
+/// A S D F G H J K L row plays the white keys, the W E T Y U O row above it plays the black keys, and
+/// Z and X shift these keys down and up by an octave. Keys are matched by their physical position,
+/// so this works the same on every keyboard layout.
+pub struct PianoKeyboard<'a, Message, Theme = crate::Theme>
+where
+    Theme: Catalog,
+{
+    on_note: Box<dyn Fn(NoteMessage) -> Message + 'a>,
+    active_notes: &'a [u8],
+    first_note: u8,
+    note_count: u8,
+    velocity: Option<f32>,
+
+    id: Option<Id>,
+    width: Length,
+    height: Length,
+    class: Theme::Class<'a>,
+}
+
+/// State for a [`PianoKeyboard`].
+#[derive(Debug, Default)]
+struct State {
+    is_focused: bool,
+    /// Whether the mouse button was pressed on the keyboard and hasn't been released yet.
+    drag_active: bool,
+    /// The note currently held with the mouse. This is `None` while dragging outside of the keys.
+    mouse_note: Option<u8>,
+    /// The notes currently held with the computer keyboard, along with the keys playing them.
+    keyboard_notes: Vec<(Code, u8)>,
+    /// The number of octaves the computer keyboard has been shifted by with Z and X.
+    keyboard_octave_shift: i8,
+}
+
+impl State {
+    fn is_pressed(&self, note: u8) -> bool {
+        self.mouse_note == Some(note) || self.keyboard_notes.iter().any(|(_, held)| *held == note)
+    }
+}
+
+impl Focusable for State {
+    fn is_focused(&self) -> bool {
+        self.is_focused
+    }
+
+    fn focus(&mut self) {
+        self.is_focused = true;
+    }
+
+    fn unfocus(&mut self) {
+        // Held notes are released on the next event that reaches the widget
+        self.is_focused = false;
+    }
+}
+
+impl<'a, Message, Theme> PianoKeyboard<'a, Message, Theme>
+where
+    Theme: Catalog,
+{
+    /// The default width of the [`PianoKeyboard`].
+    pub const DEFAULT_WIDTH: Length = Length::Fixed(320.0);
+    /// The default height of the [`PianoKeyboard`].
+    pub const DEFAULT_HEIGHT: Length = Length::Fixed(80.0);
+    /// The velocity of notes played with the computer keyboard, unless a fixed velocity is set.
+    pub const DEFAULT_KEYBOARD_VELOCITY: f32 = 0.8;
+
+    /// Creates a new [`PianoKeyboard`] spanning the two octaves starting at C3, MIDI note 48.
+    /// `on_note` is called whenever a key is pressed or released.
+    pub fn new(on_note: impl Fn(NoteMessage) -> Message + 'a) -> Self {
+        let (first_note, note_count) = octave_range(3, 2);
+
+        Self {
+            on_note: Box::new(on_note),
+            active_notes: &[],
+            first_note,
+            note_count,
+            velocity: None,
+
+            id: None,
+            width: Self::DEFAULT_WIDTH,
+            height: Self::DEFAULT_HEIGHT,
+            class: Theme::default(),
+        }
+    }
+
+    /// Show `count` octaves starting at the C of `first_octave`, where octave 4 starts at middle C
+    /// (MIDI note 60). The range is clamped to the MIDI note range.
+    pub fn octaves(mut self, first_octave: i8, count: u8) -> Self {
+        (self.first_note, self.note_count) = octave_range(first_octave, count);
+        self
+    }
+
+    /// Highlight these MIDI notes in addition to the keys pressed on the keyboard itself, for
+    /// instance to show the notes the host is currently playing.
+    pub fn active_notes(mut self, notes: &'a [u8]) -> Self {
+        self.active_notes = notes;
+        self
+    }
+
+    /// Play every note with this velocity in `[0, 1]` instead of deriving it from where the key was
+    /// clicked.
+    pub fn velocity(mut self, velocity: f32) -> Self {
+        self.velocity = Some(velocity.clamp(0.0, 1.0));
+        self
+    }
+
+    /// Sets the [`Id`] of the [`PianoKeyboard`] so it can be focused with a widget operation.
+    pub fn id(mut self, id: impl Into<Id>) -> Self {
+        self.id = Some(id.into());
+        self
+    }
+
+    /// Sets the width of the [`PianoKeyboard`].
+    pub fn width(mut self, width: impl Into<Length>) -> Self {
+        self.width = width.into();
+        self
+    }
+
+    /// Sets the height of the [`PianoKeyboard`].
+    pub fn height(mut self, height: impl Into<Length>) -> Self {
+        self.height = height.into();
+        self
+    }
+
+    /// Sets the style of the [`PianoKeyboard`].
+    pub fn style(mut self, style: impl Fn(&Theme, Status) -> Style + 'a) -> Self
+    where
+        Theme::Class<'a>: From<StyleFn<'a, Theme>>,
+    {
+        self.class = (Box::new(style) as StyleFn<'a, Theme>).into();
+        self
+    }
+
+    /// Sets the style class of the [`PianoKeyboard`].
+    pub fn class(mut self, class: impl Into<Theme::Class<'a>>) -> Self {
+        self.class = class.into();
+        self
+    }
+
+    fn keys(&self, bounds: Rectangle) -> KeyLayout {
+        KeyLayout {
+            bounds,
+            first_note: self.first_note,
+            note_count: self.note_count,
+        }
+    }
+
+    fn note_on(&self, note: u8, velocity: f32) -> Message {
+        (self.on_note)(NoteMessage::NoteOn {
+            note,
+            velocity: self.velocity.unwrap_or(velocity),
+        })
+    }
+
+    fn note_off(&self, note: u8) -> Message {
+        (self.on_note)(NoteMessage::NoteOff { note })
+    }
+
+    /// Release every note held with the computer keyboard.
+    fn release_keyboard_notes(&self, state: &mut State, shell: &mut Shell<'_, Message>) {
+        for (_, note) in state.keyboard_notes.drain(..) {
+            shell.publish(self.note_off(note));
+        }
+    }
+}
+
+impl<'a, Message, Theme, Renderer> Widget<Message, Theme, Renderer>
+    for PianoKeyboard<'a, Message, Theme>
+where
+    Theme: Catalog,
+    Renderer: renderer::Renderer,
+{
+    fn tag(&self) -> tree::Tag {
+        tree::Tag::of::<State>()
+    }
+
+    fn state(&self) -> tree::State {
+        tree::State::new(State::default())
+    }
+
+    fn size(&self) -> Size<Length> {
+        (self.width, self.height).into()
+    }
+
+    fn layout(
+        &mut self,
+        _tree: &mut Tree,
+        _renderer: &Renderer,
+        limits: &layout::Limits,
+    ) -> layout::Node {
+        layout::atomic(limits, self.width, self.height)
+    }
+
+    fn draw(
+        &self,
+        tree: &Tree,
+        renderer: &mut Renderer,
+        theme: &Theme,
+        _style: &renderer::Style,
+        layout: Layout<'_>,
+        _cursor: mouse::Cursor,
+        _viewport: &Rectangle,
+    ) {
+        let state = tree.state.downcast_ref::<State>();
+        let status = if state.is_focused {
+            Status::Focused
+        } else {
+            Status::Active
+        };
+        let appearance = theme.style(&self.class, status);
+
+        let bounds = layout.bounds();
+        let keys = self.keys(bounds);
+        let is_pressed = |note: u8| state.is_pressed(note) || self.active_notes.contains(&note);
+
+        // The black keys are drawn on top of the white keys
+        for black in [false, true] {
+            for note in keys.notes().filter(|note| is_black_key(*note) == black) {
+                let color = match (black, is_pressed(note)) {
+                    (false, false) => appearance.white_key,
+                    (false, true) => appearance.pressed_white_key,
+                    (true, false) => appearance.black_key,
+                    (true, true) => appearance.pressed_black_key,
+                };
+
+                renderer.fill_quad(
+                    renderer::Quad {
+                        bounds: crate::scale::pixel_snap(keys.key_bounds(note)),
+                        border: Border {
+                            color: appearance.separator,
+                            width: crate::scale::snap_stroke(BORDER_WIDTH),
+                            radius: 0.0.into(),
+                        },
+                        ..Default::default()
+                    },
+                    Background::Color(color),
+                );
+            }
+        }
+
+        renderer.fill_quad(
+            renderer::Quad {
+                bounds: crate::scale::pixel_snap(bounds),
+                border: util::snap_border(appearance.border),
+                ..Default::default()
+            },
+            Background::Color(Color::TRANSPARENT),
+        );
+    }
+
+    fn update(
+        &mut self,
+        tree: &mut Tree,
+        event: &Event,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        _renderer: &Renderer,
+        _clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Message>,
+        _viewport: &Rectangle,
+    ) {
+        let state = tree.state.downcast_mut::<State>();
+        let bounds = layout.bounds();
+        let keys = self.keys(bounds);
+
+        // Focus may have been taken away by a widget operation while keys were held
+        if !state.is_focused && !state.keyboard_notes.is_empty() {
+            self.release_keyboard_notes(state, shell);
+        }
+
+        match event {
+            Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left))
+            | Event::Touch(touch::Event::FingerPressed { .. }) => {
+                let Some(position) = cursor.position_over(bounds) else {
+                    if state.is_focused {
+                        state.is_focused = false;
+                        self.release_keyboard_notes(state, shell);
+                    }
+
+                    return;
+                };
+
+                state.is_focused = true;
+                state.drag_active = true;
+                state.mouse_note = keys.note_at(position);
+                if let Some(note) = state.mouse_note {
+                    shell.publish(self.note_on(note, keys.velocity_at(note, position)));
+                }
+
+                shell.capture_event();
+            }
+            Event::Mouse(mouse::Event::CursorMoved { .. })
+            | Event::Touch(touch::Event::FingerMoved { .. })
+                if state.drag_active =>
+            {
+                let note = cursor
+                    .position_over(bounds)
+                    .and_then(|position| Some((keys.note_at(position)?, position)));
+                if note.map(|(note, _)| note) == state.mouse_note {
+                    return;
+                }
+
+                if let Some(previous) = state.mouse_note.take() {
+                    shell.publish(self.note_off(previous));
+                }
+                if let Some((note, position)) = note {
+                    state.mouse_note = Some(note);
+                    shell.publish(self.note_on(note, keys.velocity_at(note, position)));
+                }
+
+                shell.capture_event();
+            }
+            Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left))
+            | Event::Touch(touch::Event::FingerLifted { .. } | touch::Event::FingerLost { .. })
+                if state.drag_active =>
+            {
+                state.drag_active = false;
+                if let Some(note) = state.mouse_note.take() {
+                    shell.publish(self.note_off(note));
+                }
+
+                shell.capture_event();
+            }
+            Event::Keyboard(keyboard::Event::KeyPressed {
+                physical_key: Physical::Code(code),
+                modifiers,
+                ..
+            }) if state.is_focused && !modifiers.command() => {
+                match code {
+                    Code::KeyZ => {
+                        state.keyboard_octave_shift = state.keyboard_octave_shift.saturating_sub(1)
+                    }
+                    Code::KeyX => {
+                        state.keyboard_octave_shift = state.keyboard_octave_shift.saturating_add(1)
+                    }
+                    _ => {
+                        let Some(offset) = key_offset(*code) else {
+                            return;
+                        };
+
+                        // Held keys send repeated key presses
+                        if !state.keyboard_notes.iter().any(|(held, _)| held == code) {
+                            let note = self.first_note as i32
+                                + (state.keyboard_octave_shift as i32 * 12)
+                                + offset as i32;
+                            if let Ok(note @ 0..=127) = u8::try_from(note) {
+                                state.keyboard_notes.push((*code, note));
+                                shell.publish(self.note_on(note, Self::DEFAULT_KEYBOARD_VELOCITY));
+                            }
+                        }
+                    }
+                }
+
+                shell.capture_event();
+            }
+            Event::Keyboard(keyboard::Event::KeyReleased {
+                physical_key: Physical::Code(code),
+                ..
+            }) => {
+                if let Some(index) = state
+                    .keyboard_notes
+                    .iter()
+                    .position(|(held, _)| held == code)
+                {
+                    let (_, note) = state.keyboard_notes.remove(index);
+                    shell.publish(self.note_off(note));
+                    shell.capture_event();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn mouse_interaction(
+        &self,
+        _tree: &Tree,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        _viewport: &Rectangle,
+        _renderer: &Renderer,
+    ) -> mouse::Interaction {
+        if cursor.is_over(layout.bounds()) {
+            mouse::Interaction::Pointer
+        } else {
+            mouse::Interaction::default()
+        }
+    }
+
+    fn operate(
+        &mut self,
+        tree: &mut Tree,
+        layout: Layout<'_>,
+        _renderer: &Renderer,
+        operation: &mut dyn Operation,
+    ) {
+        let state = tree.state.downcast_mut::<State>();
+        operation.focusable(self.id.as_ref(), layout.bounds(), state);
+    }
+}
+
+impl<'a, Message, Theme, Renderer> From<PianoKeyboard<'a, Message, Theme>>
+    for Element<'a, Message, Theme, Renderer>
+where
+    Message: 'a,
+    Theme: Catalog + 'a,
+    Renderer: renderer::Renderer + 'a,
+{
+    fn from(widget: PianoKeyboard<'a, Message, Theme>) -> Self {
+        Element::new(widget)
+    }
+}
+
+/// The first note and the number of notes of `count` octaves starting at `first_octave`. At least
+/// one octave is shown.
+fn octave_range(first_octave: i8, count: u8) -> (u8, u8) {
+    let first_note = ((first_octave as i32 + 1) * 12).clamp(0, 120);
+    let note_count = (count.max(1) as i32 * 12).min(128 - first_note);
+
+    (first_note as u8, note_count as u8)
+}
+
+/// Whether a MIDI note is played with a black key.
+fn is_black_key(note: u8) -> bool {
+    matches!(note % 12, 1 | 3 | 6 | 8 | 10)
+}
+
+/// The number of semitones above the keyboard's first note played by a key on the computer
+/// keyboard, if it plays a note.
+fn key_offset(code: Code) -> Option<u8> {
+    let offset = match code {
+        Code::KeyA => 0,
+        Code::KeyW => 1,
+        Code::KeyS => 2,
+        Code::KeyE => 3,
+        Code::KeyD => 4,
+        Code::KeyF => 5,
+        Code::KeyT => 6,
+        Code::KeyG => 7,
+        Code::KeyY => 8,
+        Code::KeyH => 9,
+        Code::KeyU => 10,
+        Code::KeyJ => 11,
+        Code::KeyK => 12,
+        Code::KeyO => 13,
+        Code::KeyL => 14,
+        _ => return None,
+    };
+
+    Some(offset)
+}
+
+/// The positions of the keys within a [`PianoKeyboard`]'s bounds. The range always starts at a C,
+/// so it never starts with a black key.
+#[derive(Debug, Clone, Copy)]
+struct KeyLayout {
+    bounds: Rectangle,
+    first_note: u8,
+    note_count: u8,
+}
+
+impl KeyLayout {
+    fn notes(&self) -> impl Iterator<Item = u8> {
+        self.first_note..self.first_note + self.note_count
+    }
+
+    fn white_key_width(&self) -> f32 {
+        let white_keys = self.notes().filter(|note| !is_black_key(*note)).count();
+
+        self.bounds.width / white_keys.max(1) as f32
+    }
+
+    /// The number of white keys to the left of `note`.
+    fn white_keys_before(&self, note: u8) -> usize {
+        (self.first_note..note)
+            .filter(|note| !is_black_key(*note))
+            .count()
+    }
+
+    fn key_bounds(&self, note: u8) -> Rectangle {
+        let white_key_width = self.white_key_width();
+
+        if is_black_key(note) {
+            // Black keys sit centered on the edge between the two white keys next to them
+            let width = white_key_width * BLACK_KEY_WIDTH;
+            let edge_x = self.white_keys_before(note) as f32 * white_key_width;
+
+            Rectangle {
+                x: self.bounds.x + edge_x - (width / 2.0),
+                y: self.bounds.y,
+                width,
+                height: self.bounds.height * BLACK_KEY_HEIGHT,
+            }
+        } else {
+            Rectangle {
+                x: self.bounds.x + (self.white_keys_before(note) as f32 * white_key_width),
+                y: self.bounds.y,
+                width: white_key_width,
+                height: self.bounds.height,
+            }
+        }
+    }
+
+    /// The note under a point within the keyboard's bounds. Black keys are on top of the white
+    /// keys, so they're checked first.
+    fn note_at(&self, position: Point) -> Option<u8> {
+        self.notes()
+            .filter(|note| is_black_key(*note))
+            .chain(self.notes().filter(|note| !is_black_key(*note)))
+            .find(|note| self.key_bounds(*note).contains(position))
+    }
+
+    /// The velocity of a click on a key, going up towards the bottom of the key like on a real
+    /// piano.
+    fn velocity_at(&self, note: u8, position: Point) -> f32 {
+        let t = util::remap_rect_y_coordinate(&self.key_bounds(note), position.y);
+
+        ((1.0 - t) * MIN_CLICK_VELOCITY) + t
+    }
+}
+
+/// The possible status of a [`PianoKeyboard`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    /// The [`PianoKeyboard`] can be interacted with.
+    Active,
+    /// The [`PianoKeyboard`] has keyboard focus, so it can be played with the computer keyboard.
+    Focused,
+}
+
+/// The appearance of a [`PianoKeyboard`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Style {
+    /// The color of the white keys.
+    pub white_key: Color,
+    /// The color of the white keys while they're pressed.
+    pub pressed_white_key: Color,
+    /// The color of the black keys.
+    pub black_key: Color,
+    /// The color of the black keys while they're pressed.
+    pub pressed_black_key: Color,
+    /// The color of the lines between the keys.
+    pub separator: Color,
+    /// The [`Border`] around the whole keyboard.
+    pub border: Border,
+}
+
+/// The theme catalog of a [`PianoKeyboard`].
+pub trait Catalog: Sized {
+    /// The item class of the [`Catalog`].
+    type Class<'a>;
+
+    /// The default class produced by the [`Catalog`].
+    fn default<'a>() -> Self::Class<'a>;
+
+    /// The [`Style`] of a class with the given status.
+    fn style(&self, class: &Self::Class<'_>, status: Status) -> Style;
+}
+
+/// A styling function for a [`PianoKeyboard`].
+pub type StyleFn<'a, Theme> = Box<dyn Fn(&Theme, Status) -> Style + 'a>;
+
+impl Catalog for Theme {
+    type Class<'a> = StyleFn<'a, Self>;
+
+    fn default<'a>() -> Self::Class<'a> {
+        Box::new(default)
+    }
+
+    fn style(&self, class: &Self::Class<'_>, status: Status) -> Style {
+        class(self, status)
+    }
+}
+
+/// The default style of a [`PianoKeyboard`]. The keys keep their usual colors on both light and
+/// dark themes, and pressed keys use the primary color.
+pub fn default(theme: &Theme, status: Status) -> Style {
+    let palette = theme.extended_palette();
+    let colors = crate::theme::colors(theme);
+
+    let border_color = match status {
+        Status::Active => colors.outline,
+        Status::Focused => colors.accent,
+    };
+
+    Style {
+        white_key: Color::WHITE,
+        pressed_white_key: palette.primary.weak.color,
+        black_key: Color::from_rgb8(0x20, 0x20, 0x20),
+        pressed_black_key: palette.primary.strong.color,
+        separator: Color::from_rgb8(0x40, 0x40, 0x40),
+        border: Border {
+            color: border_color,
+            width: BORDER_WIDTH,
+            radius: 0.0.into(),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// One octave starting at middle C, with 20 pixel wide white keys.
+    fn one_octave() -> KeyLayout {
+        KeyLayout {
+            bounds: Rectangle::new(Point::new(10.0, 0.0), Size::new(140.0, 100.0)),
+            first_note: 60,
+            note_count: 12,
+        }
+    }
+
+    #[test]
+    fn click_position_to_note() {
+        let keys = one_octave();
+
+        // Below the black keys every click hits a white key
+        assert_eq!(keys.note_at(Point::new(15.0, 90.0)), Some(60));
+        assert_eq!(keys.note_at(Point::new(35.0, 90.0)), Some(62));
+        assert_eq!(keys.note_at(Point::new(75.0, 90.0)), Some(65));
+        assert_eq!(keys.note_at(Point::new(145.0, 90.0)), Some(71));
+
+        // The black keys cover the edges between the white keys
+        assert_eq!(keys.note_at(Point::new(30.0, 10.0)), Some(61));
+        assert_eq!(keys.note_at(Point::new(15.0, 10.0)), Some(60));
+        assert_eq!(keys.note_at(Point::new(130.0, 10.0)), Some(70));
+        // There's no black key between E and F
+        assert_eq!(keys.note_at(Point::new(65.0, 10.0)), Some(64));
+
+        assert_eq!(keys.note_at(Point::new(5.0, 90.0)), None);
+        assert_eq!(keys.note_at(Point::new(155.0, 90.0)), None);
+    }
+
+    #[test]
+    fn velocity_increases_towards_bottom() {
+        let keys = one_octave();
+
+        assert_eq!(
+            keys.velocity_at(60, Point::new(15.0, 0.0)),
+            MIN_CLICK_VELOCITY
+        );
+        assert_eq!(keys.velocity_at(60, Point::new(15.0, 100.0)), 1.0);
+        assert!(keys.velocity_at(61, Point::new(30.0, 59.9)) > 0.99);
+        assert!(keys.velocity_at(60, Point::new(15.0, 50.0)) < 1.0);
+    }
+
+    #[test]
+    fn octave_ranges() {
+        let keyboard = PianoKeyboard::<(), Theme>::new(|_| ());
+        assert_eq!((keyboard.first_note, keyboard.note_count), (48, 24));
+
+        let keyboard = keyboard.octaves(-1, 1);
+        assert_eq!((keyboard.first_note, keyboard.note_count), (0, 12));
+
+        // The highest octave only goes up to G9
+        let keyboard = keyboard.octaves(9, 2);
+        assert_eq!((keyboard.first_note, keyboard.note_count), (120, 8));
+
+        assert_eq!(key_offset(Code::KeyA), Some(0));
+        assert_eq!(key_offset(Code::KeyW), Some(1));
+        assert_eq!(key_offset(Code::KeyK), Some(12));
+        assert_eq!(key_offset(Code::KeyZ), None);
+    }
+}
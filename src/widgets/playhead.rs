@@ -0,0 +1,235 @@
+//! A playhead for waveform and sequencer views that moves smoothly between audio callbacks.
+//!
+//! The audio thread only knows the transport position once per buffer, which would make a playhead
+//! drawn at that position move in visible steps. The plugin instead writes the position to a shared
+//! [`TransportInfo`] from its `process()` function, together with the time it was written. A
+//! [`Playhead`] stored in the state of the widget that shows the time-based content then
+//! extrapolates the current position from that for every frame while the transport is playing, and
+//! draws a vertical line at the matching x-coordinate within the widget's bounds. When the
+//! transport is stopped the line sits exactly at the reported position.
+//!
+//! The extrapolated position never goes past the end of the region the widget shows. It also
+//! never moves backwards by a few samples because the next buffer's reported position is slightly
+//! behind the extrapolated one. Larger jumps backwards, for instance when the transport loops or
+//! the user moves the playhead in the host, are shown right away instead of the line animating
+//! backwards across the view.
+//!
+//! The window needs to be redrawn every frame for the line to move, so set
+//! [`WindowSubs::on_frame`][crate::WindowSubs::on_frame] while [`TransportSnapshot::playing`] is
+//! set.
+
+use crossbeam::atomic::AtomicCell;
+use std::time::{Duration, Instant};
+
+use crate::core::{renderer, Color, Rectangle};
+
+/// How far the reported position may lag behind the extrapolated position before it's treated as
+/// a jump instead of timing jitter.
+const JITTER_TOLERANCE: Duration = Duration::from_millis(50);
+
+/// The transport position, shared between the audio thread and the editor. Wrap this in an `Arc`
+/// and update it from the plugin's `process()` function.
+#[derive(Debug)]
+pub struct TransportInfo {
+    snapshot: AtomicCell<TransportSnapshot>,
+}
+
+/// The transport state as it was last written to a [`TransportInfo`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TransportSnapshot {
+    /// The position in samples at the start of the buffer.
+    pub position_samples: i64,
+    /// The sample rate the position is in.
+    pub sample_rate: f32,
+    /// Whether the transport was playing.
+    pub playing: bool,
+    /// When this was written.
+    pub written_at: Instant,
+}
+
+impl Default for TransportInfo {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TransportInfo {
+    /// A stopped transport at the start of the timeline.
+    pub fn new() -> Self {
+        Self {
+            snapshot: AtomicCell::new(TransportSnapshot {
+                position_samples: 0,
+                sample_rate: 44_100.0,
+                playing: false,
+                written_at: Instant::now(),
+            }),
+        }
+    }
+
+    /// Store the transport state at the start of the current buffer. NIH-plug's `Transport` has
+    /// the position in `pos_samples()` and the sample rate in `sample_rate`.
+    pub fn update(&self, position_samples: i64, sample_rate: f32, playing: bool) {
+        self.update_at(position_samples, sample_rate, playing, Instant::now());
+    }
+
+    /// Like [`update()`][Self::update()], but with an explicit time for when the position was
+    /// current.
+    pub fn update_at(&self, position_samples: i64, sample_rate: f32, playing: bool, at: Instant) {
+        self.snapshot.store(TransportSnapshot {
+            position_samples,
+            sample_rate,
+            playing,
+            written_at: at,
+        });
+    }
+
+    /// The transport state that was last written.
+    pub fn snapshot(&self) -> TransportSnapshot {
+        self.snapshot.load()
+    }
+}
+
+impl TransportSnapshot {
+    /// The position in samples at `now`. This assumes the transport kept playing at the same rate
+    /// since the snapshot was written.
+    pub fn extrapolate(&self, now: Instant) -> f64 {
+        let position = self.position_samples as f64;
+        let known_rate = self.sample_rate.is_finite() && self.sample_rate > 0.0;
+        if !(self.playing && known_rate) {
+            return position;
+        }
+
+        position
+            + now.saturating_duration_since(self.written_at).as_secs_f64() * self.sample_rate as f64
+    }
+}
+
+/// Extrapolates and draws the playhead for a view showing the first `length_samples` samples of
+/// the timeline. See the [module documentation][self].
+#[derive(Debug, Default)]
+pub struct Playhead {
+    length_samples: u64,
+    /// The position that was last returned from [`position()`][Self::position()].
+    shown: AtomicCell<Option<f64>>,
+}
+
+impl Playhead {
+    /// A playhead for a view that shows the first `length_samples` samples of the timeline.
+    pub fn new(length_samples: u64) -> Self {
+        Self {
+            length_samples,
+            shown: AtomicCell::new(None),
+        }
+    }
+
+    /// Change the length of the region shown by the view.
+    pub fn set_length(&mut self, length_samples: u64) {
+        self.length_samples = length_samples;
+    }
+
+    /// The playhead position in samples at `now`, clamped to the region.
+    pub fn position(&self, transport: &TransportSnapshot, now: Instant) -> f64 {
+        let mut position = transport
+            .extrapolate(now)
+            .clamp(0.0, self.length_samples as f64);
+
+        // A position slightly behind the one shown last time only means that the last buffer
+        // arrived a bit late, so the line stays put until the transport catches up
+        if transport.playing {
+            let tolerance = JITTER_TOLERANCE.as_secs_f64() * transport.sample_rate as f64;
+            if let Some(shown) = self.shown.load() {
+                if position < shown && shown - position <= tolerance {
+                    position = shown;
+                }
+            }
+        }
+
+        self.shown.store(Some(position));
+        position
+    }
+
+    /// The x-coordinate within `bounds` for the playhead at `now`.
+    pub fn x(&self, bounds: Rectangle, transport: &TransportSnapshot, now: Instant) -> f32 {
+        let t = if self.length_samples == 0 {
+            0.0
+        } else {
+            self.position(transport, now) / self.length_samples as f64
+        };
+
+        super::util::remap_rect_x_t(&bounds, t as f32)
+    }
+
+    /// Draw the playhead at `now` as a vertical line of `width` logical pixels within `bounds`.
+    /// Call this at the end of the host widget's `draw()` so the line ends up on top.
+    pub fn draw<Renderer: renderer::Renderer>(
+        &self,
+        renderer: &mut Renderer,
+        bounds: Rectangle,
+        transport: &TransportSnapshot,
+        now: Instant,
+        width: f32,
+        color: Color,
+    ) {
+        let x = self.x(bounds, transport, now);
+        let line = Rectangle {
+            x: (x - width / 2.0).clamp(bounds.x, (bounds.x + bounds.width - width).max(bounds.x)),
+            y: bounds.y,
+            width,
+            height: bounds.height,
+        };
+
+        renderer.fill_quad(
+            renderer::Quad {
+                bounds: line,
+                ..Default::default()
+            },
+            color,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extrapolation() {
+        let start = Instant::now();
+        let transport = TransportInfo::new();
+        let playhead = Playhead::new(48_000);
+        let at = |ms: u64| start + Duration::from_millis(ms);
+
+        // Stopped transports don't move
+        transport.update_at(1_000, 48_000.0, false, start);
+        assert_eq!(playhead.position(&transport.snapshot(), at(100)), 1_000.0);
+
+        // Playing transports move with the elapsed time
+        transport.update_at(1_000, 48_000.0, true, start);
+        assert_eq!(playhead.position(&transport.snapshot(), at(10)), 1_480.0);
+        assert_eq!(playhead.position(&transport.snapshot(), at(20)), 1_960.0);
+
+        // A late buffer reports a position slightly behind the extrapolated one
+        transport.update_at(1_900, 48_000.0, true, at(20));
+        assert_eq!(playhead.position(&transport.snapshot(), at(20)), 1_960.0);
+        assert_eq!(playhead.position(&transport.snapshot(), at(30)), 2_380.0);
+
+        // Never past the end of the region
+        assert_eq!(
+            playhead.position(&transport.snapshot(), at(5_000)),
+            48_000.0
+        );
+
+        // Looping back to the start shows up right away
+        transport.update_at(0, 48_000.0, true, at(5_000));
+        assert_eq!(playhead.position(&transport.snapshot(), at(5_000)), 0.0);
+        assert_eq!(playhead.position(&transport.snapshot(), at(5_010)), 480.0);
+
+        // Stopping snaps to the reported position, even when that's slightly behind
+        transport.update_at(470, 48_000.0, false, at(5_010));
+        assert_eq!(playhead.position(&transport.snapshot(), at(5_100)), 470.0);
+
+        let bounds = Rectangle::new(crate::Point::new(10.0, 0.0), crate::Size::new(200.0, 50.0));
+        transport.update_at(12_000, 48_000.0, false, start);
+        assert_eq!(playhead.x(bounds, &transport.snapshot(), start), 60.0);
+    }
+}
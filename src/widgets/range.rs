@@ -0,0 +1,179 @@
+//! A parameter's range and steps, for widgets that need to know more about a parameter than its
+//! current value. This is used to snap values, to draw tick marks and scale labels, and to decide
+//! whether a widget fills from its start or from the middle.
+//!
+//! All of the built-in parameter widgets go through [`ParamRange`] instead of inspecting the
+//! parameter themselves, so sliders, knobs, and other widgets treat the same parameter the same
+//! way. Custom widgets should do the same.
+
+use nih_plug::prelude::{Param, ParamPtr};
+
+/// Parameters whose default value lies within this distance of the middle of their range are shown
+/// as bipolar parameters, see [`ParamRange::bipolar_origin()`].
+const BIPOLAR_TOLERANCE: f32 = 0.05;
+
+/// A snapshot of a parameter's range. This works the same way for continuous and stepped
+/// parameters, and it can be created from both a parameter reference and a [`ParamPtr`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ParamRange {
+    /// The plain value at a normalized value of `0.0`. This is larger than `max` for parameters
+    /// with a reversed range. Booleans and enums use `0.0` and `1.0` or the variant index.
+    pub min: f32,
+    /// The plain value at a normalized value of `1.0`.
+    pub max: f32,
+    /// The parameter's default value, normalized.
+    pub default_normalized_value: f32,
+    /// The number of steps between the parameter's discrete values, or `None` for continuous
+    /// parameters. A parameter with `n` steps has `n + 1` values.
+    pub step_count: Option<usize>,
+}
+
+impl ParamRange {
+    /// Get the range of a parameter.
+    pub fn of<P: Param>(param: &P) -> Self {
+        // SAFETY: The pointer is created from a reference, so the parameter is still alive
+        unsafe { Self::from_ptr(param.as_ptr()) }
+    }
+
+    /// Get the range of a parameter through its type erased pointer.
+    ///
+    /// # Safety
+    ///
+    /// The parameter `param` points to needs to still be alive, just like with the functions on
+    /// [`ParamPtr`].
+    pub unsafe fn from_ptr(param: ParamPtr) -> Self {
+        Self {
+            min: param.preview_plain(0.0),
+            max: param.preview_plain(1.0),
+            default_normalized_value: param.default_normalized_value(),
+            step_count: param.step_count(),
+        }
+    }
+
+    /// Whether the parameter only has discrete values.
+    pub fn is_stepped(&self) -> bool {
+        self.step_count.is_some()
+    }
+
+    /// The distance between two adjacent discrete values in normalized terms, or `None` for
+    /// continuous parameters. Parameters with a single value have a step size of `1.0`.
+    pub fn step_size(&self) -> Option<f32> {
+        self.step_count
+            .map(|step_count| 1.0 / step_count.max(1) as f32)
+    }
+
+    /// Round a normalized value to the nearest discrete value. Continuous parameters are only
+    /// clamped to `[0, 1]`.
+    pub fn snap(&self, normalized_value: f32) -> f32 {
+        let normalized_value = normalized_value.clamp(0.0, 1.0);
+        match self.step_size() {
+            Some(step_size) => ((normalized_value / step_size).round() * step_size).min(1.0),
+            None => normalized_value,
+        }
+    }
+
+    /// The normalized values of all of the parameter's discrete values, in order. Continuous
+    /// parameters only get their two ends.
+    pub fn values(&self) -> Vec<f32> {
+        let steps = self.step_count.unwrap_or(1).max(1);
+
+        (0..=steps).map(|step| step as f32 / steps as f32).collect()
+    }
+
+    /// The normalized value widgets should fill from if the parameter is bipolar, like a pan or a
+    /// detune amount. That's the case for continuous parameters with a default value in the middle
+    /// of their range. Other parameters fill from the start of the widget, since filling from the
+    /// middle looks jarring for them.
+    pub fn bipolar_origin(&self) -> Option<f32> {
+        let is_centered = (self.default_normalized_value - 0.5).abs() <= BIPOLAR_TOLERANCE;
+
+        (!self.is_stepped() && is_centered).then_some(self.default_normalized_value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nih_plug::prelude::{
+        BoolParam, Enum, EnumParam, FloatParam, FloatRange, IntParam, IntRange,
+    };
+
+    #[derive(Debug, PartialEq)]
+    enum Shape {
+        Sine,
+        Triangle,
+        Square,
+    }
+
+    impl Enum for Shape {
+        fn variants() -> &'static [&'static str] {
+            &["Sine", "Triangle", "Square"]
+        }
+
+        fn ids() -> Option<&'static [&'static str]> {
+            None
+        }
+
+        fn to_index(self) -> usize {
+            self as usize
+        }
+
+        fn from_index(index: usize) -> Self {
+            match index {
+                0 => Shape::Sine,
+                1 => Shape::Triangle,
+                _ => Shape::Square,
+            }
+        }
+    }
+
+    #[test]
+    fn continuous_params() {
+        let pan = FloatParam::new(
+            "Pan",
+            0.0,
+            FloatRange::Linear {
+                min: -1.0,
+                max: 1.0,
+            },
+        );
+        let range = ParamRange::of(&pan);
+
+        assert_eq!((range.min, range.max), (-1.0, 1.0));
+        assert!(!range.is_stepped());
+        assert_eq!(range.step_size(), None);
+        assert_eq!(range.snap(0.33), 0.33);
+        assert_eq!(range.snap(1.5), 1.0);
+        assert_eq!(range.values(), [0.0, 1.0]);
+        assert_eq!(range.bipolar_origin(), Some(0.5));
+
+        let gain = FloatParam::new("Gain", 0.0, FloatRange::Linear { min: 0.0, max: 2.0 });
+        assert_eq!(ParamRange::of(&gain).bipolar_origin(), None);
+    }
+
+    #[test]
+    fn stepped_params() {
+        let octave = IntParam::new("Octave", 0, IntRange::Linear { min: -2, max: 2 });
+        let range = ParamRange::of(&octave);
+
+        // A centered default doesn't make a stepped parameter bipolar
+        assert_eq!((range.min, range.max), (-2.0, 2.0));
+        assert_eq!(range.step_count, Some(4));
+        assert_eq!(range.step_size(), Some(0.25));
+        assert_eq!(range.snap(0.3), 0.25);
+        assert_eq!(range.values(), [0.0, 0.25, 0.5, 0.75, 1.0]);
+        assert_eq!(range.bipolar_origin(), None);
+
+        let bypass = BoolParam::new("Bypass", false);
+        let range = ParamRange::of(&bypass);
+        assert_eq!(range.step_count, Some(1));
+        assert_eq!(range.snap(0.6), 1.0);
+        assert_eq!(range.values(), [0.0, 1.0]);
+
+        let shape = EnumParam::new("Shape", Shape::Triangle);
+        let range = unsafe { ParamRange::from_ptr(shape.as_ptr()) };
+        assert_eq!((range.min, range.max), (0.0, 2.0));
+        assert_eq!(range.default_normalized_value, 0.5);
+        assert_eq!(range.values(), [0.0, 0.5, 1.0]);
+    }
+}
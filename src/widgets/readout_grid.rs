@@ -0,0 +1,664 @@
+//! A compact grid of numeric readouts for editing many parameters at once, like the send levels of
+//! a matrix mixer.
+
+use nih_plug::prelude::Param;
+
+use crate::core::keyboard::key::Named;
+use crate::core::text::Renderer as TextRenderer;
+use crate::core::widget::tree::{self, Tree};
+use crate::core::widget::Operation;
+use crate::core::{
+    alignment, keyboard, layout, mouse, renderer, text, touch, Background, Border, Clipboard,
+    Color, Element, Event, Font, Layout, Length, Pixels, Point, Rectangle, Shell, Size, Theme,
+    Widget,
+};
+use crate::interaction::{self, ScrollToAdjust};
+use crate::scale;
+use crate::widget::text_input;
+
+use super::mapping::DisplayMapping;
+use super::param_drag::{
+    self, DragBehavior, ParamDrag, TextEntry, GRANULAR_DRAG_MULTIPLIER, SCROLL_LINE_DELTA,
+};
+use super::range::ParamRange;
+use super::ParamMessage;
+
+/// The thickness of a cell's border.
+const BORDER_WIDTH: f32 = 1.0;
+
+/// A grid of small editable readouts, one per parameter. Every cell has the same size and uses the
+/// same formatting and drag sensitivity, so the values line up in columns.
+///
+/// Each cell is edited like the other parameter widgets, see the [`param_drag`] module. Dragging a
+/// cell up or down changes its value, and the drag stays on that cell even when the cursor moves
+/// over other cells. Double clicking resets a cell, and Alt+clicking opens a text entry for it.
+///
+/// Clicking a cell also focuses it for keyboard input. Tab and Shift+Tab move the focus to the
+/// next and previous cells in reading order, the arrow keys change the focused cell's value, Enter
+/// opens its text entry, and Escape removes the focus.
+///
+/// Rows don't need to have the same length. Shorter rows leave empty space at the end.
+pub struct ReadoutGrid<'a, P: Param, Theme = crate::Theme>
+where
+    Theme: Catalog,
+{
+    rows: Vec<Vec<&'a P>>,
+
+    cell_width: f32,
+    cell_height: f32,
+    spacing: f32,
+    drag_distance: f32,
+    show_units: bool,
+    text_size: Option<Pixels>,
+    font: Option<Font>,
+    scroll_to_adjust: Option<ScrollToAdjust>,
+    class: Theme::Class<'a>,
+}
+
+/// State for a [`ReadoutGrid`].
+#[derive(Debug, Default)]
+struct State {
+    /// Dragging, clicking, and scrolling for each cell, in reading order.
+    cells: Vec<ParamDrag>,
+    /// The index of the cell that receives keyboard input.
+    focused: Option<usize>,
+    /// The index of the cell the text entry is open for.
+    text_entry_cell: Option<usize>,
+    /// The text field for typing in a value. This stores its state in the widget's only child.
+    text_entry: TextEntry,
+}
+
+impl<'a, P, Theme> ReadoutGrid<'a, P, Theme>
+where
+    P: Param,
+    Theme: Catalog,
+{
+    pub const DEFAULT_CELL_WIDTH: f32 = 44.0;
+    pub const DEFAULT_CELL_HEIGHT: f32 = 18.0;
+    pub const DEFAULT_SPACING: f32 = 2.0;
+    /// How far a cell needs to be dragged up or down to sweep the parameter's entire range.
+    pub const DEFAULT_DRAG_DISTANCE: f32 = 200.0;
+
+    /// Creates a new [`ReadoutGrid`] from rows of parameters.
+    pub fn new<R>(rows: impl IntoIterator<Item = R>) -> Self
+    where
+        R: IntoIterator<Item = &'a P>,
+    {
+        Self {
+            rows: rows
+                .into_iter()
+                .map(|row| row.into_iter().collect())
+                .collect(),
+
+            cell_width: Self::DEFAULT_CELL_WIDTH,
+            cell_height: Self::DEFAULT_CELL_HEIGHT,
+            spacing: Self::DEFAULT_SPACING,
+            drag_distance: Self::DEFAULT_DRAG_DISTANCE,
+            show_units: false,
+            text_size: None,
+            font: None,
+            scroll_to_adjust: None,
+            class: Theme::default(),
+        }
+    }
+
+    /// Sets the size of every cell in the [`ReadoutGrid`].
+    pub fn cell_size(mut self, width: f32, height: f32) -> Self {
+        self.cell_width = width.max(0.0);
+        self.cell_height = height.max(0.0);
+        self
+    }
+
+    /// Sets the space between the [`ReadoutGrid`]'s cells.
+    pub fn spacing(mut self, spacing: f32) -> Self {
+        self.spacing = spacing.max(0.0);
+        self
+    }
+
+    /// Sets how far a cell needs to be dragged up or down to sweep the parameter's entire range.
+    pub fn drag_distance(mut self, distance: f32) -> Self {
+        self.drag_distance = distance;
+        self
+    }
+
+    /// Include the parameters' units in the readouts. This is disabled by default to keep the
+    /// cells small.
+    pub fn show_units(mut self, show_units: bool) -> Self {
+        self.show_units = show_units;
+        self
+    }
+
+    /// Sets the text size of the [`ReadoutGrid`].
+    pub fn text_size(mut self, size: Pixels) -> Self {
+        self.text_size = Some(size);
+        self
+    }
+
+    /// Sets the font of the [`ReadoutGrid`].
+    pub fn font(mut self, font: Font) -> Self {
+        self.font = Some(font);
+        self
+    }
+
+    /// Sets when scrolling over the [`ReadoutGrid`] changes a cell's value. This overrides the
+    /// [`InteractionSettings`][crate::InteractionSettings]' setting, so it can be used to keep
+    /// hover scrolling for a widget even when [`ScrollToAdjust::Guarded`] is the default, or to
+    /// only guard the widgets inside of a scrollable panel.
+    pub fn scroll_to_adjust(mut self, scroll_to_adjust: ScrollToAdjust) -> Self {
+        self.scroll_to_adjust = Some(scroll_to_adjust);
+        self
+    }
+
+    /// Sets the style of the [`ReadoutGrid`].
+    pub fn style(mut self, style: impl Fn(&Theme, Status) -> Style + 'a) -> Self
+    where
+        Theme::Class<'a>: From<StyleFn<'a, Theme>>,
+    {
+        self.class = (Box::new(style) as StyleFn<'a, Theme>).into();
+        self
+    }
+
+    /// Sets the style class of the [`ReadoutGrid`].
+    pub fn class(mut self, class: impl Into<Theme::Class<'a>>) -> Self {
+        self.class = class.into();
+        self
+    }
+
+    /// The number of columns, which is the length of the longest row.
+    fn column_count(&self) -> usize {
+        self.rows.iter().map(Vec::len).max().unwrap_or(0)
+    }
+
+    /// Every cell's parameter and bounds, in reading order.
+    fn cells(&self, bounds: Rectangle) -> Vec<(&'a P, Rectangle)> {
+        let mut cells = Vec::new();
+        for (row, params) in self.rows.iter().enumerate() {
+            for (column, param) in params.iter().enumerate() {
+                let position = Point::new(
+                    bounds.x + (column as f32 * (self.cell_width + self.spacing)),
+                    bounds.y + (row as f32 * (self.cell_height + self.spacing)),
+                );
+
+                cells.push((
+                    *param,
+                    Rectangle::new(position, Size::new(self.cell_width, self.cell_height)),
+                ));
+            }
+        }
+
+        cells
+    }
+
+    /// Move a parameter one step up or down from the keyboard.
+    fn nudge(param: &P, up: bool, granular: bool, shell: &mut Shell<'_, ParamMessage>) {
+        let step = match ParamRange::of(param).step_size() {
+            Some(step_size) => step_size,
+            None if granular => SCROLL_LINE_DELTA * GRANULAR_DRAG_MULTIPLIER,
+            None => SCROLL_LINE_DELTA,
+        };
+        let delta = if up { step } else { -step };
+        let normalized_value = (param.unmodulated_normalized_value() + delta).clamp(0.0, 1.0);
+
+        shell.publish(ParamMessage::BeginSetParameter(param.as_ptr()));
+        param_drag::set_normalized_value(param, shell, normalized_value);
+        shell.publish(ParamMessage::EndSetParameter(param.as_ptr()));
+    }
+}
+
+impl<'a, P, Theme, Renderer> Widget<ParamMessage, Theme, Renderer> for ReadoutGrid<'a, P, Theme>
+where
+    P: Param,
+    Theme: Catalog + text_input::Catalog,
+    Renderer: TextRenderer,
+    Renderer::Font: From<crate::Font>,
+{
+    fn tag(&self) -> tree::Tag {
+        tree::Tag::of::<State>()
+    }
+
+    fn state(&self) -> tree::State {
+        tree::State::new(State::default())
+    }
+
+    fn children(&self) -> Vec<Tree> {
+        // One child to store text input state, shared by all cells
+        vec![TextEntry::tree::<Theme, Renderer>()]
+    }
+
+    fn size(&self) -> Size<Length> {
+        Size {
+            width: Length::Shrink,
+            height: Length::Shrink,
+        }
+    }
+
+    fn layout(
+        &mut self,
+        _tree: &mut Tree,
+        _renderer: &Renderer,
+        limits: &layout::Limits,
+    ) -> layout::Node {
+        let extent = |count: usize, cell_size: f32| {
+            if count == 0 {
+                0.0
+            } else {
+                (count as f32 * cell_size) + ((count - 1) as f32 * self.spacing)
+            }
+        };
+        let size = Size::new(
+            extent(self.column_count(), self.cell_width),
+            extent(self.rows.len(), self.cell_height),
+        );
+
+        layout::Node::new(limits.resolve(Length::Shrink, Length::Shrink, size))
+    }
+
+    fn operate(
+        &mut self,
+        tree: &mut Tree,
+        layout: Layout<'_>,
+        _renderer: &Renderer,
+        operation: &mut dyn Operation,
+    ) {
+        let state = tree.state.downcast_ref::<State>();
+        let cells = self.cells(layout.bounds());
+        if let Some((_, cell_bounds)) = state.text_entry_cell.and_then(|index| cells.get(index)) {
+            state.text_entry.operate::<Renderer>(
+                &mut tree.children[0],
+                cell_bounds.shrink(BORDER_WIDTH),
+                operation,
+            );
+        }
+    }
+
+    fn draw(
+        &self,
+        tree: &Tree,
+        renderer: &mut Renderer,
+        theme: &Theme,
+        _style: &renderer::Style,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        viewport: &Rectangle,
+    ) {
+        let state = tree.state.downcast_ref::<State>();
+        let text_size = self.text_size.unwrap_or_else(|| renderer.default_size());
+        let font = self
+            .font
+            .map(Renderer::Font::from)
+            .unwrap_or_else(|| renderer.default_font());
+
+        for (index, (param, cell_bounds)) in self.cells(layout.bounds()).into_iter().enumerate() {
+            let Some(clip_bounds) = cell_bounds.intersection(viewport) else {
+                continue;
+            };
+
+            let is_editing = state.text_entry_cell == Some(index) && state.text_entry.is_open();
+            let is_dragging = state.cells.get(index).is_some_and(ParamDrag::is_dragging);
+            let status = if interaction::is_input_locked() {
+                Status::Disabled
+            } else if is_dragging || is_editing {
+                Status::Dragged
+            } else if state.focused == Some(index) {
+                Status::Focused
+            } else if cursor.is_over(cell_bounds) {
+                Status::Hovered
+            } else {
+                Status::Active
+            };
+            let appearance = <Theme as Catalog>::style(theme, &self.class, status);
+            let border_color = if is_editing && state.text_entry.is_out_of_range(param) {
+                appearance.out_of_range_color
+            } else {
+                appearance.border_color
+            };
+
+            let frame = scale::pixel_snap(cell_bounds);
+            let border_width = scale::snap_stroke(BORDER_WIDTH);
+            renderer.fill_quad(
+                renderer::Quad {
+                    bounds: frame,
+                    border: Border {
+                        color: border_color,
+                        width: border_width,
+                        radius: 0.0.into(),
+                    },
+                    ..Default::default()
+                },
+                appearance.background,
+            );
+
+            let inner_bounds = frame.shrink(border_width);
+            if is_editing {
+                state.text_entry.draw(
+                    &tree.children[0],
+                    renderer,
+                    theme,
+                    inner_bounds,
+                    self.font,
+                    self.text_size,
+                    cursor,
+                    viewport,
+                );
+
+                continue;
+            }
+
+            let text = text::Text {
+                content: param.normalized_value_to_string(
+                    param.modulated_normalized_value(),
+                    self.show_units,
+                ),
+                font,
+                size: text_size,
+                bounds: inner_bounds.size(),
+                align_x: alignment::Horizontal::Center.into(),
+                align_y: alignment::Vertical::Center,
+                line_height: text::LineHeight::Relative(1.0),
+                shaping: Default::default(),
+                wrapping: text::Wrapping::None,
+            };
+            let position = scale::snap_text(&text, inner_bounds.center());
+            renderer.fill_text(text, position, appearance.text_color, clip_bounds);
+        }
+    }
+
+    fn update(
+        &mut self,
+        tree: &mut Tree,
+        event: &Event,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        renderer: &Renderer,
+        clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, ParamMessage>,
+        viewport: &Rectangle,
+    ) {
+        let state = tree.state.downcast_mut::<State>();
+        let cells = self.cells(layout.bounds());
+        state.cells.resize_with(cells.len(), ParamDrag::default);
+        if state.focused.is_some_and(|index| index >= cells.len()) {
+            state.focused = None;
+        }
+
+        if let Some(index) = state.text_entry_cell {
+            match cells.get(index) {
+                Some(&(param, cell_bounds)) if state.text_entry.is_open() => {
+                    state.text_entry.update::<P, Theme, Renderer>(
+                        param,
+                        &mut tree.children[0],
+                        event,
+                        cell_bounds.shrink(BORDER_WIDTH),
+                        self.font,
+                        self.text_size,
+                        cursor,
+                        renderer,
+                        clipboard,
+                        shell,
+                        viewport,
+                    );
+
+                    return;
+                }
+                _ => state.text_entry_cell = None,
+            }
+        }
+
+        if let Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left))
+        | Event::Touch(touch::Event::FingerPressed { .. }) = event
+        {
+            let focused = cells
+                .iter()
+                .position(|(_, cell_bounds)| cursor.is_over(*cell_bounds));
+            if focused != state.focused {
+                state.focused = focused;
+                shell.request_redraw();
+            }
+        }
+
+        // Every cell sees every event, but only the cell a drag started on is dragged
+        let mut open_text_entry = None;
+        for (index, (&(param, cell_bounds), drag)) in cells.iter().zip(&mut state.cells).enumerate()
+        {
+            if drag.update(
+                param,
+                DisplayMapping::new(),
+                DragBehavior::Vertical(self.drag_distance),
+                event,
+                cell_bounds,
+                cursor,
+                shell,
+            ) {
+                open_text_entry = Some(index);
+            }
+
+            drag.scroll(
+                param,
+                DisplayMapping::new(),
+                self.scroll_to_adjust,
+                event,
+                cell_bounds,
+                cursor,
+                shell,
+            );
+        }
+
+        if let (
+            Some(focused),
+            Event::Keyboard(keyboard::Event::KeyPressed {
+                key: keyboard::Key::Named(key),
+                modifiers,
+                ..
+            }),
+        ) = (state.focused, event)
+        {
+            let (param, _) = cells[focused];
+            let granular = interaction::current().is_fine_adjust(*modifiers);
+            match key {
+                Named::Tab => {
+                    let offset = if modifiers.shift() {
+                        cells.len() - 1
+                    } else {
+                        1
+                    };
+                    state.focused = Some((focused + offset) % cells.len());
+                }
+                Named::ArrowUp | Named::ArrowRight if !interaction::is_input_locked() => {
+                    Self::nudge(param, true, granular, shell)
+                }
+                Named::ArrowDown | Named::ArrowLeft if !interaction::is_input_locked() => {
+                    Self::nudge(param, false, granular, shell)
+                }
+                Named::Enter if !interaction::is_input_locked() => open_text_entry = Some(focused),
+                Named::Escape => state.focused = None,
+                _ => return,
+            }
+
+            shell.capture_event();
+            shell.request_redraw();
+        }
+
+        if let Some(index) = open_text_entry {
+            let (param, _) = cells[index];
+            state.focused = Some(index);
+            state.text_entry_cell = Some(index);
+            state
+                .text_entry
+                .open::<P, Renderer>(param, &mut tree.children[0]);
+        }
+    }
+
+    fn mouse_interaction(
+        &self,
+        tree: &Tree,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        _viewport: &Rectangle,
+        _renderer: &Renderer,
+    ) -> mouse::Interaction {
+        let state = tree.state.downcast_ref::<State>();
+        let is_dragging = state.cells.iter().any(ParamDrag::is_dragging);
+        let is_over_cell = self
+            .cells(layout.bounds())
+            .iter()
+            .any(|(_, cell_bounds)| cursor.is_over(*cell_bounds));
+
+        if is_over_cell && interaction::is_input_locked() {
+            mouse::Interaction::NotAllowed
+        } else if is_dragging || is_over_cell {
+            mouse::Interaction::ResizingVertically
+        } else {
+            mouse::Interaction::default()
+        }
+    }
+}
+
+impl<'a, P, Theme> ReadoutGrid<'a, P, Theme>
+where
+    P: Param + 'a,
+    Theme: Catalog + text_input::Catalog + 'a,
+{
+    /// Convert this [`ReadoutGrid`] into an [`Element`] with the correct message. You should have a
+    /// variant on your own message type that wraps around [`ParamMessage`] so you can forward those
+    /// messages to
+    /// [`IcedEditor::handle_param_message()`][crate::IcedEditor::handle_param_message()].
+    pub fn map<Message, Renderer, F>(self, f: F) -> Element<'a, Message, Theme, Renderer>
+    where
+        Message: 'static,
+        F: Fn(ParamMessage) -> Message + 'static,
+        Renderer: TextRenderer + 'a,
+        Renderer::Font: From<crate::Font>,
+    {
+        Element::from(self).map(f)
+    }
+}
+
+impl<'a, P, Theme, Renderer> From<ReadoutGrid<'a, P, Theme>>
+    for Element<'a, ParamMessage, Theme, Renderer>
+where
+    P: Param + 'a,
+    Theme: Catalog + text_input::Catalog + 'a,
+    Renderer: TextRenderer + 'a,
+    Renderer::Font: From<crate::Font>,
+{
+    fn from(widget: ReadoutGrid<'a, P, Theme>) -> Self {
+        Element::new(widget)
+    }
+}
+
+/// The possible status of a cell in a [`ReadoutGrid`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    /// The cell can be interacted with.
+    Active,
+    /// The cell is being hovered.
+    Hovered,
+    /// The cell has keyboard focus.
+    Focused,
+    /// The cell is being dragged, or a value is being typed into it.
+    Dragged,
+    /// The editor's input is locked, see
+    /// [`interaction::is_input_locked()`][crate::interaction::is_input_locked()].
+    Disabled,
+}
+
+/// The appearance of a cell in a [`ReadoutGrid`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Style {
+    /// The [`Background`] of the cell.
+    pub background: Background,
+    /// The color of the cell's border.
+    pub border_color: Color,
+    /// The color of the cell's border while the typed-in value is outside of the parameter's range.
+    /// The value will be clamped when it's submitted.
+    pub out_of_range_color: Color,
+    /// The color of the value text.
+    pub text_color: Color,
+}
+
+/// The theme catalog of a [`ReadoutGrid`].
+pub trait Catalog: Sized {
+    /// The item class of the [`Catalog`].
+    type Class<'a>;
+
+    /// The default class produced by the [`Catalog`].
+    fn default<'a>() -> Self::Class<'a>;
+
+    /// The [`Style`] of a class with the given status.
+    fn style(&self, class: &Self::Class<'_>, status: Status) -> Style;
+}
+
+/// A styling function for a [`ReadoutGrid`].
+pub type StyleFn<'a, Theme> = Box<dyn Fn(&Theme, Status) -> Style + 'a>;
+
+impl Catalog for Theme {
+    type Class<'a> = StyleFn<'a, Self>;
+
+    fn default<'a>() -> Self::Class<'a> {
+        Box::new(default)
+    }
+
+    fn style(&self, class: &Self::Class<'_>, status: Status) -> Style {
+        class(self, status)
+    }
+}
+
+/// The default style of a [`ReadoutGrid`]. This only uses the extended palette's semantic colors,
+/// so it works for both light and dark themes.
+pub fn default(theme: &Theme, status: Status) -> Style {
+    let colors = crate::theme::colors(theme);
+
+    let (background, border_color) = match status {
+        Status::Active => (colors.surface, colors.surface_hover),
+        Status::Hovered => (colors.surface_hover, colors.surface_hover),
+        Status::Focused => (colors.surface, colors.accent),
+        Status::Dragged => (colors.surface_hover, colors.accent),
+        Status::Disabled => (colors.surface, colors.surface),
+    };
+
+    Style {
+        background: Background::Color(background),
+        border_color,
+        out_of_range_color: colors.danger,
+        text_color: colors.text,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nih_plug::prelude::{FloatParam, FloatRange};
+
+    #[test]
+    fn cell_layout() {
+        let params: Vec<_> = (0..5)
+            .map(|i| {
+                FloatParam::new(
+                    format!("Send {i}"),
+                    0.0,
+                    FloatRange::Linear { min: 0.0, max: 1.0 },
+                )
+            })
+            .collect();
+        let grid: ReadoutGrid<_> = ReadoutGrid::new([&params[..3], &params[3..]])
+            .cell_size(40.0, 20.0)
+            .spacing(2.0);
+
+        // The shorter row leaves its last column empty
+        assert_eq!(grid.column_count(), 3);
+        let cells = grid.cells(Rectangle::new(
+            Point::new(10.0, 10.0),
+            Size::new(500.0, 500.0),
+        ));
+        assert_eq!(cells.len(), 5);
+        assert_eq!(
+            cells[1].1,
+            Rectangle::new(Point::new(52.0, 10.0), Size::new(40.0, 20.0))
+        );
+        assert_eq!(
+            cells[4].1,
+            Rectangle::new(Point::new(52.0, 32.0), Size::new(40.0, 20.0))
+        );
+        assert!(std::ptr::eq(cells[4].0, &params[4]));
+    }
+}
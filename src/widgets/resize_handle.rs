@@ -6,26 +6,127 @@ use crate::core::mouse;
 use crate::core::renderer;
 use crate::core::widget::{tree, Tree};
 use crate::core::{
-    Border, Clipboard, Color, Element, Length, Point, Rectangle, Shadow, Shell, Size, Vector, Widget,
+    Background, Clipboard, Color, Element, Length, Point, Rectangle, Shell, Size, Theme, Vector,
+    Widget,
 };
 
-/// A resize handle placed at the bottom right of the window that lets you resize the window.
+/// The most rows of quads a [`ResizeHandle`]'s triangle is drawn with.
+const MAX_TRIANGLE_ROWS: usize = 512;
+/// How long the grip drawn on an edge handle is, relative to the handle's size.
+const GRIP_LENGTH: f32 = 3.0;
+/// How thick the grip drawn on an edge handle is, relative to the handle's size.
+const GRIP_THICKNESS: f32 = 0.2;
+
+/// A resize handle placed at an edge or a corner of the window that lets you resize the window. By
+/// default it's a triangle in the bottom right corner, see [`ResizeHandle::with_edge()`] for the
+/// other edges.
 ///
 /// This widget should be rendered on top of other UI elements (last in the layout tree) to ensure
 /// it receives mouse events properly.
-pub struct ResizeHandle<Message> {
+pub struct ResizeHandle<'a, Message, Theme = crate::Theme>
+where
+    Theme: Catalog,
+{
+    /// The edge or corner of the window this handle resizes
+    edge: ResizeEdge,
     /// The size of the handle in logical pixels
     size: f32,
-    /// The color of the triangle
-    color: Color,
+    /// The space between the handle and the window's edges, see
+    /// [`ResizeHandle::padding()`]
+    padding: Vector,
+    /// The style class used to draw the triangle
+    class: Theme::Class<'a>,
     /// Minimum window width
     min_width: f32,
     /// Minimum window height
     min_height: f32,
+    /// Maximum window width
+    max_width: f32,
+    /// Maximum window height
+    max_height: f32,
     /// Current window size (needed for drag calculations)
     current_size: Size,
     /// Callback to emit the new window size when dragging
     on_resize: Box<dyn Fn(Size) -> Message>,
+    /// Only emit `on_resize` once, when the mouse button is released
+    resize_on_release: bool,
+    /// Callback to emit the window size a drag would result in while `resize_on_release` holds
+    /// back `on_resize`
+    on_preview: Option<Box<dyn Fn(Size) -> Message>>,
+}
+
+/// The edge or corner of the window a [`ResizeHandle`] sits at.
+///
+/// Plugin windows can't move themselves, so the host keeps the window's top left corner where it
+/// is. Dragging a left or top handle outwards still grows the window, but the opposite edge is the
+/// one that moves.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ResizeEdge {
+    /// The top edge, changing the window's height.
+    Top,
+    /// The bottom edge, changing the window's height.
+    Bottom,
+    /// The left edge, changing the window's width.
+    Left,
+    /// The right edge, changing the window's width.
+    Right,
+    /// The top left corner.
+    TopLeft,
+    /// The top right corner.
+    TopRight,
+    /// The bottom left corner.
+    BottomLeft,
+    /// The bottom right corner. This is the default.
+    #[default]
+    BottomRight,
+}
+
+impl ResizeEdge {
+    /// How much the window's width and height change when the cursor moves one pixel right and
+    /// down. Handles on the right and bottom grow the window, handles on the left and top shrink
+    /// it, and edge handles leave the other axis alone.
+    fn growth(self) -> Vector {
+        match self {
+            ResizeEdge::Top => Vector::new(0.0, -1.0),
+            ResizeEdge::Bottom => Vector::new(0.0, 1.0),
+            ResizeEdge::Left => Vector::new(-1.0, 0.0),
+            ResizeEdge::Right => Vector::new(1.0, 0.0),
+            ResizeEdge::TopLeft => Vector::new(-1.0, -1.0),
+            ResizeEdge::TopRight => Vector::new(1.0, -1.0),
+            ResizeEdge::BottomLeft => Vector::new(-1.0, 1.0),
+            ResizeEdge::BottomRight => Vector::new(1.0, 1.0),
+        }
+    }
+
+    /// Whether this is one of the corners, which are drawn as a triangle.
+    fn is_corner(self) -> bool {
+        let growth = self.growth();
+        growth.x != 0.0 && growth.y != 0.0
+    }
+
+    /// The directional resize cursor for this edge.
+    fn interaction(self) -> mouse::Interaction {
+        match self {
+            ResizeEdge::Top | ResizeEdge::Bottom => mouse::Interaction::ResizingVertically,
+            ResizeEdge::Left | ResizeEdge::Right => mouse::Interaction::ResizingHorizontally,
+            ResizeEdge::TopLeft | ResizeEdge::BottomRight => {
+                mouse::Interaction::ResizingDiagonallyDown
+            }
+            ResizeEdge::TopRight | ResizeEdge::BottomLeft => {
+                mouse::Interaction::ResizingDiagonallyUp
+            }
+        }
+    }
+
+    /// Whether `point` is over a handle for this edge covering `bounds`. Corner handles only
+    /// respond to the triangle in their corner, edge handles to their entire bounds.
+    fn contains(self, bounds: Rectangle, point: Point) -> bool {
+        if self.is_corner() {
+            intersects_triangle(bounds, mirror_point(bounds, self, point))
+        } else {
+            bounds.contains(point)
+        }
+    }
 }
 
 /// Internal state for tracking drag operations
@@ -43,19 +144,14 @@ struct State {
     last_emitted_size: Size,
 }
 
-impl<Message> ResizeHandle<Message> {
+impl<'a, Message, Theme> ResizeHandle<'a, Message, Theme>
+where
+    Theme: Catalog,
+{
     /// The default size of the resize handle in logical pixels
     const DEFAULT_SIZE: f32 = 20.0;
 
-    /// The default color of the resize handle (semi-transparent gray)
-    const DEFAULT_COLOR: Color = Color {
-        r: 0.5,
-        g: 0.5,
-        b: 0.5,
-        a: 0.5,
-    };
-
-    /// Create a new resize handle.
+    /// Create a new resize handle for the bottom right corner of the window.
     ///
     /// # Parameters
     /// - `on_resize`: Callback that receives the new window `Size` when the user drags the handle
@@ -65,25 +161,81 @@ impl<Message> ResizeHandle<Message> {
     /// ResizeHandle::new(|size| Message::ResizeWindow(size))
     /// ```
     pub fn new(current_size: Size, on_resize: impl Fn(Size) -> Message + 'static) -> Self {
+        Self::with_edge(ResizeEdge::BottomRight, current_size, on_resize)
+    }
+
+    /// Create a new resize handle for an edge or a corner of the window. Corner handles are
+    /// `size` by `size` triangles, while the handles for the top and bottom edges fill the
+    /// available width and the handles for the left and right edges fill the available height.
+    /// Place the handle at that edge of the window yourself, for instance in a `Stack`:
+    ///
+    /// ```ignore
+    /// stack![
+    ///     content,
+    ///     container(ResizeHandle::with_edge(ResizeEdge::Right, size, Message::ResizeWindow))
+    ///         .align_right(Length::Fill),
+    /// ]
+    /// ```
+    pub fn with_edge(
+        edge: ResizeEdge,
+        current_size: Size,
+        on_resize: impl Fn(Size) -> Message + 'static,
+    ) -> Self {
         Self {
+            edge,
             size: Self::DEFAULT_SIZE,
-            color: Self::DEFAULT_COLOR,
+            padding: Vector::ZERO,
+            class: Theme::default(),
             min_width: 400.0,
             min_height: 300.0,
+            max_width: f32::INFINITY,
+            max_height: f32::INFINITY,
             current_size,
             on_resize: Box::new(on_resize),
+            resize_on_release: false,
+            on_preview: None,
         }
     }
 
-    /// Set the size of the handle in logical pixels (default: 20.0)
+    /// Set the size of the handle in logical pixels (default: 20.0). For edge handles this is the
+    /// handle's thickness.
     pub fn size(mut self, size: f32) -> Self {
         self.size = size;
         self
     }
 
-    /// Set the color of the triangle
-    pub fn color(mut self, color: Color) -> Self {
-        self.color = color;
+    /// Move the handle `x` logical pixels away from the window's left or right edge and `y`
+    /// logical pixels away from its top or bottom edge (default: 0.0). The handle's layout grows
+    /// by the padding and the triangle or grip sits in the part away from the window's edges, so it
+    /// doesn't cover a scrollbar running along them. Padding along an axis an edge handle doesn't
+    /// resize is ignored.
+    pub fn padding(mut self, x: f32, y: f32) -> Self {
+        self.padding = Vector::new(x.max(0.0), y.max(0.0));
+        self
+    }
+
+    /// Set a fixed color for the triangle or the grip, overriding the theme's style
+    pub fn color(self, color: Color) -> Self
+    where
+        Theme::Class<'a>: From<StyleFn<'a, Theme>>,
+    {
+        self.style(move |_, _| Style {
+            background: Background::Color(color),
+        })
+    }
+
+    /// Set the style of the handle
+    pub fn style(mut self, style: impl Fn(&Theme, Status) -> Style + 'a) -> Self
+    where
+        Theme::Class<'a>: From<StyleFn<'a, Theme>>,
+    {
+        self.class = (Box::new(style) as StyleFn<'a, Theme>).into();
+        self
+    }
+
+    /// Set the style class of the handle
+    pub fn class(mut self, class: impl Into<Theme::Class<'a>>) -> Self {
+        self.class = class.into();
         self
     }
 
@@ -93,16 +245,62 @@ impl<Message> ResizeHandle<Message> {
         self.min_height = height;
         self
     }
+
+    /// Set the maximum window size (default: unbounded)
+    pub fn max_size(mut self, width: f32, height: f32) -> Self {
+        self.max_width = width;
+        self.max_height = height;
+        self
+    }
+
+    /// Only emit `on_resize` once, with the final size, when the mouse button is released (default:
+    /// false). Some hosts reconfigure the plugin on every resize request, so this avoids sending
+    /// them one for every step of the drag. Use [`on_preview()`][Self::on_preview()] to still
+    /// follow the drag in the editor.
+    pub fn resize_on_release(mut self, enabled: bool) -> Self {
+        self.resize_on_release = enabled;
+        self
+    }
+
+    /// Set a callback that receives the size the window would get if the drag ended now, whenever
+    /// that changes during a drag. This is only called when
+    /// [`resize_on_release()`][Self::resize_on_release()] is enabled, so the editor can for
+    /// instance show the new size without asking the host to resize the window yet.
+    pub fn on_preview(mut self, on_preview: impl Fn(Size) -> Message + 'static) -> Self {
+        self.on_preview = Some(Box::new(on_preview));
+        self
+    }
+
+    /// The padding along the axes this handle resizes.
+    fn inset(&self) -> Vector {
+        let growth = self.edge.growth();
+        Vector::new(
+            if growth.x != 0.0 { self.padding.x } else { 0.0 },
+            if growth.y != 0.0 { self.padding.y } else { 0.0 },
+        )
+    }
 }
 
-impl<Message, Theme, Renderer> Widget<Message, Theme, Renderer> for ResizeHandle<Message>
+impl<Message, Theme, Renderer> Widget<Message, Theme, Renderer> for ResizeHandle<'_, Message, Theme>
 where
+    Theme: Catalog,
     Renderer: renderer::Renderer,
 {
     fn size(&self) -> Size<Length> {
-        Size {
-            width: Length::Fixed(self.size),
-            height: Length::Fixed(self.size),
+        let inset = self.inset();
+        match self.edge {
+            ResizeEdge::Top | ResizeEdge::Bottom => Size {
+                width: Length::Fill,
+                height: Length::Fixed(self.size + inset.y),
+            },
+            ResizeEdge::Left | ResizeEdge::Right => Size {
+                width: Length::Fixed(self.size + inset.x),
+                height: Length::Fill,
+            },
+            _ => Size {
+                width: Length::Fixed(self.size + inset.x),
+                height: Length::Fixed(self.size + inset.y),
+            },
         }
     }
 
@@ -110,9 +308,30 @@ where
         &mut self,
         _tree: &mut Tree,
         _renderer: &Renderer,
-        _limits: &layout::Limits,
+        limits: &layout::Limits,
     ) -> layout::Node {
-        layout::Node::new(Size::new(self.size, self.size))
+        let inset = self.inset();
+        let size = if self.edge.is_corner() {
+            Size::new(self.size + inset.x, self.size + inset.y)
+        } else {
+            let size = Widget::<Message, Theme, Renderer>::size(self);
+            layout::atomic(limits, size.width, size.height).size()
+        };
+
+        // The handle itself is the only child, translated away from the window's edges. Handles on
+        // the right and bottom already start at the top left of the padded bounds.
+        let growth = self.edge.growth();
+        let offset = Vector::new(
+            if growth.x < 0.0 { inset.x } else { 0.0 },
+            if growth.y < 0.0 { inset.y } else { 0.0 },
+        );
+        let handle = layout::Node::new(Size::new(
+            (size.width - inset.x).max(0.0),
+            (size.height - inset.y).max(0.0),
+        ))
+        .translate(offset);
+
+        layout::Node::with_children(size, vec![handle])
     }
 
     fn tag(&self) -> tree::Tag {
@@ -135,14 +354,14 @@ where
         _viewport: &Rectangle,
     ) {
         let state = tree.state.downcast_mut::<State>();
-        let bounds = layout.bounds();
+        let bounds = handle_bounds(layout);
 
         match event {
             Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) => {
                 if let Some(cursor_position) = cursor.position() {
-                    // Check if cursor is within the bounds (for now, we draw a rectangle)
-                    // TODO: Draw actual triangle and use triangle intersection test
-                    if bounds.contains(cursor_position) {
+                    // Only a corner's triangle can be dragged, clicks in the other half of the
+                    // bounds are meant for the widgets underneath
+                    if self.edge.contains(bounds, cursor_position) {
                         state.drag_active = true;
                         state.start_size = self.current_size;
                         state.last_cursor = cursor_position;
@@ -154,6 +373,11 @@ where
             Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) => {
                 if state.drag_active {
                     state.drag_active = false;
+
+                    // The host only hears about the size the drag ended at
+                    if self.resize_on_release && state.accumulated_size != state.start_size {
+                        shell.publish((self.on_resize)(state.accumulated_size));
+                    }
                 }
             }
             Event::Mouse(mouse::Event::CursorMoved { .. }) => {
@@ -169,13 +393,22 @@ where
                         // Update last cursor position for next frame
                         state.last_cursor = cursor_position;
 
-                        // Accumulate the delta into our size
-                        state.accumulated_size.width = (state.accumulated_size.width + delta.x).max(self.min_width);
-                        state.accumulated_size.height = (state.accumulated_size.height + delta.y).max(self.min_height);
+                        // Accumulate the delta into our size. Moving a left or top handle outwards
+                        // grows the window, and edge handles only change one axis.
+                        let growth = self.edge.growth();
+                        state.accumulated_size.width = (state.accumulated_size.width
+                            + delta.x * growth.x)
+                            .max(self.min_width)
+                            .min(self.max_width);
+                        state.accumulated_size.height = (state.accumulated_size.height
+                            + delta.y * growth.y)
+                            .max(self.min_height)
+                            .min(self.max_height);
 
                         // Only emit if the size actually changed to reduce message spam
                         if state.accumulated_size != state.last_emitted_size {
-                            nih_plug::nih_log!(
+                            #[cfg(feature = "verbose-logging")]
+                            log::trace!(
                                 "ResizeHandle: cursor: ({}, {}), delta: ({}, {}), bounds: ({}, {}), accumulated size: {}x{}",
                                 cursor_position.x, cursor_position.y,
                                 delta.x, delta.y,
@@ -184,8 +417,13 @@ where
                             );
 
                             state.last_emitted_size = state.accumulated_size;
-                            // Emit the resize message
-                            shell.publish((self.on_resize)(state.accumulated_size));
+                            // Emit the resize message, or only preview it until the button is
+                            // released
+                            if !self.resize_on_release {
+                                shell.publish((self.on_resize)(state.accumulated_size));
+                            } else if let Some(on_preview) = &self.on_preview {
+                                shell.publish(on_preview(state.accumulated_size));
+                            }
                         }
                     }
                 }
@@ -196,45 +434,71 @@ where
 
     fn draw(
         &self,
-        _tree: &Tree,
+        tree: &Tree,
         renderer: &mut Renderer,
-        _theme: &Theme,
+        theme: &Theme,
         _style: &renderer::Style,
         layout: Layout<'_>,
-        _cursor: mouse::Cursor,
+        cursor: mouse::Cursor,
         _viewport: &Rectangle,
     ) {
-        let bounds = layout.bounds();
-
-        // Draw a simple triangle in the bottom-right corner
-        // Points: bottom-left, bottom-right, top-right (forming a right-angled triangle)
-        renderer.fill_quad(
-            renderer::Quad {
-                bounds,
-                border: Border {
-                    color: Color::TRANSPARENT,
-                    width: 0.0,
-                    radius: 0.0.into(),
+        let state = tree.state.downcast_ref::<State>();
+        let bounds = handle_bounds(layout);
+
+        let status = if state.drag_active {
+            Status::Dragged
+        } else if cursor
+            .position()
+            .is_some_and(|position| self.edge.contains(bounds, position))
+        {
+            Status::Hovered
+        } else {
+            Status::Active
+        };
+        let appearance = theme.style(&self.class, status);
+
+        if !self.edge.is_corner() {
+            renderer.fill_quad(
+                renderer::Quad {
+                    bounds: crate::scale::pixel_snap(grip(bounds, self.edge, self.size)),
+                    ..Default::default()
                 },
-                shadow: Shadow::default(),
-                ..Default::default()
-            },
-            self.color,
-        );
+                appearance.background,
+            );
+
+            return;
+        }
+
+        // The triangle is drawn as one quad per row of physical pixels, each reaching from the
+        // diagonal to the window's edge
+        for row in triangle_rows(bounds, self.edge, crate::scale::scale_factor()) {
+            renderer.fill_quad(
+                renderer::Quad {
+                    bounds: row,
+                    ..Default::default()
+                },
+                appearance.background,
+            );
+        }
     }
 
     fn mouse_interaction(
         &self,
-        _tree: &Tree,
+        tree: &Tree,
         layout: Layout<'_>,
         cursor: mouse::Cursor,
         _viewport: &Rectangle,
         _renderer: &Renderer,
     ) -> mouse::Interaction {
+        // The cursor can run ahead of the handle while the window catches up with the drag, so
+        // this doesn't depend on where the cursor is
+        if tree.state.downcast_ref::<State>().drag_active {
+            return mouse::Interaction::Grabbing;
+        }
+
         if let Some(cursor_position) = cursor.position() {
-            // TODO: Use triangle intersection when we draw actual triangle
-            if layout.bounds().contains(cursor_position) {
-                return mouse::Interaction::Grabbing;
+            if self.edge.contains(handle_bounds(layout), cursor_position) {
+                return self.edge.interaction();
             }
         }
 
@@ -242,25 +506,181 @@ where
     }
 }
 
-impl<'a, Message, Theme, Renderer> From<ResizeHandle<Message>> for Element<'a, Message, Theme, Renderer>
+impl<'a, Message, Theme, Renderer> From<ResizeHandle<'a, Message, Theme>>
+    for Element<'a, Message, Theme, Renderer>
 where
     Message: 'a,
-    Theme: 'a,
+    Theme: Catalog + 'a,
     Renderer: renderer::Renderer + 'a,
 {
-    fn from(handle: ResizeHandle<Message>) -> Self {
+    fn from(handle: ResizeHandle<'a, Message, Theme>) -> Self {
         Element::new(handle)
     }
 }
 
 /// Helper function to create a resize handle
-pub fn resize_handle<Message>(
+pub fn resize_handle<'a, Message, Theme>(
     current_size: Size,
     on_resize: impl Fn(Size) -> Message + 'static,
-) -> ResizeHandle<Message> {
+) -> ResizeHandle<'a, Message, Theme>
+where
+    Theme: Catalog,
+{
     ResizeHandle::new(current_size, on_resize)
 }
 
+/// The possible status of a [`ResizeHandle`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    /// The handle can be interacted with.
+    Active,
+    /// The handle is being hovered.
+    Hovered,
+    /// The handle is being dragged.
+    Dragged,
+}
+
+/// The appearance of a [`ResizeHandle`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Style {
+    /// The [`Background`] of the handle's triangle, or of the grip on an edge handle.
+    pub background: Background,
+}
+
+/// The theme catalog of a [`ResizeHandle`].
+pub trait Catalog: Sized {
+    /// The item class of the [`Catalog`].
+    type Class<'a>;
+
+    /// The default class produced by the [`Catalog`].
+    fn default<'a>() -> Self::Class<'a>;
+
+    /// The [`Style`] of a class with the given status.
+    fn style(&self, class: &Self::Class<'_>, status: Status) -> Style;
+}
+
+/// A styling function for a [`ResizeHandle`].
+pub type StyleFn<'a, Theme> = Box<dyn Fn(&Theme, Status) -> Style + 'a>;
+
+impl Catalog for Theme {
+    type Class<'a> = StyleFn<'a, Self>;
+
+    fn default<'a>() -> Self::Class<'a> {
+        Box::new(default)
+    }
+
+    fn style(&self, class: &Self::Class<'_>, status: Status) -> Style {
+        class(self, status)
+    }
+}
+
+/// The default style of a [`ResizeHandle`], a semi-transparent version of the theme's strongest
+/// background color that becomes more opaque while it's being interacted with.
+pub fn default(theme: &Theme, status: Status) -> Style {
+    let colors = crate::theme::colors(theme);
+
+    let alpha = match status {
+        Status::Active => 0.5,
+        Status::Hovered => 0.75,
+        Status::Dragged => 1.0,
+    };
+
+    Style {
+        background: Background::Color(colors.outline.scale_alpha(alpha)),
+    }
+}
+
+/// The bounds of the handle within a [`ResizeHandle`]'s padded layout.
+fn handle_bounds(layout: Layout<'_>) -> Rectangle {
+    layout
+        .children()
+        .next()
+        .map_or(layout.bounds(), |handle| handle.bounds())
+}
+
+/// The bar drawn on an edge handle covering `bounds`, centered in the handle and running along the
+/// window's edge.
+fn grip(bounds: Rectangle, edge: ResizeEdge, size: f32) -> Rectangle {
+    let (width, height) = if edge.growth().x == 0.0 {
+        (size * GRIP_LENGTH, size * GRIP_THICKNESS)
+    } else {
+        (size * GRIP_THICKNESS, size * GRIP_LENGTH)
+    };
+    let (width, height) = (width.min(bounds.width), height.min(bounds.height));
+
+    Rectangle {
+        x: bounds.center_x() - width / 2.0,
+        y: bounds.center_y() - height / 2.0,
+        width,
+        height,
+    }
+}
+
+/// Mirror `point` within `bounds` so that a point in the triangle of the `edge` corner ends up in
+/// the bottom right triangle.
+fn mirror_point(bounds: Rectangle, edge: ResizeEdge, point: Point) -> Point {
+    let growth = edge.growth();
+    Point::new(
+        if growth.x < 0.0 {
+            bounds.x * 2.0 + bounds.width - point.x
+        } else {
+            point.x
+        },
+        if growth.y < 0.0 {
+            bounds.y * 2.0 + bounds.height - point.y
+        } else {
+            point.y
+        },
+    )
+}
+
+/// The rows of quads that make up the triangle of a resize handle covering `bounds` in the `edge`
+/// corner, one per row of physical pixels at `scale_factor`. Each row spans from the diagonal at
+/// the row's vertical center to the side of the bounds at that corner, and its edges are snapped
+/// to the physical pixel grid. Rows that would be narrower than a physical pixel are left out.
+fn triangle_rows(
+    bounds: Rectangle,
+    edge: ResizeEdge,
+    scale_factor: f32,
+) -> impl Iterator<Item = Rectangle> {
+    let scale_factor = if scale_factor.is_finite() && scale_factor > 0.0 {
+        scale_factor
+    } else {
+        1.0
+    };
+    let bounds = crate::scale::pixel_snap_at(bounds, scale_factor);
+    let rows = ((bounds.height * scale_factor).round() as usize).min(MAX_TRIANGLE_ROWS);
+    let row_height = bounds.height / rows.max(1) as f32;
+
+    let snap = move |value: f32| (value * scale_factor).round() / scale_factor;
+    let right = bounds.x + bounds.width;
+    let growth = edge.growth();
+
+    (0..rows).filter_map(move |row| {
+        let t = (row as f32 + 0.5) / rows as f32;
+        let x = snap(bounds.x + bounds.width * (1.0 - t));
+        let y = snap(bounds.y + row as f32 * row_height);
+        let bottom = snap(bounds.y + (row + 1) as f32 * row_height);
+
+        // The rows are computed for the bottom right corner and then mirrored. The bounds are
+        // already snapped, so the mirrored edges stay on the pixel grid.
+        (x < right && y < bottom).then_some(Rectangle {
+            x: if growth.x < 0.0 {
+                bounds.x * 2.0 + bounds.width - right
+            } else {
+                x
+            },
+            y: if growth.y < 0.0 {
+                bounds.y * 2.0 + bounds.height - bottom
+            } else {
+                y
+            },
+            width: right - x,
+            height: bottom - y,
+        })
+    })
+}
+
 /// Test whether a point intersects with the triangle of this resize handle.
 ///
 /// The triangle is formed by three points:
@@ -271,10 +691,10 @@ pub fn resize_handle<Message>(
 /// This creates a right-angled triangle in the bottom-right corner.
 fn intersects_triangle(bounds: Rectangle, point: Point) -> bool {
     // We use the determinant method (cross product) to check if the point is on the correct side
-    // of each edge of the triangle. For a point to be inside, it must be on the right side of all
-    // edges when traversed clockwise.
+    // of each edge of the triangle. For a point to be inside, it must be on the same side of all
+    // edges when they're traversed in order.
 
-    // Triangle vertices (clockwise from bottom-left)
+    // Triangle vertices (counterclockwise on screen from bottom-left, since y points down)
     let p1 = Point::new(bounds.x, bounds.y + bounds.height); // Bottom-left
     let p2 = Point::new(bounds.x + bounds.width, bounds.y + bounds.height); // Bottom-right
     let p3 = Point::new(bounds.x + bounds.width, bounds.y); // Top-right
@@ -294,13 +714,14 @@ fn intersects_triangle(bounds: Rectangle, point: Point) -> bool {
     let to_point3 = Vector::new(point.x - p3.x, point.y - p3.y);
     let cross3 = v3.x * to_point3.y - v3.y * to_point3.x;
 
-    // Point is inside if all cross products have the same sign (all >= 0 for clockwise winding)
-    cross1 >= 0.0 && cross2 >= 0.0 && cross3 >= 0.0
+    // With y pointing down, points inside have a cross product of at most zero for every edge
+    cross1 <= 0.0 && cross2 <= 0.0 && cross3 <= 0.0
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::runtime::user_interface::{Cache, UserInterface};
 
     #[test]
     fn triangle_intersection() {
@@ -324,4 +745,341 @@ mod tests {
         assert!(!intersects_triangle(bounds, Point::new(14.9, 15.0)));
         assert!(!intersects_triangle(bounds, Point::new(15.0, 14.9)));
     }
+
+    #[test]
+    fn triangle_rows_follow_the_diagonal() {
+        let bounds = Rectangle {
+            x: 10.0,
+            y: 10.0,
+            width: 10.0,
+            height: 10.0,
+        };
+
+        // At a scale factor of two every row is one physical pixel high and ends at the right edge.
+        // The top row would be narrower than a physical pixel, so it's left out.
+        let rows: Vec<_> = triangle_rows(bounds, ResizeEdge::BottomRight, 2.0).collect();
+        assert_eq!(rows.len(), 19);
+        for (i, row) in rows.iter().enumerate() {
+            assert_eq!(row.y, 10.5 + i as f32 * 0.5);
+            assert_eq!(row.height, 0.5);
+            assert_eq!(row.x + row.width, 20.0);
+        }
+
+        // The rows grow towards the bottom-left corner and stay within half a physical pixel of the
+        // diagonal, so the top-left half stays empty
+        assert!(rows.windows(2).all(|rows| rows[0].width < rows[1].width));
+        assert!(rows[18].width >= 9.5);
+        for row in &rows {
+            let diagonal = bounds.x + bounds.width - (row.center_y() - bounds.y);
+            assert!((row.x - diagonal).abs() <= 0.25);
+        }
+    }
+
+    /// Press the button on a handle at the top left of the window at the first point, move the
+    /// cursor through the other points, and release it. Returns the sizes the handle emitted.
+    fn drag<'a, Message: 'a>(handle: ResizeHandle<'a, Message>, path: &[Point]) -> Vec<Message> {
+        let mut interface = UserInterface::build(
+            Element::<'_, Message, Theme, ()>::from(handle),
+            Size::new(200.0, 200.0),
+            Cache::default(),
+            &mut (),
+        );
+
+        let (press, moves) = path.split_first().unwrap();
+        let last = *path.last().unwrap();
+        let events = std::iter::once((
+            Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)),
+            *press,
+        ))
+        .chain(moves.iter().map(|&position| {
+            (
+                Event::Mouse(mouse::Event::CursorMoved { position }),
+                position,
+            )
+        }))
+        .chain(std::iter::once((
+            Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)),
+            last,
+        )));
+
+        let mut messages = Vec::new();
+        for (event, cursor) in events {
+            let _ = interface.update(
+                &[event],
+                mouse::Cursor::Available(cursor),
+                &mut (),
+                &mut crate::core::clipboard::Null,
+                &mut messages,
+            );
+        }
+
+        messages
+    }
+
+    #[test]
+    fn only_the_triangle_starts_a_drag() {
+        let handle = || ResizeHandle::new(Size::new(100.0, 100.0), |size| size).min_size(0.0, 0.0);
+        let drag_by_5 =
+            |position: Point| drag(handle(), &[position, position + Vector::new(5.0, 5.0)]);
+
+        // The top-left corner is outside of the triangle, so the click is left alone
+        assert_eq!(drag_by_5(Point::new(0.0, 0.0)), []);
+        assert_eq!(drag_by_5(Point::new(4.0, 8.0)), []);
+        assert_eq!(drag_by_5(Point::new(15.0, 15.0)), [Size::new(105.0, 105.0)]);
+    }
+
+    #[test]
+    fn mirrored_corners() {
+        let bounds = Rectangle {
+            x: 10.0,
+            y: 10.0,
+            width: 10.0,
+            height: 10.0,
+        };
+
+        // Every corner's triangle includes its own corner and leaves the opposite one alone
+        for (edge, corner, opposite) in [
+            (ResizeEdge::TopLeft, (10.0, 10.0), (20.0, 20.0)),
+            (ResizeEdge::TopRight, (20.0, 10.0), (10.0, 20.0)),
+            (ResizeEdge::BottomLeft, (10.0, 20.0), (20.0, 10.0)),
+            (ResizeEdge::BottomRight, (20.0, 20.0), (10.0, 10.0)),
+        ] {
+            assert!(
+                edge.contains(bounds, Point::new(corner.0, corner.1)),
+                "{edge:?}"
+            );
+            assert!(
+                !edge.contains(bounds, Point::new(opposite.0, opposite.1)),
+                "{edge:?}"
+            );
+
+            // The rows stay within the bounds and hug the corner's side
+            let rows: Vec<_> = triangle_rows(bounds, edge, 1.0).collect();
+            assert!(!rows.is_empty());
+            for row in rows {
+                assert!(bounds.contains(row.position()), "{edge:?}");
+                let side = if corner.0 == 10.0 {
+                    row.x
+                } else {
+                    row.x + row.width
+                };
+                assert_eq!(side, corner.0, "{edge:?}");
+            }
+        }
+
+        // Edge handles respond everywhere in their bounds
+        assert!(ResizeEdge::Left.contains(bounds, Point::new(19.0, 11.0)));
+        assert_eq!(
+            ResizeEdge::TopLeft.interaction(),
+            mouse::Interaction::ResizingDiagonallyDown
+        );
+        assert_eq!(
+            ResizeEdge::BottomLeft.interaction(),
+            mouse::Interaction::ResizingDiagonallyUp
+        );
+    }
+
+    #[test]
+    fn edges_resize_their_own_axis() {
+        let handle = |edge| {
+            ResizeHandle::with_edge(edge, Size::new(400.0, 300.0), |size| size).min_size(0.0, 0.0)
+        };
+        let path = [Point::new(8.0, 15.0), Point::new(-2.0, 25.0)];
+
+        // Dragging a left handle outwards grows the window, and edges leave the other axis alone
+        assert_eq!(
+            drag(handle(ResizeEdge::Left), &path),
+            [Size::new(410.0, 300.0)]
+        );
+        assert_eq!(
+            drag(handle(ResizeEdge::Bottom), &path),
+            [Size::new(400.0, 310.0)]
+        );
+        assert_eq!(
+            drag(handle(ResizeEdge::Top), &path),
+            [Size::new(400.0, 290.0)]
+        );
+        assert_eq!(
+            drag(handle(ResizeEdge::BottomLeft), &path),
+            [Size::new(410.0, 310.0)]
+        );
+    }
+
+    #[test]
+    fn padding_insets_the_handle() {
+        let handle = |edge| {
+            ResizeHandle::with_edge(edge, Size::new(400.0, 300.0), |size| size)
+                .min_size(0.0, 0.0)
+                .padding(10.0, 5.0)
+        };
+        let drag_from = |edge, from: Point, to: Point| drag(handle(edge), &[from, to]);
+
+        // A bottom right handle keeps its triangle at the top left of the padded bounds, so the
+        // padding towards the window's corner doesn't respond to clicks
+        assert_eq!(
+            drag_from(
+                ResizeEdge::BottomRight,
+                Point::new(15.0, 15.0),
+                Point::new(20.0, 20.0)
+            ),
+            [Size::new(405.0, 305.0)]
+        );
+        assert_eq!(
+            drag_from(
+                ResizeEdge::BottomRight,
+                Point::new(25.0, 22.0),
+                Point::new(30.0, 27.0)
+            ),
+            []
+        );
+
+        // A top left handle is translated by the padding instead
+        assert_eq!(
+            drag_from(
+                ResizeEdge::TopLeft,
+                Point::new(2.0, 2.0),
+                Point::new(0.0, 0.0)
+            ),
+            []
+        );
+        assert_eq!(
+            drag_from(
+                ResizeEdge::TopLeft,
+                Point::new(12.0, 7.0),
+                Point::new(2.0, 2.0)
+            ),
+            [Size::new(410.0, 305.0)]
+        );
+    }
+
+    #[test]
+    fn cursor_follows_the_drag() {
+        let mut handle = ResizeHandle::new(Size::new(400.0, 300.0), |size| size);
+        let mut tree = Tree::new(&handle as &dyn Widget<Size, Theme, ()>);
+        let node = layout::Node::new(Size::new(20.0, 20.0));
+        let layout = Layout::new(&node);
+        let interaction = |handle: &mut ResizeHandle<'_, Size>, tree: &mut Tree, event, at| {
+            let cursor = mouse::Cursor::Available(at);
+            if let Some(event) = event {
+                let mut messages = Vec::new();
+                Widget::<Size, Theme, ()>::update(
+                    handle,
+                    tree,
+                    &event,
+                    layout,
+                    cursor,
+                    &(),
+                    &mut crate::core::clipboard::Null,
+                    &mut Shell::new(&mut messages),
+                    &node.bounds(),
+                );
+            }
+
+            Widget::<Size, Theme, ()>::mouse_interaction(
+                handle,
+                tree,
+                layout,
+                cursor,
+                &node.bounds(),
+                &(),
+            )
+        };
+        let press = Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left));
+        let release = Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left));
+
+        // Hovering the triangle shows a resize arrow, the other half of the bounds doesn't
+        assert_eq!(
+            interaction(&mut handle, &mut tree, None, Point::new(2.0, 2.0)),
+            mouse::Interaction::default()
+        );
+        assert_eq!(
+            interaction(&mut handle, &mut tree, None, Point::new(15.0, 15.0)),
+            mouse::Interaction::ResizingDiagonallyDown
+        );
+
+        // While dragging the hand stays closed, even when the cursor leaves the handle
+        assert_eq!(
+            interaction(&mut handle, &mut tree, Some(press), Point::new(15.0, 15.0)),
+            mouse::Interaction::Grabbing
+        );
+        assert_eq!(
+            interaction(&mut handle, &mut tree, None, Point::new(50.0, 50.0)),
+            mouse::Interaction::Grabbing
+        );
+        assert_eq!(
+            interaction(&mut handle, &mut tree, Some(release), Point::new(5.0, 18.0)),
+            mouse::Interaction::ResizingDiagonallyDown
+        );
+    }
+
+    #[test]
+    fn sizes_are_clamped() {
+        let handle = ResizeHandle::new(Size::new(400.0, 300.0), |size| size)
+            .min_size(390.0, 290.0)
+            .max_size(420.0, 305.0);
+        let sizes = drag(
+            handle,
+            &[
+                Point::new(15.0, 15.0),
+                Point::new(25.0, 25.0),
+                Point::new(45.0, 45.0),
+                Point::new(-15.0, -15.0),
+            ],
+        );
+
+        assert_eq!(
+            sizes,
+            [
+                Size::new(410.0, 305.0),
+                Size::new(420.0, 305.0),
+                Size::new(390.0, 290.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn resizing_on_release() {
+        #[derive(Debug, PartialEq)]
+        enum Resize {
+            Preview(Size),
+            Commit(Size),
+        }
+
+        let handle = || {
+            ResizeHandle::new(Size::new(400.0, 300.0), Resize::Commit)
+                .min_size(0.0, 0.0)
+                .resize_on_release(true)
+        };
+        let path = [
+            Point::new(15.0, 15.0),
+            Point::new(25.0, 20.0),
+            Point::new(35.0, 25.0),
+        ];
+
+        // Only the final size is sent, once the button is released
+        assert_eq!(
+            drag(handle(), &path),
+            [Resize::Commit(Size::new(420.0, 310.0))]
+        );
+
+        // The editor can still follow along
+        assert_eq!(
+            drag(handle().on_preview(Resize::Preview), &path),
+            [
+                Resize::Preview(Size::new(410.0, 305.0)),
+                Resize::Preview(Size::new(420.0, 310.0)),
+                Resize::Commit(Size::new(420.0, 310.0)),
+            ]
+        );
+
+        // A drag that ends where it started doesn't resize the window
+        let back = [path[0], path[1], path[0]];
+        assert_eq!(
+            drag(handle().on_preview(Resize::Preview), &back),
+            [
+                Resize::Preview(Size::new(410.0, 305.0)),
+                Resize::Preview(Size::new(400.0, 300.0)),
+            ]
+        );
+    }
 }
@@ -0,0 +1,552 @@
+//! Axis rulers that can be placed next to a plot, like the frequency and level axes of a spectrum
+//! analyzer.
+//!
+//! The rulers only draw ticks and labels, so they can be combined with any plot widget. They use
+//! the mapping functions from the [`axis`][super::axis] module, so a ruler with the same range and
+//! length as a plot lines up with it. Labels that don't fit are left out, starting with the less
+//! important ones.
+
+use std::marker::PhantomData;
+
+use crate::core::text::{self, Paragraph, Renderer as TextRenderer};
+use crate::core::widget::Tree;
+use crate::core::{
+    alignment, layout, mouse, renderer, Background, Color, Element, Font, Layout, Length, Pixels,
+    Point, Rectangle, Size, Theme, Widget,
+};
+
+use super::axis::{self, LabelPlacement, Tick, TickLevel};
+use super::util;
+
+/// The thickness of the tick marks.
+const TICK_WIDTH: f32 = 1.0;
+/// The minimum space between two labels, in logical pixels.
+const LABEL_GAP: f32 = 4.0;
+/// The length of major and medium ticks relative to the ruler's size.
+const LABELED_TICK_LENGTH: f32 = 0.3;
+/// The length of minor ticks relative to the ruler's size.
+const MINOR_TICK_LENGTH: f32 = 0.15;
+
+/// A horizontal ruler for a logarithmic frequency axis, with labels like 20, 100, 1k and 20k.
+///
+/// If the sample rate is set, then no ticks are drawn past the Nyquist frequency. The axis itself
+/// still spans the whole range, so the ruler keeps lining up with a plot that uses the same range.
+pub struct FrequencyRuler<'a, Message, Theme = crate::Theme>
+where
+    Theme: Catalog,
+{
+    min_hz: f32,
+    max_hz: f32,
+    sample_rate: Option<f32>,
+
+    width: Length,
+    height: Length,
+    text_size: Option<Pixels>,
+    font: Option<Font>,
+    class: Theme::Class<'a>,
+
+    /// We don't emit any messages, but iced requires us to define some message type anyways.
+    _phantom: PhantomData<Message>,
+}
+
+impl<'a, Message, Theme> FrequencyRuler<'a, Message, Theme>
+where
+    Theme: Catalog,
+{
+    /// Creates a new [`FrequencyRuler`] spanning `[min_hz, max_hz]`. The range is usually
+    /// `20.0..20_000.0`.
+    pub fn new(min_hz: f32, max_hz: f32) -> Self {
+        Self {
+            min_hz,
+            max_hz,
+            sample_rate: None,
+
+            width: Length::Fill,
+            height: Length::Fixed(24.0),
+            text_size: None,
+            font: None,
+            class: Theme::default(),
+
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Don't draw ticks above the Nyquist frequency for this sample rate.
+    pub fn sample_rate(mut self, sample_rate: f32) -> Self {
+        self.sample_rate = Some(sample_rate);
+        self
+    }
+
+    /// Sets the width of the [`FrequencyRuler`].
+    pub fn width(mut self, width: impl Into<Length>) -> Self {
+        self.width = width.into();
+        self
+    }
+
+    /// Sets the height of the [`FrequencyRuler`].
+    pub fn height(mut self, height: impl Into<Length>) -> Self {
+        self.height = height.into();
+        self
+    }
+
+    /// Sets the text size of the [`FrequencyRuler`]'s labels.
+    pub fn text_size(mut self, size: impl Into<Pixels>) -> Self {
+        self.text_size = Some(size.into());
+        self
+    }
+
+    /// Sets the font of the [`FrequencyRuler`]'s labels.
+    pub fn font(mut self, font: Font) -> Self {
+        self.font = Some(font);
+        self
+    }
+
+    /// Sets the style of the [`FrequencyRuler`].
+    pub fn style(mut self, style: impl Fn(&Theme) -> Style + 'a) -> Self
+    where
+        Theme::Class<'a>: From<StyleFn<'a, Theme>>,
+    {
+        self.class = (Box::new(style) as StyleFn<'a, Theme>).into();
+        self
+    }
+
+    /// Sets the style class of the [`FrequencyRuler`].
+    pub fn class(mut self, class: impl Into<Theme::Class<'a>>) -> Self {
+        self.class = class.into();
+        self
+    }
+}
+
+impl<Message, Theme, Renderer> Widget<Message, Theme, Renderer>
+    for FrequencyRuler<'_, Message, Theme>
+where
+    Theme: Catalog,
+    Renderer: TextRenderer,
+    Renderer::Font: From<crate::Font>,
+{
+    fn size(&self) -> Size<Length> {
+        (self.width, self.height).into()
+    }
+
+    fn layout(
+        &mut self,
+        _tree: &mut Tree,
+        _renderer: &Renderer,
+        limits: &layout::Limits,
+    ) -> layout::Node {
+        layout::atomic(limits, self.width, self.height)
+    }
+
+    fn draw(
+        &self,
+        _tree: &Tree,
+        renderer: &mut Renderer,
+        theme: &Theme,
+        _style: &renderer::Style,
+        layout: Layout<'_>,
+        _cursor: mouse::Cursor,
+        viewport: &Rectangle,
+    ) {
+        let appearance = theme.style(&self.class);
+        let bounds = layout.bounds();
+        let labels = Labels::new(renderer, self.text_size, self.font);
+
+        let ticks = axis::frequency_ticks(
+            self.min_hz,
+            axis::visible_max_frequency(self.max_hz, self.sample_rate),
+        );
+        let positions: Vec<f32> = ticks
+            .iter()
+            .map(|tick| axis::frequency_to_t(tick.value, self.min_hz, self.max_hz) * bounds.width)
+            .collect();
+
+        for (tick, &x) in ticks.iter().zip(&positions) {
+            let length = tick_length(tick.level) * bounds.height;
+            let tick_bounds = Rectangle {
+                x: bounds.x + x - (TICK_WIDTH / 2.0),
+                y: bounds.y,
+                width: TICK_WIDTH,
+                height: length,
+            };
+
+            fill_tick(renderer, tick_bounds, tick, &appearance);
+        }
+
+        let texts: Vec<String> = ticks
+            .iter()
+            .map(|tick| axis::format_frequency(tick.value))
+            .collect();
+        let placements: Vec<LabelPlacement> = ticks
+            .iter()
+            .zip(&positions)
+            .zip(&texts)
+            .map(|((tick, &position), text)| LabelPlacement {
+                position,
+                size: labels.measure::<Renderer>(text).width,
+                level: tick.level,
+            })
+            .collect();
+        let visible = axis::visible_labels(&placements, bounds.width, LABEL_GAP);
+
+        let label_y = bounds.y + (LABELED_TICK_LENGTH * bounds.height);
+        for ((placement, text), visible) in placements.iter().zip(texts).zip(visible) {
+            if !visible {
+                continue;
+            }
+
+            let (start, _) = placement.span(bounds.width);
+            labels.draw(
+                renderer,
+                text,
+                Point::new(bounds.x + start, label_y),
+                alignment::Horizontal::Left,
+                appearance.text_color,
+                viewport,
+            );
+        }
+    }
+}
+
+impl<'a, Message, Theme, Renderer> From<FrequencyRuler<'a, Message, Theme>>
+    for Element<'a, Message, Theme, Renderer>
+where
+    Message: 'a,
+    Theme: Catalog + 'a,
+    Renderer: TextRenderer + 'a,
+    Renderer::Font: From<crate::Font>,
+{
+    fn from(widget: FrequencyRuler<'a, Message, Theme>) -> Self {
+        Element::new(widget)
+    }
+}
+
+/// A vertical ruler for a linear decibel axis, with the highest level at the top. The ticks are
+/// drawn on the ruler's right edge, so it fits on the left side of a plot.
+pub struct DbRuler<'a, Message, Theme = crate::Theme>
+where
+    Theme: Catalog,
+{
+    min_db: f32,
+    max_db: f32,
+    step: f32,
+
+    width: Length,
+    height: Length,
+    text_size: Option<Pixels>,
+    font: Option<Font>,
+    class: Theme::Class<'a>,
+
+    /// We don't emit any messages, but iced requires us to define some message type anyways.
+    _phantom: PhantomData<Message>,
+}
+
+impl<'a, Message, Theme> DbRuler<'a, Message, Theme>
+where
+    Theme: Catalog,
+{
+    /// The default distance between two labels, in decibel.
+    pub const DEFAULT_STEP: f32 = 6.0;
+
+    /// Creates a new [`DbRuler`] spanning `[min_db, max_db]`.
+    pub fn new(min_db: f32, max_db: f32) -> Self {
+        Self {
+            min_db,
+            max_db,
+            step: Self::DEFAULT_STEP,
+
+            width: Length::Fixed(36.0),
+            height: Length::Fill,
+            text_size: None,
+            font: None,
+            class: Theme::default(),
+
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Sets the distance between two labels in decibel. Every other label is a major label that's
+    /// kept when there isn't enough room for all of them.
+    pub fn step(mut self, step: f32) -> Self {
+        self.step = step;
+        self
+    }
+
+    /// Sets the width of the [`DbRuler`].
+    pub fn width(mut self, width: impl Into<Length>) -> Self {
+        self.width = width.into();
+        self
+    }
+
+    /// Sets the height of the [`DbRuler`].
+    pub fn height(mut self, height: impl Into<Length>) -> Self {
+        self.height = height.into();
+        self
+    }
+
+    /// Sets the text size of the [`DbRuler`]'s labels.
+    pub fn text_size(mut self, size: impl Into<Pixels>) -> Self {
+        self.text_size = Some(size.into());
+        self
+    }
+
+    /// Sets the font of the [`DbRuler`]'s labels.
+    pub fn font(mut self, font: Font) -> Self {
+        self.font = Some(font);
+        self
+    }
+
+    /// Sets the style of the [`DbRuler`].
+    pub fn style(mut self, style: impl Fn(&Theme) -> Style + 'a) -> Self
+    where
+        Theme::Class<'a>: From<StyleFn<'a, Theme>>,
+    {
+        self.class = (Box::new(style) as StyleFn<'a, Theme>).into();
+        self
+    }
+
+    /// Sets the style class of the [`DbRuler`].
+    pub fn class(mut self, class: impl Into<Theme::Class<'a>>) -> Self {
+        self.class = class.into();
+        self
+    }
+}
+
+impl<Message, Theme, Renderer> Widget<Message, Theme, Renderer> for DbRuler<'_, Message, Theme>
+where
+    Theme: Catalog,
+    Renderer: TextRenderer,
+    Renderer::Font: From<crate::Font>,
+{
+    fn size(&self) -> Size<Length> {
+        (self.width, self.height).into()
+    }
+
+    fn layout(
+        &mut self,
+        _tree: &mut Tree,
+        _renderer: &Renderer,
+        limits: &layout::Limits,
+    ) -> layout::Node {
+        layout::atomic(limits, self.width, self.height)
+    }
+
+    fn draw(
+        &self,
+        _tree: &Tree,
+        renderer: &mut Renderer,
+        theme: &Theme,
+        _style: &renderer::Style,
+        layout: Layout<'_>,
+        _cursor: mouse::Cursor,
+        viewport: &Rectangle,
+    ) {
+        let appearance = theme.style(&self.class);
+        let bounds = layout.bounds();
+        let labels = Labels::new(renderer, self.text_size, self.font);
+
+        // Positions are measured from the top, where the highest level is
+        let ticks = axis::db_ticks(self.min_db, self.max_db, self.step);
+        let positions: Vec<f32> = ticks
+            .iter()
+            .map(|tick| (1.0 - axis::db_to_t(tick.value, self.min_db, self.max_db)) * bounds.height)
+            .collect();
+
+        for (tick, &y) in ticks.iter().zip(&positions) {
+            let length = tick_length(tick.level) * bounds.width;
+            let tick_bounds = Rectangle {
+                x: bounds.x + bounds.width - length,
+                y: bounds.y + y - (TICK_WIDTH / 2.0),
+                width: length,
+                height: TICK_WIDTH,
+            };
+
+            fill_tick(renderer, tick_bounds, tick, &appearance);
+        }
+
+        let texts: Vec<String> = ticks
+            .iter()
+            .map(|tick| axis::format_db(tick.value))
+            .collect();
+        let placements: Vec<LabelPlacement> = ticks
+            .iter()
+            .zip(&positions)
+            .zip(&texts)
+            .map(|((tick, &position), text)| LabelPlacement {
+                position,
+                size: labels.measure::<Renderer>(text).height,
+                level: tick.level,
+            })
+            .collect();
+        let visible = axis::visible_labels(&placements, bounds.height, LABEL_GAP);
+
+        let label_right =
+            bounds.x + bounds.width - (LABELED_TICK_LENGTH * bounds.width) - LABEL_GAP;
+        for ((placement, text), visible) in placements.iter().zip(texts).zip(visible) {
+            if !visible {
+                continue;
+            }
+
+            let (start, _) = placement.span(bounds.height);
+            labels.draw(
+                renderer,
+                text,
+                Point::new(label_right, bounds.y + start),
+                alignment::Horizontal::Right,
+                appearance.text_color,
+                viewport,
+            );
+        }
+    }
+}
+
+impl<'a, Message, Theme, Renderer> From<DbRuler<'a, Message, Theme>>
+    for Element<'a, Message, Theme, Renderer>
+where
+    Message: 'a,
+    Theme: Catalog + 'a,
+    Renderer: TextRenderer + 'a,
+    Renderer::Font: From<crate::Font>,
+{
+    fn from(widget: DbRuler<'a, Message, Theme>) -> Self {
+        Element::new(widget)
+    }
+}
+
+/// The length of a tick relative to the ruler's size.
+fn tick_length(level: TickLevel) -> f32 {
+    match level {
+        TickLevel::Major | TickLevel::Medium => LABELED_TICK_LENGTH,
+        TickLevel::Minor => MINOR_TICK_LENGTH,
+    }
+}
+
+fn fill_tick<Renderer: renderer::Renderer>(
+    renderer: &mut Renderer,
+    bounds: Rectangle,
+    tick: &Tick,
+    appearance: &Style,
+) {
+    let color = if tick.is_labeled() {
+        appearance.tick_color
+    } else {
+        appearance.minor_tick_color
+    };
+
+    // Hairline ticks between two physical pixels would be drawn blurry or not at all
+    renderer.fill_quad(
+        renderer::Quad {
+            bounds: crate::scale::pixel_snap(bounds),
+            ..Default::default()
+        },
+        Background::Color(color),
+    );
+}
+
+/// Measures and draws the labels of a ruler.
+struct Labels<F> {
+    size: Pixels,
+    font: F,
+}
+
+impl<F: Copy> Labels<F> {
+    fn new<Renderer>(renderer: &Renderer, size: Option<Pixels>, font: Option<Font>) -> Self
+    where
+        Renderer: TextRenderer<Font = F>,
+        F: From<crate::Font>,
+    {
+        Self {
+            size: size.unwrap_or_else(|| Pixels((renderer.default_size().0 * 0.7).round())),
+            font: font.map(F::from).unwrap_or_else(|| renderer.default_font()),
+        }
+    }
+
+    fn text<Content>(
+        &self,
+        content: Content,
+        align_x: alignment::Horizontal,
+    ) -> text::Text<Content, F> {
+        text::Text {
+            content,
+            font: self.font,
+            size: self.size,
+            bounds: Size::INFINITE,
+            align_x: align_x.into(),
+            align_y: alignment::Vertical::Top,
+            line_height: Default::default(),
+            shaping: Default::default(),
+            wrapping: text::Wrapping::None,
+        }
+    }
+
+    fn measure<Renderer>(&self, content: &str) -> Size
+    where
+        Renderer: TextRenderer<Font = F>,
+    {
+        Renderer::Paragraph::with_text(self.text(content, alignment::Horizontal::Left)).min_bounds()
+    }
+
+    fn draw<Renderer>(
+        &self,
+        renderer: &mut Renderer,
+        content: String,
+        position: Point,
+        align_x: alignment::Horizontal,
+        color: Color,
+        viewport: &Rectangle,
+    ) where
+        Renderer: TextRenderer<Font = F>,
+    {
+        let text = self.text(content, align_x);
+        let position = crate::scale::snap_text(&text, position);
+        renderer.fill_text(text, position, color, *viewport);
+    }
+}
+
+/// The appearance of a [`FrequencyRuler`] or [`DbRuler`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Style {
+    /// The color of the labeled ticks.
+    pub tick_color: Color,
+    /// The color of the unlabeled ticks in between.
+    pub minor_tick_color: Color,
+    /// The color of the labels.
+    pub text_color: Color,
+}
+
+/// The theme catalog of a [`FrequencyRuler`] or [`DbRuler`].
+pub trait Catalog: Sized {
+    /// The item class of the [`Catalog`].
+    type Class<'a>;
+
+    /// The default class produced by the [`Catalog`].
+    fn default<'a>() -> Self::Class<'a>;
+
+    /// The [`Style`] of a class.
+    fn style(&self, class: &Self::Class<'_>) -> Style;
+}
+
+/// A styling function for a [`FrequencyRuler`] or [`DbRuler`].
+pub type StyleFn<'a, Theme> = Box<dyn Fn(&Theme) -> Style + 'a>;
+
+impl Catalog for Theme {
+    type Class<'a> = StyleFn<'a, Self>;
+
+    fn default<'a>() -> Self::Class<'a> {
+        Box::new(default)
+    }
+
+    fn style(&self, class: &Self::Class<'_>) -> Style {
+        class(self)
+    }
+}
+
+/// The default style of a [`FrequencyRuler`] or [`DbRuler`].
+pub fn default(theme: &Theme) -> Style {
+    let colors = crate::theme::colors(theme);
+
+    Style {
+        tick_color: colors.outline,
+        minor_tick_color: util::mix_colors(colors.background, colors.outline, 0.5),
+        text_color: colors.text,
+    }
+}
@@ -0,0 +1,791 @@
+//! A tooltip that's shown when its content is hovered or has keyboard focus.
+//!
+//! Unlike iced's own tooltip, this one also appears when a widget inside of it receives keyboard
+//! focus, so keyboard users get the same hints as mouse users. The tooltip follows these rules:
+//!
+//! - Hovering the content shows the tooltip after the [delay][Tooltip::delay]. Moving the cursor
+//!   away hides it again.
+//! - Focusing a widget in the content shows the tooltip right away. Moving focus elsewhere (blur)
+//!   hides it again.
+//! - Pressing Escape or clicking the content dismisses the tooltip. It stays hidden until the
+//!   cursor has left the content and the content has lost focus. Escape is still passed on to the
+//!   content, so it can for instance also cancel a text edit.
+//!
+//! The tooltip is drawn on the window's overlay layer. It's placed on the preferred side of the
+//! content, moved to the opposite side if it doesn't fit there, and then kept within the window.
+//!
+//! Focus is detected by querying the [`Focusable`] widgets in the content, so it works for any
+//! widget that supports focus operations like text inputs and [`Dropdown`][super::Dropdown]s. The
+//! hover delay is checked whenever the window is redrawn. Plugin editors redraw every frame, but
+//! with [`always_redraw`][crate::IcedBaseviewSettings::always_redraw] disabled the tooltip only
+//! appears with the next event after the delay has passed.
+
+use std::time::{Duration, Instant};
+
+use crate::core::keyboard::{self, key};
+use crate::core::widget::operation::{Focusable, Operation};
+use crate::core::widget::tree::{self, Tree};
+use crate::core::widget::Id;
+use crate::core::{
+    layout, mouse, overlay, renderer, Background, Border, Clipboard, Color, Element, Event, Layout,
+    Length, Padding, Rectangle, Shadow, Shell, Size, Theme, Vector, Widget,
+};
+
+/// Wraps a widget and shows a tooltip next to it while it's hovered or has keyboard focus. The
+/// tooltip's contents can be any element, so they can contain more than plain text. See the
+/// [module documentation][self] for when the tooltip is shown and hidden.
+pub struct Tooltip<'a, Message, Theme = crate::Theme, Renderer = crate::Renderer>
+where
+    Theme: Catalog,
+{
+    content: Element<'a, Message, Theme, Renderer>,
+    tooltip: Element<'a, Message, Theme, Renderer>,
+    position: Position,
+    gap: f32,
+    padding: Padding,
+    delay: Duration,
+    class: Theme::Class<'a>,
+}
+
+/// The default distance between the content and the tooltip.
+pub(super) const DEFAULT_GAP: f32 = 4.0;
+/// The default padding around the tooltip's contents.
+pub(super) const DEFAULT_PADDING: Padding = Padding {
+    top: 4.0,
+    right: 8.0,
+    bottom: 4.0,
+    left: 8.0,
+};
+
+/// The preferred side of the content to show a [`Tooltip`] on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Position {
+    /// Above the content.
+    #[default]
+    Top,
+    /// Below the content.
+    Bottom,
+    /// To the left of the content.
+    Left,
+    /// To the right of the content.
+    Right,
+}
+
+/// State for a [`Tooltip`].
+#[derive(Debug, Default)]
+struct State {
+    /// When the cursor started hovering the content, if it's hovering it now.
+    hovered_since: Option<Instant>,
+    /// Whether a widget in the content has keyboard focus.
+    focused: bool,
+    /// Set when the tooltip is dismissed, and cleared once the content is neither hovered nor
+    /// focused anymore.
+    dismissed: bool,
+}
+
+impl State {
+    fn set_hovered(&mut self, hovered: bool, now: Instant) {
+        if !hovered {
+            self.hovered_since = None;
+        } else if self.hovered_since.is_none() {
+            self.hovered_since = Some(now);
+        }
+
+        self.reset_dismissal();
+    }
+
+    fn set_focused(&mut self, focused: bool) {
+        self.focused = focused;
+        self.reset_dismissal();
+    }
+
+    fn reset_dismissal(&mut self) {
+        if self.hovered_since.is_none() && !self.focused {
+            self.dismissed = false;
+        }
+    }
+
+    fn is_visible(&self, now: Instant, delay: Duration) -> bool {
+        let hovered_long_enough = self
+            .hovered_since
+            .is_some_and(|since| now.saturating_duration_since(since) >= delay);
+
+        !self.dismissed && (self.focused || hovered_long_enough)
+    }
+}
+
+/// Checks whether any focusable widget reports that it has keyboard focus.
+struct FocusProbe {
+    focused: bool,
+}
+
+impl Operation for FocusProbe {
+    fn traverse(&mut self, operate: &mut dyn FnMut(&mut dyn Operation)) {
+        operate(self);
+    }
+
+    fn focusable(&mut self, _id: Option<&Id>, _bounds: Rectangle, state: &mut dyn Focusable) {
+        self.focused |= state.is_focused();
+    }
+}
+
+impl<'a, Message, Theme, Renderer> Tooltip<'a, Message, Theme, Renderer>
+where
+    Theme: Catalog,
+    Renderer: crate::core::Renderer,
+{
+    /// The default hover delay.
+    pub const DEFAULT_DELAY: Duration = Duration::from_millis(500);
+
+    /// Creates a new [`Tooltip`] that shows `tooltip` next to `content`.
+    pub fn new(
+        content: impl Into<Element<'a, Message, Theme, Renderer>>,
+        tooltip: impl Into<Element<'a, Message, Theme, Renderer>>,
+        position: Position,
+    ) -> Self {
+        Self {
+            content: content.into(),
+            tooltip: tooltip.into(),
+            position,
+            gap: DEFAULT_GAP,
+            padding: DEFAULT_PADDING,
+            delay: Self::DEFAULT_DELAY,
+            class: Theme::default(),
+        }
+    }
+
+    /// Sets the distance between the content and the tooltip.
+    pub fn gap(mut self, gap: f32) -> Self {
+        self.gap = gap;
+        self
+    }
+
+    /// Sets the padding around the tooltip's contents.
+    pub fn padding(mut self, padding: impl Into<Padding>) -> Self {
+        self.padding = padding.into();
+        self
+    }
+
+    /// Sets how long the content needs to be hovered before the tooltip is shown. Tooltips for
+    /// focused content are always shown immediately.
+    pub fn delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// Sets the style of the [`Tooltip`].
+    pub fn style(mut self, style: impl Fn(&Theme) -> Style + 'a) -> Self
+    where
+        Theme::Class<'a>: From<StyleFn<'a, Theme>>,
+    {
+        self.class = (Box::new(style) as StyleFn<'a, Theme>).into();
+        self
+    }
+
+    /// Sets the style class of the [`Tooltip`].
+    pub fn class(mut self, class: impl Into<Theme::Class<'a>>) -> Self {
+        self.class = class.into();
+        self
+    }
+
+    /// Update the state's focus flag from the content's focusable widgets.
+    fn probe_focus(&mut self, tree: &mut Tree, layout: Layout<'_>, renderer: &Renderer) {
+        let mut probe = FocusProbe { focused: false };
+        self.content
+            .as_widget_mut()
+            .operate(&mut tree.children[0], layout, renderer, &mut probe);
+
+        tree.state
+            .downcast_mut::<State>()
+            .set_focused(probe.focused);
+    }
+}
+
+impl<'a, Message, Theme, Renderer> Widget<Message, Theme, Renderer>
+    for Tooltip<'a, Message, Theme, Renderer>
+where
+    Theme: Catalog,
+    Renderer: crate::core::Renderer,
+{
+    fn tag(&self) -> tree::Tag {
+        tree::Tag::of::<State>()
+    }
+
+    fn state(&self) -> tree::State {
+        tree::State::new(State::default())
+    }
+
+    fn children(&self) -> Vec<Tree> {
+        vec![Tree::new(&self.content), Tree::new(&self.tooltip)]
+    }
+
+    fn diff(&self, tree: &mut Tree) {
+        tree.diff_children(&[self.content.as_widget(), self.tooltip.as_widget()]);
+    }
+
+    fn size(&self) -> Size<Length> {
+        self.content.as_widget().size()
+    }
+
+    fn size_hint(&self) -> Size<Length> {
+        self.content.as_widget().size_hint()
+    }
+
+    fn layout(
+        &mut self,
+        tree: &mut Tree,
+        renderer: &Renderer,
+        limits: &layout::Limits,
+    ) -> layout::Node {
+        self.content
+            .as_widget_mut()
+            .layout(&mut tree.children[0], renderer, limits)
+    }
+
+    fn draw(
+        &self,
+        tree: &Tree,
+        renderer: &mut Renderer,
+        theme: &Theme,
+        style: &renderer::Style,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        viewport: &Rectangle,
+    ) {
+        self.content.as_widget().draw(
+            &tree.children[0],
+            renderer,
+            theme,
+            style,
+            layout,
+            cursor,
+            viewport,
+        );
+    }
+
+    fn update(
+        &mut self,
+        tree: &mut Tree,
+        event: &Event,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        renderer: &Renderer,
+        clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Message>,
+        viewport: &Rectangle,
+    ) {
+        let now = Instant::now();
+        let state = tree.state.downcast_mut::<State>();
+        let was_visible = state.is_visible(now, self.delay);
+
+        match event {
+            Event::Mouse(mouse::Event::ButtonPressed(_)) if cursor.is_over(layout.bounds()) => {
+                state.set_hovered(true, now);
+                state.dismissed = true;
+            }
+            Event::Mouse(_) => {
+                state.set_hovered(cursor.is_over(layout.bounds()), now);
+                if let Some(since) = state.hovered_since {
+                    shell.request_redraw_at(since + self.delay);
+                }
+            }
+            Event::Keyboard(keyboard::Event::KeyPressed {
+                key: keyboard::Key::Named(key::Named::Escape),
+                ..
+            }) if was_visible => {
+                state.dismissed = true;
+            }
+            _ => {}
+        }
+
+        self.content.as_widget_mut().update(
+            &mut tree.children[0],
+            event,
+            layout,
+            cursor,
+            renderer,
+            clipboard,
+            shell,
+            viewport,
+        );
+
+        // The content may have gained or lost focus while handling the event
+        self.probe_focus(tree, layout, renderer);
+        if tree
+            .state
+            .downcast_ref::<State>()
+            .is_visible(now, self.delay)
+            != was_visible
+        {
+            shell.invalidate_layout();
+            shell.request_redraw();
+        }
+    }
+
+    fn mouse_interaction(
+        &self,
+        tree: &Tree,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        viewport: &Rectangle,
+        renderer: &Renderer,
+    ) -> mouse::Interaction {
+        self.content.as_widget().mouse_interaction(
+            &tree.children[0],
+            layout,
+            cursor,
+            viewport,
+            renderer,
+        )
+    }
+
+    fn operate(
+        &mut self,
+        tree: &mut Tree,
+        layout: Layout<'_>,
+        renderer: &Renderer,
+        operation: &mut dyn Operation,
+    ) {
+        operation.container(None, layout.bounds());
+        operation.traverse(&mut |operation| {
+            self.content.as_widget_mut().operate(
+                &mut tree.children[0],
+                layout,
+                renderer,
+                operation,
+            );
+        });
+    }
+
+    fn overlay<'b>(
+        &'b mut self,
+        tree: &'b mut Tree,
+        layout: Layout<'b>,
+        renderer: &Renderer,
+        viewport: &Rectangle,
+        translation: Vector,
+    ) -> Option<overlay::Element<'b, Message, Theme, Renderer>> {
+        // Focus can also change through widget operations, which the tooltip doesn't see
+        self.probe_focus(tree, layout, renderer);
+        let is_visible = tree
+            .state
+            .downcast_ref::<State>()
+            .is_visible(Instant::now(), self.delay);
+
+        let mut children = tree.children.iter_mut();
+        let content = self.content.as_widget_mut().overlay(
+            children.next().unwrap(),
+            layout,
+            renderer,
+            viewport,
+            translation,
+        );
+
+        let tooltip = is_visible.then(|| {
+            overlay::Element::new(Box::new(Overlay {
+                tooltip: &mut self.tooltip,
+                tree: children.next().unwrap(),
+                target: layout.bounds() + translation,
+                position: self.position,
+                gap: self.gap,
+                padding: self.padding,
+                class: &self.class,
+            }))
+        });
+
+        if content.is_some() || tooltip.is_some() {
+            Some(
+                overlay::Group::with_children(content.into_iter().chain(tooltip).collect())
+                    .overlay(),
+            )
+        } else {
+            None
+        }
+    }
+}
+
+impl<'a, Message, Theme, Renderer> From<Tooltip<'a, Message, Theme, Renderer>>
+    for Element<'a, Message, Theme, Renderer>
+where
+    Message: 'a,
+    Theme: Catalog + 'a,
+    Renderer: crate::core::Renderer + 'a,
+{
+    fn from(widget: Tooltip<'a, Message, Theme, Renderer>) -> Self {
+        Element::new(widget)
+    }
+}
+
+/// The shown tooltip, drawn on iced's overlay layer so it's shown on top of every other widget in
+/// the window.
+pub(super) struct Overlay<'a, 'b, Message, Theme, Renderer>
+where
+    Theme: Catalog,
+{
+    pub tooltip: &'b mut Element<'a, Message, Theme, Renderer>,
+    pub tree: &'b mut Tree,
+    /// The absolute bounds of the content the tooltip belongs to.
+    pub target: Rectangle,
+    pub position: Position,
+    pub gap: f32,
+    pub padding: Padding,
+    pub class: &'b Theme::Class<'a>,
+}
+
+impl<Message, Theme, Renderer> overlay::Overlay<Message, Theme, Renderer>
+    for Overlay<'_, '_, Message, Theme, Renderer>
+where
+    Theme: Catalog,
+    Renderer: crate::core::Renderer,
+{
+    fn layout(&mut self, renderer: &Renderer, bounds: Size) -> layout::Node {
+        let limits = layout::Limits::new(Size::ZERO, bounds).shrink(self.padding);
+        let content = self
+            .tooltip
+            .as_widget_mut()
+            .layout(self.tree, renderer, &limits);
+
+        let tooltip_bounds = tooltip_bounds(
+            self.target,
+            content.size().expand(self.padding),
+            bounds,
+            self.position,
+            self.gap,
+        );
+
+        layout::Node::with_children(
+            tooltip_bounds.size(),
+            vec![content.move_to((self.padding.left, self.padding.top))],
+        )
+        .move_to(tooltip_bounds.position())
+    }
+
+    fn draw(
+        &self,
+        renderer: &mut Renderer,
+        theme: &Theme,
+        style: &renderer::Style,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+    ) {
+        let bounds = layout.bounds();
+        let appearance = theme.style(self.class);
+
+        renderer.fill_quad(
+            renderer::Quad {
+                bounds: crate::scale::pixel_snap(bounds),
+                border: super::util::snap_border(appearance.border),
+                shadow: appearance.shadow,
+                ..Default::default()
+            },
+            appearance.background,
+        );
+
+        self.tooltip.as_widget().draw(
+            self.tree,
+            renderer,
+            theme,
+            &renderer::Style {
+                text_color: appearance.text_color.unwrap_or(style.text_color),
+            },
+            layout.children().next().unwrap(),
+            cursor,
+            &bounds,
+        );
+    }
+}
+
+/// Compute where a tooltip of `size` should go. The tooltip is centered on the preferred side of
+/// `target`, moved to the opposite side if there's more room there, and always clamped to the
+/// window.
+fn tooltip_bounds(
+    target: Rectangle,
+    size: Size,
+    window: Size,
+    position: Position,
+    gap: f32,
+) -> Rectangle {
+    let width = size.width.min(window.width);
+    let height = size.height.min(window.height);
+
+    let above = target.y - gap - height;
+    let below = target.y + target.height + gap;
+    let left = target.x - gap - width;
+    let right = target.x + target.width + gap;
+    let (x, y) = match position {
+        Position::Top | Position::Bottom => {
+            let fits_above = above >= 0.0;
+            let fits_below = below + height <= window.height;
+            let y = match position {
+                Position::Top if fits_above || !fits_below => above,
+                Position::Bottom if fits_below || !fits_above => below,
+                Position::Top => below,
+                _ => above,
+            };
+
+            (target.center_x() - (width / 2.0), y)
+        }
+        Position::Left | Position::Right => {
+            let fits_left = left >= 0.0;
+            let fits_right = right + width <= window.width;
+            let x = match position {
+                Position::Left if fits_left || !fits_right => left,
+                Position::Right if fits_right || !fits_left => right,
+                Position::Left => right,
+                _ => left,
+            };
+
+            (x, target.center_y() - (height / 2.0))
+        }
+    };
+
+    Rectangle {
+        x: x.clamp(0.0, window.width - width),
+        y: y.clamp(0.0, window.height - height),
+        width,
+        height,
+    }
+}
+
+/// The appearance of a [`Tooltip`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Style {
+    /// The [`Background`] of the tooltip.
+    pub background: Background,
+    /// The [`Border`] of the tooltip.
+    pub border: Border,
+    /// The [`Shadow`] of the tooltip.
+    pub shadow: Shadow,
+    /// The default text color of the tooltip's contents. The window's text color is used if this
+    /// is not set.
+    pub text_color: Option<Color>,
+}
+
+/// The theme catalog of a [`Tooltip`].
+pub trait Catalog: Sized {
+    /// The item class of the [`Catalog`].
+    type Class<'a>;
+
+    /// The default class produced by the [`Catalog`].
+    fn default<'a>() -> Self::Class<'a>;
+
+    /// The [`Style`] of a class.
+    fn style(&self, class: &Self::Class<'_>) -> Style;
+}
+
+/// A styling function for a [`Tooltip`].
+pub type StyleFn<'a, Theme> = Box<dyn Fn(&Theme) -> Style + 'a>;
+
+impl Catalog for Theme {
+    type Class<'a> = StyleFn<'a, Self>;
+
+    fn default<'a>() -> Self::Class<'a> {
+        Box::new(default)
+    }
+
+    fn style(&self, class: &Self::Class<'_>) -> Style {
+        class(self)
+    }
+}
+
+/// The default style of a [`Tooltip`].
+pub fn default(theme: &Theme) -> Style {
+    let colors = crate::theme::colors(theme);
+
+    Style {
+        background: Background::Color(colors.surface),
+        border: Border {
+            color: colors.outline,
+            width: 1.0,
+            radius: 2.0.into(),
+        },
+        shadow: Shadow::default(),
+        text_color: Some(colors.text),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::widget::operation::focusable;
+    use crate::runtime::user_interface::{self, UserInterface};
+    use std::cell::Cell;
+
+    /// A focusable widget that records whether it has been drawn.
+    struct Probe<'a> {
+        id: Id,
+        drawn: &'a Cell<bool>,
+    }
+
+    #[derive(Default)]
+    struct ProbeState(bool);
+
+    impl Focusable for ProbeState {
+        fn is_focused(&self) -> bool {
+            self.0
+        }
+
+        fn focus(&mut self) {
+            self.0 = true;
+        }
+
+        fn unfocus(&mut self) {
+            self.0 = false;
+        }
+    }
+
+    impl<Message, Theme, Renderer: crate::core::Renderer> Widget<Message, Theme, Renderer>
+        for Probe<'_>
+    {
+        fn tag(&self) -> tree::Tag {
+            tree::Tag::of::<ProbeState>()
+        }
+
+        fn state(&self) -> tree::State {
+            tree::State::new(ProbeState::default())
+        }
+
+        fn size(&self) -> Size<Length> {
+            Size::new(Length::Fixed(20.0), Length::Fixed(20.0))
+        }
+
+        fn layout(
+            &mut self,
+            _tree: &mut Tree,
+            _renderer: &Renderer,
+            limits: &layout::Limits,
+        ) -> layout::Node {
+            layout::atomic(limits, 20.0, 20.0)
+        }
+
+        fn draw(
+            &self,
+            _tree: &Tree,
+            _renderer: &mut Renderer,
+            _theme: &Theme,
+            _style: &renderer::Style,
+            _layout: Layout<'_>,
+            _cursor: mouse::Cursor,
+            _viewport: &Rectangle,
+        ) {
+            self.drawn.set(true);
+        }
+
+        fn operate(
+            &mut self,
+            tree: &mut Tree,
+            layout: Layout<'_>,
+            _renderer: &Renderer,
+            operation: &mut dyn Operation,
+        ) {
+            let state = tree.state.downcast_mut::<ProbeState>();
+            operation.focusable(Some(&self.id), layout.bounds(), state);
+        }
+    }
+
+    #[test]
+    fn visibility_rules() {
+        let delay = Duration::from_millis(500);
+        let start = Instant::now();
+        let at = |ms| start + Duration::from_millis(ms);
+        let mut state = State::default();
+
+        // Hovering waits for the delay
+        state.set_hovered(true, at(0));
+        assert!(!state.is_visible(at(100), delay));
+        assert!(state.is_visible(at(500), delay));
+        state.set_hovered(false, at(600));
+        assert!(!state.is_visible(at(600), delay));
+
+        // Focus shows the tooltip immediately, without the cursor
+        state.set_focused(true);
+        assert!(state.is_visible(at(600), delay));
+
+        // Dismissing keeps it hidden until the content is neither hovered nor focused
+        state.dismissed = true;
+        state.set_hovered(true, at(700));
+        state.set_hovered(false, at(800));
+        assert!(!state.is_visible(at(800), delay));
+        state.set_focused(false);
+        state.set_focused(true);
+        assert!(state.is_visible(at(800), delay));
+    }
+
+    #[test]
+    fn focus_shows_tooltip_without_pointer() {
+        let id = Id::unique();
+        let content_drawn = Cell::new(false);
+        let tooltip_drawn = Cell::new(false);
+        let view = || -> Element<'_, (), Theme, ()> {
+            Tooltip::new(
+                Element::new(Probe {
+                    id: id.clone(),
+                    drawn: &content_drawn,
+                }),
+                Element::new(Probe {
+                    id: Id::unique(),
+                    drawn: &tooltip_drawn,
+                }),
+                Position::Top,
+            )
+            // A hover would never show the tooltip during this test
+            .delay(Duration::from_secs(3600))
+            .into()
+        };
+
+        let window = Size::new(200.0, 100.0);
+        let style = renderer::Style {
+            text_color: Color::BLACK,
+        };
+        let frame = |cache, focus: bool| {
+            let mut interface = UserInterface::build(view(), window, cache, &mut ());
+            if focus {
+                interface.operate(&(), &mut focusable::focus(id.clone()));
+            }
+            let _ = interface.update(
+                &[],
+                mouse::Cursor::Unavailable,
+                &mut (),
+                &mut crate::core::clipboard::Null,
+                &mut Vec::new(),
+            );
+            interface.draw(&mut (), &Theme::Dark, &style, mouse::Cursor::Unavailable);
+
+            interface.into_cache()
+        };
+
+        let cache = frame(user_interface::Cache::default(), false);
+        assert!(content_drawn.get());
+        assert!(!tooltip_drawn.get());
+
+        let _ = frame(cache, true);
+        assert!(tooltip_drawn.get());
+    }
+
+    #[test]
+    fn tooltip_flips_and_stays_in_window() {
+        let window = Size::new(200.0, 100.0);
+        let size = Size::new(40.0, 20.0);
+
+        // Room above
+        let target = Rectangle::new((80.0, 50.0).into(), Size::new(40.0, 20.0));
+        assert_eq!(
+            tooltip_bounds(target, size, window, Position::Top, 4.0),
+            Rectangle::new((80.0, 26.0).into(), size)
+        );
+
+        // No room above, so it's shown below instead
+        let target = Rectangle::new((0.0, 0.0).into(), Size::new(20.0, 20.0));
+        assert_eq!(
+            tooltip_bounds(target, size, window, Position::Top, 4.0),
+            Rectangle::new((0.0, 24.0).into(), size)
+        );
+
+        // No room on the right, and it's clamped vertically
+        let target = Rectangle::new((180.0, 90.0).into(), Size::new(20.0, 10.0));
+        assert_eq!(
+            tooltip_bounds(target, size, window, Position::Right, 4.0),
+            Rectangle::new((136.0, 80.0).into(), size)
+        );
+    }
+}
@@ -1,27 +1,284 @@
 //! Utilities for creating these widgets.
+//!
+//! Widgets that draw data coming from the audio thread can receive `NaN` and infinite values, for
+//! instance when a plugin's DSP blows up. The conversion functions here never return non-finite
+//! values for finite rectangles and colors, so those values can't end up in the layout or draw
+//! calls.
 
-use crate::Rectangle;
+use crate::core::{renderer, Border, Point};
+use crate::{Color, Rectangle};
+
+/// The maximum number of dots [`fill_arc()`] and [`fill_line()`] draw for a single arc or line.
+const MAX_ARC_DOTS: usize = 512;
 
 /// Remap a `[0, 1]` value to an x-coordinate within this rectangle. The value will be clamped to
-/// `[0, 1]` if it isn't already in that range.
+/// `[0, 1]` if it isn't already in that range. `NaN` is treated as 0.
 pub fn remap_rect_x_t(rect: &Rectangle, t: f32) -> f32 {
-    rect.x + (rect.width * t.clamp(0.0, 1.0))
+    rect.x + (rect.width * clamp_t(t))
 }
 
 /// Remap a `[0, 1]` value to a y-coordinate within this rectangle. The value will be clamped to
-/// `[0, 1]` if it isn't already in that range.
+/// `[0, 1]` if it isn't already in that range. `NaN` is treated as 0.
 pub fn remap_rect_y_t(rect: &Rectangle, t: f32) -> f32 {
-    rect.y + (rect.height * t.clamp(0.0, 1.0))
+    rect.y + (rect.height * clamp_t(t))
 }
 
 /// Remap an x-coordinate to a `[0, 1]` value within this rectangle. The value will be clamped to
-/// `[0, 1]` if it isn't already in that range.
+/// `[0, 1]` if it isn't already in that range. Returns 0 for `NaN` coordinates and for coordinates
+/// at the start of an empty rectangle.
 pub fn remap_rect_x_coordinate(rect: &Rectangle, x_coord: f32) -> f32 {
-    ((x_coord - rect.x) / rect.width).clamp(0.0, 1.0)
+    clamp_t((x_coord - rect.x) / rect.width)
 }
 
 /// Remap a y-coordinate to a `[0, 1]` value within this rectangle. The value will be clamped to
-/// `[0, 1]` if it isn't already in that range.
+/// `[0, 1]` if it isn't already in that range. Returns 0 for `NaN` coordinates and for coordinates
+/// at the start of an empty rectangle.
 pub fn remap_rect_y_coordinate(rect: &Rectangle, y_coord: f32) -> f32 {
-    ((y_coord - rect.y) / rect.height).clamp(0.0, 1.0)
+    clamp_t((y_coord - rect.y) / rect.height)
+}
+
+/// Linearly interpolate between two colors, including their alpha channels. `t` will be clamped to
+/// `[0, 1]` if it isn't already in that range. `NaN` is treated as 0.
+pub fn mix_colors(a: Color, b: Color, t: f32) -> Color {
+    let t = clamp_t(t);
+
+    Color {
+        r: a.r + ((b.r - a.r) * t),
+        g: a.g + ((b.g - a.g) * t),
+        b: a.b + ((b.b - a.b) * t),
+        a: a.a + ((b.a - a.a) * t),
+    }
+}
+
+/// Sanitize a level in decibel read from the audio thread. `NaN` is treated as silence, so it
+/// becomes negative infinity. Positive infinity is clamped to [`f32::MAX`] so it still compares
+/// above every other level.
+pub fn sanitize_db(db: f32) -> f32 {
+    if db.is_nan() {
+        f32::NEG_INFINITY
+    } else {
+        db.min(f32::MAX)
+    }
+}
+
+/// Remap a level in decibel to a `[0, 1]` value within the `[min_db, max_db]` range. Levels outside
+/// of the range are clamped, and `NaN` is treated as silence.
+pub fn db_to_t(db: f32, min_db: f32, max_db: f32) -> f32 {
+    let db = sanitize_db(db).clamp(min_db, max_db);
+
+    clamp_t((db - min_db) / (max_db - min_db))
+}
+
+/// Return `value` if it's finite, or `fallback` otherwise.
+pub fn finite_or(value: f32, fallback: f32) -> f32 {
+    if value.is_finite() {
+        value
+    } else {
+        fallback
+    }
+}
+
+/// `border` with its width rounded to whole physical pixels, see
+/// [`scale::snap_stroke()`][crate::scale::snap_stroke()]. Use this together with
+/// [`scale::pixel_snap()`][crate::scale::pixel_snap()] for the quad's bounds.
+pub fn snap_border(border: Border) -> Border {
+    Border {
+        width: crate::scale::snap_stroke(border.width),
+        ..border
+    }
+}
+
+/// The point on a circle at an angle in radians, measured clockwise from the top of the circle.
+pub fn point_on_circle(center: Point, radius: f32, angle: f32) -> Point {
+    Point::new(
+        center.x + (radius * angle.sin()),
+        center.y - (radius * angle.cos()),
+    )
+}
+
+/// Draw a circular arc with round ends using only quads, so this works without the `geometry`
+/// feature. Angles are in radians, measured clockwise from the top of the circle, and a negative
+/// sweep draws the arc counterclockwise. The arc is centered on `radius`, so it extends
+/// `thickness / 2` pixels inwards and outwards. Nothing is drawn if the sweep is zero or any of the
+/// values are not finite.
+pub fn fill_arc<Renderer: renderer::Renderer>(
+    renderer: &mut Renderer,
+    center: Point,
+    radius: f32,
+    thickness: f32,
+    start_angle: f32,
+    sweep: f32,
+    color: Color,
+) {
+    let is_finite = [center.x, center.y, radius, thickness, start_angle, sweep]
+        .iter()
+        .all(|value| value.is_finite());
+    if !is_finite || sweep == 0.0 || radius < 0.0 || thickness <= 0.0 {
+        return;
+    }
+
+    // The arc is drawn as a row of overlapping circles, spaced closely enough that the edges look
+    // smooth
+    let spacing = (thickness / 4.0).max(0.5);
+    let dots = ((sweep.abs() * radius) / spacing).ceil() as usize;
+    let dots = dots.clamp(1, MAX_ARC_DOTS);
+    for i in 0..=dots {
+        let angle = start_angle + (sweep * (i as f32 / dots as f32));
+        let point = point_on_circle(center, radius, angle);
+
+        renderer.fill_quad(
+            renderer::Quad {
+                bounds: Rectangle {
+                    x: point.x - (thickness / 2.0),
+                    y: point.y - (thickness / 2.0),
+                    width: thickness,
+                    height: thickness,
+                },
+                border: Border {
+                    radius: (thickness / 2.0).into(),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            color,
+        );
+    }
+}
+
+/// Draw a straight line with round ends using only quads, like [`fill_arc()`]. The line is
+/// `thickness` pixels wide, and its ends are centered on `from` and `to`. Nothing is drawn if any
+/// of the values are not finite.
+pub fn fill_line<Renderer: renderer::Renderer>(
+    renderer: &mut Renderer,
+    from: Point,
+    to: Point,
+    thickness: f32,
+    color: Color,
+) {
+    let is_finite = [from.x, from.y, to.x, to.y, thickness]
+        .iter()
+        .all(|value| value.is_finite());
+    if !is_finite || thickness <= 0.0 {
+        return;
+    }
+
+    let spacing = (thickness / 4.0).max(0.5);
+    let dots = (from.distance(to) / spacing).ceil() as usize;
+    let dots = dots.clamp(1, MAX_ARC_DOTS);
+    for i in 0..=dots {
+        let t = i as f32 / dots as f32;
+        let point = Point::new(
+            from.x + ((to.x - from.x) * t),
+            from.y + ((to.y - from.y) * t),
+        );
+
+        renderer.fill_quad(
+            renderer::Quad {
+                bounds: Rectangle {
+                    x: point.x - (thickness / 2.0),
+                    y: point.y - (thickness / 2.0),
+                    width: thickness,
+                    height: thickness,
+                },
+                border: Border {
+                    radius: (thickness / 2.0).into(),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            color,
+        );
+    }
+}
+
+/// Clamp `t` to `[0, 1]`, treating `NaN` as 0.
+fn clamp_t(t: f32) -> f32 {
+    if t.is_nan() {
+        0.0
+    } else {
+        t.clamp(0.0, 1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SPECIAL_VALUES: [f32; 4] = [f32::NAN, f32::INFINITY, f32::NEG_INFINITY, -0.0];
+
+    #[test]
+    fn remapping_non_finite_values() {
+        let rect = Rectangle::new([10.0, 20.0].into(), [100.0, 50.0].into());
+        let empty = Rectangle::new([10.0, 20.0].into(), [0.0, 0.0].into());
+
+        let expected_t = [0.0, 1.0, 0.0, 0.0];
+        for (value, t) in SPECIAL_VALUES.into_iter().zip(expected_t) {
+            assert_eq!(remap_rect_x_t(&rect, value), 10.0 + (100.0 * t), "{value}");
+            assert_eq!(remap_rect_y_t(&rect, value), 20.0 + (50.0 * t), "{value}");
+            assert_eq!(remap_rect_x_coordinate(&rect, value), t, "{value}");
+            assert_eq!(remap_rect_y_coordinate(&rect, value), t, "{value}");
+
+            for rect in [rect, empty] {
+                assert!(remap_rect_x_t(&rect, value).is_finite());
+                assert!(remap_rect_x_coordinate(&rect, value).is_finite());
+                assert!(remap_rect_y_coordinate(&rect, value).is_finite());
+            }
+        }
+
+        // Empty rectangles would otherwise divide by zero
+        assert_eq!(remap_rect_x_coordinate(&empty, 10.0), 0.0);
+        assert_eq!(remap_rect_x_coordinate(&empty, 15.0), 1.0);
+        assert_eq!(remap_rect_y_coordinate(&empty, 5.0), 0.0);
+    }
+
+    #[test]
+    fn mixing_colors_with_non_finite_values() {
+        for value in SPECIAL_VALUES {
+            let color = mix_colors(Color::BLACK, Color::WHITE, value);
+            let expected = if value == f32::INFINITY {
+                Color::WHITE
+            } else {
+                Color::BLACK
+            };
+
+            assert_eq!(color, expected, "{value}");
+        }
+    }
+
+    #[test]
+    fn points_on_circle() {
+        let center = Point::new(10.0, 10.0);
+        let expected = [
+            (0.0, Point::new(10.0, 5.0)),
+            (std::f32::consts::FRAC_PI_2, Point::new(15.0, 10.0)),
+            (std::f32::consts::PI, Point::new(10.0, 15.0)),
+            (-std::f32::consts::FRAC_PI_2, Point::new(5.0, 10.0)),
+        ];
+        for (angle, point) in expected {
+            assert!(
+                point_on_circle(center, 5.0, angle).distance(point) < 1e-4,
+                "{angle}"
+            );
+        }
+    }
+
+    #[test]
+    fn sanitizing_levels() {
+        assert_eq!(sanitize_db(f32::NAN), f32::NEG_INFINITY);
+        assert_eq!(sanitize_db(f32::INFINITY), f32::MAX);
+        assert_eq!(sanitize_db(f32::NEG_INFINITY), f32::NEG_INFINITY);
+        assert_eq!(sanitize_db(-0.0), 0.0);
+        assert_eq!(sanitize_db(-12.0), -12.0);
+
+        let expected_t = [0.0, 1.0, 0.0, 90.0 / 110.0];
+        for (value, t) in SPECIAL_VALUES.into_iter().zip(expected_t) {
+            assert_eq!(db_to_t(value, -90.0, 20.0), t, "{value}");
+        }
+        assert_eq!(db_to_t(-200.0, -90.0, 20.0), 0.0);
+        assert_eq!(db_to_t(-35.0, -90.0, 20.0), 0.5);
+
+        assert_eq!(finite_or(f32::NAN, 1.0), 1.0);
+        assert_eq!(finite_or(f32::NEG_INFINITY, 1.0), 1.0);
+        assert_eq!(finite_or(-0.0, 1.0), 0.0);
+    }
 }
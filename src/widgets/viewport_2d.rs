@@ -0,0 +1,456 @@
+//! Zooming and panning for plot widgets.
+//!
+//! Waveform, spectrum, and spectrogram views all want to zoom in around the cursor with Ctrl/Cmd
+//! and the scroll wheel, and to pan by dragging. A [`Viewport2D`] stored in the plot widget's state
+//! keeps track of the region of the content that's currently visible, and handles those events
+//! when the widget passes them to [`Viewport2D::update()`]:
+//!
+//! - Scrolling while holding Ctrl/Cmd zooms in or out. The point under the cursor stays under the
+//!   cursor, unless that would show something outside of the content's bounds. The scroll amount
+//!   follows the [`WheelSettings`][crate::WheelSettings].
+//! - Dragging with the left mouse button or a single finger pans the view.
+//! - Pinching with two fingers zooms around the point between them. Baseview doesn't deliver touch
+//!   events at the moment, but hosts that do get this for free.
+//!
+//! The view is expressed in the content's own coordinates, like samples and decibels for a
+//! waveform, or `[0, 1]` values from [`axis::frequency_to_t()`][super::axis::frequency_to_t()] for
+//! a spectrum. It never extends past the content's bounds, and it can't get smaller than the
+//! content divided by the maximum zoom factor. Use [`to_content()`][Viewport2D::to_content()] and
+//! [`to_screen()`][Viewport2D::to_screen()] to convert between the widget's bounds and content
+//! coordinates while drawing.
+//!
+//! [`update()`][Viewport2D::update()] returns the new view whenever it changed. Widgets should
+//! publish that as a message so the editor can store the zoom, and restore it later with
+//! [`set_view()`][Viewport2D::set_view()]:
+//!
+//! ```ignore
+//! if let Some(view) = state.viewport.update(&event, bounds, cursor, shell) {
+//!     if let Some(on_view_change) = &self.on_view_change {
+//!         shell.publish(on_view_change(view));
+//!     }
+//! }
+//! ```
+
+use crate::core::{keyboard, mouse, touch, Event, Point, Rectangle, Shell, Size, Vector};
+use crate::interaction;
+
+/// How much a single scroll step zooms in or out.
+const ZOOM_PER_STEP: f32 = 1.25;
+
+/// Which axes a [`Viewport2D`] zooms and pans along. The other axis always shows the entire
+/// content.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ZoomAxes {
+    /// Only zoom along the x-axis, like for waveforms.
+    Horizontal,
+    /// Only zoom along the y-axis.
+    Vertical,
+    /// Zoom along both axes.
+    #[default]
+    Both,
+}
+
+impl ZoomAxes {
+    fn x(self) -> bool {
+        self != ZoomAxes::Vertical
+    }
+
+    fn y(self) -> bool {
+        self != ZoomAxes::Horizontal
+    }
+}
+
+/// The visible region of a plot's content, with the zoom and pan interactions that change it. See
+/// the [module documentation][self].
+#[derive(Debug, Clone)]
+pub struct Viewport2D {
+    content: Rectangle,
+    view: Rectangle,
+    axes: ZoomAxes,
+    max_zoom: f32,
+    keyboard_modifiers: keyboard::Modifiers,
+    /// The cursor position the current drag was last at.
+    drag: Option<Point>,
+    /// The fingers currently touching the widget, at most two.
+    fingers: Vec<(touch::Finger, Point)>,
+}
+
+impl Viewport2D {
+    /// A viewport showing all of `content`, which is in the content's own coordinates.
+    pub fn new(content: Rectangle) -> Self {
+        Self {
+            content,
+            view: content,
+            axes: ZoomAxes::default(),
+            max_zoom: 64.0,
+            keyboard_modifiers: interaction::modifiers(),
+            drag: None,
+            fingers: Vec::new(),
+        }
+    }
+
+    /// Only zoom along these axes. Defaults to [`ZoomAxes::Both`].
+    pub fn axes(mut self, axes: ZoomAxes) -> Self {
+        self.axes = axes;
+        self.view = self.clamp(self.view);
+        self
+    }
+
+    /// How far in the view can be zoomed, as the ratio between the content's size and the view's
+    /// size. Defaults to 64.
+    pub fn max_zoom(mut self, max_zoom: f32) -> Self {
+        self.max_zoom = if max_zoom.is_finite() {
+            max_zoom.max(1.0)
+        } else {
+            1.0
+        };
+        self.view = self.clamp(self.view);
+        self
+    }
+
+    /// The region of the content that's currently visible.
+    pub fn view(&self) -> Rectangle {
+        self.view
+    }
+
+    /// Show a region of the content, for instance to restore a view that was stored earlier. The
+    /// region is clamped to the content and the zoom limits.
+    pub fn set_view(&mut self, view: Rectangle) {
+        self.view = self.clamp(view);
+    }
+
+    /// Show all of the content again.
+    pub fn reset(&mut self) {
+        self.view = self.content;
+    }
+
+    /// Change the content's bounds, for instance after loading a longer sample. The view stays
+    /// where it is as far as the new bounds allow.
+    pub fn set_content(&mut self, content: Rectangle) {
+        self.content = content;
+        self.view = self.clamp(self.view);
+    }
+
+    /// How far the view is zoomed in along each axis, as the ratio between the content's size and
+    /// the view's size.
+    pub fn zoom(&self) -> Vector {
+        Vector::new(
+            ratio(self.content.width, self.view.width),
+            ratio(self.content.height, self.view.height),
+        )
+    }
+
+    /// Convert a position within `bounds` to content coordinates.
+    pub fn to_content(&self, bounds: Rectangle, position: Point) -> Point {
+        Point::new(
+            self.view.x + fraction(position.x - bounds.x, bounds.width) * self.view.width,
+            self.view.y + fraction(position.y - bounds.y, bounds.height) * self.view.height,
+        )
+    }
+
+    /// Convert content coordinates to a position within `bounds`.
+    pub fn to_screen(&self, bounds: Rectangle, point: Point) -> Point {
+        Point::new(
+            bounds.x + fraction(point.x - self.view.x, self.view.width) * bounds.width,
+            bounds.y + fraction(point.y - self.view.y, self.view.height) * bounds.height,
+        )
+    }
+
+    /// Zoom in by `factor` around `anchor`, which is a position within `bounds`. Factors below 1
+    /// zoom out. The content under the anchor stays under the anchor unless the view hits the
+    /// content's bounds or the zoom limits.
+    pub fn zoom_at(&mut self, bounds: Rectangle, anchor: Point, factor: f32) {
+        if !factor.is_finite() || factor <= 0.0 {
+            return;
+        }
+
+        let anchored = self.to_content(bounds, anchor);
+        let size = Size::new(self.view.width / factor, self.view.height / factor);
+        // The zoom limits are applied first so the anchor also stays in place at the limits
+        let size = self
+            .clamp(Rectangle::new(self.view.position(), size))
+            .size();
+        // The anchor keeps its relative position within the view
+        let x = anchored.x - fraction(anchor.x - bounds.x, bounds.width) * size.width;
+        let y = anchored.y - fraction(anchor.y - bounds.y, bounds.height) * size.height;
+
+        self.view = self.clamp(Rectangle::new(Point::new(x, y), size));
+    }
+
+    /// Move the content by `delta` logical pixels within `bounds`, like when dragging it.
+    pub fn pan_by(&mut self, bounds: Rectangle, delta: Vector) {
+        let offset = Vector::new(
+            fraction(delta.x, bounds.width) * self.view.width,
+            fraction(delta.y, bounds.height) * self.view.height,
+        );
+
+        self.view = self.clamp(Rectangle::new(
+            Point::new(self.view.x - offset.x, self.view.y - offset.y),
+            self.view.size(),
+        ));
+    }
+
+    /// Handle an event for a plot widget with `bounds`. Returns the new view if the event changed
+    /// it. Events that were used for zooming or panning are captured.
+    pub fn update<Message>(
+        &mut self,
+        event: &Event,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+        shell: &mut Shell<'_, Message>,
+    ) -> Option<Rectangle> {
+        let before = self.view;
+        if interaction::is_input_locked() {
+            self.drag = None;
+            self.fingers.clear();
+        }
+
+        match event {
+            Event::Keyboard(keyboard::Event::ModifiersChanged(modifiers)) => {
+                self.keyboard_modifiers = *modifiers;
+                return None;
+            }
+            _ if interaction::is_input_locked() => return None,
+            Event::Mouse(mouse::Event::WheelScrolled { delta }) => {
+                let position = cursor.position_over(bounds)?;
+                // Plain scrolling is left to the scrollable the plot may be in
+                if !self.keyboard_modifiers.command() {
+                    return None;
+                }
+
+                let steps = interaction::current().wheel.steps(*delta);
+                self.zoom_at(bounds, position, ZOOM_PER_STEP.powf(steps));
+                shell.capture_event();
+            }
+            Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) => {
+                self.drag = cursor.position_over(bounds);
+                if self.drag.is_some() {
+                    shell.capture_event();
+                }
+            }
+            Event::Mouse(mouse::Event::CursorMoved { position }) => {
+                if let Some(last) = self.drag.replace(*position) {
+                    self.pan_by(bounds, *position - last);
+                    shell.capture_event();
+                }
+            }
+            Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) => {
+                if self.drag.take().is_some() {
+                    shell.capture_event();
+                }
+            }
+            Event::Touch(touch::Event::FingerPressed { id, position }) => {
+                if bounds.contains(*position) && self.fingers.len() < 2 {
+                    self.fingers.push((*id, *position));
+                    shell.capture_event();
+                }
+            }
+            Event::Touch(touch::Event::FingerMoved { id, position }) => {
+                let index = self.fingers.iter().position(|(finger, _)| finger == id)?;
+
+                let (center, distance) = self.pinch();
+                self.fingers[index].1 = *position;
+                let (new_center, new_distance) = self.pinch();
+                if self.fingers.len() == 2 && distance > 0.0 {
+                    self.zoom_at(bounds, center, new_distance / distance);
+                }
+                self.pan_by(bounds, new_center - center);
+                shell.capture_event();
+            }
+            Event::Touch(
+                touch::Event::FingerLifted { id, .. } | touch::Event::FingerLost { id, .. },
+            ) => {
+                let before = self.fingers.len();
+                self.fingers.retain(|(finger, _)| finger != id);
+                if self.fingers.len() != before {
+                    shell.capture_event();
+                }
+            }
+            _ => return None,
+        }
+
+        if self.view == before {
+            return None;
+        }
+
+        shell.request_redraw();
+        Some(self.view)
+    }
+
+    /// The point between the fingers and the distance between them.
+    fn pinch(&self) -> (Point, f32) {
+        match self.fingers.as_slice() {
+            [(_, a), (_, b)] => (
+                Point::new((a.x + b.x) / 2.0, (a.y + b.y) / 2.0),
+                a.distance(*b),
+            ),
+            [(_, a)] => (*a, 0.0),
+            _ => (Point::ORIGIN, 0.0),
+        }
+    }
+
+    /// Keep `view` within the content and the zoom limits. Non-finite views are ignored.
+    fn clamp(&self, view: Rectangle) -> Rectangle {
+        let finite = [view.x, view.y, view.width, view.height]
+            .iter()
+            .all(|value| value.is_finite());
+        let view = if finite { view } else { self.view };
+
+        let (x, width) = clamp_axis(
+            self.axes.x(),
+            (view.x, view.width),
+            (self.content.x, self.content.width),
+            self.max_zoom,
+        );
+        let (y, height) = clamp_axis(
+            self.axes.y(),
+            (view.y, view.height),
+            (self.content.y, self.content.height),
+            self.max_zoom,
+        );
+
+        Rectangle {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+}
+
+/// Clamp the start and length of a view along one axis to the content's start and length.
+fn clamp_axis(zoomable: bool, view: (f32, f32), content: (f32, f32), max_zoom: f32) -> (f32, f32) {
+    let (start, length) = content;
+    if !zoomable {
+        return content;
+    }
+
+    let view_length = view.1.clamp(length / max_zoom, length);
+    let view_start = view.0.clamp(start, start + length - view_length);
+
+    (view_start, view_length)
+}
+
+/// `value / length`, or 0 for empty lengths.
+fn fraction(value: f32, length: f32) -> f32 {
+    if length == 0.0 {
+        0.0
+    } else {
+        value / length
+    }
+}
+
+/// `content / view`, or 1 for empty views.
+fn ratio(content: f32, view: f32) -> f32 {
+    if view == 0.0 {
+        1.0
+    } else {
+        content / view
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_near(a: Point, b: Point) {
+        assert!(a.distance(b) < 1e-2, "{a:?} != {b:?}");
+    }
+
+    #[test]
+    fn zoom_keeps_the_anchor_in_place() {
+        let bounds = Rectangle::new(Point::new(10.0, 20.0), Size::new(400.0, 200.0));
+        let content = Rectangle::new(Point::new(0.0, -60.0), Size::new(48_000.0, 60.0));
+        let mut viewport = Viewport2D::new(content).max_zoom(1_000.0);
+
+        for anchor in [
+            Point::new(10.0, 20.0),
+            Point::new(110.0, 70.0),
+            Point::new(300.0, 200.0),
+            Point::new(410.0, 220.0),
+        ] {
+            viewport.reset();
+            let under_cursor = viewport.to_content(bounds, anchor);
+
+            // Zooming in and back out again returns to the same view, with the same content under
+            // the cursor every step of the way
+            for factor in [1.25, 1.25, 2.0, 0.8, 1.25, 0.5] {
+                viewport.zoom_at(bounds, anchor, factor);
+                assert_near(viewport.to_content(bounds, anchor), under_cursor);
+            }
+            assert!((viewport.zoom().x - 1.25 * 1.25).abs() < 1e-3);
+            for _ in 0..2 {
+                viewport.zoom_at(bounds, anchor, 0.8);
+                assert_near(viewport.to_content(bounds, anchor), under_cursor);
+            }
+            assert_eq!(viewport.view(), content);
+            assert_near(viewport.to_screen(bounds, under_cursor), anchor);
+        }
+    }
+
+    #[test]
+    fn view_stays_within_limits() {
+        let bounds = Rectangle::new(Point::ORIGIN, Size::new(100.0, 100.0));
+        let content = Rectangle::new(Point::ORIGIN, Size::new(1.0, 1.0));
+        let mut viewport = Viewport2D::new(content)
+            .axes(ZoomAxes::Horizontal)
+            .max_zoom(4.0);
+
+        // The zoom limit and the unzoomable axis
+        viewport.zoom_at(bounds, Point::new(50.0, 50.0), 10.0);
+        assert_eq!(viewport.zoom(), Vector::new(4.0, 1.0));
+        assert_eq!(viewport.view().x, 0.375);
+
+        // Panning stops at the content's edges
+        viewport.pan_by(bounds, Vector::new(1_000.0, 1_000.0));
+        assert_eq!(
+            viewport.view(),
+            Rectangle::new(Point::ORIGIN, Size::new(0.25, 1.0))
+        );
+        viewport.pan_by(bounds, Vector::new(-1_000.0, 0.0));
+        assert_eq!(viewport.view().x, 0.75);
+
+        // Zooming out next to an edge shifts the view back into the content
+        viewport.zoom_at(bounds, Point::new(0.0, 0.0), 0.5);
+        assert_eq!(viewport.view().x, 0.5);
+        assert_eq!(viewport.view().width, 0.5);
+
+        viewport.set_view(Rectangle::new(
+            Point::new(f32::NAN, 0.0),
+            Size::new(0.1, 1.0),
+        ));
+        assert_eq!(viewport.view().x, 0.5);
+    }
+
+    #[test]
+    fn pinch_zooms_between_the_fingers() {
+        let bounds = Rectangle::new(Point::ORIGIN, Size::new(100.0, 100.0));
+        let mut viewport = Viewport2D::new(bounds);
+        let mut messages: Vec<()> = Vec::new();
+        let mut shell = Shell::new(&mut messages);
+        let mut send = |event| {
+            viewport.update(
+                &Event::Touch(event),
+                bounds,
+                mouse::Cursor::Unavailable,
+                &mut shell,
+            )
+        };
+
+        let (a, b) = (touch::Finger(0), touch::Finger(1));
+        let press = |id, x| touch::Event::FingerPressed {
+            id,
+            position: Point::new(x, 50.0),
+        };
+        let moved = |id, x| touch::Event::FingerMoved {
+            id,
+            position: Point::new(x, 50.0),
+        };
+        assert_eq!(send(press(a, 40.0)), None);
+        assert_eq!(send(press(b, 60.0)), None);
+        let view = send(moved(b, 80.0)).unwrap();
+        // Both fingers stay on the content they touched
+        assert_eq!(view.width, 50.0);
+        assert_eq!(view.x, 20.0);
+    }
+}
@@ -1,21 +1,40 @@
 //! An [`Application`] wrapper around an [`IcedEditor`] to bridge between `iced_baseview` and
 //! `nih_plug_iced`.
 
-use crossbeam::channel;
 use crate::iced_baseview::{
-    baseview::WindowScalePolicy, core::Element, futures::{Subscription, subscription::{EventStream, Hasher, Recipe, from_recipe}}, window::WindowSubs,
+    baseview::WindowScalePolicy,
+    child_window::{ChildWindowSettings, ChildWindowStatus},
+    core::Element,
+    futures::{
+        subscription::{from_recipe, EventStream, Hasher, Recipe},
+        Subscription,
+    },
+    hover::HoveredWidget,
+    interaction::InteractionSettings,
+    keyboard,
+    keymap::Resolution,
+    settings::EventFilter,
+    window::WindowSubs,
     Renderer, Task,
 };
-use futures_util::stream::BoxStream;
+use crossbeam::channel;
+use futures_util::stream::{BoxStream, StreamExt};
 use nih_plug::prelude::GuiContext;
-use std::sync::Arc;
 use std::hash::Hash;
+use std::path::PathBuf;
+use std::sync::Arc;
 
-use crate::{IcedEditor, ParameterUpdate};
+use crate::automation::AutomationWrite;
+use crate::futures::futures::channel::mpsc;
+use crate::widgets::{param_bounds, param_id};
+use crate::{gui_channel, IcedEditor, IcedState};
 
-/// A custom subscription recipe for parameter updates from a crossbeam channel
+/// A custom subscription recipe for parameter updates from a crossbeam channel. The channel's
+/// `recv()` blocks, so a helper thread waits on it and forwards every update into the stream. The
+/// stream ends when the channel's senders are dropped, and the thread stops together with the
+/// subscription.
 struct ParameterUpdatesRecipe {
-    receiver: Arc<channel::Receiver<ParameterUpdate>>,
+    receiver: Arc<channel::Receiver<()>>,
 }
 
 impl Recipe for ParameterUpdatesRecipe {
@@ -27,20 +46,54 @@ impl Recipe for ParameterUpdatesRecipe {
     }
 
     fn stream(self: Box<Self>, _input: EventStream) -> BoxStream<'static, Self::Output> {
-        Box::pin(futures_util::stream::unfold(
-            self.receiver,
-            |receiver| async move {
-                match receiver.try_recv() {
-                    Ok(_) => Some(((), receiver)),
-                    Err(channel::TryRecvError::Empty) => {
-                        // Wait a bit before checking again to avoid busy-waiting
-                        futures_util::future::pending::<()>().await;
-                        None
-                    }
-                    Err(channel::TryRecvError::Disconnected) => None,
+        // A single pending update is enough to redraw the GUI, so updates that arrive while one is
+        // still waiting to be handled are coalesced into it
+        let (sender, updates) = mpsc::channel(0);
+        // Dropping the stream drops this sender, which wakes up the thread so it can stop
+        let (stop_sender, stop_receiver) = channel::bounded::<()>(0);
+
+        let receiver = self.receiver;
+        let spawned = std::thread::Builder::new()
+            .name(String::from("nih_plug_iced parameter updates"))
+            .spawn(move || forward_parameter_updates(&receiver, &stop_receiver, sender));
+        if let Err(err) = spawned {
+            nih_plug::nih_error!("Could not spawn the parameter updates thread: {err}");
+        }
+
+        Box::pin(updates.map(move |()| {
+            let _ = &stop_sender;
+        }))
+    }
+}
+
+/// Emits a [`Message::ParameterUpdate`] for the updates sent to `receiver`'s channel.
+pub(crate) fn parameter_updates<E: IcedEditor>(
+    receiver: Arc<channel::Receiver<()>>,
+) -> Subscription<Message<E>> {
+    from_recipe(ParameterUpdatesRecipe { receiver }).map(|()| Message::ParameterUpdate)
+}
+
+/// Forward the updates from `receiver` to `sender` until either side is gone or `stop` disconnects.
+fn forward_parameter_updates(
+    receiver: &channel::Receiver<()>,
+    stop: &channel::Receiver<()>,
+    mut sender: mpsc::Sender<()>,
+) {
+    loop {
+        channel::select! {
+            recv(receiver) -> update => {
+                if update.is_err() {
+                    break;
                 }
-            },
-        ))
+
+                match sender.try_send(()) {
+                    Ok(()) => (),
+                    Err(err) if err.is_full() => (),
+                    Err(_) => break,
+                }
+            }
+            recv(stop) -> _ => break,
+        }
     }
 }
 
@@ -52,7 +105,13 @@ pub(crate) struct IcedEditorWrapperApplication<E: IcedEditor> {
     /// We will receive notifications about parameters being changed on here. Whenever a parameter
     /// update gets sent, we will trigger a [`Message::parameterUpdate`] which causes the UI to be
     /// redrawn.
-    parameter_updates_receiver: Arc<channel::Receiver<ParameterUpdate>>,
+    parameter_updates_receiver: Arc<channel::Receiver<()>>,
+
+    /// The scale factor used by the runtime is stored here so the editor can access it.
+    iced_state: Arc<IcedState>,
+
+    /// The automation write state the editor was last told about.
+    automation_write: AutomationWrite,
 }
 
 /// This wraps around `E::Message` to add a parameter update message which can be handled directly
@@ -63,16 +122,6 @@ pub enum Message<E: IcedEditor> {
     ParameterUpdate,
 }
 
-impl<E: IcedEditor> Message<E> {
-    fn into_editor_message(self) -> Option<E::Message> {
-        if let Message::EditorMessage(message) = self {
-            Some(message)
-        } else {
-            None
-        }
-    }
-}
-
 impl<E: IcedEditor> std::fmt::Debug for Message<E> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -96,20 +145,24 @@ impl<E: IcedEditor> crate::iced_baseview::Application for IcedEditorWrapperAppli
     type Message = Message<E>;
     type Flags = (
         Arc<dyn GuiContext>,
-        Arc<channel::Receiver<ParameterUpdate>>,
+        Arc<channel::Receiver<()>>,
+        Arc<IcedState>,
         E::InitializationFlags,
     );
     type Theme = E::Theme;
 
     fn new(
-        (context, parameter_updates_receiver, flags): Self::Flags,
+        (context, parameter_updates_receiver, iced_state, flags): Self::Flags,
     ) -> (Self, Task<Self::Message>) {
         let (editor, task) = E::new(flags, context);
+        let automation_write = iced_state.automation_write();
 
         (
             Self {
                 editor,
                 parameter_updates_receiver,
+                iced_state,
+                automation_write,
             },
             task.map(Message::EditorMessage),
         )
@@ -118,10 +171,39 @@ impl<E: IcedEditor> crate::iced_baseview::Application for IcedEditorWrapperAppli
     #[inline]
     fn update(&mut self, message: Self::Message) -> Task<Self::Message> {
         match message {
-            Message::EditorMessage(message) => {
-                self.editor.update(message).map(Message::EditorMessage)
+            Message::EditorMessage(message) => match E::param_message(&message) {
+                Some(message) => {
+                    self.editor.handle_param_message(message);
+
+                    Task::none()
+                }
+                None => self.editor.update(message).map(Message::EditorMessage),
+            },
+            Message::ParameterUpdate => {
+                // Changes to the automation write state are sent as parameter updates
+                let automation_write = self.iced_state.automation_write();
+                if automation_write != self.automation_write {
+                    self.automation_write = automation_write;
+                    self.editor.automation_write_changed(automation_write);
+                }
+
+                let mut tasks = Vec::new();
+                self.iced_state
+                    .param_update_suppression
+                    .take_updates(|update| {
+                        tasks.push(self.editor.handle_param_update(&update));
+                    });
+
+                Task::batch(tasks).map(Message::EditorMessage)
             }
-            Message::ParameterUpdate => Task::none(),
+        }
+    }
+
+    #[inline]
+    fn pre_update(&mut self, message: &Self::Message) -> bool {
+        match message {
+            Message::EditorMessage(message) => self.editor.pre_update(message),
+            Message::ParameterUpdate => true,
         }
     }
 
@@ -130,57 +212,102 @@ impl<E: IcedEditor> crate::iced_baseview::Application for IcedEditorWrapperAppli
         &self,
         window_subs: &mut WindowSubs<Self::Message>,
     ) -> Subscription<Self::Message> {
-        // Since we're wrapping around `E::Message`, we need to do this transformation ourselves
-        let on_frame = window_subs.on_frame.clone();
-        let on_window_will_close = window_subs.on_window_will_close.clone();
-        let on_resize = window_subs.on_resize.clone();
-        let mut editor_window_subs: WindowSubs<E::Message> = WindowSubs {
-            on_frame: Some(Arc::new(move || {
-                let cb = on_frame.clone();
-                cb.and_then(|cb| cb().and_then(|m| m.into_editor_message()))
-            })),
-            on_window_will_close: Some(Arc::new(move || {
-                let cb = on_window_will_close.clone();
-                cb.and_then(|cb| cb().and_then(|m| m.into_editor_message()))
-            })),
-            on_resize: on_resize.clone().map(|cb| {
-                Arc::new(move |size| {
-                    cb(size).and_then(|m| m.into_editor_message())
-                }) as Arc<dyn Fn(crate::iced_baseview::Size) -> Option<E::Message>>
-            }),
-        };
+        // Since we're wrapping around `E::Message`, we need to do this transformation ourselves.
+        // The editor starts from an empty set of callbacks on every pass so callbacks it no longer
+        // sets are cleared in the runtime as well.
+        let mut editor_window_subs: WindowSubs<E::Message> = WindowSubs::default();
 
         let subscription = Subscription::batch([
-            from_recipe(ParameterUpdatesRecipe {
-                receiver: self.parameter_updates_receiver.clone(),
+            parameter_updates(self.parameter_updates_receiver.clone()),
+            gui_channel::with_channels(&self.iced_state.gui_channels, || {
+                self.editor.subscription(&mut editor_window_subs)
             })
-            .map(|_| Message::ParameterUpdate),
-            self.editor
-                .subscription(&mut editor_window_subs)
-                .map(Message::EditorMessage),
+            .map(Message::EditorMessage),
         ]);
 
-        if let Some(message) = editor_window_subs.on_frame.as_ref() {
-            let message = Arc::clone(message);
-            window_subs.on_frame = Some(Arc::new(move || message().map(Message::EditorMessage)));
-        }
-        if let Some(message) = editor_window_subs.on_window_will_close.as_ref() {
-            let message = Arc::clone(message);
-            window_subs.on_window_will_close =
-                Some(Arc::new(move || message().map(Message::EditorMessage)));
-        }
-        if let Some(message) = editor_window_subs.on_resize.as_ref() {
-            let message = Arc::clone(message);
-            window_subs.on_resize =
-                Some(Arc::new(move |size| message(size).map(Message::EditorMessage)));
-        }
+        window_subs.on_frame = editor_window_subs.on_frame.map(|task| {
+            Arc::new(move || task().map(|task| task.map(Message::EditorMessage)))
+                as Arc<dyn Fn() -> Option<Task<Self::Message>>>
+        });
+        window_subs.on_window_will_close = editor_window_subs.on_window_will_close.map(|task| {
+            Arc::new(move || task().map(|task| task.map(Message::EditorMessage)))
+                as Arc<dyn Fn() -> Option<Task<Self::Message>>>
+        });
+        window_subs.on_resize = editor_window_subs.on_resize.map(|task| {
+            Arc::new(move |size| task(size).map(|task| task.map(Message::EditorMessage)))
+                as Arc<dyn Fn(crate::iced_baseview::Size) -> Option<Task<Self::Message>>>
+        });
+        window_subs.on_hover_changed = editor_window_subs.on_hover_changed.map(|task| {
+            Arc::new(move |hovered| task(hovered).map(|task| task.map(Message::EditorMessage)))
+                as Arc<dyn Fn(Option<HoveredWidget>) -> Option<Task<Self::Message>>>
+        });
+        window_subs.on_cursor_moved = editor_window_subs.on_cursor_moved.map(|task| {
+            Arc::new(move |position| task(position).map(|task| task.map(Message::EditorMessage)))
+                as Arc<dyn Fn(Option<crate::iced_baseview::Point>) -> Option<Task<Self::Message>>>
+        });
+        window_subs.on_files_dropped = editor_window_subs.on_files_dropped.map(|task| {
+            Arc::new(move |paths| task(paths).map(|task| task.map(Message::EditorMessage)))
+                as Arc<dyn Fn(Vec<PathBuf>) -> Option<Task<Self::Message>>>
+        });
+        window_subs.on_clipboard_change = editor_window_subs
+            .on_clipboard_change
+            .map(|watch| watch.map(Message::EditorMessage));
 
         subscription
     }
 
+    fn scale_factor_changed(&mut self, scale_factor: f32) {
+        self.iced_state.scale_factor.store(scale_factor);
+    }
+
     #[inline]
     fn view(&self) -> Element<'_, Self::Message, Self::Theme, Renderer> {
-        self.editor.view().map(Message::EditorMessage)
+        param_bounds::with_map(&self.iced_state.param_bounds(), || {
+            param_id::with_occurrences(|| self.editor.view())
+        })
+        .map(Message::EditorMessage)
+    }
+
+    #[inline]
+    fn child_view(&self) -> Option<Element<'_, Self::Message, Self::Theme, Renderer>> {
+        param_bounds::with_map(&self.iced_state.param_bounds(), || {
+            param_id::with_occurrences(|| self.editor.child_view())
+        })
+        .map(|element| element.map(Message::EditorMessage))
+    }
+
+    fn interaction_settings(&self) -> Option<InteractionSettings> {
+        self.editor.interaction_settings()
+    }
+
+    fn event_filter(&self) -> Option<EventFilter> {
+        self.editor.event_filter()
+    }
+
+    fn is_rendering_paused(&self) -> bool {
+        self.iced_state.is_rendering_paused()
+    }
+
+    fn is_input_locked(&self) -> bool {
+        self.iced_state.is_input_locked()
+    }
+
+    fn should_exit(&self) -> bool {
+        self.editor.should_exit()
+    }
+
+    fn resolve_key(&self, event: &keyboard::Event) -> Option<Resolution<Self::Message>> {
+        let resolution = self.editor.keymap()?.resolve(event)?;
+
+        Some(resolution.map(Message::EditorMessage))
+    }
+
+    fn child_window_settings(&self) -> ChildWindowSettings {
+        self.editor.child_window_settings()
+    }
+
+    fn child_window_status_changed(&mut self, status: ChildWindowStatus) {
+        self.editor.child_window_status_changed(status);
     }
 
     #[inline]